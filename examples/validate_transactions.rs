@@ -5,9 +5,8 @@
 
 use chrono::Utc;
 use rust_transaction_validator::{
-    Transaction, TransactionType, TransactionValidator, ValidatorConfig,
+    Transaction, TransactionMetadata, TransactionType, TransactionValidator, ValidatorConfig,
 };
-use std::collections::HashMap;
 
 fn main() {
     println!("=== Financial Transaction Validator ===\n");
@@ -26,13 +25,13 @@ fn main() {
         to_account: Some("ACCT-6789-0123-4567-8901".to_string()),
         timestamp: Utc::now(),
         user_id: "USER-12345".to_string(),
-        metadata: Some(HashMap::from([
-            (
-                "beneficiary_name".to_string(),
-                "Corporate Account".to_string(),
-            ),
-            ("purpose".to_string(), "Business payment".to_string()),
-        ])),
+        metadata: Some(TransactionMetadata {
+            beneficiary_name: Some("Corporate Account".to_string()),
+            purpose: Some("Business payment".to_string()),
+            ..Default::default()
+        }),
+        value_date: None,
+        settlement_date: None,
     };
 
     let result = validator.validate(&wire_transfer);
@@ -40,7 +39,7 @@ fn main() {
     println!("   Valid: {}", result.is_valid);
     println!("   Approved: {}", result.is_approved());
     println!("   Fraud Score: {}/100", result.fraud_score);
-    println!("   Warnings: {:?}", result.warnings);
+    println!("   Warnings: {:?}", result.warning_codes());
     println!("   Errors: {:?}", result.errors);
     println!();
 
@@ -56,6 +55,8 @@ fn main() {
         timestamp: Utc::now(),
         user_id: "USER-67890".to_string(),
         metadata: None,
+        value_date: None,
+        settlement_date: None,
     };
 
     let result = validator.validate(&invalid_transaction);
@@ -76,6 +77,8 @@ fn main() {
         timestamp: Utc::now(),
         user_id: "USER-11111".to_string(),
         metadata: None,
+        value_date: None,
+        settlement_date: None,
     };
 
     println!("   First submission:");
@@ -100,12 +103,14 @@ fn main() {
         timestamp: Utc::now(),
         user_id: "USER-22222".to_string(),
         metadata: None,
+        value_date: None,
+        settlement_date: None,
     };
 
     let result = validator.validate(&high_value);
     println!("   Transaction ID: {}", result.transaction_id);
     println!("   Fraud Score: {}/100", result.fraud_score);
-    println!("   Warnings: {:?}", result.warnings);
+    println!("   Warnings: {:?}", result.warning_codes());
     println!("   Approved: {}", result.is_approved());
     println!();
 
@@ -121,6 +126,8 @@ fn main() {
         timestamp: Utc::now(),
         user_id: "USER-33333".to_string(),
         metadata: None,
+        value_date: None,
+        settlement_date: None,
     };
 
     let result = validator.validate(&invalid_transfer);
@@ -142,6 +149,8 @@ fn main() {
             timestamp: Utc::now(),
             user_id: "USER-44444".to_string(),
             metadata: None,
+            value_date: None,
+            settlement_date: None,
         },
         Transaction {
             transaction_id: "TXN-BATCH-002".to_string(),
@@ -153,6 +162,8 @@ fn main() {
             timestamp: Utc::now(),
             user_id: "USER-44444".to_string(),
             metadata: None,
+            value_date: None,
+            settlement_date: None,
         },
         Transaction {
             transaction_id: "TXN-BATCH-003".to_string(),
@@ -164,6 +175,8 @@ fn main() {
             timestamp: Utc::now(),
             user_id: "USER-44444".to_string(),
             metadata: None,
+            value_date: None,
+            settlement_date: None,
         },
     ];
 
@@ -198,6 +211,7 @@ fn main() {
         velocity_check_window_minutes: 60,
         max_transactions_per_window: 10,
         max_amount_per_window: 100_000.0,
+        ..Default::default()
     };
 
     let mut custom_validator = TransactionValidator::with_config(custom_config);
@@ -212,6 +226,8 @@ fn main() {
         timestamp: Utc::now(),
         user_id: "USER-55555".to_string(),
         metadata: None,
+        value_date: None,
+        settlement_date: None,
     };
 
     let result = custom_validator.validate(&large_transaction);
@@ -223,9 +239,16 @@ fn main() {
     // Statistics
     println!("=== Validator Statistics ===");
     let stats = validator.get_stats();
-    for (key, value) in stats {
-        println!("   {}: {}", key, value);
-    }
+    println!("   Total validated: {}", stats.total_validated);
+    println!(
+        "   Decisions: {} approved, {} review, {} declined",
+        stats.decisions.approve, stats.decisions.review, stats.decisions.decline
+    );
+    println!("   Errors by type: {:?}", stats.errors_by_type);
+    println!(
+        "   History entries: {}",
+        stats.total_transactions_in_history
+    );
 
     println!("\n=== Security Features ===");
     println!("✓ Memory-safe transaction processing");
@@ -0,0 +1,17 @@
+//! Parse arbitrary bytes as a JSON configuration file.
+//!
+//! Run with `cargo fuzz run config_file_json`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_transaction_validator::{ConfigFile, ParseLimits, TransactionValidator};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = ParseLimits::default().input_text(data) else {
+        return;
+    };
+    if let Ok(file) = ConfigFile::from_json(text) {
+        let _ = TransactionValidator::builder().with_config_file(&file).build();
+    }
+});
@@ -0,0 +1,35 @@
+//! Split arbitrary bytes into OFAC primary, alias and address CSV files.
+//!
+//! Run with `cargo fuzz run ofac_csv`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_transaction_validator::{OfacListKind, OfacPublication, ParseLimits, SanctionsScreener};
+
+fuzz_target!(|data: &[u8]| {
+    let limits = ParseLimits {
+        max_input_bytes: 64 * 1024,
+        max_records: 64,
+        ..Default::default()
+    };
+    let Ok(text) = limits.input_text_lossy(data) else {
+        return;
+    };
+    // Form feeds separate the three files
+    let mut files = text.splitn(3, '\u{c}');
+    let primary = files.next().unwrap_or_default();
+    let aliases = files.next();
+    let addresses = files.next();
+    if let Ok(publication) = OfacPublication::from_csv(
+        OfacListKind::Consolidated,
+        primary,
+        aliases,
+        addresses,
+        &limits,
+    ) {
+        let mut screener = SanctionsScreener::new();
+        screener.load_ofac(publication);
+        let _ = screener.screen("NORTHWIND LINES");
+    }
+});
@@ -0,0 +1,24 @@
+//! Parse arbitrary bytes as an OFAC SDN XML file and screen against it.
+//!
+//! Run with `cargo fuzz run ofac_sdn_xml`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_transaction_validator::{OfacListKind, OfacPublication, ParseLimits, SanctionsScreener};
+
+fuzz_target!(|data: &[u8]| {
+    let limits = ParseLimits {
+        max_input_bytes: 64 * 1024,
+        max_records: 64,
+        ..Default::default()
+    };
+    let Ok(text) = limits.input_text_lossy(data) else {
+        return;
+    };
+    if let Ok(publication) = OfacPublication::from_xml(OfacListKind::Sdn, &text, &limits) {
+        let mut screener = SanctionsScreener::new();
+        screener.load_ofac(publication);
+        let _ = screener.screen("OCEANIC FREIGHT");
+    }
+});
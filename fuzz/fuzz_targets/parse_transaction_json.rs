@@ -0,0 +1,18 @@
+//! Parse arbitrary bytes as a JSON transaction and run every check on it.
+//!
+//! Run with `cargo fuzz run parse_transaction_json`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_transaction_validator::parsing::parse_transaction_json;
+use rust_transaction_validator::{AMLChecker, FraudDetector, ParseLimits, TransactionValidator};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(transaction) = parse_transaction_json(data, &ParseLimits::default()) {
+        let mut validator = TransactionValidator::new();
+        let _ = validator.validate(&transaction);
+        let _ = FraudDetector::new().calculate_fraud_score(&transaction);
+        let _ = AMLChecker::new().check_compliance(&transaction);
+    }
+});
@@ -0,0 +1,21 @@
+//! Parse arbitrary bytes as newline-delimited JSON and validate the batch.
+//!
+//! Run with `cargo fuzz run parse_transactions_jsonl`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_transaction_validator::parsing::parse_transactions_jsonl;
+use rust_transaction_validator::{ParseLimits, TransactionValidator};
+
+fuzz_target!(|data: &[u8]| {
+    let limits = ParseLimits {
+        max_input_bytes: 64 * 1024,
+        max_records: 64,
+        ..Default::default()
+    };
+    if let Ok(transactions) = parse_transactions_jsonl(data, &limits) {
+        let mut validator = TransactionValidator::new();
+        let _ = validator.validate_batch(&transactions);
+    }
+});
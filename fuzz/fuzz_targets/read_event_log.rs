@@ -0,0 +1,20 @@
+//! Parse arbitrary bytes as an event log and replay it.
+//!
+//! Run with `cargo fuzz run read_event_log`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_transaction_validator::{read_event_log, ParseLimits, TransactionValidator};
+
+fuzz_target!(|data: &[u8]| {
+    let limits = ParseLimits {
+        max_input_bytes: 64 * 1024,
+        max_records: 64,
+        ..Default::default()
+    };
+    if let Ok(events) = read_event_log(data, &limits) {
+        let mut validator = TransactionValidator::new();
+        let _ = validator.replay_events(events);
+    }
+});
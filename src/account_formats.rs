@@ -0,0 +1,243 @@
+//! Account number schemes
+//!
+//! The account format check accepts an account if it matches any scheme
+//! accepted for the transaction's type. An [`AccountScheme`] is a compiled
+//! regex or a validator function. The [`AccountFormatRegistry`] holds the
+//! schemes by name, which ones are accepted by default, and per-type
+//! overrides (for example, wires accepting only IBANs). Each validator owns
+//! its registry, so tenants in a `MultiTenantValidator` can accept
+//! different formats.
+//!
+//! Built-in schemes:
+//!
+//! - `internal`: `XXXX-XXXX-XXXX-XXXX` with uppercase letters and digits
+//! - `masked`: anything starting with `****`, as produced by PAN masking
+//! - `iban`: ISO 13616 IBAN (spaces allowed), verified with mod-97
+//! - `us_aba`: `routing:account` with a valid ABA routing number
+
+use crate::routing::validate_aba;
+use crate::TransactionType;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt;
+
+/// How a scheme recognizes its account numbers
+#[derive(Clone)]
+pub enum AccountMatcher {
+    /// Compiled once, when the scheme is created
+    Pattern(Regex),
+    Check(fn(&str) -> bool),
+}
+
+impl fmt::Debug for AccountMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccountMatcher::Pattern(regex) => write!(f, "Pattern({})", regex.as_str()),
+            AccountMatcher::Check(_) => write!(f, "Check"),
+        }
+    }
+}
+
+/// One named account number format
+#[derive(Debug, Clone)]
+pub struct AccountScheme {
+    pub name: String,
+    pub matcher: AccountMatcher,
+}
+
+impl AccountScheme {
+    /// Scheme matching a regex, anchored by the caller
+    pub fn pattern(name: &str, pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self {
+            name: name.to_string(),
+            matcher: AccountMatcher::Pattern(Regex::new(pattern)?),
+        })
+    }
+
+    /// Scheme accepting accounts for which `check` returns true
+    pub fn check(name: &str, check: fn(&str) -> bool) -> Self {
+        Self {
+            name: name.to_string(),
+            matcher: AccountMatcher::Check(check),
+        }
+    }
+
+    pub fn matches(&self, account: &str) -> bool {
+        match &self.matcher {
+            AccountMatcher::Pattern(regex) => regex.is_match(account),
+            AccountMatcher::Check(check) => check(account),
+        }
+    }
+}
+
+/// ISO 13616 IBAN with a valid mod-97 check
+pub fn is_valid_iban(account: &str) -> bool {
+    let iban: String = account.chars().filter(|c| *c != ' ').collect();
+    let bytes = iban.as_bytes();
+    if !(15..=34).contains(&bytes.len())
+        || !bytes[..2].iter().all(u8::is_ascii_uppercase)
+        || !bytes[2..4].iter().all(u8::is_ascii_digit)
+        || !bytes[4..]
+            .iter()
+            .all(|b| b.is_ascii_digit() || b.is_ascii_uppercase())
+    {
+        return false;
+    }
+    // Move the country code and check digits to the end, letters to 10-35
+    let mut remainder = 0u32;
+    for &b in bytes[4..].iter().chain(&bytes[..4]) {
+        let value = if b.is_ascii_digit() {
+            (b - b'0') as u32
+        } else {
+            (b - b'A') as u32 + 10
+        };
+        remainder = if value >= 10 {
+            (remainder * 100 + value) % 97
+        } else {
+            (remainder * 10 + value) % 97
+        };
+    }
+    remainder == 1
+}
+
+fn is_us_bank_account(account: &str) -> bool {
+    let Some((routing, number)) = account.split_once(':') else {
+        return false;
+    };
+    validate_aba(routing.trim()).is_ok()
+        && (4..=17).contains(&number.trim().len())
+        && number.trim().chars().all(|c| c.is_ascii_digit())
+}
+
+/// Named schemes and which of them each transaction type accepts
+#[derive(Debug, Clone)]
+pub struct AccountFormatRegistry {
+    schemes: HashMap<String, AccountScheme>,
+    /// Accepted when the type has no override
+    default: Vec<String>,
+    by_type: HashMap<TransactionType, Vec<String>>,
+}
+
+impl Default for AccountFormatRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AccountFormatRegistry {
+    /// Registry with the built-in schemes, all accepted by default
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+        registry.register(
+            AccountScheme::pattern(
+                "internal",
+                r"^[A-Z0-9]{4}-[A-Z0-9]{4}-[A-Z0-9]{4}-[A-Z0-9]{4}$",
+            )
+            .expect("built-in account pattern compiles"),
+        );
+        registry.register(AccountScheme::check("masked", |a| a.starts_with("****")));
+        registry.register(AccountScheme::check("iban", is_valid_iban));
+        registry.register(AccountScheme::check("us_aba", is_us_bank_account));
+        registry.default = registry.names();
+        registry
+    }
+
+    /// Registry with no schemes; every account is rejected until some are added
+    pub fn empty() -> Self {
+        Self {
+            schemes: HashMap::new(),
+            default: Vec::new(),
+            by_type: HashMap::new(),
+        }
+    }
+
+    /// Add or replace a scheme; new schemes are not accepted until listed
+    pub fn register(&mut self, scheme: AccountScheme) {
+        self.schemes.insert(scheme.name.clone(), scheme);
+    }
+
+    /// Registered scheme names, sorted
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.schemes.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Schemes accepted for types without an override
+    pub fn accept_by_default(&mut self, names: &[&str]) {
+        self.default = names.iter().map(|n| n.to_string()).collect();
+    }
+
+    /// Schemes accepted for one transaction type, replacing the default
+    pub fn accept_for(&mut self, transaction_type: TransactionType, names: &[&str]) {
+        self.by_type.insert(
+            transaction_type,
+            names.iter().map(|n| n.to_string()).collect(),
+        );
+    }
+
+    /// Names of the schemes accepted for a transaction type
+    pub fn accepted(&self, transaction_type: TransactionType) -> &[String] {
+        self.by_type.get(&transaction_type).unwrap_or(&self.default)
+    }
+
+    /// First accepted scheme the account matches
+    pub fn scheme_for(&self, account: &str, transaction_type: TransactionType) -> Option<&str> {
+        self.accepted(transaction_type)
+            .iter()
+            .filter_map(|name| self.schemes.get(name))
+            .find(|scheme| scheme.matches(account))
+            .map(|scheme| scheme.name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::TransactionValidator;
+
+    #[test]
+    fn test_builtin_schemes() {
+        let registry = AccountFormatRegistry::new();
+        let transfer = TransactionType::Transfer;
+        assert_eq!(
+            registry.scheme_for("GB82 WEST 1234 5698 7654 32", transfer),
+            Some("iban")
+        );
+        assert_eq!(
+            registry.scheme_for("GB82WEST12345698765433", transfer),
+            None
+        );
+        assert_eq!(
+            registry.scheme_for("021000021:123456789", transfer),
+            Some("us_aba")
+        );
+        assert_eq!(
+            registry.scheme_for("ACCT-1234-5678-9012", transfer),
+            Some("internal")
+        );
+        assert_eq!(registry.scheme_for("invalid", transfer), None);
+    }
+
+    #[test]
+    fn test_per_type_schemes_in_validator() {
+        let mut registry = AccountFormatRegistry::new();
+        registry.register(AccountScheme::pattern("br_agencia", r"^\d{4}/\d{5,12}-\d$").unwrap());
+        registry.accept_for(TransactionType::Transfer, &["internal", "br_agencia"]);
+        let mut validator = TransactionValidator::new();
+        validator.set_account_formats(registry);
+
+        let mut domestic = create_test_transaction("TXN-FMT-1", 100.0);
+        domestic.to_account = Some("0001/123456-7".to_string());
+        assert!(validator.validate(&domestic).is_valid);
+
+        // IBANs are accepted by default, but not for transfers here
+        let mut iban = create_test_transaction("TXN-FMT-2", 100.0);
+        iban.to_account = Some("DE89370400440532013000".to_string());
+        assert!(!validator.validate(&iban).is_valid);
+        iban.transaction_id = "TXN-FMT-3".to_string();
+        iban.transaction_type = TransactionType::Payment;
+        assert!(validator.validate(&iban).is_valid);
+    }
+}
@@ -0,0 +1,209 @@
+//! Per-user activity profiles
+//!
+//! The global [`TimeRiskProfile`](crate::TimeRiskProfile) treats 3am the
+//! same for a night-shift nurse and a nine-to-five office worker.
+//! [`ActivityProfiles`] keeps hour-of-day and day-of-week histograms per
+//! user, updated from committed transactions. Once a user has enough
+//! history, the validator scores time risk against their own pattern
+//! instead: transactions in an hour where the user has essentially never
+//! been active score [`ActivityPolicy::unusual_hour_points`], and every
+//! other hour scores 0. Users below the maturity threshold keep the global
+//! heuristic.
+
+use crate::{Transaction, Warning, WarningSeverity};
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Warning code for a transaction in an hour the user is never active
+pub const UNUSUAL_HOUR_WARNING: &str = "UNUSUAL_HOUR_FOR_USER";
+
+/// Counts of one user's transactions by UTC hour and weekday
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ActivityHistogram {
+    pub hours: [u32; 24],
+    /// Monday first
+    pub weekdays: [u32; 7],
+    pub total: u32,
+}
+
+impl ActivityHistogram {
+    pub fn record(&mut self, at: DateTime<Utc>) {
+        self.hours[at.hour() as usize] += 1;
+        self.weekdays[at.weekday().num_days_from_monday() as usize] += 1;
+        self.total += 1;
+    }
+
+    /// Share of the user's transactions in this UTC hour
+    pub fn hour_share(&self, hour: u32) -> f64 {
+        self.share(self.hours.get(hour as usize).copied().unwrap_or(0))
+    }
+
+    /// Share of the user's transactions on this weekday (0 = Monday)
+    pub fn weekday_share(&self, weekday: u32) -> f64 {
+        self.share(self.weekdays.get(weekday as usize).copied().unwrap_or(0))
+    }
+
+    fn share(&self, count: u32) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            count as f64 / self.total as f64
+        }
+    }
+}
+
+/// When a profile is trusted over the global heuristic and what it scores
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityPolicy {
+    /// Transactions needed before the user's own profile is used
+    pub min_transactions: u32,
+    /// Hours holding at most this share of the user's activity are unusual
+    pub unusual_hour_share: f64,
+    /// Time risk for a transaction in an unusual hour
+    pub unusual_hour_points: u8,
+}
+
+impl Default for ActivityPolicy {
+    fn default() -> Self {
+        Self {
+            min_transactions: 30,
+            unusual_hour_share: 0.01,
+            unusual_hour_points: 25,
+        }
+    }
+}
+
+/// Activity histograms for every user, with the policy that scores them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ActivityProfiles {
+    users: HashMap<String, ActivityHistogram>,
+    policy: ActivityPolicy,
+}
+
+impl ActivityProfiles {
+    /// Empty profiles with the default policy
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Empty profiles with a custom policy
+    pub fn with_policy(policy: ActivityPolicy) -> Self {
+        Self {
+            users: HashMap::new(),
+            policy,
+        }
+    }
+
+    /// The policy in use
+    pub fn policy(&self) -> &ActivityPolicy {
+        &self.policy
+    }
+
+    /// Add a committed transaction to its user's histogram
+    pub fn record(&mut self, transaction: &Transaction) {
+        self.users
+            .entry(transaction.user_id.clone())
+            .or_default()
+            .record(transaction.timestamp);
+    }
+
+    /// Histogram for a user, if they have any recorded activity
+    pub fn profile(&self, user_id: &str) -> Option<&ActivityHistogram> {
+        self.users.get(user_id)
+    }
+
+    /// Whether the user has enough history for their own profile to apply
+    pub fn is_mature(&self, user_id: &str) -> bool {
+        self.profile(user_id)
+            .is_some_and(|p| p.total >= self.policy.min_transactions)
+    }
+
+    /// Time risk from the user's own pattern; `None` for immature profiles
+    pub fn time_risk(&self, transaction: &Transaction) -> Option<(u8, Option<Warning>)> {
+        if !self.is_mature(&transaction.user_id) {
+            return None;
+        }
+        let profile = self.profile(&transaction.user_id)?;
+        let hour = transaction.timestamp.hour();
+        let share = profile.hour_share(hour);
+        if share > self.policy.unusual_hour_share {
+            return Some((0, None));
+        }
+        let warning = Warning::new(
+            UNUSUAL_HOUR_WARNING,
+            WarningSeverity::Medium,
+            format!(
+                "User has {:.1}% of {} transactions at {:02}:00 UTC",
+                share * 100.0,
+                profile.total,
+                hour
+            ),
+        );
+        Some((self.policy.unusual_hour_points, Some(warning)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::TransactionValidator;
+    use chrono::Duration;
+
+    #[test]
+    fn test_histogram_shares() {
+        let mut histogram = ActivityHistogram::default();
+        let noon = create_test_transaction("TXN-ACT-0", 10.0).timestamp;
+        histogram.record(noon);
+        histogram.record(noon);
+        histogram.record(noon + Duration::hours(1));
+        histogram.record(noon + Duration::hours(1));
+        assert_eq!(histogram.total, 4);
+        assert_eq!(histogram.hour_share(12), 0.5);
+        assert_eq!(histogram.hour_share(3), 0.0);
+        assert_eq!(histogram.hour_share(99), 0.0);
+        let weekday = noon.weekday().num_days_from_monday();
+        assert_eq!(histogram.weekday_share(weekday), 1.0);
+    }
+
+    #[test]
+    fn test_mature_profile_replaces_global_time_risk() {
+        let policy = ActivityPolicy {
+            min_transactions: 5,
+            ..Default::default()
+        };
+        let mut validator = TransactionValidator::builder()
+            .with_activity_profiles(ActivityProfiles::with_policy(policy))
+            .build();
+
+        // A night-shift worker active at 02:00 UTC on previous days
+        for day in 1..=5 {
+            let mut tx = create_test_transaction(&format!("TXN-ACT-{}", day), 40.0);
+            tx.timestamp -= Duration::days(day) + Duration::hours(10);
+            validator.validate(&tx);
+        }
+        let profiles = validator.activity_profiles().unwrap();
+        assert!(profiles.is_mature("USER-001"));
+        assert!(!profiles.is_mature("USER-002"));
+
+        let mut night = create_test_transaction("TXN-ACT-6", 40.0);
+        night.timestamp -= Duration::hours(10);
+        let usual = validator.validate(&night);
+        assert_eq!(usual.risk_breakdown.time_risk, 0);
+
+        // Noon is ordinary globally but unheard of for this user
+        let noon = validator.validate(&create_test_transaction("TXN-ACT-7", 40.0));
+        assert_eq!(noon.risk_breakdown.time_risk, 25);
+        assert!(noon
+            .warning_codes()
+            .contains(&UNUSUAL_HOUR_WARNING.to_string()));
+
+        // Users without history keep the global heuristic
+        let mut other = create_test_transaction("TXN-ACT-8", 40.0);
+        other.user_id = "USER-002".to_string();
+        other.timestamp -= Duration::hours(10);
+        let result = validator.validate(&other);
+        assert_eq!(result.risk_breakdown.time_risk, 20);
+    }
+}
@@ -0,0 +1,449 @@
+//! High-risk result callbacks and webhook dispatch
+//!
+//! Observers subscribe with an [`AlertTrigger`] and are called whenever a
+//! validation result matches it, so downstream case systems receive pushed
+//! events instead of polling. With the `http` feature, [`WebhookObserver`]
+//! delivers signed JSON payloads from a background thread, passing the
+//! transaction's correlation ID on as W3C baggage.
+
+use crate::{Decision, ValidationResult};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+/// Signature header sent with webhook payloads
+pub const SIGNATURE_HEADER: &str = "X-Signature-256";
+
+/// W3C baggage header carrying the correlation ID on webhook deliveries
+pub const BAGGAGE_HEADER: &str = "baggage";
+
+/// Condition under which an observer is notified
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlertTrigger {
+    /// Fraud score at or above the given value
+    ScoreAtLeast(u8),
+    /// Result carries any of the given reason codes
+    ReasonCodes(Vec<String>),
+    /// Result has the given decision
+    Decision(Decision),
+    /// Follow-up on an approval whose account later turned up in a network
+    /// pattern; raised by `TransactionValidator::retroactive_alerts`, never
+    /// by a live result
+    NetworkPattern,
+}
+
+impl AlertTrigger {
+    /// Check whether a result matches this trigger
+    pub fn matches(&self, result: &ValidationResult) -> bool {
+        match self {
+            AlertTrigger::ScoreAtLeast(score) => result.fraud_score >= *score,
+            AlertTrigger::ReasonCodes(codes) => result
+                .reason_codes()
+                .iter()
+                .any(|code| codes.contains(code)),
+            AlertTrigger::Decision(decision) => result.decision == *decision,
+            AlertTrigger::NetworkPattern => false,
+        }
+    }
+}
+
+/// Event delivered to observers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertEvent {
+    pub event_id: String,
+    pub transaction_id: String,
+    pub fraud_score: u8,
+    pub decision: Decision,
+    pub reason_codes: Vec<String>,
+    pub trigger: AlertTrigger,
+    pub created_at: DateTime<Utc>,
+    /// Upstream request the transaction arrived with, from its
+    /// `correlation_id` metadata
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+}
+
+impl AlertEvent {
+    fn new(
+        result: &ValidationResult,
+        trigger: &AlertTrigger,
+        correlation_id: Option<&str>,
+    ) -> Self {
+        Self {
+            event_id: uuid::Uuid::new_v4().to_string(),
+            transaction_id: result.transaction_id.clone(),
+            fraud_score: result.fraud_score,
+            decision: result.decision,
+            reason_codes: result.reason_codes(),
+            trigger: trigger.clone(),
+            created_at: result.validated_at,
+            correlation_id: correlation_id.map(str::to_string),
+        }
+    }
+
+    /// `baggage` header value carrying the correlation ID, if there is one
+    ///
+    /// The value is percent-encoded as the W3C baggage format requires.
+    pub fn baggage(&self) -> Option<String> {
+        let id = self.correlation_id.as_deref()?;
+        let encoded: String = id
+            .bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                    (b as char).to_string()
+                }
+                _ => format!("%{:02X}", b),
+            })
+            .collect();
+        Some(format!("correlation_id={}", encoded))
+    }
+}
+
+/// Receiver of alert events
+pub trait AlertObserver: Send + Sync {
+    fn on_alert(&self, event: &AlertEvent);
+
+    /// Events this observer accepted but could not deliver
+    fn dropped(&self) -> usize {
+        0
+    }
+}
+
+impl<F> AlertObserver for F
+where
+    F: Fn(&AlertEvent) + Send + Sync,
+{
+    fn on_alert(&self, event: &AlertEvent) {
+        self(event)
+    }
+}
+
+/// Registry of observers and their triggers
+#[derive(Default)]
+pub struct AlertDispatcher {
+    subscriptions: Vec<(AlertTrigger, Box<dyn AlertObserver>)>,
+}
+
+impl AlertDispatcher {
+    /// Create an empty dispatcher
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an observer for a trigger
+    pub fn subscribe<O: AlertObserver + 'static>(&mut self, trigger: AlertTrigger, observer: O) {
+        self.subscriptions.push((trigger, Box::new(observer)));
+    }
+
+    /// Number of registered observers
+    pub fn subscription_count(&self) -> usize {
+        self.subscriptions.len()
+    }
+
+    /// Events dropped by all observers so far
+    pub fn dropped(&self) -> usize {
+        self.subscriptions
+            .iter()
+            .map(|(_, observer)| observer.dropped())
+            .sum()
+    }
+
+    /// Notify every observer whose trigger matches; returns the number notified
+    pub fn dispatch(&self, result: &ValidationResult) -> usize {
+        self.dispatch_events(result, None).len()
+    }
+
+    /// Notify every observer whose trigger matches; returns the events sent
+    pub(crate) fn dispatch_events(
+        &self,
+        result: &ValidationResult,
+        correlation_id: Option<&str>,
+    ) -> Vec<AlertEvent> {
+        let mut events = Vec::new();
+        for (trigger, observer) in &self.subscriptions {
+            if trigger.matches(result) {
+                let event = AlertEvent::new(result, trigger, correlation_id);
+                observer.on_alert(&event);
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    /// Deliver an event raised outside validation to observers of its
+    /// trigger; returns the number notified
+    pub(crate) fn notify(&self, event: &AlertEvent) -> usize {
+        let mut notified = 0;
+        for (trigger, observer) in &self.subscriptions {
+            if *trigger == event.trigger {
+                observer.on_alert(event);
+                notified += 1;
+            }
+        }
+        notified
+    }
+}
+
+/// HMAC-SHA256 signature of a payload, hex encoded with a `sha256=` prefix
+///
+/// Receivers recompute this over the raw request body to authenticate webhooks.
+pub fn sign_payload(secret: &[u8], payload: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(payload);
+    let digest = mac.finalize().into_bytes();
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256={}", hex)
+}
+
+/// Events a [`WebhookObserver`] queues before dropping new ones
+pub const DEFAULT_WEBHOOK_QUEUE_CAPACITY: usize = 1024;
+
+/// Wait before the first retry; doubled after every further failure
+#[cfg(feature = "http")]
+const WEBHOOK_INITIAL_BACKOFF: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Longest wait between two delivery attempts
+#[cfg(feature = "http")]
+const WEBHOOK_MAX_BACKOFF: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Observer POSTing signed JSON events to a webhook URL
+///
+/// Delivery happens on a background thread so slow endpoints never add
+/// latency to `validate()`. Events wait in a bounded queue; when it is
+/// full new events are dropped rather than buffered without limit. Failed
+/// deliveries are retried up to `max_attempts` times with exponential
+/// backoff and then dropped. Both kinds of loss are counted in
+/// [`ValidatorStats::alerts_dropped`](crate::ValidatorStats::alerts_dropped).
+#[cfg(feature = "http")]
+pub struct WebhookObserver {
+    sender: std::sync::mpsc::SyncSender<AlertEvent>,
+    dropped: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+}
+
+#[cfg(feature = "http")]
+impl WebhookObserver {
+    /// Start a delivery worker for `url`, signing payloads with `secret`
+    pub fn new(url: &str, secret: &[u8], timeout: std::time::Duration, max_attempts: u32) -> Self {
+        Self::with_capacity(
+            url,
+            secret,
+            timeout,
+            max_attempts,
+            DEFAULT_WEBHOOK_QUEUE_CAPACITY,
+        )
+    }
+
+    /// Start a delivery worker that queues at most `capacity` events
+    pub fn with_capacity(
+        url: &str,
+        secret: &[u8],
+        timeout: std::time::Duration,
+        max_attempts: u32,
+        capacity: usize,
+    ) -> Self {
+        use std::sync::atomic::Ordering;
+
+        let (sender, receiver) = std::sync::mpsc::sync_channel::<AlertEvent>(capacity.max(1));
+        let dropped = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let failed = dropped.clone();
+        let url = url.to_string();
+        let secret = secret.to_vec();
+        let agent = ureq::AgentBuilder::new().timeout(timeout).build();
+
+        std::thread::spawn(move || {
+            for event in receiver {
+                let Ok(body) = serde_json::to_string(&event) else {
+                    failed.fetch_add(1, Ordering::Relaxed);
+                    continue;
+                };
+                let signature = sign_payload(&secret, body.as_bytes());
+                let baggage = event.baggage();
+                let mut backoff = WEBHOOK_INITIAL_BACKOFF;
+                let mut delivered = false;
+                for attempt in 0..max_attempts.max(1) {
+                    if attempt > 0 {
+                        std::thread::sleep(backoff);
+                        backoff = (backoff * 2).min(WEBHOOK_MAX_BACKOFF);
+                    }
+                    let mut request = agent
+                        .post(&url)
+                        .set("Content-Type", "application/json")
+                        .set(SIGNATURE_HEADER, &signature);
+                    if let Some(ref baggage) = baggage {
+                        request = request.set(BAGGAGE_HEADER, baggage);
+                    }
+                    let response = request.send_string(&body);
+                    if response.is_ok() {
+                        delivered = true;
+                        break;
+                    }
+                }
+                if !delivered {
+                    failed.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        });
+
+        Self { sender, dropped }
+    }
+}
+
+#[cfg(feature = "http")]
+impl AlertObserver for WebhookObserver {
+    fn on_alert(&self, event: &AlertEvent) {
+        if self.sender.try_send(event.clone()).is_err() {
+            self.dropped
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+
+    fn dropped(&self) -> usize {
+        self.dropped.load(std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::TransactionValidator;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_score_trigger_fires_only_for_high_risk() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let mut validator = TransactionValidator::new();
+        validator.on_alert(AlertTrigger::ScoreAtLeast(50), move |event: &AlertEvent| {
+            sink.lock().unwrap().push(event.clone());
+        });
+
+        validator.validate(&create_test_transaction("TXN-LOW", 100.0));
+        validator.validate(&create_test_transaction("TXN-HIGH", 150_000.0));
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].transaction_id, "TXN-HIGH");
+        assert!(events[0].fraud_score >= 50);
+    }
+
+    #[test]
+    fn test_reason_code_trigger() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let mut validator = TransactionValidator::new();
+        validator.on_alert(
+            AlertTrigger::ReasonCodes(vec!["DUPLICATE_TRANSACTION".to_string()]),
+            move |event: &AlertEvent| sink.lock().unwrap().push(event.clone()),
+        );
+
+        let transaction = create_test_transaction("TXN-DUP", 100.0);
+        validator.validate(&transaction);
+        validator.validate(&transaction);
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].decision, Decision::Decline);
+    }
+
+    #[test]
+    fn test_sign_payload_known_vector() {
+        // RFC 4231 test case 2
+        let signature = sign_payload(b"Jefe", b"what do ya want for nothing?");
+        assert_eq!(
+            signature,
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_events_carry_the_correlation_id() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let mut validator = TransactionValidator::new();
+        validator.on_alert(AlertTrigger::ScoreAtLeast(0), move |event: &AlertEvent| {
+            sink.lock().unwrap().push(event.clone());
+        });
+        let mut tx = create_test_transaction("TXN-CORR", 100.0);
+        tx.metadata = Some(crate::TransactionMetadata::from([(
+            crate::CORRELATION_ID_METADATA_KEY.to_string(),
+            "req 42/a".to_string(),
+        )]));
+        validator.validate(&tx);
+        validator.validate(&create_test_transaction("TXN-NO-CORR", 100.0));
+
+        let events = events.lock().unwrap();
+        assert_eq!(events[0].correlation_id.as_deref(), Some("req 42/a"));
+        assert_eq!(
+            events[0].baggage().as_deref(),
+            Some("correlation_id=req%2042%2Fa")
+        );
+        assert_eq!(events[1].correlation_id, None);
+        assert_eq!(events[1].baggage(), None);
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_undeliverable_webhooks_are_counted_as_dropped() {
+        // Nothing listens on port 9 of the loopback, so every POST fails fast
+        let observer = WebhookObserver::with_capacity(
+            "http://127.0.0.1:9/alerts",
+            b"secret",
+            std::time::Duration::from_millis(200),
+            1,
+            1,
+        );
+        let mut validator = TransactionValidator::new();
+        validator.on_alert(AlertTrigger::ScoreAtLeast(0), observer);
+        for i in 0..5 {
+            validator.validate(&create_test_transaction(&format!("TXN-WH-{}", i), 100.0));
+        }
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while validator.get_stats().alerts_dropped < 5 && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        assert_eq!(validator.get_stats().alerts_dropped, 5);
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_webhook_passes_the_correlation_id_as_baggage() {
+        use std::io::{BufRead, BufReader, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/alerts", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut headers = Vec::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                headers.push(line.trim().to_ascii_lowercase());
+            }
+            reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+            headers
+        });
+
+        let observer = WebhookObserver::new(&url, b"secret", std::time::Duration::from_secs(5), 1);
+        let mut validator = TransactionValidator::new();
+        validator.on_alert(AlertTrigger::ScoreAtLeast(0), observer);
+        let mut tx = create_test_transaction("TXN-WH-CORR", 100.0);
+        tx.metadata = Some(crate::TransactionMetadata::from([(
+            crate::CORRELATION_ID_METADATA_KEY.to_string(),
+            "req-42".to_string(),
+        )]));
+        validator.validate(&tx);
+
+        let headers = server.join().unwrap();
+        assert!(headers.contains(&"baggage: correlation_id=req-42".to_string()));
+    }
+}
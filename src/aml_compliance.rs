@@ -1,18 +1,44 @@
 //! AML/KYC compliance checks
 
-use crate::Transaction;
+use crate::geographic_risk::comprehensive_sanctions_program;
+use crate::{SanctionsScreener, Transaction};
 use serde::{Deserialize, Serialize};
 
+/// Metadata keys holding origin/destination country or region codes
+pub const JURISDICTION_KEYS: [&str; 4] = [
+    "country",
+    "destination_country",
+    "region",
+    "destination_region",
+];
+
 /// AML compliance checker
 pub struct AMLChecker {
     /// Suspicious activity thresholds
     thresholds: AMLThresholds,
-    /// Sanctioned entities list
+    /// Red flags checked; the others are never raised
+    red_flags: Vec<RedFlagType>,
+    /// Sanctioned entities list, normalized
     sanctioned_entities: Vec<String>,
+    /// Optional fuzzy screener consulted after exact matching
+    screener: Option<SanctionsScreener>,
+}
+
+/// Normalize an identifier for exact comparison
+///
+/// Case, whitespace, and punctuation are ignored, so `ofac sanctioned 001`
+/// equals `OFAC-SANCTIONED-001`, but extra characters never match.
+fn normalize_entity(entity: &str) -> String {
+    entity
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_uppercase)
+        .collect()
 }
 
 /// AML thresholds (FinCEN guidelines)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct AMLThresholds {
     /// Currency Transaction Report threshold (USD)
     pub ctr_threshold: f64,
@@ -20,6 +46,8 @@ pub struct AMLThresholds {
     pub sar_threshold: f64,
     /// Structuring detection threshold
     pub structuring_threshold: f64,
+    /// Deposits and withdrawals from here up are flagged as cash intensive
+    pub cash_threshold: f64,
 }
 
 impl Default for AMLThresholds {
@@ -28,6 +56,7 @@ impl Default for AMLThresholds {
             ctr_threshold: 10000.0,        // FinCEN CTR requirement
             sar_threshold: 5000.0,         // FinCEN SAR guideline
             structuring_threshold: 9500.0, // Just under $10k
+            cash_threshold: 5000.0,
         }
     }
 }
@@ -50,11 +79,12 @@ pub struct AMLRedFlag {
     pub severity: AlertSeverity,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum RedFlagType {
     PotentialStructuring,
     HighValueTransaction,
     SanctionedEntity,
+    SanctionedJurisdiction,
     RapidMovement,
     UnusualPattern,
     CashIntensive,
@@ -74,15 +104,45 @@ impl AMLChecker {
     pub fn new() -> Self {
         Self {
             thresholds: AMLThresholds::default(),
+            red_flags: vec![
+                RedFlagType::PotentialStructuring,
+                RedFlagType::HighValueTransaction,
+                RedFlagType::SanctionedEntity,
+                RedFlagType::SanctionedJurisdiction,
+                RedFlagType::CashIntensive,
+                RedFlagType::CrossBorder,
+            ],
             sanctioned_entities: vec![
-                "OFAC-SANCTIONED-001".to_string(),
-                "SANCTIONED-ENTITY-002".to_string(),
+                normalize_entity("OFAC-SANCTIONED-001"),
+                normalize_entity("SANCTIONED-ENTITY-002"),
             ],
+            screener: None,
         }
     }
 
     /// Check transaction for AML compliance
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "aml_check",
+            skip_all,
+            fields(
+                transaction_id = %transaction.transaction_id,
+                risk_score = tracing::field::Empty,
+            )
+        )
+    )]
     pub fn check_compliance(&self, transaction: &Transaction) -> AMLResult {
+        self.check_compliance_given(transaction, self.involves_sanctioned_entity(transaction))
+    }
+
+    /// Check compliance with the sanctioned-entity verdict already taken,
+    /// e.g. on the real accounts before pseudonymization replaced them
+    pub(crate) fn check_compliance_given(
+        &self,
+        transaction: &Transaction,
+        sanctioned_entity: bool,
+    ) -> AMLResult {
         let mut red_flags = Vec::new();
         let mut risk_score = 0u8;
 
@@ -93,7 +153,9 @@ impl AMLChecker {
         let mut requires_sar = false;
 
         // Structuring detection (amounts just below $10k)
-        if self.is_potential_structuring(transaction) {
+        if self.checks(RedFlagType::PotentialStructuring)
+            && self.is_potential_structuring(transaction)
+        {
             red_flags.push(AMLRedFlag {
                 flag_type: RedFlagType::PotentialStructuring,
                 description: format!(
@@ -102,12 +164,14 @@ impl AMLChecker {
                 ),
                 severity: AlertSeverity::High,
             });
-            risk_score += 35;
+            risk_score = risk_score.saturating_add(35);
             requires_sar = true;
         }
 
         // High value transaction
-        if transaction.amount >= self.thresholds.ctr_threshold {
+        if self.checks(RedFlagType::HighValueTransaction)
+            && transaction.amount >= self.thresholds.ctr_threshold
+        {
             red_flags.push(AMLRedFlag {
                 flag_type: RedFlagType::HighValueTransaction,
                 description: format!(
@@ -116,21 +180,11 @@ impl AMLChecker {
                 ),
                 severity: AlertSeverity::Medium,
             });
-            risk_score += 15;
+            risk_score = risk_score.saturating_add(15);
         }
 
         // Sanctioned entity check
-        let from_sanctioned = transaction
-            .from_account
-            .as_deref()
-            .map(|a| self.is_sanctioned_entity(a))
-            .unwrap_or(false);
-        let to_sanctioned = transaction
-            .to_account
-            .as_deref()
-            .map(|a| self.is_sanctioned_entity(a))
-            .unwrap_or(false);
-        if from_sanctioned || to_sanctioned {
+        if sanctioned_entity && self.checks(RedFlagType::SanctionedEntity) {
             red_flags.push(AMLRedFlag {
                 flag_type: RedFlagType::SanctionedEntity,
                 description: "Transaction involves sanctioned entity".to_string(),
@@ -140,12 +194,34 @@ impl AMLChecker {
             requires_sar = true;
         }
 
+        // Origin or destination under comprehensive sanctions
+        let sanctioned_jurisdictions: Vec<&str> = transaction
+            .metadata
+            .iter()
+            .flat_map(|m| JURISDICTION_KEYS.iter().filter_map(|key| m.get(key)))
+            .filter_map(|code| comprehensive_sanctions_program(code))
+            .collect();
+        if !sanctioned_jurisdictions.is_empty() && self.checks(RedFlagType::SanctionedJurisdiction)
+        {
+            red_flags.push(AMLRedFlag {
+                flag_type: RedFlagType::SanctionedJurisdiction,
+                description: format!(
+                    "Transaction involves comprehensively sanctioned jurisdiction: {}",
+                    sanctioned_jurisdictions.join(", ")
+                ),
+                severity: AlertSeverity::Critical,
+            });
+            risk_score = 100;
+            requires_sar = true;
+        }
+
         // Cross-border transaction check
         if let Some(ref metadata) = transaction.metadata {
-            if metadata
-                .get("cross_border")
-                .map(|v| v == "true")
-                .unwrap_or(false)
+            if self.checks(RedFlagType::CrossBorder)
+                && metadata
+                    .get("cross_border")
+                    .map(|v| v == "true")
+                    .unwrap_or(false)
             {
                 red_flags.push(AMLRedFlag {
                     flag_type: RedFlagType::CrossBorder,
@@ -153,15 +229,17 @@ impl AMLChecker {
                         .to_string(),
                     severity: AlertSeverity::Medium,
                 });
-                risk_score += 20;
+                risk_score = risk_score.saturating_add(20);
             }
         }
 
         // Cash intensive check
-        if matches!(
-            transaction.transaction_type,
-            crate::TransactionType::Deposit | crate::TransactionType::Withdrawal
-        ) && transaction.amount >= 5000.0
+        if self.checks(RedFlagType::CashIntensive)
+            && matches!(
+                transaction.transaction_type,
+                crate::TransactionType::Deposit | crate::TransactionType::Withdrawal
+            )
+            && transaction.amount >= self.thresholds.cash_threshold
         {
             red_flags.push(AMLRedFlag {
                 flag_type: RedFlagType::CashIntensive,
@@ -171,9 +249,12 @@ impl AMLChecker {
                 ),
                 severity: AlertSeverity::High,
             });
-            risk_score += 25;
+            risk_score = risk_score.saturating_add(25);
         }
 
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("risk_score", risk_score.min(100));
+
         AMLResult {
             compliant: risk_score < 75,
             requires_ctr,
@@ -183,19 +264,63 @@ impl AMLChecker {
         }
     }
 
+    fn checks(&self, flag: RedFlagType) -> bool {
+        self.red_flags.contains(&flag)
+    }
+
+    /// Replace the thresholds, e.g. with a jurisdiction's
+    pub fn with_thresholds(mut self, thresholds: AMLThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Check only these red flags
+    pub fn with_red_flags(mut self, red_flags: Vec<RedFlagType>) -> Self {
+        self.red_flags = red_flags;
+        self
+    }
+
+    /// Thresholds in use
+    pub fn thresholds(&self) -> &AMLThresholds {
+        &self.thresholds
+    }
+
     fn is_potential_structuring(&self, transaction: &Transaction) -> bool {
         transaction.amount >= self.thresholds.structuring_threshold
             && transaction.amount < self.thresholds.ctr_threshold
     }
 
+    /// Whether either account of the transaction is a sanctioned entity
+    pub fn involves_sanctioned_entity(&self, transaction: &Transaction) -> bool {
+        [&transaction.from_account, &transaction.to_account]
+            .into_iter()
+            .flatten()
+            .any(|account| self.is_sanctioned_entity(account))
+    }
+
     fn is_sanctioned_entity(&self, entity: &str) -> bool {
-        self.sanctioned_entities.iter().any(|s| entity.contains(s))
+        let normalized = normalize_entity(entity);
+        if normalized.is_empty() {
+            return false;
+        }
+        self.sanctioned_entities.contains(&normalized)
+            || self
+                .screener
+                .as_ref()
+                .is_some_and(|s| s.screen(entity).has_high_confidence_match())
+    }
+
+    /// Also treat high-confidence fuzzy matches from `screener` as sanctioned
+    pub fn with_screener(mut self, screener: SanctionsScreener) -> Self {
+        self.screener = Some(screener);
+        self
     }
 
     /// Add sanctioned entity to list
     pub fn add_sanctioned_entity(&mut self, entity: String) {
-        if !self.sanctioned_entities.contains(&entity) {
-            self.sanctioned_entities.push(entity);
+        let normalized = normalize_entity(&entity);
+        if !normalized.is_empty() && !self.sanctioned_entities.contains(&normalized) {
+            self.sanctioned_entities.push(normalized);
         }
     }
 
@@ -283,19 +408,16 @@ pub struct KYCValidationResult {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_fixtures;
     use chrono::Utc;
 
     fn create_test_transaction(amount: f64, txn_type: crate::TransactionType) -> Transaction {
         Transaction {
-            transaction_id: "TXN-001".to_string(),
+            transaction_type: txn_type,
             from_account: Some("ACC-123".to_string()),
             to_account: Some("ACC-456".to_string()),
-            amount,
-            currency: "USD".to_string(),
             timestamp: Utc::now(),
-            transaction_type: txn_type,
-            user_id: "USER-001".to_string(),
-            metadata: None,
+            ..test_fixtures::create_test_transaction("TXN-001", amount)
         }
     }
 
@@ -341,6 +463,54 @@ mod tests {
             .any(|f| f.flag_type == RedFlagType::SanctionedEntity));
     }
 
+    #[test]
+    fn test_sanctions_list_requires_exact_match() {
+        let mut checker = AMLChecker::new();
+        assert!(checker.check_sanctions_list("ofac sanctioned 001"));
+        assert!(!checker.check_sanctions_list("NOT-OFAC-SANCTIONED-001-REALLY"));
+        assert!(!checker.check_sanctions_list("OFAC-SANCTIONED-0010"));
+        assert!(!checker.check_sanctions_list("OFAC-SANCTIONED"));
+        assert!(!checker.check_sanctions_list(""));
+        assert!(!checker.check_sanctions_list("--"));
+
+        checker.add_sanctioned_entity("Acme Shell Co.".to_string());
+        assert!(checker.check_sanctions_list("ACME SHELL CO"));
+        assert!(!checker.check_sanctions_list("ACME SHELL COMPANY"));
+
+        let mut txn = create_test_transaction(1000.0, crate::TransactionType::Transfer);
+        txn.to_account = Some("NOT-OFAC-SANCTIONED-001-REALLY".to_string());
+        assert!(!checker
+            .check_compliance(&txn)
+            .red_flags
+            .iter()
+            .any(|f| f.flag_type == RedFlagType::SanctionedEntity));
+    }
+
+    #[test]
+    fn test_sanctions_list_delegates_to_screener() {
+        let checker = AMLChecker::new().with_screener(SanctionsScreener::new());
+        assert!(checker.check_sanctions_list("E1 LTD"));
+        assert!(!checker.check_sanctions_list("LEGITIMATE COMPANY XYZ"));
+        assert!(!AMLChecker::new().check_sanctions_list("E1 LTD"));
+    }
+
+    #[test]
+    fn test_sanctioned_jurisdiction() {
+        let checker = AMLChecker::new();
+        let mut txn = create_test_transaction(100.0, crate::TransactionType::Transfer);
+        txn.metadata = Some(crate::TransactionMetadata::from([(
+            "destination_region".to_string(),
+            "UA-43".to_string(),
+        )]));
+
+        let result = checker.check_compliance(&txn);
+        assert!(!result.compliant);
+        assert!(result
+            .red_flags
+            .iter()
+            .any(|f| f.flag_type == RedFlagType::SanctionedJurisdiction));
+    }
+
     #[test]
     fn test_cash_intensive() {
         let checker = AMLChecker::new();
@@ -357,8 +527,8 @@ mod tests {
     fn test_cross_border() {
         let checker = AMLChecker::new();
         let mut txn = create_test_transaction(5000.0, crate::TransactionType::Transfer);
-        let mut metadata = std::collections::HashMap::new();
-        metadata.insert("cross_border".to_string(), "true".to_string());
+        let mut metadata = crate::TransactionMetadata::new();
+        metadata.insert("cross_border", "true");
         txn.metadata = Some(metadata);
 
         let result = checker.check_compliance(&txn);
@@ -0,0 +1,216 @@
+//! Dashboard aggregates from the audit trail
+//!
+//! [`AnalyticsReport`] groups [`AuditRecord`]s into hourly or daily buckets
+//! (by validation time, UTC) and summarizes each: validation counts by
+//! decision, the total amount not approved, the most frequent reason codes,
+//! alert volumes by trigger, and the fraud score distribution. The report
+//! and its buckets serialize as-is, so a dashboard can read them directly
+//! instead of re-parsing raw results. Records can be fed incrementally with
+//! [`AnalyticsAggregator`], e.g. from an [`AuditSink`](crate::AuditSink).
+
+use crate::batch::{ReasonCodeCount, TOP_REASON_CODES_LIMIT};
+use crate::stats::{DecisionCounts, ScoreDistribution};
+use crate::{AuditRecord, Decision};
+use chrono::{DateTime, DurationRound, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Width of an aggregation bucket
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BucketWidth {
+    Hour,
+    Day,
+}
+
+impl BucketWidth {
+    /// Start of the bucket containing `at`
+    pub fn bucket_start(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let width = match self {
+            BucketWidth::Hour => TimeDelta::hours(1),
+            BucketWidth::Day => TimeDelta::days(1),
+        };
+        at.duration_trunc(width).unwrap_or(at)
+    }
+}
+
+/// Aggregates for one time bucket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BucketAggregate {
+    pub bucket_start: DateTime<Utc>,
+    pub validations: usize,
+    pub decisions: DecisionCounts,
+    /// Total amount of validations not approved
+    pub flagged_amount: f64,
+    /// Most frequent error reason codes, most common first
+    pub top_reason_codes: Vec<ReasonCodeCount>,
+    pub alerts: usize,
+    /// Alert counts keyed by trigger
+    pub alerts_by_trigger: BTreeMap<String, usize>,
+    pub score_distribution: ScoreDistribution,
+}
+
+/// Time-bucketed aggregates, oldest bucket first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnalyticsReport {
+    pub width: BucketWidth,
+    pub buckets: Vec<BucketAggregate>,
+}
+
+impl AnalyticsReport {
+    /// Aggregate a slice of the audit trail
+    pub fn from_records<'a>(
+        records: impl IntoIterator<Item = &'a AuditRecord>,
+        width: BucketWidth,
+    ) -> Self {
+        let mut aggregator = AnalyticsAggregator::new(width);
+        for record in records {
+            aggregator.record(record);
+        }
+        aggregator.report()
+    }
+
+    /// Export as JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct BucketCounters {
+    validations: usize,
+    decisions: DecisionCounts,
+    flagged_amount: f64,
+    reason_codes: HashMap<String, usize>,
+    alerts: usize,
+    alerts_by_trigger: BTreeMap<String, usize>,
+    score_distribution: ScoreDistribution,
+}
+
+/// Running aggregates over audit records
+#[derive(Debug, Clone)]
+pub struct AnalyticsAggregator {
+    width: BucketWidth,
+    buckets: BTreeMap<DateTime<Utc>, BucketCounters>,
+}
+
+fn parse_decision(decision: &str) -> Option<Decision> {
+    match decision {
+        "approve" => Some(Decision::Approve),
+        "review" => Some(Decision::Review),
+        "step_up" => Some(Decision::StepUp),
+        "decline" => Some(Decision::Decline),
+        _ => None,
+    }
+}
+
+impl AnalyticsAggregator {
+    pub fn new(width: BucketWidth) -> Self {
+        Self {
+            width,
+            buckets: BTreeMap::new(),
+        }
+    }
+
+    /// Add one validation to its bucket
+    pub fn record(&mut self, record: &AuditRecord) {
+        let start = self.width.bucket_start(record.result.validated_at);
+        let bucket = self.buckets.entry(start).or_default();
+        bucket.validations += 1;
+        let decision = parse_decision(&record.result.decision);
+        if let Some(decision) = decision {
+            bucket.decisions.record(decision);
+        }
+        if decision != Some(Decision::Approve) {
+            bucket.flagged_amount += record.result.amount;
+        }
+        for error in &record.errors {
+            *bucket
+                .reason_codes
+                .entry(error.reason_code.clone())
+                .or_insert(0) += 1;
+        }
+        bucket.alerts += record.alerts.len();
+        for alert in &record.alerts {
+            *bucket
+                .alerts_by_trigger
+                .entry(alert.trigger.clone())
+                .or_insert(0) += 1;
+        }
+        bucket.score_distribution.record(record.result.fraud_score);
+    }
+
+    /// Snapshot of the aggregates so far
+    pub fn report(&self) -> AnalyticsReport {
+        let buckets = self
+            .buckets
+            .iter()
+            .map(|(start, counters)| {
+                let mut top_reason_codes: Vec<ReasonCodeCount> = counters
+                    .reason_codes
+                    .iter()
+                    .map(|(code, count)| ReasonCodeCount {
+                        code: code.clone(),
+                        count: *count,
+                    })
+                    .collect();
+                top_reason_codes
+                    .sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.code.cmp(&b.code)));
+                top_reason_codes.truncate(TOP_REASON_CODES_LIMIT);
+                BucketAggregate {
+                    bucket_start: *start,
+                    validations: counters.validations,
+                    decisions: counters.decisions.clone(),
+                    flagged_amount: counters.flagged_amount,
+                    top_reason_codes,
+                    alerts: counters.alerts,
+                    alerts_by_trigger: counters.alerts_by_trigger.clone(),
+                    score_distribution: counters.score_distribution.clone(),
+                }
+            })
+            .collect();
+        AnalyticsReport {
+            width: self.width,
+            buckets,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::TransactionValidator;
+    use chrono::Duration;
+
+    #[test]
+    fn test_daily_buckets_from_audit_records() {
+        let mut validator = TransactionValidator::new();
+        let mut records = Vec::new();
+        for (id, amount, account) in [
+            ("TXN-AN-1", 100.0, "ACCT-1234-5678-9012"),
+            ("TXN-AN-2", 250.0, "bad account"),
+            ("TXN-AN-3", 75.0, "bad account"),
+        ] {
+            let mut tx = create_test_transaction(id, amount);
+            tx.from_account = Some(account.to_string());
+            let result = validator.validate(&tx);
+            records.push(AuditRecord::new(&tx, &result, &[], "v1"));
+        }
+        // Move the last record to the next day
+        records[2].result.validated_at += Duration::days(1);
+
+        let report = AnalyticsReport::from_records(&records, BucketWidth::Day);
+        assert_eq!(report.buckets.len(), 2);
+        let first = &report.buckets[0];
+        assert_eq!(first.validations, 2);
+        assert_eq!(first.decisions.approve, 1);
+        assert_eq!(first.flagged_amount, 250.0);
+        assert_eq!(first.top_reason_codes[0].code, "INVALID_ACCOUNT");
+        assert_eq!(first.score_distribution.low, 2);
+        assert_eq!(first.bucket_start.time(), chrono::NaiveTime::MIN);
+        assert_eq!(report.buckets[1].flagged_amount, 75.0);
+
+        let parsed: AnalyticsReport = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(parsed, report);
+    }
+}
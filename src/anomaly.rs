@@ -0,0 +1,361 @@
+//! Population-level anomaly detection
+//!
+//! Per-transaction checks judge each payment on its own; some laundering
+//! and fraud only shows up in the shape of an account's activity. The
+//! detectors here compare each debited account's transactions in a batch
+//! with the same account's baseline period:
+//!
+//! - amount shift: the two-sample Kolmogorov-Smirnov statistic between
+//!   baseline and batch amounts exceeds the critical value
+//! - counterparty concentration: one beneficiary takes an outsized share of
+//!   the account's volume, well above its baseline share
+//! - hour concentration: one hour of day (UTC) holds an outsized share of
+//!   the account's transactions, well above its baseline share
+//! - digit frequency: leading digits of the account's amounts depart from
+//!   Benford's law (chi-square, 8 degrees of freedom)
+//!
+//! Each finding's score is its statistic divided by the threshold, so every
+//! finding scores at least 1 and findings rank across detectors, highest
+//! first.
+
+use crate::Transaction;
+use chrono::Timelike;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Thresholds for the anomaly detectors
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnomalyCheckConfig {
+    /// Transactions an account needs in the batch, and in the baseline for
+    /// comparisons with it
+    pub min_transactions: usize,
+    /// Kolmogorov-Smirnov coefficient c(α); 1.628 is α = 0.01
+    pub amount_shift_coefficient: f64,
+    /// Share of volume to one counterparty that counts as concentrated
+    pub counterparty_share: f64,
+    /// Share of transactions in one hour of day that counts as concentrated
+    pub hour_share: f64,
+    /// Amounts needed before leading digits are tested
+    pub min_digit_sample: usize,
+    /// Chi-square critical value; 20.09 is α = 0.01 with 8 degrees of freedom
+    pub digit_chi_square: f64,
+}
+
+impl Default for AnomalyCheckConfig {
+    fn default() -> Self {
+        Self {
+            min_transactions: 10,
+            amount_shift_coefficient: 1.628,
+            counterparty_share: 0.6,
+            hour_share: 0.5,
+            min_digit_sample: 50,
+            digit_chi_square: 20.09,
+        }
+    }
+}
+
+/// Kind of distributional anomaly
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnomalyKind {
+    AmountShift,
+    CounterpartyConcentration,
+    HourConcentration,
+    DigitFrequency,
+}
+
+/// Distributional anomaly in one account's batch activity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnomalyFinding {
+    /// Debited account
+    pub account: String,
+    pub kind: AnomalyKind,
+    /// Statistic divided by its threshold; at least 1 for every finding
+    pub score: f64,
+    /// Positions of the involved transactions in the batch
+    pub indices: Vec<usize>,
+    pub description: String,
+}
+
+/// Two-sample Kolmogorov-Smirnov statistic
+pub fn ks_statistic(a: &[f64], b: &[f64]) -> f64 {
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+    a.sort_by(f64::total_cmp);
+    b.sort_by(f64::total_cmp);
+    let (mut i, mut j, mut d) = (0, 0, 0.0f64);
+    while i < a.len() && j < b.len() {
+        let x = a[i].min(b[j]);
+        while i < a.len() && a[i] <= x {
+            i += 1;
+        }
+        while j < b.len() && b[j] <= x {
+            j += 1;
+        }
+        d = d.max((i as f64 / a.len() as f64 - j as f64 / b.len() as f64).abs());
+    }
+    d
+}
+
+fn leading_digit(amount: f64) -> Option<usize> {
+    if !amount.is_finite() || amount <= 0.0 {
+        return None;
+    }
+    let exponent = amount.log10().floor();
+    let digit = (amount / 10f64.powf(exponent)).floor() as usize;
+    Some(digit.clamp(1, 9))
+}
+
+/// Chi-square statistic of leading digits against Benford's law
+pub fn benford_chi_square(amounts: &[f64]) -> f64 {
+    let mut observed = [0usize; 10];
+    for digit in amounts.iter().filter_map(|a| leading_digit(*a)) {
+        observed[digit] += 1;
+    }
+    let n: usize = observed.iter().sum();
+    if n == 0 {
+        return 0.0;
+    }
+    (1..=9)
+        .map(|d| {
+            let expected = n as f64 * (1.0 + 1.0 / d as f64).log10();
+            (observed[d] as f64 - expected).powi(2) / expected
+        })
+        .sum()
+}
+
+/// Most common key and its share of the total weight
+fn top_share<K: Ord + Clone>(weights: &BTreeMap<K, f64>) -> Option<(K, f64)> {
+    let total: f64 = weights.values().sum();
+    let (key, weight) = weights
+        .iter()
+        .max_by(|a, b| a.1.total_cmp(b.1).then_with(|| b.0.cmp(a.0)))?;
+    (total > 0.0).then(|| (key.clone(), weight / total))
+}
+
+fn share_of<K: Ord>(weights: &BTreeMap<K, f64>, key: &K) -> f64 {
+    let total: f64 = weights.values().sum();
+    if total > 0.0 {
+        weights.get(key).copied().unwrap_or(0.0) / total
+    } else {
+        0.0
+    }
+}
+
+/// Find distributional anomalies per debited account, ranked by score
+pub fn detect_anomalies<'a>(
+    batch: &[Transaction],
+    baseline: impl IntoIterator<Item = &'a Transaction>,
+    config: &AnomalyCheckConfig,
+) -> Vec<AnomalyFinding> {
+    let mut by_account: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (i, tx) in batch.iter().enumerate() {
+        if let Some(account) = tx.from_account.as_deref() {
+            by_account.entry(account).or_default().push(i);
+        }
+    }
+    let mut baseline_by_account: HashMap<&str, Vec<&Transaction>> = HashMap::new();
+    for tx in baseline {
+        if let Some(account) = tx.from_account.as_deref() {
+            baseline_by_account.entry(account).or_default().push(tx);
+        }
+    }
+
+    let mut findings = Vec::new();
+    for (account, indices) in by_account {
+        if indices.len() < config.min_transactions {
+            continue;
+        }
+        let current: Vec<&Transaction> = indices.iter().map(|&i| &batch[i]).collect();
+        let past = baseline_by_account.remove(account).unwrap_or_default();
+        let has_baseline = past.len() >= config.min_transactions;
+        let mut push = |kind, score: f64, involved: Vec<usize>, description: String| {
+            findings.push(AnomalyFinding {
+                account: account.to_string(),
+                kind,
+                score,
+                indices: involved,
+                description,
+            });
+        };
+
+        let amounts: Vec<f64> = current.iter().map(|tx| tx.amount).collect();
+        if has_baseline {
+            let past_amounts: Vec<f64> = past.iter().map(|tx| tx.amount).collect();
+            let (n, m) = (amounts.len() as f64, past_amounts.len() as f64);
+            let critical = config.amount_shift_coefficient * ((n + m) / (n * m)).sqrt();
+            let d = ks_statistic(&amounts, &past_amounts);
+            if d > critical {
+                push(
+                    AnomalyKind::AmountShift,
+                    d / critical,
+                    indices.clone(),
+                    format!(
+                        "Amounts of {} shifted from baseline (KS {:.2} > {:.2})",
+                        account, d, critical
+                    ),
+                );
+            }
+        }
+
+        let mut volume: BTreeMap<&str, f64> = BTreeMap::new();
+        for tx in &current {
+            if let Some(to) = tx.to_account.as_deref() {
+                *volume.entry(to).or_insert(0.0) += tx.amount;
+            }
+        }
+        if let Some((to, share)) = top_share(&volume) {
+            let mut past_volume: BTreeMap<&str, f64> = BTreeMap::new();
+            for tx in &past {
+                if let Some(past_to) = tx.to_account.as_deref() {
+                    *past_volume.entry(past_to).or_insert(0.0) += tx.amount;
+                }
+            }
+            let baseline_share = share_of(&past_volume, &to);
+            if share >= config.counterparty_share && (!has_baseline || baseline_share < share / 2.0)
+            {
+                push(
+                    AnomalyKind::CounterpartyConcentration,
+                    share / config.counterparty_share,
+                    indices
+                        .iter()
+                        .copied()
+                        .filter(|&i| batch[i].to_account.as_deref() == Some(to))
+                        .collect(),
+                    format!(
+                        "{:.0}% of {}'s volume went to {} (baseline {:.0}%)",
+                        share * 100.0,
+                        account,
+                        to,
+                        baseline_share * 100.0
+                    ),
+                );
+            }
+        }
+
+        let hours = |txs: &[&Transaction]| {
+            let mut counts: BTreeMap<u32, f64> = BTreeMap::new();
+            for tx in txs {
+                *counts.entry(tx.timestamp.hour()).or_insert(0.0) += 1.0;
+            }
+            counts
+        };
+        if let Some((hour, share)) = top_share(&hours(&current)) {
+            let baseline_share = share_of(&hours(&past), &hour);
+            if share >= config.hour_share && (!has_baseline || baseline_share < share / 2.0) {
+                push(
+                    AnomalyKind::HourConcentration,
+                    share / config.hour_share,
+                    indices
+                        .iter()
+                        .copied()
+                        .filter(|&i| batch[i].timestamp.hour() == hour)
+                        .collect(),
+                    format!(
+                        "{:.0}% of {}'s transactions fell in hour {:02}:00 UTC (baseline {:.0}%)",
+                        share * 100.0,
+                        account,
+                        hour,
+                        baseline_share * 100.0
+                    ),
+                );
+            }
+        }
+
+        if amounts.len() >= config.min_digit_sample {
+            let chi_square = benford_chi_square(&amounts);
+            if chi_square > config.digit_chi_square {
+                push(
+                    AnomalyKind::DigitFrequency,
+                    chi_square / config.digit_chi_square,
+                    indices.clone(),
+                    format!(
+                        "Leading digits of {}'s amounts depart from Benford's law (chi-square {:.1})",
+                        account, chi_square
+                    ),
+                );
+            }
+        }
+    }
+
+    findings.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.account.cmp(&b.account))
+    });
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::TransactionValidator;
+    use chrono::Duration;
+
+    /// Spread-out activity: varied amounts, payees, and hours
+    fn varied(prefix: &str, count: usize) -> Vec<Transaction> {
+        (0..count)
+            .map(|i| {
+                let mut tx =
+                    create_test_transaction(&format!("{}-{}", prefix, i), 20.0 + i as f64 * 7.0);
+                tx.to_account = Some(format!("ACCT-5000-0000-{:04}", i % 8));
+                tx.timestamp -= Duration::hours((i % 12) as i64);
+                tx
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_statistics() {
+        assert_eq!(ks_statistic(&[1.0, 2.0, 3.0], &[1.0, 2.0, 3.0]), 0.0);
+        assert_eq!(ks_statistic(&[1.0, 2.0], &[5.0, 6.0]), 1.0);
+        // Amounts spanning several orders of magnitude follow Benford closely
+        let spread: Vec<f64> = (0..500).map(|i| 1.02f64.powi(i)).collect();
+        assert!(benford_chi_square(&spread) < 20.09);
+        assert!(benford_chi_square(&[5000.0; 60]) > 20.09);
+    }
+
+    #[test]
+    fn test_shifted_account_is_ranked() {
+        let baseline = varied("TXN-BASE", 20);
+        let mut batch = varied("TXN-NORM", 12);
+        // The same account now sends large payments to one payee at 03:00
+        for tx in &mut batch {
+            tx.from_account = Some("ACCT-0000-1111-2222".to_string());
+        }
+        let mut shifted = varied("TXN-SHIFT", 12);
+        for tx in &mut shifted {
+            tx.amount *= 40.0;
+            tx.to_account = Some("ACCT-9999-0000-0001".to_string());
+            tx.timestamp = tx
+                .timestamp
+                .date_naive()
+                .and_hms_opt(3, 0, 0)
+                .unwrap()
+                .and_utc();
+        }
+        batch.extend(shifted);
+
+        let config = AnomalyCheckConfig::default();
+        let findings = detect_anomalies(&batch, &baseline, &config);
+        let flagged: Vec<AnomalyKind> = findings
+            .iter()
+            .filter(|f| f.account == "ACCT-1234-5678-9012")
+            .map(|f| f.kind)
+            .collect();
+        assert!(flagged.contains(&AnomalyKind::AmountShift));
+        assert!(flagged.contains(&AnomalyKind::CounterpartyConcentration));
+        assert!(flagged.contains(&AnomalyKind::HourConcentration));
+        assert!(findings.windows(2).all(|w| w[0].score >= w[1].score));
+        assert!(findings.iter().all(|f| f.score >= 1.0));
+
+        // The validator's history serves as the baseline
+        let mut validator = TransactionValidator::with_config(crate::ValidatorConfig {
+            max_transactions_per_window: 100,
+            ..Default::default()
+        });
+        validator.validate_batch(&baseline);
+        let from_history = validator.batch_anomalies(&batch, &config);
+        assert_eq!(from_history, findings);
+    }
+}
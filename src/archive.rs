@@ -0,0 +1,456 @@
+//! Object storage archival
+//!
+//! Record-keeping rules require validation results and audit records to be
+//! kept for years, well past the life of an operational database. An
+//! [`ArchiveSink`] buffers both as JSON Lines and writes each batch as one
+//! object once it reaches [`ArchivePolicy::max_object_bytes`] or has been
+//! open for [`ArchivePolicy::max_object_age_seconds`]. Objects are
+//! gzip-compressed by default; results can be written as Parquet instead
+//! with the `parquet` feature (uncompressed, since the crate builds Parquet
+//! without codecs). The crate does not link a storage client; implement
+//! [`ObjectStore`] over the application's S3-compatible client so archival
+//! shares its credentials and retry setup.
+//!
+//! Clones of a sink share one buffer, so a validator can write audit
+//! records through [`set_audit_sink`](crate::TransactionValidator::set_audit_sink)
+//! while the application pushes full results with
+//! [`ArchiveSink::push_result`]. Keys are laid out by record kind and UTC
+//! date:
+//!
+//! ```text
+//! {prefix}/results/date=2026-10-14/20261014T120000.000Z-{sink}-000001.jsonl.gz
+//! {prefix}/audit/date=2026-10-14/20261014T120000.000Z-{sink}-000002.jsonl.gz
+//! ```
+
+use crate::clock::system_clock;
+use crate::{AuditRecord, AuditSink, SharedClock, ValidationResult};
+use chrono::{DateTime, Duration, Utc};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+/// Compression applied to JSON Lines objects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ArchiveCompression {
+    None,
+    #[default]
+    Gzip,
+}
+
+/// Encoding of result objects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ResultFormat {
+    /// Full results, one JSON object per line
+    #[default]
+    JsonLines,
+    /// The flattened columns of `ValidationResult::write_parquet`
+    #[cfg(feature = "parquet")]
+    Parquet,
+}
+
+/// When batches are written and how they are encoded
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivePolicy {
+    /// Key prefix, without a trailing slash
+    pub prefix: String,
+    /// Write a batch once its uncompressed JSON Lines reach this size
+    pub max_object_bytes: usize,
+    /// Write a batch once its first record is this old
+    pub max_object_age_seconds: i64,
+    pub compression: ArchiveCompression,
+    pub result_format: ResultFormat,
+}
+
+impl Default for ArchivePolicy {
+    fn default() -> Self {
+        Self {
+            prefix: "validator".to_string(),
+            max_object_bytes: 16 * 1024 * 1024,
+            max_object_age_seconds: 300,
+            compression: ArchiveCompression::Gzip,
+            result_format: ResultFormat::JsonLines,
+        }
+    }
+}
+
+/// One object handed to [`ObjectStore::put_object`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveObject {
+    pub key: String,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+    /// Results or audit records in the object
+    pub records: usize,
+}
+
+/// Minimal object writer backed by the application's storage client
+pub trait ObjectStore: Send {
+    /// Store the object under its key, replacing any existing one
+    fn put_object(&mut self, object: &ArchiveObject) -> io::Result<()>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Results,
+    Audit,
+}
+
+impl Kind {
+    fn name(&self) -> &'static str {
+        match self {
+            Kind::Results => "results",
+            Kind::Audit => "audit",
+        }
+    }
+}
+
+/// Records waiting for one object
+#[derive(Default)]
+struct Batch {
+    lines: Vec<u8>,
+    records: usize,
+    opened_at: Option<DateTime<Utc>>,
+    /// Kept for Parquet encoding
+    #[cfg(feature = "parquet")]
+    results: Vec<ValidationResult>,
+}
+
+impl Batch {
+    fn append(&mut self, line: &[u8], now: DateTime<Utc>) {
+        self.lines.extend_from_slice(line);
+        self.lines.push(b'\n');
+        self.records += 1;
+        self.opened_at.get_or_insert(now);
+    }
+
+    fn truncate(&mut self, len: usize, records: usize) {
+        self.lines.truncate(len);
+        self.records = records;
+        if records == 0 {
+            self.opened_at = None;
+        }
+        #[cfg(feature = "parquet")]
+        self.results.truncate(records);
+    }
+
+    fn is_due(&self, policy: &ArchivePolicy, now: DateTime<Utc>) -> bool {
+        let aged = self
+            .opened_at
+            .is_some_and(|opened| now - opened >= Duration::seconds(policy.max_object_age_seconds));
+        self.records > 0 && (self.lines.len() >= policy.max_object_bytes || aged)
+    }
+}
+
+struct ArchiveState<S> {
+    store: S,
+    policy: ArchivePolicy,
+    clock: SharedClock,
+    sink_id: String,
+    sequence: u64,
+    results: Batch,
+    audit: Batch,
+}
+
+impl<S: ObjectStore> ArchiveState<S> {
+    fn batch(&mut self, kind: Kind) -> &mut Batch {
+        match kind {
+            Kind::Results => &mut self.results,
+            Kind::Audit => &mut self.audit,
+        }
+    }
+
+    fn is_due(&self, kind: Kind, now: DateTime<Utc>) -> bool {
+        let batch = match kind {
+            Kind::Results => &self.results,
+            Kind::Audit => &self.audit,
+        };
+        batch.is_due(&self.policy, now)
+    }
+
+    fn encode(&self, kind: Kind) -> io::Result<(Vec<u8>, &'static str, &'static str)> {
+        let batch = match kind {
+            Kind::Results => &self.results,
+            Kind::Audit => &self.audit,
+        };
+        #[cfg(feature = "parquet")]
+        if kind == Kind::Results && self.policy.result_format == ResultFormat::Parquet {
+            let mut body = Vec::new();
+            ValidationResult::write_parquet(&batch.results, &mut body).map_err(io::Error::other)?;
+            return Ok((body, "parquet", "application/vnd.apache.parquet"));
+        }
+        Ok(match self.policy.compression {
+            ArchiveCompression::None => (batch.lines.clone(), "jsonl", "application/x-ndjson"),
+            ArchiveCompression::Gzip => (gzip(&batch.lines)?, "jsonl.gz", "application/gzip"),
+        })
+    }
+
+    /// Write one batch; it stays buffered if the store fails
+    fn write(&mut self, kind: Kind) -> io::Result<bool> {
+        let (records, opened_at) = {
+            let batch = self.batch(kind);
+            match batch.opened_at {
+                Some(opened_at) if batch.records > 0 => (batch.records, opened_at),
+                _ => return Ok(false),
+            }
+        };
+        let (body, extension, content_type) = self.encode(kind)?;
+        let object = ArchiveObject {
+            key: format!(
+                "{}/{}/date={}/{}-{}-{:06}.{}",
+                self.policy.prefix,
+                kind.name(),
+                opened_at.format("%Y-%m-%d"),
+                opened_at.format("%Y%m%dT%H%M%S%.3fZ"),
+                self.sink_id,
+                self.sequence + 1,
+                extension
+            ),
+            content_type,
+            body,
+            records,
+        };
+        self.store.put_object(&object)?;
+        self.sequence += 1;
+        *self.batch(kind) = Batch::default();
+        Ok(true)
+    }
+
+    fn write_due(&mut self) -> io::Result<usize> {
+        let now = self.clock.now();
+        let mut written = 0;
+        for kind in [Kind::Results, Kind::Audit] {
+            if self.is_due(kind, now) {
+                written += self.write(kind)? as usize;
+            }
+        }
+        Ok(written)
+    }
+
+    /// Append records and write the batch if due, undoing the append on failure
+    fn push(&mut self, kind: Kind, lines: Vec<Vec<u8>>) -> io::Result<()> {
+        let now = self.clock.now();
+        let batch = self.batch(kind);
+        let (len, records) = (batch.lines.len(), batch.records);
+        for line in &lines {
+            batch.append(line, now);
+        }
+        if !self.is_due(kind, now) {
+            return Ok(());
+        }
+        let result = self.write(kind);
+        if result.is_err() {
+            self.batch(kind).truncate(len, records);
+        }
+        result.map(|_| ())
+    }
+}
+
+/// Batching writer of results and audit records to object storage
+pub struct ArchiveSink<S: ObjectStore> {
+    state: Arc<Mutex<ArchiveState<S>>>,
+}
+
+impl<S: ObjectStore> Clone for ArchiveSink<S> {
+    fn clone(&self) -> Self {
+        Self {
+            state: Arc::clone(&self.state),
+        }
+    }
+}
+
+impl<S: ObjectStore> ArchiveSink<S> {
+    pub fn new(store: S, policy: ArchivePolicy) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(ArchiveState {
+                store,
+                policy,
+                clock: system_clock(),
+                sink_id: uuid::Uuid::new_v4().simple().to_string()[..8].to_string(),
+                sequence: 0,
+                results: Batch::default(),
+                audit: Batch::default(),
+            })),
+        }
+    }
+
+    /// Read batch ages and key dates from `clock`
+    pub fn with_clock(self, clock: SharedClock) -> Self {
+        self.lock().clock = clock;
+        self
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ArchiveState<S>> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Buffer a result, writing any batch that is due
+    ///
+    /// On error the result is not buffered and may be pushed again.
+    pub fn push_result(&self, result: &ValidationResult) -> io::Result<()> {
+        let line = serde_json::to_vec(result)?;
+        let mut state = self.lock();
+        #[cfg(feature = "parquet")]
+        state.results.results.push(result.clone());
+        state.push(Kind::Results, vec![line])
+    }
+
+    /// Write batches that reached their size or age; returns objects written
+    ///
+    /// Age is only checked when records arrive, so call this periodically
+    /// to bound how long a quiet period leaves records unwritten.
+    pub fn flush_due(&self) -> io::Result<usize> {
+        self.lock().write_due()
+    }
+
+    /// Write every non-empty batch now; returns objects written
+    pub fn flush(&self) -> io::Result<usize> {
+        let mut state = self.lock();
+        let results = state.write(Kind::Results)? as usize;
+        Ok(results + state.write(Kind::Audit)? as usize)
+    }
+
+    /// Buffered results and audit records
+    pub fn pending(&self) -> (usize, usize) {
+        let state = self.lock();
+        (state.results.records, state.audit.records)
+    }
+
+    /// Objects written by this sink and its clones
+    pub fn objects_written(&self) -> u64 {
+        self.lock().sequence
+    }
+}
+
+impl<S: ObjectStore> AuditSink for ArchiveSink<S> {
+    /// Buffer the batch; on error none of it is buffered
+    fn write_batch(&mut self, records: &[AuditRecord]) -> io::Result<()> {
+        let lines = records
+            .iter()
+            .map(serde_json::to_vec)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.lock().push(Kind::Audit, lines)
+    }
+}
+
+fn gzip(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{MockClock, TransactionValidator};
+    use chrono::TimeZone;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    /// Store keeping objects in memory; fails while `failing` is set
+    #[derive(Clone, Default)]
+    struct MemoryStore {
+        objects: Arc<Mutex<Vec<ArchiveObject>>>,
+        failing: Arc<Mutex<bool>>,
+    }
+
+    impl ObjectStore for MemoryStore {
+        fn put_object(&mut self, object: &ArchiveObject) -> io::Result<()> {
+            if *self.failing.lock().unwrap() {
+                return Err(io::Error::other("bucket unavailable"));
+            }
+            self.objects.lock().unwrap().push(object.clone());
+            Ok(())
+        }
+    }
+
+    fn gunzip(member: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        GzDecoder::new(member).read_to_end(&mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_results_written_on_size_trigger() {
+        let store = MemoryStore::default();
+        let policy = ArchivePolicy {
+            prefix: "archive".to_string(),
+            max_object_bytes: 4096,
+            ..Default::default()
+        };
+        let sink = ArchiveSink::new(store.clone(), policy);
+        let mut validator = TransactionValidator::new();
+        for i in 0..10 {
+            let result =
+                validator.validate(&create_test_transaction(&format!("TXN-AR-{}", i), 100.0));
+            sink.push_result(&result).unwrap();
+        }
+
+        let objects = store.objects.lock().unwrap().clone();
+        assert!(!objects.is_empty());
+        let first = &objects[0];
+        assert!(first.key.starts_with("archive/results/date="));
+        assert!(first.key.ends_with("-000001.jsonl.gz"));
+        let lines = gunzip(&first.body);
+        let archived: Vec<ValidationResult> = String::from_utf8(lines)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(archived.len(), first.records);
+        assert_eq!(archived[0].transaction_id, "TXN-AR-0");
+
+        let (pending, _) = sink.pending();
+        let written: usize = objects.iter().map(|o| o.records).sum();
+        assert_eq!(written + pending, 10);
+        sink.flush().unwrap();
+        assert_eq!(sink.pending(), (0, 0));
+    }
+
+    #[test]
+    fn test_audit_records_written_on_age_and_kept_on_failure() {
+        let store = MemoryStore::default();
+        let start = Utc.with_ymd_and_hms(2026, 10, 14, 9, 30, 0).unwrap();
+        let clock = MockClock::new(start);
+        let policy = ArchivePolicy {
+            compression: ArchiveCompression::None,
+            ..Default::default()
+        };
+        let sink = ArchiveSink::new(store.clone(), policy).with_clock(Arc::new(clock.clone()));
+        let mut validator = TransactionValidator::new();
+        validator.set_audit_sink(Box::new(sink.clone()), 1);
+
+        validator.validate(&create_test_transaction("TXN-AR-20", 100.0));
+        assert_eq!(sink.pending(), (0, 1));
+        assert_eq!(sink.flush_due().unwrap(), 0);
+
+        *store.failing.lock().unwrap() = true;
+        clock.advance(Duration::seconds(300));
+        assert!(sink.flush_due().is_err());
+        // A new batch that cannot be written is refused whole
+        validator.validate(&create_test_transaction("TXN-AR-21", 100.0));
+        assert_eq!(sink.pending(), (0, 1));
+
+        *store.failing.lock().unwrap() = false;
+        validator.flush_audit().unwrap();
+        let objects = store.objects.lock().unwrap().clone();
+        assert_eq!(objects.len(), 1);
+        assert!(objects[0]
+            .key
+            .starts_with("validator/audit/date=2026-10-14/20261014T093000.000Z-"));
+        assert_eq!(objects[0].content_type, "application/x-ndjson");
+        let records: Vec<AuditRecord> = String::from_utf8(objects[0].body.clone())
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let ids: Vec<&str> = records
+            .iter()
+            .map(|r| r.result.transaction_id.as_str())
+            .collect();
+        assert_eq!(ids, ["TXN-AR-20", "TXN-AR-21"]);
+        assert_eq!(sink.objects_written(), 1);
+    }
+}
@@ -0,0 +1,327 @@
+//! Normalized audit records
+//!
+//! Compliance reporting wants SQL, not JSON blobs: "every declined wire with
+//! a sanctions failure last quarter" should be a join, not a scan. An
+//! [`AuditRecord`] splits one validation into rows for a result table and
+//! child tables for errors, warnings, compliance-check outcomes (including
+//! sanctions and jurisdiction screening), and the alerts it raised. An
+//! [`AuditSink`] receives records in batches; the validator buffers them and
+//! hands over a batch once `batch_size` records are pending or on
+//! [`TransactionValidator::flush_audit`](crate::TransactionValidator::flush_audit).
+//!
+//! Records are also where look-backs start: an [`AuditTrail`] returns the
+//! stored records touching an account, which is how follow-up alerts find
+//! transactions that were approved before a pattern became visible.
+//!
+//! With the `postgres-sql` feature,
+//! [`PostgresAuditSink`](crate::postgres::PostgresAuditSink) builds
+//! multi-row inserts of batches into normalized Postgres tables and runs
+//! them through the application's own driver.
+
+use crate::{hash_identifier, AlertEvent, Transaction, ValidationResult};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io;
+
+/// One row per validation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResultRow {
+    pub transaction_id: String,
+    pub user_id_hash: String,
+    #[serde(default)]
+    pub from_account_hash: Option<String>,
+    #[serde(default)]
+    pub to_account_hash: Option<String>,
+    pub amount: f64,
+    pub currency: String,
+    pub decision: String,
+    pub is_valid: bool,
+    pub fraud_score: u8,
+    pub tenant_id: Option<String>,
+    pub committed: bool,
+    pub config_version: String,
+    pub validated_at: DateTime<Utc>,
+}
+
+/// One row per validation error
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorRow {
+    pub transaction_id: String,
+    pub reason_code: String,
+    pub code: u16,
+    pub message: String,
+}
+
+/// One row per warning
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WarningRow {
+    pub transaction_id: String,
+    pub code: String,
+    pub severity: String,
+    pub message: String,
+}
+
+/// One row per compliance or screening check outcome
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComplianceRow {
+    pub transaction_id: String,
+    pub check_name: String,
+    pub passed: bool,
+}
+
+/// One row per alert raised
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlertRow {
+    pub event_id: String,
+    pub transaction_id: String,
+    pub trigger: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// One validation, split into normalized rows
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub result: ResultRow,
+    pub errors: Vec<ErrorRow>,
+    pub warnings: Vec<WarningRow>,
+    pub compliance_checks: Vec<ComplianceRow>,
+    pub alerts: Vec<AlertRow>,
+}
+
+impl AuditRecord {
+    /// Build a record from a validation and the alerts it raised
+    pub fn new(
+        transaction: &Transaction,
+        result: &ValidationResult,
+        alerts: &[AlertEvent],
+        config_version: &str,
+    ) -> Self {
+        let id = &result.transaction_id;
+        let mut compliance_checks: Vec<ComplianceRow> = result
+            .compliance_checks
+            .iter()
+            .map(|(name, passed)| ComplianceRow {
+                transaction_id: id.clone(),
+                check_name: name.clone(),
+                passed: *passed,
+            })
+            .collect();
+        compliance_checks.sort_by(|a, b| a.check_name.cmp(&b.check_name));
+
+        Self {
+            result: ResultRow {
+                transaction_id: id.clone(),
+                user_id_hash: hash_identifier(&transaction.user_id),
+                from_account_hash: transaction.from_account.as_deref().map(hash_identifier),
+                to_account_hash: transaction.to_account.as_deref().map(hash_identifier),
+                amount: result.amount,
+                currency: transaction.currency.clone(),
+                decision: result.decision.to_string(),
+                is_valid: result.is_valid,
+                fraud_score: result.fraud_score,
+                tenant_id: result.tenant_id.clone(),
+                committed: result.committed,
+                config_version: config_version.to_string(),
+                validated_at: result.validated_at,
+            },
+            errors: result
+                .errors
+                .iter()
+                .map(|e| ErrorRow {
+                    transaction_id: id.clone(),
+                    reason_code: e.reason_code().to_string(),
+                    code: e.code(),
+                    message: e.to_string(),
+                })
+                .collect(),
+            warnings: result
+                .warnings
+                .iter()
+                .map(|w| WarningRow {
+                    transaction_id: id.clone(),
+                    code: w.code.clone(),
+                    severity: format!("{:?}", w.severity),
+                    message: w.message.clone(),
+                })
+                .collect(),
+            compliance_checks,
+            alerts: alerts
+                .iter()
+                .map(|a| AlertRow {
+                    event_id: a.event_id.clone(),
+                    transaction_id: a.transaction_id.clone(),
+                    trigger: format!("{:?}", a.trigger),
+                    created_at: a.created_at,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Destination for batches of audit records
+pub trait AuditSink: Send {
+    /// Persist a batch; either every record is written or none is
+    fn write_batch(&mut self, records: &[AuditRecord]) -> io::Result<()>;
+}
+
+/// Read access to stored audit records
+pub trait AuditTrail {
+    /// Records debiting or crediting the account with this hash, validated
+    /// at or after `since`
+    fn records_for_account(
+        &self,
+        account_hash: &str,
+        since: DateTime<Utc>,
+    ) -> io::Result<Vec<AuditRecord>>;
+
+    /// Records submitted by the user with this hash, validated at or after
+    /// `since`
+    fn records_for_user(
+        &self,
+        user_id_hash: &str,
+        since: DateTime<Utc>,
+    ) -> io::Result<Vec<AuditRecord>>;
+}
+
+impl AuditTrail for [AuditRecord] {
+    fn records_for_account(
+        &self,
+        account_hash: &str,
+        since: DateTime<Utc>,
+    ) -> io::Result<Vec<AuditRecord>> {
+        Ok(self
+            .iter()
+            .filter(|r| {
+                let row = &r.result;
+                row.validated_at >= since
+                    && (row.from_account_hash.as_deref() == Some(account_hash)
+                        || row.to_account_hash.as_deref() == Some(account_hash))
+            })
+            .cloned()
+            .collect())
+    }
+
+    fn records_for_user(
+        &self,
+        user_id_hash: &str,
+        since: DateTime<Utc>,
+    ) -> io::Result<Vec<AuditRecord>> {
+        Ok(self
+            .iter()
+            .filter(|r| r.result.validated_at >= since && r.result.user_id_hash == user_id_hash)
+            .cloned()
+            .collect())
+    }
+}
+
+impl AuditTrail for Vec<AuditRecord> {
+    fn records_for_account(
+        &self,
+        account_hash: &str,
+        since: DateTime<Utc>,
+    ) -> io::Result<Vec<AuditRecord>> {
+        self.as_slice().records_for_account(account_hash, since)
+    }
+
+    fn records_for_user(
+        &self,
+        user_id_hash: &str,
+        since: DateTime<Utc>,
+    ) -> io::Result<Vec<AuditRecord>> {
+        self.as_slice().records_for_user(user_id_hash, since)
+    }
+}
+
+/// Sink and pending records held by the validator
+pub(crate) struct AuditBuffer {
+    pub(crate) sink: Box<dyn AuditSink>,
+    pub(crate) pending: Vec<AuditRecord>,
+    pub(crate) batch_size: usize,
+}
+
+impl AuditBuffer {
+    pub(crate) fn new(sink: Box<dyn AuditSink>, batch_size: usize) -> Self {
+        Self {
+            sink,
+            pending: Vec::new(),
+            batch_size: batch_size.max(1),
+        }
+    }
+
+    /// Queue a record, writing the batch once it is full
+    pub(crate) fn push(&mut self, record: AuditRecord) -> io::Result<()> {
+        self.pending.push(record);
+        if self.pending.len() >= self.batch_size {
+            self.flush()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Write pending records; they stay pending if the sink fails
+    pub(crate) fn flush(&mut self) -> io::Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        self.sink.write_batch(&self.pending)?;
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{AlertTrigger, Decision, TransactionValidator};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct MemorySink(Arc<Mutex<Vec<Vec<AuditRecord>>>>);
+
+    impl AuditSink for MemorySink {
+        fn write_batch(&mut self, records: &[AuditRecord]) -> io::Result<()> {
+            self.0.lock().unwrap().push(records.to_vec());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_records_are_batched_and_normalized() {
+        let sink = MemorySink::default();
+        let mut validator = TransactionValidator::new();
+        validator.on_alert(
+            AlertTrigger::Decision(Decision::Decline),
+            |_: &AlertEvent| {},
+        );
+        validator.set_audit_sink(Box::new(sink.clone()), 2);
+
+        validator.validate(&create_test_transaction("TXN-AUD-1", 100.0));
+        assert!(sink.0.lock().unwrap().is_empty());
+        validator.validate(&create_test_transaction("TXN-AUD-2", -5.0));
+        validator.validate(&create_test_transaction("TXN-AUD-3", 100.0));
+        assert_eq!(sink.0.lock().unwrap().len(), 1);
+        validator.flush_audit().unwrap();
+
+        let batches = sink.0.lock().unwrap();
+        assert_eq!(batches.iter().map(Vec::len).collect::<Vec<_>>(), vec![2, 1]);
+        let declined = &batches[0][1];
+        assert_eq!(declined.result.decision, "decline");
+        assert_eq!(declined.errors[0].reason_code, "INVALID_AMOUNT");
+        assert_eq!(declined.alerts.len(), 1);
+        assert_ne!(declined.result.user_id_hash, "USER-001");
+        assert_eq!(
+            declined.result.to_account_hash.as_deref(),
+            Some(hash_identifier("ACCT-6789-0123-4567").as_str())
+        );
+
+        let trail: Vec<AuditRecord> = batches.concat();
+        let payee = hash_identifier("ACCT-6789-0123-4567");
+        let since = trail[0].result.validated_at;
+        assert_eq!(trail.records_for_account(&payee, since).unwrap().len(), 3);
+        assert!(trail
+            .records_for_account(&hash_identifier("ACCT-0000-0000-0000"), since)
+            .unwrap()
+            .is_empty());
+    }
+}
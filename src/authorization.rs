@@ -0,0 +1,344 @@
+//! Authorization holds and settlement
+//!
+//! Card, hotel, and rental flows authorize an amount first and capture
+//! (settle) it later, possibly in several parts and possibly for a little
+//! more than was authorized (tips, incidentals). An [`AuthorizationBook`]
+//! keeps the open holds. A `Capture` names its authorization in the
+//! `parent_transaction_id` metadata entry and is rejected if the hold is
+//! unknown, expired, in another currency, or would be over-captured beyond
+//! the policy's tolerance. An `Authorization` naming an existing hold is an
+//! incremental authorization: it raises the hold and restarts its expiry.
+//!
+//! A long auth-to-capture gap raises risk: the card may have been reported
+//! lost since the hold was placed. Holds past expiry are released by
+//! [`AuthorizationBook::expire`].
+
+use crate::{Transaction, TransactionType, ValidationError, Warning, WarningSeverity};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Warning code for a capture long after its authorization
+pub const AUTH_CAPTURE_GAP_WARNING: &str = "AUTH_CAPTURE_GAP";
+
+/// Warning code for a capture over the authorized amount, within tolerance
+pub const OVER_CAPTURE_WARNING: &str = "OVER_CAPTURE";
+
+/// Hold lifetime and capture rules
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuthorizationPolicy {
+    /// How long a hold stays capturable
+    pub hold_hours: i64,
+    /// Fraction above the authorized amount that may still be captured
+    pub over_capture_tolerance: f64,
+    /// Captures later than this after authorization carry gap risk
+    pub late_capture_hours: i64,
+    /// Risk added for a late capture
+    pub late_capture_risk: u8,
+    /// Risk added for a capture over the authorized amount
+    pub over_capture_risk: u8,
+}
+
+impl Default for AuthorizationPolicy {
+    fn default() -> Self {
+        Self {
+            hold_hours: 7 * 24,
+            over_capture_tolerance: 0.0,
+            late_capture_hours: 72,
+            late_capture_risk: 15,
+            over_capture_risk: 10,
+        }
+    }
+}
+
+impl AuthorizationPolicy {
+    /// Hotel and car rental: 30-day holds, 20% over-capture for incidentals
+    pub fn lodging() -> Self {
+        Self {
+            hold_hours: 30 * 24,
+            over_capture_tolerance: 0.2,
+            late_capture_hours: 14 * 24,
+            ..Default::default()
+        }
+    }
+}
+
+/// An open authorization
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuthorizationHold {
+    pub authorized: f64,
+    pub captured: f64,
+    pub currency: String,
+    pub authorized_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl AuthorizationHold {
+    /// Authorized amount not yet captured
+    pub fn remaining(&self) -> f64 {
+        (self.authorized - self.captured).max(0.0)
+    }
+}
+
+/// Open authorization holds by transaction ID
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuthorizationBook {
+    holds: HashMap<String, AuthorizationHold>,
+    policy: AuthorizationPolicy,
+}
+
+impl AuthorizationBook {
+    /// Empty book with the default policy
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Empty book with a custom policy
+    pub fn with_policy(policy: AuthorizationPolicy) -> Self {
+        Self {
+            holds: HashMap::new(),
+            policy,
+        }
+    }
+
+    /// The policy in use
+    pub fn policy(&self) -> &AuthorizationPolicy {
+        &self.policy
+    }
+
+    /// Open hold for an authorization
+    pub fn hold(&self, authorization_id: &str) -> Option<&AuthorizationHold> {
+        self.holds.get(authorization_id)
+    }
+
+    /// Number of open holds
+    pub fn len(&self) -> usize {
+        self.holds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.holds.is_empty()
+    }
+
+    /// Hold a capture or incremental authorization applies to
+    fn parent_hold(&self, transaction: &Transaction) -> Option<(&str, &AuthorizationHold)> {
+        let id = transaction.parent_transaction_id()?;
+        self.holds
+            .get_key_value(id)
+            .map(|(id, hold)| (id.as_str(), hold))
+    }
+
+    /// Check a capture against its hold
+    ///
+    /// Other transaction types always pass.
+    pub fn check(&self, transaction: &Transaction) -> Result<(), ValidationError> {
+        if transaction.transaction_type != TransactionType::Capture
+            || transaction.parent_transaction_id().is_none()
+        {
+            return Ok(());
+        }
+        let Some((id, hold)) = self.parent_hold(transaction) else {
+            return Err(ValidationError::BusinessRuleViolation(format!(
+                "No open authorization {}",
+                transaction.parent_transaction_id().unwrap_or_default()
+            )));
+        };
+        if transaction.timestamp > hold.expires_at {
+            return Err(ValidationError::BusinessRuleViolation(format!(
+                "Authorization {} expired at {}",
+                id, hold.expires_at
+            )));
+        }
+        if hold.currency != transaction.currency {
+            return Err(ValidationError::BusinessRuleViolation(format!(
+                "Capture in {} does not match authorization {} in {}",
+                transaction.currency, id, hold.currency
+            )));
+        }
+        let ceiling = hold.authorized * (1.0 + self.policy.over_capture_tolerance.max(0.0));
+        if hold.captured + transaction.amount > ceiling + 0.005 {
+            return Err(ValidationError::BusinessRuleViolation(format!(
+                "Capture of {:.2} exceeds the {:.2} still capturable on {}",
+                transaction.amount,
+                (ceiling - hold.captured).max(0.0),
+                id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Risk from the capture's timing and amount, with explaining warnings
+    pub fn capture_risk(&self, transaction: &Transaction) -> (u8, Vec<Warning>) {
+        let mut risk = 0u8;
+        let mut warnings = Vec::new();
+        if transaction.transaction_type != TransactionType::Capture {
+            return (risk, warnings);
+        }
+        let Some((id, hold)) = self.parent_hold(transaction) else {
+            return (risk, warnings);
+        };
+
+        let gap = transaction.timestamp - hold.authorized_at;
+        if gap > Duration::hours(self.policy.late_capture_hours) {
+            risk = risk.saturating_add(self.policy.late_capture_risk);
+            warnings.push(Warning::new(
+                AUTH_CAPTURE_GAP_WARNING,
+                WarningSeverity::Medium,
+                format!(
+                    "Captured {} hours after authorization {}",
+                    gap.num_hours(),
+                    id
+                ),
+            ));
+        }
+        if hold.captured + transaction.amount > hold.authorized + 0.005 {
+            risk = risk.saturating_add(self.policy.over_capture_risk);
+            warnings.push(Warning::new(
+                OVER_CAPTURE_WARNING,
+                WarningSeverity::Low,
+                format!(
+                    "Capture brings {} to {:.2} of {:.2} authorized",
+                    id,
+                    hold.captured + transaction.amount,
+                    hold.authorized
+                ),
+            ));
+        }
+        (risk, warnings)
+    }
+
+    /// Record a committed authorization or capture
+    pub fn record(&mut self, transaction: &Transaction) {
+        let expires_at = transaction.timestamp + Duration::hours(self.policy.hold_hours);
+        match transaction.transaction_type {
+            TransactionType::Authorization => {
+                if let Some(hold) = transaction
+                    .parent_transaction_id()
+                    .and_then(|id| self.holds.get_mut(id))
+                {
+                    // Incremental authorization
+                    hold.authorized += transaction.amount;
+                    hold.expires_at = hold.expires_at.max(expires_at);
+                    return;
+                }
+                self.holds.insert(
+                    transaction.transaction_id.clone(),
+                    AuthorizationHold {
+                        authorized: transaction.amount,
+                        captured: 0.0,
+                        currency: transaction.currency.clone(),
+                        authorized_at: transaction.timestamp,
+                        expires_at,
+                    },
+                );
+            }
+            TransactionType::Capture => {
+                if let Some(hold) = transaction
+                    .parent_transaction_id()
+                    .and_then(|id| self.holds.get_mut(id))
+                {
+                    hold.captured += transaction.amount;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Release a hold without capturing it (void)
+    pub fn void(&mut self, authorization_id: &str) -> Option<AuthorizationHold> {
+        self.holds.remove(authorization_id)
+    }
+
+    /// Release every hold expired as of `now`, returning their IDs
+    pub fn expire(&mut self, now: DateTime<Utc>) -> Vec<String> {
+        let mut expired: Vec<String> = self
+            .holds
+            .iter()
+            .filter(|(_, hold)| hold.expires_at < now)
+            .map(|(id, _)| id.clone())
+            .collect();
+        expired.sort();
+        for id in &expired {
+            self.holds.remove(id);
+        }
+        expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+    use crate::{TransactionMetadata, TransactionValidator, PARENT_TRANSACTION_METADATA_KEY};
+
+    fn create_test_transaction(id: &str, amount: f64) -> Transaction {
+        Transaction {
+            transaction_type: TransactionType::Authorization,
+            ..test_fixtures::create_test_transaction(id, amount)
+        }
+    }
+
+    fn capture_of(id: &str, authorization: &str, amount: f64) -> Transaction {
+        let mut capture = create_test_transaction(id, amount);
+        capture.transaction_type = TransactionType::Capture;
+        capture.metadata = Some(TransactionMetadata::from([(
+            PARENT_TRANSACTION_METADATA_KEY.to_string(),
+            authorization.to_string(),
+        )]));
+        capture
+    }
+
+    #[test]
+    fn test_captures_are_bounded_by_the_hold() {
+        let mut validator = TransactionValidator::new();
+        validator.set_authorization_book(AuthorizationBook::new());
+        assert!(
+            validator
+                .validate(&create_test_transaction("TXN-AUTH-1", 200.0))
+                .is_valid
+        );
+
+        assert!(
+            validator
+                .validate(&capture_of("TXN-CAP-1", "TXN-AUTH-1", 150.0))
+                .is_valid
+        );
+        // 50 remains; capturing 60 over-captures without tolerance
+        assert!(
+            !validator
+                .validate(&capture_of("TXN-CAP-2", "TXN-AUTH-1", 60.0))
+                .is_valid
+        );
+        assert!(
+            !validator
+                .validate(&capture_of("TXN-CAP-3", "TXN-AUTH-9", 10.0))
+                .is_valid
+        );
+        let hold = validator.authorization_book().unwrap().hold("TXN-AUTH-1");
+        assert_eq!(hold.unwrap().remaining(), 50.0);
+    }
+
+    #[test]
+    fn test_late_capture_and_expiry() {
+        let mut book = AuthorizationBook::with_policy(AuthorizationPolicy::lodging());
+        let auth = create_test_transaction("TXN-AUTH-2", 300.0);
+        book.record(&auth);
+
+        // Incidentals within tolerance, but two weeks and a day later
+        let mut capture = capture_of("TXN-CAP-4", "TXN-AUTH-2", 330.0);
+        capture.timestamp = auth.timestamp + Duration::days(15);
+        assert!(book.check(&capture).is_ok());
+        let (risk, warnings) = book.capture_risk(&capture);
+        assert_eq!(risk, 25);
+        assert_eq!(warnings[0].code, AUTH_CAPTURE_GAP_WARNING);
+        assert_eq!(warnings[1].code, OVER_CAPTURE_WARNING);
+
+        capture.timestamp = auth.timestamp + Duration::days(31);
+        assert!(book.check(&capture).is_err());
+        assert_eq!(
+            book.expire(auth.timestamp + Duration::days(31)),
+            vec!["TXN-AUTH-2".to_string()]
+        );
+        assert!(book.is_empty());
+    }
+}
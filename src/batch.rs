@@ -0,0 +1,311 @@
+//! Batch validation summary
+//!
+//! A [`BatchReport`] aggregates the results of one batch so operators get an
+//! actionable summary (decision counts, top reasons, flagged amounts, and the
+//! users behind them) instead of reading N raw results. Cross-transaction
+//! checks look across the whole batch for patterns that are invisible when
+//! each row is validated on its own.
+
+use crate::stats::DecisionCounts;
+use crate::{Decision, Transaction, ValidationResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Number of reason codes reported in [`BatchReport::top_reason_codes`]
+pub const TOP_REASON_CODES_LIMIT: usize = 5;
+
+/// Thresholds for cross-transaction batch checks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchCheckConfig {
+    /// Reporting threshold that per-user batch totals are compared against
+    pub ctr_threshold: f64,
+    /// Timestamp bucket used when comparing transaction content
+    pub duplicate_bucket_seconds: i64,
+    /// Payments to one previously unseen beneficiary that trigger a finding
+    pub new_beneficiary_min_payments: usize,
+}
+
+impl Default for BatchCheckConfig {
+    fn default() -> Self {
+        Self {
+            ctr_threshold: 10000.0,
+            duplicate_bucket_seconds: 300,
+            new_beneficiary_min_payments: 3,
+        }
+    }
+}
+
+/// Kind of cross-transaction pattern found in a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BatchFindingKind {
+    /// Same content submitted more than once under different IDs
+    DuplicateContent,
+    /// One user's sub-threshold amounts adding up past the reporting threshold
+    AggregateStructuring,
+    /// Several payments to a beneficiary never seen before
+    NewBeneficiaryFanIn,
+}
+
+impl BatchFindingKind {
+    /// Warning code attached to transactions involved in the finding
+    pub fn code(&self) -> &'static str {
+        match self {
+            BatchFindingKind::DuplicateContent => "BATCH_DUPLICATE_CONTENT",
+            BatchFindingKind::AggregateStructuring => "BATCH_STRUCTURING",
+            BatchFindingKind::NewBeneficiaryFanIn => "BATCH_NEW_BENEFICIARY",
+        }
+    }
+}
+
+/// Pattern spanning several transactions of a batch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchFinding {
+    pub kind: BatchFindingKind,
+    /// Positions of the involved transactions in the submitted batch
+    pub indices: Vec<usize>,
+    pub description: String,
+}
+
+/// Find cross-transaction patterns in a batch
+///
+/// `is_known_beneficiary` reports whether an account has received payments
+/// before this batch.
+pub fn analyze_batch(
+    transactions: &[Transaction],
+    config: &BatchCheckConfig,
+    is_known_beneficiary: impl Fn(&str) -> bool,
+) -> Vec<BatchFinding> {
+    let mut findings = Vec::new();
+
+    // Content duplicates under different IDs
+    let mut by_fingerprint: HashMap<String, Vec<usize>> = HashMap::new();
+    for (i, tx) in transactions.iter().enumerate() {
+        by_fingerprint
+            .entry(tx.fingerprint(config.duplicate_bucket_seconds))
+            .or_default()
+            .push(i);
+    }
+    for indices in by_fingerprint.into_values() {
+        let first = &transactions[indices[0]].transaction_id;
+        if indices
+            .iter()
+            .any(|&i| &transactions[i].transaction_id != first)
+        {
+            findings.push(BatchFinding {
+                kind: BatchFindingKind::DuplicateContent,
+                description: format!(
+                    "{} transactions in batch share the content of {}",
+                    indices.len(),
+                    first
+                ),
+                indices,
+            });
+        }
+    }
+
+    // Per-user totals split into sub-threshold amounts
+    let mut by_user: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, tx) in transactions.iter().enumerate() {
+        if tx.amount > 0.0 && tx.amount < config.ctr_threshold {
+            by_user.entry(tx.user_id.as_str()).or_default().push(i);
+        }
+    }
+    for (user_id, indices) in by_user {
+        let total: f64 = indices.iter().map(|&i| transactions[i].amount).sum();
+        if indices.len() >= 2 && total >= config.ctr_threshold {
+            findings.push(BatchFinding {
+                kind: BatchFindingKind::AggregateStructuring,
+                description: format!(
+                    "User {} split {:.2} across {} sub-threshold transactions in batch",
+                    user_id,
+                    total,
+                    indices.len()
+                ),
+                indices,
+            });
+        }
+    }
+
+    // Fan-in to beneficiaries never paid before
+    let mut by_beneficiary: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, tx) in transactions.iter().enumerate() {
+        if let Some(to) = tx.to_account.as_deref() {
+            by_beneficiary.entry(to).or_default().push(i);
+        }
+    }
+    for (beneficiary, indices) in by_beneficiary {
+        if indices.len() >= config.new_beneficiary_min_payments.max(2)
+            && !is_known_beneficiary(beneficiary)
+        {
+            findings.push(BatchFinding {
+                kind: BatchFindingKind::NewBeneficiaryFanIn,
+                description: format!(
+                    "{} payments in batch to new beneficiary {}",
+                    indices.len(),
+                    beneficiary
+                ),
+                indices,
+            });
+        }
+    }
+
+    findings.sort_by_key(|f| (f.indices[0], f.kind as u8));
+    findings
+}
+
+/// Occurrences of one reason code in a batch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReasonCodeCount {
+    pub code: String,
+    pub count: usize,
+}
+
+/// Aggregate summary of a validated batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchReport {
+    pub total_transactions: usize,
+    pub decisions: DecisionCounts,
+    /// Most frequent reason codes, most common first
+    pub top_reason_codes: Vec<ReasonCodeCount>,
+    /// Total amount of transactions not approved
+    pub total_flagged_amount: f64,
+    /// Flagged (review or decline) transaction counts per user
+    pub flags_by_user: HashMap<String, usize>,
+    pub processing_time_micros: u64,
+}
+
+impl BatchReport {
+    /// Summarize results produced for `transactions` (same order)
+    pub fn new(
+        transactions: &[Transaction],
+        results: &[ValidationResult],
+        processing_time: std::time::Duration,
+    ) -> Self {
+        let mut decisions = DecisionCounts::default();
+        let mut reason_counts: HashMap<String, usize> = HashMap::new();
+        let mut total_flagged_amount = 0.0;
+        let mut flags_by_user: HashMap<String, usize> = HashMap::new();
+
+        for (transaction, result) in transactions.iter().zip(results) {
+            decisions.record(result.decision);
+            for code in result.reason_codes() {
+                *reason_counts.entry(code).or_insert(0) += 1;
+            }
+            if result.decision != Decision::Approve {
+                total_flagged_amount += transaction.amount;
+                *flags_by_user
+                    .entry(transaction.user_id.clone())
+                    .or_insert(0) += 1;
+            }
+        }
+
+        let mut top_reason_codes: Vec<ReasonCodeCount> = reason_counts
+            .into_iter()
+            .map(|(code, count)| ReasonCodeCount { code, count })
+            .collect();
+        top_reason_codes.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.code.cmp(&b.code)));
+        top_reason_codes.truncate(TOP_REASON_CODES_LIMIT);
+
+        Self {
+            total_transactions: results.len(),
+            decisions,
+            top_reason_codes,
+            total_flagged_amount,
+            flags_by_user,
+            processing_time_micros: processing_time.as_micros() as u64,
+        }
+    }
+
+    /// Export as JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+    use crate::TransactionValidator;
+
+    fn create_test_transaction(id: &str, user_id: &str, amount: f64) -> Transaction {
+        Transaction {
+            user_id: user_id.to_string(),
+            ..test_fixtures::create_test_transaction(id, amount)
+        }
+    }
+
+    #[test]
+    fn test_batch_report_aggregates() {
+        let mut validator = TransactionValidator::new();
+        let transactions = vec![
+            create_test_transaction("TXN-B1", "USER-001", 100.0),
+            create_test_transaction("TXN-B1", "USER-001", 100.0),
+            create_test_transaction("TXN-B2", "USER-002", -10.0),
+            create_test_transaction("TXN-B3", "USER-003", 250.0),
+        ];
+
+        let (results, report) = validator.validate_batch_with_report(&transactions);
+        assert_eq!(results.len(), 4);
+        assert_eq!(report.total_transactions, 4);
+        assert_eq!(report.decisions.decline, 2);
+        assert_eq!(report.decisions.approve, 2);
+        assert_eq!(report.total_flagged_amount, 90.0);
+        assert_eq!(report.flags_by_user["USER-001"], 1);
+        assert_eq!(report.flags_by_user["USER-002"], 1);
+        assert!(!report.flags_by_user.contains_key("USER-003"));
+        assert!(report
+            .top_reason_codes
+            .iter()
+            .any(|r| r.code == "DUPLICATE_TRANSACTION" && r.count == 1));
+        assert!(report.to_json().unwrap().contains("top_reason_codes"));
+    }
+
+    #[test]
+    fn test_cross_checks_find_batch_patterns() {
+        let mut replay = create_test_transaction("TXN-C2", "USER-001", 100.0);
+        replay.to_account = Some("ACCT-1111-2222-3333".to_string());
+        let mut original = replay.clone();
+        original.transaction_id = "TXN-C1".to_string();
+
+        let mut split: Vec<Transaction> = (0..3)
+            .map(|i| {
+                let mut tx =
+                    create_test_transaction(&format!("TXN-S{}", i), "USER-002", 4000.0 + i as f64);
+                tx.to_account = Some("ACCT-9999-8888-7777".to_string());
+                tx
+            })
+            .collect();
+        let mut transactions = vec![original, replay];
+        transactions.append(&mut split);
+
+        let mut validator = TransactionValidator::new();
+        let (results, findings) =
+            validator.validate_batch_cross_checked(&transactions, &BatchCheckConfig::default());
+
+        let kinds: Vec<BatchFindingKind> = findings.iter().map(|f| f.kind).collect();
+        assert!(kinds.contains(&BatchFindingKind::DuplicateContent));
+        assert!(kinds.contains(&BatchFindingKind::AggregateStructuring));
+        assert!(kinds.contains(&BatchFindingKind::NewBeneficiaryFanIn));
+
+        // Findings surface as warnings on the involved rows
+        assert!(results[1]
+            .warnings
+            .iter()
+            .any(|w| w.message.contains("TXN-C1")));
+        assert!(results[3]
+            .warnings
+            .iter()
+            .any(|w| w.message.contains("sub-threshold")));
+        assert_eq!(results[3].decision, Decision::Review);
+    }
+
+    #[test]
+    fn test_known_beneficiary_is_not_fan_in() {
+        let transactions: Vec<Transaction> = (0..3)
+            .map(|i| create_test_transaction(&format!("TXN-K{}", i), "USER-001", 10.0 + i as f64))
+            .collect();
+        let findings = analyze_batch(&transactions, &BatchCheckConfig::default(), |_| true);
+        assert!(findings.is_empty());
+    }
+}
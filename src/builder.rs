@@ -0,0 +1,389 @@
+//! Validator builder
+//!
+//! Assembles a [`TransactionValidator`] with its screening, scoring, network,
+//! and audit components in one expression so the whole pipeline is wired
+//! explicitly instead of attaching modules one setter at a time.
+
+use crate::{
+    AMLChecker, AccountFormatRegistry, ActivityProfiles, AuthorizationBook, ChallengeProvider,
+    ConfigFile, CounterpartyTrust, DecisionLogger, EnrichmentProvider, FraudDetector,
+    GeographicRiskScorer, JurisdictionProfile, MetadataNormalizer, NetworkAnalyzer,
+    PostValidationHook, PreValidationHook, Pseudonymizer, RefundLedger, ReportingQueue,
+    SanctionsScreener, ScreeningBackend, SettlementCalendar, SharedClock, SharedDedup,
+    SharedHistory, TransactionValidator, TypologyLibrary, ValidationRule, ValidatorConfig,
+    Watchlist,
+};
+
+/// Builder for [`TransactionValidator`], created by [`TransactionValidator::builder`]
+#[derive(Default)]
+pub struct TransactionValidatorBuilder {
+    config: ValidatorConfig,
+    sanctions_screener: Option<Box<dyn ScreeningBackend>>,
+    geo_scorer: Option<GeographicRiskScorer>,
+    fraud_detector: Option<FraudDetector>,
+    aml_checker: Option<AMLChecker>,
+    network_analyzer: Option<NetworkAnalyzer>,
+    counterparty_trust: Option<CounterpartyTrust>,
+    activity_profiles: Option<ActivityProfiles>,
+    refund_ledger: Option<RefundLedger>,
+    settlement_calendar: Option<SettlementCalendar>,
+    reporting_queue: Option<ReportingQueue>,
+    authorization_book: Option<AuthorizationBook>,
+    typologies: Option<TypologyLibrary>,
+    account_formats: Option<AccountFormatRegistry>,
+    history: Option<SharedHistory>,
+    dedup: Option<SharedDedup>,
+    watchlist: Option<Watchlist>,
+    decision_logger: Option<Box<dyn DecisionLogger + Send>>,
+    normalizer: Option<MetadataNormalizer>,
+    pseudonymizer: Option<Pseudonymizer>,
+    pre_hooks: Vec<Box<dyn PreValidationHook>>,
+    post_hooks: Vec<Box<dyn PostValidationHook>>,
+    rules: Vec<Box<dyn ValidationRule>>,
+    challenge_provider: Option<Box<dyn ChallengeProvider>>,
+    #[cfg(feature = "ml")]
+    model_scorer: Option<Box<dyn crate::ml::ModelScorer>>,
+    enrichment_providers: Vec<Box<dyn EnrichmentProvider>>,
+    clock: Option<SharedClock>,
+}
+
+impl TransactionValidatorBuilder {
+    /// Start from the default configuration with no optional components
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use a custom configuration
+    pub fn with_config(mut self, config: ValidatorConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Screen counterparty names against sanctions lists
+    pub fn with_sanctions(self, screener: SanctionsScreener) -> Self {
+        self.with_screening_backend(screener)
+    }
+
+    /// Screen counterparty names through a custom backend
+    pub fn with_screening_backend<B: ScreeningBackend + 'static>(mut self, backend: B) -> Self {
+        self.sanctions_screener = Some(Box::new(backend));
+        self
+    }
+
+    /// Check origin/destination countries for prohibited jurisdictions
+    pub fn with_geo(mut self, scorer: GeographicRiskScorer) -> Self {
+        self.geo_scorer = Some(scorer);
+        self
+    }
+
+    /// Add a stateful fraud detector to the pattern check, sharing the validator's history
+    pub fn with_fraud_detector(mut self, detector: FraudDetector) -> Self {
+        self.fraud_detector = Some(detector);
+        self
+    }
+
+    /// Apply AML rules in the AML check
+    pub fn with_aml(mut self, checker: AMLChecker) -> Self {
+        self.aml_checker = Some(checker);
+        self
+    }
+
+    /// Configure for a jurisdiction: required checks, retention, and AML thresholds
+    ///
+    /// Applies to the config set so far, so call after `with_config`.
+    pub fn with_jurisdiction(mut self, profile: &JurisdictionProfile) -> Self {
+        profile.apply(&mut self.config);
+        self.aml_checker = Some(profile.aml_checker());
+        self
+    }
+
+    /// Apply a loaded configuration file
+    ///
+    /// AML thresholds from the file replace those of the AML checker, which
+    /// is added if the builder has none. Account formats from the file
+    /// replace the registry.
+    pub fn with_config_file(mut self, file: &ConfigFile) -> Self {
+        self.config = file.config.clone();
+        if let Some(ref thresholds) = file.aml_thresholds {
+            let checker = self.aml_checker.take().unwrap_or_default();
+            self.aml_checker = Some(checker.with_thresholds(thresholds.clone()));
+        }
+        if let Some(ref registry) = file.account_formats {
+            self.account_formats = Some(registry.clone());
+        }
+        self
+    }
+
+    /// Read the current time from `clock`, shared with every component
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Keep velocity and fraud history in a store shared with the caller
+    pub fn with_history(mut self, history: SharedHistory) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Share duplicate detection with other validators holding the same store
+    pub fn with_dedup(mut self, dedup: SharedDedup) -> Self {
+        self.dedup = Some(dedup);
+        self
+    }
+
+    /// Consult an institution watchlist before other checks
+    pub fn with_watchlist(mut self, watchlist: Watchlist) -> Self {
+        self.watchlist = Some(watchlist);
+        self
+    }
+
+    /// Feed committed transfers into a network analyzer
+    pub fn with_network(mut self, analyzer: NetworkAnalyzer) -> Self {
+        self.network_analyzer = Some(analyzer);
+        self
+    }
+
+    /// Offset risk by learned trust in each (user, counterparty) relationship
+    pub fn with_counterparty_trust(mut self, trust: CounterpartyTrust) -> Self {
+        self.counterparty_trust = Some(trust);
+        self
+    }
+
+    /// Score time risk against each mature user's own activity pattern
+    pub fn with_activity_profiles(mut self, profiles: ActivityProfiles) -> Self {
+        self.activity_profiles = Some(profiles);
+        self
+    }
+
+    /// Check refunds and reversals against the transactions they undo
+    pub fn with_refund_ledger(mut self, ledger: RefundLedger) -> Self {
+        self.refund_ledger = Some(ledger);
+        self
+    }
+
+    /// Check value and settlement dates against holidays and cut-offs
+    pub fn with_settlement_calendar(mut self, calendar: SettlementCalendar) -> Self {
+        self.settlement_calendar = Some(calendar);
+        self
+    }
+
+    /// Enqueue the CTRs and SARs the AML checker requires on commit
+    pub fn with_reporting_queue(mut self, queue: ReportingQueue) -> Self {
+        self.reporting_queue = Some(queue);
+        self
+    }
+
+    /// Match transactions against the enabled typology packs
+    pub fn with_typologies(mut self, library: TypologyLibrary) -> Self {
+        self.typologies = Some(library);
+        self
+    }
+
+    /// Accept the account number schemes in a custom registry
+    pub fn with_account_formats(mut self, registry: AccountFormatRegistry) -> Self {
+        self.account_formats = Some(registry);
+        self
+    }
+
+    /// Check captures against authorization holds
+    pub fn with_authorization_book(mut self, book: AuthorizationBook) -> Self {
+        self.authorization_book = Some(book);
+        self
+    }
+
+    /// Record one audit record per validation
+    pub fn with_audit<L: DecisionLogger + Send + 'static>(mut self, logger: L) -> Self {
+        self.decision_logger = Some(Box::new(logger));
+        self
+    }
+
+    /// Canonicalize metadata after pre-hooks and before checks run
+    pub fn with_normalizer(mut self, normalizer: MetadataNormalizer) -> Self {
+        self.normalizer = Some(normalizer);
+        self
+    }
+
+    /// Store user IDs and account numbers only as keyed HMAC tokens
+    pub fn with_pseudonymizer(mut self, pseudonymizer: Pseudonymizer) -> Self {
+        self.pseudonymizer = Some(pseudonymizer);
+        self
+    }
+
+    /// Add a hook that can enrich the transaction before checks run
+    pub fn with_pre_hook<H: PreValidationHook + 'static>(mut self, hook: H) -> Self {
+        self.pre_hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Add a hook that can annotate or override the result after checks run
+    pub fn with_post_hook<H: PostValidationHook + 'static>(mut self, hook: H) -> Self {
+        self.post_hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Run a custom rule after the built-in checks, in registration order
+    pub fn with_rule(mut self, rule: Box<dyn ValidationRule>) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Challenge the customer on Review and StepUp results
+    /// Blend a trained model's score into the risk breakdown
+    #[cfg(feature = "ml")]
+    pub fn with_model_scorer(mut self, scorer: Box<dyn crate::ml::ModelScorer>) -> Self {
+        self.model_scorer = Some(scorer);
+        self
+    }
+
+    pub fn with_challenge_provider<P: ChallengeProvider + 'static>(mut self, provider: P) -> Self {
+        self.challenge_provider = Some(Box::new(provider));
+        self
+    }
+
+    /// Add an enrichment provider, consulted in registration order
+    pub fn with_enrichment_provider<P: EnrichmentProvider + 'static>(
+        mut self,
+        provider: P,
+    ) -> Self {
+        self.enrichment_providers.push(Box::new(provider));
+        self
+    }
+
+    /// Assemble the validator
+    pub fn build(self) -> TransactionValidator {
+        let mut validator = TransactionValidator::with_config(self.config);
+        validator.sanctions_screener = self.sanctions_screener;
+        validator.geo_scorer = self.geo_scorer;
+        if let Some(history) = self.history {
+            validator.set_history(history);
+        }
+        if let Some(dedup) = self.dedup {
+            validator.set_dedup(dedup);
+        }
+        if let Some(detector) = self.fraud_detector {
+            validator.set_fraud_detector(detector);
+        }
+        validator.aml_checker = self.aml_checker;
+        validator.network_analyzer = self.network_analyzer;
+        validator.counterparty_trust = self.counterparty_trust;
+        validator.activity_profiles = self.activity_profiles;
+        validator.refund_ledger = self.refund_ledger;
+        validator.settlement_calendar = self.settlement_calendar;
+        validator.reporting_queue = self.reporting_queue;
+        validator.authorization_book = self.authorization_book;
+        validator.typologies = self.typologies;
+        if let Some(registry) = self.account_formats {
+            validator.account_formats = registry;
+        }
+        if let Some(watchlist) = self.watchlist {
+            validator.watchlist = watchlist;
+        }
+        validator.decision_logger = self.decision_logger;
+        validator.normalizer = self.normalizer;
+        validator.pseudonymizer = self.pseudonymizer;
+        validator.pre_hooks = self.pre_hooks;
+        validator.post_hooks = self.post_hooks;
+        validator.rules = self.rules;
+        validator.challenge_provider = self.challenge_provider;
+        #[cfg(feature = "ml")]
+        {
+            validator.model_scorer = self.model_scorer;
+        }
+        validator.enrichment_providers = self.enrichment_providers;
+        if let Some(clock) = self.clock {
+            validator.set_clock(clock);
+        }
+        validator
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+    use crate::{JsonLinesDecisionLogger, Transaction};
+    use chrono::Duration;
+    use std::io::{self, Write};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn create_test_transaction(id: &str, from: &str, to: &str, amount: f64) -> Transaction {
+        Transaction {
+            from_account: Some(from.to_string()),
+            to_account: Some(to.to_string()),
+            ..test_fixtures::create_test_transaction(id, amount)
+        }
+    }
+
+    #[test]
+    fn test_builder_wires_full_pipeline() {
+        let buffer = SharedBuffer::default();
+        let mut validator = TransactionValidator::builder()
+            .with_sanctions(SanctionsScreener::new())
+            .with_geo(GeographicRiskScorer::new())
+            .with_fraud_detector(FraudDetector::new())
+            .with_network(NetworkAnalyzer::new())
+            .with_audit(JsonLinesDecisionLogger::new(buffer.clone()))
+            .build();
+
+        let transaction = create_test_transaction(
+            "TXN-BLD-001",
+            "ACCT-1234-5678-9012",
+            "ACCT-6789-0123-4567",
+            20_000.0,
+        );
+        let result = validator.validate(&transaction);
+
+        // The detector's round-amount flag surfaces on the result
+        assert!(result.warning_codes().contains(&"FRAUD_FLAG".to_string()));
+        assert!(validator
+            .network_analyzer()
+            .and_then(|n| n.get_account_stats("ACCT-1234-5678-9012"))
+            .is_some());
+        assert_eq!(buffer.0.lock().unwrap().split(|b| *b == b'\n').count(), 2);
+    }
+
+    #[test]
+    fn test_network_pattern_warning() {
+        let accounts = [
+            "ACCT-1111-1111-1111",
+            "ACCT-2222-2222-2222",
+            "ACCT-3333-3333-3333",
+        ];
+        let mut validator = TransactionValidator::builder()
+            .with_network(NetworkAnalyzer::new())
+            .build();
+
+        // A -> B -> C -> A closes a circular flow
+        for i in 0..3 {
+            let mut tx = create_test_transaction(
+                &format!("TXN-NET-{}", i),
+                accounts[i],
+                accounts[(i + 1) % 3],
+                5000.0,
+            );
+            tx.timestamp += Duration::minutes(i as i64);
+            validator.validate(&tx);
+        }
+
+        let probe =
+            create_test_transaction("TXN-NET-PROBE", accounts[0], "ACCT-4444-4444-4444", 100.0);
+        let result = validator.validate(&probe);
+        assert!(result
+            .warning_codes()
+            .contains(&"NETWORK_PATTERN".to_string()));
+    }
+}
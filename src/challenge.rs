@@ -0,0 +1,118 @@
+//! Step-up authentication challenges
+//!
+//! Instead of declining or queueing every borderline transaction, the
+//! validator can hand Review and StepUp results to a [`ChallengeProvider`]
+//! that asks the customer to prove themselves (2FA, OTP, 3-D Secure). The
+//! outcome settles the decision: a passed challenge approves, a failed one
+//! declines, and an unavailable provider leaves the decision as it was.
+//!
+//! Challenges run after the decision is derived and before post-hooks, and
+//! never on dry runs. They change only the decision and add a warning;
+//! `is_valid` and the errors still describe the checks themselves.
+
+use crate::{Transaction, ValidationResult};
+use serde::{Deserialize, Serialize};
+
+/// Result of a step-up challenge
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChallengeOutcome {
+    /// Customer completed verification
+    Passed,
+    /// Customer failed or abandoned verification
+    Failed(String),
+    /// No challenge could be issued (no enrolled factor, provider down)
+    Unavailable(String),
+}
+
+/// Issues step-up challenges for Review-class results
+pub trait ChallengeProvider: Send + Sync {
+    /// Provider name used in warnings
+    fn name(&self) -> &str;
+
+    /// Challenge the customer behind `transaction` and report the outcome
+    fn challenge(&self, transaction: &Transaction, result: &ValidationResult) -> ChallengeOutcome;
+}
+
+impl<F> ChallengeProvider for F
+where
+    F: Fn(&Transaction, &ValidationResult) -> ChallengeOutcome + Send + Sync,
+{
+    fn name(&self) -> &str {
+        "closure"
+    }
+
+    fn challenge(&self, transaction: &Transaction, result: &ValidationResult) -> ChallengeOutcome {
+        self(transaction, result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{
+        Decision, ListType, TransactionValidator, WarningSeverity, WatchlistEntry, WatchlistSubject,
+    };
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn graylisted_validator() -> TransactionValidator {
+        let mut validator = TransactionValidator::new();
+        validator.watchlist_mut().add(WatchlistEntry::new(
+            WatchlistSubject::User,
+            "USER-001",
+            ListType::Gray,
+            "Recent password reset",
+            "analyst-7",
+        ));
+        validator
+    }
+
+    #[test]
+    fn test_challenge_outcome_settles_decision() {
+        let mut validator = graylisted_validator();
+        validator.set_challenge_provider(|_: &Transaction, _: &ValidationResult| {
+            ChallengeOutcome::Passed
+        });
+        let result = validator.validate(&create_test_transaction("TXN-CHL-1", 100.0));
+        assert_eq!(result.decision, Decision::Approve);
+        assert!(result
+            .warning_codes()
+            .contains(&"CHALLENGE_PASSED".to_string()));
+
+        let mut validator = graylisted_validator();
+        validator.set_challenge_provider(|_: &Transaction, _: &ValidationResult| {
+            ChallengeOutcome::Failed("OTP rejected".to_string())
+        });
+        let result = validator.validate(&create_test_transaction("TXN-CHL-2", 100.0));
+        assert_eq!(result.decision, Decision::Decline);
+        assert!(result
+            .warnings_at_least(WarningSeverity::High)
+            .iter()
+            .any(|w| w.code == "CHALLENGE_FAILED"));
+
+        let mut validator = graylisted_validator();
+        validator.set_challenge_provider(|_: &Transaction, _: &ValidationResult| {
+            ChallengeOutcome::Unavailable("No enrolled device".to_string())
+        });
+        let result = validator.validate(&create_test_transaction("TXN-CHL-3", 100.0));
+        assert_eq!(result.decision, Decision::StepUp);
+    }
+
+    #[test]
+    fn test_challenge_skips_clear_results_and_dry_runs() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let mut validator = graylisted_validator();
+        validator.set_challenge_provider(move |_: &Transaction, _: &ValidationResult| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            ChallengeOutcome::Passed
+        });
+
+        validator.validate_dry_run(&create_test_transaction("TXN-CHL-4", 100.0));
+        let result = validator.validate(&create_test_transaction("TXN-CHL-5", -5.0));
+        assert_eq!(result.decision, Decision::Decline);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+}
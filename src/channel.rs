@@ -0,0 +1,178 @@
+//! Channel-aware validation rules
+//!
+//! A $900 ATM withdrawal, a $900 branch transfer, and a $900 API payout
+//! carry very different risk. Transactions name their channel in the
+//! `channel` metadata field; a [`ChannelPolicy`] registered for that channel
+//! layers a config override on top of the segment and limit-profile layers
+//! and can restrict which transaction types the channel accepts.
+
+use crate::{ConfigOverride, Transaction, TransactionType};
+use serde::{Deserialize, Serialize};
+
+/// Channel a transaction was submitted through
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum Channel {
+    Web,
+    MobileApp,
+    Branch,
+    Atm,
+    Api,
+    Batch,
+}
+
+impl Channel {
+    /// Every channel
+    pub const ALL: [Channel; 6] = [
+        Channel::Web,
+        Channel::MobileApp,
+        Channel::Branch,
+        Channel::Atm,
+        Channel::Api,
+        Channel::Batch,
+    ];
+
+    /// Parse a channel name (case-insensitive)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "web" | "online" => Some(Channel::Web),
+            "mobile" | "mobile_app" | "app" => Some(Channel::MobileApp),
+            "branch" | "teller" => Some(Channel::Branch),
+            "atm" => Some(Channel::Atm),
+            "api" => Some(Channel::Api),
+            "batch" | "file" => Some(Channel::Batch),
+            _ => None,
+        }
+    }
+
+    /// Channel named in the transaction's metadata, if any
+    pub fn of(transaction: &Transaction) -> Option<Self> {
+        transaction
+            .metadata
+            .as_ref()
+            .and_then(|m| m.channel.as_deref())
+            .and_then(Self::parse)
+    }
+}
+
+/// Thresholds and restrictions for one channel
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChannelPolicy {
+    /// Config layer applied to transactions from the channel
+    pub overrides: ConfigOverride,
+    /// Transaction types the channel accepts; empty accepts all
+    pub allowed_types: Vec<TransactionType>,
+}
+
+impl ChannelPolicy {
+    /// Policy applying a config override
+    pub fn new(overrides: ConfigOverride) -> Self {
+        Self {
+            overrides,
+            allowed_types: Vec::new(),
+        }
+    }
+
+    /// Accept only the given transaction types
+    pub fn with_allowed_types(mut self, types: &[TransactionType]) -> Self {
+        self.allowed_types = types.to_vec();
+        self
+    }
+
+    /// Cash machine: withdrawals and deposits only, with a per-transaction cap
+    pub fn atm(withdrawal_cap: f64) -> Self {
+        Self::new(ConfigOverride {
+            max_transaction_amount: Some(withdrawal_cap),
+            ..Default::default()
+        })
+        .with_allowed_types(&[TransactionType::Withdrawal, TransactionType::Deposit])
+    }
+
+    /// Check whether the channel accepts a transaction type
+    pub fn allows(&self, transaction_type: &TransactionType) -> bool {
+        self.allowed_types.is_empty() || self.allowed_types.contains(transaction_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{TransactionMetadata, TransactionValidator, ValidationError, ValidatorConfig};
+
+    fn on_channel(mut tx: Transaction, channel: &str) -> Transaction {
+        tx.metadata = Some(TransactionMetadata {
+            channel: Some(channel.to_string()),
+            ..Default::default()
+        });
+        tx
+    }
+
+    #[test]
+    fn test_atm_caps_and_restricts_types() {
+        let mut validator = TransactionValidator::new();
+        validator.set_channel_policy(Channel::Atm, ChannelPolicy::atm(800.0));
+
+        let mut withdrawal = on_channel(create_test_transaction("TXN-CHN-1", 1000.0), "ATM");
+        withdrawal.transaction_type = TransactionType::Withdrawal;
+        withdrawal.to_account = None;
+        let result = validator.validate(&withdrawal);
+        assert_eq!(result.channel, Some(Channel::Atm));
+        assert_eq!(result.effective_config.max_transaction_amount, 800.0);
+        assert!(matches!(
+            result.errors.as_slice(),
+            [ValidationError::InvalidAmount(_)]
+        ));
+
+        let transfer = on_channel(create_test_transaction("TXN-CHN-2", 100.0), "atm");
+        let result = validator.validate(&transfer);
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::BusinessRuleViolation(_))));
+
+        // The same transfer through the web is unaffected
+        let web = on_channel(create_test_transaction("TXN-CHN-3", 1000.0), "web");
+        assert!(validator.validate(&web).is_valid);
+    }
+
+    #[test]
+    fn test_api_only_velocity() {
+        let config = ValidatorConfig {
+            enable_velocity_check: false,
+            max_transactions_per_window: 2,
+            ..Default::default()
+        };
+        let mut validator = TransactionValidator::with_config(config);
+        validator.set_channel_policy(
+            Channel::Api,
+            ChannelPolicy::new(ConfigOverride {
+                enable_velocity_check: Some(true),
+                ..Default::default()
+            }),
+        );
+
+        for i in 0..3 {
+            let tx = on_channel(
+                create_test_transaction(&format!("TXN-CHN-W{i}"), 10.0),
+                "web",
+            );
+            assert!(validator.validate(&tx).is_valid);
+        }
+        let velocity_errors: Vec<usize> = (0..4)
+            .map(|i| {
+                let tx = on_channel(
+                    create_test_transaction(&format!("TXN-CHN-A{i}"), 10.0),
+                    "api",
+                );
+                validator
+                    .validate(&tx)
+                    .errors
+                    .iter()
+                    .filter(|e| matches!(e, ValidationError::VelocityViolation(_)))
+                    .count()
+            })
+            .collect();
+        assert_eq!(velocity_errors.first(), Some(&0));
+        assert_eq!(velocity_errors.last(), Some(&1));
+    }
+}
@@ -0,0 +1,392 @@
+//! Check execution planning
+//!
+//! Each validation check declares a relative cost and the checks it depends
+//! on. The validator runs them cheapest-first (respecting dependencies) and
+//! defers deferrable checks once its time budget is spent. Deferred checks
+//! mark the result for review and, once the transaction is committed, are
+//! queued as [`DeferredChecks`] for `TransactionValidator::run_deferred`.
+
+use crate::{
+    Channel, CustomerSegment, DegradationReason, EnrichmentContext, ExplanationNode,
+    ExternalFindings, ReportKind, RiskBreakdown, SamplingDecision, Transaction, TypologyHit,
+    ValidationError, ValidationResult, ValidatorConfig, Warning,
+};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+/// Individual checks run by `TransactionValidator`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Check {
+    /// Sanctions and prohibited-jurisdiction screening
+    Screening,
+    Amount,
+    AccountFormat,
+    Duplicate,
+    Velocity,
+    FraudPatterns,
+    TimeRisk,
+    Aml,
+    BusinessRules,
+    /// Rules registered with `TransactionValidator::register_rule`
+    CustomRules,
+}
+
+impl Check {
+    /// All built-in checks
+    pub const ALL: [Check; 10] = [
+        Check::Screening,
+        Check::Amount,
+        Check::AccountFormat,
+        Check::Duplicate,
+        Check::Velocity,
+        Check::FraudPatterns,
+        Check::TimeRisk,
+        Check::Aml,
+        Check::BusinessRules,
+        Check::CustomRules,
+    ];
+
+    /// Stable check name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Check::Screening => "screening",
+            Check::Amount => "amount",
+            Check::AccountFormat => "account_format",
+            Check::Duplicate => "duplicate",
+            Check::Velocity => "velocity",
+            Check::FraudPatterns => "fraud_patterns",
+            Check::TimeRisk => "time_risk",
+            Check::Aml => "aml",
+            Check::BusinessRules => "business_rules",
+            Check::CustomRules => "custom_rules",
+        }
+    }
+
+    /// Relative execution cost (higher is more expensive)
+    pub fn cost(&self) -> u32 {
+        match self {
+            Check::Amount | Check::TimeRisk | Check::BusinessRules => 1,
+            Check::Duplicate | Check::FraudPatterns | Check::Aml => 2,
+            Check::CustomRules => 3,
+            Check::AccountFormat => 5,
+            Check::Velocity => 10,
+            Check::Screening => 50,
+        }
+    }
+
+    /// Checks that must complete before this one runs
+    ///
+    /// State-recording checks wait for screening so a hard fail can stop
+    /// them from committing the transaction to dedup, velocity history, and
+    /// fraud profiles; fraud pattern scoring also runs the network and
+    /// typology analysis, the work a hard fail most needs to skip. Custom
+    /// rules add to the pattern risk fraud pattern scoring sets.
+    pub fn dependencies(&self) -> &'static [Check] {
+        match self {
+            Check::Duplicate | Check::Velocity | Check::FraudPatterns => &[Check::Screening],
+            Check::CustomRules => &[Check::FraudPatterns],
+            _ => &[],
+        }
+    }
+
+    /// Whether the check may be deferred once the time budget is exhausted
+    ///
+    /// AML always runs: its filings and red flags must be part of the decision.
+    pub fn is_deferrable(&self) -> bool {
+        matches!(
+            self,
+            Check::Velocity | Check::FraudPatterns | Check::TimeRisk
+        )
+    }
+}
+
+impl std::fmt::Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Ordered list of checks to execute
+#[derive(Debug, Clone)]
+pub struct ExecutionPlan {
+    order: Vec<Check>,
+}
+
+impl ExecutionPlan {
+    /// Order checks by cost while respecting dependencies
+    ///
+    /// Dependencies that are not part of `checks` are ignored.
+    pub fn new(checks: &[Check]) -> Self {
+        let mut pending: Vec<Check> = checks.to_vec();
+        pending.sort();
+        pending.dedup();
+
+        let mut order = Vec::with_capacity(pending.len());
+        while !pending.is_empty() {
+            let next = pending
+                .iter()
+                .copied()
+                .filter(|check| {
+                    check
+                        .dependencies()
+                        .iter()
+                        .all(|dep| !pending.contains(dep))
+                })
+                .min_by_key(|check| (check.cost(), *check))
+                .expect("built-in check dependencies are acyclic");
+            pending.retain(|check| *check != next);
+            order.push(next);
+        }
+
+        Self { order }
+    }
+
+    /// Checks in execution order
+    pub fn checks(&self) -> &[Check] {
+        &self.order
+    }
+}
+
+/// Time spent in one check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckTiming {
+    pub check: Check,
+    pub micros: u64,
+}
+
+/// Which checks ran on a result and how long they took
+///
+/// Recorded when `ValidatorConfig::record_check_telemetry` is set.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct CheckTelemetry {
+    /// Checks that ran, in execution order
+    pub executed: Vec<Check>,
+    pub skipped: Vec<Check>,
+    /// One timing per executed check, in execution order
+    pub timings: Vec<CheckTiming>,
+    /// Whole validation, including enrichment and scoring
+    pub total_micros: u64,
+}
+
+impl CheckTelemetry {
+    pub(crate) fn record(&mut self, check: Check, elapsed: Duration) {
+        self.executed.push(check);
+        self.timings.push(CheckTiming {
+            check,
+            micros: elapsed.as_micros() as u64,
+        });
+    }
+
+    /// Whether a check ran
+    pub fn ran(&self, check: Check) -> bool {
+        self.executed.contains(&check)
+    }
+
+    /// Required checks that did not run
+    pub fn missing(&self, required: &[Check]) -> Vec<Check> {
+        required
+            .iter()
+            .copied()
+            .filter(|check| !self.ran(*check))
+            .collect()
+    }
+
+    /// The check that took longest
+    pub fn slowest(&self) -> Option<&CheckTiming> {
+        self.timings.iter().max_by_key(|t| t.micros)
+    }
+}
+
+/// Checks deferred past the time budget, queued once the transaction commits
+#[derive(Debug, Clone)]
+pub struct DeferredChecks {
+    /// The working copy the validator decided on, after hooks, masking and pseudonyms
+    pub transaction: Transaction,
+    /// Deferred checks in execution order
+    pub checks: Vec<Check>,
+    pub(crate) context: EnrichmentContext,
+    /// Layered configuration the decision was made under
+    pub(crate) config: ValidatorConfig,
+}
+
+/// Findings of deferred checks run after the decision
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FollowUpResult {
+    pub transaction_id: String,
+    pub checks: Vec<Check>,
+    pub errors: Vec<ValidationError>,
+    pub warnings: Vec<Warning>,
+    /// Risk components scored by the deferred checks only
+    pub risk_breakdown: RiskBreakdown,
+}
+
+impl FollowUpResult {
+    /// Whether the deferred checks found nothing
+    pub fn is_clean(&self) -> bool {
+        self.errors.is_empty() && self.warnings.is_empty()
+    }
+}
+
+/// State changes checks want to commit once the outcome is known
+#[derive(Debug, Default)]
+pub(crate) struct PendingState {
+    pub(crate) transaction_id: bool,
+    pub(crate) fingerprint: Option<String>,
+    pub(crate) history: bool,
+    /// Transaction joins the fraud detector's hour and location profiles
+    pub(crate) profiles: bool,
+    /// Reports the AML checker found the transaction requires
+    pub(crate) filings: Vec<ReportKind>,
+    /// Checks to run after the decision
+    pub(crate) follow_up: Option<DeferredChecks>,
+}
+
+/// A transaction checked and decided, but not yet committed or logged
+pub(crate) struct Evaluated<'a> {
+    /// The working copy the checks saw, after hooks, masking and pseudonyms
+    pub(crate) transaction: Cow<'a, Transaction>,
+    pub(crate) result: ValidationResult,
+    pub(crate) pending: PendingState,
+    #[cfg(feature = "metrics")]
+    pub(crate) elapsed: Duration,
+}
+
+/// Account check results taken before pseudonymization replaces the numbers
+#[derive(Debug)]
+pub(crate) struct AccountVerdicts {
+    /// Whether the from and to accounts match a known scheme
+    pub(crate) formats: (bool, bool),
+    /// ABA check of the routing numbers embedded in the accounts and metadata
+    pub(crate) routing: Result<(), ValidationError>,
+    /// AML sanctioned-entity match on either account, with an AML checker
+    pub(crate) sanctioned_entity: Option<bool>,
+}
+
+/// Mutable state threaded through check execution
+#[derive(Debug)]
+pub(crate) struct CheckState {
+    pub(crate) errors: Vec<ValidationError>,
+    pub(crate) warnings: Vec<Warning>,
+    pub(crate) compliance_checks: HashMap<String, bool>,
+    pub(crate) risk_breakdown: RiskBreakdown,
+    pub(crate) hard_fail: bool,
+    /// A blocklist entry matched; remaining checks are skipped
+    pub(crate) blocked: bool,
+    /// An allowlist entry matched; the risk threshold does not apply
+    pub(crate) allowlisted: bool,
+    pub(crate) skipped_checks: Vec<Check>,
+    pub(crate) context: EnrichmentContext,
+    pub(crate) segment: Option<CustomerSegment>,
+    pub(crate) channel: Option<Channel>,
+    pub(crate) limit_profile: Option<String>,
+    pub(crate) degraded: Vec<DegradationReason>,
+    pub(crate) typology_hits: Vec<TypologyHit>,
+    /// Rule nodes for the explanation, keyed by risk component
+    pub(crate) explanation: BTreeMap<&'static str, Vec<ExplanationNode>>,
+    pub(crate) telemetry: Option<CheckTelemetry>,
+    pub(crate) sampling: Vec<SamplingDecision>,
+    /// Findings awaited by `validate_async`, applied with the screening check
+    pub(crate) external: Option<ExternalFindings>,
+    /// Account checks taken on the real numbers before pseudonymization
+    pub(crate) account_verdicts: Option<AccountVerdicts>,
+    pub(crate) pending: PendingState,
+}
+
+impl CheckState {
+    pub(crate) fn new() -> Self {
+        Self {
+            errors: Vec::new(),
+            warnings: Vec::new(),
+            compliance_checks: HashMap::new(),
+            risk_breakdown: RiskBreakdown::new(),
+            hard_fail: false,
+            blocked: false,
+            allowlisted: false,
+            skipped_checks: Vec::new(),
+            context: EnrichmentContext::default(),
+            segment: None,
+            channel: None,
+            limit_profile: None,
+            degraded: Vec::new(),
+            typology_hits: Vec::new(),
+            explanation: BTreeMap::new(),
+            telemetry: None,
+            sampling: Vec::new(),
+            external: None,
+            account_verdicts: None,
+            pending: PendingState::default(),
+        }
+    }
+
+    /// Record a rule's contribution to a risk component
+    pub(crate) fn explain(&mut self, component: &'static str, node: ExplanationNode) {
+        self.explanation.entry(component).or_default().push(node);
+    }
+
+    pub(crate) fn was_skipped(&self, check: Check) -> bool {
+        self.skipped_checks.contains(&check)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_orders_by_cost() {
+        let plan = ExecutionPlan::new(&[Check::AccountFormat, Check::Amount, Check::Aml]);
+        assert_eq!(
+            plan.checks(),
+            &[Check::Amount, Check::Aml, Check::AccountFormat]
+        );
+    }
+
+    #[test]
+    fn test_plan_respects_dependencies() {
+        let plan = ExecutionPlan::new(&Check::ALL);
+        let position = |check| plan.checks().iter().position(|c| *c == check).unwrap();
+
+        assert_eq!(plan.checks().len(), Check::ALL.len());
+        // Screening is the most expensive check but gates state-recording checks
+        assert!(position(Check::Screening) < position(Check::Duplicate));
+        assert!(position(Check::Screening) < position(Check::Velocity));
+        assert!(position(Check::Screening) < position(Check::FraudPatterns));
+        assert!(position(Check::FraudPatterns) < position(Check::CustomRules));
+        assert_eq!(plan.checks()[0], Check::Amount);
+    }
+
+    #[test]
+    fn test_telemetry_lists_executed_and_skipped_checks() {
+        use crate::test_fixtures::create_test_transaction;
+        use crate::{TransactionValidator, ValidatorConfig};
+
+        let transaction = create_test_transaction("TXN-TEL-1", 100.0);
+        let mut validator = TransactionValidator::new();
+        assert!(validator.validate(&transaction).check_telemetry.is_none());
+
+        let mut validator = TransactionValidator::with_config(ValidatorConfig {
+            record_check_telemetry: true,
+            enable_aml_check: false,
+            ..Default::default()
+        });
+        let result = validator.validate(&transaction);
+        let telemetry = result.check_telemetry.unwrap();
+        assert_eq!(telemetry.executed, validator.execution_plan().checks());
+        assert_eq!(telemetry.timings.len(), telemetry.executed.len());
+        assert!(telemetry.skipped.is_empty());
+        assert_eq!(
+            telemetry.missing(&[Check::Velocity, Check::Aml]),
+            vec![Check::Aml]
+        );
+        let slowest = telemetry.slowest().unwrap();
+        assert!(slowest.micros <= telemetry.total_micros);
+    }
+
+    #[test]
+    fn test_plan_ignores_missing_dependencies() {
+        let plan = ExecutionPlan::new(&[Check::Velocity, Check::Duplicate]);
+        assert_eq!(plan.checks(), &[Check::Duplicate, Check::Velocity]);
+    }
+}
@@ -0,0 +1,119 @@
+//! Time source for validation
+//!
+//! Every component that reads the current time (result timestamps,
+//! watchlist expiry, screening times, history pruning, stats uptime) asks a
+//! [`Clock`] instead of calling `Utc::now()` directly. The default is
+//! [`SystemClock`]; [`FixedClock`] and [`MockClock`] make validation
+//! reproducible in tests and replays.
+//!
+//! `TransactionValidator::set_clock` hands the same clock to the sanctions
+//! screener, fraud detector, and network analyzer, including ones attached
+//! later.
+
+use chrono::{DateTime, Duration, Utc};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+/// Source of the current time
+pub trait Clock: Debug + Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Clock shared between the validator and its components
+pub type SharedClock = Arc<dyn Clock>;
+
+/// Wall-clock time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Clock that always returns the same instant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+/// Clock moved by hand; clones share the same time
+#[derive(Debug, Clone)]
+pub struct MockClock(Arc<Mutex<DateTime<Utc>>>);
+
+impl MockClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self(Arc::new(Mutex::new(start)))
+    }
+
+    pub fn set(&self, at: DateTime<Utc>) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = at;
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) += by;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Default clock for components created without one
+pub(crate) fn system_clock() -> SharedClock {
+    Arc::new(SystemClock)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{create_test_transaction, test_now};
+    use crate::{
+        ListType, SanctionsScreener, TransactionValidator, WatchlistEntry, WatchlistSubject,
+    };
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_validator_reads_injected_clock() {
+        let start = test_now() + Duration::seconds(5);
+        let clock = MockClock::new(start);
+        let mut validator = TransactionValidator::new();
+        validator.set_clock(Arc::new(clock.clone()));
+        validator.watchlist_mut().add(
+            WatchlistEntry::new(
+                WatchlistSubject::User,
+                "USER-001",
+                ListType::Block,
+                "temporary hold",
+                "ops",
+            )
+            .with_expiry(start + Duration::minutes(10)),
+        );
+
+        let held = validator.validate(&create_test_transaction("TXN-CLK-1", 100.0));
+        assert_eq!(held.validated_at, start);
+        assert!(!held.is_valid);
+
+        // The hold expires on the validator's clock, not the wall clock
+        clock.advance(Duration::minutes(30));
+        let released = validator.validate(&create_test_transaction("TXN-CLK-2", 100.0));
+        assert_eq!(released.validated_at, start + Duration::minutes(30));
+        assert!(released.is_valid);
+        assert_eq!(validator.get_stats().uptime_seconds, 30 * 60);
+    }
+
+    #[test]
+    fn test_screener_stamps_results_with_its_clock() {
+        let at = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let mut screener = SanctionsScreener::new();
+        screener.set_clock(Arc::new(FixedClock(at)));
+        assert_eq!(screener.screen("ACME TRADING").screening_time, at);
+    }
+}
@@ -0,0 +1,214 @@
+//! Validation from many threads at once
+//!
+//! [`TransactionValidator::validate`] takes `&mut self`, so a web service
+//! sharing one validator has to put it behind a single mutex. A
+//! [`ConcurrentTransactionValidator`] holds several validators instead,
+//! each behind its own lock, and routes every transaction to one of them
+//! by user ID, the same way [`ValidatorService`](crate::ValidatorService)
+//! routes to workers. Requests for different users proceed in parallel;
+//! one user's transactions are serialised on one shard, so velocity checks
+//! see them in order.
+//!
+//! Every shard holds the same [`SharedHistory`] and [`SharedDedup`], so
+//! velocity history and duplicate detection cover all shards. The duplicate
+//! check reserves each ID under the store's write lock, so a transaction ID
+//! submitted by two different users at the same moment passes on at most
+//! one shard. Other state, such as network graphs, statistics and decision
+//! logs, stays per shard.
+
+use crate::service::route;
+use crate::{SharedDedup, SharedHistory, Transaction, TransactionValidator, ValidationResult};
+use std::sync::{Mutex, MutexGuard};
+
+/// Validator that can be shared between threads behind an `Arc`
+pub struct ConcurrentTransactionValidator {
+    shards: Vec<Mutex<TransactionValidator>>,
+    history: SharedHistory,
+    dedup: SharedDedup,
+}
+
+impl ConcurrentTransactionValidator {
+    /// Build `shards` validators with `factory`, called with each shard's index
+    ///
+    /// The first shard's history and duplicate stores are installed in every
+    /// other shard, so a factory that already shares stores keeps them.
+    pub fn new<F>(shards: usize, factory: F) -> Self
+    where
+        F: Fn(usize) -> TransactionValidator,
+    {
+        let first = factory(0);
+        let history = first.history().clone();
+        let dedup = first.dedup().clone();
+        let mut validators = vec![Mutex::new(first)];
+        for index in 1..shards.max(1) {
+            let mut validator = factory(index);
+            validator.set_history(history.clone());
+            validator.set_dedup(dedup.clone());
+            validators.push(Mutex::new(validator));
+        }
+        Self {
+            shards: validators,
+            history,
+            dedup,
+        }
+    }
+
+    /// Validate a transaction on its user's shard
+    pub fn validate(&self, transaction: &Transaction) -> ValidationResult {
+        self.lock_shard(self.shard_for(&transaction.user_id))
+            .validate(transaction)
+    }
+
+    /// Validate without committing history or duplicate state
+    pub fn validate_dry_run(&self, transaction: &Transaction) -> ValidationResult {
+        self.lock_shard(self.shard_for(&transaction.user_id))
+            .validate_dry_run(transaction)
+    }
+
+    /// Number of shards
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Shard that validates transactions from `user_id`
+    pub fn shard_for(&self, user_id: &str) -> usize {
+        route(user_id, self.shards.len())
+    }
+
+    /// Lock one shard, for configuration or per-shard statistics
+    ///
+    /// A shard whose lock was poisoned by a panic is still returned.
+    pub fn lock_shard(&self, index: usize) -> MutexGuard<'_, TransactionValidator> {
+        self.shards[index].lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// History store shared by every shard
+    pub fn history(&self) -> &SharedHistory {
+        &self.history
+    }
+
+    /// Duplicate detection store shared by every shard
+    pub fn dedup(&self) -> &SharedDedup {
+        &self.dedup
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::ValidationError;
+
+    use std::sync::Arc;
+
+    #[test]
+    fn test_threads_share_duplicate_detection() {
+        let validator = Arc::new(ConcurrentTransactionValidator::new(4, |_| {
+            TransactionValidator::new()
+        }));
+        assert_eq!(validator.shard_count(), 4);
+
+        let handles: Vec<_> = (0..8)
+            .map(|worker| {
+                let validator = Arc::clone(&validator);
+                std::thread::spawn(move || {
+                    for i in 0..10 {
+                        let mut tx =
+                            create_test_transaction(&format!("TXN-CC-{}-{}", worker, i), 100.0);
+                        tx.user_id = format!("USER-{:03}", worker);
+                        assert!(validator.validate(&tx).is_valid);
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(validator.dedup().read().len(), 80);
+
+        // Replay worker 0's first ID from a user on another shard
+        let original = validator.shard_for("USER-000");
+        let mut replay = create_test_transaction("TXN-CC-0-0", 100.0);
+        replay.user_id = (100..)
+            .map(|i| format!("USER-{:03}", i))
+            .find(|user| validator.shard_for(user) != original)
+            .unwrap();
+        let result = validator.validate(&replay);
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::DuplicateTransaction(_))));
+    }
+
+    #[test]
+    fn test_simultaneous_ids_pass_on_one_shard_only() {
+        let validator = Arc::new(ConcurrentTransactionValidator::new(2, |_| {
+            TransactionValidator::new()
+        }));
+        // A fresh user per round keeps velocity limits out of the way
+        let users: Vec<Vec<String>> = [0, 1]
+            .iter()
+            .map(|shard| {
+                (0..)
+                    .map(|i| format!("USER-{:03}", i))
+                    .filter(|user| validator.shard_for(user) == *shard)
+                    .take(50)
+                    .collect()
+            })
+            .collect();
+
+        let barrier = Arc::new(std::sync::Barrier::new(2));
+        let handles: Vec<_> = users
+            .into_iter()
+            .map(|users| {
+                let validator = Arc::clone(&validator);
+                let barrier = Arc::clone(&barrier);
+                std::thread::spawn(move || {
+                    let mut passed = 0;
+                    for (i, user) in users.into_iter().enumerate() {
+                        let mut tx = create_test_transaction(&format!("TXN-RACE-{}", i), 1.0);
+                        tx.user_id = user;
+                        barrier.wait();
+                        passed += usize::from(validator.validate(&tx).is_valid);
+                    }
+                    passed
+                })
+            })
+            .collect();
+        let passed: usize = handles.into_iter().map(|h| h.join().unwrap()).sum();
+        assert_eq!(passed, 50);
+        assert_eq!(validator.dedup().read().len(), 50);
+
+        // A dry run's reservation is released again
+        let dry = validator.validate_dry_run(&create_test_transaction("TXN-RACE-DRY", 1.0));
+        assert!(dry.is_valid);
+        assert!(!validator.dedup().read().is_reserved("TXN-RACE-DRY"));
+        assert!(
+            validator
+                .validate(&create_test_transaction("TXN-RACE-DRY", 1.0))
+                .is_valid
+        );
+    }
+
+    #[test]
+    fn test_shards_share_velocity_history() {
+        let validator = ConcurrentTransactionValidator::new(2, |_| TransactionValidator::new());
+        assert!(validator
+            .history()
+            .ptr_eq(validator.lock_shard(1).history()));
+
+        validator.validate(&create_test_transaction("TXN-CC-H-1", 100.0));
+        assert_eq!(
+            validator
+                .history()
+                .read()
+                .get(crate::HistoryKey::User, "USER-001")
+                .len(),
+            1
+        );
+
+        let dry = validator.validate_dry_run(&create_test_transaction("TXN-CC-H-2", 100.0));
+        assert!(dry.is_valid);
+        assert!(!validator.dedup().read().contains("TXN-CC-H-2"));
+    }
+}
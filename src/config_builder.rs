@@ -0,0 +1,521 @@
+//! Checked configuration builder
+//!
+//! [`ValidatorConfig`] is a plain struct, so nothing stops a caller from
+//! setting a minimum above the maximum or a zero-length velocity window,
+//! which makes every transaction fail (or pass). The builder checks those
+//! invariants and reports the first violation as a [`ConfigError`].
+
+use crate::{
+    AdaptiveVelocity, AmountRiskTiers, AmountSignPolicy, CommitPolicy, CorridorLimits, DisputeRisk,
+    InboundVelocityLimits, Jurisdiction, NewAccountRisk, RiskWeights, RoundAmountRule,
+    SamplingPolicy, TimeRiskProfile, ValidatorConfig,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Invalid configuration value
+#[derive(Error, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ConfigError {
+    #[error("Invalid amount range: minimum {min} must be non-negative and below maximum {max}")]
+    AmountRange { min: f64, max: f64 },
+
+    #[error("Fraud threshold {0} exceeds 100")]
+    FraudThreshold(u8),
+
+    #[error("{0} must be greater than zero")]
+    NonPositive(String),
+
+    #[error("Invalid {field} range: minimum {min} exceeds maximum {max}")]
+    InvertedRange { field: String, min: f64, max: f64 },
+
+    #[error("Risk weight {field} must be a non-negative number, got {value}")]
+    InvalidWeight { field: String, value: f64 },
+
+    #[error("Invalid hour {0}: must be 0-23")]
+    InvalidHour(u32),
+
+    #[error("Invalid amount tier above {above}: {points} points (max 100)")]
+    InvalidAmountTier { above: f64, points: u8 },
+
+    #[error("Sampling rate {0} must be between 0 and 1")]
+    SamplingRate(f64),
+
+    #[error("{field} severity {value} exceeds 100")]
+    Severity { field: String, value: u8 },
+}
+
+fn require_positive(field: &str, positive: bool) -> Result<(), ConfigError> {
+    if positive {
+        Ok(())
+    } else {
+        Err(ConfigError::NonPositive(field.to_string()))
+    }
+}
+
+impl ValidatorConfig {
+    /// Start a checked builder from the default configuration
+    pub fn builder() -> ValidatorConfigBuilder {
+        ValidatorConfigBuilder::new()
+    }
+
+    /// Check the configuration's invariants
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let (min, max) = (self.min_transaction_amount, self.max_transaction_amount);
+        if !(min.is_finite() && max.is_finite() && min >= 0.0 && min < max) {
+            return Err(ConfigError::AmountRange { min, max });
+        }
+        if self.fraud_threshold > 100 {
+            return Err(ConfigError::FraudThreshold(self.fraud_threshold));
+        }
+        require_positive(
+            "velocity_check_window_minutes",
+            self.velocity_check_window_minutes > 0,
+        )?;
+        require_positive(
+            "max_transactions_per_window",
+            self.max_transactions_per_window > 0,
+        )?;
+        require_positive("max_amount_per_window", self.max_amount_per_window > 0.0)?;
+        if let Some(bucket) = self.fingerprint_bucket_seconds {
+            require_positive("fingerprint_bucket_seconds", bucket > 0)?;
+        }
+        if let Some(window) = self.double_payment_window_minutes {
+            require_positive("double_payment_window_minutes", window > 0)?;
+        }
+        if let Some(ref rule) = self.new_account_risk {
+            require_positive("new_account_risk.max_age_days", rule.max_age_days > 0)?;
+            require_positive("new_account_risk.large_amount", rule.large_amount > 0.0)?;
+        }
+        if let Some(ref rule) = self.dispute_risk {
+            require_positive("dispute_risk.lookback_days", rule.lookback_days > 0)?;
+        }
+        if let Some(days) = self.max_reference_data_age_days {
+            require_positive("max_reference_data_age_days", days > 0)?;
+        }
+        if let Some(days) = self.record_retention_days {
+            require_positive("record_retention_days", days > 0)?;
+        }
+        if let Some(ref adaptive) = self.adaptive_velocity {
+            require_positive(
+                "adaptive_velocity.baseline_days",
+                adaptive.baseline_days > 0,
+            )?;
+            require_positive("adaptive_velocity.multiplier", adaptive.multiplier > 0.0)?;
+            if adaptive.min_transactions_per_window > adaptive.max_transactions_per_window {
+                return Err(ConfigError::InvertedRange {
+                    field: "adaptive_velocity.transactions_per_window".to_string(),
+                    min: adaptive.min_transactions_per_window as f64,
+                    max: adaptive.max_transactions_per_window as f64,
+                });
+            }
+            if adaptive.min_amount_per_window > adaptive.max_amount_per_window {
+                return Err(ConfigError::InvertedRange {
+                    field: "adaptive_velocity.amount_per_window".to_string(),
+                    min: adaptive.min_amount_per_window,
+                    max: adaptive.max_amount_per_window,
+                });
+            }
+        }
+        if let Some(ref limits) = self.high_risk_corridor_limits {
+            require_positive(
+                "high_risk_corridor_limits.max_transactions_per_window",
+                limits.max_transactions_per_window > 0,
+            )?;
+            require_positive(
+                "high_risk_corridor_limits.max_amount_per_window",
+                limits.max_amount_per_window > 0.0,
+            )?;
+        }
+        if let Some(ref limits) = self.inbound_velocity {
+            require_positive("inbound_velocity.window_minutes", limits.window_minutes > 0)?;
+            require_positive(
+                "inbound_velocity.max_credits_per_window",
+                limits.max_credits_per_window > 0,
+            )?;
+            require_positive(
+                "inbound_velocity.max_amount_per_window",
+                limits.max_amount_per_window > 0.0,
+            )?;
+        }
+        if let Some(ref sampling) = self.sampling {
+            if !(0.0..=1.0).contains(&sampling.rate) {
+                return Err(ConfigError::SamplingRate(sampling.rate));
+            }
+        }
+        let weights = &self.risk_weights;
+        for (field, value) in [
+            ("amount", weights.amount),
+            ("velocity", weights.velocity),
+            ("pattern", weights.pattern),
+            ("time", weights.time),
+            ("geo", weights.geo),
+            ("network", weights.network),
+            ("model", weights.model),
+        ] {
+            if !(value.is_finite() && value >= 0.0) {
+                return Err(ConfigError::InvalidWeight {
+                    field: field.to_string(),
+                    value,
+                });
+            }
+        }
+        let profile = &self.time_risk_profile;
+        for hour in [
+            profile.business_hours.0,
+            profile.business_hours.1,
+            profile.extended_hours.0,
+            profile.extended_hours.1,
+        ] {
+            if hour > 23 {
+                return Err(ConfigError::InvalidHour(hour));
+            }
+        }
+        require_positive("round_amount.modulus", self.round_amount.modulus > 0.0)?;
+        if self.round_amount.severity > 100 {
+            return Err(ConfigError::Severity {
+                field: "round_amount".to_string(),
+                value: self.round_amount.severity,
+            });
+        }
+        let amount_tiers = &self.amount_risk_tiers;
+        let all_tiers = amount_tiers
+            .tiers
+            .iter()
+            .chain(amount_tiers.overrides.iter().flat_map(|o| &o.tiers));
+        for tier in all_tiers {
+            if !tier.above.is_finite() || tier.points > 100 {
+                return Err(ConfigError::InvalidAmountTier {
+                    above: tier.above,
+                    points: tier.points,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Builder for [`ValidatorConfig`] that rejects impossible settings
+#[derive(Debug, Clone, Default)]
+pub struct ValidatorConfigBuilder {
+    config: ValidatorConfig,
+}
+
+impl ValidatorConfigBuilder {
+    /// Start from the default configuration
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start from an existing configuration
+    pub fn from_config(config: ValidatorConfig) -> Self {
+        Self { config }
+    }
+
+    /// Accepted amount range (minimum inclusive, maximum inclusive)
+    pub fn amount_range(mut self, min: f64, max: f64) -> Self {
+        self.config.min_transaction_amount = min;
+        self.config.max_transaction_amount = max;
+        self
+    }
+
+    /// Risk score (0-100) above which transactions are rejected
+    pub fn fraud_threshold(mut self, threshold: u8) -> Self {
+        self.config.fraud_threshold = threshold;
+        self
+    }
+
+    /// Enable or disable duplicate detection
+    pub fn duplicate_check(mut self, enabled: bool) -> Self {
+        self.config.enable_duplicate_check = enabled;
+        self
+    }
+
+    /// Enable or disable the AML compliance check
+    pub fn aml_check(mut self, enabled: bool) -> Self {
+        self.config.enable_aml_check = enabled;
+        self
+    }
+
+    /// Enable or disable the velocity check
+    pub fn velocity_check(mut self, enabled: bool) -> Self {
+        self.config.enable_velocity_check = enabled;
+        self
+    }
+
+    /// Enable or disable time-of-day risk scoring
+    pub fn time_risk(mut self, enabled: bool) -> Self {
+        self.config.enable_time_risk = enabled;
+        self
+    }
+
+    /// Enable or disable the large round-amount pattern
+    pub fn round_amount_check(mut self, enabled: bool) -> Self {
+        self.config.enable_round_amount_check = enabled;
+        self
+    }
+
+    /// Enable or disable flagging wire transfers for review
+    pub fn wire_review(mut self, enabled: bool) -> Self {
+        self.config.enable_wire_review = enabled;
+        self
+    }
+
+    /// Enable or disable blocking comprehensively sanctioned jurisdictions
+    pub fn block_sanctioned_jurisdictions(mut self, enabled: bool) -> Self {
+        self.config.block_sanctioned_jurisdictions = enabled;
+        self
+    }
+
+    /// Enable or disable account number format validation
+    pub fn account_format_check(mut self, enabled: bool) -> Self {
+        self.config.enable_account_format_check = enabled;
+        self
+    }
+
+    /// Enable or disable business rule checks
+    pub fn business_rules(mut self, enabled: bool) -> Self {
+        self.config.enable_business_rules = enabled;
+        self
+    }
+
+    /// Velocity window length and the count and amount limits within it
+    pub fn velocity(
+        mut self,
+        window_minutes: i64,
+        max_transactions: usize,
+        max_amount: f64,
+    ) -> Self {
+        self.config.velocity_check_window_minutes = window_minutes;
+        self.config.max_transactions_per_window = max_transactions;
+        self.config.max_amount_per_window = max_amount;
+        self
+    }
+
+    /// Skip remaining checks after a sanctions hit or prohibited country
+    pub fn short_circuit_hard_fails(mut self, enabled: bool) -> Self {
+        self.config.short_circuit_hard_fails = enabled;
+        self
+    }
+
+    /// Time budget after which deferrable checks are skipped
+    pub fn check_budget_micros(mut self, budget: Option<u64>) -> Self {
+        self.config.check_budget_micros = budget;
+        self
+    }
+
+    /// Derive velocity limits from each user's baseline
+    pub fn adaptive_velocity(mut self, adaptive: Option<AdaptiveVelocity>) -> Self {
+        self.config.adaptive_velocity = adaptive;
+        self
+    }
+
+    /// Tighten velocity limits on corridors the geo scorer rates High
+    pub fn high_risk_corridor_limits(mut self, limits: Option<CorridorLimits>) -> Self {
+        self.config.high_risk_corridor_limits = limits;
+        self
+    }
+
+    /// Limit credits received per account and window
+    pub fn inbound_velocity(mut self, limits: Option<InboundVelocityLimits>) -> Self {
+        self.config.inbound_velocity = limits;
+        self
+    }
+
+    /// Sample network analysis and model scoring on low-risk traffic
+    pub fn sampling(mut self, policy: Option<SamplingPolicy>) -> Self {
+        self.config.sampling = policy;
+        self
+    }
+
+    /// Flag content replays, bucketing timestamps to this many seconds
+    pub fn fingerprint_bucket_seconds(mut self, bucket: Option<i64>) -> Self {
+        self.config.fingerprint_bucket_seconds = bucket;
+        self
+    }
+
+    /// Warn on repeated payments to one beneficiary within this window
+    pub fn double_payment_window_minutes(mut self, window: Option<i64>) -> Self {
+        self.config.double_payment_window_minutes = window;
+        self
+    }
+
+    /// Extra pattern risk for very new accounts with large or rapid activity
+    pub fn new_account_risk(mut self, rule: Option<NewAccountRisk>) -> Self {
+        self.config.new_account_risk = rule;
+        self
+    }
+
+    /// Extra pattern risk for users and counterparties with prior disputes
+    pub fn dispute_risk(mut self, rule: Option<DisputeRisk>) -> Self {
+        self.config.dispute_risk = rule;
+        self
+    }
+
+    /// Send degraded validations that would be approved to review instead
+    pub fn review_when_degraded(mut self, enabled: bool) -> Self {
+        self.config.review_when_degraded = enabled;
+        self
+    }
+
+    /// Mask card numbers before checking or storing anything
+    pub fn mask_pans(mut self, enabled: bool) -> Self {
+        self.config.mask_pans = enabled;
+        self
+    }
+
+    /// Let `customer_segment` metadata pick segment overrides
+    pub fn trust_segment_metadata(mut self, trusted: bool) -> Self {
+        self.config.trust_segment_metadata = trusted;
+        self
+    }
+
+    /// Record which checks ran and how long each took on every result
+    pub fn record_check_telemetry(mut self, enabled: bool) -> Self {
+        self.config.record_check_telemetry = enabled;
+        self
+    }
+
+    /// Warn when a loaded reference dataset is older than this many days
+    pub fn max_reference_data_age_days(mut self, days: Option<i64>) -> Self {
+        self.config.max_reference_data_age_days = days;
+        self
+    }
+
+    /// Record the regulatory regime the configuration follows
+    pub fn jurisdiction(mut self, jurisdiction: Option<Jurisdiction>) -> Self {
+        self.config.jurisdiction = jurisdiction;
+        self
+    }
+
+    /// Keep validation records for this many days
+    pub fn record_retention_days(mut self, days: Option<i64>) -> Self {
+        self.config.record_retention_days = days;
+        self
+    }
+
+    /// When validation records transactions into dedup and velocity state
+    pub fn commit_policy(mut self, policy: CommitPolicy) -> Self {
+        self.config.commit_policy = policy;
+        self
+    }
+
+    /// Multipliers applied to each risk component
+    pub fn risk_weights(mut self, weights: RiskWeights) -> Self {
+        self.config.risk_weights = weights;
+        self
+    }
+
+    /// Hour windows and scores used for time-of-day risk
+    pub fn time_risk_profile(mut self, profile: TimeRiskProfile) -> Self {
+        self.config.time_risk_profile = profile;
+        self
+    }
+
+    /// Amount thresholds and the risk points they carry
+    pub fn amount_risk_tiers(mut self, tiers: AmountRiskTiers) -> Self {
+        self.config.amount_risk_tiers = tiers;
+        self
+    }
+
+    /// Transaction types that may carry zero or negative amounts
+    pub fn amount_sign_policy(mut self, policy: AmountSignPolicy) -> Self {
+        self.config.amount_sign_policy = policy;
+        self
+    }
+
+    /// Round-amount pattern thresholds, points, and exempt products
+    pub fn round_amount(mut self, rule: RoundAmountRule) -> Self {
+        self.config.round_amount = rule;
+        self
+    }
+
+    /// Check invariants and return the configuration
+    pub fn build(self) -> Result<ValidatorConfig, ConfigError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_accepts_valid_config() {
+        let config = ValidatorConfig::builder()
+            .amount_range(1.0, 5000.0)
+            .fraud_threshold(50)
+            .velocity(30, 5, 10_000.0)
+            .fingerprint_bucket_seconds(Some(300))
+            .build()
+            .unwrap();
+        assert_eq!(config.max_transaction_amount, 5000.0);
+        assert_eq!(config.velocity_check_window_minutes, 30);
+        assert!(ValidatorConfig::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_builder_rejects_impossible_config() {
+        let error = |builder: ValidatorConfigBuilder| builder.build().unwrap_err();
+        assert_eq!(
+            error(ValidatorConfig::builder().amount_range(100.0, 10.0)),
+            ConfigError::AmountRange {
+                min: 100.0,
+                max: 10.0
+            }
+        );
+        assert_eq!(
+            error(ValidatorConfig::builder().fraud_threshold(101)),
+            ConfigError::FraudThreshold(101)
+        );
+        assert_eq!(
+            error(ValidatorConfig::builder().velocity(0, 10, 1000.0)),
+            ConfigError::NonPositive("velocity_check_window_minutes".to_string())
+        );
+
+        let inverted = AdaptiveVelocity {
+            min_transactions_per_window: 50,
+            max_transactions_per_window: 5,
+            ..Default::default()
+        };
+        assert!(matches!(
+            error(ValidatorConfig::builder().adaptive_velocity(Some(inverted))),
+            ConfigError::InvertedRange { .. }
+        ));
+
+        let negative = RiskWeights {
+            time: -1.0,
+            ..Default::default()
+        };
+        assert!(matches!(
+            error(ValidatorConfig::builder().risk_weights(negative)),
+            ConfigError::InvalidWeight { .. }
+        ));
+
+        let late = TimeRiskProfile {
+            business_hours: (9, 24),
+            ..Default::default()
+        };
+        assert_eq!(
+            error(ValidatorConfig::builder().time_risk_profile(late)),
+            ConfigError::InvalidHour(24)
+        );
+
+        let oversampled = crate::SamplingPolicy {
+            rate: 1.5,
+            ..Default::default()
+        };
+        assert_eq!(
+            error(ValidatorConfig::builder().sampling(Some(oversampled))),
+            ConfigError::SamplingRate(1.5)
+        );
+
+        assert_eq!(
+            error(ValidatorConfig::builder().round_amount(RoundAmountRule::with_severity(101))),
+            ConfigError::Severity {
+                field: "round_amount".to_string(),
+                value: 101
+            }
+        );
+    }
+}
@@ -0,0 +1,333 @@
+//! Configuration files
+//!
+//! Compliance teams tune thresholds in a JSON file instead of code.
+//! Top-level keys are [`ValidatorConfig`] fields, and any left out keep
+//! their defaults. Two optional sections configure components that live
+//! outside the config: `aml` sets [`AMLThresholds`], and `account_formats`
+//! adds regex account schemes and picks which are accepted. Unknown keys,
+//! invalid values, and regexes that do not compile are reported when the
+//! file is loaded, not on first use.
+//!
+//! ```json
+//! {
+//!   "fraud_threshold": 60,
+//!   "velocity_check_window_minutes": 30,
+//!   "max_transactions_per_window": 5,
+//!   "aml": { "structuring_threshold": 9000 },
+//!   "account_formats": {
+//!     "accept": ["iban", "acme"],
+//!     "schemes": [{ "name": "acme", "pattern": "^ACME-\\d{8}$" }]
+//!   }
+//! }
+//! ```
+//!
+//! Only JSON is read. TOML and YAML are not supported: the crate has no
+//! TOML or YAML parser, and a `.toml`, `.yaml`, or `.yml` path fails with
+//! [`ConfigFileError::UnsupportedFormat`] rather than being parsed as JSON.
+//! Such files need converting to JSON first. Files are read through
+//! [`ParseLimits`], so an oversized file is rejected before it is parsed.
+
+use crate::{
+    AMLThresholds, AccountFormatRegistry, AccountScheme, ConfigError, ConfigOverride, ParseError,
+    ParseLimits, ValidatorConfig,
+};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::path::Path;
+use thiserror::Error;
+
+/// Error loading a configuration file
+#[derive(Error, Debug)]
+pub enum ConfigFileError {
+    #[error("Failed to read config file: {0}")]
+    Read(#[from] ParseError),
+
+    #[error("Unrecognized config file extension: {0}")]
+    UnknownFormat(String),
+
+    #[error("TOML and YAML config files are not supported, convert {0} to JSON")]
+    UnsupportedFormat(String),
+
+    #[error("Syntax error on line {line}: {message}")]
+    Syntax { line: usize, message: String },
+
+    #[error("Unknown setting {0}")]
+    UnknownKey(String),
+
+    #[error("Invalid setting: {0}")]
+    Schema(#[from] serde_json::Error),
+
+    #[error("Invalid configuration: {0}")]
+    Config(#[from] ConfigError),
+
+    #[error("Account scheme {scheme} has an invalid pattern: {source}")]
+    Pattern {
+        scheme: String,
+        source: regex::Error,
+    },
+
+    #[error("Account scheme {0} is accepted but not defined")]
+    UnknownScheme(String),
+}
+
+fn syntax(line: usize, message: impl Into<String>) -> ConfigFileError {
+    ConfigFileError::Syntax {
+        line,
+        message: message.into(),
+    }
+}
+
+/// A loaded and checked configuration file
+#[derive(Debug, Clone)]
+pub struct ConfigFile {
+    /// Defaults with the file's settings applied
+    pub config: ValidatorConfig,
+    /// Set when the file has an `aml` section
+    pub aml_thresholds: Option<AMLThresholds>,
+    /// Set when the file has an `account_formats` section
+    pub account_formats: Option<AccountFormatRegistry>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AccountFormatsSection {
+    #[serde(default)]
+    schemes: Vec<SchemeSpec>,
+    /// Accepted scheme names; all registered schemes when omitted
+    accept: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SchemeSpec {
+    name: String,
+    pattern: String,
+}
+
+impl AccountFormatsSection {
+    fn registry(self) -> Result<AccountFormatRegistry, ConfigFileError> {
+        let mut registry = AccountFormatRegistry::new();
+        for spec in self.schemes {
+            let scheme = AccountScheme::pattern(&spec.name, &spec.pattern).map_err(|source| {
+                ConfigFileError::Pattern {
+                    scheme: spec.name.clone(),
+                    source,
+                }
+            })?;
+            registry.register(scheme);
+        }
+        let names = registry.names();
+        let accepted = self.accept.unwrap_or_else(|| names.clone());
+        if let Some(unknown) = accepted.iter().find(|name| !names.contains(name)) {
+            return Err(ConfigFileError::UnknownScheme(unknown.clone()));
+        }
+        let accepted: Vec<&str> = accepted.iter().map(String::as_str).collect();
+        registry.accept_by_default(&accepted);
+        Ok(registry)
+    }
+}
+
+impl ConfigFile {
+    /// Parse and check a JSON configuration
+    pub fn from_json(text: &str) -> Result<Self, ConfigFileError> {
+        let value = serde_json::from_str(text).map_err(|e| {
+            if e.is_syntax() || e.is_eof() {
+                syntax(e.line(), e.to_string())
+            } else {
+                ConfigFileError::Schema(e)
+            }
+        })?;
+        Self::from_value(value)
+    }
+
+    /// Read a `.json` configuration file under default [`ParseLimits`]
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigFileError> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("json") => {}
+            Some("toml" | "yaml" | "yml") => {
+                return Err(ConfigFileError::UnsupportedFormat(
+                    path.display().to_string(),
+                ))
+            }
+            _ => return Err(ConfigFileError::UnknownFormat(path.display().to_string())),
+        }
+        let limits = ParseLimits::default();
+        let bytes = limits.read_file(path)?;
+        Self::from_json(limits.input_text(&bytes)?)
+    }
+
+    fn from_value(value: Value) -> Result<Self, ConfigFileError> {
+        let mut table = match value {
+            Value::Object(table) => table,
+            Value::Null => Map::new(),
+            _ => return Err(syntax(1, "expected a table of settings")),
+        };
+        let aml = table.remove("aml");
+        let formats = table.remove("account_formats");
+
+        // Every override field serializes, so its keys are the valid settings
+        let known = match serde_json::to_value(ConfigOverride::default())? {
+            Value::Object(fields) => fields,
+            _ => Map::new(),
+        };
+        if let Some(key) = table.keys().find(|key| !known.contains_key(*key)) {
+            return Err(ConfigFileError::UnknownKey(key.clone()));
+        }
+        let overrides: ConfigOverride = serde_json::from_value(Value::Object(table))?;
+        let config = overrides.apply(&ValidatorConfig::default());
+        config.validate()?;
+
+        let aml_thresholds = aml.map(serde_json::from_value).transpose()?;
+        let account_formats = formats
+            .map(serde_json::from_value::<AccountFormatsSection>)
+            .transpose()?
+            .map(AccountFormatsSection::registry)
+            .transpose()?;
+        Ok(Self {
+            config,
+            aml_thresholds,
+            account_formats,
+        })
+    }
+}
+
+impl ValidatorConfig {
+    /// Parse a JSON configuration, ignoring its `aml` and `account_formats` sections
+    pub fn from_json(text: &str) -> Result<Self, ConfigFileError> {
+        Ok(ConfigFile::from_json(text)?.config)
+    }
+
+    /// Read a configuration file, ignoring its `aml` and `account_formats` sections
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigFileError> {
+        Ok(ConfigFile::from_file(path)?.config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{CommitPolicy, TransactionType, TransactionValidator};
+
+    const JSON: &str = r#"{
+  "fraud_threshold": 60,
+  "velocity_check_window_minutes": 30,
+  "max_transactions_per_window": 5,
+  "max_amount_per_window": 50000,
+  "commit_policy": "Always",
+  "risk_weights": {
+    "amount": 1.5, "velocity": 1.0, "pattern": 1.0, "time": 0.5,
+    "geo": 1.0, "network": 1.0, "model": 0.0
+  },
+  "aml": { "structuring_threshold": 9000 },
+  "account_formats": {
+    "accept": ["iban", "acme"],
+    "schemes": [{ "name": "acme", "pattern": "^ACME-\\d{8}$" }]
+  }
+}"#;
+
+    #[test]
+    fn test_json_file_loads_settings() {
+        let file = ConfigFile::from_json(JSON).unwrap();
+        assert_eq!(file.config.fraud_threshold, 60);
+        assert_eq!(file.config.velocity_check_window_minutes, 30);
+        assert_eq!(file.config.max_transactions_per_window, 5);
+        assert_eq!(file.config.max_amount_per_window, 50_000.0);
+        assert_eq!(file.config.commit_policy, CommitPolicy::Always);
+        assert_eq!(file.config.risk_weights.amount, 1.5);
+        // Untouched settings keep their defaults
+        assert_eq!(
+            file.config.max_transaction_amount,
+            ValidatorConfig::default().max_transaction_amount
+        );
+        let aml = file.aml_thresholds.as_ref().unwrap();
+        assert_eq!(aml.structuring_threshold, 9000.0);
+        assert_eq!(aml.ctr_threshold, AMLThresholds::default().ctr_threshold);
+        let formats = file.account_formats.as_ref().unwrap();
+        let transfer = TransactionType::Transfer;
+        assert_eq!(formats.scheme_for("ACME-12345678", transfer), Some("acme"));
+        assert_eq!(formats.scheme_for("ACCT-1234-5678-9012", transfer), None);
+    }
+
+    #[test]
+    fn test_invalid_files_are_rejected_on_load() {
+        let error = ConfigFile::from_json(r#"{"fraud_treshold": 60}"#).unwrap_err();
+        assert!(matches!(error, ConfigFileError::UnknownKey(key) if key == "fraud_treshold"));
+
+        let error = ValidatorConfig::from_json(r#"{"fraud_threshold": 101}"#);
+        assert!(matches!(
+            error,
+            Err(ConfigFileError::Config(ConfigError::FraudThreshold(101)))
+        ));
+
+        let error = ValidatorConfig::from_json(r#"{"fraud_threshold": "high"}"#);
+        assert!(matches!(error, Err(ConfigFileError::Schema(_))));
+
+        let error = ConfigFile::from_json("{\n\n\"fraud_threshold\": [1, 2");
+        assert!(matches!(
+            error,
+            Err(ConfigFileError::Syntax { line: 3, .. })
+        ));
+        let error = ConfigFile::from_json("[60]");
+        assert!(matches!(
+            error,
+            Err(ConfigFileError::Syntax { line: 1, .. })
+        ));
+
+        let json = r#"{"account_formats": {"schemes": [{"name": "bad", "pattern": "^(ACME"}]}}"#;
+        let error = ConfigFile::from_json(json).unwrap_err();
+        assert!(matches!(error, ConfigFileError::Pattern { scheme, .. } if scheme == "bad"));
+
+        let json = r#"{"account_formats": {"accept": ["swift"]}}"#;
+        let error = ConfigFile::from_json(json).unwrap_err();
+        assert!(matches!(error, ConfigFileError::UnknownScheme(name) if name == "swift"));
+
+        let error = ConfigFile::from_file("validator.toml").unwrap_err();
+        assert!(matches!(error, ConfigFileError::UnsupportedFormat(_)));
+        let error = ConfigFile::from_file("validator.ini").unwrap_err();
+        assert!(matches!(error, ConfigFileError::UnknownFormat(_)));
+    }
+
+    #[test]
+    fn test_validator_built_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("validator.json");
+        std::fs::write(&path, JSON).unwrap();
+        let file = ConfigFile::from_file(&path).unwrap();
+        assert_eq!(
+            ValidatorConfig::from_file(&path).unwrap().fraud_threshold,
+            60
+        );
+        assert!(matches!(
+            ConfigFile::from_file(dir.path().join("validator.yml")),
+            Err(ConfigFileError::UnsupportedFormat(_))
+        ));
+        assert!(matches!(
+            ConfigFile::from_file(dir.path().join("missing.json")),
+            Err(ConfigFileError::Read(ParseError::Io(_)))
+        ));
+
+        let mut validator = TransactionValidator::builder()
+            .with_config_file(&file)
+            .build();
+        let aml = validator.aml_checker().unwrap();
+        assert_eq!(aml.thresholds().structuring_threshold, 9000.0);
+        assert_eq!(
+            validator
+                .account_formats()
+                .accepted(TransactionType::Transfer),
+            ["iban", "acme"]
+        );
+
+        // The default test accounts are no longer an accepted format
+        let mut transaction = create_test_transaction("TXN-CFG-1", 100.0);
+        transaction.from_account = Some("ACME-12345678".to_string());
+        let result = validator.validate(&transaction);
+        assert_eq!(result.effective_config.fraud_threshold, 60);
+        assert!(!result.is_valid);
+    }
+}
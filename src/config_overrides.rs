@@ -0,0 +1,350 @@
+//! Layered configuration overrides
+//!
+//! Configuration resolves in layers: global defaults, then a tenant
+//! override, then a customer-segment override chosen per transaction,
+//! then the user's limit profile, then the channel policy.
+//! Fields left as `None` in an override inherit from the layer below.
+//!
+//! The segment comes from the enrichment context's customer tier. Metadata
+//! is written by the submitter, so a `customer_segment` entry is only used
+//! with `ValidatorConfig::trust_segment_metadata` set.
+
+use crate::{
+    AdaptiveVelocity, AmountRiskTiers, AmountSignPolicy, CommitPolicy, CorridorLimits, DisputeRisk,
+    EnrichmentContext, InboundVelocityLimits, Jurisdiction, NewAccountRisk, RiskWeights,
+    RoundAmountRule, SamplingPolicy, TimeRiskProfile, Transaction, ValidatorConfig,
+};
+use serde::{Deserialize, Serialize};
+
+/// Metadata key carrying the customer segment when no provider supplies one
+/// and the validator trusts submitters to set it
+pub const SEGMENT_METADATA_KEY: &str = "customer_segment";
+
+/// Customer segment used to pick segment-level overrides
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum CustomerSegment {
+    Retail,
+    Smb,
+    Corporate,
+}
+
+impl CustomerSegment {
+    /// Parse a segment name (case-insensitive)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "retail" => Some(CustomerSegment::Retail),
+            "smb" | "small_business" => Some(CustomerSegment::Smb),
+            "corporate" => Some(CustomerSegment::Corporate),
+            _ => None,
+        }
+    }
+
+    /// Resolve the segment of a transaction
+    ///
+    /// The enrichment context's customer tier wins; transaction metadata is
+    /// consulted only when `trust_metadata` is set.
+    pub fn of(
+        transaction: &Transaction,
+        context: &EnrichmentContext,
+        trust_metadata: bool,
+    ) -> Option<Self> {
+        let enriched = context.customer_tier.as_deref().and_then(Self::parse);
+        if enriched.is_some() || !trust_metadata {
+            return enriched;
+        }
+        transaction
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get(SEGMENT_METADATA_KEY))
+            .and_then(Self::parse)
+    }
+}
+
+/// Partial configuration; set fields replace those of the layer below
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfigOverride {
+    pub max_transaction_amount: Option<f64>,
+    pub min_transaction_amount: Option<f64>,
+    pub fraud_threshold: Option<u8>,
+    pub enable_duplicate_check: Option<bool>,
+    pub enable_aml_check: Option<bool>,
+    pub enable_velocity_check: Option<bool>,
+    pub enable_time_risk: Option<bool>,
+    pub enable_round_amount_check: Option<bool>,
+    pub enable_wire_review: Option<bool>,
+    pub block_sanctioned_jurisdictions: Option<bool>,
+    pub enable_account_format_check: Option<bool>,
+    pub enable_business_rules: Option<bool>,
+    pub velocity_check_window_minutes: Option<i64>,
+    pub max_transactions_per_window: Option<usize>,
+    pub max_amount_per_window: Option<f64>,
+    pub short_circuit_hard_fails: Option<bool>,
+    pub check_budget_micros: Option<u64>,
+    pub adaptive_velocity: Option<AdaptiveVelocity>,
+    pub high_risk_corridor_limits: Option<CorridorLimits>,
+    pub inbound_velocity: Option<InboundVelocityLimits>,
+    pub sampling: Option<SamplingPolicy>,
+    pub fingerprint_bucket_seconds: Option<i64>,
+    pub double_payment_window_minutes: Option<i64>,
+    pub new_account_risk: Option<NewAccountRisk>,
+    pub dispute_risk: Option<DisputeRisk>,
+    pub review_when_degraded: Option<bool>,
+    pub mask_pans: Option<bool>,
+    pub trust_segment_metadata: Option<bool>,
+    pub max_reference_data_age_days: Option<i64>,
+    pub jurisdiction: Option<Jurisdiction>,
+    pub record_retention_days: Option<i64>,
+    pub record_check_telemetry: Option<bool>,
+    pub commit_policy: Option<CommitPolicy>,
+    pub risk_weights: Option<RiskWeights>,
+    pub time_risk_profile: Option<TimeRiskProfile>,
+    pub amount_risk_tiers: Option<AmountRiskTiers>,
+    pub amount_sign_policy: Option<AmountSignPolicy>,
+    pub round_amount: Option<RoundAmountRule>,
+}
+
+impl ConfigOverride {
+    /// Apply this override on top of `base`
+    pub fn apply(&self, base: &ValidatorConfig) -> ValidatorConfig {
+        let mut config = base.clone();
+        if let Some(v) = self.max_transaction_amount {
+            config.max_transaction_amount = v;
+        }
+        if let Some(v) = self.min_transaction_amount {
+            config.min_transaction_amount = v;
+        }
+        if let Some(v) = self.fraud_threshold {
+            config.fraud_threshold = v;
+        }
+        if let Some(v) = self.enable_duplicate_check {
+            config.enable_duplicate_check = v;
+        }
+        if let Some(v) = self.enable_aml_check {
+            config.enable_aml_check = v;
+        }
+        if let Some(v) = self.enable_velocity_check {
+            config.enable_velocity_check = v;
+        }
+        if let Some(v) = self.enable_time_risk {
+            config.enable_time_risk = v;
+        }
+        if let Some(v) = self.enable_round_amount_check {
+            config.enable_round_amount_check = v;
+        }
+        if let Some(v) = self.enable_wire_review {
+            config.enable_wire_review = v;
+        }
+        if let Some(v) = self.block_sanctioned_jurisdictions {
+            config.block_sanctioned_jurisdictions = v;
+        }
+        if let Some(v) = self.enable_account_format_check {
+            config.enable_account_format_check = v;
+        }
+        if let Some(v) = self.enable_business_rules {
+            config.enable_business_rules = v;
+        }
+        if let Some(v) = self.velocity_check_window_minutes {
+            config.velocity_check_window_minutes = v;
+        }
+        if let Some(v) = self.max_transactions_per_window {
+            config.max_transactions_per_window = v;
+        }
+        if let Some(v) = self.max_amount_per_window {
+            config.max_amount_per_window = v;
+        }
+        if let Some(v) = self.short_circuit_hard_fails {
+            config.short_circuit_hard_fails = v;
+        }
+        if self.check_budget_micros.is_some() {
+            config.check_budget_micros = self.check_budget_micros;
+        }
+        if self.adaptive_velocity.is_some() {
+            config.adaptive_velocity = self.adaptive_velocity.clone();
+        }
+        if self.high_risk_corridor_limits.is_some() {
+            config.high_risk_corridor_limits = self.high_risk_corridor_limits.clone();
+        }
+        if self.inbound_velocity.is_some() {
+            config.inbound_velocity = self.inbound_velocity.clone();
+        }
+        if self.sampling.is_some() {
+            config.sampling = self.sampling.clone();
+        }
+        if self.fingerprint_bucket_seconds.is_some() {
+            config.fingerprint_bucket_seconds = self.fingerprint_bucket_seconds;
+        }
+        if self.double_payment_window_minutes.is_some() {
+            config.double_payment_window_minutes = self.double_payment_window_minutes;
+        }
+        if self.new_account_risk.is_some() {
+            config.new_account_risk = self.new_account_risk.clone();
+        }
+        if self.dispute_risk.is_some() {
+            config.dispute_risk = self.dispute_risk.clone();
+        }
+        if let Some(v) = self.review_when_degraded {
+            config.review_when_degraded = v;
+        }
+        if let Some(v) = self.mask_pans {
+            config.mask_pans = v;
+        }
+        if let Some(v) = self.trust_segment_metadata {
+            config.trust_segment_metadata = v;
+        }
+        if self.max_reference_data_age_days.is_some() {
+            config.max_reference_data_age_days = self.max_reference_data_age_days;
+        }
+        if self.jurisdiction.is_some() {
+            config.jurisdiction = self.jurisdiction;
+        }
+        if self.record_retention_days.is_some() {
+            config.record_retention_days = self.record_retention_days;
+        }
+        if let Some(v) = self.record_check_telemetry {
+            config.record_check_telemetry = v;
+        }
+        if let Some(v) = self.commit_policy {
+            config.commit_policy = v;
+        }
+        if let Some(ref v) = self.risk_weights {
+            config.risk_weights = v.clone();
+        }
+        if let Some(ref v) = self.time_risk_profile {
+            config.time_risk_profile = v.clone();
+        }
+        if let Some(ref v) = self.amount_risk_tiers {
+            config.amount_risk_tiers = v.clone();
+        }
+        if let Some(ref v) = self.amount_sign_policy {
+            config.amount_sign_policy = v.clone();
+        }
+        if let Some(ref v) = self.round_amount {
+            config.round_amount = v.clone();
+        }
+        config
+    }
+
+    /// Check whether the override changes nothing
+    pub fn is_empty(&self) -> bool {
+        self == &ConfigOverride::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+    use crate::{TransactionMetadata, TransactionValidator, ValidationError};
+
+    fn create_test_transaction(id: &str, amount: f64, segment: Option<&str>) -> Transaction {
+        Transaction {
+            metadata: segment.map(|s| {
+                TransactionMetadata::from([(SEGMENT_METADATA_KEY.to_string(), s.to_string())])
+            }),
+            ..test_fixtures::create_test_transaction(id, amount)
+        }
+    }
+
+    #[test]
+    fn test_override_inherits_unset_fields() {
+        let base = ValidatorConfig::default();
+        let config = ConfigOverride {
+            fraud_threshold: Some(40),
+            ..Default::default()
+        }
+        .apply(&base);
+
+        assert_eq!(config.fraud_threshold, 40);
+        assert_eq!(config.max_transaction_amount, base.max_transaction_amount);
+        assert!(ConfigOverride::default().is_empty());
+    }
+
+    #[test]
+    fn test_segment_resolution() {
+        let tx = create_test_transaction("TXN-SEG-001", 1.0, Some("Corporate"));
+        assert_eq!(
+            CustomerSegment::of(&tx, &EnrichmentContext::default(), true),
+            Some(CustomerSegment::Corporate)
+        );
+        assert_eq!(
+            CustomerSegment::of(&tx, &EnrichmentContext::default(), false),
+            None
+        );
+
+        let context = EnrichmentContext {
+            customer_tier: Some("retail".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            CustomerSegment::of(&tx, &context, true),
+            Some(CustomerSegment::Retail)
+        );
+        assert_eq!(CustomerSegment::parse("platinum"), None);
+    }
+
+    #[test]
+    fn test_segment_override_applied_at_validation() {
+        let mut validator = TransactionValidator::with_config(ValidatorConfig {
+            max_transaction_amount: 10_000.0,
+            ..Default::default()
+        });
+        validator.set_segment_override(
+            CustomerSegment::Corporate,
+            ConfigOverride {
+                max_transaction_amount: Some(500_000.0),
+                ..Default::default()
+            },
+        );
+
+        let retail = validator.validate(&create_test_transaction("TXN-SEG-R", 50_000.0, None));
+        assert!(retail
+            .errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::InvalidAmount(_))));
+        assert_eq!(retail.segment, None);
+        assert_eq!(retail.effective_config.max_transaction_amount, 10_000.0);
+
+        // Submitters cannot claim a segment unless the validator trusts them
+        let claimed = validator.validate(&create_test_transaction(
+            "TXN-SEG-X",
+            50_000.0,
+            Some("corporate"),
+        ));
+        assert_eq!(claimed.segment, None);
+        assert_eq!(claimed.effective_config.max_transaction_amount, 10_000.0);
+
+        let enriched = validator.validate_with_context(
+            &create_test_transaction("TXN-SEG-E", 50_000.0, None),
+            EnrichmentContext {
+                customer_tier: Some("corporate".to_string()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(enriched.segment, Some(CustomerSegment::Corporate));
+        assert!(enriched.is_valid);
+
+        let mut validator = TransactionValidator::with_config(ValidatorConfig {
+            max_transaction_amount: 10_000.0,
+            trust_segment_metadata: true,
+            ..Default::default()
+        });
+        validator.set_segment_override(
+            CustomerSegment::Corporate,
+            ConfigOverride {
+                max_transaction_amount: Some(500_000.0),
+                ..Default::default()
+            },
+        );
+        let corporate = validator.validate(&create_test_transaction(
+            "TXN-SEG-C",
+            50_000.0,
+            Some("corporate"),
+        ));
+        assert!(!corporate
+            .errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::InvalidAmount(_))));
+        assert_eq!(corporate.segment, Some(CustomerSegment::Corporate));
+        assert_eq!(corporate.effective_config.max_transaction_amount, 500_000.0);
+    }
+}
@@ -0,0 +1,238 @@
+//! Learned counterparty trust
+//!
+//! Paying the same landlord for two years is not the same risk as paying a
+//! payee first seen an hour ago. [`CounterpartyTrust`] keeps statistics per
+//! (user, counterparty) pair from committed transactions and turns them into
+//! a 0-100 trust score. The validator converts the score into a signed
+//! offset on the total risk score: established relationships lower it,
+//! brand-new ones raise it, and disputes erase earned trust.
+
+use crate::Transaction;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// History of one user paying one counterparty
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelationshipStats {
+    pub interactions: u32,
+    pub total_volume: f64,
+    /// Chargebacks, fraud claims, and other disputes on this relationship
+    pub disputes: u32,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+impl RelationshipStats {
+    /// Average amount per interaction
+    pub fn average_amount(&self) -> f64 {
+        if self.interactions == 0 {
+            0.0
+        } else {
+            self.total_volume / self.interactions as f64
+        }
+    }
+}
+
+/// How relationship history maps to trust and risk offsets
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrustPolicy {
+    /// Interactions at which the count contributes full trust
+    pub established_interactions: u32,
+    /// Relationship age at which tenure contributes full trust
+    pub established_tenure_days: i64,
+    /// Trust points removed per dispute
+    pub dispute_penalty: u8,
+    /// Largest risk reduction, reached at trust 100
+    pub max_discount: u8,
+    /// Risk added for a counterparty the user has never paid
+    pub new_counterparty_penalty: u8,
+    /// Amounts above this multiple of the relationship average earn no discount
+    pub amount_outlier_multiplier: f64,
+}
+
+impl Default for TrustPolicy {
+    fn default() -> Self {
+        Self {
+            established_interactions: 5,
+            established_tenure_days: 90,
+            dispute_penalty: 40,
+            max_discount: 15,
+            new_counterparty_penalty: 10,
+            amount_outlier_multiplier: 3.0,
+        }
+    }
+}
+
+/// Per (user, counterparty) statistics and the trust derived from them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CounterpartyTrust {
+    relationships: HashMap<String, HashMap<String, RelationshipStats>>,
+    policy: TrustPolicy,
+}
+
+impl CounterpartyTrust {
+    /// Empty tracker with the default policy
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Empty tracker with a custom policy
+    pub fn with_policy(policy: TrustPolicy) -> Self {
+        Self {
+            relationships: HashMap::new(),
+            policy,
+        }
+    }
+
+    /// The policy in use
+    pub fn policy(&self) -> &TrustPolicy {
+        &self.policy
+    }
+
+    /// Record a payment from the transaction's user to its `to_account`
+    pub fn record(&mut self, transaction: &Transaction) {
+        let Some(ref counterparty) = transaction.to_account else {
+            return;
+        };
+        let stats = self
+            .relationships
+            .entry(transaction.user_id.clone())
+            .or_default()
+            .entry(counterparty.clone())
+            .or_insert_with(|| RelationshipStats {
+                interactions: 0,
+                total_volume: 0.0,
+                disputes: 0,
+                first_seen: transaction.timestamp,
+                last_seen: transaction.timestamp,
+            });
+        stats.interactions += 1;
+        stats.total_volume += transaction.amount;
+        stats.first_seen = stats.first_seen.min(transaction.timestamp);
+        stats.last_seen = stats.last_seen.max(transaction.timestamp);
+    }
+
+    /// Record a dispute against a relationship
+    ///
+    /// Returns false if the user has never paid the counterparty.
+    pub fn record_dispute(&mut self, user_id: &str, counterparty: &str) -> bool {
+        match self
+            .relationships
+            .get_mut(user_id)
+            .and_then(|r| r.get_mut(counterparty))
+        {
+            Some(stats) => {
+                stats.disputes += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Statistics for one relationship, if the user has paid the counterparty
+    pub fn relationship(&self, user_id: &str, counterparty: &str) -> Option<&RelationshipStats> {
+        self.relationships.get(user_id)?.get(counterparty)
+    }
+
+    /// Trust (0-100) in a relationship as of `at`; 0 for unknown relationships
+    pub fn trust_score(&self, user_id: &str, counterparty: &str, at: DateTime<Utc>) -> u8 {
+        let Some(stats) = self.relationship(user_id, counterparty) else {
+            return 0;
+        };
+        let policy = &self.policy;
+        let count =
+            (stats.interactions as f64 / policy.established_interactions.max(1) as f64).min(1.0);
+        let tenure_days = (at - stats.first_seen).num_days().max(0);
+        let tenure = (tenure_days as f64 / policy.established_tenure_days.max(1) as f64).min(1.0);
+        let earned = 50.0 * count + 50.0 * tenure;
+        let lost = stats.disputes as f64 * policy.dispute_penalty as f64;
+        (earned - lost).round().clamp(0.0, 100.0) as u8
+    }
+
+    /// Signed risk offset for the transaction
+    ///
+    /// Negative for trusted relationships paid a typical amount, positive
+    /// for a first payment to a counterparty, zero otherwise.
+    pub fn risk_adjustment(&self, transaction: &Transaction) -> i8 {
+        let Some(ref counterparty) = transaction.to_account else {
+            return 0;
+        };
+        let Some(stats) = self.relationship(&transaction.user_id, counterparty) else {
+            return self.policy.new_counterparty_penalty.min(100) as i8;
+        };
+        let outlier =
+            transaction.amount > stats.average_amount() * self.policy.amount_outlier_multiplier;
+        if outlier || stats.disputes > 0 {
+            return 0;
+        }
+        let trust = self.trust_score(&transaction.user_id, counterparty, transaction.timestamp);
+        let discount = self.policy.max_discount.min(100) as f64 * trust as f64 / 100.0;
+        -(discount.round() as i8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::TransactionValidator;
+    use chrono::Duration;
+
+    #[test]
+    fn test_trust_grows_with_history_and_drops_on_dispute() {
+        let mut trust = CounterpartyTrust::new();
+        let now = create_test_transaction("TXN-CPT-0", 1200.0);
+        assert_eq!(trust.risk_adjustment(&now), 10);
+
+        // Monthly rent for six months
+        for month in 0..6 {
+            let mut rent = create_test_transaction(&format!("TXN-CPT-{}", month + 1), 1200.0);
+            rent.timestamp -= Duration::days(30 * (6 - month));
+            trust.record(&rent);
+        }
+        let stats = trust
+            .relationship("USER-001", "ACCT-6789-0123-4567")
+            .unwrap();
+        assert_eq!(stats.interactions, 6);
+        assert_eq!(stats.average_amount(), 1200.0);
+        assert_eq!(
+            trust.trust_score("USER-001", "ACCT-6789-0123-4567", now.timestamp),
+            100
+        );
+        assert_eq!(trust.risk_adjustment(&now), -15);
+
+        // An unusually large payment earns no discount
+        assert_eq!(
+            trust.risk_adjustment(&create_test_transaction("TXN-CPT-7", 9000.0)),
+            0
+        );
+
+        assert!(trust.record_dispute("USER-001", "ACCT-6789-0123-4567"));
+        assert!(!trust.record_dispute("USER-001", "ACCT-0000-0000-0000"));
+        assert_eq!(
+            trust.trust_score("USER-001", "ACCT-6789-0123-4567", now.timestamp),
+            60
+        );
+        assert_eq!(trust.risk_adjustment(&now), 0);
+    }
+
+    #[test]
+    fn test_validator_applies_trust_offset() {
+        let mut validator = TransactionValidator::builder()
+            .with_counterparty_trust(CounterpartyTrust::new())
+            .build();
+
+        let first = validator.validate(&create_test_transaction("TXN-CPT-10", 500.0));
+        assert_eq!(first.risk_breakdown.trust_adjustment, 10);
+
+        let trust = validator.counterparty_trust().unwrap();
+        assert_eq!(
+            trust
+                .relationship("USER-001", "ACCT-6789-0123-4567")
+                .unwrap()
+                .interactions,
+            1
+        );
+    }
+}
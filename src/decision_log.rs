@@ -0,0 +1,153 @@
+//! Structured decision logging
+//!
+//! Emits one machine-readable record per validation for ingestion into
+//! SIEM/ELK pipelines. Records carry hashes rather than raw identifiers and
+//! are independent of the audit trail.
+
+use crate::{hash_identifier, Decision, RiskBreakdown, Transaction, ValidationResult};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{self, Write};
+
+/// One structured record per validation decision
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionRecord {
+    pub transaction_id: String,
+    /// SHA-256 of the serialized transaction
+    pub inputs_hash: String,
+    pub user_id_hash: String,
+    pub fraud_score: u8,
+    pub risk_breakdown: RiskBreakdown,
+    pub reason_codes: Vec<String>,
+    pub warning_count: usize,
+    pub decision: Decision,
+    pub config_version: String,
+    pub logged_at: DateTime<Utc>,
+}
+
+impl DecisionRecord {
+    /// Build a record from a transaction and its validation result
+    pub fn new(transaction: &Transaction, result: &ValidationResult, config_version: &str) -> Self {
+        let encoded = serde_json::to_vec(transaction).unwrap_or_default();
+        let digest = Sha256::digest(&encoded);
+
+        Self {
+            transaction_id: result.transaction_id.clone(),
+            inputs_hash: digest.iter().map(|b| format!("{:02x}", b)).collect(),
+            user_id_hash: hash_identifier(&transaction.user_id),
+            fraud_score: result.fraud_score,
+            risk_breakdown: result.risk_breakdown.clone(),
+            reason_codes: result.reason_codes(),
+            warning_count: result.warnings.len(),
+            decision: result.decision,
+            config_version: config_version.to_string(),
+            logged_at: result.validated_at,
+        }
+    }
+}
+
+/// Sink for structured decision records
+pub trait DecisionLogger {
+    /// Write a single decision record
+    fn log_decision(&mut self, record: &DecisionRecord) -> io::Result<()>;
+}
+
+/// Decision logger writing one JSON object per line
+pub struct JsonLinesDecisionLogger<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> JsonLinesDecisionLogger<W> {
+    /// Create a logger over any writer (file, socket, buffer)
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Consume the logger and return the underlying writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> DecisionLogger for JsonLinesDecisionLogger<W> {
+    fn log_decision(&mut self, record: &DecisionRecord) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, record)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+    use crate::TransactionValidator;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::other("sink unavailable"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn create_test_transaction(id: &str) -> Transaction {
+        test_fixtures::create_test_transaction(id, 1000.0)
+    }
+
+    #[test]
+    fn test_one_record_per_validation() {
+        let buffer = SharedBuffer::default();
+        let mut validator = TransactionValidator::new();
+        validator.set_decision_logger(Box::new(JsonLinesDecisionLogger::new(buffer.clone())));
+
+        let transaction = create_test_transaction("TXN-LOG-001");
+        validator.validate(&transaction);
+        validator.validate(&transaction); // duplicate
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let records: Vec<DecisionRecord> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].decision, Decision::Approve);
+        assert_eq!(records[1].decision, Decision::Decline);
+        assert!(records[1]
+            .reason_codes
+            .contains(&"DUPLICATE_TRANSACTION".to_string()));
+        assert_eq!(records[0].inputs_hash, records[1].inputs_hash);
+        assert!(!records[0].user_id_hash.contains("USER-001"));
+        assert!(!output.contains("USER-001"));
+    }
+
+    #[test]
+    fn test_logger_failure_does_not_affect_decision() {
+        let mut validator = TransactionValidator::new();
+        validator.set_decision_logger(Box::new(JsonLinesDecisionLogger::new(FailingWriter)));
+
+        let result = validator.validate(&create_test_transaction("TXN-LOG-002"));
+        assert_eq!(result.decision, Decision::Approve);
+        assert_eq!(validator.get_stats().decision_log_failures, 1);
+    }
+}
@@ -0,0 +1,186 @@
+//! Shared duplicate detection state
+//!
+//! The duplicate check remembers every committed transaction ID and, with
+//! `fingerprint_bucket_seconds` set, every content fingerprint. A
+//! [`DedupStore`] holds both; validators that hold clones of one
+//! [`SharedDedup`] handle reject a replay no matter which of them saw the
+//! original.
+//!
+//! Fingerprints remember the timestamp of the transaction that claimed
+//! them and are pruned with history by
+//! [`TransactionValidator::clear_old_history`](crate::TransactionValidator::clear_old_history);
+//! transaction IDs are kept for good.
+//!
+//! The duplicate check reserves a transaction's ID and fingerprint under the
+//! store's write lock, so two validators seeing the same ID at the same
+//! moment cannot both pass. A reservation becomes a record when the
+//! validation commits and is released when it does not.
+
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Committed transaction IDs and content fingerprints
+#[derive(Debug, Clone, Default)]
+pub struct DedupStore {
+    transaction_ids: HashSet<String>,
+    /// Content fingerprint -> first transaction ID seen with it and its timestamp
+    fingerprints: HashMap<String, (String, DateTime<Utc>)>,
+    /// IDs held by validations that have not committed yet
+    reserved_ids: HashSet<String>,
+    /// Fingerprints held the same way -> reserving transaction ID
+    reserved_fingerprints: HashMap<String, String>,
+}
+
+impl DedupStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn contains(&self, transaction_id: &str) -> bool {
+        self.transaction_ids.contains(transaction_id)
+    }
+
+    /// ID of the first transaction committed with this fingerprint
+    pub fn original(&self, fingerprint: &str) -> Option<&str> {
+        self.fingerprints
+            .get(fingerprint)
+            .map(|(id, _)| id.as_str())
+    }
+
+    /// Committed fingerprints
+    pub fn fingerprint_count(&self) -> usize {
+        self.fingerprints.len()
+    }
+
+    pub fn record_id(&mut self, transaction_id: &str) {
+        self.reserved_ids.remove(transaction_id);
+        self.transaction_ids.insert(transaction_id.to_string());
+    }
+
+    /// Remember a fingerprint unless an earlier transaction already claimed it
+    pub fn record_fingerprint(
+        &mut self,
+        fingerprint: String,
+        transaction_id: &str,
+        timestamp: DateTime<Utc>,
+    ) {
+        if self
+            .reserved_fingerprints
+            .get(&fingerprint)
+            .map(String::as_str)
+            == Some(transaction_id)
+        {
+            self.reserved_fingerprints.remove(&fingerprint);
+        }
+        self.fingerprints
+            .entry(fingerprint)
+            .or_insert_with(|| (transaction_id.to_string(), timestamp));
+    }
+
+    /// Forget fingerprints of transactions timestamped before `cutoff`
+    pub fn prune_fingerprints_before(&mut self, cutoff: DateTime<Utc>) {
+        self.fingerprints
+            .retain(|_, (_, timestamp)| *timestamp >= cutoff);
+    }
+
+    /// Whether an uncommitted validation holds this ID
+    pub fn is_reserved(&self, transaction_id: &str) -> bool {
+        self.reserved_ids.contains(transaction_id)
+    }
+
+    /// Hold an ID for a validation; false if it is committed or already held
+    pub fn reserve_id(&mut self, transaction_id: &str) -> bool {
+        !self.contains(transaction_id) && self.reserved_ids.insert(transaction_id.to_string())
+    }
+
+    /// Hold a fingerprint, or return the ID that committed or holds it or
+    /// one of its `neighbours`
+    pub fn reserve_fingerprint(
+        &mut self,
+        fingerprint: &str,
+        neighbours: &[String],
+        transaction_id: &str,
+    ) -> Result<(), String> {
+        for candidate in std::iter::once(fingerprint).chain(neighbours.iter().map(String::as_str)) {
+            if let Some(original) = self
+                .fingerprints
+                .get(candidate)
+                .map(|(id, _)| id)
+                .or_else(|| self.reserved_fingerprints.get(candidate))
+            {
+                return Err(original.clone());
+            }
+        }
+        self.reserved_fingerprints
+            .insert(fingerprint.to_string(), transaction_id.to_string());
+        Ok(())
+    }
+
+    /// Give up the reservations of a validation that did not commit
+    pub fn release(&mut self, transaction_id: &str, fingerprint: Option<&str>) {
+        self.reserved_ids.remove(transaction_id);
+        if let Some(fingerprint) = fingerprint {
+            if self
+                .reserved_fingerprints
+                .get(fingerprint)
+                .map(String::as_str)
+                == Some(transaction_id)
+            {
+                self.reserved_fingerprints.remove(fingerprint);
+            }
+        }
+    }
+
+    /// Committed transaction IDs
+    pub fn len(&self) -> usize {
+        self.transaction_ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transaction_ids.is_empty()
+    }
+
+    /// Approximate heap and inline bytes held by IDs and fingerprints
+    pub fn estimated_memory_bytes(&self) -> usize {
+        let string = std::mem::size_of::<String>();
+        let ids: usize = self
+            .transaction_ids
+            .iter()
+            .map(|id| string + id.capacity())
+            .sum();
+        let fingerprints: usize = self
+            .fingerprints
+            .iter()
+            .map(|(fp, (id, _))| {
+                2 * string + std::mem::size_of::<DateTime<Utc>>() + fp.capacity() + id.capacity()
+            })
+            .sum();
+        ids + fingerprints
+    }
+}
+
+/// Cloneable handle to a [`DedupStore`] shared between validators
+#[derive(Debug, Clone, Default)]
+pub struct SharedDedup(Arc<RwLock<DedupStore>>);
+
+impl SharedDedup {
+    pub fn new(store: DedupStore) -> Self {
+        Self(Arc::new(RwLock::new(store)))
+    }
+
+    /// Lock the store for reading, ignoring poisoning as `SharedHistory` does
+    pub fn read(&self) -> RwLockReadGuard<'_, DedupStore> {
+        self.0.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Lock the store for writing
+    pub fn write(&self) -> RwLockWriteGuard<'_, DedupStore> {
+        self.0.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Check whether both handles refer to the same store
+    pub fn ptr_eq(&self, other: &SharedDedup) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
@@ -0,0 +1,140 @@
+//! Degraded-mode reporting
+//!
+//! A validation that ran without some of its reference data still returns a
+//! decision, but the decision rests on less than usual. Each missing input
+//! is recorded in [`ValidationResult::degraded`](crate::ValidationResult::degraded)
+//! as a [`DegradationReason`]. With `ValidatorConfig::review_when_degraded`
+//! set, a degraded validation that would have been approved goes to
+//! review instead.
+//!
+//! Screening backends and enrichment providers report their own state
+//! through `ScreeningBackend::degradations` and
+//! `EnrichmentProvider::degradations`; the resilience wrappers report an
+//! open circuit there even when a fallback answered the call.
+
+use crate::SanctionsList;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Why a validation ran without some of its reference data
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DegradationReason {
+    /// The list's last refresh failed; its previous version was screened
+    SanctionsRefreshFailed { list: SanctionsList, error: String },
+    /// The provider's circuit breaker is open
+    CircuitOpen { provider: String },
+    /// The provider failed and its data is missing
+    ProviderFailed { provider: String },
+    /// Countries were present but the geographic risk dataset is empty
+    GeoDataMissing,
+}
+
+impl DegradationReason {
+    /// Stable machine-readable code
+    pub fn code(&self) -> &'static str {
+        match self {
+            DegradationReason::SanctionsRefreshFailed { .. } => "SANCTIONS_REFRESH_FAILED",
+            DegradationReason::CircuitOpen { .. } => "CIRCUIT_OPEN",
+            DegradationReason::ProviderFailed { .. } => "PROVIDER_FAILED",
+            DegradationReason::GeoDataMissing => "GEO_DATA_MISSING",
+        }
+    }
+}
+
+impl fmt::Display for DegradationReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DegradationReason::SanctionsRefreshFailed { list, error } => {
+                write!(f, "{} list refresh failed: {}", list.name(), error)
+            }
+            DegradationReason::CircuitOpen { provider } => {
+                write!(f, "Circuit open for provider {}", provider)
+            }
+            DegradationReason::ProviderFailed { provider } => {
+                write!(f, "Provider {} failed", provider)
+            }
+            DegradationReason::GeoDataMissing => write!(f, "Geographic risk data not loaded"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{
+        Decision, EnrichmentContext, EnrichmentError, EnrichmentProvider, GeographicRiskScorer,
+        ResiliencePolicy, ResilientEnrichment, RetryPolicy, SanctionsScreener, Transaction,
+        TransactionMetadata, TransactionValidator, ValidatorConfig,
+    };
+
+    #[test]
+    fn test_stale_sanctions_list_forces_review() {
+        let mut screener = SanctionsScreener::new();
+        screener.record_refresh_failure(SanctionsList::OFAC, "HTTP 503");
+        let mut validator = TransactionValidator::with_config(ValidatorConfig {
+            review_when_degraded: true,
+            ..Default::default()
+        });
+        validator.set_sanctions_screener(screener);
+
+        let result = validator.validate(&create_test_transaction("TXN-DEG-1", 100.0));
+        assert!(result.is_valid);
+        assert_eq!(result.degraded.len(), 1);
+        assert_eq!(result.degraded[0].code(), "SANCTIONS_REFRESH_FAILED");
+        assert_eq!(result.decision, Decision::Review);
+
+        // A successful refresh clears the failure
+        let mut screener = SanctionsScreener::new();
+        screener.record_refresh_failure(SanctionsList::OFAC, "HTTP 503");
+        screener.set_list_source(SanctionsList::OFAC, Default::default());
+        assert!(screener.refresh_failures().is_empty());
+    }
+
+    #[test]
+    fn test_open_circuit_and_missing_geo_data_are_reported() {
+        struct Down;
+
+        impl EnrichmentProvider for Down {
+            fn name(&self) -> &str {
+                "crm"
+            }
+
+            fn enrich(&self, _: &Transaction) -> Result<EnrichmentContext, EnrichmentError> {
+                Err(EnrichmentError::Unavailable("down".to_string()))
+            }
+        }
+
+        let provider = ResilientEnrichment::new(Down)
+            .with_policy(ResiliencePolicy {
+                retry: RetryPolicy {
+                    max_attempts: 1,
+                    ..Default::default()
+                },
+                failure_threshold: 1,
+                ..Default::default()
+            })
+            .with_fallback(EnrichmentContext::default());
+        let mut validator = TransactionValidator::new();
+        validator.add_enrichment_provider(provider);
+        validator.set_geographic_scorer(GeographicRiskScorer::empty());
+
+        let mut tx = create_test_transaction("TXN-DEG-2", 100.0);
+        tx.metadata = Some(TransactionMetadata::from([(
+            "country".to_string(),
+            "US".to_string(),
+        )]));
+        let result = validator.validate(&tx);
+        assert_eq!(
+            result.degraded,
+            vec![
+                DegradationReason::CircuitOpen {
+                    provider: "crm".to_string()
+                },
+                DegradationReason::GeoDataMissing,
+            ]
+        );
+        // Without review_when_degraded the decision is unchanged
+        assert_eq!(result.decision, Decision::Approve);
+    }
+}
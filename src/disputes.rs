@@ -0,0 +1,182 @@
+//! Chargeback and dispute history
+//!
+//! Confirmed disputes are among the strongest fraud signals available, but
+//! they live in the card processor or case-management system rather than on
+//! the transaction. A [`DisputeHistoryProvider`] looks up history for the
+//! user and the receiving account; wrapped in [`DisputeEnrichment`] it runs
+//! as an ordinary enrichment provider and fills the dispute fields of the
+//! [`EnrichmentContext`](crate::EnrichmentContext). The validator's dispute
+//! check then escalates risk for recent confirmed disputes when
+//! `ValidatorConfig::dispute_risk` is set.
+
+use crate::{EnrichmentContext, EnrichmentError, EnrichmentProvider, Transaction};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Prior chargebacks and disputes for a user or counterparty
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DisputeHistory {
+    /// Lifetime chargebacks, confirmed or not
+    pub chargebacks: u32,
+    /// When each confirmed dispute was resolved against the party
+    pub confirmed: Vec<DateTime<Utc>>,
+}
+
+impl DisputeHistory {
+    /// Confirmed disputes in the `days` before `at`
+    pub fn recent_confirmed(&self, at: DateTime<Utc>, days: i64) -> usize {
+        let since = Duration::try_days(days)
+            .and_then(|d| at.checked_sub_signed(d))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        self.confirmed
+            .iter()
+            .filter(|t| **t >= since && **t <= at)
+            .count()
+    }
+}
+
+/// Source of dispute history for users and receiving accounts
+pub trait DisputeHistoryProvider: Send + Sync {
+    /// Provider name used in warnings
+    fn name(&self) -> &str;
+
+    /// History of disputes raised against the user
+    fn user_history(&self, user_id: &str) -> Result<Option<DisputeHistory>, EnrichmentError>;
+
+    /// History of disputes involving the receiving account
+    fn counterparty_history(
+        &self,
+        account: &str,
+    ) -> Result<Option<DisputeHistory>, EnrichmentError>;
+}
+
+/// Runs a [`DisputeHistoryProvider`] as an enrichment provider
+pub struct DisputeEnrichment<P>(pub P);
+
+impl<P: DisputeHistoryProvider> EnrichmentProvider for DisputeEnrichment<P> {
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    fn enrich(&self, transaction: &Transaction) -> Result<EnrichmentContext, EnrichmentError> {
+        let user_disputes = self.0.user_history(&transaction.user_id)?;
+        let counterparty_disputes = match transaction.to_account {
+            Some(ref account) => self.0.counterparty_history(account)?,
+            None => None,
+        };
+        Ok(EnrichmentContext {
+            prior_chargebacks: user_disputes.as_ref().map(|h| h.chargebacks),
+            user_disputes,
+            counterparty_disputes,
+            ..Default::default()
+        })
+    }
+}
+
+/// In-memory dispute history, keyed by user ID and account
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DisputeLedger {
+    users: HashMap<String, DisputeHistory>,
+    counterparties: HashMap<String, DisputeHistory>,
+}
+
+impl DisputeLedger {
+    /// Empty ledger
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a chargeback against a user, confirmed at `confirmed_at` if set
+    pub fn record_user_chargeback(&mut self, user_id: &str, confirmed_at: Option<DateTime<Utc>>) {
+        Self::record(
+            self.users.entry(user_id.to_string()).or_default(),
+            confirmed_at,
+        );
+    }
+
+    /// Record a chargeback involving a receiving account
+    pub fn record_counterparty_chargeback(
+        &mut self,
+        account: &str,
+        confirmed_at: Option<DateTime<Utc>>,
+    ) {
+        Self::record(
+            self.counterparties.entry(account.to_string()).or_default(),
+            confirmed_at,
+        );
+    }
+
+    fn record(history: &mut DisputeHistory, confirmed_at: Option<DateTime<Utc>>) {
+        history.chargebacks += 1;
+        history.confirmed.extend(confirmed_at);
+    }
+}
+
+impl DisputeHistoryProvider for DisputeLedger {
+    fn name(&self) -> &str {
+        "dispute-ledger"
+    }
+
+    fn user_history(&self, user_id: &str) -> Result<Option<DisputeHistory>, EnrichmentError> {
+        Ok(self.users.get(user_id).cloned())
+    }
+
+    fn counterparty_history(
+        &self,
+        account: &str,
+    ) -> Result<Option<DisputeHistory>, EnrichmentError> {
+        Ok(self.counterparties.get(account).cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{DisputeRisk, TransactionValidator, ValidatorConfig};
+
+    fn validator_with(ledger: DisputeLedger) -> TransactionValidator {
+        let mut validator = TransactionValidator::with_config(ValidatorConfig {
+            dispute_risk: Some(DisputeRisk::default()),
+            ..Default::default()
+        });
+        validator.add_enrichment_provider(DisputeEnrichment(ledger));
+        validator
+    }
+
+    #[test]
+    fn test_recent_confirmed_disputes_escalate_risk() {
+        let tx = create_test_transaction("TXN-DSP-1", 250.0);
+        let mut ledger = DisputeLedger::new();
+        ledger.record_user_chargeback("USER-001", Some(tx.timestamp - Duration::days(10)));
+        ledger.record_user_chargeback("USER-001", Some(tx.timestamp - Duration::days(400)));
+        ledger.record_counterparty_chargeback("ACCT-6789-0123-4567", Some(tx.timestamp));
+
+        let result = validator_with(ledger).validate(&tx);
+        assert!(result
+            .warning_codes()
+            .contains(&"RECENT_DISPUTES".to_string()));
+        // One recent user dispute plus one recent counterparty dispute
+        assert!(result.risk_breakdown.pattern_risk >= 70);
+    }
+
+    #[test]
+    fn test_old_or_unconfirmed_chargebacks_are_mild() {
+        let tx = create_test_transaction("TXN-DSP-2", 250.0);
+        let mut ledger = DisputeLedger::new();
+        for _ in 0..3 {
+            ledger.record_user_chargeback("USER-001", None);
+        }
+        ledger.record_user_chargeback("USER-001", Some(tx.timestamp - Duration::days(400)));
+
+        let result = validator_with(ledger).validate(&tx);
+        let codes = result.warning_codes();
+        assert!(codes.contains(&"CHARGEBACK_HISTORY".to_string()));
+        assert!(!codes.contains(&"RECENT_DISPUTES".to_string()));
+        assert!(result.is_valid);
+
+        let clean = validator_with(DisputeLedger::new()).validate(&tx);
+        assert_eq!(clean.risk_breakdown.pattern_risk, 0);
+    }
+}
@@ -0,0 +1,332 @@
+//! External enrichment providers
+//!
+//! Providers look up facts the transaction itself does not carry (account
+//! age, balance, customer tier, prior chargebacks) and return them as an
+//! [`EnrichmentContext`] that checks read during validation.
+
+use crate::{DegradationReason, DisputeHistory, Transaction};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use thiserror::Error;
+
+/// Enrichment lookup errors
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EnrichmentError {
+    #[error("Provider unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("Record not found: {0}")]
+    NotFound(String),
+
+    #[error("Provider timed out")]
+    Timeout,
+}
+
+impl EnrichmentError {
+    /// Whether retrying the same lookup may succeed
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            EnrichmentError::Unavailable(_) | EnrichmentError::Timeout
+        )
+    }
+}
+
+/// Metadata key holding the source account's opening date (RFC 3339 or YYYY-MM-DD)
+pub const ACCOUNT_OPENED_METADATA_KEY: &str = "account_opened_at";
+
+/// Typed facts about the account and customer behind a transaction
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EnrichmentContext {
+    /// Days since the source account was opened
+    pub account_age_days: Option<u32>,
+    /// When the source account was opened, if the provider reports a date
+    #[serde(default)]
+    pub account_opened_at: Option<DateTime<Utc>>,
+    /// Available balance on the source account
+    pub account_balance: Option<f64>,
+    /// Customer tier or segment name
+    pub customer_tier: Option<String>,
+    /// Prior chargebacks or disputes for the user
+    pub prior_chargebacks: Option<u32>,
+    /// Dispute history of the user
+    #[serde(default)]
+    pub user_disputes: Option<DisputeHistory>,
+    /// Dispute history of the receiving account
+    #[serde(default)]
+    pub counterparty_disputes: Option<DisputeHistory>,
+    /// Name of the user's limit profile
+    pub limit_profile: Option<String>,
+    /// Provider-specific attributes
+    pub custom: HashMap<String, String>,
+}
+
+impl EnrichmentContext {
+    /// Fill fields that are still unset from `other`
+    ///
+    /// Values already present win, so earlier providers take precedence.
+    pub fn merge(&mut self, other: EnrichmentContext) {
+        if self.account_age_days.is_none() {
+            self.account_age_days = other.account_age_days;
+        }
+        if self.account_opened_at.is_none() {
+            self.account_opened_at = other.account_opened_at;
+        }
+        if self.account_balance.is_none() {
+            self.account_balance = other.account_balance;
+        }
+        if self.customer_tier.is_none() {
+            self.customer_tier = other.customer_tier;
+        }
+        if self.prior_chargebacks.is_none() {
+            self.prior_chargebacks = other.prior_chargebacks;
+        }
+        if self.user_disputes.is_none() {
+            self.user_disputes = other.user_disputes;
+        }
+        if self.counterparty_disputes.is_none() {
+            self.counterparty_disputes = other.counterparty_disputes;
+        }
+        if self.limit_profile.is_none() {
+            self.limit_profile = other.limit_profile;
+        }
+        for (key, value) in other.custom {
+            self.custom.entry(key).or_insert(value);
+        }
+    }
+
+    /// Age of the source account in days when the transaction was made
+    ///
+    /// Uses `account_age_days`, then `account_opened_at`, then the
+    /// [`ACCOUNT_OPENED_METADATA_KEY`] metadata entry.
+    pub fn account_age_at(&self, transaction: &Transaction) -> Option<i64> {
+        if let Some(days) = self.account_age_days {
+            return Some(days as i64);
+        }
+        let opened = self.account_opened_at.or_else(|| {
+            let raw = transaction
+                .metadata
+                .as_ref()?
+                .get(ACCOUNT_OPENED_METADATA_KEY)?
+                .trim();
+            DateTime::parse_from_rfc3339(raw)
+                .map(|d| d.with_timezone(&Utc))
+                .ok()
+                .or_else(|| {
+                    NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+                        .ok()
+                        .and_then(|d| d.and_hms_opt(0, 0, 0))
+                        .map(|d| d.and_utc())
+                })
+        })?;
+        Some((transaction.timestamp - opened).num_days().max(0))
+    }
+
+    /// Check whether no field is set
+    pub fn is_empty(&self) -> bool {
+        self == &EnrichmentContext::default()
+    }
+}
+
+/// Synchronous enrichment lookup, called from `validate()`
+pub trait EnrichmentProvider: Send + Sync {
+    /// Provider name used in warnings
+    fn name(&self) -> &str;
+
+    /// Look up enrichment data for the transaction's user and accounts
+    fn enrich(&self, transaction: &Transaction) -> Result<EnrichmentContext, EnrichmentError>;
+
+    /// Reference data the provider is currently answering without
+    fn degradations(&self) -> Vec<DegradationReason> {
+        Vec::new()
+    }
+}
+
+/// Asynchronous enrichment lookup for remote services
+///
+/// Await the lookup in the caller's runtime, then pass the context to
+/// `TransactionValidator::validate_with_context`.
+pub trait AsyncEnrichmentProvider: Send + Sync {
+    /// Provider name used in warnings
+    fn name(&self) -> &str;
+
+    /// Look up enrichment data for the transaction's user and accounts
+    fn enrich(
+        &self,
+        transaction: &Transaction,
+    ) -> impl Future<Output = Result<EnrichmentContext, EnrichmentError>> + Send;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+    use crate::{TransactionMetadata, TransactionValidator};
+
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+
+    struct StaticProvider(EnrichmentContext);
+
+    impl EnrichmentProvider for StaticProvider {
+        fn name(&self) -> &str {
+            "static"
+        }
+
+        fn enrich(&self, _transaction: &Transaction) -> Result<EnrichmentContext, EnrichmentError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    impl AsyncEnrichmentProvider for StaticProvider {
+        fn name(&self) -> &str {
+            "static"
+        }
+
+        async fn enrich(
+            &self,
+            _transaction: &Transaction,
+        ) -> Result<EnrichmentContext, EnrichmentError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    struct DownProvider;
+
+    impl EnrichmentProvider for DownProvider {
+        fn name(&self) -> &str {
+            "core-banking"
+        }
+
+        fn enrich(&self, _transaction: &Transaction) -> Result<EnrichmentContext, EnrichmentError> {
+            Err(EnrichmentError::Timeout)
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    fn create_test_transaction(amount: f64) -> Transaction {
+        test_fixtures::create_test_transaction("TXN-ENRICH-001", amount)
+    }
+
+    #[test]
+    fn test_merge_keeps_existing_values() {
+        let mut context = EnrichmentContext {
+            account_balance: Some(100.0),
+            ..Default::default()
+        };
+        context.merge(EnrichmentContext {
+            account_balance: Some(999.0),
+            account_age_days: Some(30),
+            ..Default::default()
+        });
+
+        assert_eq!(context.account_balance, Some(100.0));
+        assert_eq!(context.account_age_days, Some(30));
+    }
+
+    #[test]
+    fn test_account_age_from_context_or_metadata() {
+        let mut transaction = create_test_transaction(100.0);
+        let opened = transaction.timestamp - chrono::Duration::days(12);
+        let context = EnrichmentContext {
+            account_opened_at: Some(opened),
+            ..Default::default()
+        };
+        assert_eq!(context.account_age_at(&transaction), Some(12));
+        assert_eq!(
+            EnrichmentContext::default().account_age_at(&transaction),
+            None
+        );
+
+        transaction.metadata = Some(TransactionMetadata::from([(
+            ACCOUNT_OPENED_METADATA_KEY.to_string(),
+            opened.date_naive().format("%Y-%m-%d").to_string(),
+        )]));
+        assert_eq!(
+            EnrichmentContext::default().account_age_at(&transaction),
+            Some(12)
+        );
+    }
+
+    #[test]
+    fn test_provider_balance_used_by_checks() {
+        let mut validator = TransactionValidator::new();
+        validator.add_enrichment_provider(StaticProvider(EnrichmentContext {
+            account_balance: Some(500.0),
+            ..Default::default()
+        }));
+
+        let result = validator.validate(&create_test_transaction(1000.0));
+        assert!(result
+            .warnings
+            .iter()
+            .any(|w| w.message.contains("exceeds available balance")));
+        assert!(result.risk_breakdown.pattern_risk > 0);
+    }
+
+    #[test]
+    fn test_new_account_risk_scores_large_activity() {
+        let mut validator = TransactionValidator::with_config(crate::ValidatorConfig {
+            new_account_risk: Some(crate::NewAccountRisk::default()),
+            ..Default::default()
+        });
+        validator.add_enrichment_provider(StaticProvider(EnrichmentContext {
+            account_age_days: Some(3),
+            ..Default::default()
+        }));
+
+        let small = validator.validate(&create_test_transaction(100.0));
+        assert!(!small
+            .warning_codes()
+            .contains(&"NEW_ACCOUNT_ACTIVITY".to_string()));
+
+        let mut large = create_test_transaction(8000.0);
+        large.transaction_id = "TXN-ENR-LARGE".to_string();
+        let result = validator.validate(&large);
+        assert!(result
+            .warning_codes()
+            .contains(&"NEW_ACCOUNT_ACTIVITY".to_string()));
+        assert!(result.risk_breakdown.pattern_risk >= 25);
+    }
+
+    #[test]
+    fn test_provider_failure_becomes_warning() {
+        let mut validator = TransactionValidator::new();
+        validator.add_enrichment_provider(DownProvider);
+
+        let result = validator.validate(&create_test_transaction(100.0));
+        assert!(result.is_valid);
+        assert!(result
+            .warnings
+            .iter()
+            .any(|w| w.message.contains("core-banking")));
+    }
+
+    #[test]
+    fn test_async_provider_context() {
+        let provider = StaticProvider(EnrichmentContext {
+            account_balance: Some(50.0),
+            ..Default::default()
+        });
+        let transaction = create_test_transaction(100.0);
+        let context = block_on(AsyncEnrichmentProvider::enrich(&provider, &transaction)).unwrap();
+
+        let mut validator = TransactionValidator::new();
+        let result = validator.validate_with_context(&transaction, context);
+        assert!(result
+            .warnings
+            .iter()
+            .any(|w| w.message.contains("exceeds available balance")));
+    }
+}
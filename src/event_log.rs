@@ -0,0 +1,277 @@
+//! Write-ahead event log and crash recovery
+//!
+//! Dedup sets, velocity history, the transaction graph, and counterparty
+//! trust live in memory, so a restarted validator would wave through a
+//! replay of every transaction it saw before the crash. With an
+//! [`EventLog`] attached, the validator appends one [`ValidationEvent`] per
+//! validation *before* committing the transaction into that state; after a
+//! restart, [`TransactionValidator::replay_events`](crate::TransactionValidator::replay_events)
+//! re-applies the committed events without re-running any check.
+//!
+//! The legs of a transaction group are all appended before any of them
+//! commits, each tagged with a [`GroupMembership`]; replay applies a group
+//! only once every one of its legs is in the log.
+//!
+//! Events carry the full transaction, since history and graph state cannot
+//! be rebuilt from hashes. Store the log with the same care as the
+//! transactions themselves.
+
+use crate::checks::PendingState;
+use crate::{Decision, ParseError, ParseLimits, ReportKind, Transaction, ValidationResult};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+
+/// State a validation committed, re-applied verbatim on replay
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedState {
+    /// Transaction ID joined the dedup set
+    pub transaction_id: bool,
+    /// Content fingerprint joined the replay set
+    pub fingerprint: Option<String>,
+    /// Transaction joined velocity history
+    pub history: bool,
+    /// Transaction joined the fraud detector's user profiles
+    #[serde(default)]
+    pub profiles: bool,
+    /// Reports enqueued for the transaction
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filings: Vec<ReportKind>,
+}
+
+impl From<&PendingState> for RecordedState {
+    fn from(pending: &PendingState) -> Self {
+        Self {
+            transaction_id: pending.transaction_id,
+            fingerprint: pending.fingerprint.clone(),
+            history: pending.history,
+            profiles: pending.profiles,
+            filings: pending.filings.clone(),
+        }
+    }
+}
+
+impl From<RecordedState> for PendingState {
+    fn from(recorded: RecordedState) -> Self {
+        Self {
+            transaction_id: recorded.transaction_id,
+            fingerprint: recorded.fingerprint,
+            history: recorded.history,
+            profiles: recorded.profiles,
+            filings: recorded.filings,
+            // Follow-ups are queued for the live validator only
+            follow_up: None,
+        }
+    }
+}
+
+/// One validation, as written to the event log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationEvent {
+    /// Position in the log, starting at 1
+    pub sequence: u64,
+    pub transaction: Transaction,
+    pub decision: Decision,
+    pub reason_codes: Vec<String>,
+    /// Whether the validation committed state
+    pub committed: bool,
+    pub recorded: RecordedState,
+    pub logged_at: DateTime<Utc>,
+    /// Group the transaction was validated in, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<GroupMembership>,
+}
+
+/// Marks an event as one leg of a transaction group
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupMembership {
+    pub group_id: String,
+    /// Number of legs in the group
+    pub legs: usize,
+}
+
+impl ValidationEvent {
+    pub(crate) fn new(
+        sequence: u64,
+        transaction: &Transaction,
+        result: &ValidationResult,
+        committed: bool,
+        pending: &PendingState,
+    ) -> Self {
+        Self {
+            sequence,
+            transaction: transaction.clone(),
+            decision: result.decision,
+            reason_codes: result.reason_codes(),
+            committed,
+            recorded: if committed {
+                pending.into()
+            } else {
+                RecordedState::default()
+            },
+            logged_at: result.validated_at,
+            group: None,
+        }
+    }
+
+    pub(crate) fn in_group(mut self, group: &GroupMembership) -> Self {
+        self.group = Some(group.clone());
+        self
+    }
+}
+
+/// Durable sink for validation events
+pub trait EventLog {
+    /// Append one event; return only once it is durable
+    fn append(&mut self, event: &ValidationEvent) -> io::Result<()>;
+}
+
+/// Event log writing one JSON object per line
+pub struct JsonLinesEventLog<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> JsonLinesEventLog<W> {
+    /// Create a log over any writer; files should be opened in append mode
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Consume the log and return the underlying writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> EventLog for JsonLinesEventLog<W> {
+    fn append(&mut self, event: &ValidationEvent) -> io::Result<()> {
+        let mut line = serde_json::to_vec(event)?;
+        line.push(b'\n');
+        self.writer.write_all(&line)?;
+        self.writer.flush()
+    }
+}
+
+/// Read a JSON-lines event log
+///
+/// A malformed final line without a trailing newline is a write torn by
+/// the crash and is ignored; malformed lines anywhere else are errors.
+pub fn read_event_log(
+    input: &[u8],
+    limits: &ParseLimits,
+) -> Result<Vec<ValidationEvent>, ParseError> {
+    limits.check_input_size(input.len())?;
+
+    let lines: Vec<&[u8]> = input.split(|b| *b == b'\n').collect();
+    let mut events = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let line_number = index + 1;
+        limits.check_line(line_number, line.len())?;
+        if line.iter().all(|b| b.is_ascii_whitespace()) {
+            continue;
+        }
+
+        limits.check_record_count(events.len() + 1)?;
+        let text =
+            std::str::from_utf8(line).map_err(|e| ParseError::InvalidEncoding(e.to_string()))?;
+        match serde_json::from_str::<ValidationEvent>(text) {
+            Ok(event) => events.push(event),
+            Err(_) if index == lines.len() - 1 => break,
+            Err(e) => {
+                return Err(ParseError::Malformed {
+                    line: line_number,
+                    message: e.to_string(),
+                })
+            }
+        }
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{TransactionValidator, ValidationError};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_replay_restores_dedup_after_crash() {
+        let buffer = SharedBuffer::default();
+        let mut validator = TransactionValidator::new();
+        validator.set_event_log(Box::new(JsonLinesEventLog::new(buffer.clone())));
+        validator.validate(&create_test_transaction("TXN-WAL-1", 100.0));
+        validator.validate(&create_test_transaction("TXN-WAL-2", -5.0));
+        validator.validate_dry_run(&create_test_transaction("TXN-WAL-3", 100.0));
+        drop(validator);
+
+        // Simulate a write torn by the crash
+        let mut log = buffer.0.lock().unwrap().clone();
+        log.extend_from_slice(br#"{"sequence":3,"transac"#);
+        let events = read_event_log(&log, &ParseLimits::default()).unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events[0].committed);
+        assert!(!events[1].committed);
+
+        let mut recovered = TransactionValidator::new();
+        assert_eq!(recovered.replay_events(events.clone()), 1);
+        // Replaying the same log twice changes nothing
+        assert_eq!(recovered.replay_events(events), 0);
+        assert_eq!(recovered.get_stats().total_transactions_in_history, 1);
+
+        let replayed = recovered.validate(&create_test_transaction("TXN-WAL-1", 100.0));
+        assert!(replayed
+            .errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::DuplicateTransaction(_))));
+    }
+
+    #[test]
+    fn test_failed_append_commits_nothing() {
+        struct FailingLog;
+
+        impl EventLog for FailingLog {
+            fn append(&mut self, _event: &ValidationEvent) -> io::Result<()> {
+                Err(io::Error::other("disk full"))
+            }
+        }
+
+        let mut validator = TransactionValidator::new();
+        validator.set_event_log(Box::new(FailingLog));
+        let tx = create_test_transaction("TXN-WAL-FAIL", 100.0);
+        let result = validator.validate(&tx);
+        assert!(!result.committed);
+        assert_eq!(result.decision, Decision::Review);
+        assert!(result.warnings.iter().any(|w| w.code == "EVENT_LOG_FAILED"));
+        let stats = validator.get_stats();
+        assert_eq!(stats.event_log_failures, 1);
+        assert_eq!(stats.total_transactions_in_history, 0);
+
+        // A retry fails closed the same way rather than approving
+        let retry = validator.validate(&tx);
+        assert_eq!(retry.decision, Decision::Review);
+        assert!(!retry.committed);
+    }
+
+    #[test]
+    fn test_malformed_interior_line_is_an_error() {
+        let log = b"not json\n{}\n";
+        assert!(matches!(
+            read_event_log(log, &ParseLimits::default()),
+            Err(ParseError::Malformed { line: 1, .. })
+        ));
+    }
+}
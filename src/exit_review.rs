@@ -0,0 +1,348 @@
+//! Exit and de-risking review reports
+//!
+//! Before exiting a relationship, the compliance committee reviews
+//! everything known about the account or customer. [`ExitReviewReport`]
+//! gathers it in one serializable struct: validation history, alerts, and
+//! failed screenings from the audit trail; transactions still held in the
+//! validator's history; watchlist entries; graph statistics and network
+//! patterns; and an overall [`RiskRating`] with the factors behind it.
+//! Build one with
+//! [`TransactionValidator::exit_review`](crate::TransactionValidator::exit_review).
+
+use crate::audit::{AlertRow, ComplianceRow, ResultRow};
+use crate::history::HistoryKey;
+use crate::network_analysis::{AccountStats, NetworkAnalysisReport};
+use crate::{
+    hash_identifier, AuditRecord, AuditTrail, ListType, TransactionValidator, WatchlistEntry,
+    WatchlistSubject,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+
+/// Screening checks whose failure alone makes a subject high risk
+const HARD_SCREENING_CHECKS: [&str; 3] = ["SANCTIONS", "COUNTRY_SANCTIONS", "WATCHLIST"];
+
+/// Account or customer under review
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReviewSubject {
+    Account(String),
+    /// Submitting user, across all of their accounts
+    Customer(String),
+}
+
+/// Overall risk of keeping the relationship
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum RiskRating {
+    Low,
+    Medium,
+    High,
+}
+
+/// Everything known about one account or customer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExitReviewReport {
+    pub subject: ReviewSubject,
+    pub generated_at: DateTime<Utc>,
+    /// Start of the audit look-back
+    pub period_start: DateTime<Utc>,
+    pub risk_rating: RiskRating,
+    /// Why the subject has its rating, most severe first
+    pub rating_factors: Vec<String>,
+    /// Accounts reviewed: the account itself, or the customer's debit accounts
+    pub accounts: Vec<String>,
+    /// Audited validations in the period, oldest first
+    pub validations: Vec<ResultRow>,
+    /// Validation count per decision
+    pub decisions: BTreeMap<String, usize>,
+    pub volume_by_currency: BTreeMap<String, f64>,
+    pub max_fraud_score: u8,
+    /// Error and warning code frequencies across the period
+    pub reason_codes: BTreeMap<String, usize>,
+    pub alerts: Vec<AlertRow>,
+    /// Failed compliance and screening checks
+    pub failed_screenings: Vec<ComplianceRow>,
+    /// Active watchlist entries for the subject or its accounts
+    pub watchlist: Vec<WatchlistEntry>,
+    /// Transactions still in the validator's velocity history
+    pub recent_transactions: usize,
+    /// Graph statistics per account known to the network analyzer
+    pub account_stats: Vec<AccountStats>,
+    /// Network patterns involving the accounts, when an analyzer is attached
+    pub network: Option<NetworkAnalysisReport>,
+}
+
+impl ExitReviewReport {
+    pub(crate) fn compile(
+        validator: &TransactionValidator,
+        subject: &ReviewSubject,
+        trail: &dyn AuditTrail,
+        lookback: Duration,
+    ) -> io::Result<Self> {
+        let now = validator.clock.now();
+        let period_start = now
+            .checked_sub_signed(lookback)
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        let history = validator.history.read();
+
+        let (mut records, accounts, recent_transactions) = match subject {
+            ReviewSubject::Account(account) => (
+                trail.records_for_account(&hash_identifier(account), period_start)?,
+                vec![account.clone()],
+                history.get(HistoryKey::Account, account).len()
+                    + history.get(HistoryKey::Counterparty, account).len(),
+            ),
+            ReviewSubject::Customer(user_id) => {
+                let transactions = history.get(HistoryKey::User, user_id);
+                let accounts: BTreeSet<String> = transactions
+                    .iter()
+                    .filter_map(|t| t.from_account.clone())
+                    .collect();
+                (
+                    trail.records_for_user(&hash_identifier(user_id), period_start)?,
+                    accounts.into_iter().collect(),
+                    transactions.len(),
+                )
+            }
+        };
+        records.sort_by_key(|r| r.result.validated_at);
+
+        let mut watchlist: Vec<WatchlistEntry> = accounts
+            .iter()
+            .flat_map(|a| {
+                validator
+                    .watchlist
+                    .lookup(WatchlistSubject::Account, a, now)
+            })
+            .cloned()
+            .collect();
+        if let ReviewSubject::Customer(user_id) = subject {
+            watchlist.extend(
+                validator
+                    .watchlist
+                    .lookup(WatchlistSubject::User, user_id, now)
+                    .into_iter()
+                    .cloned(),
+            );
+        }
+
+        let (account_stats, network) = match &validator.network_analyzer {
+            Some(analyzer) => {
+                let ids: Vec<&str> = accounts.iter().map(String::as_str).collect();
+                (
+                    ids.iter()
+                        .filter_map(|a| analyzer.get_account_stats(a))
+                        .collect(),
+                    Some(analyzer.analyze_all().for_accounts(&ids)),
+                )
+            }
+            None => (Vec::new(), None),
+        };
+
+        let mut report = Self {
+            subject: subject.clone(),
+            generated_at: now,
+            period_start,
+            risk_rating: RiskRating::Low,
+            rating_factors: Vec::new(),
+            accounts,
+            validations: Vec::new(),
+            decisions: BTreeMap::new(),
+            volume_by_currency: BTreeMap::new(),
+            max_fraud_score: 0,
+            reason_codes: BTreeMap::new(),
+            alerts: Vec::new(),
+            failed_screenings: Vec::new(),
+            watchlist,
+            recent_transactions,
+            account_stats,
+            network,
+        };
+        for record in records {
+            report.add_record(record);
+        }
+        report.rate(validator.config.fraud_threshold);
+        Ok(report)
+    }
+
+    fn add_record(&mut self, record: AuditRecord) {
+        let row = &record.result;
+        *self.decisions.entry(row.decision.clone()).or_default() += 1;
+        *self
+            .volume_by_currency
+            .entry(row.currency.clone())
+            .or_default() += row.amount;
+        self.max_fraud_score = self.max_fraud_score.max(row.fraud_score);
+        let codes = record
+            .errors
+            .iter()
+            .map(|e| &e.reason_code)
+            .chain(record.warnings.iter().map(|w| &w.code));
+        for code in codes {
+            *self.reason_codes.entry(code.clone()).or_default() += 1;
+        }
+        self.alerts.extend(record.alerts);
+        self.failed_screenings.extend(
+            record
+                .compliance_checks
+                .into_iter()
+                .filter(|check| !check.passed),
+        );
+        self.validations.push(record.result);
+    }
+
+    fn rate(&mut self, fraud_threshold: u8) {
+        let mut factors: Vec<(RiskRating, String)> = Vec::new();
+        for check in HARD_SCREENING_CHECKS {
+            let failures = self
+                .failed_screenings
+                .iter()
+                .filter(|row| row.check_name == check)
+                .count();
+            if failures > 0 {
+                factors.push((
+                    RiskRating::High,
+                    format!("{} failed {} screenings", failures, check),
+                ));
+            }
+        }
+        if self
+            .watchlist
+            .iter()
+            .any(|e| e.list_type == ListType::Block)
+        {
+            factors.push((RiskRating::High, "Active blocklist entry".to_string()));
+        }
+        let patterns = self
+            .network
+            .as_ref()
+            .map_or(0, |n| n.suspicious_pattern_count());
+        if patterns > 0 {
+            factors.push((RiskRating::High, format!("{} network patterns", patterns)));
+        }
+
+        let other_failures = self
+            .failed_screenings
+            .iter()
+            .filter(|row| !HARD_SCREENING_CHECKS.contains(&row.check_name.as_str()))
+            .count();
+        if other_failures > 0 {
+            factors.push((
+                RiskRating::Medium,
+                format!("{} other failed compliance checks", other_failures),
+            ));
+        }
+        if let Some(declines) = self.decisions.get("decline") {
+            factors.push((
+                RiskRating::Medium,
+                format!("{} declined validations", declines),
+            ));
+        }
+        if !self.alerts.is_empty() {
+            factors.push((RiskRating::Medium, format!("{} alerts", self.alerts.len())));
+        }
+        if self.max_fraud_score > fraud_threshold {
+            factors.push((
+                RiskRating::Medium,
+                format!(
+                    "Fraud score {} above threshold {}",
+                    self.max_fraud_score, fraud_threshold
+                ),
+            ));
+        }
+        if self
+            .watchlist
+            .iter()
+            .any(|e| matches!(e.list_type, ListType::Gray | ListType::Monitor))
+        {
+            factors.push((RiskRating::Medium, "Watchlist monitoring".to_string()));
+        }
+
+        factors.sort_by_key(|f| std::cmp::Reverse(f.0));
+        self.risk_rating = factors.first().map_or(RiskRating::Low, |f| f.0);
+        self.rating_factors = factors.into_iter().map(|(_, factor)| factor).collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{AuditSink, NetworkAnalyzer, SanctionsScreener, TransactionMetadata};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct MemoryTrail(Arc<Mutex<Vec<AuditRecord>>>);
+
+    impl AuditSink for MemoryTrail {
+        fn write_batch(&mut self, records: &[AuditRecord]) -> io::Result<()> {
+            self.0.lock().unwrap().extend_from_slice(records);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_customer_report_collects_history_and_screening() {
+        let trail = MemoryTrail::default();
+        let mut validator = TransactionValidator::builder()
+            .with_sanctions(SanctionsScreener::new())
+            .with_network(NetworkAnalyzer::new())
+            .build();
+        validator.set_audit_sink(Box::new(trail.clone()), 1);
+
+        validator.validate(&create_test_transaction("TXN-XR-1", 250.0));
+        validator.validate(&create_test_transaction("TXN-XR-2", 750.0));
+        let mut sanctioned = create_test_transaction("TXN-XR-3", 100.0);
+        sanctioned.metadata = Some(TransactionMetadata {
+            beneficiary_name: Some("Sanctioned Entity One".to_string()),
+            ..Default::default()
+        });
+        validator.validate(&sanctioned);
+        let mut other = create_test_transaction("TXN-XR-4", 100.0);
+        other.user_id = "USER-002".to_string();
+        validator.validate(&other);
+
+        let records = trail.0.lock().unwrap().clone();
+        let subject = ReviewSubject::Customer("USER-001".to_string());
+        let report = validator
+            .exit_review(&subject, &records, Duration::days(90))
+            .unwrap();
+
+        assert_eq!(report.validations.len(), 3);
+        assert_eq!(report.accounts, vec!["ACCT-1234-5678-9012".to_string()]);
+        assert_eq!(report.volume_by_currency["USD"], 1_100.0);
+        assert_eq!(report.decisions["decline"], 1);
+        assert!(report
+            .failed_screenings
+            .iter()
+            .any(|row| row.check_name == "SANCTIONS"));
+        assert_eq!(report.risk_rating, RiskRating::High);
+        assert!(report.rating_factors[0].contains("SANCTIONS"));
+        assert_eq!(report.account_stats.len(), 1);
+        assert!(report.network.is_some());
+
+        let json = serde_json::to_string(&report).unwrap();
+        let parsed: ExitReviewReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.subject, subject);
+    }
+
+    #[test]
+    fn test_quiet_account_rates_low() {
+        let trail = MemoryTrail::default();
+        let mut validator = TransactionValidator::new();
+        validator.set_audit_sink(Box::new(trail.clone()), 1);
+        validator.validate(&create_test_transaction("TXN-XR-10", 80.0));
+
+        let records = trail.0.lock().unwrap().clone();
+        let subject = ReviewSubject::Account("ACCT-6789-0123-4567".to_string());
+        let report = validator
+            .exit_review(&subject, &records, Duration::days(90))
+            .unwrap();
+        assert_eq!(report.validations.len(), 1);
+        assert_eq!(report.recent_transactions, 1);
+        assert_eq!(report.risk_rating, RiskRating::Low);
+        assert!(report.rating_factors.is_empty());
+        assert!(report.network.is_none());
+    }
+}
@@ -0,0 +1,200 @@
+//! Score explanations
+//!
+//! Every result carries an [`Explanation`]: one node per risk component
+//! with the points it added to the fraud score after weighting, and below
+//! it the rules that produced the component score, each with the value it
+//! compared, the threshold it compared against, and the inputs it used. The
+//! tree serializes as-is, so analysts and adverse-action notices can quote
+//! exactly why a transaction scored what it did.
+
+use crate::{RiskBreakdown, RiskWeights};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// One contribution to a score
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExplanationNode {
+    /// Risk component, rule, or warning code that contributed
+    pub label: String,
+    /// Points added to the parent; negative for discounts
+    pub points: f64,
+    /// Value compared against `threshold`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f64>,
+    /// Inputs the contribution was computed from
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub inputs: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<ExplanationNode>,
+}
+
+impl ExplanationNode {
+    pub fn new(label: &str, points: f64) -> Self {
+        Self {
+            label: label.to_string(),
+            points,
+            value: None,
+            threshold: None,
+            inputs: BTreeMap::new(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Record the comparison that produced the points
+    pub fn compared(mut self, value: f64, threshold: f64) -> Self {
+        self.value = Some(value);
+        self.threshold = Some(threshold);
+        self
+    }
+
+    /// Record an input
+    pub fn input(mut self, name: &str, value: impl ToString) -> Self {
+        self.inputs.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    pub fn with_children(mut self, children: Vec<ExplanationNode>) -> Self {
+        self.children = children;
+        self
+    }
+
+    /// Depth-first search for a node by label
+    pub fn find(&self, label: &str) -> Option<&ExplanationNode> {
+        if self.label == label {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(label))
+    }
+}
+
+/// Why a result has its fraud score
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Explanation {
+    pub fraud_score: u8,
+    pub fraud_threshold: u8,
+    /// A hard failure (sanctions, prohibited jurisdiction) forced the score to 100
+    pub hard_fail: bool,
+    /// One node per risk component, labelled with its `RiskBreakdown` field
+    pub components: Vec<ExplanationNode>,
+}
+
+impl Explanation {
+    /// Assemble the tree from the final breakdown and each check's rule nodes
+    ///
+    /// `details` maps a component label to the rules that scored it.
+    pub(crate) fn new(
+        breakdown: &RiskBreakdown,
+        weights: &RiskWeights,
+        fraud_threshold: u8,
+        hard_fail: bool,
+        mut details: BTreeMap<&'static str, Vec<ExplanationNode>>,
+    ) -> Self {
+        let weighted = [
+            ("amount_risk", breakdown.amount_risk, weights.amount),
+            ("velocity_risk", breakdown.velocity_risk, weights.velocity),
+            ("pattern_risk", breakdown.pattern_risk, weights.pattern),
+            ("time_risk", breakdown.time_risk, weights.time),
+            ("geo_risk", breakdown.geo_risk, weights.geo),
+            ("network_risk", breakdown.network_risk, weights.network),
+            ("model_risk", breakdown.model_risk, weights.model),
+        ];
+        let mut components: Vec<ExplanationNode> = weighted
+            .into_iter()
+            .map(|(label, score, weight)| {
+                ExplanationNode::new(label, score as f64 * weight)
+                    .input("score", score)
+                    .input("weight", weight)
+                    .with_children(details.remove(label).unwrap_or_default())
+            })
+            .collect();
+        components.push(
+            ExplanationNode::new("trust_adjustment", breakdown.trust_adjustment as f64)
+                .with_children(details.remove("trust_adjustment").unwrap_or_default()),
+        );
+        Self {
+            fraud_score: breakdown.total_score,
+            fraud_threshold,
+            hard_fail,
+            components,
+        }
+    }
+
+    /// Whether the score exceeded the fraud threshold
+    pub fn exceeded(&self) -> bool {
+        self.fraud_score > self.fraud_threshold
+    }
+
+    /// Node for a component, by `RiskBreakdown` field name
+    pub fn component(&self, label: &str) -> Option<&ExplanationNode> {
+        self.components.iter().find(|c| c.label == label)
+    }
+
+    /// First node with this label anywhere in the tree
+    pub fn find(&self, label: &str) -> Option<&ExplanationNode> {
+        self.components.iter().find_map(|c| c.find(label))
+    }
+
+    /// Components that added points, largest first
+    pub fn contributors(&self) -> Vec<&ExplanationNode> {
+        let mut contributors: Vec<&ExplanationNode> =
+            self.components.iter().filter(|c| c.points != 0.0).collect();
+        contributors.sort_by(|a, b| b.points.abs().total_cmp(&a.points.abs()));
+        contributors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{TransactionType, TransactionValidator};
+
+    #[test]
+    fn test_explanation_accounts_for_the_score() {
+        let mut validator = TransactionValidator::new();
+        let mut tx = create_test_transaction("TXN-EX-1", 60_000.0);
+        tx.transaction_type = TransactionType::WireTransfer;
+        let result = validator.validate(&tx);
+        let explanation = &result.explanation;
+
+        assert_eq!(explanation.fraud_score, result.fraud_score);
+        let total: f64 = explanation.components.iter().map(|c| c.points).sum();
+        assert_eq!(total.round().clamp(0.0, 100.0) as u8, result.fraud_score);
+        assert_eq!(explanation.exceeded(), result.fraud_score > 70);
+
+        let tier = explanation.find("amount_tier").unwrap();
+        assert_eq!(tier.value, Some(60_000.0));
+        assert_eq!(tier.threshold, Some(50_000.0));
+        assert_eq!(
+            tier.points, result.risk_breakdown.amount_risk as f64,
+            "the matched tier supplies the amount score"
+        );
+        let pattern = explanation.component("pattern_risk").unwrap();
+        let rules = pattern.find("rules").unwrap();
+        assert!(rules.children.iter().any(|r| r.label == "HIGH_VALUE"));
+        assert!(rules.children.iter().any(|r| r.label == "WIRE_TRANSFER"));
+        let rule_points: f64 = rules.children.iter().map(|r| r.points).sum();
+        assert_eq!(rule_points, rules.points);
+        assert_eq!(explanation.contributors()[0].label, "pattern_risk");
+
+        let json = serde_json::to_string(explanation).unwrap();
+        let parsed: Explanation = serde_json::from_str(&json).unwrap();
+        assert_eq!(&parsed, explanation);
+    }
+
+    #[test]
+    fn test_velocity_comparisons_are_explained() {
+        let mut validator = TransactionValidator::new();
+        for i in 0..6 {
+            validator.validate(&create_test_transaction(&format!("TXN-EX-V{}", i), 100.0));
+        }
+        let result = validator.validate(&create_test_transaction("TXN-EX-V6", 100.0));
+        let count = result.explanation.find("transaction_count").unwrap();
+        assert_eq!(count.value, Some(6.0));
+        assert_eq!(count.threshold, Some(5.0));
+        assert_eq!(count.points, 15.0);
+        assert_eq!(count.inputs["window_minutes"], "60");
+    }
+}
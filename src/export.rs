@@ -0,0 +1,319 @@
+//! Result export helpers
+//!
+//! Flattens validation results into one row per transaction with scalar
+//! columns (risk breakdown, decision, joined error and warning codes) so
+//! they load straight into spreadsheets and analytics tools. CSV is always
+//! available; Parquet requires the `parquet` feature. CSV text cells that
+//! a spreadsheet would run as a formula are escaped with a leading `'`.
+
+use crate::ValidationResult;
+use std::io::{self, Write};
+
+/// Column names, in output order
+pub const EXPORT_COLUMNS: [&str; 20] = [
+    "transaction_id",
+    "amount",
+    "is_valid",
+    "decision",
+    "fraud_score",
+    "risk_level",
+    "amount_risk",
+    "velocity_risk",
+    "pattern_risk",
+    "time_risk",
+    "geo_risk",
+    "network_risk",
+    "error_codes",
+    "error_messages",
+    "warning_codes",
+    "warning_messages",
+    "skipped_checks",
+    "tenant_id",
+    "committed",
+    "validated_at",
+];
+
+/// Separator used when several codes or messages share one column
+pub const LIST_SEPARATOR: &str = "; ";
+
+/// How one column is read from a result, by its export type
+enum Column {
+    Text(fn(&ValidationResult) -> String),
+    Double(fn(&ValidationResult) -> f64),
+    Bool(fn(&ValidationResult) -> bool),
+    Int(fn(&ValidationResult) -> i32),
+}
+
+fn join(items: impl Iterator<Item = String>) -> String {
+    items.collect::<Vec<_>>().join(LIST_SEPARATOR)
+}
+
+/// Every column, in [`EXPORT_COLUMNS`] order
+const COLUMNS: [(&str, Column); 20] = [
+    ("transaction_id", Column::Text(|r| r.transaction_id.clone())),
+    ("amount", Column::Double(|r| r.amount)),
+    ("is_valid", Column::Bool(|r| r.is_valid)),
+    ("decision", Column::Text(|r| r.decision.to_string())),
+    ("fraud_score", Column::Int(|r| r.fraud_score.into())),
+    ("risk_level", Column::Text(|r| r.risk_level().to_string())),
+    (
+        "amount_risk",
+        Column::Int(|r| r.risk_breakdown.amount_risk.into()),
+    ),
+    (
+        "velocity_risk",
+        Column::Int(|r| r.risk_breakdown.velocity_risk.into()),
+    ),
+    (
+        "pattern_risk",
+        Column::Int(|r| r.risk_breakdown.pattern_risk.into()),
+    ),
+    (
+        "time_risk",
+        Column::Int(|r| r.risk_breakdown.time_risk.into()),
+    ),
+    (
+        "geo_risk",
+        Column::Int(|r| r.risk_breakdown.geo_risk.into()),
+    ),
+    (
+        "network_risk",
+        Column::Int(|r| r.risk_breakdown.network_risk.into()),
+    ),
+    (
+        "error_codes",
+        Column::Text(|r| join(r.reason_codes().into_iter())),
+    ),
+    (
+        "error_messages",
+        Column::Text(|r| join(r.errors.iter().map(|e| e.to_string()))),
+    ),
+    (
+        "warning_codes",
+        Column::Text(|r| join(r.warning_codes().into_iter())),
+    ),
+    (
+        "warning_messages",
+        Column::Text(|r| join(r.warnings.iter().map(|w| w.message.clone()))),
+    ),
+    (
+        "skipped_checks",
+        Column::Text(|r| join(r.skipped_checks.iter().map(|c| c.name().to_string()))),
+    ),
+    (
+        "tenant_id",
+        Column::Text(|r| r.tenant_id.clone().unwrap_or_default()),
+    ),
+    ("committed", Column::Bool(|r| r.committed)),
+    (
+        "validated_at",
+        Column::Text(|r| r.validated_at.to_rfc3339()),
+    ),
+];
+
+impl Column {
+    /// CSV cell; only free text can start a spreadsheet formula
+    fn csv(&self, result: &ValidationResult) -> String {
+        match self {
+            Column::Text(value) => csv_field(&value(result)),
+            Column::Double(value) => value(result).to_string(),
+            Column::Bool(value) => value(result).to_string(),
+            Column::Int(value) => value(result).to_string(),
+        }
+    }
+}
+
+/// Quote a CSV field when it contains a delimiter, quote, or line break
+///
+/// Text a spreadsheet would evaluate as a formula (leading `=`, `+`, `-`,
+/// `@`, tab, or carriage return) is prefixed with a single quote first.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+impl ValidationResult {
+    /// Write results as CSV with a header row
+    pub fn write_csv<W: Write>(results: &[ValidationResult], mut writer: W) -> io::Result<()> {
+        writeln!(writer, "{}", EXPORT_COLUMNS.join(","))?;
+        for result in results {
+            let fields: Vec<String> = COLUMNS.iter().map(|(_, c)| c.csv(result)).collect();
+            writeln!(writer, "{}", fields.join(","))?;
+        }
+        writer.flush()
+    }
+
+    /// Write results as a single-row-group Parquet file
+    ///
+    /// Numeric and boolean columns are written from the result's own
+    /// values; list columns are joined with
+    /// [`LIST_SEPARATOR`] as in the CSV export.
+    #[cfg(feature = "parquet")]
+    pub fn write_parquet<W: Write + Send>(
+        results: &[ValidationResult],
+        writer: W,
+    ) -> Result<(), parquet::errors::ParquetError> {
+        parquet_export::write(results, writer)
+    }
+}
+
+#[cfg(feature = "parquet")]
+mod parquet_export {
+    use super::{Column, COLUMNS};
+    use crate::ValidationResult;
+    use parquet::data_type::{BoolType, ByteArray, ByteArrayType, DoubleType, Int32Type};
+    use parquet::errors::ParquetError;
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::parser::parse_message_type;
+    use std::io::Write;
+    use std::sync::Arc;
+
+    fn schema() -> String {
+        let fields: String = COLUMNS
+            .iter()
+            .map(|(name, column)| match column {
+                Column::Text(_) => format!("REQUIRED BYTE_ARRAY {} (UTF8); ", name),
+                Column::Double(_) => format!("REQUIRED DOUBLE {}; ", name),
+                Column::Bool(_) => format!("REQUIRED BOOLEAN {}; ", name),
+                Column::Int(_) => format!("REQUIRED INT32 {}; ", name),
+            })
+            .collect();
+        format!("message validation_result {{ {} }}", fields)
+    }
+
+    pub(super) fn write<W: Write + Send>(
+        results: &[ValidationResult],
+        writer: W,
+    ) -> Result<(), ParquetError> {
+        let schema = Arc::new(parse_message_type(&schema())?);
+        let properties = Arc::new(WriterProperties::builder().build());
+        let mut file = SerializedFileWriter::new(writer, schema, properties)?;
+
+        let mut row_group = file.next_row_group()?;
+        let mut columns = COLUMNS.iter();
+        while let Some(mut writer) = row_group.next_column()? {
+            let Some((_, column)) = columns.next() else {
+                return Err(ParquetError::General("schema has extra columns".into()));
+            };
+            match column {
+                Column::Text(value) => {
+                    let values: Vec<ByteArray> = results
+                        .iter()
+                        .map(|r| ByteArray::from(value(r).as_str()))
+                        .collect();
+                    writer
+                        .typed::<ByteArrayType>()
+                        .write_batch(&values, None, None)?;
+                }
+                Column::Double(value) => {
+                    let values: Vec<f64> = results.iter().map(value).collect();
+                    writer
+                        .typed::<DoubleType>()
+                        .write_batch(&values, None, None)?;
+                }
+                Column::Bool(value) => {
+                    let values: Vec<bool> = results.iter().map(value).collect();
+                    writer
+                        .typed::<BoolType>()
+                        .write_batch(&values, None, None)?;
+                }
+                Column::Int(value) => {
+                    let values: Vec<i32> = results.iter().map(value).collect();
+                    writer
+                        .typed::<Int32Type>()
+                        .write_batch(&values, None, None)?;
+                }
+            }
+            writer.close()?;
+        }
+        row_group.close()?;
+        file.close()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+    use crate::{Transaction, TransactionType, TransactionValidator};
+
+    fn create_test_transaction(id: &str, amount: f64) -> Transaction {
+        Transaction {
+            transaction_type: TransactionType::WireTransfer,
+            ..test_fixtures::create_test_transaction(id, amount)
+        }
+    }
+
+    #[test]
+    fn test_write_csv_flattens_results() {
+        let mut validator = TransactionValidator::new();
+        let results = vec![
+            validator.validate(&create_test_transaction("TXN-CSV-1", 100.0)),
+            validator.validate(&create_test_transaction("TXN-CSV-2", -5.0)),
+        ];
+
+        let mut out = Vec::new();
+        ValidationResult::write_csv(&results, &mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("transaction_id,amount,is_valid,decision"));
+        assert!(lines[1].starts_with("TXN-CSV-1,100,true,review"));
+        assert!(lines[2].contains("INVALID_AMOUNT"));
+        assert!(lines[1].contains("WIRE_TRANSFER"));
+    }
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn test_csv_text_cannot_start_a_formula() {
+        assert_eq!(csv_field("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+        assert_eq!(csv_field("+1"), "'+1");
+        assert_eq!(csv_field("-1"), "'-1");
+        assert_eq!(csv_field("@SUM(A1)"), "'@SUM(A1)");
+
+        // Numbers are typed columns and stay unprefixed
+        let mut validator = TransactionValidator::new();
+        let tx = create_test_transaction("=cmd|' /C calc'!A0", -5.0);
+        let results = vec![validator.validate(&tx)];
+        let mut out = Vec::new();
+        ValidationResult::write_csv(&results, &mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let row = csv.lines().nth(1).unwrap();
+        assert!(row.starts_with("'=cmd|' /C calc'!A0,-5,false"));
+    }
+
+    #[test]
+    fn test_columns_match_export_columns() {
+        let names: Vec<&str> = COLUMNS.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, EXPORT_COLUMNS);
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_write_parquet() {
+        let mut validator = TransactionValidator::new();
+        let results = vec![validator.validate(&create_test_transaction("TXN-PQ-1", 100.0))];
+
+        let mut out = Vec::new();
+        ValidationResult::write_parquet(&results, &mut out).unwrap();
+        assert_eq!(&out[..4], b"PAR1");
+        assert_eq!(&out[out.len() - 4..], b"PAR1");
+    }
+}
@@ -0,0 +1,304 @@
+//! Async external checks
+//!
+//! Sanctions screening, geolocation and KYC often live behind vendor APIs
+//! that are only reachable asynchronously. An [`ExternalScreeningProvider`]
+//! wraps such a client; [`TransactionValidator::validate_async`](crate::TransactionValidator::validate_async)
+//! awaits its lookups in the caller's runtime and then runs the usual
+//! checks, applying the [`ExternalFindings`] alongside the screening check:
+//!
+//! - A high-confidence vendor sanctions hit declines like a local one, and a
+//!   failed sanctions lookup fails closed.
+//! - A geolocated country is screened and scored like the metadata countries.
+//! - A failed KYC verification declines; a pending one raises `KYC_PENDING`.
+//!
+//! Failed geolocation and KYC lookups are recorded as warnings and
+//! degradations. Lookups see the transaction as submitted, before pre-hooks
+//! and normalization.
+
+use crate::{SanctionsResult, ScreeningError, Transaction};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+
+/// Outcome of a customer identity verification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KycStatus {
+    Verified,
+    /// Verification has started but not completed
+    Pending,
+    Failed,
+}
+
+/// Lookup made by an external provider
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExternalStage {
+    Sanctions,
+    Geolocation,
+    Kyc,
+}
+
+impl ExternalStage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExternalStage::Sanctions => "sanctions",
+            ExternalStage::Geolocation => "geolocation",
+            ExternalStage::Kyc => "kyc",
+        }
+    }
+}
+
+/// Remote sanctions, geolocation and KYC lookups
+///
+/// Every lookup defaults to "not provided", so a provider implements only
+/// the services it has. Per-call timeouts belong to the client.
+pub trait ExternalScreeningProvider: Send + Sync {
+    /// Provider name used in errors, warnings and degradations
+    fn name(&self) -> &str;
+
+    /// Screen one counterparty name
+    fn screen_name(
+        &self,
+        _name: &str,
+    ) -> impl Future<Output = Result<Option<SanctionsResult>, ScreeningError>> + Send {
+        async { Ok(None) }
+    }
+
+    /// ISO country code the transaction was initiated from
+    fn geolocate(
+        &self,
+        _transaction: &Transaction,
+    ) -> impl Future<Output = Result<Option<String>, ScreeningError>> + Send {
+        async { Ok(None) }
+    }
+
+    /// Verification status of the transaction's user
+    fn verify_kyc(
+        &self,
+        _transaction: &Transaction,
+    ) -> impl Future<Output = Result<Option<KycStatus>, ScreeningError>> + Send {
+        async { Ok(None) }
+    }
+}
+
+/// Lookup that failed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExternalFailure {
+    pub stage: ExternalStage,
+    pub error: ScreeningError,
+}
+
+/// Answers gathered from a provider before validation
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExternalFindings {
+    pub provider: String,
+    /// Screened counterparty names and the provider's results
+    pub sanctions: Vec<(String, SanctionsResult)>,
+    pub geolocation: Option<String>,
+    pub kyc: Option<KycStatus>,
+    pub failures: Vec<ExternalFailure>,
+}
+
+impl ExternalFindings {
+    /// Run every lookup for a transaction, one after another
+    pub async fn collect<P: ExternalScreeningProvider>(
+        provider: &P,
+        transaction: &Transaction,
+    ) -> Self {
+        let mut findings = Self {
+            provider: provider.name().to_string(),
+            ..Default::default()
+        };
+        let names = transaction
+            .metadata
+            .iter()
+            .flat_map(|m| m.counterparty_names());
+        for name in names {
+            match provider.screen_name(name).await {
+                Ok(Some(result)) => findings.sanctions.push((name.to_string(), result)),
+                Ok(None) => {}
+                Err(error) => findings.fail(ExternalStage::Sanctions, error),
+            }
+        }
+        match provider.geolocate(transaction).await {
+            Ok(country) => findings.geolocation = country,
+            Err(error) => findings.fail(ExternalStage::Geolocation, error),
+        }
+        match provider.verify_kyc(transaction).await {
+            Ok(status) => findings.kyc = status,
+            Err(error) => findings.fail(ExternalStage::Kyc, error),
+        }
+        findings
+    }
+
+    fn fail(&mut self, stage: ExternalStage, error: ScreeningError) {
+        self.failures.push(ExternalFailure { stage, error });
+    }
+
+    /// Whether a lookup of this stage failed
+    pub fn failed(&self, stage: ExternalStage) -> bool {
+        self.failures.iter().any(|f| f.stage == stage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{
+        DegradationReason, GeographicRiskScorer, SanctionsScreener, TransactionMetadata,
+        TransactionValidator, ValidationError,
+    };
+
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+
+    /// Vendor answering from the local lists, with fixed geolocation and KYC
+    struct Vendor {
+        local: SanctionsScreener,
+        country: Option<&'static str>,
+        kyc: Result<KycStatus, ScreeningError>,
+    }
+
+    impl ExternalScreeningProvider for Vendor {
+        fn name(&self) -> &str {
+            "vendor"
+        }
+
+        async fn screen_name(&self, name: &str) -> Result<Option<SanctionsResult>, ScreeningError> {
+            Ok(Some(self.local.screen(name)))
+        }
+
+        async fn geolocate(
+            &self,
+            _transaction: &Transaction,
+        ) -> Result<Option<String>, ScreeningError> {
+            Ok(self.country.map(str::to_string))
+        }
+
+        async fn verify_kyc(
+            &self,
+            _transaction: &Transaction,
+        ) -> Result<Option<KycStatus>, ScreeningError> {
+            self.kyc.clone().map(Some)
+        }
+    }
+
+    /// Sanctions-only vendor that is down
+    struct Down;
+
+    impl ExternalScreeningProvider for Down {
+        fn name(&self) -> &str {
+            "down"
+        }
+
+        async fn screen_name(
+            &self,
+            _name: &str,
+        ) -> Result<Option<SanctionsResult>, ScreeningError> {
+            Err(ScreeningError::Timeout)
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    fn paying(id: &str, beneficiary: &str) -> Transaction {
+        let mut tx = create_test_transaction(id, 100.0);
+        tx.metadata = Some(TransactionMetadata::from([(
+            "beneficiary_name".to_string(),
+            beneficiary.to_string(),
+        )]));
+        tx
+    }
+
+    fn vendor(country: Option<&'static str>, kyc: KycStatus) -> Vendor {
+        Vendor {
+            local: SanctionsScreener::new(),
+            country,
+            kyc: Ok(kyc),
+        }
+    }
+
+    #[test]
+    fn test_vendor_findings_apply_to_validation() {
+        let mut validator = TransactionValidator::new();
+
+        let clear = vendor(None, KycStatus::Verified);
+        let result =
+            block_on(validator.validate_async(&paying("TXN-EXT-1", "ACME TRADING"), &clear));
+        assert!(result.is_valid);
+        assert_eq!(result.compliance_checks.get("SANCTIONS"), Some(&true));
+        assert_eq!(result.compliance_checks.get("KYC"), Some(&true));
+
+        let hit = block_on(
+            validator.validate_async(&paying("TXN-EXT-2", "SANCTIONED ENTITY ONE"), &clear),
+        );
+        assert!(!hit.is_valid);
+        assert!(hit
+            .errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::SanctionsMatch(_))));
+
+        let pending = vendor(None, KycStatus::Pending);
+        let result =
+            block_on(validator.validate_async(&paying("TXN-EXT-3", "ACME TRADING"), &pending));
+        assert!(result.warning_codes().contains(&"KYC_PENDING".to_string()));
+        assert_eq!(result.compliance_checks.get("KYC"), Some(&false));
+
+        let failed = vendor(None, KycStatus::Failed);
+        let result =
+            block_on(validator.validate_async(&paying("TXN-EXT-4", "ACME TRADING"), &failed));
+        assert!(!result.is_valid);
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::ComplianceFailed(d) if d.contains("KYC"))));
+    }
+
+    #[test]
+    fn test_geolocated_country_is_screened() {
+        let mut validator = TransactionValidator::new();
+        validator.set_geographic_scorer(GeographicRiskScorer::new());
+
+        let tx = paying("TXN-EXT-GEO", "ACME TRADING");
+        let result =
+            block_on(validator.validate_async(&tx, &vendor(Some("KP"), KycStatus::Verified)));
+        assert!(!result.is_valid);
+        assert!(result.risk_breakdown.geo_risk > 0);
+
+        let mut validator = TransactionValidator::new();
+        let kyc_down = Vendor {
+            kyc: Err(ScreeningError::Unavailable("503".to_string())),
+            ..vendor(None, KycStatus::Verified)
+        };
+        let result = block_on(validator.validate_async(&tx, &kyc_down));
+        assert!(result.is_valid);
+        assert!(result
+            .warning_codes()
+            .contains(&"EXTERNAL_CHECK_FAILED".to_string()));
+        assert!(result
+            .degraded
+            .contains(&DegradationReason::ProviderFailed {
+                provider: "vendor".to_string()
+            }));
+    }
+
+    #[test]
+    fn test_failed_sanctions_lookup_fails_closed() {
+        let tx = paying("TXN-EXT-DOWN", "ACME TRADING");
+        let findings = block_on(ExternalFindings::collect(&Down, &tx));
+        assert!(findings.failed(ExternalStage::Sanctions));
+        assert!(findings.kyc.is_none());
+
+        let mut validator = TransactionValidator::new();
+        let result = block_on(validator.validate_async(&tx, &Down));
+        assert!(!result.is_valid);
+        assert_eq!(result.compliance_checks.get("SANCTIONS"), Some(&false));
+    }
+}
@@ -0,0 +1,396 @@
+//! Feature vectors for model training
+//!
+//! [`FeatureExtractor`] turns a transaction plus the validator's state
+//! (history, counterparty relationships, transaction graph, reference data)
+//! into the numeric features available when the transaction is decided.
+//! Extract before validating so the transaction's own effect on that state
+//! is not included; history entries with the same transaction ID are
+//! skipped either way.
+//!
+//! The layout is versioned by [`FEATURE_VERSION`]. Features are appended
+//! or changed only with a version bump, so a model can check the version
+//! it was trained on. Version 1:
+//!
+//! | # | Name                          | Meaning                                                     |
+//! |--:|-------------------------------|-------------------------------------------------------------|
+//! | 0 | `log_amount`                  | `ln(1 + amount)`                                            |
+//! | 1 | `hour_of_day`                 | UTC hour, 0-23                                              |
+//! | 2 | `day_of_week`                 | 0 = Monday ... 6 = Sunday                                   |
+//! | 3 | `is_weekend`                  | 1 on Saturday and Sunday                                    |
+//! | 4 | `is_cross_border`             | 1 when `cross_border` metadata is `true`                    |
+//! | 5 | `amount_risk`                 | Amount tier score, 0-100                                    |
+//! | 6 | `time_risk`                   | Time-of-day score, 0-100                                    |
+//! | 7 | `geo_risk`                    | Highest country risk in metadata, 0-100                     |
+//! | 8 | `user_count_window`           | User's transactions in the velocity window                  |
+//! | 9 | `log_user_amount_window`      | `ln(1 + total)` of those transactions                       |
+//! |10 | `user_count_24h`              | User's transactions in the previous 24 hours                |
+//! |11 | `hours_since_last_user_tx`    | Hours since the user's latest earlier transaction           |
+//! |12 | `amount_to_user_mean`         | Amount over the mean of the user's retained history         |
+//! |13 | `counterparty_interactions`   | Earlier payments from the user to `to_account`              |
+//! |14 | `counterparty_disputes`       | Disputes on that relationship                               |
+//! |15 | `counterparty_trust`          | Relationship trust score, 0-100                             |
+//! |16 | `sender_out_degree`           | Distinct accounts `from_account` has paid                   |
+//! |17 | `receiver_in_degree`          | Distinct accounts that have paid `to_account`               |
+//! |18 | `log_receiver_inflow`         | `ln(1 + total inflow)` of `to_account`                      |
+//!
+//! Features that need a component the validator does not have (no
+//! counterparty trust, no network analyzer) or an earlier transaction
+//! that does not exist are [`MISSING`]. Unknown accounts and
+//! relationships with the component present are 0.
+
+use crate::{HistoryKey, Transaction, TransactionValidator};
+use chrono::{DateTime, Datelike, Duration, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+
+/// Version of the feature layout
+pub const FEATURE_VERSION: u32 = 1;
+
+/// Value of a feature that could not be computed
+pub const MISSING: f64 = -1.0;
+
+/// Names of the features, in vector order
+pub const FEATURE_NAMES: [&str; 19] = [
+    "log_amount",
+    "hour_of_day",
+    "day_of_week",
+    "is_weekend",
+    "is_cross_border",
+    "amount_risk",
+    "time_risk",
+    "geo_risk",
+    "user_count_window",
+    "log_user_amount_window",
+    "user_count_24h",
+    "hours_since_last_user_tx",
+    "amount_to_user_mean",
+    "counterparty_interactions",
+    "counterparty_disputes",
+    "counterparty_trust",
+    "sender_out_degree",
+    "receiver_in_degree",
+    "log_receiver_inflow",
+];
+
+/// Features of one transaction
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeatureVector {
+    pub transaction_id: String,
+    pub version: u32,
+    /// One value per [`FEATURE_NAMES`] entry
+    pub values: Vec<f64>,
+}
+
+impl FeatureVector {
+    /// Value of a feature by name
+    pub fn get(&self, name: &str) -> Option<f64> {
+        let index = FEATURE_NAMES.iter().position(|n| *n == name)?;
+        self.values.get(index).copied()
+    }
+
+    /// Write vectors as CSV: `transaction_id`, `feature_version`, then one
+    /// column per feature
+    pub fn write_csv<W: Write>(vectors: &[FeatureVector], mut writer: W) -> io::Result<()> {
+        writeln!(
+            writer,
+            "transaction_id,feature_version,{}",
+            FEATURE_NAMES.join(",")
+        )?;
+        for vector in vectors {
+            let values: Vec<String> = vector.values.iter().map(|v| v.to_string()).collect();
+            writeln!(
+                writer,
+                "{},{},{}",
+                csv_field(&vector.transaction_id),
+                vector.version,
+                values.join(",")
+            )?;
+        }
+        writer.flush()
+    }
+
+    /// Write vectors as a single-row-group Parquet file with the CSV
+    /// columns; features are `DOUBLE`
+    #[cfg(feature = "parquet")]
+    pub fn write_parquet<W: Write + Send>(
+        vectors: &[FeatureVector],
+        writer: W,
+    ) -> Result<(), parquet::errors::ParquetError> {
+        parquet_export::write(vectors, writer)
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Computes [`FeatureVector`]s from a validator's current state
+pub struct FeatureExtractor<'a> {
+    validator: &'a TransactionValidator,
+}
+
+impl<'a> FeatureExtractor<'a> {
+    pub fn new(validator: &'a TransactionValidator) -> Self {
+        Self { validator }
+    }
+
+    /// Features of one transaction
+    pub fn extract(&self, transaction: &Transaction) -> FeatureVector {
+        let validator = self.validator;
+        let at = transaction.timestamp;
+        let flag = |set: bool| if set { 1.0 } else { 0.0 };
+        let cross_border = transaction
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("cross_border"))
+            .is_some_and(|v| v == "true");
+
+        let mut values = vec![
+            transaction.amount.max(0.0).ln_1p(),
+            at.hour() as f64,
+            at.weekday().num_days_from_monday() as f64,
+            flag(at.weekday().num_days_from_monday() >= 5),
+            flag(cross_border),
+            validator.calculate_amount_risk(&validator.config, transaction) as f64,
+            validator.calculate_time_risk(&validator.config, &at) as f64,
+            validator.calculate_geo_risk(transaction) as f64,
+        ];
+        values.extend(self.history_features(transaction));
+        values.extend(self.counterparty_features(transaction));
+        values.extend(self.graph_features(transaction));
+
+        FeatureVector {
+            transaction_id: transaction.transaction_id.clone(),
+            version: FEATURE_VERSION,
+            values,
+        }
+    }
+
+    /// Features of each transaction against the same state
+    pub fn extract_all(&self, transactions: &[Transaction]) -> Vec<FeatureVector> {
+        transactions.iter().map(|tx| self.extract(tx)).collect()
+    }
+
+    fn history_features(&self, transaction: &Transaction) -> [f64; 5] {
+        let at = transaction.timestamp;
+        let since = |window: Option<Duration>| {
+            window
+                .and_then(|w| at.checked_sub_signed(w))
+                .unwrap_or(DateTime::<Utc>::MIN_UTC)
+        };
+        let window_start = since(Duration::try_minutes(
+            self.validator.config.velocity_check_window_minutes,
+        ));
+        let day_start = since(Duration::try_hours(24));
+
+        let history = self.validator.history.read();
+        let prior: Vec<&Transaction> = history
+            .get(HistoryKey::User, &transaction.user_id)
+            .iter()
+            .map(|h| h.as_ref())
+            .filter(|h| h.transaction_id != transaction.transaction_id)
+            .collect();
+        let in_window: Vec<&&Transaction> = prior
+            .iter()
+            .filter(|h| h.timestamp >= window_start)
+            .collect();
+        let window_amount: f64 = in_window.iter().map(|h| h.amount).sum();
+        let count_24h = prior.iter().filter(|h| h.timestamp >= day_start).count();
+        let hours_since_last = prior
+            .iter()
+            .map(|h| h.timestamp)
+            .filter(|t| *t <= at)
+            .max()
+            .map_or(MISSING, |last| (at - last).num_seconds() as f64 / 3600.0);
+        let mean = prior.iter().map(|h| h.amount).sum::<f64>() / prior.len().max(1) as f64;
+        let amount_to_mean = if prior.is_empty() || mean <= 0.0 {
+            MISSING
+        } else {
+            transaction.amount / mean
+        };
+
+        [
+            in_window.len() as f64,
+            window_amount.max(0.0).ln_1p(),
+            count_24h as f64,
+            hours_since_last,
+            amount_to_mean,
+        ]
+    }
+
+    fn counterparty_features(&self, transaction: &Transaction) -> [f64; 3] {
+        let (Some(trust), Some(counterparty)) = (
+            self.validator.counterparty_trust.as_ref(),
+            transaction.to_account.as_deref(),
+        ) else {
+            return [MISSING; 3];
+        };
+        let user = &transaction.user_id;
+        let (interactions, disputes) = trust
+            .relationship(user, counterparty)
+            .map_or((0, 0), |s| (s.interactions, s.disputes));
+        [
+            interactions as f64,
+            disputes as f64,
+            trust.trust_score(user, counterparty, transaction.timestamp) as f64,
+        ]
+    }
+
+    fn graph_features(&self, transaction: &Transaction) -> [f64; 3] {
+        let Some(analyzer) = self.validator.network_analyzer.as_ref() else {
+            return [MISSING; 3];
+        };
+        let stats = |account: Option<&String>| account.and_then(|a| analyzer.get_account_stats(a));
+        let sender = stats(transaction.from_account.as_ref());
+        let receiver = stats(transaction.to_account.as_ref());
+        [
+            sender.map_or(0.0, |s| s.outgoing_connections as f64),
+            receiver
+                .as_ref()
+                .map_or(0.0, |s| s.incoming_connections as f64),
+            receiver.map_or(0.0, |s| s.total_inflow.max(0.0).ln_1p()),
+        ]
+    }
+}
+
+#[cfg(feature = "parquet")]
+mod parquet_export {
+    use super::{FeatureVector, FEATURE_NAMES};
+    use parquet::data_type::{ByteArray, ByteArrayType, DoubleType, Int32Type};
+    use parquet::errors::ParquetError;
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::parser::parse_message_type;
+    use std::io::Write;
+    use std::sync::Arc;
+
+    fn schema() -> String {
+        let features: String = FEATURE_NAMES
+            .iter()
+            .map(|name| format!("REQUIRED DOUBLE {}; ", name))
+            .collect();
+        format!(
+            "message feature_vector {{ REQUIRED BYTE_ARRAY transaction_id (UTF8); \
+             REQUIRED INT32 feature_version; {} }}",
+            features
+        )
+    }
+
+    pub(super) fn write<W: Write + Send>(
+        vectors: &[FeatureVector],
+        writer: W,
+    ) -> Result<(), ParquetError> {
+        let schema = Arc::new(parse_message_type(&schema())?);
+        let properties = Arc::new(WriterProperties::builder().build());
+        let mut file = SerializedFileWriter::new(writer, schema, properties)?;
+
+        let mut row_group = file.next_row_group()?;
+        let mut index = 0;
+        while let Some(mut column) = row_group.next_column()? {
+            match index {
+                0 => {
+                    let values: Vec<ByteArray> = vectors
+                        .iter()
+                        .map(|v| ByteArray::from(v.transaction_id.as_str()))
+                        .collect();
+                    column
+                        .typed::<ByteArrayType>()
+                        .write_batch(&values, None, None)?;
+                }
+                1 => {
+                    let values: Vec<i32> = vectors.iter().map(|v| v.version as i32).collect();
+                    column
+                        .typed::<Int32Type>()
+                        .write_batch(&values, None, None)?;
+                }
+                _ => {
+                    let values: Vec<f64> = vectors
+                        .iter()
+                        .map(|v| v.values.get(index - 2).copied().unwrap_or(super::MISSING))
+                        .collect();
+                    column
+                        .typed::<DoubleType>()
+                        .write_batch(&values, None, None)?;
+                }
+            }
+            column.close()?;
+            index += 1;
+        }
+        row_group.close()?;
+        file.close()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{CounterpartyTrust, NetworkAnalyzer};
+
+    #[test]
+    fn test_features_reflect_state_before_the_decision() {
+        let mut validator = TransactionValidator::new();
+        let extractor = FeatureExtractor::new(&validator);
+        let first = create_test_transaction("TXN-FE-1", 100.0);
+        let fresh = extractor.extract(&first);
+        assert_eq!(fresh.version, FEATURE_VERSION);
+        assert_eq!(fresh.values.len(), FEATURE_NAMES.len());
+        assert_eq!(fresh.get("hour_of_day"), Some(12.0));
+        assert_eq!(fresh.get("user_count_window"), Some(0.0));
+        assert_eq!(fresh.get("hours_since_last_user_tx"), Some(MISSING));
+        assert_eq!(fresh.get("counterparty_trust"), Some(MISSING));
+
+        validator.set_counterparty_trust(CounterpartyTrust::new());
+        validator.set_network_analyzer(NetworkAnalyzer::new());
+        validator.validate(&first);
+        // Re-extracting a validated transaction ignores its own history entry
+        let extractor = FeatureExtractor::new(&validator);
+        assert_eq!(
+            extractor.extract(&first).get("user_count_window"),
+            Some(0.0)
+        );
+
+        let mut second = create_test_transaction("TXN-FE-2", 300.0);
+        second.timestamp += Duration::minutes(30);
+        let features = extractor.extract(&second);
+        assert_eq!(features.get("user_count_window"), Some(1.0));
+        assert_eq!(features.get("hours_since_last_user_tx"), Some(0.5));
+        assert_eq!(features.get("amount_to_user_mean"), Some(3.0));
+        assert_eq!(features.get("counterparty_interactions"), Some(1.0));
+        assert_eq!(features.get("receiver_in_degree"), Some(1.0));
+    }
+
+    #[test]
+    fn test_write_csv_has_versioned_header() {
+        let validator = TransactionValidator::new();
+        let vectors = FeatureExtractor::new(&validator).extract_all(&[
+            create_test_transaction("TXN-FE-3", 10.0),
+            create_test_transaction("TXN,FE-4", 20.0),
+        ]);
+        let mut out = Vec::new();
+        FeatureVector::write_csv(&vectors, &mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("transaction_id,feature_version,log_amount,"));
+        assert_eq!(lines[0].split(',').count(), FEATURE_NAMES.len() + 2);
+        assert!(lines[1].starts_with("TXN-FE-3,1,"));
+        assert!(lines[2].starts_with("\"TXN,FE-4\",1,"));
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_write_parquet() {
+        let validator = TransactionValidator::new();
+        let vectors = FeatureExtractor::new(&validator)
+            .extract_all(&[create_test_transaction("TXN-FE-5", 10.0)]);
+        let mut out = Vec::new();
+        FeatureVector::write_parquet(&vectors, &mut out).unwrap();
+        assert_eq!(&out[..4], b"PAR1");
+    }
+}
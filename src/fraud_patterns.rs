@@ -1,16 +1,109 @@
 //! Advanced fraud detection patterns
+//!
+//! ## Concurrency
+//!
+//! [`FraudDetector`] is `Send + Sync` and scores through `&self`, so one
+//! instance can sit behind an `Arc` and serve many threads. History lives in
+//! a [`SharedHistory`] and per-user profiles behind their own lock; scoring
+//! takes read locks only, and recording takes each write lock briefly after
+//! scoring. Each score sees a consistent snapshot, but two transactions for
+//! the same key scored at the same moment may not see each other.
 
+use crate::clock::{system_clock, SharedClock};
+use crate::geographic_risk::country_distance_km;
+use crate::history::{HistoryKey, HistoryStore, SharedHistory};
 use crate::Transaction;
+use chrono::{DateTime, Timelike, Utc};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Metadata key naming the product a payment belongs to (e.g. `rent`)
+pub const PRODUCT_METADATA_KEY: &str = "product";
+
+/// Round-amount heuristic shared by [`FraudDetector`] and the validator
+///
+/// Amounts of at least `min_amount` that are an exact multiple of `modulus`
+/// score `severity` points, unless the transaction's
+/// [`PRODUCT_METADATA_KEY`] names an excluded fixed-amount product.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoundAmountRule {
+    pub modulus: f64,
+    pub min_amount: f64,
+    pub severity: u8,
+    /// Products (case-insensitive) that legitimately use round amounts
+    pub excluded_products: Vec<String>,
+}
+
+impl RoundAmountRule {
+    /// Rule with the given severity and the default modulus, minimum, and exclusions
+    pub fn with_severity(severity: u8) -> Self {
+        Self {
+            severity,
+            ..Default::default()
+        }
+    }
+
+    /// Check whether a transaction is a suspicious round amount
+    pub fn matches(&self, transaction: &Transaction) -> bool {
+        if transaction.amount < self.min_amount || self.modulus <= 0.0 {
+            return false;
+        }
+        if transaction.amount % self.modulus != 0.0 {
+            return false;
+        }
+        let product = transaction
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get(PRODUCT_METADATA_KEY));
+        !product.is_some_and(|p| {
+            self.excluded_products
+                .iter()
+                .any(|excluded| excluded.eq_ignore_ascii_case(p.trim()))
+        })
+    }
+}
+
+impl Default for RoundAmountRule {
+    fn default() -> Self {
+        Self {
+            modulus: 1000.0,
+            min_amount: 10000.0,
+            severity: 15,
+            excluded_products: vec!["rent".to_string(), "payroll".to_string()],
+        }
+    }
+}
+
+/// Suffix appended to flag descriptions for non-account history
+fn describe_key(key: HistoryKey) -> &'static str {
+    match key {
+        HistoryKey::Account => "",
+        HistoryKey::User => " (by user)",
+        HistoryKey::Counterparty => " (by counterparty)",
+    }
+}
 
 /// Fraud pattern detector
 pub struct FraudDetector {
-    /// Transaction history for velocity checks
-    history: HashMap<String, Vec<Transaction>>,
+    /// Transaction history for velocity checks, possibly shared with a validator
+    history: SharedHistory,
+    /// Per-user location and hour profiles
+    profiles: RwLock<UserProfiles>,
     /// High-risk countries
     high_risk_countries: Vec<String>,
     /// Suspicious amount thresholds
     thresholds: FraudThresholds,
+    clock: SharedClock,
+}
+
+/// Per-user state learned from observed transactions
+#[derive(Debug, Default)]
+struct UserProfiles {
+    /// Most recent country and time seen per user, for travel anomalies
+    last_location: HashMap<String, (DateTime<Utc>, String)>,
+    /// Transaction counts per UTC hour of day, per user
+    hour_profiles: HashMap<String, [u32; 24]>,
 }
 
 /// Fraud detection thresholds
@@ -22,8 +115,63 @@ pub struct FraudThresholds {
     pub max_transactions_per_hour: usize,
     /// Maximum total amount per day
     pub max_daily_total: f64,
-    /// Suspicious round amount threshold
-    pub round_amount_threshold: f64,
+    /// Suspicious round amount heuristic
+    pub round_amount: RoundAmountRule,
+    /// Sensitivity of the impossible-travel check
+    pub geographic_anomaly: GeographicAnomalyThresholds,
+    /// Sensitivity of the unusual-hour check
+    pub time_anomaly: TimeAnomalyThresholds,
+    /// Window in which a same-amount payment to the same counterparty is a duplicate
+    pub duplicate_window_minutes: i64,
+    /// Dimensions history is checked under
+    pub history_keys: Vec<HistoryKey>,
+    /// Half-life, in minutes, for recency-weighting history
+    ///
+    /// When set, velocity counts the last 24 hours with each transaction
+    /// weighted `0.5^(age / half_life)` instead of counting the last hour
+    /// equally, and the historical average amount is weighted the same way.
+    /// `None` disables decay.
+    pub decay_half_life_minutes: Option<f64>,
+}
+
+/// Unusual-hour sensitivity, learned from each user's own activity
+///
+/// Once a user has `min_history` transactions, a transaction is flagged when
+/// its UTC hour is at least `min_hours_from_active` hours (wrapping at
+/// midnight) from every hour the user has been active in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeAnomalyThresholds {
+    pub min_history: u32,
+    pub min_hours_from_active: u32,
+}
+
+impl Default for TimeAnomalyThresholds {
+    fn default() -> Self {
+        Self {
+            min_history: 10,
+            min_hours_from_active: 3,
+        }
+    }
+}
+
+/// Impossible-travel sensitivity for consecutive transactions of one user
+///
+/// Two transactions from countries at least `min_distance_km` apart are
+/// flagged when covering that distance in the time between them would need
+/// more than `max_travel_speed_kmh`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeographicAnomalyThresholds {
+    pub min_distance_km: f64,
+    pub max_travel_speed_kmh: f64,
+}
+
+impl Default for GeographicAnomalyThresholds {
+    fn default() -> Self {
+        Self {
+            min_distance_km: 1000.0,
+            max_travel_speed_kmh: 900.0,
+        }
+    }
 }
 
 impl Default for FraudThresholds {
@@ -32,20 +180,25 @@ impl Default for FraudThresholds {
             max_amount: 50000.0,
             max_transactions_per_hour: 10,
             max_daily_total: 100000.0,
-            round_amount_threshold: 10000.0,
+            round_amount: RoundAmountRule::default(),
+            geographic_anomaly: GeographicAnomalyThresholds::default(),
+            time_anomaly: TimeAnomalyThresholds::default(),
+            duplicate_window_minutes: 10,
+            history_keys: vec![HistoryKey::Account],
+            decay_half_life_minutes: None,
         }
     }
 }
 
 /// Fraud risk score (0-100)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FraudScore {
     pub score: u8,
     pub risk_level: RiskLevel,
     pub flags: Vec<FraudFlag>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum RiskLevel {
     Low,      // 0-25
     Medium,   // 26-50
@@ -53,14 +206,14 @@ pub enum RiskLevel {
     Critical, // 76-100
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FraudFlag {
     pub flag_type: FraudFlagType,
     pub description: String,
     pub severity: u8,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FraudFlagType {
     VelocityExceeded,
     UnusualAmount,
@@ -71,19 +224,30 @@ pub enum FraudFlagType {
     AmountProgression,
     TimeAnomaly,
     GeographicAnomaly,
+    DailyTotalLimit,
+}
+
+/// Origin country recorded in transaction metadata
+fn transaction_country(transaction: &Transaction) -> Option<&str> {
+    transaction
+        .metadata
+        .as_ref()
+        .and_then(|m| m.country.as_deref())
 }
 
 impl FraudDetector {
     /// Create new fraud detector
     pub fn new() -> Self {
         Self {
-            history: HashMap::new(),
+            history: SharedHistory::default(),
+            profiles: RwLock::default(),
             high_risk_countries: vec![
                 "KP".to_string(), // North Korea
                 "IR".to_string(), // Iran
                 "SY".to_string(), // Syria
             ],
             thresholds: FraudThresholds::default(),
+            clock: system_clock(),
         }
     }
 
@@ -94,44 +258,118 @@ impl FraudDetector {
         detector
     }
 
+    /// Read and record history in a shared store instead of a private one
+    pub fn with_history(mut self, history: SharedHistory) -> Self {
+        self.set_history(history);
+        self
+    }
+
+    /// Replace the history store the detector reads and records into
+    pub fn set_history(&mut self, history: SharedHistory) {
+        self.history = history;
+    }
+
+    /// Read the current time for history cleanup and daily totals from `clock`
+    pub fn set_clock(&mut self, clock: SharedClock) {
+        self.clock = clock;
+    }
+
+    /// Handle to the detector's history store
+    pub fn history(&self) -> &SharedHistory {
+        &self.history
+    }
+
     /// Calculate fraud score for transaction
-    pub fn calculate_fraud_score(&mut self, transaction: &Transaction) -> FraudScore {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "fraud_score",
+            skip_all,
+            fields(
+                transaction_id = %transaction.transaction_id,
+                score = tracing::field::Empty,
+            )
+        )
+    )]
+    pub fn calculate_fraud_score(&self, transaction: &Transaction) -> FraudScore {
+        let score = self.assess(transaction);
+        self.observe(transaction);
+        self.history.write().record(transaction);
+
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("score", score.score);
+
+        score
+    }
+
+    /// Score a transaction without recording it
+    ///
+    /// Pair with [`FraudDetector::observe`] and a history write when the
+    /// caller decides separately whether the transaction is kept, as the
+    /// validator does under its commit policy.
+    pub fn assess(&self, transaction: &Transaction) -> FraudScore {
+        let store = self.history.read();
+        let profiles = self.profiles();
         let mut score = 0u8;
         let mut flags = Vec::new();
 
         // Check velocity (transactions per hour)
-        if let Some(velocity_flag) = self.check_velocity(transaction) {
-            score += velocity_flag.severity;
+        if let Some(velocity_flag) = self.check_velocity(&store, transaction) {
+            score = score.saturating_add(velocity_flag.severity);
             flags.push(velocity_flag);
         }
 
         // Check unusual amounts
-        if let Some(amount_flag) = self.check_unusual_amount(transaction) {
-            score += amount_flag.severity;
+        if let Some(amount_flag) = self.check_unusual_amount(&store, transaction) {
+            score = score.saturating_add(amount_flag.severity);
             flags.push(amount_flag);
         }
 
         // Check round amounts (potential structuring)
         if let Some(round_flag) = self.check_round_amount(transaction) {
-            score += round_flag.severity;
+            score = score.saturating_add(round_flag.severity);
             flags.push(round_flag);
         }
 
         // Check high-risk countries
         if let Some(country_flag) = self.check_high_risk_country(transaction) {
-            score += country_flag.severity;
+            score = score.saturating_add(country_flag.severity);
             flags.push(country_flag);
         }
 
         // Check rapid succession
-        if let Some(rapid_flag) = self.check_rapid_succession(transaction) {
-            score += rapid_flag.severity;
+        if let Some(rapid_flag) = self.check_rapid_succession(&store, transaction) {
+            score = score.saturating_add(rapid_flag.severity);
             flags.push(rapid_flag);
         }
 
+        // Check impossible travel between consecutive transactions
+        if let Some(geo_flag) = self.check_geographic_anomaly(&profiles, transaction) {
+            score = score.saturating_add(geo_flag.severity);
+            flags.push(geo_flag);
+        }
+
+        // Check cumulative daily amount
+        if let Some(daily_flag) = self.check_daily_total(&store, transaction) {
+            score = score.saturating_add(daily_flag.severity);
+            flags.push(daily_flag);
+        }
+
+        // Check same-amount repeats to the same counterparty
+        if let Some(duplicate_flag) = self.check_duplicate(&store, transaction) {
+            score = score.saturating_add(duplicate_flag.severity);
+            flags.push(duplicate_flag);
+        }
+
+        // Check activity far outside the user's usual hours
+        if let Some(time_flag) = self.check_time_anomaly(&profiles, transaction) {
+            score = score.saturating_add(time_flag.severity);
+            flags.push(time_flag);
+        }
+
         // Check amount progression (potential testing)
-        if let Some(progression_flag) = self.check_amount_progression(transaction) {
-            score += progression_flag.severity;
+        if let Some(progression_flag) = self.check_amount_progression(&store, transaction) {
+            score = score.saturating_add(progression_flag.severity);
             flags.push(progression_flag);
         }
 
@@ -143,9 +381,6 @@ impl FraudDetector {
             _ => RiskLevel::Critical,
         };
 
-        // Add to history
-        self.add_to_history(transaction.clone());
-
         FraudScore {
             score: score.min(100),
             risk_level,
@@ -153,30 +388,83 @@ impl FraudDetector {
         }
     }
 
-    fn check_velocity(&self, transaction: &Transaction) -> Option<FraudFlag> {
-        let account = transaction.from_account.as_ref()?;
-        if let Some(history) = self.history.get(account) {
-            let one_hour_ago = transaction.timestamp - chrono::Duration::hours(1);
-            let recent = history
-                .iter()
-                .filter(|t| t.timestamp > one_hour_ago)
-                .count();
+    /// Update the per-user hour and location profiles
+    pub fn observe(&self, transaction: &Transaction) {
+        let mut profiles = self.profiles_mut();
+        profiles
+            .hour_profiles
+            .entry(transaction.user_id.clone())
+            .or_insert([0; 24])[transaction.timestamp.hour() as usize] += 1;
+        if let Some(country) = transaction_country(transaction) {
+            profiles.last_location.insert(
+                transaction.user_id.clone(),
+                (transaction.timestamp, country.to_string()),
+            );
+        }
+    }
 
-            if recent >= self.thresholds.max_transactions_per_hour {
-                return Some(FraudFlag {
-                    flag_type: FraudFlagType::VelocityExceeded,
-                    description: format!(
-                        "{} transactions in last hour (limit: {})",
-                        recent, self.thresholds.max_transactions_per_hour
-                    ),
-                    severity: 25,
-                });
+    /// Weight of history seen at `seen` when scoring a transaction at `at`
+    fn recency_weight(&self, at: DateTime<Utc>, seen: DateTime<Utc>) -> f64 {
+        match self.thresholds.decay_half_life_minutes {
+            Some(half_life) if half_life > 0.0 => {
+                let age_minutes = (at - seen).num_seconds().max(0) as f64 / 60.0;
+                0.5f64.powf(age_minutes / half_life)
             }
+            _ => 1.0,
         }
-        None
     }
 
-    fn check_unusual_amount(&self, transaction: &Transaction) -> Option<FraudFlag> {
+    fn check_velocity(&self, store: &HistoryStore, transaction: &Transaction) -> Option<FraudFlag> {
+        let decayed = self.thresholds.decay_half_life_minutes.is_some();
+        let lookback = if decayed {
+            chrono::Duration::hours(24)
+        } else {
+            chrono::Duration::hours(1)
+        };
+        let since = transaction
+            .timestamp
+            .checked_sub_signed(lookback)
+            .unwrap_or(chrono::DateTime::<chrono::Utc>::MIN_UTC);
+        let limit = self.thresholds.max_transactions_per_hour;
+        self.histories(store, transaction)
+            .find_map(|(key, history)| {
+                let recent: f64 = history
+                    .iter()
+                    .filter(|t| t.timestamp > since)
+                    .map(|t| self.recency_weight(transaction.timestamp, t.timestamp))
+                    .sum();
+                if recent < limit as f64 {
+                    return None;
+                }
+
+                let description = if decayed {
+                    format!(
+                        "{:.1} recency-weighted transactions (limit: {}){}",
+                        recent,
+                        limit,
+                        describe_key(key)
+                    )
+                } else {
+                    format!(
+                        "{} transactions in last hour (limit: {}){}",
+                        recent,
+                        limit,
+                        describe_key(key)
+                    )
+                };
+                Some(FraudFlag {
+                    flag_type: FraudFlagType::VelocityExceeded,
+                    description,
+                    severity: 25,
+                })
+            })
+    }
+
+    fn check_unusual_amount(
+        &self,
+        store: &HistoryStore,
+        transaction: &Transaction,
+    ) -> Option<FraudFlag> {
         if transaction.amount > self.thresholds.max_amount {
             return Some(FraudFlag {
                 flag_type: FraudFlagType::UnusualAmount,
@@ -189,38 +477,38 @@ impl FraudDetector {
         }
 
         // Check against historical average
-        if let Some(account) = &transaction.from_account {
-            if let Some(history) = self.history.get(account) {
-                if !history.is_empty() {
-                    let avg: f64 =
-                        history.iter().map(|t| t.amount).sum::<f64>() / history.len() as f64;
-                    if transaction.amount > avg * 5.0 {
-                        return Some(FraudFlag {
-                            flag_type: FraudFlagType::UnusualAmount,
-                            description: format!(
-                                "Amount {} is 5x higher than average {}",
-                                transaction.amount, avg
-                            ),
-                            severity: 20,
-                        });
-                    }
+        self.histories(store, transaction)
+            .find_map(|(key, history)| {
+                if history.is_empty() {
+                    return None;
                 }
-            }
-        }
-        None
+                let (weighted, weights) = history.iter().fold((0.0, 0.0), |(sum, total), t| {
+                    let weight = self.recency_weight(transaction.timestamp, t.timestamp);
+                    (sum + t.amount * weight, total + weight)
+                });
+                let avg: f64 = weighted / weights;
+                (transaction.amount > avg * 5.0).then(|| FraudFlag {
+                    flag_type: FraudFlagType::UnusualAmount,
+                    description: format!(
+                        "Amount {} is 5x higher than average {}{}",
+                        transaction.amount,
+                        avg,
+                        describe_key(key)
+                    ),
+                    severity: 20,
+                })
+            })
     }
 
     fn check_round_amount(&self, transaction: &Transaction) -> Option<FraudFlag> {
-        if transaction.amount >= self.thresholds.round_amount_threshold
-            && transaction.amount % 1000.0 == 0.0
-        {
+        if self.thresholds.round_amount.matches(transaction) {
             return Some(FraudFlag {
                 flag_type: FraudFlagType::RoundAmount,
                 description: format!(
                     "Suspicious round amount: {} (potential structuring)",
                     transaction.amount
                 ),
-                severity: 15,
+                severity: self.thresholds.round_amount.severity,
             });
         }
         None
@@ -228,7 +516,7 @@ impl FraudDetector {
 
     fn check_high_risk_country(&self, transaction: &Transaction) -> Option<FraudFlag> {
         if let Some(ref metadata) = transaction.metadata {
-            if let Some(country) = metadata.get("country") {
+            if let Some(ref country) = metadata.country {
                 if self.high_risk_countries.contains(country) {
                     return Some(FraudFlag {
                         flag_type: FraudFlagType::HighRiskCountry,
@@ -241,88 +529,267 @@ impl FraudDetector {
         None
     }
 
-    fn check_rapid_succession(&self, transaction: &Transaction) -> Option<FraudFlag> {
-        if let Some(account) = &transaction.from_account {
-            if let Some(history) = self.history.get(account) {
-                if let Some(last) = history.last() {
-                    let time_diff = transaction.timestamp - last.timestamp;
-                    if time_diff < chrono::Duration::seconds(30) {
-                        return Some(FraudFlag {
-                            flag_type: FraudFlagType::RapidSuccession,
-                            description: format!(
-                                "Transaction within {} seconds of previous",
-                                time_diff.num_seconds()
-                            ),
-                            severity: 10,
-                        });
-                    }
-                }
-            }
+    fn check_geographic_anomaly(
+        &self,
+        profiles: &UserProfiles,
+        transaction: &Transaction,
+    ) -> Option<FraudFlag> {
+        let country = transaction_country(transaction)?;
+        let (last_time, last_country) = profiles.last_location.get(&transaction.user_id)?;
+        if last_country.eq_ignore_ascii_case(country) {
+            return None;
+        }
+        let distance = country_distance_km(last_country, country)?;
+        let limits = &self.thresholds.geographic_anomaly;
+        if distance < limits.min_distance_km {
+            return None;
         }
-        None
-    }
 
-    fn check_amount_progression(&self, transaction: &Transaction) -> Option<FraudFlag> {
-        if let Some(account) = &transaction.from_account {
-            if let Some(history) = self.history.get(account) {
-                if history.len() >= 3 {
-                    let last_three: Vec<f64> =
-                        history.iter().rev().take(3).map(|t| t.amount).collect();
-                    // Check if amounts are incrementing (potential testing pattern)
-                    if last_three.windows(2).all(|w| w[0] < w[1]) {
-                        return Some(FraudFlag {
-                            flag_type: FraudFlagType::AmountProgression,
-                            description:
-                                "Incrementing amounts detected (potential account testing)"
-                                    .to_string(),
-                            severity: 20,
-                        });
-                    }
-                }
-            }
+        let hours = (transaction.timestamp - *last_time).num_seconds().abs() as f64 / 3600.0;
+        let required_speed = if hours > 0.0 {
+            distance / hours
+        } else {
+            f64::INFINITY
+        };
+        if required_speed <= limits.max_travel_speed_kmh {
+            return None;
         }
-        None
+
+        Some(FraudFlag {
+            flag_type: FraudFlagType::GeographicAnomaly,
+            description: format!(
+                "Moved {} -> {} ({:.0} km) in {:.1} hours",
+                last_country, country, distance, hours
+            ),
+            severity: 25,
+        })
     }
 
-    fn add_to_history(&mut self, transaction: Transaction) {
-        if let Some(account) = transaction.from_account.clone() {
-            self.history.entry(account).or_default().push(transaction);
+    /// Share of the daily cap at which the daily-total flag starts
+    const DAILY_TOTAL_WARNING_RATIO: f64 = 0.8;
+
+    fn check_daily_total(
+        &self,
+        store: &HistoryStore,
+        transaction: &Transaction,
+    ) -> Option<FraudFlag> {
+        let cap = self.thresholds.max_daily_total;
+        if cap <= 0.0 {
+            return None;
         }
+        self.histories(store, transaction)
+            .find_map(|(key, history)| {
+                let total = daily_total_at(&history, transaction.timestamp) + transaction.amount;
+                let ratio = total / cap;
+                if ratio < Self::DAILY_TOTAL_WARNING_RATIO {
+                    return None;
+                }
+
+                // 16 points at 80% of the cap, 20 at the cap, up to 40 at twice the cap
+                let severity = (ratio.min(2.0) * 20.0).round() as u8;
+                let description = if ratio >= 1.0 {
+                    format!(
+                        "Daily total {:.2} exceeds cap {:.2}{}",
+                        total,
+                        cap,
+                        describe_key(key)
+                    )
+                } else {
+                    format!(
+                        "Daily total {:.2} is {:.0}% of cap {:.2}{}",
+                        total,
+                        ratio * 100.0,
+                        cap,
+                        describe_key(key)
+                    )
+                };
+                Some(FraudFlag {
+                    flag_type: FraudFlagType::DailyTotalLimit,
+                    description,
+                    severity,
+                })
+            })
+    }
+
+    fn check_duplicate(
+        &self,
+        store: &HistoryStore,
+        transaction: &Transaction,
+    ) -> Option<FraudFlag> {
+        let window = chrono::Duration::minutes(self.thresholds.duplicate_window_minutes);
+        self.histories(store, transaction)
+            .find_map(|(key, history)| {
+                let original = history.iter().rev().find(|t| {
+                    (transaction.timestamp - t.timestamp).abs() <= window
+                        && (t.amount - transaction.amount).abs() < 0.005
+                        && t.currency == transaction.currency
+                        && t.to_account == transaction.to_account
+                })?;
+
+                Some(FraudFlag {
+                    flag_type: FraudFlagType::DuplicateTransaction,
+                    description: format!(
+                        "Same amount to the same counterparty as {} within {} minutes{}",
+                        original.transaction_id,
+                        self.thresholds.duplicate_window_minutes,
+                        describe_key(key)
+                    ),
+                    severity: 20,
+                })
+            })
     }
 
-    /// Clear old history (keep last 24 hours)
-    pub fn cleanup_history(&mut self) {
-        let now = chrono::Utc::now();
-        let cutoff = now - chrono::Duration::hours(24);
+    fn check_time_anomaly(
+        &self,
+        profiles: &UserProfiles,
+        transaction: &Transaction,
+    ) -> Option<FraudFlag> {
+        let profile = profiles.hour_profiles.get(&transaction.user_id)?;
+        let limits = &self.thresholds.time_anomaly;
+        if profile.iter().sum::<u32>() < limits.min_history.max(1) {
+            return None;
+        }
 
-        for transactions in self.history.values_mut() {
-            transactions.retain(|t| t.timestamp > cutoff);
+        let hour = transaction.timestamp.hour();
+        let nearest = (0..24u32)
+            .filter(|h| profile[*h as usize] > 0)
+            .map(|h| {
+                let diff = h.abs_diff(hour);
+                diff.min(24 - diff)
+            })
+            .min()?;
+        if nearest < limits.min_hours_from_active {
+            return None;
         }
 
-        // Remove empty entries
-        self.history.retain(|_, v| !v.is_empty());
+        Some(FraudFlag {
+            flag_type: FraudFlagType::TimeAnomaly,
+            description: format!(
+                "Transaction at {:02}:00 UTC is {} hours from the user's usual activity",
+                hour, nearest
+            ),
+            severity: 15,
+        })
+    }
+
+    fn check_rapid_succession(
+        &self,
+        store: &HistoryStore,
+        transaction: &Transaction,
+    ) -> Option<FraudFlag> {
+        self.histories(store, transaction)
+            .find_map(|(key, history)| {
+                let last = history.last()?;
+                let time_diff = transaction.timestamp - last.timestamp;
+                (time_diff < chrono::Duration::seconds(30)).then(|| FraudFlag {
+                    flag_type: FraudFlagType::RapidSuccession,
+                    description: format!(
+                        "Transaction within {} seconds of previous{}",
+                        time_diff.num_seconds(),
+                        describe_key(key)
+                    ),
+                    severity: 10,
+                })
+            })
+    }
+
+    fn check_amount_progression(
+        &self,
+        store: &HistoryStore,
+        transaction: &Transaction,
+    ) -> Option<FraudFlag> {
+        self.histories(store, transaction)
+            .find_map(|(key, history)| {
+                if history.len() < 3 {
+                    return None;
+                }
+                let last_three: Vec<f64> = history.iter().rev().take(3).map(|t| t.amount).collect();
+                // Check if amounts are incrementing (potential testing pattern)
+                last_three
+                    .windows(2)
+                    .all(|w| w[0] < w[1])
+                    .then(|| FraudFlag {
+                        flag_type: FraudFlagType::AmountProgression,
+                        description: format!(
+                            "Incrementing amounts detected (potential account testing){}",
+                            describe_key(key)
+                        ),
+                        severity: 20,
+                    })
+            })
+    }
+
+    /// Histories of the transaction under each configured key
+    ///
+    /// The transaction's own entry is left out, so a follow-up run after it
+    /// was committed does not match it against itself.
+    fn histories<'a>(
+        &'a self,
+        store: &'a HistoryStore,
+        transaction: &'a Transaction,
+    ) -> impl Iterator<Item = (HistoryKey, Vec<Arc<Transaction>>)> + 'a {
+        self.thresholds.history_keys.iter().filter_map(move |key| {
+            let history: Vec<Arc<Transaction>> = store
+                .get(*key, key.id(transaction)?)
+                .iter()
+                .filter(|t| t.transaction_id != transaction.transaction_id)
+                .cloned()
+                .collect();
+            (!history.is_empty()).then_some((*key, history))
+        })
+    }
+
+    /// Clear history older than the store's retention window
+    pub fn cleanup_history(&self) {
+        let now = self.clock.now();
+        let mut history = self.history.write();
+        history.prune(now);
+
+        let cutoff = now - history.retention();
+        self.profiles_mut()
+            .last_location
+            .retain(|_, (seen, _)| *seen >= cutoff);
+    }
+
+    // A panic while a profile lock was held leaves the maps usable, so
+    // poisoning is ignored as for `SharedHistory`
+    fn profiles(&self) -> RwLockReadGuard<'_, UserProfiles> {
+        self.profiles.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn profiles_mut(&self) -> RwLockWriteGuard<'_, UserProfiles> {
+        self.profiles.write().unwrap_or_else(|e| e.into_inner())
     }
 
     /// Get transaction count for account
     pub fn get_transaction_count(&self, account: &str) -> usize {
-        self.history.get(account).map_or(0, |h| h.len())
+        self.get_history_count(HistoryKey::Account, account)
+    }
+
+    /// Get transaction count recorded under a history key
+    pub fn get_history_count(&self, key: HistoryKey, id: &str) -> usize {
+        self.history.read().get(key, id).len()
     }
 
     /// Get daily total for account
     pub fn get_daily_total(&self, account: &str) -> f64 {
-        if let Some(history) = self.history.get(account) {
-            let one_day_ago = chrono::Utc::now() - chrono::Duration::hours(24);
-            history
-                .iter()
-                .filter(|t| t.timestamp > one_day_ago)
-                .map(|t| t.amount)
-                .sum()
-        } else {
-            0.0
-        }
+        daily_total_at(
+            self.history.read().get(HistoryKey::Account, account),
+            self.clock.now(),
+        )
     }
 }
 
+/// Total amount in the 24 hours up to `at`
+fn daily_total_at(history: &[Arc<Transaction>], at: DateTime<Utc>) -> f64 {
+    let one_day_ago = at - chrono::Duration::hours(24);
+    history
+        .iter()
+        .filter(|t| t.timestamp > one_day_ago && t.timestamp <= at)
+        .map(|t| t.amount)
+        .sum()
+}
+
 impl Default for FraudDetector {
     fn default() -> Self {
         Self::new()
@@ -332,25 +799,30 @@ impl Default for FraudDetector {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::Utc;
+    use crate::test_fixtures::{self, test_now};
+    use crate::{FixedClock, TransactionMetadata};
 
+    /// Each call gets a fresh ID; history scans skip matching IDs
     fn create_test_transaction(amount: f64) -> Transaction {
+        static NEXT_ID: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(1);
+        let id = NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
         Transaction {
-            transaction_id: "TXN-001".to_string(),
             from_account: Some("ACC-123".to_string()),
             to_account: Some("ACC-456".to_string()),
-            amount,
-            currency: "USD".to_string(),
-            timestamp: Utc::now(),
-            transaction_type: crate::TransactionType::Transfer,
-            user_id: "USER-001".to_string(),
-            metadata: None,
+            ..test_fixtures::create_test_transaction(&format!("TXN-{:03}", id), amount)
         }
     }
 
+    /// Detector whose clock stands still at the fixtures' timestamp
+    fn fixed_detector(thresholds: FraudThresholds) -> FraudDetector {
+        let mut detector = FraudDetector::with_thresholds(thresholds);
+        detector.set_clock(Arc::new(FixedClock(test_now())));
+        detector
+    }
+
     #[test]
     fn test_low_risk_transaction() {
-        let mut detector = FraudDetector::new();
+        let detector = fixed_detector(FraudThresholds::default());
         let txn = create_test_transaction(100.0);
         let score = detector.calculate_fraud_score(&txn);
 
@@ -360,7 +832,7 @@ mod tests {
 
     #[test]
     fn test_high_amount_detection() {
-        let mut detector = FraudDetector::new();
+        let detector = fixed_detector(FraudThresholds::default());
         let txn = create_test_transaction(60000.0);
         let score = detector.calculate_fraud_score(&txn);
 
@@ -373,7 +845,7 @@ mod tests {
 
     #[test]
     fn test_round_amount_detection() {
-        let mut detector = FraudDetector::new();
+        let detector = fixed_detector(FraudThresholds::default());
         let txn = create_test_transaction(15000.0);
         let score = detector.calculate_fraud_score(&txn);
 
@@ -383,9 +855,282 @@ mod tests {
             .any(|f| f.flag_type == FraudFlagType::RoundAmount));
     }
 
+    #[test]
+    fn test_flag_severities_saturate() {
+        let detector = fixed_detector(FraudThresholds {
+            round_amount: RoundAmountRule::with_severity(250),
+            ..Default::default()
+        });
+        let score = detector.assess(&create_test_transaction(60_000.0));
+        assert_eq!(score.score, 100);
+        assert_eq!(score.risk_level, RiskLevel::Critical);
+    }
+
+    #[test]
+    fn test_round_amount_rule_is_configurable() {
+        let mut rent = create_test_transaction(15000.0);
+        rent.metadata = Some(TransactionMetadata::from([(
+            PRODUCT_METADATA_KEY.to_string(),
+            "Rent".to_string(),
+        )]));
+        let rule = RoundAmountRule::default();
+        assert!(rule.matches(&create_test_transaction(15000.0)));
+        assert!(!rule.matches(&rent), "excluded product");
+
+        let detector = fixed_detector(FraudThresholds {
+            round_amount: RoundAmountRule {
+                modulus: 500.0,
+                min_amount: 1000.0,
+                severity: 5,
+                excluded_products: Vec::new(),
+            },
+            ..Default::default()
+        });
+        let score = detector.calculate_fraud_score(&create_test_transaction(1500.0));
+        let flag = score
+            .flags
+            .iter()
+            .find(|f| f.flag_type == FraudFlagType::RoundAmount)
+            .expect("round-amount flag");
+        assert_eq!(flag.severity, 5);
+    }
+
+    #[test]
+    fn test_geographic_anomaly() {
+        let located = |id: &str, country: &str, minutes: i64| {
+            let mut txn = create_test_transaction(100.0);
+            txn.transaction_id = id.to_string();
+            txn.timestamp = test_now() + chrono::Duration::minutes(minutes);
+            txn.metadata = Some(TransactionMetadata::from([(
+                "country".to_string(),
+                country.to_string(),
+            )]));
+            txn
+        };
+        let has_geo_flag = |score: &FraudScore| {
+            score
+                .flags
+                .iter()
+                .any(|f| f.flag_type == FraudFlagType::GeographicAnomaly)
+        };
+
+        let detector = fixed_detector(FraudThresholds::default());
+        assert!(!has_geo_flag(
+            &detector.calculate_fraud_score(&located("TXN-1", "DE", 0))
+        ));
+        // Neighbouring country, or a distant one after enough time, is plausible
+        assert!(!has_geo_flag(
+            &detector.calculate_fraud_score(&located("TXN-2", "NL", 10))
+        ));
+        assert!(!has_geo_flag(&detector.calculate_fraud_score(&located(
+            "TXN-3",
+            "GB",
+            24 * 60
+        ))));
+        // Singapore thirty minutes after the UK is not
+        assert!(has_geo_flag(&detector.calculate_fraud_score(&located(
+            "TXN-4",
+            "SG",
+            24 * 60 + 30
+        ))));
+    }
+
+    #[test]
+    fn test_time_anomaly_uses_user_hours() {
+        let day_start = test_now()
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            .and_utc();
+        let at_hour = |id: String, day: i64, hour: i64| {
+            let mut txn = create_test_transaction(100.0);
+            txn.transaction_id = id;
+            txn.timestamp = day_start - chrono::Duration::days(day) + chrono::Duration::hours(hour);
+            txn
+        };
+        let has_time_flag = |score: &FraudScore| {
+            score
+                .flags
+                .iter()
+                .any(|f| f.flag_type == FraudFlagType::TimeAnomaly)
+        };
+
+        let detector = fixed_detector(FraudThresholds::default());
+        // Ten days of activity between 09:00 and 11:00
+        for day in 0..10 {
+            let score = detector.calculate_fraud_score(&at_hour(
+                format!("TXN-D{}", day),
+                20 - day,
+                9 + day % 3,
+            ));
+            assert!(!has_time_flag(&score));
+        }
+
+        assert!(!has_time_flag(&detector.calculate_fraud_score(&at_hour(
+            "TXN-NOON".to_string(),
+            5,
+            12
+        ))));
+        assert!(has_time_flag(&detector.calculate_fraud_score(&at_hour(
+            "TXN-NIGHT".to_string(),
+            4,
+            3
+        ))));
+    }
+
+    #[test]
+    fn test_duplicate_detection() {
+        let is_duplicate = |score: &FraudScore| {
+            score
+                .flags
+                .iter()
+                .any(|f| f.flag_type == FraudFlagType::DuplicateTransaction)
+        };
+        let detector = fixed_detector(FraudThresholds::default());
+        let first = create_test_transaction(250.0);
+        assert!(!is_duplicate(&detector.calculate_fraud_score(&first)));
+
+        let mut repeat = first.clone();
+        repeat.transaction_id = "TXN-002".to_string();
+        repeat.timestamp = first.timestamp + chrono::Duration::minutes(2);
+        assert!(is_duplicate(&detector.calculate_fraud_score(&repeat)));
+
+        let mut other_payee = repeat.clone();
+        other_payee.transaction_id = "TXN-003".to_string();
+        other_payee.to_account = Some("ACC-789".to_string());
+        assert!(!is_duplicate(&detector.calculate_fraud_score(&other_payee)));
+
+        let mut later = first.clone();
+        later.transaction_id = "TXN-004".to_string();
+        later.timestamp = first.timestamp + chrono::Duration::hours(1);
+        assert!(!is_duplicate(&detector.calculate_fraud_score(&later)));
+    }
+
+    #[test]
+    fn test_daily_total_flag_scales_with_cap() {
+        let daily_flag = |score: &FraudScore| {
+            score
+                .flags
+                .iter()
+                .find(|f| f.flag_type == FraudFlagType::DailyTotalLimit)
+                .map(|f| f.severity)
+        };
+        let detector = fixed_detector(FraudThresholds {
+            max_daily_total: 10_000.0,
+            ..Default::default()
+        });
+        let payment = |id: &str, amount: f64, minutes: i64| {
+            let mut txn = create_test_transaction(amount);
+            txn.transaction_id = id.to_string();
+            txn.to_account = Some(format!("ACC-{}", id));
+            txn.timestamp =
+                test_now() - chrono::Duration::hours(2) + chrono::Duration::minutes(minutes);
+            txn
+        };
+
+        assert_eq!(
+            daily_flag(&detector.calculate_fraud_score(&payment("A", 4_000.0, 0))),
+            None
+        );
+        assert_eq!(
+            daily_flag(&detector.calculate_fraud_score(&payment("B", 4_500.0, 5))),
+            Some(17)
+        );
+        assert_eq!(
+            daily_flag(&detector.calculate_fraud_score(&payment("C", 3_500.0, 10))),
+            Some(24)
+        );
+        assert_eq!(detector.get_daily_total("ACC-123"), 12_000.0);
+    }
+
+    #[test]
+    fn test_recency_decay() {
+        let thresholds = FraudThresholds {
+            max_transactions_per_hour: 5,
+            decay_half_life_minutes: Some(60.0),
+            ..Default::default()
+        };
+        let now = test_now();
+        let burst = |detector: &FraudDetector, minutes_ago: i64| {
+            for i in 0..8 {
+                let mut txn = create_test_transaction(100.0);
+                txn.timestamp =
+                    now - chrono::Duration::minutes(minutes_ago) + chrono::Duration::seconds(i);
+                detector.calculate_fraud_score(&txn);
+            }
+        };
+        let is_velocity = |score: &FraudScore| {
+            score
+                .flags
+                .iter()
+                .any(|f| f.flag_type == FraudFlagType::VelocityExceeded)
+        };
+
+        let recent = fixed_detector(thresholds.clone());
+        burst(&recent, 2);
+        assert!(is_velocity(&recent.assess(&create_test_transaction(100.0))));
+
+        let stale = fixed_detector(thresholds.clone());
+        burst(&stale, 6 * 60);
+        assert!(!is_velocity(&stale.assess(&create_test_transaction(100.0))));
+
+        // Yesterday's large payments barely move the average of today's small ones
+        let detector = fixed_detector(thresholds);
+        for (amount, hours_ago) in [(10_000.0, 12), (10_000.0, 12), (100.0, 1), (100.0, 1)] {
+            let mut txn = create_test_transaction(amount);
+            txn.timestamp = now - chrono::Duration::hours(hours_ago);
+            detector.calculate_fraud_score(&txn);
+        }
+        let score = detector.assess(&create_test_transaction(1000.0));
+        assert!(score
+            .flags
+            .iter()
+            .any(|f| f.flag_type == FraudFlagType::UnusualAmount));
+    }
+
+    #[test]
+    fn test_user_keyed_history_covers_deposits() {
+        let deposit = |id: &str| {
+            let mut txn = create_test_transaction(500.0);
+            txn.transaction_id = id.to_string();
+            txn.transaction_type = crate::TransactionType::Deposit;
+            txn.from_account = None;
+            txn
+        };
+        let thresholds = FraudThresholds {
+            max_transactions_per_hour: 2,
+            ..Default::default()
+        };
+
+        let by_account = fixed_detector(thresholds.clone());
+        let by_user = fixed_detector(FraudThresholds {
+            history_keys: vec![HistoryKey::Account, HistoryKey::User],
+            ..thresholds
+        });
+        for i in 0..2 {
+            by_account.calculate_fraud_score(&deposit(&format!("TXN-DEP-{}", i)));
+            by_user.calculate_fraud_score(&deposit(&format!("TXN-DEP-{}", i)));
+        }
+
+        let is_velocity = |score: FraudScore| {
+            score
+                .flags
+                .iter()
+                .any(|f| f.flag_type == FraudFlagType::VelocityExceeded)
+        };
+        assert!(!is_velocity(
+            by_account.calculate_fraud_score(&deposit("TXN-DEP-X"))
+        ));
+        assert!(is_velocity(
+            by_user.calculate_fraud_score(&deposit("TXN-DEP-Y"))
+        ));
+        assert_eq!(by_user.get_history_count(HistoryKey::User, "USER-001"), 3);
+        assert_eq!(by_user.get_transaction_count("ACC-123"), 0);
+    }
+
     #[test]
     fn test_velocity_detection() {
-        let mut detector = FraudDetector::with_thresholds(FraudThresholds {
+        let detector = fixed_detector(FraudThresholds {
             max_transactions_per_hour: 2,
             ..Default::default()
         });
@@ -420,11 +1165,12 @@ mod tests {
 
     #[test]
     fn test_high_risk_country() {
-        let mut detector = FraudDetector::new();
+        let detector = fixed_detector(FraudThresholds::default());
         let mut txn = create_test_transaction(1000.0);
-        let mut metadata = std::collections::HashMap::new();
-        metadata.insert("country".to_string(), "IR".to_string());
-        txn.metadata = Some(metadata);
+        txn.metadata = Some(TransactionMetadata {
+            country: Some("IR".to_string()),
+            ..Default::default()
+        });
 
         let score = detector.calculate_fraud_score(&txn);
         assert!(score
@@ -433,9 +1179,39 @@ mod tests {
             .any(|f| f.flag_type == FraudFlagType::HighRiskCountry));
     }
 
+    #[test]
+    fn test_detector_is_shareable_across_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<FraudDetector>();
+
+        let detector = fixed_detector(FraudThresholds {
+            history_keys: vec![HistoryKey::User],
+            ..Default::default()
+        });
+        std::thread::scope(|scope| {
+            for worker in 0..4 {
+                let detector = &detector;
+                scope.spawn(move || {
+                    for i in 0..25 {
+                        let mut txn = create_test_transaction(100.0);
+                        txn.transaction_id = format!("TXN-{}-{}", worker, i);
+                        txn.user_id = format!("USER-{}", worker);
+                        detector.calculate_fraud_score(&txn);
+                    }
+                });
+            }
+        });
+
+        for worker in 0..4 {
+            let user = format!("USER-{}", worker);
+            assert_eq!(detector.get_history_count(HistoryKey::User, &user), 25);
+        }
+        assert_eq!(detector.get_transaction_count("ACC-123"), 100);
+    }
+
     #[test]
     fn test_history_cleanup() {
-        let mut detector = FraudDetector::new();
+        let detector = fixed_detector(FraudThresholds::default());
         for _ in 0..10 {
             let txn = create_test_transaction(100.0);
             detector.calculate_fraud_score(&txn);
@@ -449,7 +1225,7 @@ mod tests {
 
     #[test]
     fn test_daily_total() {
-        let mut detector = FraudDetector::new();
+        let detector = fixed_detector(FraudThresholds::default());
         detector.calculate_fraud_score(&create_test_transaction(1000.0));
         detector.calculate_fraud_score(&create_test_transaction(2000.0));
         detector.calculate_fraud_score(&create_test_transaction(1500.0));
@@ -2,6 +2,8 @@
 //!
 //! Provides country and jurisdiction-based risk assessment.
 
+use crate::ReferenceDataset;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -86,19 +88,51 @@ impl JurisdictionRisk {
 pub struct GeographicRiskScorer {
     country_risks: HashMap<String, CountryRisk>,
     jurisdiction_risks: HashMap<String, JurisdictionRisk>,
+    country_risk_published_at: Option<DateTime<Utc>>,
+    fatf_published_at: Option<DateTime<Utc>>,
 }
 
 impl GeographicRiskScorer {
     /// Create a new geographic risk scorer
     pub fn new() -> Self {
-        let mut scorer = Self {
-            country_risks: HashMap::new(),
-            jurisdiction_risks: HashMap::new(),
-        };
+        let mut scorer = Self::empty();
         scorer.load_default_risks();
         scorer
     }
 
+    /// Create a scorer with no risk data, to be filled by a loader
+    pub fn empty() -> Self {
+        Self {
+            country_risks: HashMap::new(),
+            jurisdiction_risks: HashMap::new(),
+            country_risk_published_at: None,
+            fatf_published_at: None,
+        }
+    }
+
+    /// Record when the loaded country risk table was published
+    pub fn set_country_risk_published_at(&mut self, published_at: DateTime<Utc>) {
+        self.country_risk_published_at = Some(published_at);
+    }
+
+    /// Record when the loaded FATF statuses were published
+    pub fn set_fatf_published_at(&mut self, published_at: DateTime<Utc>) {
+        self.fatf_published_at = Some(published_at);
+    }
+
+    /// Loaded datasets and their publication dates
+    pub fn datasets(&self) -> Vec<ReferenceDataset> {
+        vec![
+            ReferenceDataset::new("COUNTRY_RISK", self.country_risk_published_at),
+            ReferenceDataset::new("FATF", self.fatf_published_at),
+        ]
+    }
+
+    /// Check whether no country risk data is loaded
+    pub fn is_empty(&self) -> bool {
+        self.country_risks.is_empty()
+    }
+
     /// Load default country and jurisdiction risks
     fn load_default_risks(&mut self) {
         // High-risk countries (simplified list)
@@ -264,6 +298,14 @@ impl GeographicRiskScorer {
     }
 
     /// Calculate transaction risk based on origin and destination countries
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "geographic_risk",
+            skip(self),
+            fields(combined_score = tracing::field::Empty)
+        )
+    )]
     pub fn calculate_transaction_risk(
         &self,
         origin: &str,
@@ -284,6 +326,9 @@ impl GeographicRiskScorer {
         let requires_edd = origin_risk.is_some_and(|r| r.requires_edd())
             || dest_risk.is_some_and(|r| r.requires_edd());
 
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("combined_score", combined_score);
+
         let risk_level = if is_prohibited {
             CountryRiskLevel::Prohibited
         } else if combined_score >= 70 {
@@ -335,6 +380,88 @@ impl Default for GeographicRiskScorer {
     }
 }
 
+/// Countries and regions (ISO 3166-1 / 3166-2) under comprehensive sanctions,
+/// with the program imposing them
+///
+/// Transactions touching these are blocked outright, regardless of any
+/// per-country risk scoring or name match.
+pub const COMPREHENSIVE_SANCTIONS: &[(&str, &str)] = &[
+    ("CU", "Cuba"),
+    ("IR", "Iran"),
+    ("KP", "North Korea"),
+    ("SY", "Syria"),
+    ("UA-09", "Luhansk region of Ukraine"),
+    ("UA-14", "Donetsk region of Ukraine"),
+    ("UA-43", "Crimea region of Ukraine"),
+];
+
+/// Comprehensive sanctions program covering a country or region code, if any
+pub fn comprehensive_sanctions_program(code: &str) -> Option<&'static str> {
+    let code = code.trim();
+    COMPREHENSIVE_SANCTIONS
+        .iter()
+        .find(|(c, _)| c.eq_ignore_ascii_case(code))
+        .map(|(_, program)| *program)
+}
+
+/// Approximate centroids (latitude, longitude) of commonly seen countries
+const COUNTRY_CENTROIDS: &[(&str, f64, f64)] = &[
+    ("AE", 23.4, 53.8),
+    ("AR", -38.4, -63.6),
+    ("AU", -25.3, 133.8),
+    ("BR", -14.2, -51.9),
+    ("CA", 56.1, -106.3),
+    ("CH", 46.8, 8.2),
+    ("CN", 35.9, 104.2),
+    ("DE", 51.2, 10.5),
+    ("EG", 26.8, 30.8),
+    ("ES", 40.5, -3.7),
+    ("FR", 46.2, 2.2),
+    ("GB", 55.4, -3.4),
+    ("HK", 22.3, 114.2),
+    ("ID", -0.8, 113.9),
+    ("IE", 53.4, -8.2),
+    ("IN", 20.6, 79.0),
+    ("IR", 32.4, 53.7),
+    ("IT", 41.9, 12.6),
+    ("JP", 36.2, 138.3),
+    ("KP", 40.3, 127.5),
+    ("KR", 35.9, 127.8),
+    ("MM", 21.9, 95.9),
+    ("MX", 23.6, -102.6),
+    ("NG", 9.1, 8.7),
+    ("NL", 52.1, 5.3),
+    ("PK", 30.4, 69.3),
+    ("RU", 61.5, 105.3),
+    ("SA", 23.9, 45.1),
+    ("SG", 1.4, 103.8),
+    ("SY", 34.8, 39.0),
+    ("TR", 39.0, 35.2),
+    ("US", 37.1, -95.7),
+    ("YE", 15.6, 48.5),
+    ("ZA", -30.6, 22.9),
+];
+
+/// Approximate centroid (latitude, longitude) of a country
+pub fn country_centroid(country_code: &str) -> Option<(f64, f64)> {
+    COUNTRY_CENTROIDS
+        .iter()
+        .find(|(code, _, _)| code.eq_ignore_ascii_case(country_code))
+        .map(|&(_, lat, lon)| (lat, lon))
+}
+
+/// Great-circle distance between two country centroids in kilometres
+pub fn country_distance_km(from: &str, to: &str) -> Option<f64> {
+    const EARTH_RADIUS_KM: f64 = 6371.0;
+    let (lat1, lon1) = country_centroid(from)?;
+    let (lat2, lon2) = country_centroid(to)?;
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_phi = (lat2 - lat1).to_radians();
+    let d_lambda = (lon2 - lon1).to_radians();
+    let a = (d_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2);
+    Some(2.0 * EARTH_RADIUS_KM * a.sqrt().asin())
+}
+
 /// Transaction geographic risk assessment
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionGeographicRisk {
@@ -371,6 +498,25 @@ mod tests {
         assert!(!us.requires_edd());
     }
 
+    #[test]
+    fn test_comprehensive_sanctions() {
+        assert_eq!(comprehensive_sanctions_program("ir"), Some("Iran"));
+        assert_eq!(
+            comprehensive_sanctions_program(" UA-43 "),
+            Some("Crimea region of Ukraine")
+        );
+        assert_eq!(comprehensive_sanctions_program("UA"), None);
+        assert_eq!(comprehensive_sanctions_program("PK"), None);
+    }
+
+    #[test]
+    fn test_country_distance() {
+        let us_gb = country_distance_km("US", "gb").unwrap();
+        assert!((6000.0..8000.0).contains(&us_gb));
+        assert_eq!(country_distance_km("DE", "DE"), Some(0.0));
+        assert_eq!(country_distance_km("US", "XX"), None);
+    }
+
     #[test]
     fn test_transaction_risk() {
         let scorer = GeographicRiskScorer::new();
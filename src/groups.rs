@@ -0,0 +1,371 @@
+//! Multi-leg transactions
+//!
+//! A payout split across several beneficiaries, or an FX conversion followed
+//! by a transfer, is one business event made of several [`Transaction`]s.
+//! A [`TransactionGroup`] is validated as a unit. The group has its own
+//! checks: each leg belongs to the same user, each leg's source is the
+//! group's origin or an earlier leg's destination, the amount leaving the
+//! origin stays within the per-transaction limit, and sub-threshold legs do
+//! not add up past the reporting threshold. Every leg is then checked as if
+//! the legs before it had been committed, and the legs are only committed
+//! once the group and all its legs pass.
+
+use crate::{Decision, Transaction, ValidationError, ValidationResult, Warning, WarningSeverity};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// Warning code for legs that split a reportable amount
+pub const GROUP_STRUCTURING_WARNING: &str = "GROUP_STRUCTURING";
+
+/// Legs validated as one business event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionGroup {
+    pub group_id: String,
+    /// Legs in execution order
+    pub legs: Vec<Transaction>,
+}
+
+impl TransactionGroup {
+    pub fn new(group_id: &str, legs: Vec<Transaction>) -> Self {
+        Self {
+            group_id: group_id.to_string(),
+            legs,
+        }
+    }
+
+    /// Legs funded from outside the group, i.e. not from an earlier leg
+    pub fn outflow_legs(&self) -> impl Iterator<Item = &Transaction> {
+        let mut intermediate = HashSet::new();
+        self.legs.iter().filter(move |leg| {
+            let external = leg
+                .from_account
+                .as_ref()
+                .is_none_or(|from| !intermediate.contains(from));
+            if let Some(ref to) = leg.to_account {
+                intermediate.insert(to.clone());
+            }
+            external
+        })
+    }
+
+    /// Amount leaving the group's origin, per currency
+    pub fn outflow_by_currency(&self) -> BTreeMap<String, f64> {
+        let mut totals = BTreeMap::new();
+        for leg in self.outflow_legs() {
+            *totals.entry(leg.currency.clone()).or_insert(0.0) += leg.amount;
+        }
+        totals
+    }
+}
+
+/// Thresholds for group-level checks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupCheckConfig {
+    /// Reporting threshold that split outflows are compared against
+    pub ctr_threshold: f64,
+}
+
+impl Default for GroupCheckConfig {
+    fn default() -> Self {
+        Self {
+            ctr_threshold: 10000.0,
+        }
+    }
+}
+
+/// Outcome of validating a group
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupResult {
+    pub group_id: String,
+    /// The group and every leg passed
+    pub is_valid: bool,
+    /// Most severe leg decision; `Decline` on any group-level error
+    pub decision: Decision,
+    /// Errors about the group as a whole
+    pub errors: Vec<ValidationError>,
+    /// Per-leg results, in leg order
+    pub legs: Vec<ValidationResult>,
+    /// Whether the legs were committed to dedup and velocity state
+    pub committed: bool,
+}
+
+impl GroupResult {
+    pub(crate) fn new(
+        group: &TransactionGroup,
+        errors: Vec<ValidationError>,
+        legs: Vec<ValidationResult>,
+        committed: bool,
+    ) -> Self {
+        let is_valid = errors.is_empty() && legs.iter().all(|leg| leg.is_valid);
+        let decision = if errors.is_empty() {
+            legs.iter()
+                .map(|leg| leg.decision)
+                .max_by_key(|decision| decision_rank(*decision))
+                .unwrap_or(Decision::Approve)
+        } else {
+            Decision::Decline
+        };
+        Self {
+            group_id: group.group_id.clone(),
+            is_valid,
+            decision,
+            errors,
+            legs,
+            committed,
+        }
+    }
+}
+
+fn decision_rank(decision: Decision) -> u8 {
+    match decision {
+        Decision::Approve => 0,
+        Decision::Review => 1,
+        Decision::StepUp => 2,
+        Decision::Decline => 3,
+    }
+}
+
+/// Group-level errors, and warnings to attach to every leg
+pub(crate) fn check_group(
+    group: &TransactionGroup,
+    config: &GroupCheckConfig,
+    max_transaction_amount: f64,
+) -> (Vec<ValidationError>, Vec<Warning>) {
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    let Some(first) = group.legs.first() else {
+        errors.push(ValidationError::BusinessRuleViolation(format!(
+            "Group {} has no legs",
+            group.group_id
+        )));
+        return (errors, warnings);
+    };
+
+    let mut ids = HashSet::new();
+    for leg in &group.legs {
+        if !ids.insert(leg.transaction_id.as_str()) {
+            errors.push(ValidationError::DuplicateTransaction(format!(
+                "{} appears twice in group {}",
+                leg.transaction_id, group.group_id
+            )));
+        }
+    }
+
+    // Consistent counterparties: one user, and funds only move onward
+    if let Some(leg) = group.legs.iter().find(|leg| leg.user_id != first.user_id) {
+        errors.push(ValidationError::BusinessRuleViolation(format!(
+            "Leg {} belongs to {}, not group owner {}",
+            leg.transaction_id, leg.user_id, first.user_id
+        )));
+    }
+    let mut reachable: HashSet<&str> = first.from_account.as_deref().into_iter().collect();
+    for leg in &group.legs {
+        if let Some(from) = leg.from_account.as_deref() {
+            if !reachable.contains(from) {
+                errors.push(ValidationError::BusinessRuleViolation(format!(
+                    "Leg {} draws on {}, which is neither the group origin nor an earlier leg's destination",
+                    leg.transaction_id, from
+                )));
+            }
+        }
+        reachable.extend(leg.to_account.as_deref());
+    }
+
+    // Aggregate amount: splitting must not get around the per-transaction limit
+    for (currency, total) in group.outflow_by_currency() {
+        if total > max_transaction_amount {
+            errors.push(ValidationError::InvalidAmount(format!(
+                "Group {} moves {:.2} {} in total, above the {:.2} limit",
+                group.group_id, total, currency, max_transaction_amount
+            )));
+        }
+    }
+
+    // Structuring: sub-threshold legs adding up past the reporting threshold
+    let split: Vec<&Transaction> = group
+        .outflow_legs()
+        .filter(|leg| leg.amount < config.ctr_threshold)
+        .collect();
+    let split_total: f64 = split.iter().map(|leg| leg.amount).sum();
+    if split.len() >= 2 && split_total >= config.ctr_threshold {
+        warnings.push(Warning::new(
+            GROUP_STRUCTURING_WARNING,
+            WarningSeverity::High,
+            format!(
+                "Group {} splits {:.2} across {} sub-threshold legs",
+                group.group_id,
+                split_total,
+                split.len()
+            ),
+        ));
+    }
+
+    (errors, warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::TransactionValidator;
+
+    #[test]
+    fn test_fx_chain_is_validated_as_a_unit() {
+        let mut validator = TransactionValidator::new();
+        let mut convert = create_test_transaction("TXN-GRP-1", 500.0);
+        convert.to_account = Some("ACCT-5555-0000-1111".to_string());
+        let mut send = create_test_transaction("TXN-GRP-2", 460.0);
+        send.currency = "EUR".to_string();
+        send.from_account = Some("ACCT-5555-0000-1111".to_string());
+        let group = TransactionGroup::new("GRP-FX", vec![convert, send]);
+
+        // Only the first leg leaves the origin
+        assert_eq!(group.outflow_by_currency().len(), 1);
+        let result = validator.validate_group(&group, &GroupCheckConfig::default());
+        assert!(result.is_valid);
+        assert!(result.committed);
+        assert_eq!(result.decision, Decision::Approve);
+
+        // A leg drawing on an unrelated account breaks the chain
+        let mut stray = create_test_transaction("TXN-GRP-3", 10.0);
+        stray.from_account = Some("ACCT-9999-8888-7777".to_string());
+        let group = TransactionGroup::new(
+            "GRP-BAD",
+            vec![create_test_transaction("TXN-GRP-4", 10.0), stray],
+        );
+        let result = validator.validate_group(&group, &GroupCheckConfig::default());
+        assert!(!result.is_valid);
+        assert!(!result.committed);
+        assert_eq!(result.decision, Decision::Decline);
+    }
+
+    #[test]
+    fn test_failing_group_commits_no_leg() {
+        let mut validator = TransactionValidator::new();
+        let group = TransactionGroup::new(
+            "GRP-DUP",
+            vec![
+                create_test_transaction("TXN-GRP-DUP", 10.0),
+                create_test_transaction("TXN-GRP-DUP", 20.0),
+            ],
+        );
+        let result = validator.validate_group(&group, &GroupCheckConfig::default());
+        assert!(!result.is_valid);
+        assert!(!result.committed);
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::DuplicateTransaction(_))));
+        assert!(!result.legs[1].is_valid);
+        assert!(validator.history().read().is_empty());
+        assert!(!validator.dedup().read().contains("TXN-GRP-DUP"));
+        assert!(!validator.dedup().read().is_reserved("TXN-GRP-DUP"));
+
+        // Legs count toward velocity for the legs after them
+        let legs = (0..11)
+            .map(|i| {
+                let mut leg = create_test_transaction(&format!("TXN-GRP-V-{}", i), 10.0 + i as f64);
+                leg.to_account = Some(format!("ACCT-2222-3333-{:04}", i));
+                leg
+            })
+            .collect();
+        let group = TransactionGroup::new("GRP-VELOCITY", legs);
+        let result = validator.validate_group(&group, &GroupCheckConfig::default());
+        assert!(!result.committed);
+        assert!(result.legs[..10].iter().all(|leg| leg.is_valid));
+        assert!(!result.legs[10].is_valid);
+        assert!(validator.history().read().is_empty());
+        // Failed legs of both groups are not committed but are still counted
+        assert_eq!(validator.get_stats().total_validated, 13);
+
+        let single = validator.validate(&create_test_transaction("TXN-GRP-DUP", 10.0));
+        assert!(single.is_valid);
+    }
+
+    #[test]
+    fn test_split_payout_aggregates_and_flags_structuring() {
+        let mut validator = TransactionValidator::new();
+        let legs = (0..3)
+            .map(|i| {
+                let mut leg = create_test_transaction(&format!("TXN-SPLIT-{}", i), 4000.0);
+                leg.to_account = Some(format!("ACCT-2222-3333-000{}", i));
+                leg
+            })
+            .collect();
+        let group = TransactionGroup::new("GRP-SPLIT", legs);
+        let result = validator.validate_group(&group, &GroupCheckConfig::default());
+        assert!(result.is_valid);
+        assert!(result.legs.iter().all(|leg| leg
+            .warnings
+            .iter()
+            .any(|w| w.code == GROUP_STRUCTURING_WARNING)));
+
+        // The same split is rejected once the total exceeds the limit
+        let mut strict = TransactionValidator::with_config(crate::ValidatorConfig {
+            max_transaction_amount: 10000.0,
+            ..Default::default()
+        });
+        let result = strict.validate_group(&group, &GroupCheckConfig::default());
+        assert!(!result.is_valid);
+        assert!(matches!(
+            result.errors[0],
+            ValidationError::InvalidAmount(_)
+        ));
+        assert_eq!(strict.get_stats().total_validated, 3);
+        assert!(strict.history().read().is_empty());
+    }
+
+    #[test]
+    fn test_group_commits_no_leg_when_a_leg_cannot_be_logged() {
+        use crate::event_log::{EventLog, ValidationEvent};
+        use std::io;
+        use std::sync::{Arc, Mutex};
+
+        /// Log that accepts a fixed number of events, then fails
+        struct FlakyLog {
+            written: Arc<Mutex<Vec<ValidationEvent>>>,
+            capacity: usize,
+        }
+
+        impl EventLog for FlakyLog {
+            fn append(&mut self, event: &ValidationEvent) -> io::Result<()> {
+                let mut written = self.written.lock().unwrap();
+                if written.len() == self.capacity {
+                    return Err(io::Error::other("disk full"));
+                }
+                written.push(event.clone());
+                Ok(())
+            }
+        }
+
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let mut validator = TransactionValidator::new();
+        validator.set_event_log(Box::new(FlakyLog {
+            written: written.clone(),
+            capacity: 1,
+        }));
+        let legs = (0..2)
+            .map(|i| {
+                let mut leg = create_test_transaction(&format!("TXN-GRP-LOG-{}", i), 100.0);
+                leg.to_account = Some(format!("ACCT-2222-3333-000{}", i));
+                leg
+            })
+            .collect();
+        let group = TransactionGroup::new("GRP-LOG", legs);
+        let result = validator.validate_group(&group, &GroupCheckConfig::default());
+        assert!(!result.committed);
+        assert!(result.legs.iter().all(|leg| !leg.committed
+            && leg.decision == Decision::Review
+            && leg.warnings.iter().any(|w| w.code == "EVENT_LOG_FAILED")));
+        assert!(validator.history().read().is_empty());
+        assert!(!validator.dedup().read().is_reserved("TXN-GRP-LOG-0"));
+        assert_eq!(validator.get_stats().event_log_failures, 1);
+
+        // Replay of the partial group applies nothing
+        let events = written.lock().unwrap().clone();
+        assert_eq!(events.len(), 1);
+        let mut recovered = TransactionValidator::new();
+        assert_eq!(recovered.replay_events(events), 0);
+        assert!(recovered.history().read().is_empty());
+    }
+}
@@ -0,0 +1,277 @@
+//! Shared transaction history
+//!
+//! The validator's velocity checks and the [`FraudDetector`](crate::FraudDetector)
+//! both look back over recent transactions. A [`HistoryStore`] keeps one copy
+//! of each, indexed by account, user, and counterparty, and both components
+//! read and write it through a cloned [`SharedHistory`] handle. One retention
+//! window governs pruning for both.
+
+use crate::Transaction;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Dimension under which transaction history is indexed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HistoryKey {
+    /// Debited account (`from_account`)
+    Account,
+    /// Submitting user, across all of their accounts and deposits
+    User,
+    /// Credited account (`to_account`), catching many payers to one payee
+    Counterparty,
+}
+
+impl HistoryKey {
+    /// All dimensions, in index order
+    pub const ALL: [HistoryKey; 3] = [
+        HistoryKey::Account,
+        HistoryKey::User,
+        HistoryKey::Counterparty,
+    ];
+
+    /// The transaction's ID under this dimension, if it has one
+    pub fn id<'a>(&self, transaction: &'a Transaction) -> Option<&'a str> {
+        match self {
+            HistoryKey::Account => transaction.from_account.as_deref(),
+            HistoryKey::User => Some(transaction.user_id.as_str()),
+            HistoryKey::Counterparty => transaction.to_account.as_deref(),
+        }
+    }
+}
+
+/// Default retention window, covering the default adaptive velocity baseline
+pub const DEFAULT_RETENTION_DAYS: i64 = 90;
+
+/// Recorded transactions, indexed by every [`HistoryKey`]
+#[derive(Debug, Clone)]
+pub struct HistoryStore {
+    transactions: Vec<Arc<Transaction>>,
+    index: HashMap<(HistoryKey, String), Vec<Arc<Transaction>>>,
+    retention: Duration,
+    /// Latest cutoff transactions have been pruned before
+    pruned_before: Option<DateTime<Utc>>,
+}
+
+impl HistoryStore {
+    /// Empty store with the default retention window
+    pub fn new() -> Self {
+        Self::with_retention(Duration::days(DEFAULT_RETENTION_DAYS))
+    }
+
+    /// Empty store that keeps transactions for `retention`
+    pub fn with_retention(retention: Duration) -> Self {
+        Self {
+            transactions: Vec::new(),
+            index: HashMap::new(),
+            retention,
+            pruned_before: None,
+        }
+    }
+
+    /// How long transactions are kept by [`HistoryStore::prune`]
+    pub fn retention(&self) -> Duration {
+        self.retention
+    }
+
+    /// Latest cutoff passed to [`HistoryStore::prune_before`], if any
+    ///
+    /// The store is complete only for transactions timestamped from here on.
+    pub fn pruned_before(&self) -> Option<DateTime<Utc>> {
+        self.pruned_before
+    }
+
+    /// Record a transaction under each dimension it has an ID for
+    pub fn record(&mut self, transaction: &Transaction) {
+        self.record_shared(transaction);
+    }
+
+    /// Record a transaction, returning the entry for [`HistoryStore::remove`]
+    pub(crate) fn record_shared(&mut self, transaction: &Transaction) -> Arc<Transaction> {
+        let transaction = Arc::new(transaction.clone());
+        for key in HistoryKey::ALL {
+            if let Some(id) = key.id(&transaction) {
+                self.index
+                    .entry((key, id.to_string()))
+                    .or_default()
+                    .push(Arc::clone(&transaction));
+            }
+        }
+        self.transactions.push(Arc::clone(&transaction));
+        transaction
+    }
+
+    /// Take back one entry returned by [`HistoryStore::record_shared`]
+    pub(crate) fn remove(&mut self, recorded: &Arc<Transaction>) {
+        self.transactions.retain(|t| !Arc::ptr_eq(t, recorded));
+        for key in HistoryKey::ALL {
+            if let Some(id) = key.id(recorded) {
+                let slot = (key, id.to_string());
+                if let Some(entries) = self.index.get_mut(&slot) {
+                    entries.retain(|t| !Arc::ptr_eq(t, recorded));
+                    if entries.is_empty() {
+                        self.index.remove(&slot);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Transactions recorded under one ID, in recording order
+    pub fn get(&self, key: HistoryKey, id: &str) -> &[Arc<Transaction>] {
+        self.index
+            .get(&(key, id.to_string()))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// All recorded transactions, in recording order
+    pub fn iter(&self) -> impl Iterator<Item = &Transaction> {
+        self.transactions.iter().map(|t| t.as_ref())
+    }
+
+    /// Number of recorded transactions
+    pub fn len(&self) -> usize {
+        self.transactions.len()
+    }
+
+    /// Check whether nothing is recorded
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+
+    /// Drop transactions older than the retention window before `now`
+    pub fn prune(&mut self, now: DateTime<Utc>) {
+        let cutoff = now
+            .checked_sub_signed(self.retention)
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        self.prune_before(cutoff);
+    }
+
+    /// Drop transactions timestamped before `cutoff`
+    pub fn prune_before(&mut self, cutoff: DateTime<Utc>) {
+        self.transactions.retain(|t| t.timestamp >= cutoff);
+        for transactions in self.index.values_mut() {
+            transactions.retain(|t| t.timestamp >= cutoff);
+        }
+        self.index.retain(|_, v| !v.is_empty());
+        self.pruned_before = self.pruned_before.max(Some(cutoff));
+    }
+
+    /// Estimate heap and inline memory held by recorded transactions
+    pub(crate) fn estimated_memory_bytes(&self) -> usize {
+        let transactions: usize = self
+            .iter()
+            .map(|t| {
+                std::mem::size_of::<Transaction>()
+                    + t.transaction_id.capacity()
+                    + t.user_id.capacity()
+                    + t.currency.capacity()
+                    + t.from_account.as_ref().map_or(0, |a| a.capacity())
+                    + t.to_account.as_ref().map_or(0, |a| a.capacity())
+            })
+            .sum();
+        let index: usize = self
+            .index
+            .iter()
+            .map(|((_, id), v)| {
+                std::mem::size_of::<(HistoryKey, String)>()
+                    + id.capacity()
+                    + v.capacity() * std::mem::size_of::<Arc<Transaction>>()
+            })
+            .sum();
+        transactions + index
+    }
+}
+
+impl Default for HistoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Cloneable handle to a [`HistoryStore`] shared between components
+#[derive(Debug, Clone, Default)]
+pub struct SharedHistory(Arc<RwLock<HistoryStore>>);
+
+impl SharedHistory {
+    /// Share an existing store
+    pub fn new(store: HistoryStore) -> Self {
+        Self(Arc::new(RwLock::new(store)))
+    }
+
+    /// Lock the store for reading
+    ///
+    /// A panic while another holder had the lock leaves the store usable, so
+    /// poisoning is ignored.
+    pub fn read(&self) -> RwLockReadGuard<'_, HistoryStore> {
+        self.0.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Lock the store for writing
+    pub fn write(&self) -> RwLockWriteGuard<'_, HistoryStore> {
+        self.0.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Check whether both handles refer to the same store
+    pub fn ptr_eq(&self, other: &SharedHistory) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{FraudDetector, TransactionValidator};
+
+    #[test]
+    fn test_store_indexes_and_prunes() {
+        let mut store = HistoryStore::with_retention(Duration::hours(24));
+        let mut old = create_test_transaction("TXN-HIS-1", 100.0);
+        old.timestamp -= Duration::days(2);
+        let mut deposit = create_test_transaction("TXN-HIS-2", 200.0);
+        deposit.from_account = None;
+        store.record(&old);
+        store.record(&deposit);
+
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.get(HistoryKey::User, "USER-001").len(), 2);
+        assert_eq!(
+            store.get(HistoryKey::Account, "ACCT-1234-5678-9012").len(),
+            1
+        );
+        assert_eq!(
+            store
+                .get(HistoryKey::Counterparty, "ACCT-6789-0123-4567")
+                .len(),
+            2
+        );
+
+        store.prune(deposit.timestamp);
+        assert_eq!(store.len(), 1);
+        assert!(store
+            .get(HistoryKey::Account, "ACCT-1234-5678-9012")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_validator_and_detector_share_history() {
+        let shared = SharedHistory::default();
+        let mut validator = TransactionValidator::builder()
+            .with_history(shared.clone())
+            .with_fraud_detector(FraudDetector::new())
+            .build();
+
+        for i in 0..3 {
+            let mut tx = create_test_transaction(&format!("TXN-SHR-{}", i), 100.0);
+            tx.timestamp += Duration::minutes(i);
+            validator.validate(&tx);
+        }
+
+        // One copy per transaction, visible to the validator's stats and the caller
+        assert_eq!(shared.read().len(), 3);
+        assert_eq!(validator.get_stats().total_transactions_in_history, 3);
+        assert!(validator.history().ptr_eq(&shared));
+    }
+}
@@ -0,0 +1,108 @@
+//! Pre/post validation middleware hooks
+//!
+//! Pre-hooks may enrich a copy of the transaction before any check runs.
+//! Post-hooks may annotate or override the result after the decision has
+//! been derived; their changes are final and are what stats and the
+//! decision log record.
+
+use crate::{Transaction, ValidationResult};
+
+/// Hook run before checks, with a mutable copy of the transaction
+pub trait PreValidationHook: Send + Sync {
+    fn before_validate(&self, transaction: &mut Transaction);
+}
+
+/// Hook run after the decision is derived, with a mutable result
+pub trait PostValidationHook: Send + Sync {
+    fn after_validate(&self, transaction: &Transaction, result: &mut ValidationResult);
+}
+
+impl<F> PreValidationHook for F
+where
+    F: Fn(&mut Transaction) + Send + Sync,
+{
+    fn before_validate(&self, transaction: &mut Transaction) {
+        self(transaction)
+    }
+}
+
+impl<F> PostValidationHook for F
+where
+    F: Fn(&Transaction, &mut ValidationResult) + Send + Sync,
+{
+    fn after_validate(&self, transaction: &Transaction, result: &mut ValidationResult) {
+        self(transaction, result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+    use crate::{Decision, TransactionMetadata, TransactionValidator};
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn create_test_transaction() -> Transaction {
+        test_fixtures::create_test_transaction("TXN-HOOK-001", 1000.0)
+    }
+
+    struct CountingHook(Arc<AtomicUsize>);
+
+    impl PostValidationHook for CountingHook {
+        fn after_validate(&self, _transaction: &Transaction, _result: &mut ValidationResult) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_pre_hook_enriches_transaction() {
+        let mut validator = TransactionValidator::new();
+        validator.add_pre_hook(|tx: &mut Transaction| {
+            tx.metadata
+                .get_or_insert_with(TransactionMetadata::new)
+                .insert("country", "IR");
+        });
+        validator.set_geographic_scorer(crate::GeographicRiskScorer::new());
+
+        // Enrichment is visible to checks
+        let result = validator.validate(&create_test_transaction());
+        assert_eq!(result.decision, Decision::Decline);
+        assert!(result
+            .reason_codes()
+            .contains(&"PROHIBITED_JURISDICTION".to_string()));
+    }
+
+    #[test]
+    fn test_post_hook_overrides_result() {
+        let mut validator = TransactionValidator::new();
+        validator.add_post_hook(|_tx: &Transaction, result: &mut ValidationResult| {
+            result.warnings.push(crate::Warning::new(
+                "MANUAL_OVERRIDE",
+                crate::WarningSeverity::Info,
+                "Reviewed by override hook",
+            ));
+            result.decision = Decision::Review;
+        });
+
+        let result = validator.validate(&create_test_transaction());
+        assert_eq!(result.decision, Decision::Review);
+        assert!(result
+            .warnings
+            .iter()
+            .any(|w| w.message.contains("override hook")));
+        assert_eq!(validator.get_stats().decisions.review, 1);
+    }
+
+    #[test]
+    fn test_trait_object_hooks_run_in_order() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut validator = TransactionValidator::new();
+        validator.add_post_hook(CountingHook(calls.clone()));
+        validator.add_post_hook(CountingHook(calls.clone()));
+
+        validator.validate(&create_test_transaction());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
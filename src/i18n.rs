@@ -0,0 +1,279 @@
+//! Localized error and warning messages
+//!
+//! Message catalogs map reason and warning codes to templates in an
+//! operator's locale. Codes stay stable; only the rendered text changes.
+//! Templates may reference `{detail}` (error payload) or `{message}` (the
+//! original warning text). Codes missing from a catalog fall back to the
+//! built-in English text.
+
+use crate::{ValidationError, ValidationResult, Warning};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Locales with built-in catalogs
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum Locale {
+    English,
+    Spanish,
+    French,
+}
+
+impl Locale {
+    /// BCP 47 language tag
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::Spanish => "es",
+            Locale::French => "fr",
+        }
+    }
+
+    /// Parse a language tag such as `es` or `fr-CA`
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag.split(['-', '_']).next()?.to_ascii_lowercase();
+        match language.as_str() {
+            "en" => Some(Locale::English),
+            "es" => Some(Locale::Spanish),
+            "fr" => Some(Locale::French),
+            _ => None,
+        }
+    }
+}
+
+const SPANISH: &[(&str, &str)] = &[
+    ("INVALID_AMOUNT", "Importe no válido: {detail}"),
+    ("INVALID_ACCOUNT", "Número de cuenta no válido: {detail}"),
+    ("INVALID_ROUTING_NUMBER", "Número ABA no válido: {detail}"),
+    ("DUPLICATE_TRANSACTION", "Transacción duplicada: {detail}"),
+    ("FRAUD_DETECTED", "Patrón de fraude detectado: {detail}"),
+    (
+        "COMPLIANCE_FAILED",
+        "Fallo en el control de cumplimiento: {detail}",
+    ),
+    (
+        "BUSINESS_RULE_VIOLATION",
+        "Infracción de regla de negocio: {detail}",
+    ),
+    (
+        "VELOCITY_VIOLATION",
+        "Control de velocidad fallido: {detail}",
+    ),
+    (
+        "RISK_THRESHOLD_EXCEEDED",
+        "Umbral de riesgo superado: {detail}",
+    ),
+    (
+        "SANCTIONS_MATCH",
+        "Coincidencia con lista de sanciones: {detail}",
+    ),
+    (
+        "PROHIBITED_JURISDICTION",
+        "Jurisdicción prohibida: {detail}",
+    ),
+    (
+        "SANCTIONED_JURISDICTION",
+        "Jurisdicción bajo sanciones integrales: {detail}",
+    ),
+    ("BLOCKLISTED", "Bloqueado por lista interna: {detail}"),
+    ("ROUND_AMOUNT", "Transacción de importe redondo elevado"),
+    ("HIGH_VALUE", "Transacción de alto valor; requiere revisión"),
+    (
+        "WIRE_TRANSFER",
+        "Transferencia bancaria marcada para revisión",
+    ),
+    (
+        "OUTSIDE_BUSINESS_HOURS",
+        "Transacción fuera del horario laboral",
+    ),
+    (
+        "HIGH_VELOCITY",
+        "Velocidad de transacciones elevada ({message})",
+    ),
+    (
+        "APPROACHING_AMOUNT_LIMIT",
+        "Cerca del límite de importe ({message})",
+    ),
+    (
+        "POSSIBLE_SANCTIONS_MATCH",
+        "Posible coincidencia con sanciones ({message})",
+    ),
+    (
+        "ENHANCED_DUE_DILIGENCE",
+        "Jurisdicción de alto riesgo; requiere diligencia reforzada ({message})",
+    ),
+    (
+        "PROBABLE_DUPLICATE_PAYMENT",
+        "Probable pago duplicado ({message})",
+    ),
+];
+
+const FRENCH: &[(&str, &str)] = &[
+    ("INVALID_AMOUNT", "Montant invalide : {detail}"),
+    ("INVALID_ACCOUNT", "Numéro de compte invalide : {detail}"),
+    ("INVALID_ROUTING_NUMBER", "Code ABA invalide : {detail}"),
+    ("DUPLICATE_TRANSACTION", "Transaction en double : {detail}"),
+    ("FRAUD_DETECTED", "Schéma de fraude détecté : {detail}"),
+    (
+        "COMPLIANCE_FAILED",
+        "Échec du contrôle de conformité : {detail}",
+    ),
+    (
+        "BUSINESS_RULE_VIOLATION",
+        "Violation de règle métier : {detail}",
+    ),
+    (
+        "VELOCITY_VIOLATION",
+        "Échec du contrôle de vélocité : {detail}",
+    ),
+    (
+        "RISK_THRESHOLD_EXCEEDED",
+        "Seuil de risque dépassé : {detail}",
+    ),
+    (
+        "SANCTIONS_MATCH",
+        "Correspondance avec une liste de sanctions : {detail}",
+    ),
+    (
+        "PROHIBITED_JURISDICTION",
+        "Juridiction interdite : {detail}",
+    ),
+    (
+        "SANCTIONED_JURISDICTION",
+        "Juridiction sous sanctions globales : {detail}",
+    ),
+    ("BLOCKLISTED", "Bloqué par une liste interne : {detail}"),
+    ("ROUND_AMOUNT", "Transaction d'un montant rond élevé"),
+    ("HIGH_VALUE", "Transaction de montant élevé à examiner"),
+    ("WIRE_TRANSFER", "Virement signalé pour examen"),
+    (
+        "OUTSIDE_BUSINESS_HOURS",
+        "Transaction hors des heures ouvrées",
+    ),
+    (
+        "HIGH_VELOCITY",
+        "Vélocité de transactions élevée ({message})",
+    ),
+    (
+        "APPROACHING_AMOUNT_LIMIT",
+        "Proche de la limite de montant ({message})",
+    ),
+    (
+        "POSSIBLE_SANCTIONS_MATCH",
+        "Correspondance possible avec des sanctions ({message})",
+    ),
+    (
+        "ENHANCED_DUE_DILIGENCE",
+        "Juridiction à haut risque, vigilance renforcée requise ({message})",
+    ),
+    (
+        "PROBABLE_DUPLICATE_PAYMENT",
+        "Paiement probablement en double ({message})",
+    ),
+];
+
+/// Message templates for one locale, keyed by code
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageCatalog {
+    pub locale: Locale,
+    messages: HashMap<String, String>,
+}
+
+impl MessageCatalog {
+    /// Built-in catalog for a locale (English renders the original text)
+    pub fn builtin(locale: Locale) -> Self {
+        let entries: &[(&str, &str)] = match locale {
+            Locale::English => &[],
+            Locale::Spanish => SPANISH,
+            Locale::French => FRENCH,
+        };
+        Self {
+            locale,
+            messages: entries
+                .iter()
+                .map(|(code, template)| (code.to_string(), template.to_string()))
+                .collect(),
+        }
+    }
+
+    /// Add or replace the template for a code
+    pub fn set_message(&mut self, code: &str, template: &str) {
+        self.messages.insert(code.to_string(), template.to_string());
+    }
+
+    /// Render an error in this catalog's locale
+    pub fn error_message(&self, error: &ValidationError) -> String {
+        match self.messages.get(error.reason_code()) {
+            Some(template) => template.replace("{detail}", error.detail()),
+            None => error.to_string(),
+        }
+    }
+
+    /// Render a warning in this catalog's locale
+    pub fn warning_message(&self, warning: &Warning) -> String {
+        match self.messages.get(&warning.code) {
+            Some(template) => template.replace("{message}", &warning.message),
+            None => warning.message.clone(),
+        }
+    }
+
+    /// Render every error and warning of a result
+    pub fn localize(&self, result: &ValidationResult) -> LocalizedMessages {
+        LocalizedMessages {
+            locale: self.locale,
+            errors: result
+                .errors
+                .iter()
+                .map(|e| self.error_message(e))
+                .collect(),
+            warnings: result
+                .warnings
+                .iter()
+                .map(|w| self.warning_message(w))
+                .collect(),
+        }
+    }
+}
+
+/// Localized text for one result, in the same order as its errors and warnings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocalizedMessages {
+    pub locale: Locale,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WarningSeverity;
+
+    #[test]
+    fn test_error_rendered_in_locale() {
+        let error = ValidationError::InvalidAmount("Amount must be positive".to_string());
+        let spanish = MessageCatalog::builtin(Locale::Spanish);
+        assert_eq!(
+            spanish.error_message(&error),
+            "Importe no válido: Amount must be positive"
+        );
+        assert_eq!(
+            MessageCatalog::builtin(Locale::English).error_message(&error),
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn test_warning_fallback_and_override() {
+        let mut french = MessageCatalog::builtin(Locale::French);
+        let known = Warning::new("HIGH_VALUE", WarningSeverity::Medium, "High-value");
+        let unknown = Warning::new("CUSTOM_CHECK", WarningSeverity::Low, "Custom text");
+        assert_eq!(
+            french.warning_message(&known),
+            "Transaction de montant élevé à examiner"
+        );
+        assert_eq!(french.warning_message(&unknown), "Custom text");
+
+        french.set_message("CUSTOM_CHECK", "Contrôle personnalisé");
+        assert_eq!(french.warning_message(&unknown), "Contrôle personnalisé");
+        assert_eq!(Locale::from_tag("fr-CA"), Some(Locale::French));
+    }
+}
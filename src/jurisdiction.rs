@@ -0,0 +1,263 @@
+//! Jurisdictional compliance profiles
+//!
+//! Reporting thresholds, the AML red flags that must be checked, and
+//! record retention differ by regulator. A [`JurisdictionProfile`] holds
+//! them for one regime and configures every component from the same
+//! values: [`AMLChecker`] thresholds and red flags, the
+//! [`ValidatorConfig`] checks and retention period, and the batch and
+//! group reporting thresholds. [`ComprehensiveReport`]s name the
+//! jurisdiction and the date until which the record must be kept.
+//!
+//! | Profile         | Currency | Large transaction report | Cash flag | Retention |
+//! |-----------------|----------|-------------------------:|----------:|-----------|
+//! | `US-FinCEN`     | USD      |                   10,000 |     5,000 | 5 years   |
+//! | `EU-AMLD6`      | EUR      |                   10,000 |     5,000 | 5 years   |
+//! | `UK`            | GBP      |                   10,000 |     5,000 | 5 years   |
+//! | `SG-MAS`        | SGD      |                   20,000 |    10,000 | 5 years   |
+//! | `CA-FINTRAC`    | CAD      |                   10,000 |     5,000 | 5 years   |
+//!
+//! Thresholds are compared with transaction amounts as given; convert to
+//! the profile's currency upstream.
+//!
+//! [`ComprehensiveReport`]: crate::ComprehensiveReport
+
+use crate::aml_compliance::{AMLThresholds, RedFlagType};
+use crate::{AMLChecker, BatchCheckConfig, GroupCheckConfig, ValidatorConfig};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Regulatory regime a deployment reports under
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum Jurisdiction {
+    UsFincen,
+    EuAmld6,
+    Uk,
+    SingaporeMas,
+    CanadaFintrac,
+}
+
+impl Jurisdiction {
+    /// Every supported jurisdiction
+    pub const ALL: [Jurisdiction; 5] = [
+        Jurisdiction::UsFincen,
+        Jurisdiction::EuAmld6,
+        Jurisdiction::Uk,
+        Jurisdiction::SingaporeMas,
+        Jurisdiction::CanadaFintrac,
+    ];
+}
+
+impl fmt::Display for Jurisdiction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Jurisdiction::UsFincen => "US-FinCEN",
+            Jurisdiction::EuAmld6 => "EU-AMLD6",
+            Jurisdiction::Uk => "UK",
+            Jurisdiction::SingaporeMas => "SG-MAS",
+            Jurisdiction::CanadaFintrac => "CA-FINTRAC",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Thresholds, red flags, and retention for one jurisdiction
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JurisdictionProfile {
+    pub jurisdiction: Jurisdiction,
+    /// Currency the thresholds are expressed in
+    pub currency: String,
+    /// Large (cash) transaction report threshold: CTR, LCTR, CTR (MAS)
+    pub report_threshold: f64,
+    /// Amounts from here up to the report threshold are flagged as structuring
+    pub structuring_threshold: f64,
+    pub sar_threshold: f64,
+    /// Deposits and withdrawals from here up are flagged as cash intensive
+    pub cash_threshold: f64,
+    /// How long validation records must be kept
+    pub retention_days: i64,
+    /// Red flags the AML checker must raise
+    pub required_red_flags: Vec<RedFlagType>,
+}
+
+const FIVE_YEARS_DAYS: i64 = 5 * 365 + 1;
+
+impl JurisdictionProfile {
+    /// The built-in profile for a jurisdiction
+    pub fn new(jurisdiction: Jurisdiction) -> Self {
+        let all_flags = vec![
+            RedFlagType::PotentialStructuring,
+            RedFlagType::HighValueTransaction,
+            RedFlagType::SanctionedEntity,
+            RedFlagType::SanctionedJurisdiction,
+            RedFlagType::CashIntensive,
+            RedFlagType::CrossBorder,
+        ];
+        let (currency, report_threshold, structuring_threshold, sar_threshold, cash_threshold) =
+            match jurisdiction {
+                // 31 CFR 1010.311 CTR, 1020.320 SAR
+                Jurisdiction::UsFincen => ("USD", 10_000.0, 9_500.0, 5_000.0, 5_000.0),
+                // AMLR cash limit; suspicion reports have no amount floor
+                Jurisdiction::EuAmld6 => ("EUR", 10_000.0, 9_000.0, 0.0, 5_000.0),
+                Jurisdiction::Uk => ("GBP", 10_000.0, 9_000.0, 0.0, 5_000.0),
+                // MAS cash transaction reports from SGD 20,000
+                Jurisdiction::SingaporeMas => ("SGD", 20_000.0, 18_000.0, 0.0, 10_000.0),
+                // FINTRAC large cash and international EFT reports
+                Jurisdiction::CanadaFintrac => ("CAD", 10_000.0, 9_000.0, 0.0, 5_000.0),
+            };
+        Self {
+            jurisdiction,
+            currency: currency.to_string(),
+            report_threshold,
+            structuring_threshold,
+            sar_threshold,
+            cash_threshold,
+            retention_days: FIVE_YEARS_DAYS,
+            required_red_flags: all_flags,
+        }
+    }
+
+    /// AML thresholds for this profile
+    pub fn aml_thresholds(&self) -> AMLThresholds {
+        AMLThresholds {
+            ctr_threshold: self.report_threshold,
+            sar_threshold: self.sar_threshold,
+            structuring_threshold: self.structuring_threshold,
+            cash_threshold: self.cash_threshold,
+        }
+    }
+
+    /// AML checker with this profile's thresholds and red flags
+    pub fn aml_checker(&self) -> AMLChecker {
+        AMLChecker::new()
+            .with_thresholds(self.aml_thresholds())
+            .with_red_flags(self.required_red_flags.clone())
+    }
+
+    /// Turn on the checks the profile requires and record its retention
+    pub fn apply(&self, config: &mut ValidatorConfig) {
+        config.jurisdiction = Some(self.jurisdiction);
+        config.record_retention_days = Some(self.retention_days);
+        config.enable_aml_check = true;
+        if self
+            .required_red_flags
+            .contains(&RedFlagType::SanctionedJurisdiction)
+        {
+            config.block_sanctioned_jurisdictions = true;
+        }
+    }
+
+    /// Default configuration with this profile applied
+    pub fn config(&self) -> ValidatorConfig {
+        let mut config = ValidatorConfig::default();
+        self.apply(&mut config);
+        config
+    }
+
+    /// Required checks the config has turned off
+    pub fn missing_checks(&self, config: &ValidatorConfig) -> Vec<&'static str> {
+        let mut missing = Vec::new();
+        if !config.enable_aml_check {
+            missing.push("enable_aml_check");
+        }
+        if self
+            .required_red_flags
+            .contains(&RedFlagType::SanctionedJurisdiction)
+            && !config.block_sanctioned_jurisdictions
+        {
+            missing.push("block_sanctioned_jurisdictions");
+        }
+        if config
+            .record_retention_days
+            .is_none_or(|days| days < self.retention_days)
+        {
+            missing.push("record_retention_days");
+        }
+        missing
+    }
+
+    /// Batch checks comparing split totals with the report threshold
+    pub fn batch_check_config(&self) -> BatchCheckConfig {
+        BatchCheckConfig {
+            ctr_threshold: self.report_threshold,
+            ..Default::default()
+        }
+    }
+
+    /// Group checks comparing split outflows with the report threshold
+    pub fn group_check_config(&self) -> GroupCheckConfig {
+        GroupCheckConfig {
+            ctr_threshold: self.report_threshold,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{TransactionValidator, ValidationPipeline};
+
+    #[test]
+    fn test_profiles_configure_components_consistently() {
+        for jurisdiction in Jurisdiction::ALL {
+            let profile = JurisdictionProfile::new(jurisdiction);
+            let config = profile.config();
+            assert!(config.validate().is_ok());
+            assert!(profile.missing_checks(&config).is_empty());
+            assert_eq!(config.jurisdiction, Some(jurisdiction));
+            assert_eq!(
+                profile.batch_check_config().ctr_threshold,
+                profile.aml_thresholds().ctr_threshold
+            );
+        }
+        // The US profile matches the checker's historical defaults
+        let us = JurisdictionProfile::new(Jurisdiction::UsFincen).aml_thresholds();
+        let defaults = AMLThresholds::default();
+        assert_eq!(us.ctr_threshold, defaults.ctr_threshold);
+        assert_eq!(us.structuring_threshold, defaults.structuring_threshold);
+
+        let config = ValidatorConfig {
+            enable_aml_check: false,
+            block_sanctioned_jurisdictions: false,
+            record_retention_days: Some(365),
+            ..Default::default()
+        };
+        assert_eq!(
+            JurisdictionProfile::new(Jurisdiction::Uk).missing_checks(&config),
+            vec![
+                "enable_aml_check",
+                "block_sanctioned_jurisdictions",
+                "record_retention_days"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_singapore_thresholds_reach_the_checker() {
+        let checker = JurisdictionProfile::new(Jurisdiction::SingaporeMas).aml_checker();
+        let result = checker.check_compliance(&create_test_transaction("TXN-SG-1", 15_000.0));
+        assert!(!result.requires_ctr);
+        assert!(result.red_flags.is_empty());
+        let result = checker.check_compliance(&create_test_transaction("TXN-SG-2", 19_000.0));
+        assert_eq!(
+            result.red_flags[0].flag_type,
+            RedFlagType::PotentialStructuring
+        );
+
+        let profile = JurisdictionProfile::new(Jurisdiction::SingaporeMas);
+        let mut validator = TransactionValidator::builder()
+            .with_jurisdiction(&profile)
+            .build();
+        let result = validator.validate(&create_test_transaction("TXN-SG-3", 25_000.0));
+        assert_eq!(result.effective_config.record_retention_days, Some(1826));
+        assert!(result.warnings.iter().any(|w| w.code == "AML_RED_FLAG"));
+
+        let mut pipeline = ValidationPipeline::from_builder(
+            TransactionValidator::builder().with_jurisdiction(&profile),
+        );
+        let report = pipeline.report(&create_test_transaction("TXN-SG-4", 500.0));
+        assert_eq!(report.jurisdiction, Some(Jurisdiction::SingaporeMas));
+        let kept = report.retain_until.unwrap() - report.validation.validated_at;
+        assert_eq!(kept.num_days(), 1826);
+    }
+}
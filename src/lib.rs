@@ -29,27 +29,244 @@
 //! - **Geographic Risk**: Country and jurisdiction-based risk scoring
 //! - **Network Analysis**: Graph-based suspicious pattern detection
 //! - **Enhanced Reporting**: Detailed compliance and audit reports
+//!
+//! ## Optional Features
+//!
+//! - `tracing`: Emits `tracing` spans for `validate()`, each check, sanctions
+//!   screening, and network analysis. User IDs are recorded as hashes only.
+//! - `http`: Enables `alerts::WebhookObserver` for signed webhook delivery.
+//! - `parquet`: Enables `ValidationResult::write_parquet` and
+//!   `FeatureVector::write_parquet` for result and feature export.
+//! - `postgres-sql`: Enables `postgres::PostgresAuditSink`, which builds the
+//!   SQL for audit tables and runs it through an executor the application
+//!   implements over its own Postgres driver.
+//! - `archive`: Enables `archive::ArchiveSink`, which batches results and
+//!   audit records into objects for S3-compatible storage.
+//! - `metrics`: Adds metric events and per-check spans on top of `tracing`,
+//!   as fields `tracing-opentelemetry` turns into metrics. No exporter is
+//!   linked.
+//! - `async`: Adds `ValidatorService::start_async` and `submit_async` for
+//!   feeding the service from an async stream on any executor.
 
+pub mod account_formats;
+pub mod activity;
+pub mod alerts;
 pub mod aml_compliance;
+pub mod analytics;
+pub mod anomaly;
+#[cfg(feature = "archive")]
+pub mod archive;
+pub mod audit;
+pub mod authorization;
+pub mod batch;
+pub mod builder;
+pub mod challenge;
+pub mod channel;
+pub mod checks;
+pub mod clock;
+pub mod concurrent;
+pub mod config_builder;
+pub mod config_file;
+pub mod config_overrides;
+pub mod counterparty;
+pub mod decision_log;
+pub mod dedup;
+pub mod degradation;
+pub mod disputes;
+pub mod enrichment;
+pub mod event_log;
+pub mod exit_review;
+pub mod explanation;
+pub mod export;
+pub mod external;
+pub mod features;
 pub mod fraud_patterns;
 pub mod geographic_risk;
+pub mod groups;
+pub mod history;
+pub mod hooks;
+pub mod i18n;
+pub mod jurisdiction;
+pub mod limit_profiles;
+pub mod metadata;
+#[cfg(feature = "ml")]
+pub mod ml;
 pub mod network_analysis;
+pub mod normalization;
+pub mod ofac;
+pub mod pan;
+pub mod parsing;
+pub mod pipeline;
+pub mod policy;
+#[cfg(feature = "postgres-sql")]
+pub mod postgres;
+pub mod presets;
+pub mod prioritization;
+pub mod pseudonym;
+pub mod reference_data;
+pub mod refunds;
+pub mod reporting;
+pub mod resilience;
+pub mod retroactive;
+pub mod routing;
+pub mod rules;
+pub mod sampling;
 pub mod sanctions;
+pub mod screening;
+pub mod service;
+pub mod settlement;
+pub mod similarity;
+pub mod stats;
+#[cfg(feature = "metrics")]
+pub mod telemetry;
+pub mod tenancy;
+#[cfg(test)]
+mod test_fixtures;
+pub mod typologies;
+pub mod warnings;
+pub mod watchlist;
 
-pub use aml_compliance::{AMLChecker, AMLResult, KYCValidationResult, KYCValidator};
-pub use fraud_patterns::{FraudDetector, FraudScore, FraudThresholds, RiskLevel};
-pub use geographic_risk::{CountryRisk, GeographicRiskScorer, JurisdictionRisk};
-pub use network_analysis::{NetworkAnalyzer, SuspiciousPattern, TransactionGraph};
-pub use sanctions::{SanctionsList, SanctionsResult, SanctionsScreener};
+pub use account_formats::{AccountFormatRegistry, AccountMatcher, AccountScheme};
+pub use activity::{ActivityHistogram, ActivityPolicy, ActivityProfiles};
+pub use alerts::{AlertDispatcher, AlertEvent, AlertObserver, AlertTrigger};
+pub use aml_compliance::{
+    AMLChecker, AMLResult, AMLThresholds, KYCValidationResult, KYCValidator, RedFlagType,
+};
+pub use analytics::{AnalyticsAggregator, AnalyticsReport, BucketAggregate, BucketWidth};
+pub use anomaly::{AnomalyCheckConfig, AnomalyFinding, AnomalyKind};
+pub use audit::{
+    AlertRow, AuditRecord, AuditSink, AuditTrail, ComplianceRow, ErrorRow, ResultRow, WarningRow,
+};
+pub use authorization::{
+    AuthorizationBook, AuthorizationHold, AuthorizationPolicy, AUTH_CAPTURE_GAP_WARNING,
+    OVER_CAPTURE_WARNING,
+};
+pub use batch::{BatchCheckConfig, BatchFinding, BatchFindingKind, BatchReport, ReasonCodeCount};
+pub use builder::TransactionValidatorBuilder;
+pub use challenge::{ChallengeOutcome, ChallengeProvider};
+pub use channel::{Channel, ChannelPolicy};
+pub use checks::{
+    Check, CheckTelemetry, CheckTiming, DeferredChecks, ExecutionPlan, FollowUpResult,
+};
+pub use clock::{Clock, FixedClock, MockClock, SharedClock, SystemClock};
+pub use concurrent::ConcurrentTransactionValidator;
+pub use config_builder::{ConfigError, ValidatorConfigBuilder};
+pub use config_file::{ConfigFile, ConfigFileError};
+pub use config_overrides::{ConfigOverride, CustomerSegment};
+pub use counterparty::{CounterpartyTrust, RelationshipStats, TrustPolicy};
+pub use decision_log::{DecisionLogger, DecisionRecord, JsonLinesDecisionLogger};
+pub use dedup::{DedupStore, SharedDedup};
+pub use degradation::DegradationReason;
+pub use disputes::{DisputeEnrichment, DisputeHistory, DisputeHistoryProvider, DisputeLedger};
+pub use enrichment::{
+    AsyncEnrichmentProvider, EnrichmentContext, EnrichmentError, EnrichmentProvider,
+    ACCOUNT_OPENED_METADATA_KEY,
+};
+pub use event_log::{
+    read_event_log, EventLog, GroupMembership, JsonLinesEventLog, RecordedState, ValidationEvent,
+};
+pub use exit_review::{ExitReviewReport, ReviewSubject, RiskRating};
+pub use explanation::{Explanation, ExplanationNode};
+pub use external::{
+    ExternalFailure, ExternalFindings, ExternalScreeningProvider, ExternalStage, KycStatus,
+};
+pub use features::{FeatureExtractor, FeatureVector};
+pub use fraud_patterns::{
+    FraudDetector, FraudScore, FraudThresholds, GeographicAnomalyThresholds, RiskLevel,
+    RoundAmountRule, TimeAnomalyThresholds, PRODUCT_METADATA_KEY,
+};
+pub use geographic_risk::{
+    comprehensive_sanctions_program, CountryRisk, CountryRiskLevel, GeographicRiskScorer,
+    JurisdictionRisk, TransactionGeographicRisk, COMPREHENSIVE_SANCTIONS,
+};
+pub use groups::{GroupCheckConfig, GroupResult, TransactionGroup, GROUP_STRUCTURING_WARNING};
+pub use history::{HistoryKey, HistoryStore, SharedHistory};
+pub use hooks::{PostValidationHook, PreValidationHook};
+pub use i18n::{Locale, LocalizedMessages, MessageCatalog};
+pub use jurisdiction::{Jurisdiction, JurisdictionProfile};
+pub use limit_profiles::{LimitProfile, LimitProfiles};
+pub use metadata::{
+    TransactionMetadata, CORRELATION_ID_METADATA_KEY, KNOWN_METADATA_KEYS,
+    PARENT_TRANSACTION_METADATA_KEY,
+};
+pub use network_analysis::{
+    GraphDiff, NetworkAnalysisReport, NetworkAnalyzer, SuspiciousPattern, TransactionGraph,
+};
+pub use normalization::{IpCountryResolver, MetadataNormalizer, NormalizationReport};
+pub use ofac::{
+    OfacAddress, OfacAlias, OfacEntry, OfacEntryType, OfacId, OfacListKind, OfacLoadError,
+    OfacPublication,
+};
+pub use pan::{luhn_valid, mask_pans, PAN_MASKED_WARNING};
+pub use parsing::{ParseError, ParseLimits};
+pub use pipeline::{ComprehensiveReport, ValidationPipeline};
+pub use policy::{PolicyArchive, PolicySnapshot, ReplayError, RULES_VERSION};
+pub use prioritization::{prioritize, prioritize_with_weights, PriorityWeights, RankedResult};
+pub use pseudonym::{is_pseudonym, Pseudonymizer, PSEUDONYM_PREFIX};
+pub use reference_data::{DatasetAge, ReferenceDataset, STALE_REFERENCE_DATA_WARNING};
+pub use refunds::{LedgerEntry, NetExposure, RefundLedger};
+pub use reporting::{ReportKind, ReportObligation, ReportingPolicy, ReportingQueue};
+pub use resilience::{
+    CallError, CircuitBreaker, CircuitState, Resilience, ResiliencePolicy, ResilientEnrichment,
+    ResilientIpResolver, ResilientScreening,
+};
+pub use retroactive::RetroactiveAlerter;
+pub use routing::{
+    validate_aba, BENEFICIARY_ROUTING_NUMBER_METADATA_KEY, ROUTING_NUMBER_METADATA_KEY,
+};
+pub use rules::{RuleOutcome, ValidationContext, ValidationRule};
+pub use sampling::{SampledStage, SamplingDecision, SamplingPolicy, SamplingReason};
+pub use sanctions::{
+    ListPolicy, ListProvenance, ListSource, RescreeningChange, SanctionsList, SanctionsResult,
+    SanctionsScreener,
+};
+pub use screening::{
+    AsyncScreeningBackend, FallbackScreening, RetryPolicy, ScreeningBackend, ScreeningError,
+};
+#[cfg(feature = "async")]
+pub use service::AsyncResultStream;
+pub use service::{ResultStream, ServiceConfig, SubmitError, ValidatorService};
+pub use settlement::{
+    SettlementCalendar, SettlementPolicy, CUT_OFF_MISSED_WARNING, HOLIDAY_SETTLEMENT_WARNING,
+    WEEKEND_SETTLEMENT_WARNING,
+};
+pub use similarity::SimilarityAlgorithm;
+pub use stats::ValidatorStats;
+pub use tenancy::{MultiTenantValidator, TenantError};
+pub use typologies::{
+    Indicator, TypologyHit, TypologyLibrary, TypologyPack, TypologyRule, BUILTIN_TYPOLOGY_PACKS,
+    CUSTOMER_AGE_METADATA_KEY, TYPOLOGY_MATCH_WARNING,
+};
+pub use warnings::{Warning, WarningSeverity};
+pub use watchlist::{ListType, Watchlist, WatchlistEntry, WatchlistSubject, STEP_UP_WARNING_CODE};
 
-use chrono::{DateTime, Duration, Timelike, Utc};
-use regex::Regex;
+use aml_compliance::{AlertSeverity, JURISDICTION_KEYS};
+use checks::{AccountVerdicts, CheckState, Evaluated, PendingState};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Timelike, Utc, Weekday};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
 use thiserror::Error;
 
+/// Hash an identifier for logs and traces (first 16 hex chars of SHA-256)
+pub(crate) fn hash_identifier(value: &str) -> String {
+    let digest = Sha256::digest(value.as_bytes());
+    digest
+        .iter()
+        .take(8)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
 /// Validation errors
+///
+/// Serialized as `{ code, reason_code, message, detail }` so consumers can
+/// switch on the numeric code without matching English text.
 #[derive(Error, Debug, Clone, Serialize, Deserialize)]
+#[serde(into = "SerializedError", try_from = "SerializedError")]
 pub enum ValidationError {
     #[error("Invalid amount: {0}")]
     InvalidAmount(String),
@@ -60,6 +277,9 @@ pub enum ValidationError {
     #[error("Duplicate transaction detected: {0}")]
     DuplicateTransaction(String),
 
+    #[error("Invalid routing number: {0}")]
+    InvalidRoutingNumber(String),
+
     #[error("Fraud pattern detected: {0}")]
     FraudDetected(String),
 
@@ -74,6 +294,153 @@ pub enum ValidationError {
 
     #[error("Risk threshold exceeded: {0}")]
     RiskThresholdExceeded(String),
+
+    #[error("Sanctions match: {0}")]
+    SanctionsMatch(String),
+
+    #[error("Prohibited jurisdiction: {0}")]
+    ProhibitedJurisdiction(String),
+
+    #[error("Comprehensively sanctioned jurisdiction: {0}")]
+    SanctionedJurisdiction(String),
+
+    #[error("Blocklisted: {0}")]
+    Blocklisted(String),
+}
+
+impl ValidationError {
+    /// Machine-readable reason code for this error
+    pub fn reason_code(&self) -> &'static str {
+        match self {
+            ValidationError::InvalidAmount(_) => "INVALID_AMOUNT",
+            ValidationError::InvalidAccount(_) => "INVALID_ACCOUNT",
+            ValidationError::DuplicateTransaction(_) => "DUPLICATE_TRANSACTION",
+            ValidationError::InvalidRoutingNumber(_) => "INVALID_ROUTING_NUMBER",
+            ValidationError::FraudDetected(_) => "FRAUD_DETECTED",
+            ValidationError::ComplianceFailed(_) => "COMPLIANCE_FAILED",
+            ValidationError::BusinessRuleViolation(_) => "BUSINESS_RULE_VIOLATION",
+            ValidationError::VelocityViolation(_) => "VELOCITY_VIOLATION",
+            ValidationError::RiskThresholdExceeded(_) => "RISK_THRESHOLD_EXCEEDED",
+            ValidationError::SanctionsMatch(_) => "SANCTIONS_MATCH",
+            ValidationError::ProhibitedJurisdiction(_) => "PROHIBITED_JURISDICTION",
+            ValidationError::SanctionedJurisdiction(_) => "SANCTIONED_JURISDICTION",
+            ValidationError::Blocklisted(_) => "BLOCKLISTED",
+        }
+    }
+
+    /// Stable numeric code for this error
+    ///
+    /// Codes are grouped by category: 1xxx input, 2xxx risk, 3xxx
+    /// compliance, 4xxx business rules. They never change once assigned.
+    pub fn code(&self) -> u16 {
+        match self {
+            ValidationError::InvalidAmount(_) => 1001,
+            ValidationError::InvalidAccount(_) => 1002,
+            ValidationError::DuplicateTransaction(_) => 1003,
+            ValidationError::InvalidRoutingNumber(_) => 1004,
+            ValidationError::FraudDetected(_) => 2001,
+            ValidationError::VelocityViolation(_) => 2002,
+            ValidationError::RiskThresholdExceeded(_) => 2003,
+            ValidationError::ComplianceFailed(_) => 3001,
+            ValidationError::SanctionsMatch(_) => 3002,
+            ValidationError::ProhibitedJurisdiction(_) => 3003,
+            ValidationError::SanctionedJurisdiction(_) => 3004,
+            ValidationError::Blocklisted(_) => 3005,
+            ValidationError::BusinessRuleViolation(_) => 4001,
+        }
+    }
+
+    /// Variant payload without the category prefix
+    pub fn detail(&self) -> &str {
+        match self {
+            ValidationError::InvalidAmount(d)
+            | ValidationError::InvalidAccount(d)
+            | ValidationError::DuplicateTransaction(d)
+            | ValidationError::InvalidRoutingNumber(d)
+            | ValidationError::FraudDetected(d)
+            | ValidationError::ComplianceFailed(d)
+            | ValidationError::BusinessRuleViolation(d)
+            | ValidationError::VelocityViolation(d)
+            | ValidationError::RiskThresholdExceeded(d)
+            | ValidationError::SanctionsMatch(d)
+            | ValidationError::ProhibitedJurisdiction(d)
+            | ValidationError::SanctionedJurisdiction(d)
+            | ValidationError::Blocklisted(d) => d,
+        }
+    }
+
+    /// Rebuild an error from its numeric code and detail
+    pub fn from_code(code: u16, detail: String) -> Option<Self> {
+        Some(match code {
+            1001 => ValidationError::InvalidAmount(detail),
+            1002 => ValidationError::InvalidAccount(detail),
+            1003 => ValidationError::DuplicateTransaction(detail),
+            1004 => ValidationError::InvalidRoutingNumber(detail),
+            2001 => ValidationError::FraudDetected(detail),
+            2002 => ValidationError::VelocityViolation(detail),
+            2003 => ValidationError::RiskThresholdExceeded(detail),
+            3001 => ValidationError::ComplianceFailed(detail),
+            3002 => ValidationError::SanctionsMatch(detail),
+            3003 => ValidationError::ProhibitedJurisdiction(detail),
+            3004 => ValidationError::SanctionedJurisdiction(detail),
+            3005 => ValidationError::Blocklisted(detail),
+            4001 => ValidationError::BusinessRuleViolation(detail),
+            _ => return None,
+        })
+    }
+}
+
+/// Wire format for [`ValidationError`]
+#[derive(Serialize, Deserialize)]
+struct SerializedError {
+    code: u16,
+    reason_code: String,
+    message: String,
+    detail: String,
+}
+
+impl From<ValidationError> for SerializedError {
+    fn from(error: ValidationError) -> Self {
+        Self {
+            code: error.code(),
+            reason_code: error.reason_code().to_string(),
+            message: error.to_string(),
+            detail: error.detail().to_string(),
+        }
+    }
+}
+
+impl TryFrom<SerializedError> for ValidationError {
+    type Error = String;
+
+    fn try_from(value: SerializedError) -> Result<Self, Self::Error> {
+        ValidationError::from_code(value.code, value.detail)
+            .ok_or_else(|| format!("unknown validation error code {}", value.code))
+    }
+}
+
+/// Final decision for a validated transaction
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum Decision {
+    /// Passed all checks
+    Approve,
+    /// Valid but flagged for manual review
+    Review,
+    /// Valid once the customer passes step-up verification (OTP, 3-D Secure)
+    StepUp,
+    /// Failed one or more checks
+    Decline,
+}
+
+impl std::fmt::Display for Decision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Decision::Approve => write!(f, "approve"),
+            Decision::Review => write!(f, "review"),
+            Decision::StepUp => write!(f, "step_up"),
+            Decision::Decline => write!(f, "decline"),
+        }
+    }
 }
 
 /// Risk breakdown for detailed analysis
@@ -83,6 +450,18 @@ pub struct RiskBreakdown {
     pub velocity_risk: u8,
     pub pattern_risk: u8,
     pub time_risk: u8,
+    /// Highest known risk of the origin/destination countries (needs a geographic scorer)
+    #[serde(default)]
+    pub geo_risk: u8,
+    /// Risk from suspicious network patterns (needs a network analyzer)
+    #[serde(default)]
+    pub network_risk: u8,
+    /// Unweighted offset from counterparty trust (negative for established payees)
+    #[serde(default)]
+    pub trust_adjustment: i8,
+    /// Fraud probability × 100 from the model scorer (`ml` feature)
+    #[serde(default)]
+    pub model_risk: u8,
     pub total_score: u8,
 }
 
@@ -93,28 +472,94 @@ impl RiskBreakdown {
             velocity_risk: 0,
             pattern_risk: 0,
             time_risk: 0,
+            geo_risk: 0,
+            network_risk: 0,
+            trust_adjustment: 0,
+            model_risk: 0,
             total_score: 0,
         }
     }
 
-    fn calculate_total(&mut self) {
-        self.total_score = self
-            .amount_risk
-            .saturating_add(self.velocity_risk)
-            .saturating_add(self.pattern_risk)
-            .saturating_add(self.time_risk)
-            .min(100);
+    fn calculate_total(&mut self, weights: &RiskWeights) {
+        let total = self.amount_risk as f64 * weights.amount
+            + self.velocity_risk as f64 * weights.velocity
+            + self.pattern_risk as f64 * weights.pattern
+            + self.time_risk as f64 * weights.time
+            + self.geo_risk as f64 * weights.geo
+            + self.network_risk as f64 * weights.network
+            + self.model_risk as f64 * weights.model
+            + self.trust_adjustment as f64;
+        self.total_score = total.round().clamp(0.0, 100.0) as u8;
+    }
+}
+
+/// Multipliers applied to each risk component when computing the total score
+///
+/// Geographic and network risk are recorded in the breakdown but weighted 0
+/// by default, so scores only change when a deployment opts in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskWeights {
+    pub amount: f64,
+    pub velocity: f64,
+    pub pattern: f64,
+    pub time: f64,
+    pub geo: f64,
+    pub network: f64,
+    /// Only non-zero when a model scorer is attached
+    #[serde(default = "default_model_weight")]
+    pub model: f64,
+}
+
+fn default_model_weight() -> f64 {
+    1.0
+}
+
+impl Default for RiskWeights {
+    fn default() -> Self {
+        Self {
+            amount: 1.0,
+            velocity: 1.0,
+            pattern: 1.0,
+            time: 1.0,
+            geo: 0.0,
+            network: 0.0,
+            model: default_model_weight(),
+        }
     }
 }
 
 /// Transaction type
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum TransactionType {
     Deposit,
     Withdrawal,
     Transfer,
     Payment,
     WireTransfer,
+    AchCredit,
+    AchDebit,
+    CardPurchase,
+    Check,
+    Refund,
+    Reversal,
+    Fee,
+    CryptoTransfer,
+    /// Hold placed on funds, settled later by a `Capture`
+    Authorization,
+    /// Settlement of an earlier `Authorization`
+    Capture,
+}
+
+impl TransactionType {
+    /// Refunds, reversals, and captures act on an earlier transaction and must name it
+    pub fn references_parent(&self) -> bool {
+        self.reverses_parent() || *self == TransactionType::Capture
+    }
+
+    /// Refunds and reversals undo an earlier transaction
+    pub fn reverses_parent(&self) -> bool {
+        matches!(self, TransactionType::Refund | TransactionType::Reversal)
+    }
 }
 
 impl std::fmt::Display for TransactionType {
@@ -125,6 +570,16 @@ impl std::fmt::Display for TransactionType {
             TransactionType::Transfer => write!(f, "transfer"),
             TransactionType::Payment => write!(f, "payment"),
             TransactionType::WireTransfer => write!(f, "wire_transfer"),
+            TransactionType::AchCredit => write!(f, "ach_credit"),
+            TransactionType::AchDebit => write!(f, "ach_debit"),
+            TransactionType::CardPurchase => write!(f, "card_purchase"),
+            TransactionType::Check => write!(f, "check"),
+            TransactionType::Refund => write!(f, "refund"),
+            TransactionType::Reversal => write!(f, "reversal"),
+            TransactionType::Fee => write!(f, "fee"),
+            TransactionType::CryptoTransfer => write!(f, "crypto_transfer"),
+            TransactionType::Authorization => write!(f, "authorization"),
+            TransactionType::Capture => write!(f, "capture"),
         }
     }
 }
@@ -140,19 +595,111 @@ pub struct Transaction {
     pub to_account: Option<String>,
     pub timestamp: DateTime<Utc>,
     pub user_id: String,
-    pub metadata: Option<HashMap<String, String>>,
+    pub metadata: Option<TransactionMetadata>,
+    /// Date the funds are booked to the beneficiary
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_date: Option<NaiveDate>,
+    /// Date the payment settles between institutions, if it differs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settlement_date: Option<NaiveDate>,
+}
+
+impl Transaction {
+    /// Content fingerprint ignoring the transaction ID
+    ///
+    /// Hashes amount (in cents), currency, accounts, type, and the timestamp
+    /// rounded down to `bucket_seconds`, so a payment resubmitted under a
+    /// fresh ID within the same bucket produces the same fingerprint.
+    pub fn fingerprint(&self, bucket_seconds: i64) -> String {
+        self.fingerprint_in_bucket(self.bucket(bucket_seconds))
+    }
+
+    /// Fingerprints this transaction would have in the buckets just before
+    /// and after its own
+    ///
+    /// A resubmission a few seconds later can land across a bucket edge;
+    /// the duplicate check matches these too.
+    pub fn neighbouring_fingerprints(&self, bucket_seconds: i64) -> [String; 2] {
+        let bucket = self.bucket(bucket_seconds);
+        [
+            self.fingerprint_in_bucket(bucket - 1),
+            self.fingerprint_in_bucket(bucket + 1),
+        ]
+    }
+
+    fn bucket(&self, bucket_seconds: i64) -> i64 {
+        self.timestamp.timestamp().div_euclid(bucket_seconds.max(1))
+    }
+
+    fn fingerprint_in_bucket(&self, bucket: i64) -> String {
+        let content = format!(
+            "{}|{}|{}|{}|{}|{}",
+            (self.amount * 100.0).round() as i64,
+            self.currency,
+            self.from_account.as_deref().unwrap_or(""),
+            self.to_account.as_deref().unwrap_or(""),
+            self.transaction_type,
+            bucket
+        );
+        let digest = Sha256::digest(content.as_bytes());
+        digest.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Transaction a refund or reversal undoes, from its metadata
+    pub fn parent_transaction_id(&self) -> Option<&str> {
+        self.metadata
+            .as_ref()
+            .and_then(|m| m.get(PARENT_TRANSACTION_METADATA_KEY))
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+    }
 }
 
 /// Validation result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationResult {
     pub transaction_id: String,
+    /// Transaction amount, kept for downstream ranking and reporting
+    pub amount: f64,
     pub is_valid: bool,
     pub errors: Vec<ValidationError>,
-    pub warnings: Vec<String>,
+    pub warnings: Vec<Warning>,
     pub fraud_score: u8,
     pub risk_breakdown: RiskBreakdown,
     pub compliance_checks: HashMap<String, bool>,
+    /// Checks skipped by budget limits or short-circuiting
+    pub skipped_checks: Vec<Check>,
+    pub decision: Decision,
+    /// Tenant that validated the transaction (multi-tenant deployments)
+    pub tenant_id: Option<String>,
+    /// Customer segment used to pick config overrides
+    pub segment: Option<CustomerSegment>,
+    /// Channel the transaction was submitted through
+    pub channel: Option<Channel>,
+    /// Limit profile whose limits replaced the global ones
+    pub limit_profile: Option<String>,
+    /// Configuration in effect after all override layers were applied
+    pub effective_config: ValidatorConfig,
+    /// Version of the validator's policy (see [`PolicySnapshot::version`])
+    #[serde(default)]
+    pub policy_version: String,
+    /// Reference data that was unavailable to this validation
+    #[serde(default)]
+    pub degraded: Vec<DegradationReason>,
+    /// Typology pack rules the transaction matched
+    #[serde(default)]
+    pub typology_hits: Vec<TypologyHit>,
+    /// Each risk component's contribution to the fraud score
+    #[serde(default)]
+    pub explanation: Explanation,
+    /// Executed checks and their timings, with `record_check_telemetry` set
+    #[serde(default)]
+    pub check_telemetry: Option<CheckTelemetry>,
+    /// Which sampled stages ran, when `ValidatorConfig::sampling` is set
+    #[serde(default)]
+    pub sampling: Vec<SamplingDecision>,
+    /// Whether dedup and velocity state was updated with this transaction
+    pub committed: bool,
     pub validated_at: DateTime<Utc>,
 }
 
@@ -162,11 +709,54 @@ impl ValidationResult {
         self.is_valid && self.errors.is_empty() && self.fraud_score < 50
     }
 
+    /// Check if a graylist entry asks for step-up verification
+    pub fn requires_step_up(&self) -> bool {
+        self.warnings.iter().any(|w| w.code == STEP_UP_WARNING_CODE)
+    }
+
     /// Check if transaction requires manual review
     pub fn requires_manual_review(&self) -> bool {
         self.fraud_score >= 50 || !self.warnings.is_empty()
     }
 
+    /// Reason codes for all errors on this result
+    pub fn reason_codes(&self) -> Vec<String> {
+        self.errors
+            .iter()
+            .map(|e| e.reason_code().to_string())
+            .collect()
+    }
+
+    /// Numeric codes for all errors on this result
+    pub fn error_codes(&self) -> Vec<u16> {
+        self.errors.iter().map(|e| e.code()).collect()
+    }
+
+    /// Codes of all warnings on this result
+    pub fn warning_codes(&self) -> Vec<String> {
+        self.warnings.iter().map(|w| w.code.clone()).collect()
+    }
+
+    /// Warnings at or above a severity
+    pub fn warnings_at_least(&self, severity: WarningSeverity) -> Vec<&Warning> {
+        self.warnings
+            .iter()
+            .filter(|w| w.severity >= severity)
+            .collect()
+    }
+
+    fn derive_decision(&self) -> Decision {
+        if !self.is_valid {
+            Decision::Decline
+        } else if self.requires_step_up() {
+            Decision::StepUp
+        } else if self.requires_manual_review() {
+            Decision::Review
+        } else {
+            Decision::Approve
+        }
+    }
+
     /// Get risk level description
     pub fn risk_level(&self) -> &str {
         match self.fraud_score {
@@ -183,339 +773,3452 @@ impl ValidationResult {
     }
 }
 
-/// Transaction history entry for velocity checking
-#[derive(Debug, Clone)]
-struct TransactionHistory {
-    user_id: String,
-    timestamp: DateTime<Utc>,
-    amount: f64,
-}
-
 /// Transaction validator configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidatorConfig {
     pub max_transaction_amount: f64,
     pub min_transaction_amount: f64,
     pub fraud_threshold: u8,
     pub enable_duplicate_check: bool,
     pub enable_aml_check: bool,
+    pub enable_velocity_check: bool,
+    pub enable_time_risk: bool,
+    /// Flag large round amounts in the pattern check
+    pub enable_round_amount_check: bool,
+    /// Flag every wire transfer for review in the pattern check
+    pub enable_wire_review: bool,
+    /// Hard-block transactions whose origin or destination is under
+    /// comprehensive sanctions, even without a name match
+    pub block_sanctioned_jurisdictions: bool,
+    pub enable_account_format_check: bool,
+    pub enable_business_rules: bool,
     pub velocity_check_window_minutes: i64,
     pub max_transactions_per_window: usize,
     pub max_amount_per_window: f64,
+    /// Skip remaining checks once a sanctions hit or prohibited country is found
+    pub short_circuit_hard_fails: bool,
+    /// Time budget for checks (microseconds); deferrable checks are deferred once spent
+    pub check_budget_micros: Option<u64>,
+    /// Derive per-user velocity limits from each user's own history
+    pub adaptive_velocity: Option<AdaptiveVelocity>,
+    /// Stricter velocity limits on corridors the geo scorer rates High
+    pub high_risk_corridor_limits: Option<CorridorLimits>,
+    /// Limit the credits one receiving account collects per window
+    pub inbound_velocity: Option<InboundVelocityLimits>,
+    /// Run expensive stages on only a sample of low-risk traffic
+    pub sampling: Option<SamplingPolicy>,
+    /// Also flag replays by content fingerprint, bucketing timestamps to this
+    /// many seconds; the buckets on either side match as well
+    pub fingerprint_bucket_seconds: Option<i64>,
+    /// Warn on same user, beneficiary, and amount within this many minutes
+    pub double_payment_window_minutes: Option<i64>,
+    /// Extra pattern risk for very new accounts with large or rapid activity
+    pub new_account_risk: Option<NewAccountRisk>,
+    /// Extra pattern risk for users and counterparties with prior disputes
+    pub dispute_risk: Option<DisputeRisk>,
+    /// Send degraded validations that would be approved to review instead
+    pub review_when_degraded: bool,
+    /// Mask card numbers in accounts and metadata before checking or storing anything
+    pub mask_pans: bool,
+    /// Take the customer segment from `customer_segment` metadata when
+    /// enrichment supplies no tier; only for submitters that are trusted
+    #[serde(default)]
+    pub trust_segment_metadata: bool,
+    /// Warn when a loaded reference dataset is older than this many days
+    pub max_reference_data_age_days: Option<i64>,
+    /// Regulatory regime whose profile configured this validator
+    pub jurisdiction: Option<Jurisdiction>,
+    /// How long validation records must be kept
+    pub record_retention_days: Option<i64>,
+    /// Record which checks ran and how long each took on every result
+    #[serde(default)]
+    pub record_check_telemetry: bool,
+    /// When `validate()` records transactions into dedup and velocity state
+    pub commit_policy: CommitPolicy,
+    /// Weights of the risk components in the total score
+    pub risk_weights: RiskWeights,
+    /// Hour windows and scores used for time-of-day risk
+    pub time_risk_profile: TimeRiskProfile,
+    /// Amount thresholds and the risk points they carry
+    pub amount_risk_tiers: AmountRiskTiers,
+    /// Transaction types that may carry zero or negative amounts
+    pub amount_sign_policy: AmountSignPolicy,
+    /// Round-amount pattern thresholds, points, and exempt products
+    pub round_amount: RoundAmountRule,
 }
 
-impl Default for ValidatorConfig {
+/// When validation commits a transaction into dedup and velocity state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum CommitPolicy {
+    /// Record every validated transaction, including rejected attempts
+    Always,
+    /// Record only transactions that pass, so corrected resubmissions are not
+    /// flagged as duplicates
+    #[default]
+    OnValid,
+}
+
+/// Adaptive velocity limits derived from a user's trailing baseline
+///
+/// Limits are `multiplier` times the user's average count and amount per
+/// velocity window over the baseline period, clamped to the given bounds.
+/// Users with no baseline get the floor values.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AdaptiveVelocity {
+    pub baseline_days: i64,
+    pub multiplier: f64,
+    pub min_transactions_per_window: usize,
+    pub max_transactions_per_window: usize,
+    pub min_amount_per_window: f64,
+    pub max_amount_per_window: f64,
+}
+
+impl Default for AdaptiveVelocity {
     fn default() -> Self {
         Self {
-            max_transaction_amount: 1_000_000.0,
-            min_transaction_amount: 0.01,
-            fraud_threshold: 70,
-            enable_duplicate_check: true,
-            enable_aml_check: true,
-            velocity_check_window_minutes: 60, // 1 hour window
-            max_transactions_per_window: 10,
-            max_amount_per_window: 100_000.0,
+            baseline_days: 90,
+            multiplier: 3.0,
+            min_transactions_per_window: 10,
+            max_transactions_per_window: 100,
+            min_amount_per_window: 100_000.0,
+            max_amount_per_window: 1_000_000.0,
         }
     }
 }
 
-/// Financial transaction validator
-pub struct TransactionValidator {
-    config: ValidatorConfig,
-    processed_transactions: Vec<String>,
-    transaction_history: Vec<TransactionHistory>,
+/// Velocity limits for transactions on high-risk corridors
+///
+/// Applies when both the origin and destination country are known and the
+/// geographic scorer rates the pair High or Prohibited. Each limit caps the
+/// regular (or adaptive) limit; the lower one wins.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CorridorLimits {
+    pub max_transactions_per_window: usize,
+    pub max_amount_per_window: f64,
 }
 
-impl TransactionValidator {
-    /// Create a new validator with default configuration
-    pub fn new() -> Self {
+impl Default for CorridorLimits {
+    fn default() -> Self {
         Self {
-            config: ValidatorConfig::default(),
-            processed_transactions: Vec::new(),
-            transaction_history: Vec::new(),
+            max_transactions_per_window: 3,
+            max_amount_per_window: 10_000.0,
         }
     }
+}
 
-    /// Create a new validator with custom configuration
-    pub fn with_config(config: ValidatorConfig) -> Self {
+/// Velocity limits on credits received by one account
+///
+/// Every transaction crediting the receiving account (`to_account`) in the
+/// window counts, whoever sent it, so collection and mule accounts are
+/// caught as funds arrive rather than by later graph analysis. Scored with
+/// the outbound limits in the velocity check.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InboundVelocityLimits {
+    pub window_minutes: i64,
+    pub max_credits_per_window: usize,
+    pub max_amount_per_window: f64,
+}
+
+impl Default for InboundVelocityLimits {
+    fn default() -> Self {
         Self {
-            config,
-            processed_transactions: Vec::new(),
-            transaction_history: Vec::new(),
+            window_minutes: 60,
+            max_credits_per_window: 20,
+            max_amount_per_window: 50_000.0,
         }
     }
+}
 
-    /// Validate a transaction
-    pub fn validate(&mut self, transaction: &Transaction) -> ValidationResult {
-        let mut errors = Vec::new();
-        let mut warnings = Vec::new();
-        let mut compliance_checks = HashMap::new();
-        let mut risk_breakdown = RiskBreakdown::new();
-
-        // 1. Amount validation
-        if let Err(e) = self.validate_amount(transaction) {
-            errors.push(e);
-        }
-
-        // Calculate amount risk
-        risk_breakdown.amount_risk = self.calculate_amount_risk(transaction.amount);
+/// Extra risk for very new accounts moving large amounts or transacting rapidly
+///
+/// Account age comes from enrichment (`account_age_days` or
+/// `account_opened_at`) or the `account_opened_at` metadata key. Accounts of
+/// unknown age are not scored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewAccountRisk {
+    /// Accounts younger than this many days count as new
+    pub max_age_days: i64,
+    /// Single-transaction amount considered large for a new account
+    pub large_amount: f64,
+    /// Transactions in the velocity window, including this one, that count as rapid
+    pub high_velocity_count: usize,
+    /// Pattern-risk points added when a new account is large or rapid
+    pub points: u8,
+}
 
-        // 2. Account validation
-        if let Err(e) = self.validate_accounts(transaction) {
-            errors.push(e);
+impl Default for NewAccountRisk {
+    fn default() -> Self {
+        Self {
+            max_age_days: 30,
+            large_amount: 5_000.0,
+            high_velocity_count: 5,
+            points: 25,
         }
+    }
+}
 
-        // 3. Duplicate detection
-        if self.config.enable_duplicate_check {
-            if self
-                .processed_transactions
-                .contains(&transaction.transaction_id)
-            {
-                errors.push(ValidationError::DuplicateTransaction(
-                    transaction.transaction_id.clone(),
-                ));
-            } else {
-                self.processed_transactions
-                    .push(transaction.transaction_id.clone());
-            }
-        }
+/// Extra risk for users and counterparties with chargeback history
+///
+/// History comes from enrichment (`user_disputes`, `counterparty_disputes`,
+/// or the legacy `prior_chargebacks` count). Recent confirmed disputes
+/// escalate sharply; older or unconfirmed chargebacks add a little.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DisputeRisk {
+    /// Confirmed disputes within this many days count as recent
+    pub lookback_days: i64,
+    /// Pattern-risk points per recent confirmed dispute
+    pub points_per_dispute: u8,
+    /// Cap on points from recent confirmed disputes
+    pub max_points: u8,
+    /// Lifetime chargebacks at which history alone is flagged
+    pub chargeback_threshold: u32,
+    /// Pattern-risk points for reaching `chargeback_threshold`
+    pub chargeback_points: u8,
+}
 
-        // 4. Velocity checks
-        let velocity_result = self.check_velocity(transaction);
-        risk_breakdown.velocity_risk = velocity_result.0;
-        if let Some(err) = velocity_result.1 {
-            errors.push(err);
-        }
-        if !velocity_result.2.is_empty() {
-            warnings.extend(velocity_result.2);
+impl Default for DisputeRisk {
+    fn default() -> Self {
+        Self {
+            lookback_days: 90,
+            points_per_dispute: 35,
+            max_points: 70,
+            chargeback_threshold: 3,
+            chargeback_points: 10,
         }
+    }
+}
 
-        // Record transaction in history
-        self.transaction_history.push(TransactionHistory {
-            user_id: transaction.user_id.clone(),
-            timestamp: transaction.timestamp,
-            amount: transaction.amount,
-        });
-
-        // 5. Fraud detection
-        let fraud_checks = self.check_fraud_patterns(transaction);
-        risk_breakdown.pattern_risk = fraud_checks.0;
-        if !fraud_checks.1.is_empty() {
-            warnings.extend(fraud_checks.1);
-        }
+/// Amounts strictly above `above` carry `points` of amount risk
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AmountTier {
+    pub above: f64,
+    pub points: u8,
+}
 
-        // 6. Time-based risk
-        risk_breakdown.time_risk = self.calculate_time_risk(&transaction.timestamp);
+/// Tier table used in place of the default for matching transactions
+///
+/// `None` fields match anything.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AmountTierOverride {
+    pub transaction_type: Option<TransactionType>,
+    pub currency: Option<String>,
+    pub tiers: Vec<AmountTier>,
+}
 
-        // Calculate total risk
-        risk_breakdown.calculate_total();
-        let fraud_score = risk_breakdown.total_score;
-
-        // 7. AML compliance
-        if self.config.enable_aml_check {
-            let aml_result = self.check_aml_compliance(transaction);
-            compliance_checks.insert("AML".to_string(), aml_result);
-            if !aml_result {
-                errors.push(ValidationError::ComplianceFailed(
-                    "AML compliance check failed".to_string(),
-                ));
+impl AmountTierOverride {
+    /// Number of matching fields, or `None` if any set field differs
+    fn specificity(&self, transaction: &Transaction) -> Option<u8> {
+        let mut matched = 0;
+        if let Some(transaction_type) = self.transaction_type {
+            if transaction_type != transaction.transaction_type {
+                return None;
             }
+            matched += 1;
         }
-
-        // 8. Business rules
-        if let Err(e) = self.check_business_rules(transaction) {
-            errors.push(e);
-        }
-
-        // 9. Risk threshold check
-        if fraud_score > self.config.fraud_threshold {
-            errors.push(ValidationError::RiskThresholdExceeded(format!(
-                "Risk score {} exceeds threshold {}",
-                fraud_score, self.config.fraud_threshold
-            )));
+        if let Some(ref currency) = self.currency {
+            if !currency.eq_ignore_ascii_case(&transaction.currency) {
+                return None;
+            }
+            matched += 1;
         }
+        Some(matched)
+    }
+}
 
-        let is_valid = errors.is_empty();
+/// Amount-risk tier table, optionally per transaction type and currency
+///
+/// A transaction scores the highest points among the tiers it exceeds. The
+/// most specific matching override replaces the default tiers; ties go to
+/// the override listed first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AmountRiskTiers {
+    pub tiers: Vec<AmountTier>,
+    pub overrides: Vec<AmountTierOverride>,
+}
 
-        ValidationResult {
-            transaction_id: transaction.transaction_id.clone(),
-            is_valid,
-            errors,
-            warnings,
-            fraud_score,
-            risk_breakdown,
-            compliance_checks,
-            validated_at: Utc::now(),
+impl Default for AmountRiskTiers {
+    fn default() -> Self {
+        Self {
+            tiers: vec![
+                AmountTier {
+                    above: 10_000.0,
+                    points: 15,
+                },
+                AmountTier {
+                    above: 50_000.0,
+                    points: 30,
+                },
+                AmountTier {
+                    above: 100_000.0,
+                    points: 40,
+                },
+            ],
+            overrides: Vec::new(),
         }
     }
+}
 
-    /// Calculate amount-based risk score
-    fn calculate_amount_risk(&self, amount: f64) -> u8 {
-        if amount > 100_000.0 {
-            40
-        } else if amount > 50_000.0 {
-            30
-        } else if amount > 10_000.0 {
-            15
-        } else {
-            0
+impl AmountRiskTiers {
+    /// Tiers that apply to a transaction
+    pub fn tiers_for(&self, transaction: &Transaction) -> &[AmountTier] {
+        let mut best: Option<(u8, &AmountTierOverride)> = None;
+        for candidate in &self.overrides {
+            if let Some(specificity) = candidate.specificity(transaction) {
+                if best.is_none_or(|(top, _)| specificity > top) {
+                    best = Some((specificity, candidate));
+                }
+            }
         }
+        best.map_or(&self.tiers, |(_, o)| &o.tiers)
     }
 
-    /// Calculate time-based risk score
-    fn calculate_time_risk(&self, timestamp: &DateTime<Utc>) -> u8 {
-        let hour = timestamp.hour();
-        if !(6..=22).contains(&hour) {
-            20 // High risk outside business hours
-        } else if !(9..=17).contains(&hour) {
-            10 // Medium risk outside normal hours
-        } else {
-            0 // Low risk during business hours
-        }
+    /// Highest-scoring tier the transaction exceeds
+    pub fn tier_for(&self, transaction: &Transaction) -> Option<&AmountTier> {
+        self.tiers_for(transaction)
+            .iter()
+            .filter(|tier| transaction.amount > tier.above)
+            .max_by_key(|tier| tier.points)
     }
 
-    /// Check transaction velocity (multiple transactions in short period)
-    fn check_velocity(
-        &self,
-        transaction: &Transaction,
-    ) -> (u8, Option<ValidationError>, Vec<String>) {
-        let mut risk_score = 0u8;
-        let mut error = None;
-        let mut warnings = Vec::new();
-
-        let window_start =
-            transaction.timestamp - Duration::minutes(self.config.velocity_check_window_minutes);
-
-        // Get recent transactions from same user
-        let recent_transactions: Vec<&TransactionHistory> = self
-            .transaction_history
-            .iter()
-            .filter(|h| h.user_id == transaction.user_id && h.timestamp >= window_start)
-            .collect();
+    /// Amount risk points for a transaction
+    pub fn score(&self, transaction: &Transaction) -> u8 {
+        self.tier_for(transaction).map_or(0, |tier| tier.points)
+    }
+}
 
-        let transaction_count = recent_transactions.len();
-        let total_amount: f64 =
-            recent_transactions.iter().map(|h| h.amount).sum::<f64>() + transaction.amount;
+/// Which transaction types may carry zero or negative amounts
+///
+/// By default every amount must be positive. Feeds that include
+/// adjustments, fee reversals, or zero-amount card verifications list those
+/// types here instead of filtering them out first. Minimum and maximum
+/// amounts apply to the magnitude of a negative amount and not to zero, and
+/// negative amounts never offset velocity window totals.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AmountSignPolicy {
+    /// Types accepted with an amount of exactly zero
+    pub allow_zero: Vec<TransactionType>,
+    /// Types accepted with a negative amount
+    pub allow_negative: Vec<TransactionType>,
+}
 
-        // Check transaction count
-        if transaction_count >= self.config.max_transactions_per_window {
-            risk_score = risk_score.saturating_add(30);
-            error = Some(ValidationError::VelocityViolation(format!(
-                "Too many transactions: {} in {} minutes",
-                transaction_count + 1,
-                self.config.velocity_check_window_minutes
-            )));
-        } else if transaction_count >= (self.config.max_transactions_per_window / 2) {
-            risk_score = risk_score.saturating_add(15);
-            warnings.push(format!(
-                "High transaction velocity: {} transactions in window",
-                transaction_count + 1
-            ));
+impl AmountSignPolicy {
+    /// Zero-amount authorizations and negative fees, reversals, and refunds
+    pub fn ledger() -> Self {
+        Self {
+            allow_zero: vec![TransactionType::Authorization],
+            allow_negative: vec![
+                TransactionType::Fee,
+                TransactionType::Reversal,
+                TransactionType::Refund,
+            ],
         }
+    }
 
-        // Check total amount
-        if total_amount >= self.config.max_amount_per_window {
-            risk_score = risk_score.saturating_add(25);
-            error = Some(ValidationError::VelocityViolation(format!(
-                "Total amount ${:.2} exceeds window limit ${:.2}",
-                total_amount, self.config.max_amount_per_window
-            )));
-        } else if total_amount >= (self.config.max_amount_per_window * 0.75) {
-            risk_score = risk_score.saturating_add(10);
-            warnings.push(format!(
-                "Approaching amount limit: ${:.2} of ${:.2}",
-                total_amount, self.config.max_amount_per_window
-            ));
+    /// Check whether `amount` has a sign the transaction type may carry
+    pub fn permits(&self, transaction_type: TransactionType, amount: f64) -> bool {
+        if amount > 0.0 {
+            true
+        } else if amount == 0.0 {
+            self.allow_zero.contains(&transaction_type)
+        } else {
+            self.allow_negative.contains(&transaction_type)
         }
-
-        (risk_score, error, warnings)
     }
+}
 
-    /// Validate transaction amount
-    fn validate_amount(&self, transaction: &Transaction) -> Result<(), ValidationError> {
-        if transaction.amount <= 0.0 {
-            return Err(ValidationError::InvalidAmount(
-                "Amount must be positive".to_string(),
-            ));
-        }
+/// Time-of-day risk bands (UTC hours, inclusive)
+///
+/// Hours inside `business_hours` score 0, hours inside `extended_hours`
+/// score `extended_hours_score`, and anything else scores `off_hours_score`.
+/// A window whose start is after its end wraps past midnight.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeRiskProfile {
+    pub business_hours: (u32, u32),
+    pub extended_hours: (u32, u32),
+    pub extended_hours_score: u8,
+    pub off_hours_score: u8,
+    /// Score for every hour on Saturdays and Sundays; `None` scores weekends like weekdays
+    pub weekend_score: Option<u8>,
+}
 
-        if transaction.amount < self.config.min_transaction_amount {
-            return Err(ValidationError::InvalidAmount(format!(
-                "Amount {} below minimum {}",
-                transaction.amount, self.config.min_transaction_amount
-            )));
+impl Default for TimeRiskProfile {
+    fn default() -> Self {
+        Self {
+            business_hours: (9, 17),
+            extended_hours: (6, 22),
+            extended_hours_score: 10,
+            off_hours_score: 20,
+            weekend_score: None,
         }
+    }
+}
 
-        if transaction.amount > self.config.max_transaction_amount {
-            return Err(ValidationError::InvalidAmount(format!(
-                "Amount {} exceeds maximum {}",
-                transaction.amount, self.config.max_transaction_amount
-            )));
+impl TimeRiskProfile {
+    /// Round-the-clock profile (e.g. card businesses): no hour carries time risk
+    pub fn always_open() -> Self {
+        Self {
+            business_hours: (0, 23),
+            extended_hours: (0, 23),
+            extended_hours_score: 0,
+            off_hours_score: 0,
+            weekend_score: None,
         }
-
-        Ok(())
     }
 
-    /// Validate account numbers
-    fn validate_accounts(&self, transaction: &Transaction) -> Result<(), ValidationError> {
-        let account_regex =
-            Regex::new(r"^[A-Z0-9]{4}-[A-Z0-9]{4}-[A-Z0-9]{4}-[A-Z0-9]{4}$").unwrap();
+    /// Whether `hour` falls inside the extended window
+    pub fn is_extended_hours(&self, hour: u32) -> bool {
+        in_window(self.extended_hours, hour)
+    }
 
-        if let Some(ref from_account) = transaction.from_account {
-            if !account_regex.is_match(from_account) && !from_account.starts_with("****") {
-                return Err(ValidationError::InvalidAccount(format!(
-                    "Invalid from_account format: {}",
-                    from_account
-                )));
+    /// Risk score for a timestamp
+    pub fn score(&self, timestamp: &DateTime<Utc>) -> u8 {
+        if let Some(score) = self.weekend_score {
+            if matches!(timestamp.weekday(), Weekday::Sat | Weekday::Sun) {
+                return score;
             }
         }
-
-        if let Some(ref to_account) = transaction.to_account {
-            if !account_regex.is_match(to_account) && !to_account.starts_with("****") {
-                return Err(ValidationError::InvalidAccount(format!(
-                    "Invalid to_account format: {}",
-                    to_account
-                )));
-            }
+        let hour = timestamp.hour();
+        if in_window(self.business_hours, hour) {
+            0
+        } else if in_window(self.extended_hours, hour) {
+            self.extended_hours_score
+        } else {
+            self.off_hours_score
         }
+    }
+}
 
-        Ok(())
+/// Inclusive hour window, wrapping past midnight when `start > end`
+fn in_window((start, end): (u32, u32), hour: u32) -> bool {
+    if start <= end {
+        (start..=end).contains(&hour)
+    } else {
+        hour >= start || hour <= end
     }
+}
 
-    /// Check for fraud patterns
-    fn check_fraud_patterns(&self, transaction: &Transaction) -> (u8, Vec<String>) {
-        let mut score = 0u8;
+impl Default for ValidatorConfig {
+    fn default() -> Self {
+        Self {
+            max_transaction_amount: 1_000_000.0,
+            min_transaction_amount: 0.01,
+            fraud_threshold: 70,
+            enable_duplicate_check: true,
+            enable_aml_check: true,
+            enable_velocity_check: true,
+            enable_time_risk: true,
+            enable_round_amount_check: true,
+            enable_wire_review: true,
+            block_sanctioned_jurisdictions: true,
+            enable_account_format_check: true,
+            enable_business_rules: true,
+            velocity_check_window_minutes: 60, // 1 hour window
+            max_transactions_per_window: 10,
+            max_amount_per_window: 100_000.0,
+            short_circuit_hard_fails: false,
+            check_budget_micros: None,
+            adaptive_velocity: None,
+            high_risk_corridor_limits: None,
+            inbound_velocity: None,
+            sampling: None,
+            fingerprint_bucket_seconds: None,
+            double_payment_window_minutes: None,
+            new_account_risk: None,
+            dispute_risk: None,
+            review_when_degraded: false,
+            mask_pans: true,
+            trust_segment_metadata: false,
+            max_reference_data_age_days: None,
+            jurisdiction: None,
+            record_retention_days: None,
+            record_check_telemetry: false,
+            commit_policy: CommitPolicy::OnValid,
+            risk_weights: RiskWeights::default(),
+            time_risk_profile: TimeRiskProfile::default(),
+            amount_risk_tiers: AmountRiskTiers::default(),
+            amount_sign_policy: AmountSignPolicy::default(),
+            round_amount: RoundAmountRule::with_severity(20),
+        }
+    }
+}
+
+impl ValidatorConfig {
+    /// Stable version identifier (SHA-256 of the serialized config)
+    pub fn version(&self) -> String {
+        let encoded = serde_json::to_vec(self).unwrap_or_default();
+        let digest = Sha256::digest(&encoded);
+        digest.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+/// Financial transaction validator
+pub struct TransactionValidator {
+    config: ValidatorConfig,
+    plan: ExecutionPlan,
+    dedup: SharedDedup,
+    history: SharedHistory,
+    decision_logger: Option<Box<dyn DecisionLogger + Send>>,
+    event_log: Option<Box<dyn EventLog + Send>>,
+    audit: Option<audit::AuditBuffer>,
+    /// Sequence number of the last event written or replayed
+    event_sequence: u64,
+    sanctions_screener: Option<Box<dyn ScreeningBackend>>,
+    geo_scorer: Option<GeographicRiskScorer>,
+    fraud_detector: Option<FraudDetector>,
+    aml_checker: Option<AMLChecker>,
+    network_analyzer: Option<NetworkAnalyzer>,
+    counterparty_trust: Option<CounterpartyTrust>,
+    activity_profiles: Option<ActivityProfiles>,
+    refund_ledger: Option<RefundLedger>,
+    settlement_calendar: Option<SettlementCalendar>,
+    reporting_queue: Option<ReportingQueue>,
+    authorization_book: Option<AuthorizationBook>,
+    typologies: Option<TypologyLibrary>,
+    account_formats: AccountFormatRegistry,
+    normalizer: Option<MetadataNormalizer>,
+    pseudonymizer: Option<Pseudonymizer>,
+    /// Tenant stamped on every result, set by [`MultiTenantValidator`]
+    tenant_id: Option<String>,
+    pre_hooks: Vec<Box<dyn PreValidationHook>>,
+    post_hooks: Vec<Box<dyn PostValidationHook>>,
+    rules: Vec<Box<dyn ValidationRule>>,
+    challenge_provider: Option<Box<dyn ChallengeProvider>>,
+    #[cfg(feature = "ml")]
+    model_scorer: Option<Box<dyn ml::ModelScorer>>,
+    alerts: AlertDispatcher,
+    retroactive: RetroactiveAlerter,
+    enrichment_providers: Vec<Box<dyn EnrichmentProvider>>,
+    segment_overrides: HashMap<CustomerSegment, ConfigOverride>,
+    channel_policies: HashMap<Channel, ChannelPolicy>,
+    limit_profiles: LimitProfiles,
+    /// Cached [`PolicySnapshot::version`], refreshed by every policy setter
+    policy_version: String,
+    /// Every snapshot this validator has run under, for replay
+    policy_archive: PolicyArchive,
+    watchlist: Watchlist,
+    /// Budget-deferred checks of committed transactions, awaiting follow-up
+    deferred: Vec<DeferredChecks>,
+    stats: stats::StatsCollector,
+    clock: SharedClock,
+}
+
+/// Map a fraud flag's 0-100 severity onto a warning severity
+fn flag_severity(severity: u8) -> WarningSeverity {
+    match severity {
+        0..=14 => WarningSeverity::Low,
+        15..=29 => WarningSeverity::Medium,
+        _ => WarningSeverity::High,
+    }
+}
+
+/// Map an AML alert severity onto a warning severity
+fn alert_severity(severity: &AlertSeverity) -> WarningSeverity {
+    match severity {
+        AlertSeverity::Low => WarningSeverity::Low,
+        AlertSeverity::Medium => WarningSeverity::Medium,
+        AlertSeverity::High | AlertSeverity::Critical => WarningSeverity::High,
+    }
+}
+
+impl TransactionValidator {
+    /// Create a new validator with default configuration
+    pub fn new() -> Self {
+        Self::with_config(ValidatorConfig::default())
+    }
+
+    /// Create a new validator with custom configuration
+    pub fn with_config(config: ValidatorConfig) -> Self {
+        let plan = ExecutionPlan::new(&Self::enabled_checks(&config));
+        let mut validator = Self {
+            config,
+            plan,
+            dedup: SharedDedup::default(),
+            history: SharedHistory::default(),
+            decision_logger: None,
+            event_log: None,
+            audit: None,
+            event_sequence: 0,
+            sanctions_screener: None,
+            geo_scorer: None,
+            fraud_detector: None,
+            aml_checker: None,
+            network_analyzer: None,
+            counterparty_trust: None,
+            activity_profiles: None,
+            refund_ledger: None,
+            settlement_calendar: None,
+            reporting_queue: None,
+            authorization_book: None,
+            typologies: None,
+            account_formats: AccountFormatRegistry::new(),
+            normalizer: None,
+            pseudonymizer: None,
+            tenant_id: None,
+            pre_hooks: Vec::new(),
+            post_hooks: Vec::new(),
+            rules: Vec::new(),
+            challenge_provider: None,
+            #[cfg(feature = "ml")]
+            model_scorer: None,
+            alerts: AlertDispatcher::new(),
+            retroactive: RetroactiveAlerter::default(),
+            enrichment_providers: Vec::new(),
+            segment_overrides: HashMap::new(),
+            channel_policies: HashMap::new(),
+            limit_profiles: LimitProfiles::new(),
+            policy_version: String::new(),
+            policy_archive: PolicyArchive::new(),
+            watchlist: Watchlist::new(),
+            deferred: Vec::new(),
+            stats: stats::StatsCollector::new(),
+            clock: clock::system_clock(),
+        };
+        validator.refresh_policy_version();
+        validator
+    }
+
+    /// Start assembling a validator with explicit pipeline components
+    pub fn builder() -> TransactionValidatorBuilder {
+        TransactionValidatorBuilder::new()
+    }
+
+    /// Checks enabled by the configuration
+    fn enabled_checks(config: &ValidatorConfig) -> Vec<Check> {
+        Check::ALL
+            .into_iter()
+            .filter(|check| match check {
+                Check::Duplicate => config.enable_duplicate_check,
+                Check::Aml => config.enable_aml_check,
+                Check::Velocity => config.enable_velocity_check,
+                Check::TimeRisk => config.enable_time_risk,
+                Check::AccountFormat => config.enable_account_format_check,
+                Check::BusinessRules => config.enable_business_rules,
+                _ => true,
+            })
+            .collect()
+    }
+
+    /// Checks in the order `validate()` runs them
+    pub fn execution_plan(&self) -> &ExecutionPlan {
+        &self.plan
+    }
+
+    /// Take the checks deferred past the time budget on committed transactions
+    pub fn take_deferred(&mut self) -> Vec<DeferredChecks> {
+        std::mem::take(&mut self.deferred)
+    }
+
+    /// Run deferred checks without a budget
+    ///
+    /// The decision and the committed state are left as they were; the
+    /// caller acts on the findings, e.g. by opening a case.
+    pub fn run_deferred(&self, deferred: &DeferredChecks) -> FollowUpResult {
+        let mut state = CheckState::new();
+        state.context = deferred.context.clone();
+        for check in &deferred.checks {
+            self.run_check(&deferred.config, *check, &deferred.transaction, &mut state);
+        }
+        FollowUpResult {
+            transaction_id: deferred.transaction.transaction_id.clone(),
+            checks: deferred.checks.clone(),
+            errors: state.errors,
+            warnings: state.warnings,
+            risk_breakdown: state.risk_breakdown,
+        }
+    }
+
+    /// Attach a sink that receives one structured record per validation
+    pub fn set_decision_logger(&mut self, logger: Box<dyn DecisionLogger + Send>) {
+        self.decision_logger = Some(logger);
+    }
+
+    /// Send normalized audit records to `sink` in batches of `batch_size`
+    pub fn set_audit_sink(&mut self, sink: Box<dyn AuditSink>, batch_size: usize) {
+        self.audit = Some(audit::AuditBuffer::new(sink, batch_size));
+    }
+
+    /// Write audit records still waiting for a full batch
+    pub fn flush_audit(&mut self) -> std::io::Result<()> {
+        match self.audit {
+            Some(ref mut audit) => audit.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Write each validation to a write-ahead event log before committing it
+    ///
+    /// A transaction whose event fails to append is not committed: its
+    /// result has `committed` unset, an `EVENT_LOG_FAILED` warning, and a
+    /// Review decision unless it was declined, and the failure is counted
+    /// in stats.
+    pub fn set_event_log(&mut self, log: Box<dyn EventLog + Send>) {
+        self.event_log = Some(log);
+    }
+
+    /// Re-apply committed events from a write-ahead log, e.g. after a crash
+    ///
+    /// No check runs and nothing is logged again. Events at or below the last
+    /// sequence already written or replayed are skipped, so replaying the same
+    /// log twice is harmless. Group legs are applied together once the whole
+    /// group is in the log and dropped if it never is. Returns the number of
+    /// events applied.
+    pub fn replay_events<I: IntoIterator<Item = ValidationEvent>>(&mut self, events: I) -> usize {
+        let mut applied = 0;
+        let mut groups: HashMap<String, Vec<ValidationEvent>> = HashMap::new();
+        for event in events {
+            if event.sequence <= self.event_sequence {
+                continue;
+            }
+            self.event_sequence = event.sequence;
+            let ready = match event.group.clone() {
+                None => vec![event],
+                Some(group) => {
+                    let legs = groups.entry(group.group_id.clone()).or_default();
+                    legs.push(event);
+                    if legs.len() < group.legs {
+                        continue;
+                    }
+                    groups.remove(&group.group_id).unwrap_or_default()
+                }
+            };
+            for event in ready.into_iter().filter(|e| e.committed) {
+                self.commit_state(&event.transaction, event.recorded.into());
+                applied += 1;
+            }
+        }
+        applied
+    }
+
+    /// Screen counterparty names in metadata against sanctions lists
+    pub fn set_sanctions_screener(&mut self, screener: SanctionsScreener) {
+        self.set_screening_backend(screener);
+    }
+
+    /// Screen counterparty names through a custom backend, such as a vendor service
+    ///
+    /// A backend error fails the sanctions compliance check rather than
+    /// letting the transaction through unscreened; wrap remote backends in
+    /// [`FallbackScreening`] to fall back to the local engine instead.
+    pub fn set_screening_backend<B: ScreeningBackend + 'static>(&mut self, mut backend: B) {
+        backend.set_clock(self.clock.clone());
+        self.sanctions_screener = Some(Box::new(backend));
+        self.refresh_policy_version();
+    }
+
+    /// Read the current time from `clock` instead of the system clock
+    ///
+    /// The clock is handed to the screening backend, fraud detector, and
+    /// network analyzer, now and whenever one is attached later. Stats
+    /// uptime restarts from the new clock's current time.
+    pub fn set_clock(&mut self, clock: SharedClock) {
+        if let Some(ref mut screener) = self.sanctions_screener {
+            screener.set_clock(clock.clone());
+        }
+        if let Some(ref mut detector) = self.fraud_detector {
+            detector.set_clock(clock.clone());
+        }
+        if let Some(ref mut analyzer) = self.network_analyzer {
+            analyzer.set_clock(clock.clone());
+        }
+        self.stats.started_at = clock.now();
+        self.clock = clock;
+    }
+
+    /// Clock the validator reads the current time from
+    pub fn clock(&self) -> &SharedClock {
+        &self.clock
+    }
+
+    /// Check origin/destination countries in metadata for prohibited jurisdictions
+    pub fn set_geographic_scorer(&mut self, scorer: GeographicRiskScorer) {
+        self.geo_scorer = Some(scorer);
+        self.refresh_policy_version();
+    }
+
+    /// Score transactions with a stateful fraud detector during the pattern check
+    ///
+    /// The detector is switched to the validator's history store, so both see
+    /// the transactions committed under the validator's commit policy.
+    pub fn set_fraud_detector(&mut self, mut detector: FraudDetector) {
+        detector.set_history(self.history.clone());
+        detector.set_clock(self.clock.clone());
+        self.fraud_detector = Some(detector);
+        self.refresh_policy_version();
+    }
+
+    /// Replace the history store used for velocity checks and the fraud detector
+    pub fn set_history(&mut self, history: SharedHistory) {
+        if let Some(detector) = self.fraud_detector.as_mut() {
+            detector.set_history(history.clone());
+        }
+        self.history = history;
+    }
+
+    /// Handle to the validator's history store
+    pub fn history(&self) -> &SharedHistory {
+        &self.history
+    }
+
+    /// Replace the store of committed transaction IDs and fingerprints
+    pub fn set_dedup(&mut self, dedup: SharedDedup) {
+        self.dedup = dedup;
+    }
+
+    /// Handle to the validator's duplicate detection store
+    pub fn dedup(&self) -> &SharedDedup {
+        &self.dedup
+    }
+
+    /// Apply AML rules (structuring, CTR thresholds, sanctioned accounts) in the AML check
+    ///
+    /// Red flags become `AML_RED_FLAG` warnings, and a non-compliant result
+    /// fails the check.
+    pub fn set_aml_checker(&mut self, checker: AMLChecker) {
+        self.aml_checker = Some(checker);
+        self.refresh_policy_version();
+    }
+
+    /// Feed committed transfers into a network analyzer and flag accounts
+    /// involved in suspicious graph patterns
+    pub fn set_network_analyzer(&mut self, mut analyzer: NetworkAnalyzer) {
+        analyzer.set_clock(self.clock.clone());
+        self.network_analyzer = Some(analyzer);
+        self.refresh_policy_version();
+    }
+
+    /// Network analyzer fed by this validator, if any
+    pub fn network_analyzer(&self) -> Option<&NetworkAnalyzer> {
+        self.network_analyzer.as_ref()
+    }
+
+    /// Offset risk by learned trust in each (user, counterparty) relationship
+    pub fn set_counterparty_trust(&mut self, trust: CounterpartyTrust) {
+        self.counterparty_trust = Some(trust);
+        self.refresh_policy_version();
+    }
+
+    /// Counterparty trust tracker fed by this validator, if any
+    pub fn counterparty_trust(&self) -> Option<&CounterpartyTrust> {
+        self.counterparty_trust.as_ref()
+    }
+
+    /// Mutable access to the trust tracker, for recording disputes
+    pub fn counterparty_trust_mut(&mut self) -> Option<&mut CounterpartyTrust> {
+        self.counterparty_trust.as_mut()
+    }
+
+    /// Score time risk against each mature user's own activity pattern
+    pub fn set_activity_profiles(&mut self, profiles: ActivityProfiles) {
+        self.activity_profiles = Some(profiles);
+        self.refresh_policy_version();
+    }
+
+    /// Activity profiles fed by this validator, if any
+    pub fn activity_profiles(&self) -> Option<&ActivityProfiles> {
+        self.activity_profiles.as_ref()
+    }
+
+    /// Check refunds and reversals against the transactions they undo
+    pub fn set_refund_ledger(&mut self, ledger: RefundLedger) {
+        self.refund_ledger = Some(ledger);
+        self.refresh_policy_version();
+    }
+
+    /// Refund ledger fed by this validator, if any
+    pub fn refund_ledger(&self) -> Option<&RefundLedger> {
+        self.refund_ledger.as_ref()
+    }
+
+    /// Check value and settlement dates against holidays and cut-offs
+    pub fn set_settlement_calendar(&mut self, calendar: SettlementCalendar) {
+        self.settlement_calendar = Some(calendar);
+        self.refresh_policy_version();
+    }
+
+    /// Settlement calendar used by the business rules, if any
+    pub fn settlement_calendar(&self) -> Option<&SettlementCalendar> {
+        self.settlement_calendar.as_ref()
+    }
+
+    /// Enqueue the CTRs and SARs the AML checker requires on commit
+    pub fn set_reporting_queue(&mut self, queue: ReportingQueue) {
+        self.reporting_queue = Some(queue);
+    }
+
+    /// Report obligations fed by this validator, if any
+    pub fn reporting_queue(&self) -> Option<&ReportingQueue> {
+        self.reporting_queue.as_ref()
+    }
+
+    /// Mutable access to the reporting queue, for recording filings
+    pub fn reporting_queue_mut(&mut self) -> Option<&mut ReportingQueue> {
+        self.reporting_queue.as_mut()
+    }
+
+    /// Unfiled report obligations past their deadline on the validator's clock
+    pub fn overdue_reports(&self) -> Vec<&ReportObligation> {
+        let now = self.clock.now();
+        self.reporting_queue
+            .as_ref()
+            .map(|queue| queue.overdue(now))
+            .unwrap_or_default()
+    }
+
+    /// Replace the account number schemes accepted by the format check
+    pub fn set_account_formats(&mut self, registry: AccountFormatRegistry) {
+        self.account_formats = registry;
+        self.refresh_policy_version();
+    }
+
+    /// Account number schemes accepted by the format check
+    pub fn account_formats(&self) -> &AccountFormatRegistry {
+        &self.account_formats
+    }
+
+    /// Check captures against authorization holds
+    pub fn set_authorization_book(&mut self, book: AuthorizationBook) {
+        self.authorization_book = Some(book);
+        self.refresh_policy_version();
+    }
+
+    /// Authorization holds fed by this validator, if any
+    pub fn authorization_book(&self) -> Option<&AuthorizationBook> {
+        self.authorization_book.as_ref()
+    }
+
+    /// Release holds expired on the validator's clock, returning their IDs
+    pub fn expire_authorizations(&mut self) -> Vec<String> {
+        let now = self.clock.now();
+        self.authorization_book
+            .as_mut()
+            .map(|book| book.expire(now))
+            .unwrap_or_default()
+    }
+
+    /// Match transactions against the enabled typology packs
+    pub fn set_typologies(&mut self, library: TypologyLibrary) {
+        self.typologies = Some(library);
+        self.refresh_policy_version();
+    }
+
+    /// Typology packs enabled on this validator, if any
+    pub fn typologies(&self) -> Option<&TypologyLibrary> {
+        self.typologies.as_ref()
+    }
+
+    /// Fraud detector used in the pattern check, if any
+    pub fn fraud_detector(&self) -> Option<&FraudDetector> {
+        self.fraud_detector.as_ref()
+    }
+
+    /// AML checker used in the AML check, if any
+    pub fn aml_checker(&self) -> Option<&AMLChecker> {
+        self.aml_checker.as_ref()
+    }
+
+    /// Sanctions screening backend, if any
+    pub fn screening_backend(&self) -> Option<&dyn ScreeningBackend> {
+        self.sanctions_screener.as_deref()
+    }
+
+    /// Geographic risk scorer, if any
+    pub fn geographic_scorer(&self) -> Option<&GeographicRiskScorer> {
+        self.geo_scorer.as_ref()
+    }
+
+    /// Canonicalize metadata after pre-hooks and before checks run
+    pub fn set_normalizer(&mut self, normalizer: MetadataNormalizer) {
+        self.normalizer = Some(normalizer);
+        self.refresh_policy_version();
+    }
+
+    /// Metadata normalizer, if any
+    pub fn normalizer(&self) -> Option<&MetadataNormalizer> {
+        self.normalizer.as_ref()
+    }
+
+    /// Store user IDs and account numbers only as keyed HMAC tokens
+    pub fn set_pseudonymizer(&mut self, pseudonymizer: Pseudonymizer) {
+        self.pseudonymizer = Some(pseudonymizer);
+        self.refresh_policy_version();
+    }
+
+    /// Identifier pseudonymizer, if any
+    pub fn pseudonymizer(&self) -> Option<&Pseudonymizer> {
+        self.pseudonymizer.as_ref()
+    }
+
+    /// Tenant this validator serves, if registered with a [`MultiTenantValidator`]
+    pub fn tenant_id(&self) -> Option<&str> {
+        self.tenant_id.as_deref()
+    }
+
+    pub(crate) fn set_tenant_id(&mut self, tenant_id: &str) {
+        self.tenant_id = Some(tenant_id.to_string());
+    }
+
+    /// Add a hook that can enrich the transaction before checks run
+    pub fn add_pre_hook<H: PreValidationHook + 'static>(&mut self, hook: H) {
+        self.pre_hooks.push(Box::new(hook));
+        self.refresh_policy_version();
+    }
+
+    /// Add a hook that can annotate or override the result after checks run
+    pub fn add_post_hook<H: PostValidationHook + 'static>(&mut self, hook: H) {
+        self.post_hooks.push(Box::new(hook));
+        self.refresh_policy_version();
+    }
+
+    /// Run a custom rule after the built-in checks, in registration order
+    pub fn register_rule(&mut self, rule: Box<dyn ValidationRule>) {
+        self.rules.push(rule);
+        self.refresh_policy_version();
+    }
+
+    /// Names of the registered custom rules, in execution order
+    pub fn rule_names(&self) -> Vec<&str> {
+        self.rules.iter().map(|rule| rule.name()).collect()
+    }
+
+    /// Notify `observer` whenever a result matches `trigger`
+    pub fn on_alert<O: AlertObserver + 'static>(&mut self, trigger: AlertTrigger, observer: O) {
+        self.alerts.subscribe(trigger, observer);
+    }
+
+    /// Follow-up alerts for approvals on accounts in newly detected patterns
+    ///
+    /// Runs the network analyzer, reads each newly involved account's recent
+    /// records from `trail`, and notifies `AlertTrigger::NetworkPattern`
+    /// observers once per approval. Each (account, pattern) pair is followed
+    /// up once. Without a network analyzer there is nothing to follow up.
+    pub fn retroactive_alerts(
+        &mut self,
+        trail: &dyn AuditTrail,
+    ) -> std::io::Result<Vec<AlertEvent>> {
+        let Some(analyzer) = &self.network_analyzer else {
+            return Ok(Vec::new());
+        };
+        let report = analyzer.analyze_all();
+        let events = self
+            .retroactive
+            .follow_ups(&report, trail, self.clock.now())?;
+        for event in &events {
+            self.alerts.notify(event);
+        }
+        Ok(events)
+    }
+
+    /// Compile an exit review of an account or customer
+    ///
+    /// Audit records validated within `lookback` supply the validation
+    /// history, alerts, and screening outcomes; the validator adds its own
+    /// history, watchlist, and network view.
+    pub fn exit_review(
+        &self,
+        subject: &ReviewSubject,
+        trail: &dyn AuditTrail,
+        lookback: Duration,
+    ) -> std::io::Result<ExitReviewReport> {
+        ExitReviewReport::compile(self, subject, trail, lookback)
+    }
+
+    /// Replace the look-back used by [`retroactive_alerts`](Self::retroactive_alerts)
+    pub fn set_retroactive_lookback(&mut self, lookback: Duration) {
+        self.retroactive = RetroactiveAlerter::new(lookback);
+    }
+
+    /// Add a provider queried for enrichment data on every validation
+    ///
+    /// Providers are consulted in registration order; earlier providers win
+    /// when several supply the same field.
+    pub fn add_enrichment_provider<P: EnrichmentProvider + 'static>(&mut self, provider: P) {
+        self.enrichment_providers.push(Box::new(provider));
+        self.refresh_policy_version();
+    }
+
+    /// Override configuration for transactions from a customer segment
+    pub fn set_segment_override(&mut self, segment: CustomerSegment, config: ConfigOverride) {
+        self.segment_overrides.insert(segment, config);
+        self.refresh_policy_version();
+    }
+
+    /// Thresholds and restrictions for transactions from a channel
+    pub fn set_channel_policy(&mut self, channel: Channel, policy: ChannelPolicy) {
+        self.channel_policies.insert(channel, policy);
+        self.refresh_policy_version();
+    }
+
+    /// Add or replace a named limit profile
+    pub fn add_limit_profile(&mut self, name: &str, profile: LimitProfile) {
+        self.limit_profiles.add_profile(name, profile);
+        self.refresh_policy_version();
+    }
+
+    /// Base configuration, override layers, and rules version in force
+    pub fn policy_snapshot(&self) -> PolicySnapshot {
+        PolicySnapshot::new(
+            &self.config,
+            self.segment_overrides.iter(),
+            self.channel_policies.iter(),
+            self.limit_profiles.profiles(),
+            self.limit_profiles.assignments(),
+            self.attached_components(),
+        )
+    }
+
+    /// Names of attached components a [`PolicySnapshot`] cannot capture
+    fn attached_components(&self) -> Vec<String> {
+        let attached = [
+            ("screening_backend", self.sanctions_screener.is_some()),
+            ("geographic_scorer", self.geo_scorer.is_some()),
+            ("fraud_detector", self.fraud_detector.is_some()),
+            ("aml_checker", self.aml_checker.is_some()),
+            ("network_analyzer", self.network_analyzer.is_some()),
+            ("counterparty_trust", self.counterparty_trust.is_some()),
+            ("activity_profiles", self.activity_profiles.is_some()),
+            ("refund_ledger", self.refund_ledger.is_some()),
+            ("settlement_calendar", self.settlement_calendar.is_some()),
+            ("authorization_book", self.authorization_book.is_some()),
+            ("typologies", self.typologies.is_some()),
+            ("normalizer", self.normalizer.is_some()),
+            ("pseudonymizer", self.pseudonymizer.is_some()),
+            ("challenge_provider", self.challenge_provider.is_some()),
+            ("pre_hooks", !self.pre_hooks.is_empty()),
+            ("post_hooks", !self.post_hooks.is_empty()),
+            (
+                "enrichment_providers",
+                !self.enrichment_providers.is_empty(),
+            ),
+            (
+                "account_formats",
+                self.account_formats.names() != AccountFormatRegistry::default().names(),
+            ),
+            #[cfg(feature = "ml")]
+            ("model_scorer", self.model_scorer.is_some()),
+        ];
+        attached
+            .iter()
+            .filter(|(_, attached)| *attached)
+            .map(|(name, _)| name.to_string())
+            .chain(
+                self.rules
+                    .iter()
+                    .map(|rule| format!("rule:{}", rule.name())),
+            )
+            .collect()
+    }
+
+    /// Version stamped on every result, audit record, and decision record
+    pub fn policy_version(&self) -> &str {
+        &self.policy_version
+    }
+
+    /// Snapshots available to [`replay`](Self::replay)
+    pub fn policy_archive(&self) -> &PolicyArchive {
+        &self.policy_archive
+    }
+
+    /// Replace the archive, e.g. with one persisted by an earlier process
+    ///
+    /// The current policy is archived again so it stays replayable.
+    pub fn set_policy_archive(&mut self, archive: PolicyArchive) {
+        self.policy_archive = archive;
+        self.refresh_policy_version();
+    }
+
+    /// Validator running exactly the given policy
+    pub fn from_policy(snapshot: &PolicySnapshot) -> Self {
+        let mut validator = Self::with_config(snapshot.config.clone());
+        for (segment, layer) in &snapshot.segment_overrides {
+            validator.set_segment_override(*segment, layer.clone());
+        }
+        for (channel, policy) in &snapshot.channel_policies {
+            validator.set_channel_policy(*channel, policy.clone());
+        }
+        for (name, profile) in &snapshot.limit_profiles {
+            validator.add_limit_profile(name, profile.clone());
+        }
+        for (user_id, profile) in &snapshot.limit_assignments {
+            validator.limit_profiles.assign(user_id, profile);
+        }
+        validator.refresh_policy_version();
+        validator
+    }
+
+    /// Re-run a past transaction under an archived policy version
+    ///
+    /// Runs on a fresh validator with its clock fixed at the transaction's
+    /// timestamp, seeded with the history committed before it and the
+    /// watchlist entries added by then; this validator's state is untouched. Fails if the version was
+    /// recorded with attached components, or if history the policy looks
+    /// back over has been pruned.
+    pub fn replay(
+        &self,
+        transaction: &Transaction,
+        config_version: &str,
+    ) -> Result<ValidationResult, ReplayError> {
+        let snapshot = self.policy_archive.replayable(config_version)?;
+        if !snapshot.components.is_empty() {
+            return Err(ReplayError::UnreproducibleComponents(
+                snapshot.components.clone(),
+            ));
+        }
+
+        let history = self.history.read();
+        let needed_from = transaction.timestamp - Self::history_lookback(&snapshot.config);
+        if let Some(pruned_before) = history.pruned_before().filter(|p| *p > needed_from) {
+            return Err(ReplayError::HistoryPruned {
+                needed_from,
+                pruned_before,
+            });
+        }
+
+        let mut replayer = Self::from_policy(snapshot);
+        replayer.set_clock(Arc::new(FixedClock(transaction.timestamp)));
+        replayer.set_watchlist(self.watchlist.as_of(transaction.timestamp));
+        // Committed before it if it is in history, otherwise by timestamp
+        let position = history
+            .iter()
+            .position(|t| t.transaction_id == transaction.transaction_id);
+        let earlier: Vec<&Transaction> = match position {
+            Some(position) => history.iter().take(position).collect(),
+            None => history
+                .iter()
+                .filter(|t| t.timestamp <= transaction.timestamp)
+                .collect(),
+        };
+        let bucket = snapshot.config.fingerprint_bucket_seconds;
+        for earlier in earlier {
+            let pending = PendingState {
+                transaction_id: true,
+                fingerprint: bucket.map(|bucket| earlier.fingerprint(bucket)),
+                history: true,
+                ..Default::default()
+            };
+            replayer.commit_state(earlier, pending);
+        }
+        Ok(replayer.validate(transaction))
+    }
+
+    /// How far back before a transaction the policy's checks read history
+    fn history_lookback(config: &ValidatorConfig) -> Duration {
+        [
+            Some(Duration::minutes(config.velocity_check_window_minutes)),
+            config
+                .inbound_velocity
+                .as_ref()
+                .map(|limits| Duration::minutes(limits.window_minutes)),
+            config.double_payment_window_minutes.map(Duration::minutes),
+            config
+                .adaptive_velocity
+                .as_ref()
+                .map(|adaptive| Duration::days(adaptive.baseline_days)),
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+        .unwrap_or_else(Duration::zero)
+    }
+
+    fn refresh_policy_version(&mut self) {
+        let snapshot = self.policy_snapshot();
+        self.policy_version = self.policy_archive.insert(snapshot);
+    }
+
+    /// Hold a user to a named limit profile instead of the global limits
+    pub fn assign_limit_profile(&mut self, user_id: &str, profile: &str) {
+        match self.pseudonymizer {
+            Some(ref p) => self.limit_profiles.assign(&p.pseudonym(user_id), profile),
+            None => self.limit_profiles.assign(user_id, profile),
+        }
+        self.refresh_policy_version();
+    }
+
+    /// Blend a trained model's score into the risk breakdown
+    #[cfg(feature = "ml")]
+    pub fn set_model_scorer(&mut self, scorer: Box<dyn ml::ModelScorer>) {
+        self.model_scorer = Some(scorer);
+        self.refresh_policy_version();
+    }
+
+    /// Challenge the customer on Review and StepUp results, settling the decision
+    pub fn set_challenge_provider<P: ChallengeProvider + 'static>(&mut self, provider: P) {
+        self.challenge_provider = Some(Box::new(provider));
+        self.refresh_policy_version();
+    }
+
+    /// Replace the institution's watchlist
+    pub fn set_watchlist(&mut self, watchlist: Watchlist) {
+        self.watchlist = watchlist;
+    }
+
+    /// The institution's watchlist, consulted before any other check
+    pub fn watchlist(&self) -> &Watchlist {
+        &self.watchlist
+    }
+
+    /// Mutable access to the watchlist for adding entries
+    pub fn watchlist_mut(&mut self) -> &mut Watchlist {
+        &mut self.watchlist
+    }
+
+    /// Validate a transaction
+    ///
+    /// Dedup and velocity state is committed according to the configured
+    /// [`CommitPolicy`].
+    pub fn validate(&mut self, transaction: &Transaction) -> ValidationResult {
+        self.validate_with_context(transaction, EnrichmentContext::default())
+    }
+
+    /// Validate a transaction with prefetched enrichment data
+    ///
+    /// Registered providers only fill fields `context` leaves unset.
+    pub fn validate_with_context(
+        &mut self,
+        transaction: &Transaction,
+        context: EnrichmentContext,
+    ) -> ValidationResult {
+        self.run_validation(transaction, context, None, Vec::new())
+    }
+
+    /// Validate after awaiting a provider's remote lookups
+    ///
+    /// The provider's sanctions, geolocation and KYC answers are applied
+    /// with the screening check; see [`external`] for how each one affects
+    /// the result. The validator stays borrowed while the lookups run.
+    pub async fn validate_async<P: ExternalScreeningProvider>(
+        &mut self,
+        transaction: &Transaction,
+        provider: &P,
+    ) -> ValidationResult {
+        let findings = ExternalFindings::collect(provider, transaction).await;
+        self.run_validation_with(
+            transaction,
+            EnrichmentContext::default(),
+            None,
+            Vec::new(),
+            Some(findings),
+        )
+    }
+
+    /// Validate without committing dedup or velocity state
+    ///
+    /// Stats, alerts, and the decision log are not touched either, so a
+    /// dry run leaves the validator exactly as it was.
+    pub fn validate_dry_run(&mut self, transaction: &Transaction) -> ValidationResult {
+        self.run_validation(
+            transaction,
+            EnrichmentContext::default(),
+            Some(false),
+            Vec::new(),
+        )
+    }
+
+    /// Validate and commit state only if `commit` is true, regardless of outcome
+    pub fn validate_with_commit(
+        &mut self,
+        transaction: &Transaction,
+        commit: bool,
+    ) -> ValidationResult {
+        self.run_validation(
+            transaction,
+            EnrichmentContext::default(),
+            Some(commit),
+            Vec::new(),
+        )
+    }
+
+    fn run_validation(
+        &mut self,
+        transaction: &Transaction,
+        context: EnrichmentContext,
+        commit: Option<bool>,
+        notes: Vec<Warning>,
+    ) -> ValidationResult {
+        self.run_validation_with(transaction, context, commit, notes, None)
+    }
+
+    fn run_validation_with(
+        &mut self,
+        transaction: &Transaction,
+        context: EnrichmentContext,
+        commit: Option<bool>,
+        notes: Vec<Warning>,
+        external: Option<ExternalFindings>,
+    ) -> ValidationResult {
+        let evaluated = self.evaluate(transaction, context, commit, notes, external);
+        self.record(evaluated, commit)
+    }
+
+    /// Run every check and decide, without committing or logging anything
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "validate",
+            skip_all,
+            fields(
+                transaction_id = %transaction.transaction_id,
+                user_id_hash = %hash_identifier(&transaction.user_id),
+                amount_risk = tracing::field::Empty,
+                velocity_risk = tracing::field::Empty,
+                pattern_risk = tracing::field::Empty,
+                time_risk = tracing::field::Empty,
+                fraud_score = tracing::field::Empty,
+                is_valid = tracing::field::Empty,
+                decision = tracing::field::Empty,
+                reason_codes = tracing::field::Empty,
+                correlation_id = tracing::field::Empty,
+            )
+        )
+    )]
+    fn evaluate<'a>(
+        &self,
+        transaction: &'a Transaction,
+        context: EnrichmentContext,
+        commit: Option<bool>,
+        notes: Vec<Warning>,
+        external: Option<ExternalFindings>,
+    ) -> Evaluated<'a> {
+        let started = Instant::now();
+
+        // Pre-hooks and normalization work on a copy so the caller's
+        // transaction is untouched
+        let mut normalized = NormalizationReport::default();
+        let mut masked = Vec::new();
+        let mut account_verdicts = None;
+        let working = if self.pre_hooks.is_empty()
+            && self.normalizer.is_none()
+            && self.pseudonymizer.is_none()
+            && !(self.config.mask_pans && Self::carries_pan(transaction))
+        {
+            Cow::Borrowed(transaction)
+        } else {
+            let mut copy = transaction.clone();
+            for hook in &self.pre_hooks {
+                hook.before_validate(&mut copy);
+            }
+            if let Some(ref normalizer) = self.normalizer {
+                normalized = normalizer.normalize(&mut copy);
+            }
+            if self.config.mask_pans {
+                masked = pan::mask_transaction(&mut copy);
+            }
+            if let Some(ref pseudonymizer) = self.pseudonymizer {
+                account_verdicts = Some(AccountVerdicts {
+                    formats: self.account_formats_valid(&copy),
+                    routing: self.validate_routing_numbers(&copy),
+                    sanctioned_entity: self
+                        .aml_checker
+                        .as_ref()
+                        .map(|checker| checker.involves_sanctioned_entity(&copy)),
+                });
+                pseudonymizer.pseudonymize(&mut copy);
+            }
+            Cow::Owned(copy)
+        };
+        let transaction: &Transaction = &working;
+        #[cfg(feature = "tracing")]
+        if let Some(id) = transaction
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get(CORRELATION_ID_METADATA_KEY))
+        {
+            tracing::Span::current().record("correlation_id", tracing::field::display(id));
+        }
+        let mut state = CheckState::new();
+        state.context = context;
+        state.external = external;
+        state.account_verdicts = account_verdicts;
+        state.warnings = notes;
+        state.warnings.extend(normalized.warnings());
+        state.warnings.extend(pan::masked_warning(&masked));
+        for provider in &self.enrichment_providers {
+            match provider.enrich(transaction) {
+                Ok(found) => state.context.merge(found),
+                Err(e) => {
+                    state.warnings.push(Warning::new(
+                        "ENRICHMENT_FAILED",
+                        WarningSeverity::Low,
+                        format!("Enrichment provider {} failed: {}", provider.name(), e),
+                    ));
+                    state.degraded.push(DegradationReason::ProviderFailed {
+                        provider: provider.name().to_string(),
+                    });
+                }
+            }
+            state.degraded.extend(provider.degradations());
+        }
+
+        // Segment and limit-profile layers apply for this validation only
+        let layered = self.resolve_config(transaction, &mut state);
+        let layered_plan = layered
+            .as_ref()
+            .map(|config| ExecutionPlan::new(&Self::enabled_checks(config)));
+        let config = layered.as_ref().unwrap_or(&self.config);
+
+        let budget = config
+            .check_budget_micros
+            .map(std::time::Duration::from_micros);
+        let plan = layered_plan
+            .as_ref()
+            .unwrap_or(&self.plan)
+            .checks()
+            .to_vec();
+        if config.record_check_telemetry {
+            state.telemetry = Some(CheckTelemetry::default());
+        }
+        self.check_watchlist(transaction, &mut state);
+
+        let mut deferred = Vec::new();
+        for check in plan {
+            let short_circuited =
+                state.blocked || (state.hard_fail && config.short_circuit_hard_fails);
+            let over_budget =
+                check.is_deferrable() && budget.is_some_and(|b| started.elapsed() >= b);
+            let dependency_deferred = check
+                .dependencies()
+                .iter()
+                .any(|dep| deferred.contains(dep));
+            let dependency_skipped = check
+                .dependencies()
+                .iter()
+                .any(|dep| state.was_skipped(*dep));
+
+            if !short_circuited && (over_budget || dependency_deferred) {
+                // Later velocity checks and the follow-up still need the history
+                if check == Check::Velocity
+                    || (check == Check::FraudPatterns && self.fraud_detector.is_some())
+                {
+                    state.pending.history = true;
+                }
+                if check == Check::FraudPatterns && self.fraud_detector.is_some() {
+                    state.pending.profiles = true;
+                }
+                deferred.push(check);
+                state.skipped_checks.push(check);
+                continue;
+            }
+            if short_circuited || dependency_skipped {
+                state.skipped_checks.push(check);
+                continue;
+            }
+            #[cfg(feature = "tracing")]
+            let _span = tracing::info_span!("check", check = check.name()).entered();
+            let check_started = Instant::now();
+            self.run_check(config, check, transaction, &mut state);
+            if let Some(ref mut telemetry) = state.telemetry {
+                telemetry.record(check, check_started.elapsed());
+            }
+            #[cfg(feature = "metrics")]
+            telemetry::record_check(check, check_started.elapsed());
+        }
+
+        if let Some(ref trust) = self.counterparty_trust {
+            let adjustment = trust.risk_adjustment(transaction);
+            state.risk_breakdown.trust_adjustment = adjustment;
+            if let Some(ref counterparty) = transaction.to_account {
+                let score =
+                    trust.trust_score(&transaction.user_id, counterparty, transaction.timestamp);
+                let node = ExplanationNode::new("counterparty", adjustment as f64)
+                    .input("counterparty", counterparty)
+                    .input("trust_score", score);
+                state.explain("trust_adjustment", node);
+            }
+        }
+        #[cfg(feature = "ml")]
+        self.run_model_scorer(config, transaction, &mut state);
+
+        // Calculate total risk
+        state.risk_breakdown.calculate_total(&config.risk_weights);
+        if state.hard_fail {
+            state.risk_breakdown.total_score = 100;
+        }
+
+        // Risk threshold check
+        let fraud_score = state.risk_breakdown.total_score;
+        let exempt = state.blocked
+            || state.allowlisted
+            || (state.hard_fail && config.short_circuit_hard_fails);
+        if fraud_score > config.fraud_threshold && !exempt {
+            state
+                .errors
+                .push(ValidationError::RiskThresholdExceeded(format!(
+                    "Risk score {} exceeds threshold {}",
+                    fraud_score, config.fraud_threshold
+                )));
+        }
+
+        if let Some(ref mut telemetry) = state.telemetry {
+            telemetry.skipped = state.skipped_checks.clone();
+            telemetry.total_micros = started.elapsed().as_micros() as u64;
+        }
+        let effective = layered.unwrap_or_else(|| self.config.clone());
+        if !deferred.is_empty() {
+            // A decision missing checks is never approved outright
+            let names: Vec<&str> = deferred.iter().map(Check::name).collect();
+            state.warnings.push(Warning::new(
+                "CHECKS_DEFERRED",
+                WarningSeverity::Medium,
+                format!("Checks deferred past the time budget: {}", names.join(", ")),
+            ));
+            state.pending.follow_up = Some(DeferredChecks {
+                transaction: transaction.clone(),
+                checks: deferred,
+                context: state.context.clone(),
+                config: effective.clone(),
+            });
+        }
+        let (result, pending) = self.decide(transaction, state, commit, effective);
+
+        #[cfg(feature = "tracing")]
+        {
+            let span = tracing::Span::current();
+            span.record("decision", tracing::field::display(result.decision));
+            span.record(
+                "reason_codes",
+                tracing::field::display(result.reason_codes().join(",")),
+            );
+        }
+        Evaluated {
+            transaction: working,
+            result,
+            pending,
+            #[cfg(feature = "metrics")]
+            elapsed: started.elapsed(),
+        }
+    }
+
+    /// Add the model's score to the breakdown, or mark the result degraded
+    #[cfg(feature = "ml")]
+    fn run_model_scorer(
+        &self,
+        config: &ValidatorConfig,
+        transaction: &Transaction,
+        state: &mut CheckState,
+    ) {
+        let Some(ref scorer) = self.model_scorer else {
+            return;
+        };
+        if !self.sample_stage(config, SampledStage::ModelScoring, transaction, state) {
+            return;
+        }
+        let window_start = Duration::try_minutes(config.velocity_check_window_minutes)
+            .and_then(|window| transaction.timestamp.checked_sub_signed(window))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        let (recent_count, recent_amount) = self
+            .history
+            .read()
+            .get(HistoryKey::User, &transaction.user_id)
+            .iter()
+            .filter(|h| h.timestamp >= window_start)
+            .fold((0, 0.0), |(count, amount), h| {
+                (count + 1, amount + h.amount)
+            });
+        let features = ml::model_features(
+            transaction,
+            &state.risk_breakdown,
+            recent_count,
+            recent_amount,
+        );
+        match scorer.score(&features) {
+            Ok(probability) => {
+                let model_risk = (probability * 100.0).round() as u8;
+                state.risk_breakdown.model_risk = model_risk;
+                let node = ExplanationNode::new("model", model_risk as f64)
+                    .input("scorer", scorer.name())
+                    .input("probability", probability);
+                state.explain("model_risk", node);
+            }
+            Err(e) => {
+                state.warnings.push(Warning::new(
+                    "MODEL_SCORING_FAILED",
+                    WarningSeverity::Low,
+                    format!("Model {} failed: {}", scorer.name(), e),
+                ));
+                state.degraded.push(DegradationReason::ProviderFailed {
+                    provider: scorer.name().to_string(),
+                });
+            }
+        }
+    }
+
+    /// Layer segment and limit-profile overrides over the base config
+    ///
+    /// Returns `None` when no layer applies to the transaction.
+    fn resolve_config(
+        &self,
+        transaction: &Transaction,
+        state: &mut CheckState,
+    ) -> Option<ValidatorConfig> {
+        let mut resolved: Option<ValidatorConfig> = None;
+
+        state.segment = CustomerSegment::of(
+            transaction,
+            &state.context,
+            self.config.trust_segment_metadata,
+        );
+        if let Some(layer) = state
+            .segment
+            .and_then(|segment| self.segment_overrides.get(&segment))
+        {
+            resolved = Some(layer.apply(&self.config));
+        }
+
+        let layered = resolved.as_ref().unwrap_or(&self.config);
+        if let Some(name) = self
+            .limit_profiles
+            .profile_name(transaction, &state.context, layered)
+        {
+            match self.limit_profiles.get(name) {
+                Some(profile) => {
+                    let base = resolved.as_ref().unwrap_or(&self.config);
+                    resolved = Some(profile.to_override().apply(base));
+                    state.limit_profile = Some(name.to_string());
+                }
+                None => state.warnings.push(Warning::new(
+                    "UNKNOWN_LIMIT_PROFILE",
+                    WarningSeverity::Low,
+                    format!("Unknown limit profile {}; using defaults", name),
+                )),
+            }
+        }
+
+        // Channel limits such as ATM caps apply last so no profile lifts them
+        state.channel = Channel::of(transaction);
+        if let Some(policy) = state
+            .channel
+            .and_then(|channel| self.channel_policies.get(&channel))
+        {
+            let base = resolved.as_ref().unwrap_or(&self.config);
+            resolved = Some(policy.overrides.apply(base));
+        }
+
+        resolved
+    }
+
+    /// Apply active watchlist entries matching the transaction
+    fn check_watchlist(&self, transaction: &Transaction, state: &mut CheckState) {
+        let matches = self.watchlist.matches(transaction, self.clock.now());
+        if matches.is_empty() {
+            return;
+        }
+        for entry in &matches {
+            match entry.list_type {
+                ListType::Block => {
+                    state.errors.push(ValidationError::Blocklisted(format!(
+                        "{:?} {}: {}",
+                        entry.subject, entry.value, entry.reason
+                    )));
+                    state.blocked = true;
+                    state.hard_fail = true;
+                }
+                ListType::Allow => {
+                    state.allowlisted = true;
+                    state.warnings.push(Warning::new(
+                        "WATCHLIST_ALLOW",
+                        WarningSeverity::Info,
+                        format!(
+                            "{:?} {} is allowlisted: {}",
+                            entry.subject, entry.value, entry.reason
+                        ),
+                    ));
+                }
+                ListType::Gray => state.warnings.push(Warning::new(
+                    STEP_UP_WARNING_CODE,
+                    WarningSeverity::Medium,
+                    format!(
+                        "{:?} {} is graylisted: {}",
+                        entry.subject, entry.value, entry.reason
+                    ),
+                )),
+                ListType::Monitor => state.warnings.push(Warning::new(
+                    "WATCHLIST_MONITOR",
+                    WarningSeverity::Medium,
+                    format!(
+                        "{:?} {} is on the monitoring list: {}",
+                        entry.subject, entry.value, entry.reason
+                    ),
+                )),
+            }
+        }
+        state
+            .compliance_checks
+            .insert("WATCHLIST".to_string(), !state.blocked);
+    }
+
+    /// Run a single check, recording its outcome in `state`
+    fn run_check(
+        &self,
+        config: &ValidatorConfig,
+        check: Check,
+        transaction: &Transaction,
+        state: &mut CheckState,
+    ) {
+        let first_new_warning = state.warnings.len();
+        match check {
+            Check::Screening => {
+                state.hard_fail = self.check_hard_fails(
+                    config,
+                    transaction,
+                    &mut state.errors,
+                    &mut state.warnings,
+                    &mut state.compliance_checks,
+                    &mut state.degraded,
+                );
+                state.risk_breakdown.geo_risk = self.calculate_geo_risk(transaction);
+                if let Some(node) = self.explain_geo_risk(transaction) {
+                    state.explain("geo_risk", node);
+                }
+                if let Some(findings) = state.external.take() {
+                    self.apply_external_findings(config, &findings, state);
+                }
+                self.check_reference_data_age(config, &mut state.warnings);
+            }
+            Check::Amount => {
+                if let Err(e) = self.validate_amount(config, transaction) {
+                    state.errors.push(e);
+                }
+                state.risk_breakdown.amount_risk = self.calculate_amount_risk(config, transaction);
+                let tiers = &config.amount_risk_tiers;
+                if let Some(tier) = tiers.tier_for(transaction) {
+                    let node = ExplanationNode::new("amount_tier", tier.points as f64)
+                        .compared(transaction.amount, tier.above)
+                        .input("currency", &transaction.currency)
+                        .input("transaction_type", transaction.transaction_type);
+                    state.explain("amount_risk", node);
+                }
+            }
+            Check::AccountFormat => {
+                let (formats, routing) = match state.account_verdicts.as_ref() {
+                    Some(verdicts) => (Some(verdicts.formats), verdicts.routing.clone()),
+                    None => (None, self.validate_routing_numbers(transaction)),
+                };
+                if let Err(e) = self.validate_accounts(transaction, formats) {
+                    state.errors.push(e);
+                }
+                if let Err(e) = routing {
+                    state.errors.push(e);
+                }
+            }
+            Check::Duplicate => {
+                #[cfg(feature = "tracing")]
+                let _span = tracing::debug_span!("check_duplicate").entered();
+                // Check and reserve under one write lock so concurrent
+                // validators cannot both pass the same ID
+                let mut dedup = self.dedup.write();
+                if !dedup.reserve_id(&transaction.transaction_id) {
+                    state.errors.push(ValidationError::DuplicateTransaction(
+                        transaction.transaction_id.clone(),
+                    ));
+                } else {
+                    state.pending.transaction_id = true;
+                    if let Some(bucket) = config.fingerprint_bucket_seconds {
+                        let fingerprint = transaction.fingerprint(bucket);
+                        let neighbours = transaction.neighbouring_fingerprints(bucket);
+                        match dedup.reserve_fingerprint(
+                            &fingerprint,
+                            &neighbours,
+                            &transaction.transaction_id,
+                        ) {
+                            Err(original) => {
+                                state
+                                    .errors
+                                    .push(ValidationError::DuplicateTransaction(format!(
+                                        "{} replays content of {}",
+                                        transaction.transaction_id, original
+                                    )))
+                            }
+                            Ok(()) => state.pending.fingerprint = Some(fingerprint),
+                        }
+                    }
+                }
+            }
+            Check::Velocity => {
+                let outbound = self.check_velocity(config, transaction);
+                let inbound = self.check_inbound_velocity(config, transaction);
+                for (risk, error, warnings, nodes) in [outbound, inbound] {
+                    state.risk_breakdown.velocity_risk =
+                        state.risk_breakdown.velocity_risk.saturating_add(risk);
+                    state.errors.extend(error);
+                    state.warnings.extend(warnings);
+                    for node in nodes {
+                        state.explain("velocity_risk", node);
+                    }
+                }
+                state
+                    .warnings
+                    .extend(self.check_double_payment(config, transaction));
+
+                state.pending.history = true;
+            }
+            Check::FraudPatterns => {
+                let (risk, warnings, rules) =
+                    self.check_fraud_patterns(config, transaction, &state.context);
+                state.risk_breakdown.pattern_risk = risk;
+                state.warnings.extend(warnings);
+                let mut rules = ExplanationNode::new("rules", risk as f64).with_children(rules);
+
+                if let Some(ref detector) = self.fraud_detector {
+                    let score = detector.assess(transaction);
+                    // Profiles learn from the transaction once it commits
+                    state.pending.history = true;
+                    state.pending.profiles = true;
+                    let mut node = ExplanationNode::new("fraud_detector", score.score as f64)
+                        .with_children(
+                            score
+                                .flags
+                                .iter()
+                                .map(|flag| {
+                                    ExplanationNode::new("FRAUD_FLAG", flag.severity as f64)
+                                        .input("flag", format!("{:?}", flag.flag_type))
+                                        .input("description", &flag.description)
+                                })
+                                .collect(),
+                        );
+                    // Both scorers look at overlapping signals; keep the stronger one
+                    if score.score > state.risk_breakdown.pattern_risk {
+                        rules.points = 0.0;
+                        rules = rules.input("superseded_by", "fraud_detector");
+                    } else {
+                        node.points = 0.0;
+                        node = node.input("superseded_by", "rules");
+                    }
+                    state.explain("pattern_risk", node);
+                    state.risk_breakdown.pattern_risk =
+                        state.risk_breakdown.pattern_risk.max(score.score);
+                    state.warnings.extend(score.flags.iter().map(|flag| {
+                        Warning::new(
+                            "FRAUD_FLAG",
+                            flag_severity(flag.severity),
+                            &flag.description,
+                        )
+                    }));
+                }
+                state.explain("pattern_risk", rules);
+                if let Some(ref book) = self.authorization_book {
+                    let (risk, warnings) = book.capture_risk(transaction);
+                    state.risk_breakdown.pattern_risk =
+                        state.risk_breakdown.pattern_risk.saturating_add(risk);
+                    if risk > 0 {
+                        let node = ExplanationNode::new("authorization_capture", risk as f64)
+                            .input("transaction_type", transaction.transaction_type);
+                        state.explain("pattern_risk", node);
+                    }
+                    state.warnings.extend(warnings);
+                }
+                if let Some(ref library) = self.typologies {
+                    let (risk, hits, warnings) =
+                        library.evaluate(transaction, &self.history.read());
+                    state.risk_breakdown.pattern_risk =
+                        state.risk_breakdown.pattern_risk.saturating_add(risk);
+                    for hit in &hits {
+                        let points = library
+                            .packs()
+                            .iter()
+                            .filter(|pack| pack.name == hit.pack)
+                            .flat_map(|pack| &pack.rules)
+                            .find(|rule| rule.id == hit.rule_id)
+                            .map_or(0, |rule| rule.risk);
+                        let node = ExplanationNode::new(&hit.rule_id, points as f64)
+                            .input("pack", &hit.pack)
+                            .input("version", &hit.version);
+                        state.explain("pattern_risk", node);
+                    }
+                    state.typology_hits.extend(hits);
+                    state.warnings.extend(warnings);
+                }
+                if self.network_analyzer.is_some()
+                    && self.sample_stage(config, SampledStage::NetworkAnalysis, transaction, state)
+                {
+                    let (network_risk, network_warning, nodes) =
+                        self.check_network_patterns(transaction);
+                    state.risk_breakdown.network_risk = network_risk;
+                    state.warnings.extend(network_warning);
+                    for node in nodes {
+                        state.explain("network_risk", node);
+                    }
+                }
+            }
+            Check::TimeRisk => {
+                let hour = transaction.timestamp.hour();
+                let profiles = self.activity_profiles.as_ref();
+                // Mature users are scored against their own activity pattern
+                let node = match profiles.and_then(|p| p.time_risk(transaction)) {
+                    Some((time_risk, warning)) => {
+                        state.risk_breakdown.time_risk = time_risk;
+                        state.warnings.extend(warning);
+                        let share = profiles
+                            .and_then(|p| p.profile(&transaction.user_id))
+                            .map_or(0.0, |p| p.hour_share(hour));
+                        ExplanationNode::new("user_activity", time_risk as f64)
+                            .input("hour_utc", hour)
+                            .input("hour_share", share)
+                    }
+                    None => {
+                        let time_risk = self.calculate_time_risk(config, &transaction.timestamp);
+                        state.risk_breakdown.time_risk = time_risk;
+                        let (open, close) = config.time_risk_profile.business_hours;
+                        ExplanationNode::new("time_of_day", time_risk as f64)
+                            .input("hour_utc", hour)
+                            .input("weekday", transaction.timestamp.weekday())
+                            .input("business_hours", format!("{}-{}", open, close))
+                    }
+                };
+                state.explain("time_risk", node);
+            }
+            Check::Aml => {
+                let mut aml_result = self.check_aml_compliance(transaction);
+                let mut detail = "AML compliance check failed".to_string();
+                if let Some(ref checker) = self.aml_checker {
+                    // Tokens never match the sanctions list; use the verdict
+                    // taken on the real accounts
+                    let aml = match state
+                        .account_verdicts
+                        .as_ref()
+                        .and_then(|v| v.sanctioned_entity)
+                    {
+                        Some(sanctioned) => checker.check_compliance_given(transaction, sanctioned),
+                        None => checker.check_compliance(transaction),
+                    };
+                    state.warnings.extend(aml.red_flags.iter().map(|flag| {
+                        Warning::new(
+                            "AML_RED_FLAG",
+                            alert_severity(&flag.severity),
+                            &flag.description,
+                        )
+                    }));
+                    if aml.requires_ctr {
+                        state.pending.filings.push(ReportKind::Ctr);
+                    }
+                    if aml.requires_sar {
+                        state.pending.filings.push(ReportKind::Sar);
+                    }
+                    if !aml.compliant {
+                        aml_result = false;
+                        detail = format!("AML risk score {}", aml.risk_score);
+                    }
+                }
+                state
+                    .compliance_checks
+                    .insert("AML".to_string(), aml_result);
+                if !aml_result {
+                    state.errors.push(ValidationError::ComplianceFailed(detail));
+                }
+            }
+            Check::BusinessRules => {
+                if let Err(e) = self.check_business_rules(transaction) {
+                    state.errors.push(e);
+                }
+                if let Err(e) = self.check_channel_rules(transaction, state.channel) {
+                    state.errors.push(e);
+                }
+                if let Some(Err(e)) = self.refund_ledger.as_ref().map(|l| l.check(transaction)) {
+                    state.errors.push(e);
+                }
+                match self
+                    .settlement_calendar
+                    .as_ref()
+                    .map(|c| c.check(transaction))
+                {
+                    Some(Ok(warnings)) => state.warnings.extend(warnings),
+                    Some(Err(e)) => state.errors.push(e),
+                    None => {}
+                }
+                if let Some(Err(e)) = self
+                    .authorization_book
+                    .as_ref()
+                    .map(|b| b.check(transaction))
+                {
+                    state.errors.push(e);
+                }
+            }
+            Check::CustomRules => {
+                let history = self.history.read();
+                let ctx = ValidationContext {
+                    enrichment: &state.context,
+                    history: &history,
+                    config,
+                    segment: state.segment,
+                    channel: state.channel,
+                    risk: &state.risk_breakdown,
+                    now: self.clock.now(),
+                };
+                let outcomes: Vec<(&str, RuleOutcome)> = self
+                    .rules
+                    .iter()
+                    .map(|rule| (rule.name(), rule.evaluate(transaction, &ctx)))
+                    .collect();
+                drop(history);
+                for (name, outcome) in outcomes {
+                    match outcome {
+                        RuleOutcome::Pass => {}
+                        RuleOutcome::Warn(warning) => state.warnings.push(warning),
+                        RuleOutcome::Risk { points, warning } => {
+                            state.risk_breakdown.pattern_risk =
+                                state.risk_breakdown.pattern_risk.saturating_add(points);
+                            state
+                                .explain("pattern_risk", ExplanationNode::new(name, points as f64));
+                            state.warnings.extend(warning);
+                        }
+                        RuleOutcome::Reject(e) => state.errors.push(e),
+                    }
+                }
+            }
+        }
+
+        for warning in &mut state.warnings[first_new_warning..] {
+            warning.source_check.get_or_insert(check);
+        }
+    }
+
+    /// Build the result, commit state, then record stats and decision log
+    ///
+    /// `commit` overrides the configured commit policy; `Some(false)` is a
+    /// dry run with no side effects at all.
+    fn decide(
+        &self,
+        transaction: &Transaction,
+        state: CheckState,
+        commit: Option<bool>,
+        config: ValidatorConfig,
+    ) -> (ValidationResult, PendingState) {
+        let pending = state.pending;
+        let is_valid = state.errors.is_empty();
+        let fraud_score = state.risk_breakdown.total_score;
+
+        #[cfg(feature = "tracing")]
+        {
+            let span = tracing::Span::current();
+            span.record("amount_risk", state.risk_breakdown.amount_risk);
+            span.record("velocity_risk", state.risk_breakdown.velocity_risk);
+            span.record("pattern_risk", state.risk_breakdown.pattern_risk);
+            span.record("time_risk", state.risk_breakdown.time_risk);
+            span.record("fraud_score", fraud_score);
+            span.record("is_valid", is_valid);
+        }
+
+        let explanation = Explanation::new(
+            &state.risk_breakdown,
+            &config.risk_weights,
+            config.fraud_threshold,
+            state.hard_fail,
+            state.explanation,
+        );
+        let mut result = ValidationResult {
+            transaction_id: transaction.transaction_id.clone(),
+            amount: transaction.amount,
+            is_valid,
+            errors: state.errors,
+            warnings: state.warnings,
+            fraud_score,
+            risk_breakdown: state.risk_breakdown,
+            compliance_checks: state.compliance_checks,
+            skipped_checks: state.skipped_checks,
+            decision: Decision::Approve,
+            tenant_id: self.tenant_id.clone(),
+            segment: state.segment,
+            channel: state.channel,
+            limit_profile: state.limit_profile,
+            effective_config: config,
+            policy_version: self.policy_version.clone(),
+            degraded: state.degraded,
+            typology_hits: state.typology_hits,
+            explanation,
+            check_telemetry: state.telemetry,
+            sampling: state.sampling,
+            committed: false,
+            validated_at: self.clock.now(),
+        };
+        result.decision = result.derive_decision();
+        if commit != Some(false) {
+            self.run_challenge(transaction, &mut result);
+        }
+        // After the challenge, which cannot make up for missing reference data
+        if result.effective_config.review_when_degraded
+            && !result.degraded.is_empty()
+            && result.decision == Decision::Approve
+        {
+            result.decision = Decision::Review;
+        }
+        for hook in &self.post_hooks {
+            hook.after_validate(transaction, &mut result);
+        }
+        (result, pending)
+    }
+
+    /// Write ahead to the event log, then commit or release, count, and log
+    fn record(&mut self, mut evaluated: Evaluated, commit: Option<bool>) -> ValidationResult {
+        if commit == Some(false) {
+            // Dry run: leave state, stats, alerts, and the audit log untouched
+            self.release_reservations(&evaluated.transaction, &evaluated.pending);
+            return evaluated.result;
+        }
+        let mut commit = Self::commits(&evaluated.result, commit);
+        // Write ahead: the event is durable before the state it describes
+        if let Err(e) = self.append_event(&evaluated, commit, None) {
+            Self::fail_closed(&mut evaluated.result, &e);
+            commit = false;
+        }
+        self.finish(evaluated, commit)
+    }
+
+    /// Whether a result commits under an explicit override or the policy
+    fn commits(result: &ValidationResult, commit: Option<bool>) -> bool {
+        commit.unwrap_or(match result.effective_config.commit_policy {
+            CommitPolicy::Always => true,
+            CommitPolicy::OnValid => result.is_valid,
+        })
+    }
+
+    /// Append the next event to the write-ahead log, if one is attached
+    fn append_event(
+        &mut self,
+        evaluated: &Evaluated,
+        commit: bool,
+        group: Option<&GroupMembership>,
+    ) -> std::io::Result<()> {
+        let Some(ref mut log) = self.event_log else {
+            return Ok(());
+        };
+        self.event_sequence += 1;
+        let mut event = ValidationEvent::new(
+            self.event_sequence,
+            &evaluated.transaction,
+            &evaluated.result,
+            commit,
+            &evaluated.pending,
+        );
+        if let Some(group) = group {
+            event = event.in_group(group);
+        }
+        let appended = log.append(&event);
+        if appended.is_err() {
+            self.stats.event_log_failures += 1;
+        }
+        appended
+    }
+
+    /// Hold back a result whose event could not be written
+    ///
+    /// Nothing durable describes the transaction, so a restart could not
+    /// rebuild state committed now. The ID is not held either, so nothing
+    /// may be paid on it.
+    fn fail_closed(result: &mut ValidationResult, error: &std::io::Error) {
+        if result.decision != Decision::Decline {
+            result.decision = Decision::Review;
+        }
+        result.warnings.push(Warning::new(
+            "EVENT_LOG_FAILED",
+            WarningSeverity::High,
+            format!(
+                "Event log append failed, transaction not recorded: {}",
+                error
+            ),
+        ));
+    }
+
+    /// Commit or release an evaluated transaction, then count and log it
+    fn finish(&mut self, evaluated: Evaluated, commit: bool) -> ValidationResult {
+        let transaction = evaluated.transaction.as_ref();
+        let mut result = evaluated.result;
+        let mut pending = evaluated.pending;
+        if commit {
+            let follow_up = pending.follow_up.take();
+            self.commit_state(transaction, pending);
+            result.committed = true;
+            self.deferred.extend(follow_up);
+        } else {
+            self.release_reservations(transaction, &pending);
+        }
+
+        self.stats.record(&result);
+        #[cfg(feature = "metrics")]
+        telemetry::record_validation(&result, evaluated.elapsed);
+        let correlation_id = transaction
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get(CORRELATION_ID_METADATA_KEY));
+        let alerts = self.alerts.dispatch_events(&result, correlation_id);
+        if let Some(ref mut audit) = self.audit {
+            let record = AuditRecord::new(transaction, &result, &alerts, &result.policy_version);
+            if audit.push(record).is_err() {
+                self.stats.audit_failures += 1;
+            }
+        }
+
+        // Decision logging never affects the outcome; failures are counted
+        if let Some(ref mut logger) = self.decision_logger {
+            let record = DecisionRecord::new(transaction, &result, &result.policy_version);
+            if logger.log_decision(&record).is_err() {
+                self.stats.decision_log_failures += 1;
+            }
+        }
+
+        result
+    }
+
+    /// Settle a Review or StepUp decision with a step-up challenge
+    fn run_challenge(&self, transaction: &Transaction, result: &mut ValidationResult) {
+        let Some(ref provider) = self.challenge_provider else {
+            return;
+        };
+        if !matches!(result.decision, Decision::Review | Decision::StepUp) {
+            return;
+        }
+        let warning = match provider.challenge(transaction, result) {
+            ChallengeOutcome::Passed => {
+                result.decision = Decision::Approve;
+                Warning::new(
+                    "CHALLENGE_PASSED",
+                    WarningSeverity::Info,
+                    format!("Step-up challenge via {} passed", provider.name()),
+                )
+            }
+            ChallengeOutcome::Failed(reason) => {
+                result.decision = Decision::Decline;
+                Warning::new(
+                    "CHALLENGE_FAILED",
+                    WarningSeverity::High,
+                    format!(
+                        "Step-up challenge via {} failed: {}",
+                        provider.name(),
+                        reason
+                    ),
+                )
+            }
+            ChallengeOutcome::Unavailable(reason) => Warning::new(
+                "CHALLENGE_UNAVAILABLE",
+                WarningSeverity::Low,
+                format!(
+                    "Step-up challenge via {} unavailable: {}",
+                    provider.name(),
+                    reason
+                ),
+            ),
+        };
+        result.warnings.push(warning);
+    }
+
+    /// Give back the ID and fingerprint held by the duplicate check
+    fn release_reservations(&self, transaction: &Transaction, pending: &PendingState) {
+        if pending.transaction_id {
+            self.dedup
+                .write()
+                .release(&transaction.transaction_id, pending.fingerprint.as_deref());
+        }
+    }
+
+    /// Record the transaction into dedup sets and velocity history
+    fn commit_state(&mut self, transaction: &Transaction, pending: PendingState) {
+        if pending.transaction_id || pending.fingerprint.is_some() {
+            let mut dedup = self.dedup.write();
+            if pending.transaction_id {
+                dedup.record_id(&transaction.transaction_id);
+            }
+            if let Some(fingerprint) = pending.fingerprint {
+                dedup.record_fingerprint(
+                    fingerprint,
+                    &transaction.transaction_id,
+                    transaction.timestamp,
+                );
+            }
+        }
+        if pending.history {
+            self.history.write().record(transaction);
+        }
+        if let (true, Some(detector)) = (pending.profiles, self.fraud_detector.as_ref()) {
+            detector.observe(transaction);
+        }
+        if let (Some(analyzer), Some(from), Some(to)) = (
+            self.network_analyzer.as_mut(),
+            &transaction.from_account,
+            &transaction.to_account,
+        ) {
+            analyzer.add_transaction(from, to, transaction.amount, transaction.timestamp);
+        }
+        if let Some(trust) = self.counterparty_trust.as_mut() {
+            trust.record(transaction);
+        }
+        if let Some(profiles) = self.activity_profiles.as_mut() {
+            profiles.record(transaction);
+        }
+        if let Some(ledger) = self.refund_ledger.as_mut() {
+            ledger.record(transaction);
+        }
+        if let Some(book) = self.authorization_book.as_mut() {
+            book.record(transaction);
+        }
+        if let Some(queue) = self.reporting_queue.as_mut() {
+            let now = self.clock.now();
+            for kind in pending.filings {
+                queue.enqueue(kind, transaction, now);
+            }
+        }
+    }
+
+    /// Screen for conditions that always decline; returns true on a hard fail
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "check_hard_fails", level = "debug", skip_all)
+    )]
+    fn check_hard_fails(
+        &self,
+        config: &ValidatorConfig,
+        transaction: &Transaction,
+        errors: &mut Vec<ValidationError>,
+        warnings: &mut Vec<Warning>,
+        compliance_checks: &mut HashMap<String, bool>,
+        degraded: &mut Vec<DegradationReason>,
+    ) -> bool {
+        let mut hard_fail = false;
+        let metadata = transaction.metadata.as_ref();
+
+        if config.block_sanctioned_jurisdictions {
+            let mut clear = true;
+            let codes = JURISDICTION_KEYS
+                .iter()
+                .filter_map(|key| metadata.and_then(|m| m.get(key)));
+            for code in codes {
+                if let Some(program) = comprehensive_sanctions_program(code) {
+                    errors.push(ValidationError::SanctionedJurisdiction(format!(
+                        "{} ({})",
+                        program,
+                        code.trim().to_uppercase()
+                    )));
+                    clear = false;
+                    hard_fail = true;
+                }
+            }
+            compliance_checks.insert("COUNTRY_SANCTIONS".to_string(), clear);
+        }
+
+        if let Some(ref screener) = self.sanctions_screener {
+            let mut clear = true;
+            let mut failed = false;
+            let names = metadata.into_iter().flat_map(|m| m.counterparty_names());
+            for name in names {
+                let result = match screener.screen(name) {
+                    Ok(result) => result,
+                    Err(error) => {
+                        errors.push(ValidationError::ComplianceFailed(format!(
+                            "Sanctions screening via {} failed: {}",
+                            screener.name(),
+                            error
+                        )));
+                        clear = false;
+                        failed = true;
+                        continue;
+                    }
+                };
+                if let Some(hit) = result.highest_high_confidence() {
+                    errors.push(ValidationError::SanctionsMatch(format!(
+                        "'{}' matched {} on {}",
+                        name,
+                        hit.matched_name,
+                        hit.list.name()
+                    )));
+                    clear = false;
+                    hard_fail = true;
+                } else if result.is_match {
+                    warnings.push(Warning::new(
+                        "POSSIBLE_SANCTIONS_MATCH",
+                        WarningSeverity::High,
+                        format!("Possible sanctions match for '{}'", name),
+                    ));
+                }
+            }
+            compliance_checks.insert("SANCTIONS".to_string(), clear);
+            if failed {
+                degraded.push(DegradationReason::ProviderFailed {
+                    provider: screener.name().to_string(),
+                });
+            }
+            degraded.extend(screener.degradations());
+        }
+
+        if let Some(ref scorer) = self.geo_scorer {
+            let mut clear = true;
+            if scorer.is_empty() && metadata.is_some_and(|m| m.countries().next().is_some()) {
+                degraded.push(DegradationReason::GeoDataMissing);
+            }
+            let countries = metadata.into_iter().flat_map(|m| m.countries());
+            for country in countries {
+                if let Some(risk) = scorer.get_country_risk(country) {
+                    if risk.is_prohibited() {
+                        errors.push(ValidationError::ProhibitedJurisdiction(format!(
+                            "{} ({})",
+                            risk.country_name, risk.country_code
+                        )));
+                        clear = false;
+                        hard_fail = true;
+                    } else if risk.requires_edd() {
+                        warnings.push(Warning::new(
+                            "ENHANCED_DUE_DILIGENCE",
+                            WarningSeverity::Medium,
+                            format!(
+                                "High-risk jurisdiction {} requires enhanced due diligence",
+                                risk.country_code
+                            ),
+                        ));
+                    }
+                }
+            }
+            compliance_checks.insert("GEOGRAPHY".to_string(), clear);
+        }
+
+        hard_fail
+    }
+
+    /// Apply the answers of a provider awaited by `validate_async`
+    fn apply_external_findings(
+        &self,
+        config: &ValidatorConfig,
+        findings: &ExternalFindings,
+        state: &mut CheckState,
+    ) {
+        let provider = &findings.provider;
+        for failure in &findings.failures {
+            if failure.stage == ExternalStage::Sanctions {
+                state.errors.push(ValidationError::ComplianceFailed(format!(
+                    "Sanctions screening via {} failed: {}",
+                    provider, failure.error
+                )));
+            } else {
+                state.warnings.push(Warning::new(
+                    "EXTERNAL_CHECK_FAILED",
+                    WarningSeverity::Low,
+                    format!(
+                        "{} lookup via {} failed: {}",
+                        failure.stage.name(),
+                        provider,
+                        failure.error
+                    ),
+                ));
+            }
+        }
+        if !findings.failures.is_empty() {
+            state.degraded.push(DegradationReason::ProviderFailed {
+                provider: provider.clone(),
+            });
+        }
+
+        if !findings.sanctions.is_empty() || findings.failed(ExternalStage::Sanctions) {
+            let mut clear = !findings.failed(ExternalStage::Sanctions);
+            for (name, result) in &findings.sanctions {
+                if let Some(hit) = result.highest_high_confidence() {
+                    state.errors.push(ValidationError::SanctionsMatch(format!(
+                        "'{}' matched {} on {} via {}",
+                        name,
+                        hit.matched_name,
+                        hit.list.name(),
+                        provider
+                    )));
+                    clear = false;
+                    state.hard_fail = true;
+                } else if result.is_match {
+                    state.warnings.push(Warning::new(
+                        "POSSIBLE_SANCTIONS_MATCH",
+                        WarningSeverity::High,
+                        format!("Possible sanctions match for '{}' via {}", name, provider),
+                    ));
+                }
+            }
+            let entry = state
+                .compliance_checks
+                .entry("SANCTIONS".to_string())
+                .or_insert(true);
+            *entry &= clear;
+        }
+
+        if let Some(ref country) = findings.geolocation {
+            if config.block_sanctioned_jurisdictions {
+                if let Some(program) = comprehensive_sanctions_program(country) {
+                    state
+                        .errors
+                        .push(ValidationError::SanctionedJurisdiction(format!(
+                            "{} ({}, geolocated)",
+                            program,
+                            country.trim().to_uppercase()
+                        )));
+                    state.hard_fail = true;
+                }
+            }
+            if let Some(risk) = self
+                .geo_scorer
+                .as_ref()
+                .and_then(|scorer| scorer.get_country_risk(country))
+            {
+                if risk.is_prohibited() {
+                    state
+                        .errors
+                        .push(ValidationError::ProhibitedJurisdiction(format!(
+                            "{} ({}, geolocated)",
+                            risk.country_name, risk.country_code
+                        )));
+                    state.hard_fail = true;
+                } else if risk.requires_edd() {
+                    state.warnings.push(Warning::new(
+                        "ENHANCED_DUE_DILIGENCE",
+                        WarningSeverity::Medium,
+                        format!(
+                            "Transaction geolocated to high-risk jurisdiction {}",
+                            risk.country_code
+                        ),
+                    ));
+                }
+                if risk.risk_score > state.risk_breakdown.geo_risk {
+                    state.risk_breakdown.geo_risk = risk.risk_score;
+                    state.explain(
+                        "geo_risk",
+                        ExplanationNode::new("geolocated_country_risk", risk.risk_score as f64)
+                            .input("country", &risk.country_code),
+                    );
+                }
+            }
+        }
+
+        match findings.kyc {
+            Some(KycStatus::Verified) => {
+                state.compliance_checks.insert("KYC".to_string(), true);
+            }
+            Some(KycStatus::Pending) => {
+                state.compliance_checks.insert("KYC".to_string(), false);
+                state.warnings.push(Warning::new(
+                    "KYC_PENDING",
+                    WarningSeverity::Medium,
+                    format!("KYC verification via {} is still pending", provider),
+                ));
+            }
+            Some(KycStatus::Failed) => {
+                state.compliance_checks.insert("KYC".to_string(), false);
+                state.errors.push(ValidationError::ComplianceFailed(format!(
+                    "KYC verification via {} failed",
+                    provider
+                )));
+                state.hard_fail = true;
+            }
+            None => {}
+        }
+    }
+
+    /// Warn for each loaded dataset older than the configured maximum age
+    fn check_reference_data_age(&self, config: &ValidatorConfig, warnings: &mut Vec<Warning>) {
+        let Some(max_age) = config.max_reference_data_age_days else {
+            return;
+        };
+        let now = self.clock.now();
+        for dataset in self.reference_datasets() {
+            if !dataset.is_stale(now, max_age) {
+                continue;
+            }
+            let age = match dataset.age_days(now) {
+                Some(days) => format!("is {} days old", days),
+                None => "has no publication date".to_string(),
+            };
+            warnings.push(Warning::new(
+                STALE_REFERENCE_DATA_WARNING,
+                WarningSeverity::Medium,
+                format!(
+                    "Reference dataset {} {} (limit {} days)",
+                    dataset.name, age, max_age
+                ),
+            ));
+        }
+    }
+
+    /// Datasets loaded by the screening backend and geographic scorer
+    pub fn reference_datasets(&self) -> Vec<ReferenceDataset> {
+        let mut datasets = Vec::new();
+        if let Some(ref screener) = self.sanctions_screener {
+            datasets.extend(screener.datasets());
+        }
+        if let Some(ref scorer) = self.geo_scorer {
+            datasets.extend(scorer.datasets());
+        }
+        datasets
+    }
+
+    /// Highest risk score among the known countries in metadata
+    fn calculate_geo_risk(&self, transaction: &Transaction) -> u8 {
+        let (Some(scorer), Some(metadata)) = (&self.geo_scorer, &transaction.metadata) else {
+            return 0;
+        };
+        metadata
+            .countries()
+            .filter_map(|country| scorer.get_country_risk(country))
+            .map(|risk| risk.risk_score)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The country that set the geo risk score
+    fn explain_geo_risk(&self, transaction: &Transaction) -> Option<ExplanationNode> {
+        let (Some(scorer), Some(metadata)) = (&self.geo_scorer, &transaction.metadata) else {
+            return None;
+        };
+        let risk = metadata
+            .countries()
+            .filter_map(|country| scorer.get_country_risk(country))
+            .max_by_key(|risk| risk.risk_score)?;
+        Some(
+            ExplanationNode::new("country_risk", risk.risk_score as f64)
+                .input("country", &risk.country_code),
+        )
+    }
+
+    /// Calculate amount-based risk score
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "calculate_amount_risk", level = "debug", skip_all)
+    )]
+    fn calculate_amount_risk(&self, config: &ValidatorConfig, transaction: &Transaction) -> u8 {
+        config.amount_risk_tiers.score(transaction)
+    }
+
+    /// Calculate time-based risk score
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "calculate_time_risk", level = "debug", skip_all)
+    )]
+    fn calculate_time_risk(&self, config: &ValidatorConfig, timestamp: &DateTime<Utc>) -> u8 {
+        config.time_risk_profile.score(timestamp)
+    }
+
+    /// Check transaction velocity (multiple transactions in short period)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "check_velocity", level = "debug", skip_all)
+    )]
+    fn check_velocity(
+        &self,
+        config: &ValidatorConfig,
+        transaction: &Transaction,
+    ) -> (
+        u8,
+        Option<ValidationError>,
+        Vec<Warning>,
+        Vec<ExplanationNode>,
+    ) {
+        let mut risk_score = 0u8;
+        let mut error = None;
+        let mut warnings = Vec::new();
+
+        // Checked arithmetic: extreme timestamps or windows must not panic
+        let window_start = Duration::try_minutes(config.velocity_check_window_minutes)
+            .and_then(|window| transaction.timestamp.checked_sub_signed(window))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+
+        // Get recent transactions from same user; a follow-up run finds the
+        // transaction itself already recorded
+        let history = self.history.read();
+        let recent_transactions: Vec<&Transaction> = history
+            .get(HistoryKey::User, &transaction.user_id)
+            .iter()
+            .map(|h| h.as_ref())
+            .filter(|h| {
+                h.timestamp >= window_start && h.transaction_id != transaction.transaction_id
+            })
+            .collect();
+
+        let transaction_count = recent_transactions.len();
+        let total_amount: f64 = recent_transactions
+            .iter()
+            .map(|h| h.amount.max(0.0))
+            .sum::<f64>()
+            + transaction.amount.max(0.0);
+        drop(history);
+        let (mut max_count, mut max_amount) =
+            self.velocity_limits(config, transaction, window_start);
+        let corridor = config
+            .high_risk_corridor_limits
+            .as_ref()
+            .zip(self.high_risk_corridor(transaction));
+        if let Some((limits, _)) = corridor {
+            max_count = max_count.min(limits.max_transactions_per_window);
+            max_amount = max_amount.min(limits.max_amount_per_window);
+        }
+
+        let window = config.velocity_check_window_minutes;
+        let mut count_node = ExplanationNode::new("transaction_count", 0.0)
+            .compared(transaction_count as f64, (max_count / 2) as f64)
+            .input("window_minutes", window)
+            .input("max_transactions", max_count);
+        let mut amount_node = ExplanationNode::new("window_amount", 0.0)
+            .compared(total_amount, max_amount * 0.75)
+            .input("window_minutes", window)
+            .input("max_amount", max_amount);
+        if let Some((_, ref pair)) = corridor {
+            count_node = count_node.input("high_risk_corridor", pair);
+            amount_node = amount_node.input("high_risk_corridor", pair);
+        }
+
+        // Check transaction count
+        if transaction_count >= max_count {
+            risk_score = risk_score.saturating_add(30);
+            count_node.points = 30.0;
+            count_node.threshold = Some(max_count as f64);
+            error = Some(ValidationError::VelocityViolation(format!(
+                "Too many transactions: {} in {} minutes",
+                transaction_count + 1,
+                config.velocity_check_window_minutes
+            )));
+        } else if transaction_count >= (max_count / 2) {
+            risk_score = risk_score.saturating_add(15);
+            count_node.points = 15.0;
+            warnings.push(Warning::new(
+                "HIGH_VELOCITY",
+                WarningSeverity::Medium,
+                format!(
+                    "High transaction velocity: {} transactions in window",
+                    transaction_count + 1
+                ),
+            ));
+        }
+
+        // Check total amount
+        if total_amount >= max_amount {
+            risk_score = risk_score.saturating_add(25);
+            amount_node.points = 25.0;
+            amount_node.threshold = Some(max_amount);
+            error = Some(ValidationError::VelocityViolation(format!(
+                "Total amount ${:.2} exceeds window limit ${:.2}",
+                total_amount, max_amount
+            )));
+        } else if total_amount >= (max_amount * 0.75) {
+            risk_score = risk_score.saturating_add(10);
+            amount_node.points = 10.0;
+            warnings.push(Warning::new(
+                "APPROACHING_AMOUNT_LIMIT",
+                WarningSeverity::Medium,
+                format!(
+                    "Approaching amount limit: ${:.2} of ${:.2}",
+                    total_amount, max_amount
+                ),
+            ));
+        }
+
+        (risk_score, error, warnings, vec![count_node, amount_node])
+    }
+
+    /// Score credits the receiving account collected in the inbound window
+    fn check_inbound_velocity(
+        &self,
+        config: &ValidatorConfig,
+        transaction: &Transaction,
+    ) -> (
+        u8,
+        Option<ValidationError>,
+        Vec<Warning>,
+        Vec<ExplanationNode>,
+    ) {
+        let (Some(limits), Some(account)) = (
+            config.inbound_velocity.as_ref(),
+            transaction.to_account.as_ref(),
+        ) else {
+            return (0, None, Vec::new(), Vec::new());
+        };
+        let mut risk_score = 0u8;
+        let mut error = None;
+        let mut warnings = Vec::new();
+
+        let window_start = Duration::try_minutes(limits.window_minutes)
+            .and_then(|window| transaction.timestamp.checked_sub_signed(window))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        let history = self.history.read();
+        let credits: Vec<&Transaction> = history
+            .get(HistoryKey::Counterparty, account)
+            .iter()
+            .map(|h| h.as_ref())
+            .filter(|h| {
+                h.timestamp >= window_start && h.transaction_id != transaction.transaction_id
+            })
+            .collect();
+        let credit_count = credits.len();
+        let total_amount: f64 =
+            credits.iter().map(|h| h.amount.max(0.0)).sum::<f64>() + transaction.amount.max(0.0);
+        drop(history);
+
+        let window = limits.window_minutes;
+        let max_count = limits.max_credits_per_window;
+        let max_amount = limits.max_amount_per_window;
+        let mut count_node = ExplanationNode::new("inbound_credit_count", 0.0)
+            .compared(credit_count as f64, (max_count / 2) as f64)
+            .input("window_minutes", window)
+            .input("max_credits", max_count);
+        let mut amount_node = ExplanationNode::new("inbound_window_amount", 0.0)
+            .compared(total_amount, max_amount * 0.75)
+            .input("window_minutes", window)
+            .input("max_amount", max_amount);
+
+        if credit_count >= max_count {
+            risk_score = risk_score.saturating_add(30);
+            count_node.points = 30.0;
+            count_node.threshold = Some(max_count as f64);
+            error = Some(ValidationError::VelocityViolation(format!(
+                "Too many credits to {}: {} in {} minutes",
+                account,
+                credit_count + 1,
+                window
+            )));
+        } else if credit_count >= (max_count / 2) {
+            risk_score = risk_score.saturating_add(15);
+            count_node.points = 15.0;
+            warnings.push(Warning::new(
+                "HIGH_INBOUND_VELOCITY",
+                WarningSeverity::Medium,
+                format!(
+                    "High inbound velocity: {} credits to {} in window",
+                    credit_count + 1,
+                    account
+                ),
+            ));
+        }
+
+        if total_amount >= max_amount {
+            risk_score = risk_score.saturating_add(25);
+            amount_node.points = 25.0;
+            amount_node.threshold = Some(max_amount);
+            error = Some(ValidationError::VelocityViolation(format!(
+                "Credits to {} of ${:.2} exceed inbound window limit ${:.2}",
+                account, total_amount, max_amount
+            )));
+        } else if total_amount >= (max_amount * 0.75) {
+            risk_score = risk_score.saturating_add(10);
+            amount_node.points = 10.0;
+            warnings.push(Warning::new(
+                "APPROACHING_INBOUND_AMOUNT_LIMIT",
+                WarningSeverity::Medium,
+                format!(
+                    "Approaching inbound amount limit for {}: ${:.2} of ${:.2}",
+                    account, total_amount, max_amount
+                ),
+            ));
+        }
+
+        (risk_score, error, warnings, vec![count_node, amount_node])
+    }
+
+    /// Warn when the user already paid the same beneficiary the same amount recently
+    fn check_double_payment(
+        &self,
+        config: &ValidatorConfig,
+        transaction: &Transaction,
+    ) -> Option<Warning> {
+        let window = config.double_payment_window_minutes?;
+        let to_account = transaction.to_account.as_ref()?;
+        let window_start = Duration::try_minutes(window)
+            .and_then(|window| transaction.timestamp.checked_sub_signed(window))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+
+        let earlier = self
+            .history
+            .read()
+            .get(HistoryKey::User, &transaction.user_id)
+            .iter()
+            .filter(|h| {
+                h.transaction_id != transaction.transaction_id
+                    && h.to_account.as_ref() == Some(to_account)
+                    && (h.amount - transaction.amount).abs() < 0.005
+                    && h.timestamp >= window_start
+                    && h.timestamp <= transaction.timestamp
+            })
+            .count();
+
+        (earlier > 0).then(|| {
+            Warning::new(
+                "PROBABLE_DUPLICATE_PAYMENT",
+                WarningSeverity::Medium,
+                format!(
+                    "Probable duplicate payment: {:.2} to {} already sent {} time(s) in {} minutes",
+                    transaction.amount, to_account, earlier, window
+                ),
+            )
+        })
+    }
+
+    /// The `origin->destination` pair, when the geo scorer rates it High
+    fn high_risk_corridor(&self, transaction: &Transaction) -> Option<String> {
+        let scorer = self.geo_scorer.as_ref()?;
+        let metadata = transaction.metadata.as_ref()?;
+        let origin = metadata.country.as_deref()?;
+        let destination = metadata.destination_country.as_deref()?;
+        let risk = scorer.calculate_transaction_risk(origin, destination);
+        (risk.risk_level >= CountryRiskLevel::High).then(|| format!("{}->{}", origin, destination))
+    }
+
+    /// Count and amount limits for the user's current velocity window
+    fn velocity_limits(
+        &self,
+        config: &ValidatorConfig,
+        transaction: &Transaction,
+        window_start: DateTime<Utc>,
+    ) -> (usize, f64) {
+        let Some(adaptive) = &config.adaptive_velocity else {
+            return (
+                config.max_transactions_per_window,
+                config.max_amount_per_window,
+            );
+        };
+
+        let baseline_start = Duration::try_days(adaptive.baseline_days)
+            .and_then(|days| window_start.checked_sub_signed(days))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        let history = self.history.read();
+        let baseline: Vec<&Transaction> = history
+            .get(HistoryKey::User, &transaction.user_id)
+            .iter()
+            .map(|h| h.as_ref())
+            .filter(|h| h.timestamp >= baseline_start && h.timestamp < window_start)
+            .collect();
+
+        // Average over the span actually observed so new users are not diluted
+        let first_seen = baseline.iter().map(|h| h.timestamp).min();
+        let windows = first_seen
+            .map(|first| {
+                let span = (window_start - first).num_minutes() as f64;
+                (span / config.velocity_check_window_minutes.max(1) as f64).max(1.0)
+            })
+            .unwrap_or(1.0);
+        let avg_count = baseline.len() as f64 / windows;
+        let avg_amount = baseline.iter().map(|h| h.amount.max(0.0)).sum::<f64>() / windows;
+
+        let max_count = ((avg_count * adaptive.multiplier).ceil() as usize).clamp(
+            adaptive.min_transactions_per_window,
+            adaptive
+                .max_transactions_per_window
+                .max(adaptive.min_transactions_per_window),
+        );
+        let max_amount = (avg_amount * adaptive.multiplier).clamp(
+            adaptive.min_amount_per_window,
+            adaptive
+                .max_amount_per_window
+                .max(adaptive.min_amount_per_window),
+        );
+        (max_count, max_amount)
+    }
+
+    /// Validate transaction amount
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "validate_amount", level = "debug", skip_all)
+    )]
+    fn validate_amount(
+        &self,
+        config: &ValidatorConfig,
+        transaction: &Transaction,
+    ) -> Result<(), ValidationError> {
+        if !transaction.amount.is_finite() {
+            return Err(ValidationError::InvalidAmount(
+                "Amount must be a finite number".to_string(),
+            ));
+        }
+
+        let policy = &config.amount_sign_policy;
+        if !policy.permits(transaction.transaction_type, transaction.amount) {
+            return Err(ValidationError::InvalidAmount(
+                if policy == &AmountSignPolicy::default() {
+                    "Amount must be positive".to_string()
+                } else {
+                    format!(
+                        "Amount {} not permitted for {:?} transactions",
+                        transaction.amount, transaction.transaction_type
+                    )
+                },
+            ));
+        }
+        if transaction.amount == 0.0 {
+            return Ok(());
+        }
+
+        let magnitude = transaction.amount.abs();
+        if magnitude < config.min_transaction_amount {
+            return Err(ValidationError::InvalidAmount(format!(
+                "Amount {} below minimum {}",
+                transaction.amount, config.min_transaction_amount
+            )));
+        }
+
+        if magnitude > config.max_transaction_amount {
+            return Err(ValidationError::InvalidAmount(format!(
+                "Amount {} exceeds maximum {}",
+                transaction.amount, config.max_transaction_amount
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Validate account numbers
+    ///
+    /// `verdicts` are the (from, to) results taken before pseudonymization;
+    /// without them the accounts are checked as they are.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "validate_accounts", level = "debug", skip_all)
+    )]
+    fn validate_accounts(
+        &self,
+        transaction: &Transaction,
+        verdicts: Option<(bool, bool)>,
+    ) -> Result<(), ValidationError> {
+        let (from_valid, to_valid) =
+            verdicts.unwrap_or_else(|| self.account_formats_valid(transaction));
+
+        if let Some(ref from_account) = transaction.from_account {
+            if !from_valid {
+                return Err(ValidationError::InvalidAccount(format!(
+                    "Invalid from_account format: {}",
+                    from_account
+                )));
+            }
+        }
+
+        if let Some(ref to_account) = transaction.to_account {
+            if !to_valid {
+                return Err(ValidationError::InvalidAccount(format!(
+                    "Invalid to_account format: {}",
+                    to_account
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether the from and to accounts, where present, match a known scheme
+    fn account_formats_valid(&self, transaction: &Transaction) -> (bool, bool) {
+        let kind = transaction.transaction_type;
+        let valid = |account: &Option<String>| {
+            account
+                .as_ref()
+                .is_none_or(|a| self.account_formats.scheme_for(a, kind).is_some())
+        };
+        (
+            valid(&transaction.from_account),
+            valid(&transaction.to_account),
+        )
+    }
+
+    /// Whether a card number appears in the accounts or metadata
+    fn carries_pan(transaction: &Transaction) -> bool {
+        [&transaction.from_account, &transaction.to_account]
+            .into_iter()
+            .flatten()
+            .any(|account| pan::contains_pan(account))
+            || transaction
+                .metadata
+                .as_ref()
+                .is_some_and(|m| m.iter().any(|(_, value)| pan::contains_pan(value)))
+    }
+
+    /// Check ABA routing numbers on wires and ACH entries
+    fn validate_routing_numbers(&self, transaction: &Transaction) -> Result<(), ValidationError> {
+        if !routing::uses_routing_numbers(transaction.transaction_type) {
+            return Ok(());
+        }
+        for (source, routing_number) in routing::routing_numbers(transaction) {
+            if let Err(reason) = routing::validate_aba(routing_number) {
+                return Err(ValidationError::InvalidRoutingNumber(format!(
+                    "{} in {}",
+                    reason, source
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check for fraud patterns
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "check_fraud_patterns", level = "debug", skip_all)
+    )]
+    fn check_fraud_patterns(
+        &self,
+        config: &ValidatorConfig,
+        transaction: &Transaction,
+        context: &EnrichmentContext,
+    ) -> (u8, Vec<Warning>, Vec<ExplanationNode>) {
+        let mut score = 0u8;
         let mut warnings = Vec::new();
+        let mut rules = Vec::new();
 
         // Pattern 1: Large round numbers (possible money laundering)
-        if transaction.amount % 1000.0 == 0.0 && transaction.amount >= 10000.0 {
-            score += 20;
-            warnings.push("Large round number transaction".to_string());
+        if config.enable_round_amount_check && config.round_amount.matches(transaction) {
+            score = score.saturating_add(config.round_amount.severity);
+            rules.push(
+                ExplanationNode::new("ROUND_AMOUNT", config.round_amount.severity as f64)
+                    .input("amount", transaction.amount),
+            );
+            warnings.push(Warning::new(
+                "ROUND_AMOUNT",
+                WarningSeverity::Low,
+                "Large round number transaction",
+            ));
         }
 
         // Pattern 2: High-value transactions
         if transaction.amount > 50000.0 {
-            score += 30;
-            warnings.push("High-value transaction requires review".to_string());
+            score = score.saturating_add(30);
+            rules.push(
+                ExplanationNode::new("HIGH_VALUE", 30.0).compared(transaction.amount, 50000.0),
+            );
+            warnings.push(Warning::new(
+                "HIGH_VALUE",
+                WarningSeverity::Medium,
+                "High-value transaction requires review",
+            ));
         }
 
         // Pattern 3: Wire transfer to different account
-        if transaction.transaction_type == TransactionType::WireTransfer {
-            score += 15;
-            warnings.push("Wire transfer flagged for review".to_string());
+        if config.enable_wire_review
+            && transaction.transaction_type == TransactionType::WireTransfer
+        {
+            score = score.saturating_add(15);
+            rules.push(ExplanationNode::new("WIRE_TRANSFER", 15.0));
+            warnings.push(Warning::new(
+                "WIRE_TRANSFER",
+                WarningSeverity::Low,
+                "Wire transfer flagged for review",
+            ));
+        }
+
+        // Pattern 3b: Crypto transfers leave the banking system
+        if transaction.transaction_type == TransactionType::CryptoTransfer {
+            score = score.saturating_add(15);
+            rules.push(ExplanationNode::new("CRYPTO_TRANSFER", 15.0));
+            warnings.push(Warning::new(
+                "CRYPTO_TRANSFER",
+                WarningSeverity::Low,
+                "Crypto transfer flagged for review",
+            ));
         }
 
         // Pattern 4: Unusual timestamp (outside business hours)
-        let hour = transaction.timestamp.hour();
-        if !(6..=22).contains(&hour) {
-            score += 10;
-            warnings.push("Transaction outside business hours".to_string());
+        if !config
+            .time_risk_profile
+            .is_extended_hours(transaction.timestamp.hour())
+        {
+            score = score.saturating_add(10);
+            rules.push(
+                ExplanationNode::new("OUTSIDE_BUSINESS_HOURS", 10.0)
+                    .input("hour_utc", transaction.timestamp.hour()),
+            );
+            warnings.push(Warning::new(
+                "OUTSIDE_BUSINESS_HOURS",
+                WarningSeverity::Low,
+                "Transaction outside business hours",
+            ));
+        }
+
+        // Pattern 5: Debit larger than the known available balance
+        if let Some(balance) = context.account_balance {
+            if transaction.from_account.is_some() && transaction.amount > balance {
+                score = score.saturating_add(15);
+                rules.push(
+                    ExplanationNode::new("INSUFFICIENT_BALANCE", 15.0)
+                        .compared(transaction.amount, balance),
+                );
+                warnings.push(Warning::new(
+                    "INSUFFICIENT_BALANCE",
+                    WarningSeverity::Medium,
+                    format!(
+                        "Amount {:.2} exceeds available balance {:.2}",
+                        transaction.amount, balance
+                    ),
+                ));
+            }
+        }
+
+        // Pattern 6: Very new account with large or rapid activity
+        if let Some(warning) = self.check_new_account(config, transaction, context) {
+            let points = config.new_account_risk.as_ref().map_or(0, |r| r.points);
+            score = score.saturating_add(points);
+            rules.push(ExplanationNode::new("NEW_ACCOUNT_ACTIVITY", points as f64));
+            warnings.push(warning);
+        }
+
+        // Pattern 7: Prior chargebacks and recent confirmed disputes
+        let (points, dispute_warnings) = self.check_disputes(config, transaction, context);
+        score = score.saturating_add(points);
+        if points > 0 {
+            let codes: Vec<&str> = dispute_warnings.iter().map(|w| w.code.as_str()).collect();
+            rules.push(
+                ExplanationNode::new("DISPUTES", points as f64).input("codes", codes.join(",")),
+            );
+        }
+        warnings.extend(dispute_warnings);
+
+        (score, warnings, rules)
+    }
+
+    /// Score the user's and counterparty's dispute history
+    fn check_disputes(
+        &self,
+        config: &ValidatorConfig,
+        transaction: &Transaction,
+        context: &EnrichmentContext,
+    ) -> (u8, Vec<Warning>) {
+        let Some(ref rule) = config.dispute_risk else {
+            return (0, Vec::new());
+        };
+        let user_chargebacks = context
+            .user_disputes
+            .as_ref()
+            .map_or(0, |h| h.chargebacks)
+            .max(context.prior_chargebacks.unwrap_or(0));
+        let parties = [
+            ("User", user_chargebacks, context.user_disputes.as_ref()),
+            (
+                "Counterparty",
+                context
+                    .counterparty_disputes
+                    .as_ref()
+                    .map_or(0, |h| h.chargebacks),
+                context.counterparty_disputes.as_ref(),
+            ),
+        ];
+
+        let mut recent_points = 0u8;
+        let mut points = 0u8;
+        let mut warnings = Vec::new();
+        for (party, chargebacks, history) in parties {
+            let recent = history.map_or(0, |h| {
+                h.recent_confirmed(transaction.timestamp, rule.lookback_days)
+            });
+            if recent > 0 {
+                recent_points = recent_points.saturating_add(
+                    rule.points_per_dispute
+                        .saturating_mul(recent.min(255) as u8),
+                );
+                warnings.push(Warning::new(
+                    "RECENT_DISPUTES",
+                    WarningSeverity::High,
+                    format!(
+                        "{} has {} confirmed dispute(s) in the last {} days",
+                        party, recent, rule.lookback_days
+                    ),
+                ));
+            } else if chargebacks >= rule.chargeback_threshold {
+                points = points.saturating_add(rule.chargeback_points);
+                warnings.push(Warning::new(
+                    "CHARGEBACK_HISTORY",
+                    WarningSeverity::Low,
+                    format!("{} has {} prior chargeback(s)", party, chargebacks),
+                ));
+            }
+        }
+        (
+            points.saturating_add(recent_points.min(rule.max_points)),
+            warnings,
+        )
+    }
+
+    /// Warn when a new account moves a large amount or transacts rapidly
+    fn check_new_account(
+        &self,
+        config: &ValidatorConfig,
+        transaction: &Transaction,
+        context: &EnrichmentContext,
+    ) -> Option<Warning> {
+        let rule = config.new_account_risk.as_ref()?;
+        let age = context.account_age_at(transaction)?;
+        if age >= rule.max_age_days {
+            return None;
+        }
+
+        let window_start = Duration::try_minutes(config.velocity_check_window_minutes)
+            .and_then(|window| transaction.timestamp.checked_sub_signed(window))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        let recent = 1 + self
+            .history
+            .read()
+            .get(HistoryKey::User, &transaction.user_id)
+            .iter()
+            .filter(|h| h.timestamp >= window_start && h.timestamp <= transaction.timestamp)
+            .count();
+
+        let activity = if transaction.amount >= rule.large_amount {
+            format!("amount {:.2}", transaction.amount)
+        } else if recent >= rule.high_velocity_count {
+            format!("{} transactions in the velocity window", recent)
+        } else {
+            return None;
+        };
+        Some(Warning::new(
+            "NEW_ACCOUNT_ACTIVITY",
+            WarningSeverity::Medium,
+            format!("Account opened {} day(s) ago with {}", age, activity),
+        ))
+    }
+
+    /// Whether a sampled stage runs for this transaction, recording the decision
+    ///
+    /// Stages outside the sampling policy always run.
+    fn sample_stage(
+        &self,
+        config: &ValidatorConfig,
+        stage: SampledStage,
+        transaction: &Transaction,
+        state: &mut CheckState,
+    ) -> bool {
+        let Some(ref policy) = config.sampling else {
+            return true;
+        };
+        if !policy.samples(stage) {
+            return true;
         }
+        let mut provisional = state.risk_breakdown.clone();
+        provisional.calculate_total(&config.risk_weights);
+        let failed = state.hard_fail || !state.errors.is_empty();
+        let decision = policy.decide(
+            stage,
+            &transaction.transaction_id,
+            provisional.total_score,
+            failed,
+        );
+        let ran = decision.ran;
+        state.sampling.push(decision);
+        ran
+    }
 
-        (score, warnings)
+    /// Score and warn when either account takes part in a suspicious network pattern
+    fn check_network_patterns(
+        &self,
+        transaction: &Transaction,
+    ) -> (u8, Option<Warning>, Vec<ExplanationNode>) {
+        let Some(ref analyzer) = self.network_analyzer else {
+            return (0, None, Vec::new());
+        };
+        let report = analyzer.analyze_all();
+        let involved: Vec<&str> = [&transaction.from_account, &transaction.to_account]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .filter(|account| report.involves_account(account))
+            .collect();
+        if involved.is_empty() {
+            return (0, None, Vec::new());
+        }
+        let warning = Warning::new(
+            "NETWORK_PATTERN",
+            WarningSeverity::Medium,
+            format!(
+                "Account(s) {} involved in suspicious network activity",
+                involved.join(", ")
+            ),
+        );
+        let nodes = involved
+            .iter()
+            .map(|account| ExplanationNode::new("NETWORK_PATTERN", 30.0).input("account", account))
+            .collect();
+        (30 * involved.len() as u8, Some(warning), nodes)
     }
 
     /// Check AML/KYC compliance
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "check_aml_compliance", level = "debug", skip_all)
+    )]
     fn check_aml_compliance(&self, transaction: &Transaction) -> bool {
         // Simplified AML check
         // In production, this would check against government watch lists, PEPs, etc.
@@ -536,59 +4239,276 @@ impl TransactionValidator {
     }
 
     /// Check business rules
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "check_business_rules", level = "debug", skip_all)
+    )]
     fn check_business_rules(&self, transaction: &Transaction) -> Result<(), ValidationError> {
-        // Rule 1: Transfers must have both from and to accounts
-        if transaction.transaction_type == TransactionType::Transfer
-            && (transaction.from_account.is_none() || transaction.to_account.is_none())
-        {
+        let has_from = transaction.from_account.is_some();
+        let has_to = transaction.to_account.is_some();
+        let violation = match transaction.transaction_type {
+            // Account-to-account movements need both ends
+            TransactionType::Transfer if !(has_from && has_to) => {
+                Some("Transfers must specify both from and to accounts")
+            }
+            TransactionType::AchCredit | TransactionType::AchDebit if !(has_from && has_to) => {
+                Some("ACH entries must specify both from and to accounts")
+            }
+            TransactionType::CryptoTransfer if !(has_from && has_to) => {
+                Some("Crypto transfers must specify both from and to accounts")
+            }
+            // Money arriving needs a destination
+            TransactionType::Deposit if !has_to => Some("Deposits must specify to_account"),
+            TransactionType::Refund if !has_to => Some("Refunds must specify to_account"),
+            // Money leaving needs a source
+            TransactionType::Withdrawal if !has_from => {
+                Some("Withdrawals must specify from_account")
+            }
+            TransactionType::CardPurchase if !has_from => {
+                Some("Card purchases must specify from_account")
+            }
+            TransactionType::Check if !has_from => Some("Checks must specify from_account"),
+            TransactionType::Fee if !has_from => Some("Fees must specify from_account"),
+            TransactionType::Authorization | TransactionType::Capture if !has_from => {
+                Some("Authorizations and captures must specify from_account")
+            }
+            _ => None,
+        };
+        if let Some(violation) = violation {
             return Err(ValidationError::BusinessRuleViolation(
-                "Transfers must specify both from and to accounts".to_string(),
+                violation.to_string(),
             ));
         }
 
-        // Rule 2: Deposits must have to_account
-        if transaction.transaction_type == TransactionType::Deposit
-            && transaction.to_account.is_none()
+        // Refunds and reversals must name the transaction they undo
+        if transaction.transaction_type.references_parent()
+            && transaction.parent_transaction_id().is_none()
         {
-            return Err(ValidationError::BusinessRuleViolation(
-                "Deposits must specify to_account".to_string(),
-            ));
+            return Err(ValidationError::BusinessRuleViolation(format!(
+                "{} must reference the original transaction in {}",
+                transaction.transaction_type, PARENT_TRANSACTION_METADATA_KEY
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Reject transaction types the channel's policy does not accept
+    fn check_channel_rules(
+        &self,
+        transaction: &Transaction,
+        channel: Option<Channel>,
+    ) -> Result<(), ValidationError> {
+        let Some(channel) = channel else {
+            return Ok(());
+        };
+        match self.channel_policies.get(&channel) {
+            Some(policy) if !policy.allows(&transaction.transaction_type) => {
+                Err(ValidationError::BusinessRuleViolation(format!(
+                    "{:?} transactions are not accepted through the {:?} channel",
+                    transaction.transaction_type, channel
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Validate multiple transactions in batch
+    pub fn validate_batch(&mut self, transactions: &[Transaction]) -> Vec<ValidationResult> {
+        transactions.iter().map(|tx| self.validate(tx)).collect()
+    }
+
+    /// Validate multiple transactions and summarize the batch
+    pub fn validate_batch_with_report(
+        &mut self,
+        transactions: &[Transaction],
+    ) -> (Vec<ValidationResult>, BatchReport) {
+        let started = Instant::now();
+        let results = self.validate_batch(transactions);
+        let report = BatchReport::new(transactions, &results, started.elapsed());
+        (results, report)
+    }
+
+    /// Distributional anomalies in a batch, against the history as baseline
+    ///
+    /// Nothing is validated or recorded.
+    pub fn batch_anomalies(
+        &self,
+        transactions: &[Transaction],
+        config: &AnomalyCheckConfig,
+    ) -> Vec<AnomalyFinding> {
+        anomaly::detect_anomalies(transactions, self.history.read().iter(), config)
+    }
+
+    /// Validate a batch after checking it for cross-transaction patterns
+    ///
+    /// Each finding is added as a warning to every transaction it involves.
+    pub fn validate_batch_cross_checked(
+        &mut self,
+        transactions: &[Transaction],
+        config: &BatchCheckConfig,
+    ) -> (Vec<ValidationResult>, Vec<BatchFinding>) {
+        let mut findings = batch::analyze_batch(transactions, config, |account| {
+            !self
+                .history
+                .read()
+                .get(HistoryKey::Counterparty, account)
+                .is_empty()
+        });
+        // Descriptions quote accounts, which may be unmasked card numbers
+        if self.config.mask_pans {
+            for finding in &mut findings {
+                if let Some(masked) = pan::mask_pans(&finding.description) {
+                    finding.description = masked;
+                }
+            }
         }
 
-        // Rule 3: Withdrawals must have from_account
-        if transaction.transaction_type == TransactionType::Withdrawal
-            && transaction.from_account.is_none()
+        let results = transactions
+            .iter()
+            .enumerate()
+            .map(|(i, tx)| {
+                let notes = findings
+                    .iter()
+                    .filter(|f| f.indices.contains(&i))
+                    .map(|f| Warning::new(f.kind.code(), WarningSeverity::Medium, &f.description))
+                    .collect();
+                self.run_validation(tx, EnrichmentContext::default(), None, notes)
+            })
+            .collect();
+        (results, findings)
+    }
+
+    /// Validate the legs of a multi-leg transaction as one unit
+    ///
+    /// Group checks run first, then every leg is checked in order with the
+    /// group's warnings attached. Each leg sees the history and transaction
+    /// IDs of the legs before it, which stay staged until the group is
+    /// decided. Only if everything passes are the evaluated legs committed
+    /// under the configured commit policy; otherwise their reservations are
+    /// released and no leg is committed, though every leg is still counted,
+    /// audited, and logged. Events for all legs are appended before any leg
+    /// commits; if one cannot be written, no leg commits and replay ignores
+    /// the partial group.
+    pub fn validate_group(
+        &mut self,
+        group: &TransactionGroup,
+        config: &GroupCheckConfig,
+    ) -> GroupResult {
+        let (errors, notes) =
+            groups::check_group(group, config, self.config.max_transaction_amount);
+        let mut evaluated = Vec::with_capacity(group.legs.len());
+        let mut staged = Vec::new();
+        for leg in &group.legs {
+            let leg = self.evaluate(leg, EnrichmentContext::default(), None, notes.clone(), None);
+            if leg.pending.history {
+                staged.push(self.history.write().record_shared(&leg.transaction));
+            }
+            evaluated.push(leg);
+        }
         {
-            return Err(ValidationError::BusinessRuleViolation(
-                "Withdrawals must specify from_account".to_string(),
-            ));
+            let mut history = self.history.write();
+            for recorded in &staged {
+                history.remove(recorded);
+            }
+        }
+
+        let passed = errors.is_empty() && evaluated.iter().all(|leg| leg.result.is_valid);
+        let commits: Vec<bool> = evaluated
+            .iter()
+            .map(|leg| passed && Self::commits(&leg.result, None))
+            .collect();
+
+        // Write ahead for every leg before any of them commits
+        let membership = GroupMembership {
+            group_id: group.group_id.clone(),
+            legs: evaluated.len(),
+        };
+        let mut log_error = None;
+        for (leg, commit) in evaluated.iter().zip(&commits) {
+            if let Err(e) = self.append_event(leg, *commit, Some(&membership)) {
+                log_error = Some(e);
+                break;
+            }
         }
 
-        Ok(())
+        // A failed or unlogged group commits no leg, but each is still
+        // counted, audited, and logged
+        let legs: Vec<ValidationResult> = evaluated
+            .into_iter()
+            .zip(commits)
+            .map(|(mut leg, commit)| match log_error {
+                Some(ref e) => {
+                    Self::fail_closed(&mut leg.result, e);
+                    self.finish(leg, false)
+                }
+                None => self.finish(leg, commit),
+            })
+            .collect();
+        let committed = passed && log_error.is_none() && legs.iter().all(|leg| leg.committed);
+        GroupResult::new(group, errors, legs, committed)
     }
 
-    /// Validate multiple transactions in batch
-    pub fn validate_batch(&mut self, transactions: &[Transaction]) -> Vec<ValidationResult> {
-        transactions.iter().map(|tx| self.validate(tx)).collect()
+    /// Get validation statistics
+    pub fn get_stats(&self) -> ValidatorStats {
+        let now = self.clock.now();
+        let reference_data: Vec<DatasetAge> = self
+            .reference_datasets()
+            .into_iter()
+            .map(|d| DatasetAge::new(d, now, self.config.max_reference_data_age_days))
+            .collect();
+        ValidatorStats {
+            total_validated: self.stats.total_validated,
+            total_processed: self.dedup.read().len(),
+            total_transactions_in_history: self.history.read().len(),
+            decisions: self.stats.decisions.clone(),
+            errors_by_type: self.stats.errors_by_type.clone(),
+            warnings_by_code: self.stats.warnings_by_code.clone(),
+            score_distribution: self.stats.score_distribution.clone(),
+            estimated_memory_bytes: self.estimated_memory_bytes(),
+            started_at: self.stats.started_at,
+            uptime_seconds: now
+                .signed_duration_since(self.stats.started_at)
+                .num_seconds(),
+            top_users: stats::top_users(
+                self.history
+                    .read()
+                    .iter()
+                    .map(|h| (h.user_id.as_str(), h.amount)),
+                stats::TOP_USERS_LIMIT,
+            ),
+            decision_log_failures: self.stats.decision_log_failures,
+            event_log_failures: self.stats.event_log_failures,
+            audit_failures: self.stats.audit_failures,
+            alerts_dropped: self.alerts.dropped(),
+            stale_reference_data: reference_data.iter().filter(|d| d.stale).count(),
+            reference_data,
+        }
     }
 
-    /// Get validation statistics
-    pub fn get_stats(&self) -> HashMap<String, usize> {
-        let mut stats = HashMap::new();
-        stats.insert(
-            "total_processed".to_string(),
-            self.processed_transactions.len(),
-        );
-        stats.insert(
-            "total_transactions_in_history".to_string(),
-            self.transaction_history.len(),
-        );
-        stats
+    /// Estimate heap and inline memory held by dedup and history state
+    fn estimated_memory_bytes(&self) -> usize {
+        self.dedup.read().estimated_memory_bytes() + self.history.read().estimated_memory_bytes()
     }
 
     /// Clear old transaction history (for memory management)
+    ///
+    /// Content fingerprints of transactions timestamped before `before` go
+    /// with it; committed transaction IDs are kept.
     pub fn clear_old_history(&mut self, before: DateTime<Utc>) {
-        self.transaction_history.retain(|h| h.timestamp >= before);
+        self.history.write().prune_before(before);
+        self.dedup.write().prune_fingerprints_before(before);
+    }
+
+    /// Clear history and fingerprints older than the history's retention window
+    pub fn prune_history(&mut self) {
+        let retention = self.history.read().retention();
+        let cutoff = self
+            .clock
+            .now()
+            .checked_sub_signed(retention)
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        self.clear_old_history(cutoff);
     }
 }
 
@@ -603,21 +4523,7 @@ mod tests {
     use super::*;
 
     fn create_valid_transaction() -> Transaction {
-        // Create timestamp at 12 PM UTC (business hours) to minimize time-based risk
-        let now = Utc::now();
-        let timestamp = now.date_naive().and_hms_opt(12, 0, 0).unwrap().and_utc();
-
-        Transaction {
-            transaction_id: "TXN-001".to_string(),
-            transaction_type: TransactionType::Transfer,
-            amount: 1000.0,
-            currency: "USD".to_string(),
-            from_account: Some("ACCT-1234-5678-9012".to_string()),
-            to_account: Some("ACCT-6789-0123-4567".to_string()),
-            timestamp,
-            user_id: "USER-001".to_string(),
-            metadata: None,
-        }
+        test_fixtures::create_test_transaction("TXN-001", 1000.0)
     }
 
     #[test]
@@ -653,6 +4559,46 @@ mod tests {
         assert!(!result.errors.is_empty());
     }
 
+    #[test]
+    fn test_amount_sign_policy_per_type() {
+        let config = ValidatorConfig {
+            amount_sign_policy: AmountSignPolicy::ledger(),
+            ..Default::default()
+        };
+        let mut validator = TransactionValidator::builder().with_config(config).build();
+        let record = |id: &str, transaction_type: TransactionType, amount: f64| {
+            let mut tx = create_valid_transaction();
+            tx.transaction_id = id.to_string();
+            tx.transaction_type = transaction_type;
+            tx.amount = amount;
+            tx
+        };
+
+        let check = validator.validate(&record("TXN-SGN-1", TransactionType::Authorization, 0.0));
+        assert!(check.is_valid, "{:?}", check.errors);
+        let waiver = validator.validate(&record("TXN-SGN-2", TransactionType::Fee, -25.0));
+        assert!(waiver.is_valid, "{:?}", waiver.errors);
+
+        let invalid_amount = |result: &ValidationResult| {
+            result
+                .errors
+                .iter()
+                .any(|e| matches!(e, ValidationError::InvalidAmount(_)))
+        };
+        let zero_fee = validator.validate(&record("TXN-SGN-3", TransactionType::Fee, 0.0));
+        assert!(invalid_amount(&zero_fee));
+        let negative_transfer =
+            validator.validate(&record("TXN-SGN-4", TransactionType::Transfer, -25.0));
+        assert!(invalid_amount(&negative_transfer));
+        // Limits apply to the magnitude
+        let huge_reversal =
+            validator.validate(&record("TXN-SGN-5", TransactionType::Fee, -2_000_000.0));
+        assert!(invalid_amount(&huge_reversal));
+        let not_a_number =
+            validator.validate(&record("TXN-SGN-6", TransactionType::Transfer, f64::NAN));
+        assert!(invalid_amount(&not_a_number));
+    }
+
     #[test]
     fn test_duplicate_detection() {
         let mut validator = TransactionValidator::new();
@@ -669,6 +4615,143 @@ mod tests {
             .any(|e| matches!(e, ValidationError::DuplicateTransaction(_))));
     }
 
+    #[test]
+    fn test_fingerprint_duplicate_detection() {
+        let mut validator = TransactionValidator::with_config(ValidatorConfig {
+            fingerprint_bucket_seconds: Some(300),
+            ..Default::default()
+        });
+        let transaction = create_valid_transaction();
+        assert!(validator.validate(&transaction).is_valid);
+
+        // Same payment resubmitted under a fresh ID
+        let mut replay = transaction.clone();
+        replay.transaction_id = "TXN-REPLAY-001".to_string();
+        let result = validator.validate(&replay);
+        assert!(result.errors.iter().any(|e| matches!(
+            e,
+            ValidationError::DuplicateTransaction(msg) if msg.contains("TXN-REPLAY-001")
+        )));
+
+        // Different amount is a different payment
+        let mut other = transaction.clone();
+        other.transaction_id = "TXN-OTHER-001".to_string();
+        other.amount += 1.0;
+        assert!(validator.validate(&other).is_valid);
+        assert_ne!(transaction.fingerprint(300), other.fingerprint(300));
+
+        // A resubmission just across the bucket edge is still caught
+        let mut late = transaction.clone();
+        late.transaction_id = "TXN-LATE-001".to_string();
+        late.timestamp += Duration::seconds(300);
+        assert_ne!(transaction.fingerprint(300), late.fingerprint(300));
+        assert!(!validator.validate(&late).is_valid);
+
+        // Pruning history releases the fingerprints it covered
+        validator.clear_old_history(transaction.timestamp + Duration::seconds(1));
+        assert_eq!(validator.dedup().read().fingerprint_count(), 0);
+        let mut later = transaction.clone();
+        later.transaction_id = "TXN-LATER-001".to_string();
+        assert!(validator.validate(&later).is_valid);
+    }
+
+    #[test]
+    fn test_double_payment_warning() {
+        let mut validator = TransactionValidator::with_config(ValidatorConfig {
+            double_payment_window_minutes: Some(30),
+            ..Default::default()
+        });
+        let first = create_valid_transaction();
+        let first_result = validator.validate(&first);
+        assert!(!first_result
+            .warnings
+            .iter()
+            .any(|w| w.message.contains("duplicate payment")));
+
+        let mut second = first.clone();
+        second.transaction_id = "TXN-SECOND-001".to_string();
+        second.timestamp = first.timestamp + Duration::minutes(10);
+        let result = validator.validate(&second);
+        assert!(result.is_valid, "double payments warn but do not fail");
+        assert!(result
+            .warnings
+            .iter()
+            .any(|w| w.message.contains("Probable duplicate payment")));
+
+        // Outside the window
+        let mut later = first.clone();
+        later.transaction_id = "TXN-LATER-001".to_string();
+        later.timestamp = first.timestamp + Duration::minutes(120);
+        assert!(!validator
+            .validate(&later)
+            .warnings
+            .iter()
+            .any(|w| w.message.contains("duplicate payment")));
+    }
+
+    #[test]
+    fn test_rejected_transaction_not_committed() {
+        let mut validator = TransactionValidator::new();
+        let mut transaction = create_valid_transaction();
+        transaction.amount = -5.0;
+        let rejected = validator.validate(&transaction);
+        assert!(!rejected.is_valid);
+        assert!(!rejected.committed);
+
+        // Corrected resubmission under the same ID is not a duplicate
+        transaction.amount = 100.0;
+        let corrected = validator.validate(&transaction);
+        assert!(corrected.is_valid);
+        assert!(corrected.committed);
+        assert_eq!(validator.get_stats().total_transactions_in_history, 1);
+    }
+
+    #[test]
+    fn test_dry_run_has_no_side_effects() {
+        let mut validator = TransactionValidator::new();
+        let transaction = create_valid_transaction();
+
+        let preview = validator.validate_dry_run(&transaction);
+        assert!(preview.is_valid);
+        assert!(!preview.committed);
+        assert_eq!(validator.get_stats().total_validated, 0);
+        assert_eq!(validator.get_stats().total_transactions_in_history, 0);
+
+        assert!(validator.validate(&transaction).is_valid);
+        let forced = validator.validate_with_commit(&transaction, true);
+        assert!(!forced.is_valid);
+        assert!(forced.committed);
+    }
+
+    #[test]
+    fn test_dry_run_leaves_fraud_profiles_untouched() {
+        let located = |id: &str, country: &str, minutes: i64| {
+            let mut tx = create_valid_transaction();
+            tx.transaction_id = id.to_string();
+            tx.timestamp += Duration::minutes(minutes);
+            tx.metadata = Some(TransactionMetadata {
+                country: Some(country.to_string()),
+                ..Default::default()
+            });
+            tx
+        };
+        let moved =
+            |result: &ValidationResult| result.warnings.iter().any(|w| w.message.contains("Moved"));
+
+        for dry_run in [true, false] {
+            let mut validator = TransactionValidator::new();
+            validator.set_fraud_detector(FraudDetector::new());
+            let singapore = located("TXN-SG", "SG", 0);
+            if dry_run {
+                validator.validate_dry_run(&singapore);
+            } else {
+                validator.validate(&singapore);
+            }
+            let result = validator.validate(&located("TXN-US", "US", 30));
+            assert_eq!(moved(&result), !dry_run);
+        }
+    }
+
     #[test]
     fn test_fraud_detection() {
         let mut validator = TransactionValidator::new();
@@ -691,6 +4774,38 @@ mod tests {
         assert!(!result.is_valid);
     }
 
+    #[test]
+    fn test_type_specific_business_rules() {
+        let mut validator = TransactionValidator::new();
+
+        let mut card = create_valid_transaction();
+        card.transaction_id = "TXN-CARD-001".to_string();
+        card.transaction_type = TransactionType::CardPurchase;
+        card.to_account = None;
+        assert!(validator.validate(&card).is_valid);
+        card.transaction_id = "TXN-CARD-002".to_string();
+        card.from_account = None;
+        assert!(!validator.validate(&card).is_valid);
+
+        // A refund without the original transaction is rejected
+        let mut refund = create_valid_transaction();
+        refund.transaction_id = "TXN-REFUND-001".to_string();
+        refund.transaction_type = TransactionType::Refund;
+        let result = validator.validate(&refund);
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| e.to_string().contains(PARENT_TRANSACTION_METADATA_KEY)));
+
+        refund.transaction_id = "TXN-REFUND-002".to_string();
+        refund.metadata = Some(TransactionMetadata::from([(
+            PARENT_TRANSACTION_METADATA_KEY.to_string(),
+            "TXN-CARD-001".to_string(),
+        )]));
+        assert!(validator.validate(&refund).is_valid);
+        assert_eq!(TransactionType::AchDebit.to_string(), "ach_debit");
+    }
+
     #[test]
     fn test_velocity_check() {
         let mut validator = TransactionValidator::new();
@@ -712,7 +4827,7 @@ mod tests {
 
         // Check that velocity warnings are present
         let stats = validator.get_stats();
-        assert_eq!(stats["total_transactions_in_history"], 5);
+        assert_eq!(stats.total_transactions_in_history, 5);
     }
 
     #[test]
@@ -729,19 +4844,144 @@ mod tests {
         assert_eq!(result.risk_breakdown.total_score, result.fraud_score);
     }
 
+    #[test]
+    fn test_amount_risk_tiers() {
+        let mut transaction = create_valid_transaction();
+        transaction.amount = 20_000.0;
+        let default = AmountRiskTiers::default();
+        assert_eq!(default.score(&transaction), 15);
+
+        let tiers = AmountRiskTiers {
+            overrides: vec![
+                AmountTierOverride {
+                    transaction_type: None,
+                    currency: Some("JPY".to_string()),
+                    tiers: vec![AmountTier {
+                        above: 1_000_000.0,
+                        points: 15,
+                    }],
+                },
+                AmountTierOverride {
+                    transaction_type: Some(TransactionType::WireTransfer),
+                    currency: None,
+                    tiers: vec![AmountTier {
+                        above: 5_000.0,
+                        points: 25,
+                    }],
+                },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(tiers.score(&transaction), 15, "no override matches");
+
+        transaction.currency = "JPY".to_string();
+        assert_eq!(tiers.score(&transaction), 0);
+
+        transaction.currency = "USD".to_string();
+        transaction.transaction_type = TransactionType::WireTransfer;
+        let mut validator = TransactionValidator::with_config(ValidatorConfig {
+            amount_risk_tiers: tiers,
+            ..Default::default()
+        });
+        assert_eq!(
+            validator.validate(&transaction).risk_breakdown.amount_risk,
+            25
+        );
+    }
+
+    #[test]
+    fn test_risk_weights() {
+        let mut transaction = create_valid_transaction();
+        transaction.amount = 150_000.0;
+        transaction.metadata = Some(TransactionMetadata::from([(
+            "country".to_string(),
+            "PK".to_string(),
+        )]));
+
+        let mut validator = TransactionValidator::with_config(ValidatorConfig {
+            risk_weights: RiskWeights {
+                amount: 0.5,
+                velocity: 0.0,
+                pattern: 0.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        validator.set_geographic_scorer(GeographicRiskScorer::new());
+        let result = validator.validate(&transaction);
+        assert_eq!(result.risk_breakdown.amount_risk, 40);
+        assert_eq!(result.risk_breakdown.geo_risk, 55);
+        assert_eq!(result.fraud_score, 20, "geo is weighted 0 by default");
+
+        let mut validator = TransactionValidator::with_config(ValidatorConfig {
+            risk_weights: RiskWeights {
+                amount: 0.5,
+                velocity: 0.0,
+                pattern: 0.0,
+                geo: 0.5,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        validator.set_geographic_scorer(GeographicRiskScorer::new());
+        transaction.transaction_id = "TXN-002".to_string();
+        assert_eq!(validator.validate(&transaction).fraud_score, 48);
+    }
+
     #[test]
     fn test_time_based_risk() {
         let mut validator = TransactionValidator::new();
         let mut transaction = create_valid_transaction();
 
         // Set timestamp to late night (high risk)
-        let late_night = Utc::now().date_naive().and_hms_opt(2, 0, 0).unwrap();
+        let late_night = test_fixtures::test_now()
+            .date_naive()
+            .and_hms_opt(2, 0, 0)
+            .unwrap();
         transaction.timestamp = DateTime::from_naive_utc_and_offset(late_night, Utc);
 
         let result = validator.validate(&transaction);
         assert!(result.risk_breakdown.time_risk > 0);
     }
 
+    #[test]
+    fn test_time_risk_profiles() {
+        let weekday_night = DateTime::parse_from_rfc3339("2024-01-10T02:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let saturday_noon = DateTime::parse_from_rfc3339("2024-01-13T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let default = TimeRiskProfile::default();
+        assert_eq!(default.score(&weekday_night), 20);
+        assert_eq!(default.score(&saturday_noon), 0);
+
+        let weekend = TimeRiskProfile {
+            weekend_score: Some(15),
+            ..Default::default()
+        };
+        assert_eq!(weekend.score(&saturday_noon), 15);
+
+        let overnight = TimeRiskProfile {
+            business_hours: (22, 6),
+            ..Default::default()
+        };
+        assert_eq!(overnight.score(&weekday_night), 0);
+
+        let mut validator = TransactionValidator::with_config(ValidatorConfig {
+            time_risk_profile: TimeRiskProfile::always_open(),
+            ..Default::default()
+        });
+        let mut transaction = create_valid_transaction();
+        transaction.timestamp = weekday_night;
+        let result = validator.validate(&transaction);
+        assert_eq!(result.risk_breakdown.time_risk, 0);
+        assert!(!result
+            .warning_codes()
+            .contains(&"OUTSIDE_BUSINESS_HOURS".to_string()));
+    }
+
     #[test]
     fn test_risk_level_description() {
         let mut validator = TransactionValidator::new();
@@ -806,7 +5046,7 @@ mod tests {
         let mut validator = TransactionValidator::new();
 
         // Add transactions with old timestamps
-        let old_time = Utc::now() - Duration::hours(48);
+        let old_time = test_fixtures::test_now() - Duration::hours(48);
         for i in 0..5 {
             let mut transaction = create_valid_transaction();
             transaction.transaction_id = format!("TXN-{}", i);
@@ -814,11 +5054,11 @@ mod tests {
             validator.validate(&transaction);
         }
 
-        let cutoff = Utc::now() - Duration::hours(24);
+        let cutoff = test_fixtures::test_now() - Duration::hours(24);
         validator.clear_old_history(cutoff);
 
         let stats = validator.get_stats();
-        assert_eq!(stats["total_transactions_in_history"], 0);
+        assert_eq!(stats.total_transactions_in_history, 0);
     }
 
     #[test]
@@ -851,6 +5091,7 @@ mod tests {
             velocity_check_window_minutes: 60,
             max_transactions_per_window: 10,
             max_amount_per_window: 50_000.0, // Low limit for testing
+            ..Default::default()
         };
 
         let mut validator = TransactionValidator::with_config(config);
@@ -871,6 +5112,452 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_adaptive_velocity_uses_user_baseline() {
+        let config = ValidatorConfig {
+            max_transactions_per_window: 3,
+            adaptive_velocity: Some(AdaptiveVelocity {
+                min_transactions_per_window: 3,
+                max_transactions_per_window: 50,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let mut validator = TransactionValidator::with_config(config);
+        let now = test_fixtures::test_now();
+
+        // Heavy user: 10 transactions an hour, every hour, for the last 5 days
+        let mut history = validator.history().write();
+        for hour in 1..=120 {
+            for n in 0..10 {
+                let mut tx = create_valid_transaction();
+                tx.user_id = "USER-HEAVY".to_string();
+                tx.timestamp = now - Duration::hours(hour) + Duration::minutes(n);
+                tx.amount = 10.0;
+                tx.to_account = None;
+                history.record(&tx);
+            }
+        }
+        drop(history);
+
+        let mut heavy_error = false;
+        let mut light_error = false;
+        for i in 0..8 {
+            let mut tx = create_valid_transaction();
+            tx.transaction_id = format!("TXN-HEAVY-{}", i);
+            tx.user_id = "USER-HEAVY".to_string();
+            tx.amount = 10.0;
+            tx.timestamp = now;
+            heavy_error |= validator
+                .validate(&tx)
+                .errors
+                .iter()
+                .any(|e| matches!(e, ValidationError::VelocityViolation(_)));
+
+            let mut tx = create_valid_transaction();
+            tx.transaction_id = format!("TXN-LIGHT-{}", i);
+            tx.user_id = "USER-LIGHT".to_string();
+            tx.amount = 10.0;
+            tx.timestamp = now;
+            light_error |= validator
+                .validate(&tx)
+                .errors
+                .iter()
+                .any(|e| matches!(e, ValidationError::VelocityViolation(_)));
+        }
+
+        assert!(!heavy_error, "baseline of 10/hour allows 8 in a window");
+        assert!(light_error, "user without history is held to the floor");
+    }
+
+    #[test]
+    fn test_inbound_velocity_limits_credits_from_many_payers() {
+        let config = ValidatorConfig {
+            inbound_velocity: Some(InboundVelocityLimits {
+                max_credits_per_window: 4,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let mut validator = TransactionValidator::builder().with_config(config).build();
+        let payment = |i: usize| {
+            let mut tx = create_valid_transaction();
+            tx.transaction_id = format!("TXN-IN-{}", i);
+            tx.user_id = format!("USER-{:03}", i);
+            tx.from_account = Some(format!("ACCT-1234-5678-{:04}", i));
+            tx.amount = 200.0;
+            tx
+        };
+
+        let first = validator.validate(&payment(0));
+        assert!(first.is_valid);
+        validator.validate(&payment(1));
+        let third = validator.validate(&payment(2));
+        assert!(third
+            .warning_codes()
+            .contains(&"HIGH_INBOUND_VELOCITY".to_string()));
+        validator.validate(&payment(3));
+
+        // Each payer is far below the outbound limits; the payee is not
+        let result = validator.validate(&payment(4));
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::VelocityViolation(d) if d.contains("credits"))));
+        let count = result.explanation.find("inbound_credit_count").unwrap();
+        assert_eq!(count.points, 30.0);
+    }
+
+    #[test]
+    fn test_high_risk_corridor_tightens_velocity() {
+        let config = ValidatorConfig {
+            high_risk_corridor_limits: Some(CorridorLimits::default()),
+            ..Default::default()
+        };
+        let mut validator = TransactionValidator::builder()
+            .with_config(config)
+            .with_geo(GeographicRiskScorer::new())
+            .build();
+        let corridor_tx = |id: &str, destination: &str| {
+            let mut tx = create_valid_transaction();
+            tx.transaction_id = id.to_string();
+            tx.amount = 50.0;
+            tx.metadata = Some(TransactionMetadata {
+                country: Some("MM".to_string()),
+                destination_country: Some(destination.to_string()),
+                ..Default::default()
+            });
+            tx
+        };
+
+        for i in 0..3 {
+            let result = validator.validate(&corridor_tx(&format!("TXN-COR-{}", i), "YE"));
+            assert!(!result
+                .errors
+                .iter()
+                .any(|e| matches!(e, ValidationError::VelocityViolation(_))));
+        }
+        let result = validator.validate(&corridor_tx("TXN-COR-3", "YE"));
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::VelocityViolation(_))));
+        let count = result.explanation.find("transaction_count").unwrap();
+        assert_eq!(count.inputs["high_risk_corridor"], "MM->YE");
+
+        // MM->US is rated Medium, so the regular limit of 10 applies
+        let result = validator.validate(&corridor_tx("TXN-COR-4", "US"));
+        assert!(!result
+            .errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::VelocityViolation(_))));
+    }
+
+    #[test]
+    fn test_error_codes_serialized() {
+        let error = ValidationError::SanctionsMatch("'X' matched X on OFAC".to_string());
+        assert_eq!(error.code(), 3002);
+
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["code"], 3002);
+        assert_eq!(json["reason_code"], "SANCTIONS_MATCH");
+        assert_eq!(json["message"], error.to_string());
+
+        let back: ValidationError = serde_json::from_value(json).unwrap();
+        assert_eq!(back.code(), 3002);
+        assert_eq!(back.detail(), error.detail());
+
+        let unknown = serde_json::json!({
+            "code": 9999, "reason_code": "X", "message": "", "detail": ""
+        });
+        assert!(serde_json::from_value::<ValidationError>(unknown).is_err());
+    }
+
+    #[test]
+    fn test_decision() {
+        let mut validator = TransactionValidator::new();
+        let transaction = create_valid_transaction();
+        let result = validator.validate(&transaction);
+        assert_eq!(result.decision, Decision::Approve);
+
+        // Duplicate submission is declined
+        let result = validator.validate(&transaction);
+        assert_eq!(result.decision, Decision::Decline);
+        assert_eq!(result.reason_codes(), vec!["DUPLICATE_TRANSACTION"]);
+    }
+
+    #[test]
+    fn test_validator_stats() {
+        let mut validator = TransactionValidator::with_config(ValidatorConfig {
+            commit_policy: CommitPolicy::Always,
+            ..Default::default()
+        });
+        let transaction = create_valid_transaction();
+        validator.validate(&transaction);
+        validator.validate(&transaction); // duplicate
+
+        let mut invalid = create_valid_transaction();
+        invalid.transaction_id = "TXN-002".to_string();
+        invalid.amount = -5.0;
+        validator.validate(&invalid);
+
+        let stats = validator.get_stats();
+        assert_eq!(stats.total_validated, 3);
+        assert_eq!(stats.decisions.approve, 1);
+        assert_eq!(stats.decisions.decline, 2);
+        assert_eq!(stats.errors_by_type["DUPLICATE_TRANSACTION"], 1);
+        assert_eq!(stats.errors_by_type["INVALID_AMOUNT"], 1);
+        assert_eq!(stats.score_distribution.low, 3);
+        assert_eq!(stats.top_users[0].user_id_hash, hash_identifier("USER-001"));
+        assert_eq!(stats.top_users[0].transaction_count, 3);
+        assert!(stats.estimated_memory_bytes > 0);
+
+        let json = serde_json::to_string(&stats).unwrap();
+        assert!(json.contains("errors_by_type"));
+        assert!(!json.contains("USER-001"));
+    }
+
+    fn create_screening_validator(short_circuit: bool) -> TransactionValidator {
+        let mut validator = TransactionValidator::with_config(ValidatorConfig {
+            short_circuit_hard_fails: short_circuit,
+            ..Default::default()
+        });
+        validator.set_sanctions_screener(SanctionsScreener::new());
+        validator.set_geographic_scorer(GeographicRiskScorer::new());
+        validator
+    }
+
+    #[test]
+    fn test_sanctions_hit_short_circuits() {
+        let mut validator = create_screening_validator(true);
+        let mut transaction = create_valid_transaction();
+        transaction.metadata = Some(TransactionMetadata::from([(
+            "beneficiary_name".to_string(),
+            "Sanctioned Entity One".to_string(),
+        )]));
+
+        let result = validator.validate(&transaction);
+        assert_eq!(result.decision, Decision::Decline);
+        assert_eq!(result.fraud_score, 100);
+        assert!(!result.compliance_checks["SANCTIONS"]);
+        assert_eq!(result.reason_codes(), vec!["SANCTIONS_MATCH"]);
+
+        // Remaining checks were skipped, so no state was recorded
+        assert!(result.skipped_checks.contains(&Check::Duplicate));
+        assert!(result.skipped_checks.contains(&Check::Velocity));
+        assert!(result.skipped_checks.contains(&Check::FraudPatterns));
+        assert!(result.skipped_checks.contains(&Check::CustomRules));
+        let stats = validator.get_stats();
+        assert_eq!(stats.total_processed, 0);
+        assert_eq!(stats.total_transactions_in_history, 0);
+    }
+
+    #[test]
+    fn test_prohibited_country_without_short_circuit() {
+        let mut validator = create_screening_validator(false);
+        let mut transaction = create_valid_transaction();
+        transaction.metadata = Some(TransactionMetadata::from([(
+            "destination_country".to_string(),
+            "ir".to_string(),
+        )]));
+
+        let result = validator.validate(&transaction);
+        assert_eq!(result.decision, Decision::Decline);
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::ProhibitedJurisdiction(_))));
+
+        // All checks still ran, but the declined transaction was not committed
+        assert!(result.skipped_checks.is_empty());
+        assert!(!result.committed);
+        assert_eq!(validator.get_stats().total_transactions_in_history, 0);
+    }
+
+    #[test]
+    fn test_sanctioned_jurisdiction_blocked_without_name_match() {
+        let mut validator = TransactionValidator::new();
+        let mut transaction = create_valid_transaction();
+        transaction.metadata = Some(TransactionMetadata::from([(
+            "destination_country".to_string(),
+            "cu".to_string(),
+        )]));
+
+        let result = validator.validate(&transaction);
+        assert_eq!(result.decision, Decision::Decline);
+        assert!(result
+            .reason_codes()
+            .contains(&"SANCTIONED_JURISDICTION".to_string()));
+        assert_eq!(
+            result.compliance_checks.get("COUNTRY_SANCTIONS"),
+            Some(&false)
+        );
+
+        let mut validator = TransactionValidator::with_config(ValidatorConfig {
+            block_sanctioned_jurisdictions: false,
+            ..Default::default()
+        });
+        assert!(validator.validate(&transaction).is_valid);
+    }
+
+    #[test]
+    fn test_clean_screening_passes() {
+        let mut validator = create_screening_validator(true);
+        let mut transaction = create_valid_transaction();
+        transaction.metadata = Some(TransactionMetadata::from([
+            ("beneficiary_name".to_string(), "Acme Payroll".to_string()),
+            ("country".to_string(), "US".to_string()),
+        ]));
+
+        let result = validator.validate(&transaction);
+        assert!(result.is_valid);
+        assert!(result.compliance_checks["SANCTIONS"]);
+        assert!(result.compliance_checks["GEOGRAPHY"]);
+    }
+
+    #[test]
+    fn test_check_budget_defers_deferrable_checks() {
+        let mut validator = TransactionValidator::with_config(ValidatorConfig {
+            check_budget_micros: Some(0),
+            ..Default::default()
+        });
+        let result = validator.validate(&create_valid_transaction());
+
+        assert!(result.is_valid);
+        assert_eq!(result.decision, Decision::Review);
+        assert!(result
+            .warning_codes()
+            .contains(&"CHECKS_DEFERRED".to_string()));
+        for check in [Check::Velocity, Check::FraudPatterns, Check::TimeRisk] {
+            assert!(
+                result.skipped_checks.contains(&check),
+                "{} not skipped",
+                check
+            );
+        }
+        // Mandatory checks always run
+        assert!(!result.skipped_checks.contains(&Check::Duplicate));
+        assert!(!result.skipped_checks.contains(&Check::Aml));
+        assert_eq!(validator.get_stats().total_processed, 1);
+        assert_eq!(validator.history().read().len(), 1);
+
+        let deferred = validator.take_deferred();
+        assert_eq!(deferred.len(), 1);
+        assert!(deferred[0].checks.contains(&Check::Velocity));
+        assert!(validator.take_deferred().is_empty());
+    }
+
+    #[test]
+    fn test_deferred_velocity_runs_in_follow_up() {
+        let mut validator = TransactionValidator::with_config(ValidatorConfig {
+            check_budget_micros: Some(0),
+            ..Default::default()
+        });
+        let limit = ValidatorConfig::default().max_transactions_per_window;
+        for i in 0..=limit {
+            let mut tx = create_valid_transaction();
+            tx.transaction_id = format!("TXN-DEFER-{}", i);
+            tx.amount = 10.0 + i as f64;
+            let result = validator.validate(&tx);
+            assert_eq!(result.decision, Decision::Review);
+            if i == 0 {
+                // The transaction does not count against itself
+                let first = validator.take_deferred();
+                assert!(validator.run_deferred(&first[0]).errors.is_empty());
+            }
+        }
+
+        let deferred = validator.take_deferred();
+        assert_eq!(deferred.len(), limit);
+        let last = validator.run_deferred(&deferred[limit - 1]);
+        assert_eq!(last.transaction_id, format!("TXN-DEFER-{}", limit));
+        assert!(last
+            .errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::VelocityViolation(_))));
+        assert_eq!(validator.history().read().len(), limit + 1);
+    }
+
+    #[test]
+    fn test_deferred_fraud_patterns_skip_the_transaction_itself() {
+        let mut validator = TransactionValidator::with_config(ValidatorConfig {
+            check_budget_micros: Some(0),
+            ..Default::default()
+        });
+        validator.set_fraud_detector(FraudDetector::new());
+        let tx = create_valid_transaction();
+        validator.validate(&tx);
+
+        let deferred = validator.take_deferred();
+        assert!(deferred[0].checks.contains(&Check::FraudPatterns));
+        let follow_up = validator.run_deferred(&deferred[0]);
+        assert!(
+            !follow_up
+                .warnings
+                .iter()
+                .any(|w| w.message.contains("Same amount") || w.message.contains("seconds of")),
+            "{:?}",
+            follow_up.warnings
+        );
+        assert_eq!(validator.history().read().len(), 1);
+    }
+
+    #[test]
+    fn test_execution_plan_follows_config() {
+        let validator = TransactionValidator::with_config(ValidatorConfig {
+            enable_duplicate_check: false,
+            enable_aml_check: false,
+            ..Default::default()
+        });
+        let checks = validator.execution_plan().checks();
+        assert!(!checks.contains(&Check::Duplicate));
+        assert!(!checks.contains(&Check::Aml));
+        assert_eq!(checks.len(), Check::ALL.len() - 2);
+
+        let result = TransactionValidator::new().validate(&create_valid_transaction());
+        assert!(result.skipped_checks.is_empty());
+    }
+
+    #[test]
+    fn test_granular_check_toggles() {
+        let mut validator = TransactionValidator::with_config(ValidatorConfig {
+            enable_velocity_check: false,
+            enable_account_format_check: false,
+            enable_round_amount_check: false,
+            enable_wire_review: false,
+            ..Default::default()
+        });
+        let checks = validator.execution_plan().checks();
+        assert!(!checks.contains(&Check::Velocity));
+        assert!(!checks.contains(&Check::AccountFormat));
+        assert!(checks.contains(&Check::FraudPatterns));
+
+        let mut transaction = create_valid_transaction();
+        transaction.transaction_type = TransactionType::WireTransfer;
+        transaction.amount = 20_000.0;
+        transaction.from_account = Some("not-an-account".to_string());
+        let result = validator.validate(&transaction);
+
+        assert!(result.is_valid);
+        assert_eq!(result.risk_breakdown.velocity_risk, 0);
+        assert!(!result
+            .warning_codes()
+            .contains(&"WIRE_TRANSFER".to_string()));
+        assert!(!result.warning_codes().contains(&"ROUND_AMOUNT".to_string()));
+    }
+
+    #[test]
+    fn test_config_version() {
+        let config = ValidatorConfig::default();
+        assert_eq!(config.version(), ValidatorConfig::default().version());
+
+        let changed = ValidatorConfig {
+            fraud_threshold: 80,
+            ..Default::default()
+        };
+        assert_ne!(config.version(), changed.version());
+    }
+
     #[test]
     fn test_json_export() {
         let mut validator = TransactionValidator::new();
@@ -883,4 +5570,14 @@ mod tests {
         assert!(json_str.contains("TXN-001"));
         assert!(json_str.contains("risk_breakdown"));
     }
+
+    #[test]
+    fn test_extreme_timestamp_does_not_panic() {
+        let mut validator = TransactionValidator::new();
+        let mut transaction = create_valid_transaction();
+        transaction.timestamp = DateTime::<Utc>::MIN_UTC;
+
+        let result = validator.validate(&transaction);
+        assert_eq!(result.transaction_id, "TXN-001");
+    }
 }
@@ -0,0 +1,274 @@
+//! Per-user limit profiles
+//!
+//! A limit profile replaces the global amount and velocity limits for the
+//! users assigned to it, so new customers and private-banking clients can
+//! be held to different ceilings by the same validator.
+//!
+//! Enrichment and institution assignments are trusted. A `limit_profile`
+//! metadata entry comes from whoever submitted the transaction, so it can
+//! only tighten: it is ignored unless every limit of the named profile is
+//! at or below the limits that would otherwise apply.
+
+use crate::{ConfigOverride, EnrichmentContext, Transaction, ValidatorConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Metadata key naming the user's limit profile
+pub const LIMIT_PROFILE_METADATA_KEY: &str = "limit_profile";
+
+/// Amount and velocity limits for one risk tier
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LimitProfile {
+    pub max_transaction_amount: f64,
+    pub max_transactions_per_window: usize,
+    pub max_amount_per_window: f64,
+}
+
+impl LimitProfile {
+    /// Tight limits for recently onboarded customers
+    pub fn new_customer() -> Self {
+        Self {
+            max_transaction_amount: 5_000.0,
+            max_transactions_per_window: 5,
+            max_amount_per_window: 10_000.0,
+        }
+    }
+
+    /// Limits matching the default configuration
+    pub fn standard() -> Self {
+        Self {
+            max_transaction_amount: 1_000_000.0,
+            max_transactions_per_window: 10,
+            max_amount_per_window: 100_000.0,
+        }
+    }
+
+    /// High limits for private-banking clients
+    pub fn private_banking() -> Self {
+        Self {
+            max_transaction_amount: 10_000_000.0,
+            max_transactions_per_window: 25,
+            max_amount_per_window: 5_000_000.0,
+        }
+    }
+
+    /// The limits a configuration applies, as a profile
+    pub fn of(config: &ValidatorConfig) -> Self {
+        Self {
+            max_transaction_amount: config.max_transaction_amount,
+            max_transactions_per_window: config.max_transactions_per_window,
+            max_amount_per_window: config.max_amount_per_window,
+        }
+    }
+
+    /// Whether no limit of this profile is looser than `other`'s
+    pub fn is_within(&self, other: &LimitProfile) -> bool {
+        self.max_transaction_amount <= other.max_transaction_amount
+            && self.max_transactions_per_window <= other.max_transactions_per_window
+            && self.max_amount_per_window <= other.max_amount_per_window
+    }
+
+    /// Express the profile as a config override layer
+    pub fn to_override(&self) -> ConfigOverride {
+        ConfigOverride {
+            max_transaction_amount: Some(self.max_transaction_amount),
+            max_transactions_per_window: Some(self.max_transactions_per_window),
+            max_amount_per_window: Some(self.max_amount_per_window),
+            ..Default::default()
+        }
+    }
+}
+
+/// Named profiles and per-user assignments
+#[derive(Debug, Clone)]
+pub struct LimitProfiles {
+    profiles: HashMap<String, LimitProfile>,
+    assignments: HashMap<String, String>,
+}
+
+impl LimitProfiles {
+    /// Registry holding the built-in profiles
+    pub fn new() -> Self {
+        let mut profiles = HashMap::new();
+        profiles.insert("new_customer".to_string(), LimitProfile::new_customer());
+        profiles.insert("standard".to_string(), LimitProfile::standard());
+        profiles.insert(
+            "private_banking".to_string(),
+            LimitProfile::private_banking(),
+        );
+        Self {
+            profiles,
+            assignments: HashMap::new(),
+        }
+    }
+
+    /// Add or replace a named profile
+    pub fn add_profile(&mut self, name: &str, profile: LimitProfile) {
+        self.profiles.insert(name.to_string(), profile);
+    }
+
+    /// Look up a profile by name
+    pub fn get(&self, name: &str) -> Option<&LimitProfile> {
+        self.profiles.get(name)
+    }
+
+    /// Every named profile, in no particular order
+    pub fn profiles(&self) -> impl Iterator<Item = (&str, &LimitProfile)> {
+        self.profiles
+            .iter()
+            .map(|(name, profile)| (name.as_str(), profile))
+    }
+
+    /// Every user assignment as (user, profile), in no particular order
+    pub fn assignments(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.assignments
+            .iter()
+            .map(|(user, profile)| (user.as_str(), profile.as_str()))
+    }
+
+    /// Assign a user to a named profile
+    pub fn assign(&mut self, user_id: &str, profile: &str) {
+        self.assignments
+            .insert(user_id.to_string(), profile.to_string());
+    }
+
+    /// Profile name for a transaction's user
+    ///
+    /// Enrichment wins over assignments. A metadata profile replaces either
+    /// only when it is within the limits they, or `base` without them,
+    /// would apply; an unknown metadata name is still returned so the
+    /// caller can warn about it.
+    pub fn profile_name<'a>(
+        &'a self,
+        transaction: &'a Transaction,
+        context: &'a EnrichmentContext,
+        base: &ValidatorConfig,
+    ) -> Option<&'a str> {
+        let trusted = context.limit_profile.as_deref().or_else(|| {
+            self.assignments
+                .get(&transaction.user_id)
+                .map(|s| s.as_str())
+        });
+        let Some(requested) = transaction
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get(LIMIT_PROFILE_METADATA_KEY))
+        else {
+            return trusted;
+        };
+        let Some(profile) = self.get(requested) else {
+            return trusted.or(Some(requested));
+        };
+        let current = match trusted.and_then(|name| self.get(name)) {
+            Some(assigned) => assigned.clone(),
+            None => LimitProfile::of(base),
+        };
+        if profile.is_within(&current) {
+            Some(requested)
+        } else {
+            trusted
+        }
+    }
+}
+
+impl Default for LimitProfiles {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+    use crate::{TransactionMetadata, TransactionValidator, ValidationError};
+
+    fn create_test_transaction(id: &str, user_id: &str, amount: f64) -> Transaction {
+        Transaction {
+            user_id: user_id.to_string(),
+            ..test_fixtures::create_test_transaction(id, amount)
+        }
+    }
+
+    fn has_amount_error(errors: &[ValidationError]) -> bool {
+        errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::InvalidAmount(_)))
+    }
+
+    #[test]
+    fn test_assigned_profile_replaces_global_limits() {
+        let mut validator = TransactionValidator::new();
+        validator.assign_limit_profile("USER-NEW", "new_customer");
+
+        let newcomer =
+            validator.validate(&create_test_transaction("TXN-LP-1", "USER-NEW", 8_000.0));
+        assert!(has_amount_error(&newcomer.errors));
+        assert_eq!(newcomer.limit_profile.as_deref(), Some("new_customer"));
+
+        let regular = validator.validate(&create_test_transaction("TXN-LP-2", "USER-OLD", 8_000.0));
+        assert!(!has_amount_error(&regular.errors));
+        assert_eq!(regular.limit_profile, None);
+    }
+
+    #[test]
+    fn test_enrichment_profile_wins_over_assignment() {
+        let mut validator = TransactionValidator::new();
+        validator.assign_limit_profile("USER-001", "new_customer");
+
+        let context = EnrichmentContext {
+            limit_profile: Some("private_banking".to_string()),
+            ..Default::default()
+        };
+        let result = validator.validate_with_context(
+            &create_test_transaction("TXN-LP-3", "USER-001", 2_000_000.0),
+            context,
+        );
+        assert!(!has_amount_error(&result.errors));
+        assert_eq!(result.effective_config.max_transaction_amount, 10_000_000.0);
+    }
+
+    #[test]
+    fn test_metadata_profile_can_only_tighten() {
+        let mut validator = TransactionValidator::new();
+        validator.assign_limit_profile("USER-RETAIL", "new_customer");
+        let tagged = |id: &str, user: &str, profile: &str| {
+            let mut transaction = create_test_transaction(id, user, 8_000.0);
+            transaction.metadata = Some(TransactionMetadata::from([(
+                LIMIT_PROFILE_METADATA_KEY.to_string(),
+                profile.to_string(),
+            )]));
+            transaction
+        };
+
+        let escalated = validator.validate(&tagged("TXN-LP-5", "USER-RETAIL", "private_banking"));
+        assert!(has_amount_error(&escalated.errors));
+        assert_eq!(escalated.limit_profile.as_deref(), Some("new_customer"));
+
+        // Unassigned users cannot lift the global limits either
+        let unassigned = validator.validate(&tagged("TXN-LP-6", "USER-OTHER", "private_banking"));
+        assert_eq!(unassigned.limit_profile, None);
+        assert_eq!(
+            unassigned.effective_config.max_transaction_amount,
+            1_000_000.0
+        );
+
+        let tightened = validator.validate(&tagged("TXN-LP-7", "USER-OTHER", "new_customer"));
+        assert!(has_amount_error(&tightened.errors));
+        assert_eq!(tightened.limit_profile.as_deref(), Some("new_customer"));
+    }
+
+    #[test]
+    fn test_unknown_profile_warns_and_uses_globals() {
+        let mut validator = TransactionValidator::new();
+        let mut transaction = create_test_transaction("TXN-LP-4", "USER-001", 100.0);
+        transaction.metadata = Some(TransactionMetadata::from([(
+            LIMIT_PROFILE_METADATA_KEY.to_string(),
+            "gold".to_string(),
+        )]));
+
+        let result = validator.validate(&transaction);
+        assert!(result.warnings.iter().any(|w| w.message.contains("gold")));
+        assert_eq!(result.limit_profile, None);
+    }
+}
@@ -0,0 +1,221 @@
+//! Typed transaction metadata
+//!
+//! Checks across the crate read the same handful of metadata entries —
+//! countries, counterparty names, device and IP. [`TransactionMetadata`]
+//! gives those entries typed fields and keeps everything else in `custom`.
+//!
+//! The wire format is unchanged: metadata serializes as a flat JSON object
+//! of string values, and existing payloads deserialize with known keys moved
+//! into their fields.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Known metadata keys, in serialization order of the typed fields
+pub const KNOWN_METADATA_KEYS: [&str; 9] = [
+    "country",
+    "destination_country",
+    "ip_address",
+    "device_id",
+    "channel",
+    "mcc",
+    "originator_name",
+    "beneficiary_name",
+    "purpose",
+];
+
+/// Metadata key carrying the upstream request's correlation ID
+pub const CORRELATION_ID_METADATA_KEY: &str = "correlation_id";
+
+/// Metadata key naming the transaction a refund or reversal undoes
+pub const PARENT_TRANSACTION_METADATA_KEY: &str = "parent_transaction_id";
+
+/// Metadata attached to a transaction
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "HashMap<String, String>", into = "BTreeMap<String, String>")]
+pub struct TransactionMetadata {
+    /// Origin country (ISO 3166 alpha-2)
+    pub country: Option<String>,
+    /// Destination country (ISO 3166 alpha-2)
+    pub destination_country: Option<String>,
+    /// Originating IP address
+    pub ip_address: Option<String>,
+    /// Originating device ID
+    pub device_id: Option<String>,
+    /// Channel the transaction was submitted through
+    pub channel: Option<String>,
+    /// Card merchant category code
+    pub mcc: Option<String>,
+    /// Name of the paying party, screened against sanctions lists
+    pub originator_name: Option<String>,
+    /// Name of the receiving party, screened against sanctions lists
+    pub beneficiary_name: Option<String>,
+    /// Stated purpose of payment
+    pub purpose: Option<String>,
+    /// Every other entry
+    pub custom: HashMap<String, String>,
+}
+
+impl TransactionMetadata {
+    /// Empty metadata
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn field(&self, key: &str) -> Option<&Option<String>> {
+        Some(match key {
+            "country" => &self.country,
+            "destination_country" => &self.destination_country,
+            "ip_address" => &self.ip_address,
+            "device_id" => &self.device_id,
+            "channel" => &self.channel,
+            "mcc" => &self.mcc,
+            "originator_name" => &self.originator_name,
+            "beneficiary_name" => &self.beneficiary_name,
+            "purpose" => &self.purpose,
+            _ => return None,
+        })
+    }
+
+    fn field_mut(&mut self, key: &str) -> Option<&mut Option<String>> {
+        Some(match key {
+            "country" => &mut self.country,
+            "destination_country" => &mut self.destination_country,
+            "ip_address" => &mut self.ip_address,
+            "device_id" => &mut self.device_id,
+            "channel" => &mut self.channel,
+            "mcc" => &mut self.mcc,
+            "originator_name" => &mut self.originator_name,
+            "beneficiary_name" => &mut self.beneficiary_name,
+            "purpose" => &mut self.purpose,
+            _ => return None,
+        })
+    }
+
+    /// Value under a metadata key, typed field or custom
+    pub fn get(&self, key: &str) -> Option<&str> {
+        match self.field(key) {
+            Some(field) => field.as_deref(),
+            None => self.custom.get(key).map(String::as_str),
+        }
+    }
+
+    /// Set the value under a metadata key, returning the previous value
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) -> Option<String> {
+        let key = key.into();
+        match self.field_mut(&key) {
+            Some(field) => field.replace(value.into()),
+            None => self.custom.insert(key, value.into()),
+        }
+    }
+
+    /// Remove the value under a metadata key
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        match self.field_mut(key) {
+            Some(field) => field.take(),
+            None => self.custom.remove(key),
+        }
+    }
+
+    /// Origin and destination countries, where set
+    pub fn countries(&self) -> impl Iterator<Item = &str> {
+        [&self.country, &self.destination_country]
+            .into_iter()
+            .filter_map(|c| c.as_deref())
+    }
+
+    /// Originator and beneficiary names, where set
+    pub fn counterparty_names(&self) -> impl Iterator<Item = &str> {
+        [&self.originator_name, &self.beneficiary_name]
+            .into_iter()
+            .filter_map(|n| n.as_deref())
+    }
+
+    /// All entries as key-value pairs, typed fields first
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        KNOWN_METADATA_KEYS
+            .iter()
+            .filter_map(|key| Some((*key, self.get(key)?)))
+            .chain(self.custom.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+    }
+
+    /// Number of entries
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Check whether no entry is set
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl From<HashMap<String, String>> for TransactionMetadata {
+    fn from(entries: HashMap<String, String>) -> Self {
+        entries.into_iter().collect()
+    }
+}
+
+impl<const N: usize> From<[(String, String); N]> for TransactionMetadata {
+    fn from(entries: [(String, String); N]) -> Self {
+        entries.into_iter().collect()
+    }
+}
+
+impl FromIterator<(String, String)> for TransactionMetadata {
+    fn from_iter<I: IntoIterator<Item = (String, String)>>(entries: I) -> Self {
+        let mut metadata = TransactionMetadata::new();
+        for (key, value) in entries {
+            metadata.insert(key, value);
+        }
+        metadata
+    }
+}
+
+impl From<TransactionMetadata> for BTreeMap<String, String> {
+    fn from(metadata: TransactionMetadata) -> Self {
+        metadata
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_keys_map_to_fields() {
+        let mut metadata = TransactionMetadata::from([
+            ("country".to_string(), "US".to_string()),
+            ("beneficiary_name".to_string(), "ACME TRADING".to_string()),
+            ("invoice".to_string(), "INV-7".to_string()),
+        ]);
+        assert_eq!(metadata.country.as_deref(), Some("US"));
+        assert_eq!(metadata.get("beneficiary_name"), Some("ACME TRADING"));
+        assert_eq!(metadata.get("invoice"), Some("INV-7"));
+        assert_eq!(metadata.len(), 3);
+
+        assert_eq!(metadata.insert("country", "GB"), Some("US".to_string()));
+        assert_eq!(metadata.countries().collect::<Vec<_>>(), vec!["GB"]);
+        assert_eq!(metadata.remove("invoice"), Some("INV-7".to_string()));
+        assert!(metadata.custom.is_empty());
+    }
+
+    #[test]
+    fn test_wire_format_is_a_flat_string_map() {
+        let json = r#"{"country":"US","device_id":"DEV-1","invoice":"INV-7"}"#;
+        let metadata: TransactionMetadata = serde_json::from_str(json).unwrap();
+        assert_eq!(metadata.device_id.as_deref(), Some("DEV-1"));
+        assert_eq!(
+            metadata.custom.get("invoice").map(String::as_str),
+            Some("INV-7")
+        );
+
+        let round_trip: HashMap<String, String> =
+            serde_json::from_str(&serde_json::to_string(&metadata).unwrap()).unwrap();
+        assert_eq!(round_trip.len(), 3);
+        assert_eq!(round_trip["country"], "US");
+    }
+}
@@ -0,0 +1,213 @@
+//! Model scoring (`ml` feature)
+//!
+//! A [`ModelScorer`] turns an engineered feature vector into a fraud
+//! probability. The validator builds the vector after the rule-based checks
+//! have run, so the rules' component scores are features too, and records
+//! the model's output as `RiskBreakdown::model_risk` (probability × 100),
+//! weighted by `RiskWeights::model`. A scorer failure leaves the model risk
+//! at 0 and marks the result degraded.
+//!
+//! [`TensorModelScorer`] is an adapter from the feature vector to any
+//! inference session that takes a `[1, n]` `f32` tensor. The crate links no
+//! model runtime and cannot load a model file itself; implement
+//! [`TensorSession`] over the application's session (for example an ONNX
+//! Runtime `ort::Session`) so the model shares its execution providers and
+//! thread pool.
+//!
+//! A scorer that loads ONNX models itself, on the `ort` crate, is not part
+//! of this feature. It is left for a follow-up change that adds `ort` as an
+//! optional dependency.
+
+use crate::{RiskBreakdown, Transaction};
+use chrono::{Datelike, Timelike};
+use thiserror::Error;
+
+/// Names of the features in [`model_features`], in order
+pub const MODEL_FEATURES: [&str; 12] = [
+    "log_amount",
+    "hour_of_day",
+    "is_weekend",
+    "is_cross_border",
+    "amount_risk",
+    "velocity_risk",
+    "pattern_risk",
+    "time_risk",
+    "geo_risk",
+    "network_risk",
+    "recent_transaction_count",
+    "log_recent_amount",
+];
+
+/// Model scoring errors
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ModelError {
+    #[error("Model expects {expected} features, got {actual}")]
+    FeatureCount { expected: usize, actual: usize },
+
+    #[error("Inference failed: {0}")]
+    Inference(String),
+
+    #[error("Unusable model output: {0}")]
+    Output(String),
+}
+
+/// Fraud model consulted after the rule-based checks
+pub trait ModelScorer: Send + Sync {
+    /// Name reported in degradation reasons
+    fn name(&self) -> &str;
+
+    /// Fraud probability in `[0, 1]` for one feature vector
+    fn score(&self, features: &[f32]) -> Result<f32, ModelError>;
+}
+
+/// Engineered features for one transaction, in [`MODEL_FEATURES`] order
+///
+/// `recent_count` and `recent_amount` describe the user's transactions in
+/// the velocity window. Risk components are scaled to `[0, 1]`.
+pub fn model_features(
+    transaction: &Transaction,
+    breakdown: &RiskBreakdown,
+    recent_count: usize,
+    recent_amount: f64,
+) -> Vec<f32> {
+    let metadata = transaction.metadata.as_ref();
+    let cross_border = metadata
+        .and_then(|m| m.get("cross_border"))
+        .is_some_and(|v| v == "true");
+    let risk = |score: u8| score as f32 / 100.0;
+    vec![
+        transaction.amount.max(0.0).ln_1p() as f32,
+        transaction.timestamp.hour() as f32 / 23.0,
+        (transaction.timestamp.weekday().num_days_from_monday() >= 5) as u8 as f32,
+        cross_border as u8 as f32,
+        risk(breakdown.amount_risk),
+        risk(breakdown.velocity_risk),
+        risk(breakdown.pattern_risk),
+        risk(breakdown.time_risk),
+        risk(breakdown.geo_risk),
+        risk(breakdown.network_risk),
+        recent_count as f32,
+        recent_amount.max(0.0).ln_1p() as f32,
+    ]
+}
+
+/// Inference session of a model, backed by the application's runtime
+pub trait TensorSession: Send + Sync {
+    /// Run the model on one `f32` input tensor, returning the flattened
+    /// output tensor selected when the session was created
+    fn run(&self, input_name: &str, shape: &[i64], data: &[f32]) -> Result<Vec<f32>, String>;
+}
+
+/// [`ModelScorer`] adapter over a session taking a `[1, n]` feature tensor
+pub struct TensorModelScorer {
+    name: String,
+    session: Box<dyn TensorSession>,
+    input_name: String,
+    feature_count: usize,
+    /// Position of the fraud probability in the output, e.g. 1 for a
+    /// two-class `[p_legit, p_fraud]` output
+    output_index: usize,
+}
+
+impl TensorModelScorer {
+    /// Scorer for a model reading [`MODEL_FEATURES`] from `input_name`
+    pub fn new(name: &str, session: Box<dyn TensorSession>, input_name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            session,
+            input_name: input_name.to_string(),
+            feature_count: MODEL_FEATURES.len(),
+            output_index: 0,
+        }
+    }
+
+    /// Read the probability from this position of the output
+    pub fn with_output_index(mut self, index: usize) -> Self {
+        self.output_index = index;
+        self
+    }
+}
+
+impl ModelScorer for TensorModelScorer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn score(&self, features: &[f32]) -> Result<f32, ModelError> {
+        if features.len() != self.feature_count {
+            return Err(ModelError::FeatureCount {
+                expected: self.feature_count,
+                actual: features.len(),
+            });
+        }
+        let output = self
+            .session
+            .run(&self.input_name, &[1, features.len() as i64], features)
+            .map_err(ModelError::Inference)?;
+        let probability = output.get(self.output_index).copied().ok_or_else(|| {
+            ModelError::Output(format!(
+                "no value at index {} of {}",
+                self.output_index,
+                output.len()
+            ))
+        })?;
+        if !probability.is_finite() {
+            return Err(ModelError::Output(format!("{} is not finite", probability)));
+        }
+        Ok(probability.clamp(0.0, 1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{DegradationReason, TransactionValidator};
+
+    /// Logistic regression on log_amount, returning `[p_legit, p_fraud]`
+    struct LogisticSession;
+
+    impl TensorSession for LogisticSession {
+        fn run(&self, input_name: &str, shape: &[i64], data: &[f32]) -> Result<Vec<f32>, String> {
+            if input_name != "features" || shape != [1, MODEL_FEATURES.len() as i64] {
+                return Err(format!("unexpected input {} {:?}", input_name, shape));
+            }
+            let p = 1.0 / (1.0 + (-(data[0] - 8.0)).exp());
+            Ok(vec![1.0 - p, p])
+        }
+    }
+
+    #[test]
+    fn test_model_score_blends_into_total() {
+        let scorer = TensorModelScorer::new("logistic", Box::new(LogisticSession), "features")
+            .with_output_index(1);
+        let mut validator = TransactionValidator::new();
+        validator.set_model_scorer(Box::new(scorer));
+
+        let small = validator.validate(&create_test_transaction("TXN-ML-1", 5.0));
+        assert_eq!(small.risk_breakdown.model_risk, 0);
+        let large = validator.validate(&create_test_transaction("TXN-ML-2", 50_000.0));
+        assert!(large.risk_breakdown.model_risk > 90);
+        assert!(large.fraud_score >= large.risk_breakdown.model_risk);
+    }
+
+    #[test]
+    fn test_session_failure_degrades() {
+        let scorer = TensorModelScorer::new("logistic", Box::new(LogisticSession), "wrong_input");
+        assert!(matches!(
+            scorer.score(&[0.0; 3]),
+            Err(ModelError::FeatureCount { expected: 12, .. })
+        ));
+        let mut validator = TransactionValidator::new();
+        validator.set_model_scorer(Box::new(scorer));
+        let result = validator.validate(&create_test_transaction("TXN-ML-3", 100.0));
+        assert!(result.is_valid);
+        assert_eq!(result.risk_breakdown.model_risk, 0);
+        assert_eq!(
+            result.degraded,
+            vec![DegradationReason::ProviderFailed {
+                provider: "logistic".to_string()
+            }]
+        );
+    }
+}
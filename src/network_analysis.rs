@@ -2,12 +2,13 @@
 //!
 //! Provides graph-based analysis for detecting suspicious transaction patterns.
 
+use crate::clock::{system_clock, SharedClock};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
 /// Suspicious pattern types
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum SuspiciousPattern {
     /// Money moving in a circle back to origin
     CircularFlow,
@@ -60,7 +61,6 @@ impl TransactionNode {
         self.incoming_accounts.len() >= 5 && self.outgoing_accounts.len() <= 2
     }
 
-    #[allow(dead_code)]
     fn is_distributor(&self) -> bool {
         // Few incoming, many outgoing
         self.incoming_accounts.len() <= 2 && self.outgoing_accounts.len() >= 5
@@ -74,6 +74,18 @@ impl TransactionNode {
         let ratio = self.total_outflow / self.total_inflow;
         (0.9..=1.1).contains(&ratio) && self.transaction_count >= 4
     }
+
+    /// Account-level patterns the node currently shows
+    fn classifications(&self) -> Vec<SuspiciousPattern> {
+        [
+            (self.is_funnel(), SuspiciousPattern::FunnelAccount),
+            (self.is_distributor(), SuspiciousPattern::Distributor),
+            (self.is_pass_through(), SuspiciousPattern::PassThrough),
+        ]
+        .into_iter()
+        .filter_map(|(matched, pattern)| matched.then_some(pattern))
+        .collect()
+    }
 }
 
 /// Edge in the transaction graph
@@ -88,6 +100,10 @@ struct TransactionEdge {
 }
 
 /// Transaction graph for network analysis
+///
+/// A clone is a snapshot: keep one from each network review and pass it to
+/// [`TransactionGraph::diff`] at the next.
+#[derive(Debug, Clone)]
 pub struct TransactionGraph {
     nodes: HashMap<String, TransactionNode>,
     edges: HashMap<(String, String), TransactionEdge>,
@@ -331,6 +347,97 @@ impl TransactionGraph {
             total_amount,
         }
     }
+
+    /// What changed since an older snapshot of this graph
+    ///
+    /// Lists accounts and edges absent from the snapshot, accounts that
+    /// became or stopped being funnel, distributor, or pass-through
+    /// accounts, and per-account volume deltas, largest first.
+    pub fn diff(&self, older: &TransactionGraph) -> GraphDiff {
+        let mut new_accounts: Vec<String> = self
+            .nodes
+            .keys()
+            .filter(|id| !older.nodes.contains_key(*id))
+            .cloned()
+            .collect();
+        new_accounts.sort();
+
+        let mut new_edges: Vec<NewEdge> = self
+            .edges
+            .iter()
+            .filter(|(key, _)| !older.edges.contains_key(*key))
+            .map(|(_, edge)| NewEdge {
+                from_account: edge.from_account.clone(),
+                to_account: edge.to_account.clone(),
+                total_amount: edge.total_amount,
+                transaction_count: edge.transaction_count,
+            })
+            .collect();
+        new_edges.sort_by(|a, b| {
+            (&a.from_account, &a.to_account).cmp(&(&b.from_account, &b.to_account))
+        });
+
+        let mut classification_changes = Vec::new();
+        let mut volume_deltas = Vec::new();
+        for (id, node) in &self.nodes {
+            let before = older.nodes.get(id);
+            let was = before
+                .map(TransactionNode::classifications)
+                .unwrap_or_default();
+            let is = node.classifications();
+            let gained: Vec<SuspiciousPattern> =
+                is.iter().filter(|p| !was.contains(p)).cloned().collect();
+            let lost: Vec<SuspiciousPattern> =
+                was.iter().filter(|p| !is.contains(p)).cloned().collect();
+            if !gained.is_empty() || !lost.is_empty() {
+                classification_changes.push(ClassificationChange {
+                    account_id: id.clone(),
+                    gained,
+                    lost,
+                });
+            }
+
+            let delta = VolumeDelta {
+                account_id: id.clone(),
+                inflow_delta: node.total_inflow - before.map_or(0.0, |n| n.total_inflow),
+                outflow_delta: node.total_outflow - before.map_or(0.0, |n| n.total_outflow),
+                transaction_delta: node.transaction_count as i64
+                    - before.map_or(0, |n| n.transaction_count as i64),
+            };
+            if delta.transaction_delta != 0
+                || delta.inflow_delta != 0.0
+                || delta.outflow_delta != 0.0
+            {
+                volume_deltas.push(delta);
+            }
+        }
+        // Accounts dropped from the graph lose whatever they were classified as
+        for (id, node) in &older.nodes {
+            if !self.nodes.contains_key(id) && !node.classifications().is_empty() {
+                classification_changes.push(ClassificationChange {
+                    account_id: id.clone(),
+                    gained: Vec::new(),
+                    lost: node.classifications(),
+                });
+            }
+        }
+        classification_changes.sort_by(|a, b| a.account_id.cmp(&b.account_id));
+        volume_deltas.sort_by(|a, b| {
+            b.volume()
+                .total_cmp(&a.volume())
+                .then_with(|| a.account_id.cmp(&b.account_id))
+        });
+
+        let (now, then) = (self.get_stats(), older.get_stats());
+        GraphDiff {
+            new_accounts,
+            new_edges,
+            classification_changes,
+            volume_deltas,
+            transaction_delta: now.total_transactions as i64 - then.total_transactions as i64,
+            amount_delta: now.total_amount - then.total_amount,
+        }
+    }
 }
 
 impl Default for TransactionGraph {
@@ -342,6 +449,7 @@ impl Default for TransactionGraph {
 /// Network analyzer combining multiple detection methods
 pub struct NetworkAnalyzer {
     graph: TransactionGraph,
+    clock: SharedClock,
 }
 
 impl NetworkAnalyzer {
@@ -349,30 +457,62 @@ impl NetworkAnalyzer {
     pub fn new() -> Self {
         Self {
             graph: TransactionGraph::new(),
+            clock: system_clock(),
         }
     }
 
+    /// Stamp reports with times from `clock`
+    pub fn set_clock(&mut self, clock: SharedClock) {
+        self.clock = clock;
+    }
+
     /// Add transaction to the analyzer
     pub fn add_transaction(&mut self, from: &str, to: &str, amount: f64, timestamp: DateTime<Utc>) {
         self.graph.add_transaction(from, to, amount, timestamp);
     }
 
     /// Run all analysis methods
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "network_analysis",
+            skip_all,
+            fields(
+                node_count = self.graph.nodes.len(),
+                pattern_count = tracing::field::Empty,
+            )
+        )
+    )]
     pub fn analyze_all(&self) -> NetworkAnalysisReport {
-        NetworkAnalysisReport {
+        let report = NetworkAnalysisReport {
             circular_flows: self.graph.detect_circular_flows(5),
             structuring: self.graph.detect_structuring(),
             funnel_accounts: self.graph.detect_funnel_accounts(),
             pass_through: self.graph.detect_pass_through(),
             graph_stats: self.graph.get_stats(),
-            analysis_time: Utc::now(),
-        }
+            analysis_time: self.clock.now(),
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("pattern_count", report.suspicious_pattern_count());
+
+        report
     }
 
     /// Get account statistics
     pub fn get_account_stats(&self, account_id: &str) -> Option<AccountStats> {
         self.graph.get_account_stats(account_id)
     }
+
+    /// The graph built so far
+    pub fn graph(&self) -> &TransactionGraph {
+        &self.graph
+    }
+
+    /// Copy of the graph to diff against at the next review
+    pub fn snapshot(&self) -> TransactionGraph {
+        self.graph.clone()
+    }
 }
 
 impl Default for NetworkAnalyzer {
@@ -440,6 +580,73 @@ pub struct GraphStats {
     pub total_amount: f64,
 }
 
+/// Edge absent from the older snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewEdge {
+    pub from_account: String,
+    pub to_account: String,
+    pub total_amount: f64,
+    pub transaction_count: usize,
+}
+
+/// Account-level patterns an account took on or shed between snapshots
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassificationChange {
+    pub account_id: String,
+    pub gained: Vec<SuspiciousPattern>,
+    pub lost: Vec<SuspiciousPattern>,
+}
+
+/// Change in an account's volume between snapshots
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeDelta {
+    pub account_id: String,
+    pub inflow_delta: f64,
+    pub outflow_delta: f64,
+    pub transaction_delta: i64,
+}
+
+impl VolumeDelta {
+    /// Absolute inflow plus outflow change
+    pub fn volume(&self) -> f64 {
+        self.inflow_delta.abs() + self.outflow_delta.abs()
+    }
+}
+
+/// Changes between two snapshots of a transaction graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphDiff {
+    /// Accounts first seen since the older snapshot, sorted
+    pub new_accounts: Vec<String>,
+    /// Sorted by sender, then recipient
+    pub new_edges: Vec<NewEdge>,
+    /// Sorted by account
+    pub classification_changes: Vec<ClassificationChange>,
+    /// Accounts with new activity, largest volume change first
+    pub volume_deltas: Vec<VolumeDelta>,
+    pub transaction_delta: i64,
+    pub amount_delta: f64,
+}
+
+impl GraphDiff {
+    /// Whether nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.new_accounts.is_empty()
+            && self.new_edges.is_empty()
+            && self.classification_changes.is_empty()
+            && self.volume_deltas.is_empty()
+    }
+
+    /// Accounts that took on `pattern` since the older snapshot
+    pub fn newly_classified(&self, pattern: &SuspiciousPattern) -> Vec<&str> {
+        self.classification_changes
+            .iter()
+            .filter(|c| c.gained.contains(pattern))
+            .map(|c| c.account_id.as_str())
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkAnalysisReport {
     pub circular_flows: Vec<CircularFlowResult>,
@@ -466,6 +673,77 @@ impl NetworkAnalysisReport {
             + self.funnel_accounts.len()
             + self.pass_through.len()
     }
+
+    /// Check if an account takes part in any suspicious pattern
+    pub fn involves_account(&self, account_id: &str) -> bool {
+        self.circular_flows
+            .iter()
+            .any(|c| c.accounts.iter().any(|a| a == account_id))
+            || self.structuring.iter().any(|s| s.account_id == account_id)
+            || self
+                .funnel_accounts
+                .iter()
+                .any(|f| f.account_id == account_id)
+            || self.pass_through.iter().any(|p| p.account_id == account_id)
+    }
+
+    /// Every (account, pattern) pair in the report
+    pub fn pattern_accounts(&self) -> Vec<(String, SuspiciousPattern)> {
+        let mut pairs: Vec<(String, SuspiciousPattern)> = self
+            .circular_flows
+            .iter()
+            .flat_map(|c| c.accounts.iter().map(|a| (a.clone(), c.pattern.clone())))
+            .collect();
+        pairs.extend(
+            self.structuring
+                .iter()
+                .map(|s| (s.account_id.clone(), s.pattern.clone())),
+        );
+        pairs.extend(
+            self.funnel_accounts
+                .iter()
+                .map(|f| (f.account_id.clone(), f.pattern.clone())),
+        );
+        pairs.extend(
+            self.pass_through
+                .iter()
+                .map(|p| (p.account_id.clone(), p.pattern.clone())),
+        );
+        pairs
+    }
+
+    /// Keep only the patterns that involve one of `accounts`
+    pub fn for_accounts(&self, accounts: &[&str]) -> NetworkAnalysisReport {
+        let involved = |id: &String| accounts.contains(&id.as_str());
+        NetworkAnalysisReport {
+            circular_flows: self
+                .circular_flows
+                .iter()
+                .filter(|c| c.accounts.iter().any(involved))
+                .cloned()
+                .collect(),
+            structuring: self
+                .structuring
+                .iter()
+                .filter(|s| involved(&s.account_id))
+                .cloned()
+                .collect(),
+            funnel_accounts: self
+                .funnel_accounts
+                .iter()
+                .filter(|f| involved(&f.account_id))
+                .cloned()
+                .collect(),
+            pass_through: self
+                .pass_through
+                .iter()
+                .filter(|p| involved(&p.account_id))
+                .cloned()
+                .collect(),
+            graph_stats: self.graph_stats.clone(),
+            analysis_time: self.analysis_time,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -560,6 +838,39 @@ mod tests {
         assert!(report.graph_stats.total_transactions >= 3);
     }
 
+    #[test]
+    fn test_graph_diff() {
+        let mut analyzer = NetworkAnalyzer::new();
+        let now = Utc::now();
+        for i in 0..4 {
+            analyzer.add_transaction(&format!("SOURCE{}", i), "FUNNEL", 1000.0, now);
+        }
+        analyzer.add_transaction("A", "B", 500.0, now);
+        let review = analyzer.snapshot();
+        assert!(analyzer.graph().diff(&review).is_empty());
+
+        analyzer.add_transaction("SOURCE4", "FUNNEL", 1000.0, now);
+        analyzer.add_transaction("A", "B", 250.0, now);
+        let diff = analyzer.graph().diff(&review);
+        assert_eq!(diff.new_accounts, vec!["SOURCE4"]);
+        assert_eq!(diff.new_edges.len(), 1);
+        assert_eq!(diff.new_edges[0].from_account, "SOURCE4");
+        assert_eq!(
+            diff.newly_classified(&SuspiciousPattern::FunnelAccount),
+            vec!["FUNNEL"]
+        );
+        assert_eq!(diff.classification_changes.len(), 1);
+        let accounts: Vec<&str> = diff
+            .volume_deltas
+            .iter()
+            .map(|d| d.account_id.as_str())
+            .collect();
+        assert_eq!(accounts, vec!["FUNNEL", "SOURCE4", "A", "B"]);
+        assert_eq!(diff.volume_deltas[2].outflow_delta, 250.0);
+        assert_eq!(diff.transaction_delta, 2);
+        assert_eq!(diff.amount_delta, 1250.0);
+    }
+
     #[test]
     fn test_graph_stats() {
         let mut graph = TransactionGraph::new();
@@ -0,0 +1,333 @@
+//! Metadata normalization
+//!
+//! Upstream systems spell metadata inconsistently: `Country` vs `country`,
+//! `us` vs `US`, `cross_border: "Yes"` vs `"true"`. Checks compare exact
+//! strings, so [`MetadataNormalizer`] canonicalizes a transaction's metadata
+//! before any check runs: keys are trimmed, lowercased, and snake_cased,
+//! values are trimmed, country codes are uppercased, and boolean flags are
+//! rewritten to `true`/`false`. It also fills derived fields (origin country
+//! from the IP address, `cross_border` from the two countries) and reports
+//! keys it does not recognize.
+//!
+//! The validator runs the normalizer after pre-hooks, on the same copy of
+//! the transaction, and turns the report into warnings.
+
+use crate::config_overrides::SEGMENT_METADATA_KEY;
+use crate::enrichment::ACCOUNT_OPENED_METADATA_KEY;
+use crate::fraud_patterns::PRODUCT_METADATA_KEY;
+use crate::limit_profiles::LIMIT_PROFILE_METADATA_KEY;
+use crate::metadata::{
+    CORRELATION_ID_METADATA_KEY, KNOWN_METADATA_KEYS, PARENT_TRANSACTION_METADATA_KEY,
+};
+use crate::routing::{BENEFICIARY_ROUTING_NUMBER_METADATA_KEY, ROUTING_NUMBER_METADATA_KEY};
+use crate::typologies::CUSTOMER_AGE_METADATA_KEY;
+use crate::{Transaction, TransactionMetadata, Warning, WarningSeverity};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Resolves an IP address to an ISO 3166 alpha-2 country code
+pub trait IpCountryResolver: Send + Sync {
+    fn country(&self, ip_address: &str) -> Option<String>;
+}
+
+impl<F> IpCountryResolver for F
+where
+    F: Fn(&str) -> Option<String> + Send + Sync,
+{
+    fn country(&self, ip_address: &str) -> Option<String> {
+        self(ip_address)
+    }
+}
+
+/// What one normalization pass changed and found
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NormalizationReport {
+    /// Keys rewritten to canonical form, as (original, canonical)
+    pub renamed_keys: Vec<(String, String)>,
+    /// Canonical keys whose value was rewritten
+    pub changed_values: Vec<String>,
+    /// Keys dropped because their value was blank or a duplicate
+    pub dropped_keys: Vec<String>,
+    /// Boolean keys whose value could not be parsed
+    pub invalid_values: Vec<String>,
+    /// Canonical keys the normalizer does not recognize
+    pub unknown_keys: Vec<String>,
+    /// Keys filled in from other fields
+    pub derived_keys: Vec<String>,
+}
+
+impl NormalizationReport {
+    /// Check whether the metadata was already canonical and fully known
+    pub fn is_clean(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Warnings for findings worth surfacing on the validation result
+    pub fn warnings(&self) -> Vec<Warning> {
+        let mut warnings = Vec::new();
+        if !self.unknown_keys.is_empty() {
+            warnings.push(Warning::new(
+                "METADATA_UNKNOWN_KEYS",
+                WarningSeverity::Info,
+                format!("Unknown metadata keys: {}", self.unknown_keys.join(", ")),
+            ));
+        }
+        if !self.invalid_values.is_empty() {
+            warnings.push(Warning::new(
+                "METADATA_INVALID_VALUE",
+                WarningSeverity::Low,
+                format!(
+                    "Metadata values could not be parsed: {}",
+                    self.invalid_values.join(", ")
+                ),
+            ));
+        }
+        warnings
+    }
+}
+
+/// Canonical form of a metadata key: trimmed, lowercase, snake_case
+pub fn canonical_key(key: &str) -> String {
+    key.trim()
+        .chars()
+        .map(|c| match c {
+            '-' | ' ' | '.' => '_',
+            c => c.to_ascii_lowercase(),
+        })
+        .collect()
+}
+
+/// Parse the common spellings of a boolean flag
+fn parse_flag(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "yes" | "y" | "1" | "on" => Some(true),
+        "false" | "no" | "n" | "0" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Canonicalizes transaction metadata before validation
+pub struct MetadataNormalizer {
+    known_keys: BTreeSet<String>,
+    country_keys: BTreeSet<String>,
+    boolean_keys: BTreeSet<String>,
+    ip_resolver: Option<Box<dyn IpCountryResolver>>,
+}
+
+impl Default for MetadataNormalizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetadataNormalizer {
+    /// Normalizer that knows every metadata key the crate reads
+    pub fn new() -> Self {
+        let known_keys = KNOWN_METADATA_KEYS
+            .into_iter()
+            .chain([
+                "cross_border",
+                "region",
+                "destination_region",
+                SEGMENT_METADATA_KEY,
+                LIMIT_PROFILE_METADATA_KEY,
+                PRODUCT_METADATA_KEY,
+                ACCOUNT_OPENED_METADATA_KEY,
+                CORRELATION_ID_METADATA_KEY,
+                PARENT_TRANSACTION_METADATA_KEY,
+                ROUTING_NUMBER_METADATA_KEY,
+                BENEFICIARY_ROUTING_NUMBER_METADATA_KEY,
+                CUSTOMER_AGE_METADATA_KEY,
+            ])
+            .map(str::to_string)
+            .collect();
+        Self {
+            known_keys,
+            country_keys: ["country", "destination_country"]
+                .map(str::to_string)
+                .into(),
+            boolean_keys: BTreeSet::from(["cross_border".to_string()]),
+            ip_resolver: None,
+        }
+    }
+
+    /// Treat `key` as known so it is not reported
+    pub fn with_known_key(mut self, key: &str) -> Self {
+        self.known_keys.insert(canonical_key(key));
+        self
+    }
+
+    /// Uppercase values under `key` as country codes
+    pub fn with_country_key(mut self, key: &str) -> Self {
+        let key = canonical_key(key);
+        self.known_keys.insert(key.clone());
+        self.country_keys.insert(key);
+        self
+    }
+
+    /// Rewrite values under `key` to `true`/`false`
+    pub fn with_boolean_key(mut self, key: &str) -> Self {
+        let key = canonical_key(key);
+        self.known_keys.insert(key.clone());
+        self.boolean_keys.insert(key);
+        self
+    }
+
+    /// Fill a missing origin country from the IP address
+    pub fn with_ip_resolver<R: IpCountryResolver + 'static>(mut self, resolver: R) -> Self {
+        self.ip_resolver = Some(Box::new(resolver));
+        self
+    }
+
+    /// Keys the normalizer recognizes
+    pub fn known_keys(&self) -> impl Iterator<Item = &str> {
+        self.known_keys.iter().map(String::as_str)
+    }
+
+    /// Canonicalize the transaction's metadata in place
+    pub fn normalize(&self, transaction: &mut Transaction) -> NormalizationReport {
+        let mut report = NormalizationReport::default();
+        let Some(metadata) = transaction.metadata.take() else {
+            return report;
+        };
+
+        // Already-canonical keys win collisions, then keys in sorted order
+        let mut entries: Vec<(String, String)> = metadata
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        entries.sort_by_key(|(key, _)| (canonical_key(key) != *key, key.clone()));
+
+        let mut normalized = TransactionMetadata::new();
+        for (key, value) in entries {
+            let canonical = canonical_key(&key);
+            if canonical != key {
+                report.renamed_keys.push((key.clone(), canonical.clone()));
+            }
+            let trimmed = value.trim();
+            if canonical.is_empty() || trimmed.is_empty() || normalized.get(&canonical).is_some() {
+                report.dropped_keys.push(key);
+                continue;
+            }
+
+            let mut canonical_value = trimmed.to_string();
+            if self.country_keys.contains(&canonical) {
+                canonical_value = canonical_value.to_ascii_uppercase();
+            }
+            if self.boolean_keys.contains(&canonical) {
+                match parse_flag(trimmed) {
+                    Some(flag) => canonical_value = flag.to_string(),
+                    None => report.invalid_values.push(canonical.clone()),
+                }
+            }
+            if canonical_value != value {
+                report.changed_values.push(canonical.clone());
+            }
+            if !self.known_keys.contains(&canonical) {
+                report.unknown_keys.push(canonical.clone());
+            }
+            normalized.insert(canonical, canonical_value);
+        }
+
+        self.derive(&mut normalized, &mut report);
+        report.unknown_keys.sort();
+        transaction.metadata = Some(normalized);
+        report
+    }
+
+    fn derive(&self, metadata: &mut TransactionMetadata, report: &mut NormalizationReport) {
+        if metadata.country.is_none() {
+            let resolved = match (&self.ip_resolver, &metadata.ip_address) {
+                (Some(resolver), Some(ip)) => resolver.country(ip),
+                _ => None,
+            };
+            if let Some(country) = resolved {
+                metadata.country = Some(country.trim().to_ascii_uppercase());
+                report.derived_keys.push("country".to_string());
+            }
+        }
+        if metadata.get("cross_border").is_none() {
+            if let (Some(origin), Some(destination)) =
+                (&metadata.country, &metadata.destination_country)
+            {
+                let cross_border = origin != destination;
+                metadata.insert("cross_border", cross_border.to_string());
+                report.derived_keys.push("cross_border".to_string());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::TransactionValidator;
+
+    fn metadata(entries: &[(&str, &str)]) -> Option<TransactionMetadata> {
+        Some(
+            entries
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_normalize_keys_and_values() {
+        let mut tx = create_test_transaction("TXN-NRM-1", 100.0);
+        tx.metadata = metadata(&[
+            (" Country ", " us "),
+            ("Destination-Country", "gb"),
+            ("Cross Border", "Yes"),
+            ("Invoice.Ref", "INV-7"),
+            ("purpose", "   "),
+        ]);
+
+        let report = MetadataNormalizer::new().normalize(&mut tx);
+        let m = tx.metadata.unwrap();
+        assert_eq!(m.country.as_deref(), Some("US"));
+        assert_eq!(m.destination_country.as_deref(), Some("GB"));
+        assert_eq!(m.get("cross_border"), Some("true"));
+        assert_eq!(m.get("invoice_ref"), Some("INV-7"));
+        assert!(m.purpose.is_none());
+        assert_eq!(report.renamed_keys.len(), 4);
+        assert_eq!(report.unknown_keys, vec!["invoice_ref"]);
+        assert_eq!(report.dropped_keys, vec!["purpose"]);
+        assert!(report.derived_keys.is_empty());
+
+        let mut tx = create_test_transaction("TXN-NRM-2", 100.0);
+        tx.metadata = metadata(&[("country", "US"), ("cross_border", "false")]);
+        assert!(MetadataNormalizer::new().normalize(&mut tx).is_clean());
+    }
+
+    #[test]
+    fn test_derive_country_and_cross_border() {
+        let normalizer = MetadataNormalizer::new()
+            .with_ip_resolver(|ip: &str| ip.starts_with("203.0.113.").then(|| "au".to_string()));
+        let mut tx = create_test_transaction("TXN-NRM-3", 100.0);
+        tx.metadata = metadata(&[("ip_address", "203.0.113.7"), ("destination_country", "NZ")]);
+
+        let report = normalizer.normalize(&mut tx);
+        let m = tx.metadata.unwrap();
+        assert_eq!(m.country.as_deref(), Some("AU"));
+        assert_eq!(m.get("cross_border"), Some("true"));
+        assert_eq!(report.derived_keys, vec!["country", "cross_border"]);
+    }
+
+    #[test]
+    fn test_validator_normalizes_before_checks() {
+        let mut validator = TransactionValidator::builder()
+            .with_normalizer(MetadataNormalizer::new())
+            .build();
+        let mut tx = create_test_transaction("TXN-NRM-4", 100.0);
+        tx.metadata = metadata(&[("Cross_Border", "YES"), ("cross_border", "maybe")]);
+
+        let result = validator.validate(&tx);
+        assert!(result
+            .warning_codes()
+            .contains(&"METADATA_INVALID_VALUE".to_string()));
+        // The caller's transaction is untouched
+        assert_eq!(tx.metadata.unwrap().get("Cross_Border"), Some("YES"));
+    }
+}
@@ -0,0 +1,1005 @@
+//! OFAC list loaders
+//!
+//! [`SanctionsScreener::new`] ships fictional demonstration entries only.
+//! Production screening loads the Treasury's published files instead:
+//!
+//! - [`SanctionsScreener::load_ofac_sdn_xml`] reads `sdn.xml` or
+//!   `consolidated.xml` in the classic SDN XML schema.
+//! - [`SanctionsScreener::load_ofac_csv`] reads `sdn.csv` or `cons_prim.csv`
+//!   together with the alias (`alt.csv`, `cons_alt.csv`) and address
+//!   (`add.csv`, `cons_add.csv`) files found next to it.
+//!
+//! SDN entries go on [`SanctionsList::OFAC`]; the non-SDN Consolidated list
+//! goes on its own `OFAC Consolidated` custom list, which loading enables.
+//! Loading replaces the list's previous entries, including the demonstration
+//! entry, and records the publication date as the list's source. Strong
+//! aliases are screened; weak ones, which OFAC does not expect to produce
+//! reliable matches, are kept in the entry details only.
+//!
+//! The CSV files carry identification documents in the remarks column;
+//! remarks such as `Passport A1234567 (Iran)` are parsed into [`OfacId`]s.
+//!
+//! Parsing is bounded by [`ParseLimits`]: the loaders use
+//! [`OfacPublication::default_limits`], sized for the published files, and
+//! the `_with_limits` variants take caller limits.
+
+use crate::{ParseError, ParseLimits, SanctionsList, SanctionsScreener};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use thiserror::Error;
+
+/// Error loading an OFAC file
+#[derive(Error, Debug)]
+pub enum OfacLoadError {
+    #[error("Failed to read OFAC file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Malformed XML on line {line}: {message}")]
+    Xml { line: usize, message: String },
+
+    #[error("Malformed CSV on line {line}: {message}")]
+    Csv { line: usize, message: String },
+
+    #[error(transparent)]
+    Limit(#[from] ParseError),
+}
+
+/// Which OFAC publication a file holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OfacListKind {
+    /// Specially Designated Nationals and Blocked Persons
+    Sdn,
+    /// Consolidated (non-SDN) sanctions list
+    Consolidated,
+}
+
+impl OfacListKind {
+    /// Consolidated when the file name starts with `cons`, SDN otherwise
+    pub fn from_path(path: &Path) -> Self {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if name.starts_with("cons") {
+            OfacListKind::Consolidated
+        } else {
+            OfacListKind::Sdn
+        }
+    }
+
+    /// Screener list the publication's entries are loaded onto
+    pub fn list(&self) -> SanctionsList {
+        match self {
+            OfacListKind::Sdn => SanctionsList::OFAC,
+            OfacListKind::Consolidated => SanctionsList::Custom("OFAC Consolidated".to_string()),
+        }
+    }
+
+    /// Prefix of the entry IDs reported in matches
+    pub fn id_prefix(&self) -> &'static str {
+        match self {
+            OfacListKind::Sdn => "OFAC-SDN",
+            OfacListKind::Consolidated => "OFAC-CONS",
+        }
+    }
+
+    pub fn source_name(&self) -> &'static str {
+        match self {
+            OfacListKind::Sdn => "OFAC SDN list",
+            OfacListKind::Consolidated => "OFAC Consolidated Sanctions List",
+        }
+    }
+}
+
+/// Kind of party an entry designates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OfacEntryType {
+    Individual,
+    Entity,
+    Vessel,
+    Aircraft,
+}
+
+impl OfacEntryType {
+    /// Parse `sdnType`; the CSV files leave entities without a type
+    fn parse(value: Option<&str>) -> Self {
+        match value.map(|v| v.to_lowercase()).as_deref() {
+            Some("individual") => OfacEntryType::Individual,
+            Some("vessel") => OfacEntryType::Vessel,
+            Some("aircraft") => OfacEntryType::Aircraft,
+            _ => OfacEntryType::Entity,
+        }
+    }
+}
+
+/// Alternate name of an entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OfacAlias {
+    pub name: String,
+    /// `a.k.a.`, `f.k.a.` or `n.k.a.`
+    pub kind: Option<String>,
+    /// Weak aliases are too broad to screen on
+    pub strong: bool,
+}
+
+/// Known address of an entry
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OfacAddress {
+    pub street: Option<String>,
+    /// City, state or province, and postal code
+    pub locality: Option<String>,
+    pub country: Option<String>,
+}
+
+/// Identification document of an entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OfacId {
+    pub id_type: String,
+    pub number: String,
+    pub country: Option<String>,
+}
+
+/// One designated party
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OfacEntry {
+    /// OFAC unique ID (`uid` in XML, `ent_num` in CSV)
+    pub uid: String,
+    /// Full name, given names first for individuals
+    pub name: String,
+    pub entry_type: OfacEntryType,
+    pub programs: Vec<String>,
+    pub aliases: Vec<OfacAlias>,
+    pub addresses: Vec<OfacAddress>,
+    pub ids: Vec<OfacId>,
+    pub remarks: Option<String>,
+}
+
+/// Parsed OFAC file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OfacPublication {
+    pub kind: OfacListKind,
+    /// `Publish_Date` of an XML file; the CSV files carry none
+    pub publish_date: Option<NaiveDate>,
+    pub entries: Vec<OfacEntry>,
+}
+
+impl OfacPublication {
+    /// Limits that admit the full published SDN and Consolidated files
+    pub fn default_limits() -> ParseLimits {
+        ParseLimits {
+            max_input_bytes: 256 * 1024 * 1024,
+            max_records: 1_000_000,
+            max_field_length: 64 * 1024,
+            ..Default::default()
+        }
+    }
+
+    /// Parse a file in the classic SDN XML schema
+    pub fn from_xml(
+        kind: OfacListKind,
+        text: &str,
+        limits: &ParseLimits,
+    ) -> Result<Self, OfacLoadError> {
+        limits.check_input_size(text.len())?;
+        let root = parse_xml(text, limits)?;
+        let publish_date = root
+            .child("publshInformation")
+            .and_then(|info| info.text_of("Publish_Date"))
+            .and_then(|date| NaiveDate::parse_from_str(&date, "%m/%d/%Y").ok());
+
+        let mut entries = Vec::new();
+        for entry in root.children("sdnEntry") {
+            limits.check_record_count(entries.len() + 1)?;
+            let Some(uid) = entry.text_of("uid") else {
+                return Err(OfacLoadError::Xml {
+                    line: entry.line,
+                    message: "sdnEntry without uid".to_string(),
+                });
+            };
+            let entry_type = OfacEntryType::parse(entry.text_of("sdnType").as_deref());
+            let programs = entry
+                .children_of("programList", "program")
+                .filter_map(XmlElement::value)
+                .collect();
+            let aliases = entry
+                .children_of("akaList", "aka")
+                .filter_map(|aka| {
+                    Some(OfacAlias {
+                        name: xml_name(aka)?,
+                        kind: aka.text_of("type"),
+                        strong: aka.text_of("category").as_deref() != Some("weak"),
+                    })
+                })
+                .collect();
+            let addresses = entry
+                .children_of("addressList", "address")
+                .map(|address| OfacAddress {
+                    street: join(
+                        ["address1", "address2", "address3"].map(|f| address.text_of(f)),
+                        ", ",
+                    ),
+                    locality: join(
+                        ["city", "stateOrProvince", "postalCode"].map(|f| address.text_of(f)),
+                        " ",
+                    ),
+                    country: address.text_of("country"),
+                })
+                .filter(|address| address != &OfacAddress::default())
+                .collect();
+            let ids = entry
+                .children_of("idList", "id")
+                .filter_map(|id| {
+                    Some(OfacId {
+                        id_type: id.text_of("idType")?,
+                        number: id.text_of("idNumber")?,
+                        country: id.text_of("idCountry"),
+                    })
+                })
+                .collect();
+            entries.push(OfacEntry {
+                name: xml_name(entry).unwrap_or_else(|| uid.clone()),
+                uid,
+                entry_type,
+                programs,
+                aliases,
+                addresses,
+                ids,
+                remarks: entry.text_of("remarks"),
+            });
+        }
+
+        Ok(Self {
+            kind,
+            publish_date,
+            entries,
+        })
+    }
+
+    /// Parse the CSV files: the primary file and optional alias and address files
+    pub fn from_csv(
+        kind: OfacListKind,
+        primary: &str,
+        aliases: Option<&str>,
+        addresses: Option<&str>,
+        limits: &ParseLimits,
+    ) -> Result<Self, OfacLoadError> {
+        let parse = |text: &str| {
+            limits.check_input_size(text.len())?;
+            parse_csv(text, limits)
+        };
+        let mut entries = Vec::new();
+        let mut index = HashMap::new();
+        for (line, row) in parse(primary)? {
+            let uid = csv_field(&row, 0).ok_or_else(|| csv_error(line, "missing ent_num"))?;
+            let name = csv_field(&row, 1).ok_or_else(|| csv_error(line, "missing SDN_Name"))?;
+            let entry_type = OfacEntryType::parse(csv_field(&row, 2).as_deref());
+            let remarks = csv_field(&row, 11);
+            index.insert(uid.clone(), entries.len());
+            entries.push(OfacEntry {
+                uid,
+                name: csv_name(&name, entry_type),
+                entry_type,
+                programs: csv_field(&row, 3)
+                    .map(|programs| split_programs(&programs))
+                    .unwrap_or_default(),
+                aliases: Vec::new(),
+                addresses: Vec::new(),
+                ids: remarks.as_deref().map(ids_from_remarks).unwrap_or_default(),
+                remarks,
+            });
+        }
+
+        for (line, row) in aliases.map(parse).transpose()?.unwrap_or_default() {
+            let uid = csv_field(&row, 0).ok_or_else(|| csv_error(line, "missing ent_num"))?;
+            let Some(entry) = index.get(&uid).map(|&i| &mut entries[i]) else {
+                continue;
+            };
+            if let Some(name) = csv_field(&row, 3) {
+                entry.aliases.push(OfacAlias {
+                    name: csv_name(&name, entry.entry_type),
+                    kind: csv_field(&row, 2),
+                    strong: true,
+                });
+            }
+        }
+
+        for (line, row) in addresses.map(parse).transpose()?.unwrap_or_default() {
+            let uid = csv_field(&row, 0).ok_or_else(|| csv_error(line, "missing ent_num"))?;
+            let Some(entry) = index.get(&uid).map(|&i| &mut entries[i]) else {
+                continue;
+            };
+            let address = OfacAddress {
+                street: csv_field(&row, 2),
+                locality: csv_field(&row, 3),
+                country: csv_field(&row, 4),
+            };
+            if address != OfacAddress::default() {
+                entry.addresses.push(address);
+            }
+        }
+
+        Ok(Self {
+            kind,
+            publish_date: None,
+            entries,
+        })
+    }
+}
+
+impl SanctionsScreener {
+    /// Replace OFAC entries with an `sdn.xml` or `consolidated.xml` file
+    ///
+    /// Returns the number of entries loaded.
+    pub fn load_ofac_sdn_xml(&mut self, path: impl AsRef<Path>) -> Result<usize, OfacLoadError> {
+        self.load_ofac_sdn_xml_with_limits(path, &OfacPublication::default_limits())
+    }
+
+    /// [`SanctionsScreener::load_ofac_sdn_xml`] under caller limits
+    pub fn load_ofac_sdn_xml_with_limits(
+        &mut self,
+        path: impl AsRef<Path>,
+        limits: &ParseLimits,
+    ) -> Result<usize, OfacLoadError> {
+        let path = path.as_ref();
+        let text = read_lossy(path, limits)?;
+        let publication = OfacPublication::from_xml(OfacListKind::from_path(path), &text, limits)?;
+        Ok(self.load_ofac(publication))
+    }
+
+    /// Replace OFAC entries with `sdn.csv` or `cons_prim.csv` and its companions
+    ///
+    /// Alias and address files are read from the same directory when
+    /// present. Returns the number of entries loaded.
+    pub fn load_ofac_csv(&mut self, path: impl AsRef<Path>) -> Result<usize, OfacLoadError> {
+        self.load_ofac_csv_with_limits(path, &OfacPublication::default_limits())
+    }
+
+    /// [`SanctionsScreener::load_ofac_csv`] under caller limits
+    pub fn load_ofac_csv_with_limits(
+        &mut self,
+        path: impl AsRef<Path>,
+        limits: &ParseLimits,
+    ) -> Result<usize, OfacLoadError> {
+        let path = path.as_ref();
+        let kind = OfacListKind::from_path(path);
+        let (alias_file, address_file) = match kind {
+            OfacListKind::Sdn => ("alt.csv", "add.csv"),
+            OfacListKind::Consolidated => ("cons_alt.csv", "cons_add.csv"),
+        };
+        let sibling = |name: &str| -> Result<Option<String>, OfacLoadError> {
+            let sibling = path.with_file_name(name);
+            if sibling.is_file() {
+                read_lossy(&sibling, limits).map(Some)
+            } else {
+                Ok(None)
+            }
+        };
+        let primary = read_lossy(path, limits)?;
+        let aliases = sibling(alias_file)?;
+        let addresses = sibling(address_file)?;
+        let publication = OfacPublication::from_csv(
+            kind,
+            &primary,
+            aliases.as_deref(),
+            addresses.as_deref(),
+            limits,
+        )?;
+        Ok(self.load_ofac(publication))
+    }
+}
+
+/// OFAC files are mostly ASCII but not reliably UTF-8
+fn read_lossy(path: &Path, limits: &ParseLimits) -> Result<String, OfacLoadError> {
+    let bytes = limits.read_file(path)?;
+    Ok(limits.input_text_lossy(&bytes)?.into_owned())
+}
+
+fn join<const N: usize>(parts: [Option<String>; N], separator: &str) -> Option<String> {
+    let parts: Vec<String> = parts.into_iter().flatten().collect();
+    (!parts.is_empty()).then(|| parts.join(separator))
+}
+
+/// Given names followed by the last name, as an SDN entry or alias spells it
+fn xml_name(element: &XmlElement) -> Option<String> {
+    join(
+        [element.text_of("firstName"), element.text_of("lastName")],
+        " ",
+    )
+}
+
+/// Individuals are listed as `LAST, Given Names`; put given names first
+fn csv_name(name: &str, entry_type: OfacEntryType) -> String {
+    match (entry_type, name.split_once(", ")) {
+        (OfacEntryType::Individual, Some((last, given))) => {
+            format!("{} {}", given.trim(), last.trim())
+        }
+        _ => name.to_string(),
+    }
+}
+
+/// Split `SDGT] [IRGC` into both programs
+fn split_programs(programs: &str) -> Vec<String> {
+    programs
+        .split("] [")
+        .map(|p| p.trim_matches(|c: char| c == '[' || c == ']' || c.is_whitespace()))
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Identification types that appear in the CSV remarks column
+const REMARK_ID_TYPES: [&str; 13] = [
+    "Passport",
+    "National ID No.",
+    "Cedula No.",
+    "Tax ID No.",
+    "SSN",
+    "Registration ID",
+    "Identification Number",
+    "Driver's License No.",
+    "NIT #",
+    "RUC #",
+    "D.N.I.",
+    "Business Registration Number",
+    "Vessel Registration Identification",
+];
+
+/// Parse `Passport A1234567 (Iran); alt. Passport ...` remarks into IDs
+fn ids_from_remarks(remarks: &str) -> Vec<OfacId> {
+    remarks
+        .split(';')
+        .filter_map(|piece| {
+            let piece = piece.trim().trim_end_matches('.');
+            let piece = piece.strip_prefix("alt. ").unwrap_or(piece);
+            let id_type = REMARK_ID_TYPES
+                .iter()
+                .find(|t| piece.starts_with(&format!("{} ", t)))?;
+            let rest = piece[id_type.len()..].trim();
+            let (number, country) = match rest.split_once(" (") {
+                Some((number, country)) => (number, Some(country.trim_end_matches(')'))),
+                None => (rest, None),
+            };
+            (!number.is_empty()).then(|| OfacId {
+                id_type: id_type.to_string(),
+                number: number.trim().to_string(),
+                country: country.map(str::to_string),
+            })
+        })
+        .collect()
+}
+
+/// Element of a parsed XML document; attributes are not kept
+#[derive(Debug, Default)]
+struct XmlElement {
+    /// Local name, without a namespace prefix
+    name: String,
+    text: String,
+    children: Vec<XmlElement>,
+    line: usize,
+}
+
+impl XmlElement {
+    fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    /// Children named `item` of the child list named `list`
+    fn children_of<'a>(
+        &'a self,
+        list: &'a str,
+        item: &'a str,
+    ) -> impl Iterator<Item = &'a XmlElement> {
+        self.child(list)
+            .into_iter()
+            .flat_map(move |l| l.children(item))
+    }
+
+    /// Trimmed text, if any
+    fn value(&self) -> Option<String> {
+        let text = self.text.trim();
+        (!text.is_empty()).then(|| text.to_string())
+    }
+
+    fn text_of(&self, name: &str) -> Option<String> {
+        self.child(name).and_then(XmlElement::value)
+    }
+}
+
+/// Line numbers of byte offsets, counted forward from the previous lookup
+struct LineCounter<'a> {
+    text: &'a str,
+    pos: usize,
+    line: usize,
+}
+
+impl<'a> LineCounter<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            pos: 0,
+            line: 1,
+        }
+    }
+
+    fn at(&mut self, pos: usize) -> usize {
+        if pos < self.pos {
+            return self.text[..pos].matches('\n').count() + 1;
+        }
+        self.line += self.text[self.pos..pos].matches('\n').count();
+        self.pos = pos;
+        self.line
+    }
+
+    fn error(&mut self, pos: usize, message: &str) -> OfacLoadError {
+        OfacLoadError::Xml {
+            line: self.at(pos),
+            message: message.to_string(),
+        }
+    }
+
+    /// Position just past the next `end` at or after `pos`
+    fn skip_to(&mut self, pos: usize, end: &str, what: &str) -> Result<usize, OfacLoadError> {
+        match self.text[pos..].find(end) {
+            Some(i) => Ok(pos + i + end.len()),
+            None => Err(self.error(pos, &format!("unterminated {}", what))),
+        }
+    }
+}
+
+/// Parse an XML document into its root element
+///
+/// Covers what the OFAC files use: elements, character data and CDATA, the
+/// predefined and numeric entities, and skipped declarations, comments and
+/// attributes. DTD internal subsets are not supported. Nesting is capped at
+/// `max_depth`, which also bounds the recursion when the tree is dropped.
+fn parse_xml(text: &str, limits: &ParseLimits) -> Result<XmlElement, OfacLoadError> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut lines = LineCounter::new(text);
+
+    let mut stack = vec![XmlElement::default()];
+    let mut pos = 0;
+    while pos < text.len() {
+        let rest = &text[pos..];
+        if rest.starts_with("<?") {
+            pos = lines.skip_to(pos, "?>", "declaration")?;
+        } else if rest.starts_with("<!--") {
+            pos = lines.skip_to(pos, "-->", "comment")?;
+        } else if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let end = cdata
+                .find("]]>")
+                .ok_or_else(|| lines.error(pos, "unterminated CDATA section"))?;
+            stack.last_mut().unwrap().text.push_str(&cdata[..end]);
+            pos += "<![CDATA[".len() + end + "]]>".len();
+        } else if rest.starts_with("<!") {
+            pos = lines.skip_to(pos, ">", "doctype")?;
+        } else if let Some(close) = rest.strip_prefix("</") {
+            let end = close
+                .find('>')
+                .ok_or_else(|| lines.error(pos, "unterminated end tag"))?;
+            let name = local_name(close[..end].trim());
+            if stack.len() < 2 || stack.last().unwrap().name != name {
+                return Err(lines.error(pos, &format!("unexpected </{}>", name)));
+            }
+            let element = stack.pop().unwrap();
+            limits.check_field(&element.name, &element.text)?;
+            stack.last_mut().unwrap().children.push(element);
+            pos += 2 + end + 1;
+        } else if rest.starts_with('<') {
+            let end = tag_end(rest).ok_or_else(|| lines.error(pos, "unterminated start tag"))?;
+            let tag = &rest[1..end];
+            let self_closing = tag.ends_with('/');
+            let name = tag
+                .trim_end_matches('/')
+                .split(|c: char| c.is_whitespace())
+                .next()
+                .unwrap_or_default();
+            if name.is_empty() {
+                return Err(lines.error(pos, "start tag without a name"));
+            }
+            // The document node is not counted
+            limits.check_depth(stack.len())?;
+            let element = XmlElement {
+                name: local_name(name).to_string(),
+                line: lines.at(pos),
+                ..Default::default()
+            };
+            if self_closing {
+                stack.last_mut().unwrap().children.push(element);
+            } else {
+                stack.push(element);
+            }
+            pos += end + 1;
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            // Indentation between child elements is dropped, not accumulated
+            let raw = &rest[..end];
+            if !raw.trim().is_empty() {
+                let top = stack.last_mut().unwrap();
+                top.text.push_str(&decode_entities(raw));
+            }
+            pos += end;
+        }
+    }
+
+    if stack.len() > 1 {
+        let open = stack.pop().unwrap();
+        return Err(OfacLoadError::Xml {
+            line: open.line,
+            message: format!("<{}> is never closed", open.name),
+        });
+    }
+    let mut document = stack.pop().unwrap();
+    match document.children.len() {
+        1 => Ok(document.children.pop().unwrap()),
+        _ => Err(lines.error(0, "expected exactly one root element")),
+    }
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// Index of the `>` closing a tag, skipping quoted attribute values
+fn tag_end(tag: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in tag.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Replace predefined and numeric entities; unknown ones are kept as written
+fn decode_entities(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest.find(';').and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => entity
+                    .strip_prefix("#x")
+                    .map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| entity.strip_prefix('#').map(str::parse))
+                    .and_then(Result::ok)
+                    .and_then(char::from_u32),
+            }?;
+            Some((c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn csv_error(line: usize, message: &str) -> OfacLoadError {
+    OfacLoadError::Csv {
+        line,
+        message: message.to_string(),
+    }
+}
+
+/// Field value, treating OFAC's `-0-` null marker as absent
+fn csv_field(row: &[String], index: usize) -> Option<String> {
+    let value = row.get(index)?.trim();
+    (!value.is_empty() && value != "-0-").then(|| value.to_string())
+}
+
+/// Parse CSV records with their starting line numbers
+///
+/// Quoted fields may contain commas, doubled quotes and line breaks. Blank
+/// lines and the end-of-file marker OFAC appends are skipped.
+fn parse_csv(text: &str, limits: &ParseLimits) -> Result<Vec<(usize, Vec<String>)>, OfacLoadError> {
+    let mut records = Vec::new();
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+    while chars.peek().is_some() {
+        let start = line;
+        let mut row = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        loop {
+            match chars.next() {
+                None if quoted => return Err(csv_error(start, "unterminated quoted field")),
+                None => break,
+                Some('"') if quoted => {
+                    if chars.peek() == Some(&'"') {
+                        chars.next();
+                        field.push('"');
+                    } else {
+                        quoted = false;
+                    }
+                }
+                Some('"') if field.trim().is_empty() => {
+                    field.clear();
+                    quoted = true;
+                }
+                Some('\n') => {
+                    line += 1;
+                    if quoted {
+                        field.push('\n');
+                    } else {
+                        break;
+                    }
+                }
+                Some('\r') if !quoted => {}
+                Some(',') if !quoted => {
+                    limits.check_field("csv field", &field)?;
+                    row.push(std::mem::take(&mut field));
+                }
+                Some(c) => field.push(c),
+            }
+        }
+        limits.check_field("csv field", &field)?;
+        row.push(field);
+        let blank = row.len() == 1 && row[0].trim_matches(['\u{1a}', ' ']).is_empty();
+        if !blank {
+            limits.check_record_count(records.len() + 1)?;
+            records.push((start, row));
+        }
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    const SDN_XML: &str = r#"<?xml version="1.0" standalone="yes"?>
+<sdnList xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns="http://tempuri.org/sdnList.xsd">
+  <publshInformation>
+    <Publish_Date>10/10/2025</Publish_Date>
+    <Record_Count>2</Record_Count>
+  </publshInformation>
+  <sdnEntry>
+    <uid>1001</uid>
+    <lastName>OCEANIC FREIGHT &amp; TRADING LLC</lastName>
+    <sdnType>Entity</sdnType>
+    <programList>
+      <program>SDGT</program>
+      <program>IRGC</program>
+    </programList>
+    <idList>
+      <id>
+        <uid>501</uid>
+        <idType>Registration ID</idType>
+        <idNumber>77121</idNumber>
+        <idCountry>United Arab Emirates</idCountry>
+      </id>
+    </idList>
+    <akaList>
+      <aka>
+        <uid>601</uid>
+        <type>a.k.a.</type>
+        <category>strong</category>
+        <lastName>OFT LLC</lastName>
+      </aka>
+      <aka>
+        <uid>602</uid>
+        <type>a.k.a.</type>
+        <category>weak</category>
+        <lastName>OCEANIC</lastName>
+      </aka>
+    </akaList>
+    <addressList>
+      <address>
+        <uid>701</uid>
+        <address1>Office 12, Trade Tower</address1>
+        <city>Dubai</city>
+        <country>United Arab Emirates</country>
+      </address>
+    </addressList>
+  </sdnEntry>
+  <sdnEntry>
+    <uid>1002</uid>
+    <firstName>Viktor</firstName>
+    <lastName>KARAMAZOV</lastName>
+    <sdnType>Individual</sdnType>
+    <programList><program>RUSSIA-EO14024</program></programList>
+    <idList/>
+    <![CDATA[ignored]]>
+  </sdnEntry>
+</sdnList>
+"#;
+
+    #[test]
+    fn test_sdn_xml_replaces_demo_entries() {
+        let limits = OfacPublication::default_limits();
+        let publication = OfacPublication::from_xml(OfacListKind::Sdn, SDN_XML, &limits).unwrap();
+        assert_eq!(
+            publication.publish_date,
+            NaiveDate::from_ymd_opt(2025, 10, 10)
+        );
+        let oceanic = &publication.entries[0];
+        assert_eq!(oceanic.name, "OCEANIC FREIGHT & TRADING LLC");
+        assert_eq!(oceanic.programs, vec!["SDGT", "IRGC"]);
+        assert_eq!(oceanic.aliases.len(), 2);
+        assert!(!oceanic.aliases[1].strong);
+        assert_eq!(oceanic.ids[0].number, "77121");
+        assert_eq!(oceanic.addresses[0].locality.as_deref(), Some("Dubai"));
+        assert_eq!(publication.entries[1].name, "Viktor KARAMAZOV");
+        assert_eq!(publication.entries[1].entry_type, OfacEntryType::Individual);
+
+        let mut screener = SanctionsScreener::new();
+        assert_eq!(screener.load_ofac(publication), 2);
+        assert!(!screener.screen("SANCTIONED ENTITY ONE").is_match);
+
+        let alias = screener.screen("OFT LLC");
+        let hit = alias.highest_high_confidence().unwrap();
+        assert_eq!(hit.entry_id, "OFAC-SDN-1001");
+        assert_eq!(hit.program.as_deref(), Some("SDGT, IRGC"));
+        assert_eq!(
+            screener.ofac_entry("OFAC-SDN-1001").unwrap().addresses[0]
+                .country
+                .as_deref(),
+            Some("United Arab Emirates")
+        );
+        assert!(screener
+            .screen("Viktor Karamazov")
+            .has_high_confidence_match());
+
+        let ofac = screener
+            .list_provenance()
+            .into_iter()
+            .find(|p| p.list == SanctionsList::OFAC)
+            .unwrap();
+        assert_eq!(ofac.entry_count, 2);
+        assert_eq!(ofac.version, "2025-10-10");
+    }
+
+    #[test]
+    fn test_consolidated_csv_with_companion_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("cons_prim.csv"),
+            "2001,\"PETROV, Ivan Sergeyevich\",\"individual\",\"UKRAINE-EO13660] [RUSSIA-EO14024\",-0- ,-0- ,-0- ,-0- ,-0- ,-0- ,-0- ,\"DOB 01 Feb 1970; Passport 7201234 (Russia); alt. Passport 7205678 (Russia).\"\r\n\
+             2002,\"NORTHWIND SHIPPING, INC.\",-0- ,\"NS-PLC\",-0- ,-0- ,-0- ,-0- ,-0- ,-0- ,-0- ,-0- \r\n\
+             \u{1a}",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("cons_alt.csv"),
+            "2001,3001,\"aka\",\"PETROV, Vanya\",-0- \r\n2002,3002,\"fka\",\"NORTHWIND LINES\",-0- \r\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("cons_add.csv"),
+            "2002,4001,\"12 Harbour Road\",\"Limassol 3035\",\"Cyprus\",-0- \r\n",
+        )
+        .unwrap();
+
+        let mut screener = SanctionsScreener::new();
+        let loaded = screener
+            .load_ofac_csv(dir.path().join("cons_prim.csv"))
+            .unwrap();
+        assert_eq!(loaded, 2);
+
+        let petrov = screener.ofac_entry("OFAC-CONS-2001").unwrap();
+        assert_eq!(petrov.name, "Ivan Sergeyevich PETROV");
+        assert_eq!(petrov.programs, vec!["UKRAINE-EO13660", "RUSSIA-EO14024"]);
+        assert_eq!(petrov.aliases[0].name, "Vanya PETROV");
+        assert_eq!(petrov.ids.len(), 2);
+        assert_eq!(petrov.ids[1].number, "7205678");
+        assert_eq!(petrov.ids[1].country.as_deref(), Some("Russia"));
+
+        let northwind = screener.ofac_entry("OFAC-CONS-2002").unwrap();
+        assert_eq!(northwind.entry_type, OfacEntryType::Entity);
+        assert_eq!(northwind.name, "NORTHWIND SHIPPING, INC.");
+        assert_eq!(northwind.addresses[0].country.as_deref(), Some("Cyprus"));
+
+        // The consolidated list is separate; the demonstration OFAC entry stays
+        let hit = screener.screen("NORTHWIND LINES");
+        assert_eq!(
+            hit.highest_high_confidence().unwrap().list,
+            OfacListKind::Consolidated.list()
+        );
+        assert!(screener.screen("SANCTIONED ENTITY ONE").is_match);
+    }
+
+    #[test]
+    fn test_malformed_files_report_lines() {
+        let limits = OfacPublication::default_limits();
+        let broken = "<sdnList>\n  <sdnEntry>\n    <uid>1</uid>\n</sdnList>\n";
+        match OfacPublication::from_xml(OfacListKind::Sdn, broken, &limits) {
+            Err(OfacLoadError::Xml { line, .. }) => assert_eq!(line, 4),
+            other => panic!("expected XML error, got {:?}", other),
+        }
+
+        match OfacPublication::from_csv(OfacListKind::Sdn, "1,\"OPEN\n2,X\n", None, None, &limits) {
+            Err(OfacLoadError::Csv { line, .. }) => assert_eq!(line, 1),
+            other => panic!("expected CSV error, got {:?}", other),
+        }
+
+        // Lines are counted past earlier elements and skipped sections
+        let late = "<sdnList>\n<!-- a\nb -->\n<sdnEntry>\n<uid>1</uid>\n</sdnEntry>\n</sdnEntry>\n";
+        match OfacPublication::from_xml(OfacListKind::Sdn, late, &limits) {
+            Err(OfacLoadError::Xml { line, .. }) => assert_eq!(line, 7),
+            other => panic!("expected XML error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_deep_nesting_is_rejected() {
+        let depth = 100_000;
+        let deep = format!("{}{}", "<a>".repeat(depth), "</a>".repeat(depth));
+        let limits = OfacPublication::default_limits();
+        assert!(matches!(
+            OfacPublication::from_xml(OfacListKind::Sdn, &deep, &limits),
+            Err(OfacLoadError::Limit(ParseError::TooDeep { limit: 64 }))
+        ));
+
+        let nested = format!("{}{}", "<a>".repeat(64), "</a>".repeat(64));
+        let publication = OfacPublication::from_xml(OfacListKind::Sdn, &nested, &limits).unwrap();
+        assert!(publication.entries.is_empty());
+    }
+
+    #[test]
+    fn test_limits_bound_both_formats() {
+        let limits = ParseLimits {
+            max_records: 1,
+            ..Default::default()
+        };
+        assert!(matches!(
+            OfacPublication::from_xml(OfacListKind::Sdn, SDN_XML, &limits),
+            Err(OfacLoadError::Limit(ParseError::TooManyRecords {
+                limit: 1
+            }))
+        ));
+        assert!(matches!(
+            OfacPublication::from_csv(OfacListKind::Sdn, "1,A\n2,B\n", None, None, &limits),
+            Err(OfacLoadError::Limit(ParseError::TooManyRecords {
+                limit: 1
+            }))
+        ));
+
+        let limits = ParseLimits {
+            max_input_bytes: 64,
+            ..Default::default()
+        };
+        assert!(matches!(
+            OfacPublication::from_xml(OfacListKind::Sdn, SDN_XML, &limits),
+            Err(OfacLoadError::Limit(ParseError::InputTooLarge { .. }))
+        ));
+        let limits = ParseLimits {
+            max_field_length: 8,
+            ..Default::default()
+        };
+        assert!(matches!(
+            OfacPublication::from_csv(
+                OfacListKind::Sdn,
+                "1,A VERY LONG NAME\n",
+                None,
+                None,
+                &limits
+            ),
+            Err(OfacLoadError::Limit(ParseError::FieldTooLong { .. }))
+        ));
+    }
+}
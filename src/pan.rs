@@ -0,0 +1,166 @@
+//! Card number (PAN) detection and masking
+//!
+//! A card number pasted into an account field or a metadata value would
+//! otherwise be copied into velocity history, results, audit records, and
+//! the event log, pulling all of them into PCI scope. With
+//! `ValidatorConfig::mask_pans` set (the default), the validator looks for
+//! runs of 13-19 digits, optionally grouped by spaces or dashes, that pass
+//! the Luhn check, and replaces each with asterisks and its last four
+//! digits before anything is checked or stored. The caller's transaction is
+//! left untouched and the result carries a [`PAN_MASKED_WARNING`].
+//!
+//! Masked numbers start with `****`, which the account format check already
+//! accepts. Two cards sharing their last four digits share history keys.
+
+use crate::{Transaction, Warning, WarningSeverity};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Warning code for a validation that masked card numbers
+pub const PAN_MASKED_WARNING: &str = "PAN_MASKED";
+
+fn pan_candidates() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\b(?:\d[ -]?){12,18}\d\b").unwrap())
+}
+
+/// Luhn (mod 10) check over a string of digits
+pub fn luhn_valid(digits: &str) -> bool {
+    let mut sum = 0;
+    for (i, c) in digits.chars().rev().enumerate() {
+        let Some(mut d) = c.to_digit(10) else {
+            return false;
+        };
+        if i % 2 == 1 {
+            d *= 2;
+            if d > 9 {
+                d -= 9;
+            }
+        }
+        sum += d;
+    }
+    !digits.is_empty() && sum.is_multiple_of(10)
+}
+
+fn card_digits(candidate: &str) -> Option<String> {
+    let digits: String = candidate.chars().filter(char::is_ascii_digit).collect();
+    ((13..=19).contains(&digits.len()) && luhn_valid(&digits)).then_some(digits)
+}
+
+/// `digits` with all but the last four replaced by `*`
+pub fn mask_digits(digits: &str) -> String {
+    let keep = digits.len().saturating_sub(4);
+    "*".repeat(keep) + &digits[keep..]
+}
+
+/// Check whether the text contains a Luhn-valid card number
+pub fn contains_pan(text: &str) -> bool {
+    pan_candidates()
+        .find_iter(text)
+        .any(|m| card_digits(m.as_str()).is_some())
+}
+
+/// The text with every card number masked, if it contained any
+pub fn mask_pans(text: &str) -> Option<String> {
+    if !contains_pan(text) {
+        return None;
+    }
+    let masked = pan_candidates().replace_all(text, |caps: &regex::Captures| {
+        let candidate = &caps[0];
+        match card_digits(candidate) {
+            Some(digits) => mask_digits(&digits),
+            None => candidate.to_string(),
+        }
+    });
+    Some(masked.into_owned())
+}
+
+/// Mask card numbers in account fields and metadata values
+///
+/// Returns the names of the fields that were masked.
+pub fn mask_transaction(transaction: &mut Transaction) -> Vec<String> {
+    let mut masked = Vec::new();
+    for (field, account) in [
+        ("from_account", &mut transaction.from_account),
+        ("to_account", &mut transaction.to_account),
+    ] {
+        if let Some(value) = account.as_deref().and_then(mask_pans) {
+            *account = Some(value);
+            masked.push(field.to_string());
+        }
+    }
+    if let Some(metadata) = transaction.metadata.as_mut() {
+        let replacements: Vec<(String, String)> = metadata
+            .iter()
+            .filter_map(|(key, value)| Some((key.to_string(), mask_pans(value)?)))
+            .collect();
+        for (key, value) in replacements {
+            metadata.insert(key.as_str(), value);
+            masked.push(key);
+        }
+    }
+    masked
+}
+
+/// Warning listing the masked fields, if any
+pub(crate) fn masked_warning(fields: &[String]) -> Option<Warning> {
+    (!fields.is_empty()).then(|| {
+        Warning::new(
+            PAN_MASKED_WARNING,
+            WarningSeverity::Low,
+            format!("Card number masked in {}", fields.join(", ")),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{HistoryKey, TransactionMetadata, TransactionValidator};
+
+    #[test]
+    fn test_luhn_and_masking() {
+        assert!(luhn_valid("4111111111111111"));
+        assert!(!luhn_valid("4111111111111112"));
+        assert_eq!(
+            mask_pans("card 4111 1111 1111 1111 on file").as_deref(),
+            Some("card ************1111 on file")
+        );
+        // Luhn failures and account-style IDs are left alone
+        assert_eq!(mask_pans("4111-1111-1111-1112"), None);
+        assert_eq!(mask_pans("ACCT-1234-5678-9012"), None);
+    }
+
+    #[test]
+    fn test_pans_never_reach_history() {
+        let mut validator = TransactionValidator::new();
+        let mut tx = create_test_transaction("TXN-PAN-1", 100.0);
+        tx.from_account = Some("4111-1111-1111-1111".to_string());
+        tx.metadata = Some(TransactionMetadata::from([(
+            "purpose".to_string(),
+            "refund to 5500 0000 0000 0004".to_string(),
+        )]));
+        let result = validator.validate(&tx);
+        assert!(result.is_valid);
+        let warning = result
+            .warnings
+            .iter()
+            .find(|w| w.code == PAN_MASKED_WARNING)
+            .unwrap();
+        assert_eq!(
+            warning.message,
+            "Card number masked in from_account, purpose"
+        );
+        // The caller's transaction is untouched
+        assert_eq!(tx.from_account.as_deref(), Some("4111-1111-1111-1111"));
+
+        let history = validator.history().read();
+        let stored = history.get(HistoryKey::User, "USER-001");
+        assert_eq!(stored[0].from_account.as_deref(), Some("************1111"));
+        assert_eq!(
+            stored[0].metadata.as_ref().unwrap().purpose.as_deref(),
+            Some("refund to ************0004")
+        );
+    }
+}
@@ -0,0 +1,334 @@
+//! Bounded parsing of untrusted transaction input
+//!
+//! Every decoder in the crate reads through [`ParseLimits`] so oversized or
+//! malformed files are rejected with a [`ParseError`] instead of panicking or
+//! allocating without bound. Decoders start at [`ParseLimits::read_file`] or
+//! [`ParseLimits::input_text`], which cap the input size before anything is
+//! decoded, and then apply the record, line, and field caps as they go.
+//!
+//! Bounded decoders: transaction JSON and JSON lines (this module), the
+//! event log ([`crate::read_event_log`]), the OFAC SDN XML and CSV files
+//! ([`crate::OfacPublication`]), and JSON configuration files
+//! ([`crate::ConfigFile`]). Each has a cargo-fuzz target in `fuzz/`.
+//! The crate has no ISO 20022 or NACHA parsers.
+
+use crate::Transaction;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::io::Read;
+use std::path::Path;
+use thiserror::Error;
+
+/// Parse errors for external input formats
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ParseError {
+    #[error("Input too large: {size} bytes exceeds limit of {limit}")]
+    InputTooLarge { size: usize, limit: usize },
+
+    #[error("Too many records: limit is {limit}")]
+    TooManyRecords { limit: usize },
+
+    #[error("Line {line} too long: {length} bytes exceeds limit of {limit}")]
+    LineTooLong {
+        line: usize,
+        length: usize,
+        limit: usize,
+    },
+
+    #[error("Field '{field}' too long: {length} exceeds limit of {limit}")]
+    FieldTooLong {
+        field: String,
+        length: usize,
+        limit: usize,
+    },
+
+    #[error("Too many entries in '{field}': {count} exceeds limit of {limit}")]
+    TooManyEntries {
+        field: String,
+        count: usize,
+        limit: usize,
+    },
+
+    #[error("Nesting too deep: limit is {limit}")]
+    TooDeep { limit: usize },
+
+    #[error("Invalid encoding: {0}")]
+    InvalidEncoding(String),
+
+    #[error("Failed to read input: {0}")]
+    Io(String),
+
+    #[error("Malformed input at line {line}: {message}")]
+    Malformed { line: usize, message: String },
+}
+
+/// Resource limits applied while parsing external input
+#[derive(Debug, Clone)]
+pub struct ParseLimits {
+    /// Maximum size of the whole input (bytes)
+    pub max_input_bytes: usize,
+    /// Maximum number of records in one input
+    pub max_records: usize,
+    /// Maximum length of a single line (bytes)
+    pub max_line_length: usize,
+    /// Maximum length of a single string field
+    pub max_field_length: usize,
+    /// Maximum number of metadata entries per record
+    pub max_metadata_entries: usize,
+    /// Maximum element nesting in tree-shaped formats such as XML
+    pub max_depth: usize,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_input_bytes: 16 * 1024 * 1024, // 16 MiB
+            max_records: 100_000,
+            max_line_length: 64 * 1024,
+            max_field_length: 1024,
+            max_metadata_entries: 64,
+            max_depth: 64,
+        }
+    }
+}
+
+impl ParseLimits {
+    /// Read a file, refusing one larger than `max_input_bytes` before reading it
+    pub fn read_file(&self, path: impl AsRef<Path>) -> Result<Vec<u8>, ParseError> {
+        let file = std::fs::File::open(path).map_err(|e| ParseError::Io(e.to_string()))?;
+        let size = file
+            .metadata()
+            .map_err(|e| ParseError::Io(e.to_string()))?
+            .len();
+        self.check_input_size(usize::try_from(size).unwrap_or(usize::MAX))?;
+
+        // The file may grow after the size check; read one byte past the cap
+        let mut bytes = Vec::new();
+        file.take(self.max_input_bytes as u64 + 1)
+            .read_to_end(&mut bytes)
+            .map_err(|e| ParseError::Io(e.to_string()))?;
+        self.check_input_size(bytes.len())?;
+        Ok(bytes)
+    }
+
+    /// Check the input size and decode the input as UTF-8
+    pub fn input_text<'a>(&self, input: &'a [u8]) -> Result<&'a str, ParseError> {
+        self.check_input_size(input.len())?;
+        std::str::from_utf8(input).map_err(|e| ParseError::InvalidEncoding(e.to_string()))
+    }
+
+    /// Like [`ParseLimits::input_text`], replacing invalid UTF-8
+    pub fn input_text_lossy<'a>(&self, input: &'a [u8]) -> Result<Cow<'a, str>, ParseError> {
+        self.check_input_size(input.len())?;
+        Ok(String::from_utf8_lossy(input))
+    }
+
+    /// Reject input larger than `max_input_bytes`
+    pub fn check_input_size(&self, size: usize) -> Result<(), ParseError> {
+        if size > self.max_input_bytes {
+            return Err(ParseError::InputTooLarge {
+                size,
+                limit: self.max_input_bytes,
+            });
+        }
+        Ok(())
+    }
+
+    /// Reject a record count above `max_records`
+    pub fn check_record_count(&self, count: usize) -> Result<(), ParseError> {
+        if count > self.max_records {
+            return Err(ParseError::TooManyRecords {
+                limit: self.max_records,
+            });
+        }
+        Ok(())
+    }
+
+    /// Reject nesting deeper than `max_depth`
+    pub fn check_depth(&self, depth: usize) -> Result<(), ParseError> {
+        if depth > self.max_depth {
+            return Err(ParseError::TooDeep {
+                limit: self.max_depth,
+            });
+        }
+        Ok(())
+    }
+
+    /// Reject a field longer than `max_field_length`
+    pub fn check_field(&self, field: &str, value: &str) -> Result<(), ParseError> {
+        if value.len() > self.max_field_length {
+            return Err(ParseError::FieldTooLong {
+                field: field.to_string(),
+                length: value.len(),
+                limit: self.max_field_length,
+            });
+        }
+        Ok(())
+    }
+
+    /// Reject a line longer than `max_line_length`
+    pub fn check_line(&self, line: usize, length: usize) -> Result<(), ParseError> {
+        if length > self.max_line_length {
+            return Err(ParseError::LineTooLong {
+                line,
+                length,
+                limit: self.max_line_length,
+            });
+        }
+        Ok(())
+    }
+
+    fn check_transaction(&self, transaction: &Transaction) -> Result<(), ParseError> {
+        self.check_field("transaction_id", &transaction.transaction_id)?;
+        self.check_field("currency", &transaction.currency)?;
+        self.check_field("user_id", &transaction.user_id)?;
+        if let Some(ref account) = transaction.from_account {
+            self.check_field("from_account", account)?;
+        }
+        if let Some(ref account) = transaction.to_account {
+            self.check_field("to_account", account)?;
+        }
+        if let Some(ref metadata) = transaction.metadata {
+            if metadata.len() > self.max_metadata_entries {
+                return Err(ParseError::TooManyEntries {
+                    field: "metadata".to_string(),
+                    count: metadata.len(),
+                    limit: self.max_metadata_entries,
+                });
+            }
+            for (key, value) in metadata.iter() {
+                self.check_field("metadata key", key)?;
+                self.check_field(key, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse a single JSON-encoded transaction
+pub fn parse_transaction_json(
+    input: &[u8],
+    limits: &ParseLimits,
+) -> Result<Transaction, ParseError> {
+    let text = limits.input_text(input)?;
+    let transaction = decode_transaction(text, 1)?;
+    limits.check_transaction(&transaction)?;
+    Ok(transaction)
+}
+
+/// Parse newline-delimited JSON transactions, skipping blank lines
+pub fn parse_transactions_jsonl(
+    input: &[u8],
+    limits: &ParseLimits,
+) -> Result<Vec<Transaction>, ParseError> {
+    let text = limits.input_text(input)?;
+
+    let mut transactions = Vec::new();
+    for (index, line) in text.split('\n').enumerate() {
+        let line_number = index + 1;
+        limits.check_line(line_number, line.len())?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        limits.check_record_count(transactions.len() + 1)?;
+        let transaction = decode_transaction(line, line_number)?;
+        limits.check_transaction(&transaction)?;
+        transactions.push(transaction);
+    }
+
+    Ok(transactions)
+}
+
+fn decode_transaction(text: &str, line: usize) -> Result<Transaction, ParseError> {
+    serde_json::from_str(text).map_err(|e| ParseError::Malformed {
+        line: line + e.line().saturating_sub(1),
+        message: e.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALID_JSON: &str = r#"{"transaction_id":"TXN-001","transaction_type":"Transfer","amount":1000.0,"currency":"USD","from_account":"ACCT-1234-5678-9012","to_account":"ACCT-6789-0123-4567","timestamp":"2024-11-06T12:00:00Z","user_id":"USER-001","metadata":null}"#;
+
+    #[test]
+    fn test_parse_valid_transaction() {
+        let transaction =
+            parse_transaction_json(VALID_JSON.as_bytes(), &ParseLimits::default()).unwrap();
+        assert_eq!(transaction.transaction_id, "TXN-001");
+        assert_eq!(transaction.amount, 1000.0);
+    }
+
+    #[test]
+    fn test_input_too_large() {
+        let limits = ParseLimits {
+            max_input_bytes: 16,
+            ..Default::default()
+        };
+        let result = parse_transaction_json(VALID_JSON.as_bytes(), &limits);
+        assert!(matches!(result, Err(ParseError::InputTooLarge { .. })));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        let limits = ParseLimits::default();
+        assert!(matches!(
+            parse_transaction_json(b"{\"transaction_id\":", &limits),
+            Err(ParseError::Malformed { .. })
+        ));
+        assert!(matches!(
+            parse_transaction_json(&[0xff, 0xfe, 0x00], &limits),
+            Err(ParseError::InvalidEncoding(_))
+        ));
+    }
+
+    #[test]
+    fn test_field_too_long() {
+        let limits = ParseLimits {
+            max_field_length: 4,
+            ..Default::default()
+        };
+        let result = parse_transaction_json(VALID_JSON.as_bytes(), &limits);
+        assert!(matches!(result, Err(ParseError::FieldTooLong { .. })));
+    }
+
+    #[test]
+    fn test_jsonl_record_limit() {
+        let input = format!("{}\n\n{}\n", VALID_JSON, VALID_JSON);
+        let parsed = parse_transactions_jsonl(input.as_bytes(), &ParseLimits::default()).unwrap();
+        assert_eq!(parsed.len(), 2);
+
+        let limits = ParseLimits {
+            max_records: 1,
+            ..Default::default()
+        };
+        let result = parse_transactions_jsonl(input.as_bytes(), &limits);
+        assert_eq!(result.unwrap_err(), ParseError::TooManyRecords { limit: 1 });
+    }
+
+    #[test]
+    fn test_read_file_checks_size_first() {
+        let path = std::env::temp_dir().join(format!("parse-limits-{}.json", std::process::id()));
+        std::fs::write(&path, VALID_JSON).unwrap();
+        let limits = ParseLimits {
+            max_input_bytes: 16,
+            ..Default::default()
+        };
+        let result = limits.read_file(&path);
+        assert!(matches!(result, Err(ParseError::InputTooLarge { .. })));
+        let bytes = ParseLimits::default().read_file(&path).unwrap();
+        assert_eq!(bytes, VALID_JSON.as_bytes());
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(limits.read_file(&path), Err(ParseError::Io(_))));
+    }
+
+    #[test]
+    fn test_jsonl_reports_line_number() {
+        let input = format!("{}\nnot json\n", VALID_JSON);
+        let result = parse_transactions_jsonl(input.as_bytes(), &ParseLimits::default());
+        assert!(matches!(result, Err(ParseError::Malformed { line: 2, .. })));
+    }
+}
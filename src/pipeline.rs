@@ -0,0 +1,272 @@
+//! Unified validation pipeline
+//!
+//! [`ValidationPipeline`] owns one [`TransactionValidator`] with every
+//! analysis module attached — fraud detection, AML rules, sanctions
+//! screening, geographic risk, and network analysis — so a single call
+//! screens a transaction end to end and returns one combined decision.
+//! Each module's findings surface as errors or warnings on the result.
+//!
+//! [`ValidationPipeline::report`] additionally returns every module's own
+//! output as a serializable [`ComprehensiveReport`] for case files and audits.
+
+use crate::{
+    AMLChecker, AMLResult, Decision, FraudDetector, FraudScore, GeographicRiskScorer, Jurisdiction,
+    NetworkAnalysisReport, NetworkAnalyzer, SanctionsResult, SanctionsScreener, Transaction,
+    TransactionGeographicRisk, TransactionValidator, TransactionValidatorBuilder, ValidationResult,
+    ValidatorConfig,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Every module's output for one transaction, with one combined verdict
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComprehensiveReport {
+    pub transaction_id: String,
+    /// The validator's decision, which already accounts for every module
+    pub decision: Decision,
+    /// Highest risk score reported by the validator or any module (0-100)
+    pub risk_score: u8,
+    /// Error and warning codes, then AML filing requirements, without repeats
+    pub reason_codes: Vec<String>,
+    pub validation: ValidationResult,
+    pub fraud: Option<FraudScore>,
+    pub aml: Option<AMLResult>,
+    /// One result per screened counterparty name
+    pub sanctions: Vec<SanctionsResult>,
+    pub geography: Option<TransactionGeographicRisk>,
+    /// Network patterns involving the transaction's accounts
+    pub network: Option<NetworkAnalysisReport>,
+    /// Regulatory regime the validator was configured for
+    #[serde(default)]
+    pub jurisdiction: Option<Jurisdiction>,
+    /// Date until which this record must be kept
+    #[serde(default)]
+    pub retain_until: Option<DateTime<Utc>>,
+}
+
+/// Transaction validator with every analysis module wired in
+pub struct ValidationPipeline {
+    validator: TransactionValidator,
+}
+
+impl ValidationPipeline {
+    /// Pipeline with default configuration and default modules
+    pub fn new() -> Self {
+        Self::with_config(ValidatorConfig::default())
+    }
+
+    /// Pipeline with custom configuration and default modules
+    pub fn with_config(config: ValidatorConfig) -> Self {
+        Self::from_builder(
+            TransactionValidator::builder()
+                .with_config(config)
+                .with_sanctions(SanctionsScreener::new())
+                .with_geo(GeographicRiskScorer::new())
+                .with_fraud_detector(FraudDetector::new())
+                .with_aml(AMLChecker::new())
+                .with_network(NetworkAnalyzer::new()),
+        )
+    }
+
+    /// Pipeline around a validator assembled with custom modules
+    pub fn from_builder(builder: TransactionValidatorBuilder) -> Self {
+        Self {
+            validator: builder.build(),
+        }
+    }
+
+    /// Run every module on one transaction and return the combined result
+    pub fn validate(&mut self, transaction: &Transaction) -> ValidationResult {
+        self.validator.validate(transaction)
+    }
+
+    /// Run every module on one transaction and collect each module's output
+    ///
+    /// Module outputs are computed against the state before the transaction
+    /// is committed, except network findings, which include its transfer.
+    pub fn report(&mut self, transaction: &Transaction) -> ComprehensiveReport {
+        let validator = &self.validator;
+        let metadata = transaction.metadata.as_ref();
+
+        let fraud = validator.fraud_detector().map(|d| d.assess(transaction));
+        let aml = validator
+            .aml_checker()
+            .map(|c| c.check_compliance(transaction));
+        // Backend failures are already reported as validation errors
+        let sanctions = validator.screening_backend().map_or(Vec::new(), |backend| {
+            metadata
+                .into_iter()
+                .flat_map(|m| m.counterparty_names())
+                .filter_map(|name| backend.screen(name).ok())
+                .collect()
+        });
+        let geography = validator.geographic_scorer().and_then(|scorer| {
+            let origin = metadata.and_then(|m| m.country.as_deref());
+            let destination = metadata.and_then(|m| m.destination_country.as_deref());
+            let origin = origin.or(destination)?;
+            Some(scorer.calculate_transaction_risk(origin, destination.unwrap_or(origin)))
+        });
+
+        let validation = self.validator.validate(transaction);
+
+        let network = self.validator.network_analyzer().and_then(|analyzer| {
+            let accounts: Vec<&str> = [&transaction.from_account, &transaction.to_account]
+                .into_iter()
+                .filter_map(|a| a.as_deref())
+                .collect();
+            (!accounts.is_empty()).then(|| analyzer.analyze_all().for_accounts(&accounts))
+        });
+
+        let risk_score = [
+            Some(validation.fraud_score),
+            fraud.as_ref().map(|f| f.score),
+            aml.as_ref().map(|a| a.risk_score),
+            geography.as_ref().map(|g| g.combined_score),
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+        .unwrap_or(0);
+
+        let mut reason_codes = validation.reason_codes();
+        reason_codes.extend(validation.warning_codes());
+        if let Some(ref aml) = aml {
+            if aml.requires_ctr {
+                reason_codes.push("CTR_REQUIRED".to_string());
+            }
+            if aml.requires_sar {
+                reason_codes.push("SAR_REQUIRED".to_string());
+            }
+        }
+        let mut seen = std::collections::HashSet::new();
+        reason_codes.retain(|code| seen.insert(code.clone()));
+
+        let config = &validation.effective_config;
+        let jurisdiction = config.jurisdiction;
+        let retain_until = config
+            .record_retention_days
+            .and_then(Duration::try_days)
+            .and_then(|retention| validation.validated_at.checked_add_signed(retention));
+
+        ComprehensiveReport {
+            transaction_id: transaction.transaction_id.clone(),
+            decision: validation.decision,
+            risk_score,
+            reason_codes,
+            validation,
+            fraud,
+            aml,
+            sanctions,
+            geography,
+            network,
+            jurisdiction,
+            retain_until,
+        }
+    }
+
+    /// Run every module on each transaction in order
+    pub fn validate_batch(&mut self, transactions: &[Transaction]) -> Vec<ValidationResult> {
+        self.validator.validate_batch(transactions)
+    }
+
+    /// Graph-wide suspicious patterns across all committed transfers
+    pub fn network_report(&self) -> Option<NetworkAnalysisReport> {
+        self.validator
+            .network_analyzer()
+            .map(NetworkAnalyzer::analyze_all)
+    }
+
+    /// The underlying validator
+    pub fn validator(&self) -> &TransactionValidator {
+        &self.validator
+    }
+
+    /// The underlying validator, for hooks, alerts, and other settings
+    pub fn validator_mut(&mut self) -> &mut TransactionValidator {
+        &mut self.validator
+    }
+}
+
+impl Default for ValidationPipeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{Decision, TransactionMetadata, ValidationError};
+
+    #[test]
+    fn test_pipeline_combines_modules() {
+        let mut pipeline = ValidationPipeline::new();
+
+        let clean = pipeline.validate(&create_test_transaction("TXN-PIPE-1", 250.0));
+        assert!(clean.is_valid);
+        assert!(pipeline.network_report().is_some());
+
+        // Just under the CTR threshold: AML structuring flag
+        let structured = pipeline.validate(&create_test_transaction("TXN-PIPE-2", 9800.0));
+        assert!(structured
+            .warning_codes()
+            .contains(&"AML_RED_FLAG".to_string()));
+
+        let mut sanctioned = create_test_transaction("TXN-PIPE-3", 250.0);
+        sanctioned.metadata = Some(TransactionMetadata::from([(
+            "beneficiary_name".to_string(),
+            "SANCTIONED ENTITY ONE".to_string(),
+        )]));
+        let result = pipeline.validate(&sanctioned);
+        assert_eq!(result.decision, Decision::Decline);
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::SanctionsMatch(_))));
+    }
+
+    #[test]
+    fn test_pipeline_declines_aml_sanctioned_account() {
+        let mut pipeline = ValidationPipeline::from_builder(
+            TransactionValidator::builder()
+                .with_config(ValidatorConfig {
+                    enable_account_format_check: false,
+                    ..Default::default()
+                })
+                .with_aml(AMLChecker::new()),
+        );
+        let mut transaction = create_test_transaction("TXN-PIPE-4", 250.0);
+        transaction.to_account = Some("OFAC-SANCTIONED-001".to_string());
+
+        let result = pipeline.validate(&transaction);
+        assert_eq!(result.decision, Decision::Decline);
+        assert_eq!(result.compliance_checks.get("AML"), Some(&false));
+    }
+
+    #[test]
+    fn test_report_collects_module_outputs() {
+        let mut pipeline = ValidationPipeline::new();
+        let mut transaction = create_test_transaction("TXN-PIPE-5", 9800.0);
+        transaction.metadata = Some(TransactionMetadata::from([
+            ("beneficiary_name".to_string(), "ACME TRADING".to_string()),
+            ("country".to_string(), "US".to_string()),
+            ("destination_country".to_string(), "GB".to_string()),
+        ]));
+
+        let report = pipeline.report(&transaction);
+        assert_eq!(report.decision, report.validation.decision);
+        assert!(report.fraud.is_some());
+        assert!(report.aml.as_ref().unwrap().requires_sar);
+        assert_eq!(report.sanctions.len(), 1);
+        assert_eq!(report.geography.as_ref().unwrap().destination_country, "GB");
+        assert!(report.network.is_some());
+        assert!(report.reason_codes.contains(&"AML_RED_FLAG".to_string()));
+        assert!(report.reason_codes.contains(&"SAR_REQUIRED".to_string()));
+        assert!(report.risk_score >= report.aml.as_ref().unwrap().risk_score);
+
+        let json = serde_json::to_string(&report).unwrap();
+        let parsed: ComprehensiveReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.reason_codes, report.reason_codes);
+    }
+}
@@ -0,0 +1,283 @@
+//! Policy versioning
+//!
+//! A decision depends on the base configuration, every override layer
+//! (segments, channels, limit profiles and who is assigned to them), and
+//! the rule logic compiled into this crate. [`PolicySnapshot`] captures all
+//! of them and [`PolicySnapshot::version`] hashes the snapshot into the
+//! identifier recorded on every [`ValidationResult`](crate::ValidationResult),
+//! audit record, and decision record, so a historical decision can be
+//! matched to the exact policy that produced it.
+//!
+//! Every snapshot a validator runs under is kept in its [`PolicyArchive`].
+//! [`TransactionValidator::replay`](crate::TransactionValidator::replay)
+//! re-runs a past transaction under an archived version on a fresh validator
+//! whose clock is fixed at the transaction's timestamp, so the same inputs
+//! always produce the same decision. The replaying validator starts from
+//! this validator's history and watchlist as they stood at that timestamp.
+//! Attached components such as a fraud detector or custom rules keep logic
+//! and state a snapshot cannot capture, so a version recorded with any of
+//! them is refused rather than replayed without them, as is a transaction
+//! whose lookback has been pruned from history.
+
+use crate::{
+    Channel, ChannelPolicy, ConfigOverride, CustomerSegment, LimitProfile, ValidatorConfig,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Version of the rule logic, bumped with the crate
+pub const RULES_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Everything that determines how a validator decides
+///
+/// Layers are kept sorted so equal policies serialize, and hash, equally.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicySnapshot {
+    pub rules_version: String,
+    pub config: ValidatorConfig,
+    pub segment_overrides: Vec<(CustomerSegment, ConfigOverride)>,
+    pub channel_policies: Vec<(Channel, ChannelPolicy)>,
+    pub limit_profiles: Vec<(String, LimitProfile)>,
+    /// Users held to a limit profile, as (user, profile)
+    #[serde(default)]
+    pub limit_assignments: Vec<(String, String)>,
+    /// Attached components and extensions whose logic the snapshot does not
+    /// capture, by name
+    #[serde(default)]
+    pub components: Vec<String>,
+}
+
+impl PolicySnapshot {
+    pub(crate) fn new<'a>(
+        config: &ValidatorConfig,
+        segment_overrides: impl Iterator<Item = (&'a CustomerSegment, &'a ConfigOverride)>,
+        channel_policies: impl Iterator<Item = (&'a Channel, &'a ChannelPolicy)>,
+        limit_profiles: impl Iterator<Item = (&'a str, &'a LimitProfile)>,
+        limit_assignments: impl Iterator<Item = (&'a str, &'a str)>,
+        mut components: Vec<String>,
+    ) -> Self {
+        let mut segment_overrides: Vec<(CustomerSegment, ConfigOverride)> = segment_overrides
+            .map(|(segment, layer)| (*segment, layer.clone()))
+            .collect();
+        segment_overrides.sort_by_key(|(segment, _)| format!("{:?}", segment));
+        let mut channel_policies: Vec<(Channel, ChannelPolicy)> = channel_policies
+            .map(|(channel, policy)| (*channel, policy.clone()))
+            .collect();
+        channel_policies.sort_by_key(|(channel, _)| format!("{:?}", channel));
+        let mut limit_profiles: Vec<(String, LimitProfile)> = limit_profiles
+            .map(|(name, profile)| (name.to_string(), profile.clone()))
+            .collect();
+        limit_profiles.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut limit_assignments: Vec<(String, String)> = limit_assignments
+            .map(|(user, profile)| (user.to_string(), profile.to_string()))
+            .collect();
+        limit_assignments.sort();
+        components.sort();
+
+        Self {
+            rules_version: RULES_VERSION.to_string(),
+            config: config.clone(),
+            segment_overrides,
+            channel_policies,
+            limit_profiles,
+            limit_assignments,
+            components,
+        }
+    }
+
+    /// Stable identifier: SHA-256 of the serialized snapshot
+    pub fn version(&self) -> String {
+        let encoded = serde_json::to_vec(self).unwrap_or_default();
+        let digest = Sha256::digest(&encoded);
+        digest.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+/// Replay errors
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReplayError {
+    #[error("Unknown policy version: {0}")]
+    UnknownVersion(String),
+
+    #[error("Policy was recorded under rules {archived}, running {current}")]
+    RulesVersionMismatch { archived: String, current: String },
+
+    #[error("Policy depends on components replay cannot reproduce: {}", .0.join(", "))]
+    UnreproducibleComponents(Vec<String>),
+
+    #[error("History needed from {needed_from} was pruned before {pruned_before}")]
+    HistoryPruned {
+        needed_from: DateTime<Utc>,
+        pruned_before: DateTime<Utc>,
+    },
+}
+
+/// Policy snapshots by version
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PolicyArchive {
+    snapshots: HashMap<String, PolicySnapshot>,
+}
+
+impl PolicyArchive {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store a snapshot, returning its version
+    pub fn insert(&mut self, snapshot: PolicySnapshot) -> String {
+        let version = snapshot.version();
+        self.snapshots.entry(version.clone()).or_insert(snapshot);
+        version
+    }
+
+    pub fn get(&self, version: &str) -> Option<&PolicySnapshot> {
+        self.snapshots.get(version)
+    }
+
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// Look up a version that the running rules can replay
+    pub fn replayable(&self, version: &str) -> Result<&PolicySnapshot, ReplayError> {
+        let snapshot = self
+            .get(version)
+            .ok_or_else(|| ReplayError::UnknownVersion(version.to_string()))?;
+        if snapshot.rules_version != RULES_VERSION {
+            return Err(ReplayError::RulesVersionMismatch {
+                archived: snapshot.rules_version.clone(),
+                current: RULES_VERSION.to_string(),
+            });
+        }
+        Ok(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{DecisionRecord, TransactionMetadata, TransactionType, TransactionValidator};
+
+    #[test]
+    fn test_results_carry_the_policy_version() {
+        let mut validator = TransactionValidator::new();
+        let tx = create_test_transaction("TXN-POL-1", 100.0);
+        let before = validator.validate(&tx);
+        assert_eq!(before.policy_version, validator.policy_version());
+        assert_eq!(before.policy_version, validator.policy_snapshot().version());
+        let record = DecisionRecord::new(&tx, &before, &before.policy_version);
+        assert_eq!(record.config_version, before.policy_version);
+
+        // Changing any override layer changes the version
+        validator.set_channel_policy(Channel::Atm, ChannelPolicy::atm(500.0));
+        let after = validator.validate(&create_test_transaction("TXN-POL-2", 100.0));
+        assert_ne!(after.policy_version, before.policy_version);
+        validator.set_channel_policy(Channel::Branch, ChannelPolicy::atm(2000.0));
+
+        // Equal policies built in a different order share a version
+        let mut other = TransactionValidator::new();
+        other.set_channel_policy(Channel::Branch, ChannelPolicy::atm(2000.0));
+        other.set_channel_policy(Channel::Atm, ChannelPolicy::atm(500.0));
+        assert_eq!(other.policy_version(), validator.policy_version());
+    }
+
+    #[test]
+    fn test_replay_reproduces_decision_under_archived_policy() {
+        let mut validator = TransactionValidator::new();
+        let mut tx = create_test_transaction("TXN-POL-3", 800.0);
+        tx.transaction_type = TransactionType::Withdrawal;
+        tx.metadata = Some(TransactionMetadata::from([(
+            "channel".to_string(),
+            "ATM".to_string(),
+        )]));
+        let original = validator.validate(&tx);
+        assert!(original.is_valid);
+
+        // Tightening the ATM cap afterwards must not change the replay
+        validator.set_channel_policy(Channel::Atm, ChannelPolicy::atm(500.0));
+        let mut later = tx.clone();
+        later.transaction_id = "TXN-POL-4".to_string();
+        assert!(!validator.validate(&later).is_valid);
+
+        let replayed = validator.replay(&tx, &original.policy_version).unwrap();
+        assert!(replayed.is_valid);
+        assert_eq!(replayed.decision, original.decision);
+        assert_eq!(replayed.policy_version, original.policy_version);
+        assert_eq!(replayed.validated_at, tx.timestamp);
+        assert_eq!(validator.policy_archive().len(), 2);
+
+        assert_eq!(
+            validator.replay(&tx, "missing").unwrap_err(),
+            ReplayError::UnknownVersion("missing".to_string())
+        );
+    }
+
+    #[test]
+    fn test_replay_sees_history_as_of_the_transaction() {
+        let mut validator = TransactionValidator::with_config(crate::ValidatorConfig {
+            max_transactions_per_window: 2,
+            ..Default::default()
+        });
+        let first = create_test_transaction("TXN-POL-H-1", 100.0);
+        validator.validate(&first);
+        validator.validate(&create_test_transaction("TXN-POL-H-2", 100.0));
+        let third = create_test_transaction("TXN-POL-H-3", 100.0);
+        let original = validator.validate(&third);
+        assert!(!original.is_valid);
+
+        let replayed = validator.replay(&third, &original.policy_version).unwrap();
+        assert_eq!(replayed.decision, original.decision);
+        assert!(!replayed.is_valid);
+        let replayed = validator.replay(&first, &original.policy_version).unwrap();
+        assert!(replayed.is_valid);
+
+        // Pruning the velocity window away makes replay refuse
+        validator.clear_old_history(third.timestamp + chrono::Duration::hours(1));
+        assert!(matches!(
+            validator.replay(&third, &original.policy_version),
+            Err(ReplayError::HistoryPruned { .. })
+        ));
+    }
+
+    #[test]
+    fn test_replay_keeps_limit_profile_assignments() {
+        let mut validator = TransactionValidator::new();
+        validator.assign_limit_profile("USER-001", "new_customer");
+        let tx = create_test_transaction("TXN-POL-L-1", 8000.0);
+        let original = validator.validate(&tx);
+        assert!(!original.is_valid);
+        assert_eq!(
+            validator.policy_snapshot().limit_assignments,
+            vec![("USER-001".to_string(), "new_customer".to_string())]
+        );
+
+        let replayed = validator.replay(&tx, &original.policy_version).unwrap();
+        assert!(!replayed.is_valid);
+        assert_eq!(replayed.policy_version, original.policy_version);
+    }
+
+    #[test]
+    fn test_replay_refuses_policies_with_attached_components() {
+        let mut validator = TransactionValidator::new();
+        let bare = validator.policy_version().to_string();
+        validator.set_aml_checker(crate::AMLChecker::new());
+        assert_ne!(validator.policy_version(), bare);
+        assert_eq!(validator.policy_snapshot().components, vec!["aml_checker"]);
+
+        let tx = create_test_transaction("TXN-POL-C-1", 100.0);
+        let original = validator.validate(&tx);
+        assert_eq!(
+            validator.replay(&tx, &original.policy_version).unwrap_err(),
+            ReplayError::UnreproducibleComponents(vec!["aml_checker".to_string()])
+        );
+        assert!(validator.replay(&tx, &bare).is_ok());
+    }
+}
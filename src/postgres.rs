@@ -0,0 +1,388 @@
+//! Postgres audit sink
+//!
+//! Writes [`AuditRecord`] batches to the normalized tables in
+//! [`POSTGRES_SCHEMA`], one multi-row `INSERT` per table inside a single
+//! transaction. Errors, warnings, compliance checks, and alerts reference
+//! their `validation_results` row by `(transaction_id, validated_at)` and
+//! are indexed on it.
+//!
+//! The `postgres-sql` feature adds no database driver, sqlx included; the
+//! sink only builds SQL. Implement [`PgExecutor`] over the application's client
+//! (sqlx, tokio-postgres, postgres) so the sink shares its connection pool
+//! and TLS setup.
+
+use crate::{AuditRecord, AuditSink};
+use chrono::{DateTime, Utc};
+use std::io;
+
+/// Tables written by [`PostgresAuditSink`]
+pub const POSTGRES_SCHEMA: &str = "\
+CREATE TABLE IF NOT EXISTS validation_results (
+    transaction_id TEXT NOT NULL,
+    user_id_hash TEXT NOT NULL,
+    from_account_hash TEXT,
+    to_account_hash TEXT,
+    amount DOUBLE PRECISION NOT NULL,
+    currency TEXT NOT NULL,
+    decision TEXT NOT NULL,
+    is_valid BOOLEAN NOT NULL,
+    fraud_score SMALLINT NOT NULL,
+    tenant_id TEXT,
+    committed BOOLEAN NOT NULL,
+    config_version TEXT NOT NULL,
+    validated_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (transaction_id, validated_at)
+);
+CREATE TABLE IF NOT EXISTS validation_errors (
+    transaction_id TEXT NOT NULL,
+    reason_code TEXT NOT NULL,
+    code INTEGER NOT NULL,
+    message TEXT NOT NULL,
+    validated_at TIMESTAMPTZ NOT NULL,
+    FOREIGN KEY (transaction_id, validated_at)
+        REFERENCES validation_results (transaction_id, validated_at) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS validation_errors_result
+    ON validation_errors (transaction_id, validated_at);
+CREATE TABLE IF NOT EXISTS validation_warnings (
+    transaction_id TEXT NOT NULL,
+    code TEXT NOT NULL,
+    severity TEXT NOT NULL,
+    message TEXT NOT NULL,
+    validated_at TIMESTAMPTZ NOT NULL,
+    FOREIGN KEY (transaction_id, validated_at)
+        REFERENCES validation_results (transaction_id, validated_at) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS validation_warnings_result
+    ON validation_warnings (transaction_id, validated_at);
+CREATE TABLE IF NOT EXISTS compliance_checks (
+    transaction_id TEXT NOT NULL,
+    check_name TEXT NOT NULL,
+    passed BOOLEAN NOT NULL,
+    validated_at TIMESTAMPTZ NOT NULL,
+    FOREIGN KEY (transaction_id, validated_at)
+        REFERENCES validation_results (transaction_id, validated_at) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS compliance_checks_result
+    ON compliance_checks (transaction_id, validated_at);
+CREATE TABLE IF NOT EXISTS validation_alerts (
+    event_id TEXT PRIMARY KEY,
+    transaction_id TEXT NOT NULL,
+    trigger TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    validated_at TIMESTAMPTZ NOT NULL,
+    FOREIGN KEY (transaction_id, validated_at)
+        REFERENCES validation_results (transaction_id, validated_at) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS validation_alerts_result
+    ON validation_alerts (transaction_id, validated_at);
+";
+
+/// Postgres bind parameter limit per statement
+const MAX_PARAMS: usize = 65_535;
+
+/// Bind parameter passed to [`PgExecutor::execute`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum PgValue {
+    Text(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Timestamp(DateTime<Utc>),
+    Null,
+}
+
+impl From<Option<String>> for PgValue {
+    fn from(value: Option<String>) -> Self {
+        value.map_or(PgValue::Null, PgValue::Text)
+    }
+}
+
+/// Minimal statement executor backed by the application's Postgres client
+pub trait PgExecutor: Send {
+    /// Run one statement with `$1`-style parameters, returning rows affected
+    fn execute(&mut self, sql: &str, params: &[PgValue]) -> io::Result<u64>;
+}
+
+/// Audit sink writing to the tables in [`POSTGRES_SCHEMA`]
+pub struct PostgresAuditSink<E: PgExecutor> {
+    executor: E,
+}
+
+impl<E: PgExecutor> PostgresAuditSink<E> {
+    /// Sink over an executor; run [`POSTGRES_SCHEMA`] once beforehand
+    pub fn new(executor: E) -> Self {
+        Self { executor }
+    }
+
+    /// Create the audit tables if they do not exist
+    pub fn create_schema(&mut self) -> io::Result<()> {
+        self.executor.execute(POSTGRES_SCHEMA, &[]).map(|_| ())
+    }
+
+    /// Consume the sink and return the executor
+    pub fn into_inner(self) -> E {
+        self.executor
+    }
+
+    /// Insert rows in as few statements as the parameter limit allows
+    fn insert(&mut self, table: &str, columns: &[&str], rows: Vec<Vec<PgValue>>) -> io::Result<()> {
+        let rows_per_statement = (MAX_PARAMS / columns.len()).max(1);
+        for chunk in rows.chunks(rows_per_statement) {
+            let (sql, params) = insert_statement(table, columns, chunk);
+            self.executor.execute(&sql, &params)?;
+        }
+        Ok(())
+    }
+
+    fn write_all(&mut self, records: &[AuditRecord]) -> io::Result<()> {
+        let results = records
+            .iter()
+            .map(|r| {
+                let row = &r.result;
+                vec![
+                    PgValue::Text(row.transaction_id.clone()),
+                    PgValue::Text(row.user_id_hash.clone()),
+                    row.from_account_hash.clone().into(),
+                    row.to_account_hash.clone().into(),
+                    PgValue::Float(row.amount),
+                    PgValue::Text(row.currency.clone()),
+                    PgValue::Text(row.decision.clone()),
+                    PgValue::Bool(row.is_valid),
+                    PgValue::Int(row.fraud_score as i64),
+                    row.tenant_id.clone().into(),
+                    PgValue::Bool(row.committed),
+                    PgValue::Text(row.config_version.clone()),
+                    PgValue::Timestamp(row.validated_at),
+                ]
+            })
+            .collect();
+        self.insert(
+            "validation_results",
+            &[
+                "transaction_id",
+                "user_id_hash",
+                "from_account_hash",
+                "to_account_hash",
+                "amount",
+                "currency",
+                "decision",
+                "is_valid",
+                "fraud_score",
+                "tenant_id",
+                "committed",
+                "config_version",
+                "validated_at",
+            ],
+            results,
+        )?;
+
+        // Child rows carry their result's key for the foreign key
+        let validated_at = |r: &AuditRecord| PgValue::Timestamp(r.result.validated_at);
+
+        let errors = records
+            .iter()
+            .flat_map(|r| r.errors.iter().map(move |e| (r, e)))
+            .map(|(r, e)| {
+                vec![
+                    PgValue::Text(e.transaction_id.clone()),
+                    PgValue::Text(e.reason_code.clone()),
+                    PgValue::Int(e.code as i64),
+                    PgValue::Text(e.message.clone()),
+                    validated_at(r),
+                ]
+            })
+            .collect();
+        self.insert(
+            "validation_errors",
+            &[
+                "transaction_id",
+                "reason_code",
+                "code",
+                "message",
+                "validated_at",
+            ],
+            errors,
+        )?;
+
+        let warnings = records
+            .iter()
+            .flat_map(|r| r.warnings.iter().map(move |w| (r, w)))
+            .map(|(r, w)| {
+                vec![
+                    PgValue::Text(w.transaction_id.clone()),
+                    PgValue::Text(w.code.clone()),
+                    PgValue::Text(w.severity.clone()),
+                    PgValue::Text(w.message.clone()),
+                    validated_at(r),
+                ]
+            })
+            .collect();
+        self.insert(
+            "validation_warnings",
+            &[
+                "transaction_id",
+                "code",
+                "severity",
+                "message",
+                "validated_at",
+            ],
+            warnings,
+        )?;
+
+        let checks = records
+            .iter()
+            .flat_map(|r| r.compliance_checks.iter().map(move |c| (r, c)))
+            .map(|(r, c)| {
+                vec![
+                    PgValue::Text(c.transaction_id.clone()),
+                    PgValue::Text(c.check_name.clone()),
+                    PgValue::Bool(c.passed),
+                    validated_at(r),
+                ]
+            })
+            .collect();
+        self.insert(
+            "compliance_checks",
+            &["transaction_id", "check_name", "passed", "validated_at"],
+            checks,
+        )?;
+
+        let alerts = records
+            .iter()
+            .flat_map(|r| r.alerts.iter().map(move |a| (r, a)))
+            .map(|(r, a)| {
+                vec![
+                    PgValue::Text(a.event_id.clone()),
+                    PgValue::Text(a.transaction_id.clone()),
+                    PgValue::Text(a.trigger.clone()),
+                    PgValue::Timestamp(a.created_at),
+                    validated_at(r),
+                ]
+            })
+            .collect();
+        self.insert(
+            "validation_alerts",
+            &[
+                "event_id",
+                "transaction_id",
+                "trigger",
+                "created_at",
+                "validated_at",
+            ],
+            alerts,
+        )
+    }
+}
+
+impl<E: PgExecutor> AuditSink for PostgresAuditSink<E> {
+    fn write_batch(&mut self, records: &[AuditRecord]) -> io::Result<()> {
+        self.executor.execute("BEGIN", &[])?;
+        match self.write_all(records) {
+            Ok(()) => self.executor.execute("COMMIT", &[]).map(|_| ()),
+            Err(e) => {
+                let _ = self.executor.execute("ROLLBACK", &[]);
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Multi-row `INSERT` with numbered placeholders
+fn insert_statement(
+    table: &str,
+    columns: &[&str],
+    rows: &[Vec<PgValue>],
+) -> (String, Vec<PgValue>) {
+    let mut params = Vec::with_capacity(rows.len() * columns.len());
+    let values: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            let placeholders: Vec<String> = row
+                .iter()
+                .map(|value| {
+                    params.push(value.clone());
+                    format!("${}", params.len())
+                })
+                .collect();
+            format!("({})", placeholders.join(", "))
+        })
+        .collect();
+    let sql = format!(
+        "INSERT INTO {} ({}) VALUES {}",
+        table,
+        columns.join(", "),
+        values.join(", ")
+    );
+    (sql, params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::TransactionValidator;
+
+    #[derive(Default)]
+    struct RecordingExecutor {
+        statements: Vec<(String, usize)>,
+    }
+
+    impl PgExecutor for RecordingExecutor {
+        fn execute(&mut self, sql: &str, params: &[PgValue]) -> io::Result<u64> {
+            self.statements.push((sql.to_string(), params.len()));
+            Ok(0)
+        }
+    }
+
+    #[test]
+    fn test_batch_is_one_insert_per_table_in_a_transaction() {
+        let validator = &mut TransactionValidator::new();
+        let records: Vec<AuditRecord> = [("TXN-PG-1", 100.0), ("TXN-PG-2", -5.0)]
+            .iter()
+            .map(|(id, amount)| {
+                let tx = create_test_transaction(id, *amount);
+                AuditRecord::new(&tx, &validator.validate(&tx), &[], "v1")
+            })
+            .collect();
+
+        let mut sink = PostgresAuditSink::new(RecordingExecutor::default());
+        sink.write_batch(&records).unwrap();
+        let statements = sink.into_inner().statements;
+
+        assert_eq!(statements.first().unwrap().0, "BEGIN");
+        assert_eq!(statements.last().unwrap().0, "COMMIT");
+        let results = &statements[1];
+        assert!(results.0.starts_with("INSERT INTO validation_results"));
+        assert!(results.0.ends_with("$26)"));
+        assert_eq!(results.1, 26);
+        // Child rows carry the result key their foreign key references
+        let (errors, params) = statements
+            .iter()
+            .find(|(sql, _)| sql.starts_with("INSERT INTO validation_errors"))
+            .unwrap();
+        assert!(errors.contains("message, validated_at)"));
+        assert_eq!(params % 5, 0);
+        for table in [
+            "validation_errors",
+            "validation_warnings",
+            "compliance_checks",
+            "validation_alerts",
+        ] {
+            let definition = POSTGRES_SCHEMA
+                .split("CREATE TABLE IF NOT EXISTS ")
+                .find(|t| t.starts_with(table))
+                .unwrap();
+            assert!(
+                definition.contains("REFERENCES validation_results"),
+                "{}",
+                table
+            );
+            assert!(definition.contains(&format!("ON {} (transaction_id", table)));
+        }
+        // Rows with no children produce no statement
+        assert!(!statements
+            .iter()
+            .any(|(sql, _)| sql.starts_with("INSERT INTO validation_alerts")));
+    }
+}
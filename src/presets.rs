@@ -0,0 +1,122 @@
+//! Built-in configuration presets
+//!
+//! Named policy baselines to start from instead of the single default.
+//! Every preset passes [`ValidatorConfig::validate`].
+//!
+//! | Preset            | Max amount | Fraud threshold | Velocity limit        | Short-circuit | Content dedup | Commit   |
+//! |-------------------|-----------:|----------------:|-----------------------|:-------------:|:-------------:|----------|
+//! | `strict`          |    250,000 |              50 | 5 tx / 25k per hour   | yes           | 5 min         | always   |
+//! | `standard`        |  1,000,000 |              70 | 10 tx / 100k per hour | no            | off           | on valid |
+//! | `permissive`      |  5,000,000 |              85 | 50 tx / 1M per hour   | no            | off           | on valid |
+//! | `crypto_exchange` | 10,000,000 |              60 | 20 tx / 500k per 15m  | yes           | 1 min         | always   |
+//! | `retail_bank`     |     50,000 |              70 | 25 tx / 20k per day   | yes           | 5 min         | on valid |
+//!
+//! `crypto_exchange` additionally derives velocity limits from each user's
+//! 30-day baseline and assigns no time-of-day risk, since markets run 24/7.
+
+use crate::{AdaptiveVelocity, CommitPolicy, TimeRiskProfile, ValidatorConfig};
+
+impl ValidatorConfig {
+    /// Low limits and early declines for high-risk portfolios
+    ///
+    /// Also warns on repeated payments within an hour, and records rejected
+    /// attempts so retries count toward velocity.
+    pub fn strict() -> Self {
+        Self {
+            max_transaction_amount: 250_000.0,
+            fraud_threshold: 50,
+            max_transactions_per_window: 5,
+            max_amount_per_window: 25_000.0,
+            short_circuit_hard_fails: true,
+            fingerprint_bucket_seconds: Some(300),
+            double_payment_window_minutes: Some(60),
+            commit_policy: CommitPolicy::Always,
+            ..Default::default()
+        }
+    }
+
+    /// The default configuration
+    pub fn standard() -> Self {
+        Self::default()
+    }
+
+    /// High limits for trusted, high-volume flows where false positives cost more
+    pub fn permissive() -> Self {
+        Self {
+            max_transaction_amount: 5_000_000.0,
+            fraud_threshold: 85,
+            max_transactions_per_window: 50,
+            max_amount_per_window: 1_000_000.0,
+            ..Default::default()
+        }
+    }
+
+    /// Fractional amounts, short velocity windows, and adaptive per-user limits
+    pub fn crypto_exchange() -> Self {
+        Self {
+            max_transaction_amount: 10_000_000.0,
+            min_transaction_amount: 0.000_000_01,
+            fraud_threshold: 60,
+            velocity_check_window_minutes: 15,
+            max_transactions_per_window: 20,
+            max_amount_per_window: 500_000.0,
+            short_circuit_hard_fails: true,
+            adaptive_velocity: Some(AdaptiveVelocity {
+                baseline_days: 30,
+                min_transactions_per_window: 20,
+                min_amount_per_window: 500_000.0,
+                max_amount_per_window: 5_000_000.0,
+                ..Default::default()
+            }),
+            fingerprint_bucket_seconds: Some(60),
+            commit_policy: CommitPolicy::Always,
+            time_risk_profile: TimeRiskProfile::always_open(),
+            ..Default::default()
+        }
+    }
+
+    /// Consumer banking: modest per-transaction limits and daily velocity
+    ///
+    /// Also warns on repeated payments to one beneficiary within a day.
+    pub fn retail_bank() -> Self {
+        Self {
+            max_transaction_amount: 50_000.0,
+            velocity_check_window_minutes: 24 * 60,
+            max_transactions_per_window: 25,
+            max_amount_per_window: 20_000.0,
+            short_circuit_hard_fails: true,
+            fingerprint_bucket_seconds: Some(300),
+            double_payment_window_minutes: Some(24 * 60),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_are_valid() {
+        for config in [
+            ValidatorConfig::strict(),
+            ValidatorConfig::standard(),
+            ValidatorConfig::permissive(),
+            ValidatorConfig::crypto_exchange(),
+            ValidatorConfig::retail_bank(),
+        ] {
+            assert!(config.validate().is_ok(), "{:?}", config);
+        }
+    }
+
+    #[test]
+    fn test_presets_order_by_strictness() {
+        let strict = ValidatorConfig::strict();
+        let standard = ValidatorConfig::standard();
+        let permissive = ValidatorConfig::permissive();
+        assert!(strict.fraud_threshold < standard.fraud_threshold);
+        assert!(standard.fraud_threshold < permissive.fraud_threshold);
+        assert!(strict.max_amount_per_window < permissive.max_amount_per_window);
+        assert_eq!(standard.version(), ValidatorConfig::default().version());
+    }
+}
@@ -0,0 +1,159 @@
+//! Review-queue prioritization
+//!
+//! Ranks flagged results by a composite severity so analysts work the
+//! riskiest items first: fraud score, amount at risk, sanctions involvement,
+//! and customer segment all contribute.
+
+use crate::{CustomerSegment, Decision, ValidationResult};
+use serde::{Deserialize, Serialize};
+
+/// Weights for the composite severity score
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriorityWeights {
+    /// Multiplier applied to the 0-100 fraud score
+    pub score: f64,
+    /// Points at the top of the amount scale (logarithmic up to `amount_cap`)
+    pub amount: f64,
+    /// Amount at which the amount component saturates
+    pub amount_cap: f64,
+    /// Points added when sanctions screening was involved
+    pub sanctions: f64,
+    pub retail: f64,
+    pub smb: f64,
+    pub corporate: f64,
+}
+
+impl Default for PriorityWeights {
+    fn default() -> Self {
+        Self {
+            score: 0.5,
+            amount: 25.0,
+            amount_cap: 1_000_000.0,
+            sanctions: 40.0,
+            retail: 0.0,
+            smb: 5.0,
+            corporate: 10.0,
+        }
+    }
+}
+
+impl PriorityWeights {
+    /// Composite severity of a result
+    pub fn severity(&self, result: &ValidationResult) -> f64 {
+        let score = result.fraud_score as f64 * self.score;
+
+        let cap = self.amount_cap.max(10.0);
+        let amount_ratio = ((result.amount.max(0.0) + 1.0).log10() / cap.log10()).min(1.0);
+        let amount = amount_ratio * self.amount;
+
+        let sanctions = if is_sanctions_involved(result) {
+            self.sanctions
+        } else {
+            0.0
+        };
+
+        let tier = match result.segment {
+            Some(CustomerSegment::Retail) | None => self.retail,
+            Some(CustomerSegment::Smb) => self.smb,
+            Some(CustomerSegment::Corporate) => self.corporate,
+        };
+
+        score + amount + sanctions + tier
+    }
+}
+
+/// Whether screening found a sanctions hit or possible match
+fn is_sanctions_involved(result: &ValidationResult) -> bool {
+    result.compliance_checks.get("SANCTIONS") == Some(&false)
+        || result
+            .reason_codes()
+            .iter()
+            .any(|code| code == "SANCTIONS_MATCH")
+        || result
+            .warnings
+            .iter()
+            .any(|w| w.code == "POSSIBLE_SANCTIONS_MATCH")
+}
+
+/// Flagged result with its position in the review queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankedResult {
+    /// 1-based queue position
+    pub rank: usize,
+    pub severity: f64,
+    pub result: ValidationResult,
+}
+
+/// Rank flagged (review or decline) results, most severe first, with default weights
+pub fn prioritize(results: &[ValidationResult]) -> Vec<RankedResult> {
+    prioritize_with_weights(results, &PriorityWeights::default())
+}
+
+/// Rank flagged results using custom weights
+pub fn prioritize_with_weights(
+    results: &[ValidationResult],
+    weights: &PriorityWeights,
+) -> Vec<RankedResult> {
+    let mut ranked: Vec<(f64, &ValidationResult)> = results
+        .iter()
+        .filter(|r| r.decision != Decision::Approve)
+        .map(|r| (weights.severity(r), r))
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.0.total_cmp(&a.0)
+            .then_with(|| a.1.transaction_id.cmp(&b.1.transaction_id))
+    });
+
+    ranked
+        .into_iter()
+        .enumerate()
+        .map(|(i, (severity, result))| RankedResult {
+            rank: i + 1,
+            severity,
+            result: result.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::TransactionValidator;
+
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_prioritize_orders_by_severity() {
+        let mut validator = TransactionValidator::new();
+        let results = vec![
+            validator.validate(&create_test_transaction("TXN-OK", 100.0)),
+            validator.validate(&create_test_transaction("TXN-SMALL", -1.0)),
+            validator.validate(&create_test_transaction("TXN-LARGE", 150_000.0)),
+        ];
+
+        let ranked = prioritize(&results);
+        assert_eq!(ranked.len(), 2, "approved results are not queued");
+        assert_eq!(ranked[0].result.transaction_id, "TXN-LARGE");
+        assert_eq!(ranked[0].rank, 1);
+        assert!(ranked[0].severity > ranked[1].severity);
+    }
+
+    #[test]
+    fn test_sanctions_and_tier_raise_severity() {
+        let mut validator = TransactionValidator::new();
+        let mut base = validator.validate(&create_test_transaction("TXN-A", -1.0));
+        base.compliance_checks = HashMap::new();
+        let weights = PriorityWeights::default();
+
+        let mut sanctioned = base.clone();
+        sanctioned
+            .compliance_checks
+            .insert("SANCTIONS".to_string(), false);
+        assert!(weights.severity(&sanctioned) > weights.severity(&base));
+
+        let mut corporate = base.clone();
+        corporate.segment = Some(CustomerSegment::Corporate);
+        assert!(weights.severity(&corporate) > weights.severity(&base));
+    }
+}
@@ -0,0 +1,265 @@
+//! Keyed pseudonymization of user IDs and account numbers
+//!
+//! With a [`Pseudonymizer`] installed, the validator replaces the user ID
+//! and both account numbers on its working copy with HMAC-SHA256 tokens
+//! before anything is checked or stored, right after PAN masking. History,
+//! duplicate fingerprints, the network graph, counterparty trust, decision
+//! logs, events, and audit records then only ever hold tokens. The same
+//! key always maps a value to the same token, so velocity, duplicate, and
+//! network checks work as before.
+//!
+//! Account formats, the routing numbers embedded in accounts, and the AML
+//! sanctioned-entity list are checked on the real numbers before they are
+//! replaced; error messages quote the tokens. Anything else keyed by user or account, such as watchlist entries
+//! and queries against history, has to use [`Pseudonymizer::pseudonym`]
+//! values. Metadata is not rewritten.
+//!
+//! Every value is hashed, including one that already looks like a token, so
+//! a caller cannot pick the token its transaction is recorded under. Stored
+//! transactions are replayed through a validator whose pseudonymizer was
+//! built with [`Pseudonymizer::for_replay`], which keeps existing tokens.
+//! Losing or rotating the key unlinks everything recorded under the old one.
+
+use crate::Transaction;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::fmt;
+
+/// Prefix marking a pseudonymized identifier
+pub const PSEUDONYM_PREFIX: &str = "PSN-";
+
+/// Hex characters after the prefix: the first 128 bits of the HMAC
+const TOKEN_HEX_LEN: usize = 32;
+
+/// Replaces identifiers with HMAC-SHA256 tokens under a caller-supplied key
+#[derive(Clone)]
+pub struct Pseudonymizer {
+    key: Vec<u8>,
+    /// Pass values that already are tokens through unchanged
+    keep_tokens: bool,
+}
+
+impl Pseudonymizer {
+    pub fn new(key: impl AsRef<[u8]>) -> Self {
+        Self {
+            key: key.as_ref().to_vec(),
+            keep_tokens: false,
+        }
+    }
+
+    /// Keep values that already are tokens, for replaying stored transactions
+    ///
+    /// Only for validators fed transactions this key already pseudonymized;
+    /// on live traffic it would let a caller submit a chosen token.
+    pub fn for_replay(mut self) -> Self {
+        self.keep_tokens = true;
+        self
+    }
+
+    /// Token for one identifier, e.g. `PSN-3fa1...`
+    pub fn pseudonym(&self, value: &str) -> String {
+        if self.keep_tokens && is_pseudonym(value) {
+            return value.to_string();
+        }
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts any key length");
+        mac.update(value.as_bytes());
+        let digest = mac.finalize().into_bytes();
+        let hex: String = digest
+            .iter()
+            .take(TOKEN_HEX_LEN / 2)
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        format!("{}{}", PSEUDONYM_PREFIX, hex)
+    }
+
+    /// Replace the user ID and account numbers of a transaction
+    pub fn pseudonymize(&self, transaction: &mut Transaction) {
+        transaction.user_id = self.pseudonym(&transaction.user_id);
+        for account in [&mut transaction.from_account, &mut transaction.to_account]
+            .into_iter()
+            .flatten()
+        {
+            *account = self.pseudonym(account);
+        }
+    }
+}
+
+/// Keys never appear in logs
+impl fmt::Debug for Pseudonymizer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pseudonymizer")
+            .field("keep_tokens", &self.keep_tokens)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Whether a value has the shape of a pseudonymizer token
+pub fn is_pseudonym(value: &str) -> bool {
+    value.strip_prefix(PSEUDONYM_PREFIX).is_some_and(|hex| {
+        hex.len() == TOKEN_HEX_LEN
+            && hex
+                .chars()
+                .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{
+        AMLChecker, Decision, HistoryKey, TransactionMetadata, TransactionType,
+        TransactionValidator, ValidationError, ValidatorConfig,
+        BENEFICIARY_ROUTING_NUMBER_METADATA_KEY,
+    };
+
+    #[test]
+    fn test_pseudonyms_are_keyed_and_stable() {
+        let a = Pseudonymizer::new(b"key-a");
+        let b = Pseudonymizer::new(b"key-b");
+        let token = a.pseudonym("USER-001");
+        assert!(is_pseudonym(&token));
+        assert_eq!(token, a.pseudonym("USER-001"));
+        assert_ne!(a.pseudonym(&token), token);
+        assert_eq!(a.clone().for_replay().pseudonym(&token), token);
+        assert_ne!(token, b.pseudonym("USER-001"));
+        assert_ne!(token, a.pseudonym("USER-002"));
+        assert!(!format!("{:?}", a).contains("key-a"));
+    }
+
+    #[test]
+    fn test_stored_state_holds_only_pseudonyms() {
+        let pseudonymizer = Pseudonymizer::new(b"tenant-secret");
+        let mut validator = TransactionValidator::new();
+        validator.set_pseudonymizer(pseudonymizer.clone());
+
+        let first = create_test_transaction("TXN-PSN-1", 100.0);
+        let result = validator.validate(&first);
+        assert!(result.is_valid);
+        assert_eq!(first.user_id, "USER-001");
+
+        let replay = validator.validate(&first);
+        assert!(replay
+            .errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::DuplicateTransaction(_))));
+
+        let history = validator.history().read();
+        assert!(history.get(HistoryKey::User, "USER-001").is_empty());
+        let stored = history.get(HistoryKey::User, &pseudonymizer.pseudonym("USER-001"));
+        assert_eq!(stored.len(), 1);
+        let account = pseudonymizer.pseudonym("ACCT-1234-5678-9012");
+        assert_eq!(stored[0].from_account.as_deref(), Some(account.as_str()));
+        assert_eq!(history.get(HistoryKey::Account, &account).len(), 1);
+        drop(history);
+
+        // Formats are still checked against the real number
+        let mut bad = create_test_transaction("TXN-PSN-2", 100.0);
+        bad.to_account = Some("not an account".to_string());
+        let result = validator.validate(&bad);
+        let message = result
+            .errors
+            .iter()
+            .find_map(|e| match e {
+                ValidationError::InvalidAccount(m) => Some(m.clone()),
+                _ => None,
+            })
+            .unwrap();
+        assert!(!message.contains("not an account"));
+        assert!(message.contains(PSEUDONYM_PREFIX));
+    }
+
+    #[test]
+    fn test_token_shaped_ids_are_hashed_outside_replay() {
+        let pseudonymizer = Pseudonymizer::new(b"tenant-secret");
+        let victim = pseudonymizer.pseudonym("USER-001");
+        let mut validator = TransactionValidator::new();
+        validator.set_pseudonymizer(pseudonymizer.clone());
+
+        let mut forged = create_test_transaction("TXN-PSN-F", 100.0);
+        forged.user_id = victim.clone();
+        assert!(validator.validate(&forged).is_valid);
+        assert!(validator
+            .history()
+            .read()
+            .get(HistoryKey::User, &victim)
+            .is_empty());
+
+        // A replay validator takes the stored tokens as they are
+        let mut replayer = TransactionValidator::new();
+        replayer.set_pseudonymizer(pseudonymizer.for_replay());
+        assert!(replayer.validate(&forged).is_valid);
+        assert_eq!(
+            replayer
+                .history()
+                .read()
+                .get(HistoryKey::User, &victim)
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_routing_numbers_are_checked_before_pseudonymization() {
+        let mut validator = TransactionValidator::new();
+        validator.set_pseudonymizer(Pseudonymizer::new(b"tenant-secret"));
+        let mut tx = create_test_transaction("TXN-PSN-ABA", 100.0);
+        tx.transaction_type = TransactionType::AchCredit;
+        tx.to_account = Some("021000022:12345678".to_string());
+        let result = validator.validate(&tx);
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::InvalidRoutingNumber(_))));
+
+        // Metadata is not pseudonymized and is still checked as well
+        tx.transaction_id = "TXN-PSN-ABA-2".to_string();
+        tx.to_account = Some("ACCT-6789-0123-4567".to_string());
+        tx.metadata = Some(TransactionMetadata::from([(
+            BENEFICIARY_ROUTING_NUMBER_METADATA_KEY.to_string(),
+            "021000022".to_string(),
+        )]));
+        let result = validator.validate(&tx);
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::InvalidRoutingNumber(_))));
+    }
+
+    #[test]
+    fn test_velocity_counts_across_pseudonymized_transactions() {
+        let mut validator = TransactionValidator::new();
+        validator.set_pseudonymizer(Pseudonymizer::new(b"tenant-secret"));
+        let limit = ValidatorConfig::default().max_transactions_per_window;
+        for i in 0..limit {
+            let tx = create_test_transaction(&format!("TXN-PSN-V-{}", i), 10.0 + i as f64);
+            assert!(validator.validate(&tx).is_valid);
+        }
+        let over = create_test_transaction("TXN-PSN-V-OVER", 5.0);
+        assert!(validator
+            .validate(&over)
+            .errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::VelocityViolation(_))));
+    }
+
+    #[test]
+    fn test_sanctioned_accounts_are_matched_before_pseudonymization() {
+        let mut tx = create_test_transaction("TXN-PSN-AML", 100.0);
+        tx.from_account = Some("ACCT-1111-2222-3333".to_string());
+
+        for pseudonymized in [false, true] {
+            let mut checker = AMLChecker::new();
+            checker.add_sanctioned_entity("ACCT-1111-2222-3333".to_string());
+            let mut validator = TransactionValidator::new();
+            validator.set_aml_checker(checker);
+            if pseudonymized {
+                validator.set_pseudonymizer(Pseudonymizer::new(b"tenant-secret"));
+            }
+            let result = validator.validate(&tx);
+            assert_eq!(result.decision, Decision::Decline, "{}", pseudonymized);
+            assert_eq!(result.compliance_checks.get("AML"), Some(&false));
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! Reference-data staleness
+//!
+//! Sanctions lists, FATF lists, and country risk tables are only as good as
+//! their last refresh. Each loaded dataset reports its publication date as a
+//! [`ReferenceDataset`]; with `ValidatorConfig::max_reference_data_age_days`
+//! set, every validation warns with [`STALE_REFERENCE_DATA_WARNING`] for each
+//! dataset older than the limit, and
+//! [`ValidatorStats::reference_data`](crate::ValidatorStats::reference_data)
+//! reports the age of every dataset for monitoring.
+//!
+//! A dataset without a publication date cannot be shown to be fresh and
+//! counts as stale.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Warning code for a dataset older than the configured maximum age
+pub const STALE_REFERENCE_DATA_WARNING: &str = "STALE_REFERENCE_DATA";
+
+/// Name and publication date of one loaded dataset
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReferenceDataset {
+    /// Dataset name, e.g. `OFAC SDN` or `FATF`
+    pub name: String,
+    /// When the publisher released the loaded version
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+impl ReferenceDataset {
+    pub fn new(name: &str, published_at: Option<DateTime<Utc>>) -> Self {
+        Self {
+            name: name.to_string(),
+            published_at,
+        }
+    }
+
+    /// Whole days since publication, if the date is known
+    pub fn age_days(&self, at: DateTime<Utc>) -> Option<i64> {
+        self.published_at.map(|p| (at - p).num_days().max(0))
+    }
+
+    /// Check whether the dataset is undated or older than `max_age_days`
+    pub fn is_stale(&self, at: DateTime<Utc>, max_age_days: i64) -> bool {
+        self.age_days(at).is_none_or(|age| age > max_age_days)
+    }
+}
+
+/// Age of one dataset at a stats snapshot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatasetAge {
+    pub name: String,
+    pub published_at: Option<DateTime<Utc>>,
+    pub age_days: Option<i64>,
+    /// Older than `max_reference_data_age_days`, or undated when a limit is set
+    pub stale: bool,
+}
+
+impl DatasetAge {
+    pub(crate) fn new(
+        dataset: ReferenceDataset,
+        at: DateTime<Utc>,
+        max_age_days: Option<i64>,
+    ) -> Self {
+        Self {
+            age_days: dataset.age_days(at),
+            stale: max_age_days.is_some_and(|max| dataset.is_stale(at, max)),
+            name: dataset.name,
+            published_at: dataset.published_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{
+        GeographicRiskScorer, ListSource, SanctionsList, SanctionsScreener, TransactionValidator,
+        ValidatorConfig,
+    };
+    use chrono::Duration;
+
+    #[test]
+    fn test_stale_datasets_warn_and_show_in_stats() {
+        let now = Utc::now();
+        let mut screener = SanctionsScreener::new();
+        for list in [SanctionsList::OFAC, SanctionsList::EU, SanctionsList::UN] {
+            screener.set_list_source(
+                list,
+                ListSource {
+                    published_at: Some(now - Duration::days(2)),
+                    ..Default::default()
+                },
+            );
+        }
+        screener.set_list_source(
+            SanctionsList::UN,
+            ListSource {
+                published_at: Some(now - Duration::days(40)),
+                ..Default::default()
+            },
+        );
+        let mut scorer = GeographicRiskScorer::new();
+        scorer.set_country_risk_published_at(now - Duration::days(1));
+        scorer.set_fatf_published_at(now - Duration::days(3));
+
+        let mut validator = TransactionValidator::with_config(ValidatorConfig {
+            max_reference_data_age_days: Some(30),
+            ..Default::default()
+        });
+        validator.set_sanctions_screener(screener);
+        validator.set_geographic_scorer(scorer);
+
+        let result = validator.validate(&create_test_transaction("TXN-REF-1", 100.0));
+        let stale: Vec<&str> = result
+            .warnings
+            .iter()
+            .filter(|w| w.code == STALE_REFERENCE_DATA_WARNING)
+            .map(|w| w.message.as_str())
+            .collect();
+        assert_eq!(stale.len(), 1);
+        assert!(stale[0].contains("UN Security Council"));
+
+        let stats = validator.get_stats();
+        assert_eq!(stats.reference_data.len(), 5);
+        let un = stats
+            .reference_data
+            .iter()
+            .find(|d| d.name == "UN Security Council")
+            .unwrap();
+        assert_eq!(un.age_days, Some(40));
+        assert!(un.stale);
+        assert_eq!(stats.stale_reference_data, 1);
+    }
+
+    #[test]
+    fn test_undated_dataset_is_stale() {
+        let dataset = ReferenceDataset::new("FATF", None);
+        assert!(dataset.is_stale(Utc::now(), 365));
+    }
+}
@@ -0,0 +1,206 @@
+//! Refund and reversal linkage
+//!
+//! Refunds and reversals name the transaction they undo in the
+//! `parent_transaction_id` metadata entry. With a [`RefundLedger`] attached,
+//! the validator checks that the parent was seen, is in the same currency,
+//! and has not already been refunded in full. It also tracks net exposure
+//! per counterparty: what users paid it, less what came back. A counterparty
+//! whose refunds approach or exceed its takings is a classic refund-abuse
+//! signal.
+
+use crate::{Transaction, TransactionType, ValidationError};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Rounding slack when comparing refunded totals to the original amount
+const AMOUNT_TOLERANCE: f64 = 0.005;
+
+/// A committed transaction that may later be refunded or reversed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub amount: f64,
+    pub currency: String,
+    pub user_id: String,
+    /// Account that received the original payment
+    pub counterparty: Option<String>,
+    /// Refunded and reversed so far
+    pub refunded: f64,
+}
+
+impl LedgerEntry {
+    /// Amount still available to refund
+    pub fn remaining(&self) -> f64 {
+        (self.amount - self.refunded).max(0.0)
+    }
+}
+
+/// Money paid to and returned by one counterparty
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct NetExposure {
+    pub paid: f64,
+    pub refunded: f64,
+}
+
+impl NetExposure {
+    /// Paid less refunded
+    pub fn net(&self) -> f64 {
+        self.paid - self.refunded
+    }
+}
+
+/// Original transactions, their refunds, and net exposure per counterparty
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RefundLedger {
+    entries: HashMap<String, LedgerEntry>,
+    exposure: HashMap<String, NetExposure>,
+}
+
+impl RefundLedger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check a refund or reversal against its parent
+    ///
+    /// Other transaction types always pass.
+    pub fn check(&self, transaction: &Transaction) -> Result<(), ValidationError> {
+        if !transaction.transaction_type.reverses_parent() {
+            return Ok(());
+        }
+        let Some(parent_id) = transaction.parent_transaction_id() else {
+            // A missing reference is reported by the business rules
+            return Ok(());
+        };
+        let Some(parent) = self.entries.get(parent_id) else {
+            return Err(ValidationError::BusinessRuleViolation(format!(
+                "Original transaction {} not found",
+                parent_id
+            )));
+        };
+        if parent.currency != transaction.currency {
+            return Err(ValidationError::BusinessRuleViolation(format!(
+                "{} in {} does not match original {} in {}",
+                transaction.transaction_type, transaction.currency, parent_id, parent.currency
+            )));
+        }
+        if transaction.amount > parent.remaining() + AMOUNT_TOLERANCE {
+            return Err(ValidationError::BusinessRuleViolation(format!(
+                "{} of {:.2} exceeds the {:.2} remaining on {}",
+                transaction.transaction_type,
+                transaction.amount,
+                parent.remaining(),
+                parent_id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Record a committed transaction
+    ///
+    /// Refunds and reversals count against their parent and its
+    /// counterparty; everything else except authorization holds becomes
+    /// refundable.
+    pub fn record(&mut self, transaction: &Transaction) {
+        if transaction.transaction_type == TransactionType::Authorization {
+            return;
+        }
+        if transaction.transaction_type.reverses_parent() {
+            let Some(parent) = transaction
+                .parent_transaction_id()
+                .and_then(|id| self.entries.get_mut(id))
+            else {
+                return;
+            };
+            parent.refunded += transaction.amount;
+            if let Some(ref counterparty) = parent.counterparty {
+                self.exposure
+                    .entry(counterparty.clone())
+                    .or_default()
+                    .refunded += transaction.amount;
+            }
+            return;
+        }
+
+        if let Some(ref counterparty) = transaction.to_account {
+            self.exposure.entry(counterparty.clone()).or_default().paid += transaction.amount;
+        }
+        self.entries
+            .entry(transaction.transaction_id.clone())
+            .or_insert_with(|| LedgerEntry {
+                amount: transaction.amount,
+                currency: transaction.currency.clone(),
+                user_id: transaction.user_id.clone(),
+                counterparty: transaction.to_account.clone(),
+                refunded: 0.0,
+            });
+    }
+
+    /// Ledger entry for an original transaction
+    pub fn entry(&self, transaction_id: &str) -> Option<&LedgerEntry> {
+        self.entries.get(transaction_id)
+    }
+
+    /// Paid and refunded totals for a counterparty
+    pub fn exposure(&self, counterparty: &str) -> NetExposure {
+        self.exposure.get(counterparty).copied().unwrap_or_default()
+    }
+
+    /// Every counterparty with recorded activity
+    pub fn exposures(&self) -> impl Iterator<Item = (&str, &NetExposure)> {
+        self.exposure.iter().map(|(k, v)| (k.as_str(), v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{TransactionMetadata, TransactionValidator, PARENT_TRANSACTION_METADATA_KEY};
+
+    fn refund_of(id: &str, parent: &str, amount: f64) -> Transaction {
+        let mut refund = create_test_transaction(id, amount);
+        refund.transaction_type = TransactionType::Refund;
+        refund.from_account = Some("ACCT-6789-0123-4567".to_string());
+        refund.to_account = Some("ACCT-1234-5678-9012".to_string());
+        refund.metadata = Some(TransactionMetadata::from([(
+            PARENT_TRANSACTION_METADATA_KEY.to_string(),
+            parent.to_string(),
+        )]));
+        refund
+    }
+
+    #[test]
+    fn test_refunds_are_capped_by_the_original() {
+        let mut validator = TransactionValidator::new();
+        validator.set_refund_ledger(RefundLedger::new());
+        assert!(
+            validator
+                .validate(&create_test_transaction("TXN-ORIG-1", 100.0))
+                .is_valid
+        );
+
+        assert!(
+            validator
+                .validate(&refund_of("TXN-RFD-1", "TXN-ORIG-1", 60.0))
+                .is_valid
+        );
+        // Only 40 remains refundable
+        assert!(
+            !validator
+                .validate(&refund_of("TXN-RFD-2", "TXN-ORIG-1", 50.0))
+                .is_valid
+        );
+        assert!(
+            !validator
+                .validate(&refund_of("TXN-RFD-3", "TXN-UNKNOWN", 10.0))
+                .is_valid
+        );
+
+        let ledger = validator.refund_ledger().unwrap();
+        assert_eq!(ledger.entry("TXN-ORIG-1").unwrap().remaining(), 40.0);
+        let exposure = ledger.exposure("ACCT-6789-0123-4567");
+        assert_eq!(exposure.paid, 100.0);
+        assert_eq!(exposure.refunded, 60.0);
+        assert_eq!(exposure.net(), 40.0);
+    }
+}
@@ -0,0 +1,294 @@
+//! Regulatory report obligations
+//!
+//! When the AML checker finds that a committed transaction requires a
+//! Currency Transaction Report or a Suspicious Activity Report, the
+//! validator enqueues the obligation in its [`ReportingQueue`]. Obligations
+//! are deduplicated per report kind, customer, and UTC activity day, so a
+//! customer's third large cash deposit of the day joins the CTR already
+//! opened for it instead of opening another. Each obligation carries its
+//! filing deadline: by default 15 calendar days after the activity for a
+//! CTR and 30 days after initial detection for a SAR.
+
+use crate::Transaction;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Kind of regulatory report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ReportKind {
+    /// Currency Transaction Report
+    Ctr,
+    /// Suspicious Activity Report
+    Sar,
+}
+
+impl std::fmt::Display for ReportKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReportKind::Ctr => write!(f, "CTR"),
+            ReportKind::Sar => write!(f, "SAR"),
+        }
+    }
+}
+
+/// Filing deadlines in calendar days
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportingPolicy {
+    /// Days after the activity date a CTR is due
+    pub ctr_filing_days: i64,
+    /// Days after initial detection a SAR is due
+    pub sar_filing_days: i64,
+}
+
+impl Default for ReportingPolicy {
+    fn default() -> Self {
+        Self {
+            ctr_filing_days: 15,
+            sar_filing_days: 30,
+        }
+    }
+}
+
+/// One report to file for a customer's activity on one day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportObligation {
+    pub kind: ReportKind,
+    pub user_id: String,
+    /// UTC date of the activity being reported
+    pub activity_date: NaiveDate,
+    /// Transactions covered, in the order they were enqueued
+    pub transaction_ids: Vec<String>,
+    pub total_amount: f64,
+    /// When the first covered transaction was detected
+    pub detected_at: DateTime<Utc>,
+    /// Last day the report may be filed
+    pub due_date: NaiveDate,
+    pub filed_at: Option<DateTime<Utc>>,
+}
+
+impl ReportObligation {
+    pub fn is_filed(&self) -> bool {
+        self.filed_at.is_some()
+    }
+
+    /// Unfiled past its due date as of `now`
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        !self.is_filed() && now.date_naive() > self.due_date
+    }
+
+    /// Days left to file; negative once overdue
+    pub fn days_remaining(&self, now: DateTime<Utc>) -> i64 {
+        (self.due_date - now.date_naive()).num_days()
+    }
+}
+
+/// Report obligations keyed by kind, customer, and activity day
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReportingQueue {
+    policy: ReportingPolicy,
+    obligations: BTreeMap<(ReportKind, String, NaiveDate), ReportObligation>,
+}
+
+impl ReportingQueue {
+    /// Empty queue with the default deadlines
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Empty queue with custom deadlines
+    pub fn with_policy(policy: ReportingPolicy) -> Self {
+        Self {
+            policy,
+            obligations: BTreeMap::new(),
+        }
+    }
+
+    /// The deadlines in use
+    pub fn policy(&self) -> &ReportingPolicy {
+        &self.policy
+    }
+
+    /// Add a transaction to its customer's obligation for the day
+    ///
+    /// Returns true when this opened a new obligation. A transaction already
+    /// covered is not added twice.
+    pub fn enqueue(
+        &mut self,
+        kind: ReportKind,
+        transaction: &Transaction,
+        detected_at: DateTime<Utc>,
+    ) -> bool {
+        let activity_date = transaction.timestamp.date_naive();
+        let key = (kind, transaction.user_id.clone(), activity_date);
+        if let Some(obligation) = self.obligations.get_mut(&key) {
+            if !obligation
+                .transaction_ids
+                .contains(&transaction.transaction_id)
+            {
+                obligation
+                    .transaction_ids
+                    .push(transaction.transaction_id.clone());
+                obligation.total_amount += transaction.amount;
+            }
+            return false;
+        }
+        let due_date = match kind {
+            ReportKind::Ctr => activity_date + Duration::days(self.policy.ctr_filing_days),
+            ReportKind::Sar => {
+                detected_at.date_naive() + Duration::days(self.policy.sar_filing_days)
+            }
+        };
+        self.obligations.insert(
+            key,
+            ReportObligation {
+                kind,
+                user_id: transaction.user_id.clone(),
+                activity_date,
+                transaction_ids: vec![transaction.transaction_id.clone()],
+                total_amount: transaction.amount,
+                detected_at,
+                due_date,
+                filed_at: None,
+            },
+        );
+        true
+    }
+
+    /// The obligation for a customer's activity on a day, if any
+    pub fn get(
+        &self,
+        kind: ReportKind,
+        user_id: &str,
+        activity_date: NaiveDate,
+    ) -> Option<&ReportObligation> {
+        self.obligations
+            .get(&(kind, user_id.to_string(), activity_date))
+    }
+
+    /// Record that a report was filed; false if there is no such obligation
+    pub fn mark_filed(
+        &mut self,
+        kind: ReportKind,
+        user_id: &str,
+        activity_date: NaiveDate,
+        filed_at: DateTime<Utc>,
+    ) -> bool {
+        match self
+            .obligations
+            .get_mut(&(kind, user_id.to_string(), activity_date))
+        {
+            Some(obligation) => {
+                obligation.filed_at = Some(filed_at);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Unfiled obligations, earliest due first
+    pub fn pending(&self) -> Vec<&ReportObligation> {
+        let mut pending: Vec<&ReportObligation> = self
+            .obligations
+            .values()
+            .filter(|o| !o.is_filed())
+            .collect();
+        pending.sort_by_key(|o| o.due_date);
+        pending
+    }
+
+    /// Unfiled obligations past their due date, earliest due first
+    pub fn overdue(&self, now: DateTime<Utc>) -> Vec<&ReportObligation> {
+        self.pending()
+            .into_iter()
+            .filter(|o| o.is_overdue(now))
+            .collect()
+    }
+
+    /// Unfiled obligations due within `days` that are not yet overdue
+    pub fn due_within(&self, now: DateTime<Utc>, days: i64) -> Vec<&ReportObligation> {
+        self.pending()
+            .into_iter()
+            .filter(|o| (0..=days).contains(&o.days_remaining(now)))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.obligations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.obligations.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{AMLChecker, TransactionValidator};
+
+    #[test]
+    fn test_obligations_deduplicate_by_customer_and_day() {
+        let mut queue = ReportingQueue::new();
+        let first = create_test_transaction("TXN-RQ-1", 12_000.0);
+        let detected = first.timestamp;
+        assert!(queue.enqueue(ReportKind::Ctr, &first, detected));
+        assert!(!queue.enqueue(ReportKind::Ctr, &first, detected));
+        let second = create_test_transaction("TXN-RQ-2", 15_000.0);
+        assert!(!queue.enqueue(ReportKind::Ctr, &second, detected));
+        assert!(queue.enqueue(ReportKind::Sar, &second, detected));
+        let mut next_day = create_test_transaction("TXN-RQ-3", 11_000.0);
+        next_day.timestamp += Duration::days(1);
+        assert!(queue.enqueue(ReportKind::Ctr, &next_day, detected));
+        assert_eq!(queue.len(), 3);
+
+        let day = first.timestamp.date_naive();
+        let ctr = queue.get(ReportKind::Ctr, "USER-001", day).unwrap();
+        assert_eq!(ctr.transaction_ids, vec!["TXN-RQ-1", "TXN-RQ-2"]);
+        assert_eq!(ctr.total_amount, 27_000.0);
+        assert_eq!(ctr.due_date, day + Duration::days(15));
+        let sar = queue.get(ReportKind::Sar, "USER-001", day).unwrap();
+        assert_eq!(sar.due_date, day + Duration::days(30));
+    }
+
+    #[test]
+    fn test_overdue_queries() {
+        let mut queue = ReportingQueue::new();
+        let tx = create_test_transaction("TXN-RQ-10", 12_000.0);
+        let day = tx.timestamp.date_naive();
+        queue.enqueue(ReportKind::Ctr, &tx, tx.timestamp);
+        queue.enqueue(ReportKind::Sar, &tx, tx.timestamp);
+
+        let later = tx.timestamp + Duration::days(20);
+        let overdue = queue.overdue(later);
+        assert_eq!(overdue.len(), 1);
+        assert_eq!(overdue[0].kind, ReportKind::Ctr);
+        assert_eq!(overdue[0].days_remaining(later), -5);
+        assert_eq!(queue.due_within(later, 10).len(), 1);
+
+        assert!(queue.mark_filed(ReportKind::Ctr, "USER-001", day, later));
+        assert!(queue.overdue(later).is_empty());
+        assert_eq!(queue.pending().len(), 1);
+        assert!(!queue.mark_filed(ReportKind::Ctr, "USER-002", day, later));
+    }
+
+    #[test]
+    fn test_validator_enqueues_committed_ctrs() {
+        let mut validator = TransactionValidator::builder()
+            .with_aml(AMLChecker::new())
+            .with_reporting_queue(ReportingQueue::new())
+            .build();
+        let tx = create_test_transaction("TXN-RQ-20", 12_000.0);
+        validator.validate_dry_run(&tx);
+        assert!(validator.reporting_queue().unwrap().is_empty());
+
+        validator.validate(&tx);
+        let queue = validator.reporting_queue().unwrap();
+        let ctr = queue
+            .get(ReportKind::Ctr, "USER-001", tx.timestamp.date_naive())
+            .unwrap();
+        assert_eq!(ctr.transaction_ids, vec!["TXN-RQ-20"]);
+        assert_eq!(queue.pending().len(), queue.len());
+    }
+}
@@ -0,0 +1,551 @@
+//! Timeouts, retries, and circuit breaking for external providers
+//!
+//! Enrichment, screening, and geo-IP providers are called synchronously from
+//! `validate()`, so a vendor that hangs stalls payment processing with it.
+//! [`Resilience`] bounds every call: each attempt runs on its own thread and
+//! is abandoned once [`ResiliencePolicy::timeout`] passes, transient failures
+//! are retried under a [`RetryPolicy`], and a [`CircuitBreaker`] stops calling
+//! a provider that keeps failing until a cool-down has passed.
+//!
+//! [`ResilientEnrichment`], [`ResilientScreening`], and
+//! [`ResilientIpResolver`] wrap the provider traits and define what happens
+//! when the call cannot be made: an optional fallback context, the local
+//! sanctions engine, or an unresolved country respectively. Other provider
+//! traits can be wrapped the same way with [`Resilience::call`].
+//!
+//! Abandoned attempts keep running on their thread until the provider
+//! returns; the result is discarded.
+
+use crate::{
+    DegradationReason, EnrichmentContext, EnrichmentError, EnrichmentProvider, IpCountryResolver,
+    ReferenceDataset, RetryPolicy, SanctionsResult, SanctionsScreener, ScreeningBackend,
+    ScreeningError, SharedClock, Transaction,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// Timeout, retry, and circuit breaker settings for one provider
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResiliencePolicy {
+    /// Time after which a single attempt is abandoned
+    pub timeout: Duration,
+    /// Retries of timed-out and transiently failed attempts
+    pub retry: RetryPolicy,
+    /// Consecutive failed calls that open the circuit
+    pub failure_threshold: u32,
+    /// How long the circuit stays open before a probe call is allowed
+    pub open_for: Duration,
+}
+
+impl Default for ResiliencePolicy {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_millis(500),
+            retry: RetryPolicy::default(),
+            failure_threshold: 5,
+            open_for: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Circuit breaker position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CircuitState {
+    /// Calls go through
+    Closed,
+    /// Calls are refused until the cool-down passes
+    Open,
+    /// One probe call decides whether the circuit closes again
+    HalfOpen,
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    failures: u32,
+    opened_at: Option<Instant>,
+    probing: bool,
+}
+
+/// Consecutive-failure circuit breaker
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    open_for: Duration,
+    state: Mutex<BreakerState>,
+}
+
+impl CircuitBreaker {
+    /// Open after `failure_threshold` consecutive failures for `open_for`
+    pub fn new(failure_threshold: u32, open_for: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            open_for,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    /// Current position
+    pub fn state(&self) -> CircuitState {
+        let state = self.state.lock().unwrap();
+        match state.opened_at {
+            None => CircuitState::Closed,
+            Some(at) if state.probing || at.elapsed() >= self.open_for => CircuitState::HalfOpen,
+            Some(_) => CircuitState::Open,
+        }
+    }
+
+    /// Whether a call may go through, claiming the probe when half-open
+    pub fn allow(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        match state.opened_at {
+            None => true,
+            Some(at) if !state.probing && at.elapsed() >= self.open_for => {
+                state.probing = true;
+                true
+            }
+            Some(_) => false,
+        }
+    }
+
+    /// Record the outcome of a call that [`allow`](Self::allow) let through
+    pub fn record(&self, succeeded: bool) {
+        let mut state = self.state.lock().unwrap();
+        if succeeded {
+            *state = BreakerState::default();
+            return;
+        }
+        state.failures += 1;
+        if state.probing || state.failures >= self.failure_threshold {
+            state.opened_at = Some(Instant::now());
+            state.probing = false;
+        }
+    }
+}
+
+/// Why a guarded call produced no result
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CallError<E> {
+    #[error("Circuit open")]
+    CircuitOpen,
+
+    #[error("Call timed out")]
+    TimedOut,
+
+    #[error("Provider panicked")]
+    Panicked,
+
+    #[error("{0}")]
+    Failed(E),
+}
+
+/// Timeout, retry, and circuit breaker guard around provider calls
+#[derive(Debug)]
+pub struct Resilience {
+    policy: ResiliencePolicy,
+    breaker: CircuitBreaker,
+}
+
+impl Resilience {
+    pub fn new(policy: ResiliencePolicy) -> Self {
+        let breaker = CircuitBreaker::new(policy.failure_threshold, policy.open_for);
+        Self { policy, breaker }
+    }
+
+    pub fn policy(&self) -> &ResiliencePolicy {
+        &self.policy
+    }
+
+    pub fn circuit_state(&self) -> CircuitState {
+        self.breaker.state()
+    }
+
+    /// Run `op` under the policy
+    ///
+    /// Errors for which `is_transient` returns false are final answers from
+    /// a healthy provider: they are not retried and do not count against
+    /// the circuit.
+    pub fn call<T, E, F>(&self, op: F, is_transient: fn(&E) -> bool) -> Result<T, CallError<E>>
+    where
+        T: Send + 'static,
+        E: Send + 'static,
+        F: Fn() -> Result<T, E> + Send + Sync + 'static,
+    {
+        if !self.breaker.allow() {
+            return Err(CallError::CircuitOpen);
+        }
+        let retryable = |error: &CallError<E>| match error {
+            CallError::Failed(e) => is_transient(e),
+            _ => true,
+        };
+
+        let op = Arc::new(op);
+        let retry = &self.policy.retry;
+        let started = Instant::now();
+        let mut attempt = 0;
+        let result = loop {
+            attempt += 1;
+            match self.attempt(&op) {
+                Ok(value) => break Ok(value),
+                Err(error)
+                    if retryable(&error)
+                        && attempt < retry.max_attempts
+                        && started.elapsed() + retry.backoff < retry.deadline =>
+                {
+                    std::thread::sleep(retry.backoff);
+                }
+                Err(error) => break Err(error),
+            }
+        };
+
+        self.breaker.record(match &result {
+            Ok(_) => true,
+            Err(error) => !retryable(error),
+        });
+        result
+    }
+
+    fn attempt<T, E, F>(&self, op: &Arc<F>) -> Result<T, CallError<E>>
+    where
+        T: Send + 'static,
+        E: Send + 'static,
+        F: Fn() -> Result<T, E> + Send + Sync + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let op = Arc::clone(op);
+        std::thread::spawn(move || {
+            let _ = sender.send(op());
+        });
+        match receiver.recv_timeout(self.policy.timeout) {
+            Ok(result) => result.map_err(CallError::Failed),
+            Err(mpsc::RecvTimeoutError::Timeout) => Err(CallError::TimedOut),
+            Err(mpsc::RecvTimeoutError::Disconnected) => Err(CallError::Panicked),
+        }
+    }
+}
+
+/// Enrichment provider guarded by a [`Resilience`] policy
+pub struct ResilientEnrichment<P> {
+    provider: Arc<P>,
+    resilience: Resilience,
+    fallback: Option<EnrichmentContext>,
+}
+
+impl<P: EnrichmentProvider + 'static> ResilientEnrichment<P> {
+    /// Guard `provider` with the default policy; failures surface as errors
+    pub fn new(provider: P) -> Self {
+        Self {
+            provider: Arc::new(provider),
+            resilience: Resilience::new(ResiliencePolicy::default()),
+            fallback: None,
+        }
+    }
+
+    /// Use a custom policy
+    pub fn with_policy(mut self, policy: ResiliencePolicy) -> Self {
+        self.resilience = Resilience::new(policy);
+        self
+    }
+
+    /// Return `context` instead of an error when the provider cannot answer
+    pub fn with_fallback(mut self, context: EnrichmentContext) -> Self {
+        self.fallback = Some(context);
+        self
+    }
+
+    pub fn circuit_state(&self) -> CircuitState {
+        self.resilience.circuit_state()
+    }
+}
+
+impl<P: EnrichmentProvider + 'static> EnrichmentProvider for ResilientEnrichment<P> {
+    fn name(&self) -> &str {
+        self.provider.name()
+    }
+
+    fn enrich(&self, transaction: &Transaction) -> Result<EnrichmentContext, EnrichmentError> {
+        let provider = Arc::clone(&self.provider);
+        let transaction = transaction.clone();
+        let error = match self.resilience.call(
+            move || provider.enrich(&transaction),
+            EnrichmentError::is_transient,
+        ) {
+            Ok(context) => return Ok(context),
+            Err(CallError::Failed(e)) if !e.is_transient() => return Err(e),
+            Err(CallError::Failed(e)) => e,
+            Err(CallError::TimedOut) => EnrichmentError::Timeout,
+            Err(e) => EnrichmentError::Unavailable(e.to_string()),
+        };
+        self.fallback.clone().ok_or(error)
+    }
+
+    fn degradations(&self) -> Vec<DegradationReason> {
+        let mut reasons = self.provider.degradations();
+        if self.circuit_state() == CircuitState::Open {
+            reasons.push(DegradationReason::CircuitOpen {
+                provider: self.name().to_string(),
+            });
+        }
+        reasons
+    }
+}
+
+/// Screening backend guarded by a [`Resilience`] policy
+pub struct ResilientScreening<B> {
+    primary: Arc<B>,
+    resilience: Resilience,
+    fallback: Option<SanctionsScreener>,
+}
+
+impl<B: ScreeningBackend + 'static> ResilientScreening<B> {
+    /// Guard `primary` with the default policy, then screen locally
+    pub fn new(primary: B, fallback: SanctionsScreener) -> Self {
+        Self {
+            primary: Arc::new(primary),
+            resilience: Resilience::new(ResiliencePolicy::default()),
+            fallback: Some(fallback),
+        }
+    }
+
+    /// Guard `primary` and report its error when it cannot answer
+    pub fn without_fallback(primary: B) -> Self {
+        Self {
+            primary: Arc::new(primary),
+            resilience: Resilience::new(ResiliencePolicy::default()),
+            fallback: None,
+        }
+    }
+
+    /// Use a custom policy
+    pub fn with_policy(mut self, policy: ResiliencePolicy) -> Self {
+        self.resilience = Resilience::new(policy);
+        self
+    }
+
+    pub fn circuit_state(&self) -> CircuitState {
+        self.resilience.circuit_state()
+    }
+}
+
+impl<B: ScreeningBackend + 'static> ScreeningBackend for ResilientScreening<B> {
+    fn name(&self) -> &str {
+        self.primary.name()
+    }
+
+    fn screen(&self, name: &str) -> Result<SanctionsResult, ScreeningError> {
+        let primary = Arc::clone(&self.primary);
+        let owned = name.to_string();
+        let error = match self
+            .resilience
+            .call(move || primary.screen(&owned), ScreeningError::is_transient)
+        {
+            Ok(result) => return Ok(result),
+            Err(CallError::Failed(e)) => e,
+            Err(CallError::TimedOut) => ScreeningError::Timeout,
+            Err(e) => ScreeningError::Unavailable(e.to_string()),
+        };
+        match &self.fallback {
+            Some(local) => Ok(SanctionsScreener::screen(local, name)),
+            None => Err(error),
+        }
+    }
+
+    fn degradations(&self) -> Vec<DegradationReason> {
+        let mut reasons = self.primary.degradations();
+        if self.circuit_state() == CircuitState::Open {
+            reasons.push(DegradationReason::CircuitOpen {
+                provider: self.name().to_string(),
+            });
+        }
+        if let Some(ref local) = self.fallback {
+            reasons.extend(ScreeningBackend::degradations(local));
+        }
+        reasons
+    }
+
+    fn datasets(&self) -> Vec<ReferenceDataset> {
+        let mut datasets = self.primary.datasets();
+        if let Some(ref local) = self.fallback {
+            datasets.extend(ScreeningBackend::datasets(local));
+        }
+        datasets
+    }
+
+    /// Reaches the primary only while no abandoned attempt still holds it
+    fn set_clock(&mut self, clock: SharedClock) {
+        if let Some(ref mut local) = self.fallback {
+            local.set_clock(clock.clone());
+        }
+        if let Some(primary) = Arc::get_mut(&mut self.primary) {
+            primary.set_clock(clock);
+        }
+    }
+}
+
+/// Geo-IP resolver guarded by a [`Resilience`] policy
+///
+/// A lookup that times out or hits an open circuit leaves the country
+/// unresolved, as an unknown address would.
+pub struct ResilientIpResolver<R> {
+    resolver: Arc<R>,
+    resilience: Resilience,
+}
+
+impl<R: IpCountryResolver + 'static> ResilientIpResolver<R> {
+    /// Guard `resolver` with a 50 ms timeout and no retries
+    pub fn new(resolver: R) -> Self {
+        Self {
+            resolver: Arc::new(resolver),
+            resilience: Resilience::new(ResiliencePolicy {
+                timeout: Duration::from_millis(50),
+                retry: RetryPolicy {
+                    max_attempts: 1,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Use a custom policy
+    pub fn with_policy(mut self, policy: ResiliencePolicy) -> Self {
+        self.resilience = Resilience::new(policy);
+        self
+    }
+
+    pub fn circuit_state(&self) -> CircuitState {
+        self.resilience.circuit_state()
+    }
+}
+
+impl<R: IpCountryResolver + 'static> IpCountryResolver for ResilientIpResolver<R> {
+    fn country(&self, ip_address: &str) -> Option<String> {
+        let resolver = Arc::clone(&self.resolver);
+        let ip_address = ip_address.to_string();
+        self.resilience
+            .call(
+                move || Ok::<_, ()>(resolver.country(&ip_address)),
+                |_| false,
+            )
+            .ok()
+            .flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::TransactionValidator;
+
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Provider that sleeps `delay` before answering
+    struct SlowProvider {
+        delay: Duration,
+        calls: Arc<AtomicU32>,
+    }
+
+    impl EnrichmentProvider for SlowProvider {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn enrich(&self, _: &Transaction) -> Result<EnrichmentContext, EnrichmentError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            std::thread::sleep(self.delay);
+            Ok(EnrichmentContext {
+                customer_tier: Some("gold".to_string()),
+                ..Default::default()
+            })
+        }
+    }
+
+    fn quick_policy() -> ResiliencePolicy {
+        ResiliencePolicy {
+            timeout: Duration::from_millis(20),
+            retry: RetryPolicy {
+                max_attempts: 2,
+                backoff: Duration::ZERO,
+                ..Default::default()
+            },
+            failure_threshold: 2,
+            open_for: Duration::from_millis(100),
+        }
+    }
+
+    #[test]
+    fn test_slow_provider_times_out_to_fallback() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let provider = ResilientEnrichment::new(SlowProvider {
+            delay: Duration::from_millis(500),
+            calls: Arc::clone(&calls),
+        })
+        .with_policy(quick_policy())
+        .with_fallback(EnrichmentContext::default());
+
+        let mut validator = TransactionValidator::new();
+        validator.add_enrichment_provider(provider);
+        let started = Instant::now();
+        let result = validator.validate(&create_test_transaction("TXN-RES-1", 100.0));
+        assert!(started.elapsed() < Duration::from_millis(400));
+        assert!(result.is_valid);
+        assert!(!result
+            .warnings
+            .iter()
+            .any(|w| w.code == "ENRICHMENT_FAILED"));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_circuit_opens_then_probes() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let provider = ResilientEnrichment::new(SlowProvider {
+            delay: Duration::from_millis(50),
+            calls: Arc::clone(&calls),
+        })
+        .with_policy(quick_policy());
+        let tx = create_test_transaction("TXN-RES-2", 100.0);
+
+        assert_eq!(provider.enrich(&tx), Err(EnrichmentError::Timeout));
+        assert_eq!(provider.circuit_state(), CircuitState::Closed);
+        assert_eq!(provider.enrich(&tx), Err(EnrichmentError::Timeout));
+        assert_eq!(provider.circuit_state(), CircuitState::Open);
+
+        // Open circuit refuses without calling the provider
+        let before = calls.load(Ordering::SeqCst);
+        assert!(matches!(
+            provider.enrich(&tx),
+            Err(EnrichmentError::Unavailable(_))
+        ));
+        assert_eq!(calls.load(Ordering::SeqCst), before);
+
+        std::thread::sleep(Duration::from_millis(120));
+        assert_eq!(provider.circuit_state(), CircuitState::HalfOpen);
+        // A failed probe reopens immediately
+        assert!(provider.enrich(&tx).is_err());
+        assert_eq!(provider.circuit_state(), CircuitState::Open);
+    }
+
+    #[test]
+    fn test_unreachable_screening_falls_back_to_local() {
+        struct Hanging;
+
+        impl ScreeningBackend for Hanging {
+            fn name(&self) -> &str {
+                "vendor"
+            }
+
+            fn screen(&self, _: &str) -> Result<SanctionsResult, ScreeningError> {
+                std::thread::sleep(Duration::from_millis(500));
+                Err(ScreeningError::Unavailable("down".to_string()))
+            }
+        }
+
+        let backend =
+            ResilientScreening::new(Hanging, SanctionsScreener::new()).with_policy(quick_policy());
+        let result = ScreeningBackend::screen(&backend, "SANCTIONED ENTITY ONE").unwrap();
+        assert!(result.has_high_confidence_match());
+    }
+}
@@ -0,0 +1,186 @@
+//! Follow-up alerts on approved transactions
+//!
+//! Network patterns such as funnel accounts or circular flows often become
+//! visible only after several legs have been approved. [`RetroactiveAlerter`]
+//! remembers which (account, pattern) pairs it has already followed up.
+//! Each network report it is given is compared with those pairs, and for
+//! every account newly involved in a pattern it reads the account's recent
+//! records from an [`AuditTrail`] and raises one [`AlertEvent`] per
+//! approval, with trigger [`AlertTrigger::NetworkPattern`]. Run it through
+//! [`TransactionValidator::retroactive_alerts`](crate::TransactionValidator::retroactive_alerts)
+//! after validations, or on a schedule.
+
+use crate::network_analysis::{NetworkAnalysisReport, SuspiciousPattern};
+use crate::{hash_identifier, AlertEvent, AlertTrigger, AuditTrail, Decision};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashSet;
+use std::io;
+
+/// Default look-back for approvals to follow up
+pub const DEFAULT_LOOKBACK_DAYS: i64 = 30;
+
+/// Reason code on follow-up alerts for a pattern, e.g. `NETWORK_FUNNEL_ACCOUNT`
+pub fn pattern_reason_code(pattern: &SuspiciousPattern) -> &'static str {
+    match pattern {
+        SuspiciousPattern::CircularFlow => "NETWORK_CIRCULAR_FLOW",
+        SuspiciousPattern::Layering => "NETWORK_LAYERING",
+        SuspiciousPattern::Structuring => "NETWORK_STRUCTURING",
+        SuspiciousPattern::FunnelAccount => "NETWORK_FUNNEL_ACCOUNT",
+        SuspiciousPattern::Aggregator => "NETWORK_AGGREGATOR",
+        SuspiciousPattern::Distributor => "NETWORK_DISTRIBUTOR",
+        SuspiciousPattern::ThresholdAvoidance => "NETWORK_THRESHOLD_AVOIDANCE",
+        SuspiciousPattern::PassThrough => "NETWORK_PASS_THROUGH",
+    }
+}
+
+/// Tracks followed-up patterns and builds follow-up alerts
+#[derive(Debug, Clone)]
+pub struct RetroactiveAlerter {
+    seen: HashSet<(String, SuspiciousPattern)>,
+    lookback: Duration,
+}
+
+impl Default for RetroactiveAlerter {
+    fn default() -> Self {
+        Self::new(Duration::days(DEFAULT_LOOKBACK_DAYS))
+    }
+}
+
+impl RetroactiveAlerter {
+    /// Alerter following up approvals validated within `lookback`
+    pub fn new(lookback: Duration) -> Self {
+        Self {
+            seen: HashSet::new(),
+            lookback,
+        }
+    }
+
+    pub fn lookback(&self) -> Duration {
+        self.lookback
+    }
+
+    /// (account, pattern) pairs already followed up
+    pub fn followed_up(&self) -> usize {
+        self.seen.len()
+    }
+
+    /// Follow-up alerts for the report's new patterns, as of `now`
+    ///
+    /// A pair is only marked as followed up once its records were read, so
+    /// a failing trail is retried on the next call.
+    pub fn follow_ups(
+        &mut self,
+        report: &NetworkAnalysisReport,
+        trail: &dyn AuditTrail,
+        now: DateTime<Utc>,
+    ) -> io::Result<Vec<AlertEvent>> {
+        let since = now
+            .checked_sub_signed(self.lookback)
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        let mut events = Vec::new();
+        for pair in report.pattern_accounts() {
+            if self.seen.contains(&pair) {
+                continue;
+            }
+            let (account, pattern) = &pair;
+            let records = trail.records_for_account(&hash_identifier(account), since)?;
+            events.extend(
+                records
+                    .iter()
+                    .filter(|r| r.result.decision == Decision::Approve.to_string())
+                    .map(|r| AlertEvent {
+                        event_id: uuid::Uuid::new_v4().to_string(),
+                        transaction_id: r.result.transaction_id.clone(),
+                        fraud_score: r.result.fraud_score,
+                        decision: Decision::Approve,
+                        reason_codes: vec![pattern_reason_code(pattern).to_string()],
+                        trigger: AlertTrigger::NetworkPattern,
+                        created_at: now,
+                        correlation_id: None,
+                    }),
+            );
+            self.seen.insert(pair);
+        }
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{AuditRecord, AuditSink, NetworkAnalyzer, TransactionValidator};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct MemoryTrail(Arc<Mutex<Vec<AuditRecord>>>);
+
+    impl AuditSink for MemoryTrail {
+        fn write_batch(&mut self, records: &[AuditRecord]) -> io::Result<()> {
+            self.0.lock().unwrap().extend_from_slice(records);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_funnel_discovery_follows_up_earlier_approvals() {
+        let trail = MemoryTrail::default();
+        let mut validator = TransactionValidator::builder()
+            .with_network(NetworkAnalyzer::new())
+            .build();
+        validator.set_audit_sink(Box::new(trail.clone()), 1);
+        let alerts = Arc::new(Mutex::new(Vec::new()));
+        let received = alerts.clone();
+        validator.on_alert(AlertTrigger::NetworkPattern, move |event: &AlertEvent| {
+            received.lock().unwrap().push(event.transaction_id.clone());
+        });
+
+        // Five payers into one payee; only the last makes it a funnel
+        for i in 0..5 {
+            let mut tx = create_test_transaction(&format!("TXN-RA-{}", i), 200.0);
+            tx.from_account = Some(format!("ACCT-1000-0000-000{}", i));
+            tx.user_id = format!("USER-RA-{}", i);
+            let result = validator.validate(&tx);
+            assert_eq!(result.decision, Decision::Approve);
+        }
+        let records = trail.0.lock().unwrap().clone();
+
+        let events = validator.retroactive_alerts(&records).unwrap();
+        assert_eq!(events.len(), 5);
+        assert!(events
+            .iter()
+            .all(|e| e.reason_codes == vec!["NETWORK_FUNNEL_ACCOUNT".to_string()]));
+        assert_eq!(alerts.lock().unwrap().len(), 5);
+        assert_eq!(alerts.lock().unwrap()[0], "TXN-RA-0");
+
+        // The same pattern is not followed up twice
+        assert!(validator.retroactive_alerts(&records).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_lookback_limits_follow_ups() {
+        let mut validator = TransactionValidator::new();
+        let tx = create_test_transaction("TXN-RA-10", 200.0);
+        let mut record = AuditRecord::new(&tx, &validator.validate(&tx), &[], "v1");
+        let mut old = record.clone();
+        old.result.validated_at -= Duration::days(DEFAULT_LOOKBACK_DAYS + 1);
+        record.result.transaction_id = "TXN-RA-11".to_string();
+
+        let mut analyzer = NetworkAnalyzer::new();
+        for i in 0..5 {
+            analyzer.add_transaction(
+                &format!("ACCT-2000-0000-000{}", i),
+                "ACCT-6789-0123-4567",
+                100.0,
+                tx.timestamp,
+            );
+        }
+        let mut alerter = RetroactiveAlerter::default();
+        let events = alerter
+            .follow_ups(&analyzer.analyze_all(), &vec![old, record], Utc::now())
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].transaction_id, "TXN-RA-11");
+        assert_eq!(alerter.followed_up(), 1);
+    }
+}
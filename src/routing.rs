@@ -0,0 +1,134 @@
+//! US ABA routing numbers
+//!
+//! Wires and ACH entries carry the routing numbers of the banks at each
+//! end, either in the `routing_number` / `beneficiary_routing_number`
+//! metadata entries or in the account field as `routing:account`. A routing
+//! number is nine digits, starts with a prefix the Federal Reserve assigns
+//! (00-12 for the US government and the Fed districts, 21-32 for thrifts,
+//! 61-72 for electronic transactions, 80 for traveler's checks), and passes
+//! the 3-7-1 weighted checksum.
+
+use crate::{Transaction, TransactionType};
+
+/// Metadata key for the originating bank's routing number
+pub const ROUTING_NUMBER_METADATA_KEY: &str = "routing_number";
+
+/// Metadata key for the beneficiary bank's routing number
+pub const BENEFICIARY_ROUTING_NUMBER_METADATA_KEY: &str = "beneficiary_routing_number";
+
+/// Check a routing number, returning why it is invalid
+pub fn validate_aba(routing_number: &str) -> Result<(), String> {
+    let digits: Vec<u32> = routing_number
+        .chars()
+        .map(|c| c.to_digit(10))
+        .collect::<Option<_>>()
+        .ok_or_else(|| format!("{} contains non-digits", routing_number))?;
+    if digits.len() != 9 {
+        return Err(format!("{} is not 9 digits", routing_number));
+    }
+
+    let prefix = digits[0] * 10 + digits[1];
+    if !matches!(prefix, 0..=12 | 21..=32 | 61..=72 | 80) {
+        return Err(format!(
+            "{} has no Federal Reserve prefix {:02}",
+            routing_number, prefix
+        ));
+    }
+
+    let checksum: u32 = digits
+        .iter()
+        .zip([3, 7, 1].iter().cycle())
+        .map(|(d, w)| d * w)
+        .sum();
+    if !checksum.is_multiple_of(10) {
+        return Err(format!("{} fails the checksum", routing_number));
+    }
+    Ok(())
+}
+
+/// Whether the type moves money over US bank rails that use routing numbers
+pub fn uses_routing_numbers(transaction_type: TransactionType) -> bool {
+    matches!(
+        transaction_type,
+        TransactionType::WireTransfer | TransactionType::AchCredit | TransactionType::AchDebit
+    )
+}
+
+/// Routing number in a `routing:account` account field
+pub fn routing_in_account(account: &str) -> Option<&str> {
+    account.split_once(':').map(|(routing, _)| routing.trim())
+}
+
+/// Every routing number the transaction carries, with where it was found
+pub fn routing_numbers(transaction: &Transaction) -> Vec<(&'static str, &str)> {
+    let mut found = Vec::new();
+    if let Some(metadata) = transaction.metadata.as_ref() {
+        for key in [
+            ROUTING_NUMBER_METADATA_KEY,
+            BENEFICIARY_ROUTING_NUMBER_METADATA_KEY,
+        ] {
+            if let Some(value) = metadata.get(key) {
+                found.push((key, value.trim()));
+            }
+        }
+    }
+    for (field, account) in [
+        ("from_account", &transaction.from_account),
+        ("to_account", &transaction.to_account),
+    ] {
+        if let Some(routing) = account.as_deref().and_then(routing_in_account) {
+            found.push((field, routing));
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+    use crate::{TransactionMetadata, TransactionValidator, ValidationError};
+
+    fn create_test_transaction(id: &str, amount: f64) -> Transaction {
+        Transaction {
+            transaction_type: TransactionType::AchCredit,
+            ..test_fixtures::create_test_transaction(id, amount)
+        }
+    }
+
+    #[test]
+    fn test_aba_checksum_and_prefix() {
+        assert!(validate_aba("021000021").is_ok());
+        assert!(validate_aba("011000015").is_ok());
+        assert!(validate_aba("021000022").unwrap_err().contains("checksum"));
+        assert!(validate_aba("02100002").unwrap_err().contains("9 digits"));
+        // Checksum holds but 50 is not an assigned prefix
+        assert!(validate_aba("500000005").unwrap_err().contains("prefix"));
+    }
+
+    #[test]
+    fn test_ach_with_bad_routing_number_is_rejected() {
+        let mut validator = TransactionValidator::new();
+        let mut tx = create_test_transaction("TXN-ABA-1", 100.0);
+        tx.metadata = Some(TransactionMetadata::from([(
+            BENEFICIARY_ROUTING_NUMBER_METADATA_KEY.to_string(),
+            "021000021".to_string(),
+        )]));
+        assert!(validator.validate(&tx).is_valid);
+
+        tx.transaction_id = "TXN-ABA-2".to_string();
+        tx.metadata = Some(TransactionMetadata::from([(
+            BENEFICIARY_ROUTING_NUMBER_METADATA_KEY.to_string(),
+            "021000022".to_string(),
+        )]));
+        let result = validator.validate(&tx);
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::InvalidRoutingNumber(_))));
+        assert_eq!(
+            ValidationError::InvalidRoutingNumber(String::new()).code(),
+            1004
+        );
+    }
+}
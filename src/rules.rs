@@ -0,0 +1,145 @@
+//! Custom validation rules
+//!
+//! Institution-specific logic plugs in as a [`ValidationRule`] registered
+//! with [`TransactionValidator::register_rule`](crate::TransactionValidator::register_rule)
+//! instead of a fork of the built-in business rules. Registered rules run
+//! in registration order as the `custom_rules` check, after fraud pattern
+//! scoring, and see the transaction together with a read-only
+//! [`ValidationContext`]. A rule's [`RuleOutcome`] can reject the
+//! transaction, add pattern risk, or raise a warning; risk points appear in
+//! the explanation under `pattern_risk`, labelled with the rule's name.
+
+use crate::{
+    Channel, CustomerSegment, EnrichmentContext, HistoryStore, RiskBreakdown, Transaction,
+    ValidationError, ValidatorConfig, Warning,
+};
+use chrono::{DateTime, Utc};
+
+/// What a rule sees besides the transaction
+pub struct ValidationContext<'a> {
+    pub enrichment: &'a EnrichmentContext,
+    /// Velocity history, without the transaction being validated
+    pub history: &'a HistoryStore,
+    /// Effective configuration, with segment and limit-profile overrides applied
+    pub config: &'a ValidatorConfig,
+    pub segment: Option<CustomerSegment>,
+    pub channel: Option<Channel>,
+    /// Risk components scored so far
+    pub risk: &'a RiskBreakdown,
+    pub now: DateTime<Utc>,
+}
+
+/// Result of evaluating one rule
+#[derive(Debug, Clone)]
+pub enum RuleOutcome {
+    Pass,
+    Warn(Warning),
+    /// Add pattern risk points, optionally with a warning
+    Risk {
+        points: u8,
+        warning: Option<Warning>,
+    },
+    Reject(ValidationError),
+}
+
+/// Custom check run alongside the built-in ones
+pub trait ValidationRule: Send + Sync {
+    /// Stable name used in explanations
+    fn name(&self) -> &str;
+
+    fn evaluate(&self, transaction: &Transaction, ctx: &ValidationContext) -> RuleOutcome;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{Decision, TransactionValidator, WarningSeverity};
+
+    /// Institution policy: no transfers in currencies it does not clear
+    struct ClearedCurrencies(Vec<&'static str>);
+
+    impl ValidationRule for ClearedCurrencies {
+        fn name(&self) -> &str {
+            "cleared_currencies"
+        }
+
+        fn evaluate(&self, transaction: &Transaction, _ctx: &ValidationContext) -> RuleOutcome {
+            if self.0.contains(&transaction.currency.as_str()) {
+                RuleOutcome::Pass
+            } else {
+                RuleOutcome::Reject(ValidationError::BusinessRuleViolation(format!(
+                    "{} is not cleared",
+                    transaction.currency
+                )))
+            }
+        }
+    }
+
+    /// Adds risk to a user's second transaction in the history window
+    struct RepeatPayer;
+
+    impl ValidationRule for RepeatPayer {
+        fn name(&self) -> &str {
+            "repeat_payer"
+        }
+
+        fn evaluate(&self, transaction: &Transaction, ctx: &ValidationContext) -> RuleOutcome {
+            let prior = ctx
+                .history
+                .get(crate::HistoryKey::User, &transaction.user_id)
+                .len();
+            if prior == 0 {
+                return RuleOutcome::Pass;
+            }
+            RuleOutcome::Risk {
+                points: 12,
+                warning: Some(Warning::new(
+                    "REPEAT_PAYER",
+                    WarningSeverity::Low,
+                    format!("{} prior transactions", prior),
+                )),
+            }
+        }
+    }
+
+    #[test]
+    fn test_registered_rules_run_with_builtin_checks() {
+        let mut validator = TransactionValidator::new();
+        validator.register_rule(Box::new(ClearedCurrencies(vec!["USD", "EUR"])));
+        validator.register_rule(Box::new(RepeatPayer));
+        assert_eq!(
+            validator.rule_names(),
+            vec!["cleared_currencies", "repeat_payer"]
+        );
+
+        let first = validator.validate(&create_test_transaction("TXN-RULE-1", 100.0));
+        assert_eq!(first.decision, Decision::Approve);
+        assert!(first.explanation.find("repeat_payer").is_none());
+
+        let second = validator.validate(&create_test_transaction("TXN-RULE-2", 100.0));
+        assert!(second.warning_codes().contains(&"REPEAT_PAYER".to_string()));
+        let warning = second
+            .warnings
+            .iter()
+            .find(|w| w.code == "REPEAT_PAYER")
+            .unwrap();
+        assert_eq!(warning.source_check, Some(crate::Check::CustomRules));
+        assert_eq!(
+            second.explanation.find("repeat_payer").unwrap().points,
+            12.0
+        );
+        assert_eq!(
+            second.risk_breakdown.pattern_risk,
+            first.risk_breakdown.pattern_risk + 12
+        );
+
+        let mut yen = create_test_transaction("TXN-RULE-3", 100.0);
+        yen.currency = "JPY".to_string();
+        let result = validator.validate(&yen);
+        assert!(!result.is_valid);
+        assert!(result
+            .reason_codes()
+            .contains(&"BUSINESS_RULE_VIOLATION".to_string()));
+    }
+}
@@ -0,0 +1,177 @@
+//! Sampling of expensive stages
+//!
+//! At very high throughput, graph pattern analysis and model scoring can
+//! dominate validation cost while adding nothing to the bulk of ordinary
+//! low-risk traffic. A [`SamplingPolicy`] runs the stages it lists on only
+//! a share of that traffic. Transactions that already score at the policy's
+//! risk floor, or already failed a check, always get every stage. Whether a
+//! transaction is sampled depends only on its ID and the stage, so a replay
+//! makes the same choice. Every decision is recorded on the result as a
+//! [`SamplingDecision`].
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Stage that may be sampled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SampledStage {
+    /// Transaction graph pattern detection
+    NetworkAnalysis,
+    /// `ml` feature model scoring
+    ModelScoring,
+}
+
+impl SampledStage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SampledStage::NetworkAnalysis => "network_analysis",
+            SampledStage::ModelScoring => "model_scoring",
+        }
+    }
+}
+
+/// Which stages to sample and how much low-risk traffic keeps them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SamplingPolicy {
+    pub stages: Vec<SampledStage>,
+    /// Share of low-risk transactions that still run the stages (0.0-1.0)
+    pub rate: f64,
+    /// Transactions scoring at least this before the stage always run it
+    pub risk_floor: u8,
+}
+
+impl Default for SamplingPolicy {
+    fn default() -> Self {
+        Self {
+            stages: vec![SampledStage::NetworkAnalysis, SampledStage::ModelScoring],
+            rate: 0.1,
+            risk_floor: 30,
+        }
+    }
+}
+
+/// Why a stage did or did not run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SamplingReason {
+    /// Risky traffic always runs the stage
+    Risky,
+    /// Low-risk traffic drawn into the sample
+    Sampled,
+    /// Low-risk traffic outside the sample
+    NotSampled,
+}
+
+/// One sampling decision, recorded on the result
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SamplingDecision {
+    pub stage: SampledStage,
+    pub ran: bool,
+    pub reason: SamplingReason,
+    /// Weighted score of the checks run before the decision
+    pub provisional_score: u8,
+}
+
+impl SamplingPolicy {
+    /// Whether the policy samples this stage at all
+    pub fn samples(&self, stage: SampledStage) -> bool {
+        self.stages.contains(&stage)
+    }
+
+    /// Decide whether a transaction runs a sampled stage
+    ///
+    /// `failed` is whether an earlier check already produced an error.
+    pub fn decide(
+        &self,
+        stage: SampledStage,
+        transaction_id: &str,
+        provisional_score: u8,
+        failed: bool,
+    ) -> SamplingDecision {
+        let reason = if failed || provisional_score >= self.risk_floor {
+            SamplingReason::Risky
+        } else if sample_point(stage, transaction_id) < self.rate {
+            SamplingReason::Sampled
+        } else {
+            SamplingReason::NotSampled
+        };
+        SamplingDecision {
+            stage,
+            ran: reason != SamplingReason::NotSampled,
+            reason,
+            provisional_score,
+        }
+    }
+}
+
+/// Stable position of a transaction in `[0, 1)` for one stage
+fn sample_point(stage: SampledStage, transaction_id: &str) -> f64 {
+    let digest = Sha256::digest(format!("{}|{}", stage.name(), transaction_id).as_bytes());
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    (u64::from_be_bytes(bytes) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{NetworkAnalyzer, TransactionType, TransactionValidator, ValidatorConfig};
+
+    #[test]
+    fn test_sampling_is_stable_and_near_rate() {
+        let policy = SamplingPolicy::default();
+        let sampled = (0..10_000)
+            .filter(|i| {
+                policy
+                    .decide(
+                        SampledStage::NetworkAnalysis,
+                        &format!("TXN-{}", i),
+                        0,
+                        false,
+                    )
+                    .ran
+            })
+            .count();
+        assert!((800..1200).contains(&sampled), "sampled {}", sampled);
+
+        let first = policy.decide(SampledStage::ModelScoring, "TXN-42", 0, false);
+        let again = policy.decide(SampledStage::ModelScoring, "TXN-42", 0, false);
+        assert_eq!(first, again);
+
+        let risky = policy.decide(SampledStage::ModelScoring, "TXN-42", 30, false);
+        assert_eq!(risky.reason, SamplingReason::Risky);
+        assert!(
+            policy
+                .decide(SampledStage::ModelScoring, "TXN-42", 0, true)
+                .ran
+        );
+    }
+
+    #[test]
+    fn test_low_risk_traffic_skips_unsampled_stages() {
+        let config = ValidatorConfig {
+            sampling: Some(SamplingPolicy {
+                stages: vec![SampledStage::NetworkAnalysis],
+                rate: 0.0,
+                risk_floor: 30,
+            }),
+            ..Default::default()
+        };
+        let mut validator = TransactionValidator::builder()
+            .with_config(config)
+            .with_network(NetworkAnalyzer::new())
+            .build();
+
+        let result = validator.validate(&create_test_transaction("TXN-SMP-1", 100.0));
+        assert_eq!(result.sampling.len(), 1);
+        assert_eq!(result.sampling[0].reason, SamplingReason::NotSampled);
+        assert_eq!(result.risk_breakdown.network_risk, 0);
+
+        // A large wire scores above the floor before network analysis
+        let mut wire = create_test_transaction("TXN-SMP-2", 60_000.0);
+        wire.transaction_type = TransactionType::WireTransfer;
+        let result = validator.validate(&wire);
+        assert_eq!(result.sampling[0].reason, SamplingReason::Risky);
+        assert!(result.sampling[0].ran);
+    }
+}
@@ -2,9 +2,13 @@
 //!
 //! Provides real-time sanctions list screening against OFAC, EU, and UN lists.
 
+use crate::clock::{system_clock, SharedClock};
+use crate::geographic_risk::comprehensive_sanctions_program;
+use crate::ofac::{OfacEntry, OfacPublication};
+use crate::similarity::SimilarityAlgorithm;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 /// Sanctions list source
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -37,6 +41,34 @@ pub enum MatchType {
     Alias,
 }
 
+/// Matching and reporting policy for one sanctions list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ListPolicy {
+    /// Minimum fuzzy similarity counted as a match
+    pub fuzzy_threshold: f32,
+    /// Matches below this confidence are left out of results
+    pub min_reportable_confidence: f32,
+    /// Confidence at or above which a match is treated as a hit
+    pub high_confidence_threshold: f32,
+}
+
+impl Default for ListPolicy {
+    fn default() -> Self {
+        Self {
+            fuzzy_threshold: 0.85,
+            min_reportable_confidence: 0.0,
+            high_confidence_threshold: DEFAULT_HIGH_CONFIDENCE,
+        }
+    }
+}
+
+/// Confidence treated as a hit when no list policy says otherwise
+pub const DEFAULT_HIGH_CONFIDENCE: f32 = 0.9;
+
+fn default_high_confidence() -> f32 {
+    DEFAULT_HIGH_CONFIDENCE
+}
+
 /// Sanctions screening result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SanctionsResult {
@@ -44,13 +76,54 @@ pub struct SanctionsResult {
     pub is_match: bool,
     pub matches: Vec<SanctionsMatch>,
     pub screening_time: DateTime<Utc>,
-    pub lists_checked: Vec<SanctionsList>,
+    pub lists_checked: Vec<ListProvenance>,
+}
+
+/// Where a list's entries came from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ListSource {
+    /// Publisher or feed, e.g. a download URL or vendor name
+    pub source: String,
+    /// Publisher's version or release identifier
+    pub version: String,
+    /// When the publisher released this version
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+impl Default for ListSource {
+    fn default() -> Self {
+        Self {
+            source: "built-in demonstration entries".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            published_at: None,
+        }
+    }
+}
+
+/// Dataset of one list in force when a name was screened
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ListProvenance {
+    pub list: SanctionsList,
+    pub source: String,
+    pub version: String,
+    pub published_at: Option<DateTime<Utc>>,
+    pub entry_count: usize,
 }
 
 impl SanctionsResult {
-    /// Check if there are any high-confidence matches
+    /// Check whether a list was screened against
+    pub fn checked_list(&self, list: &SanctionsList) -> bool {
+        self.lists_checked.iter().any(|p| &p.list == list)
+    }
+
+    /// Check if there are any high-confidence matches under their list's policy
     pub fn has_high_confidence_match(&self) -> bool {
-        self.matches.iter().any(|m| m.confidence >= 0.9)
+        self.matches.iter().any(SanctionsMatch::is_high_confidence)
+    }
+
+    /// Highest-confidence match that is a hit under its list's policy
+    pub fn highest_high_confidence(&self) -> Option<&SanctionsMatch> {
+        self.matches.iter().find(|m| m.is_high_confidence())
     }
 
     /// Get highest confidence match
@@ -81,6 +154,28 @@ pub struct SanctionsMatch {
     pub entry_id: String,
     pub program: Option<String>,
     pub country: Option<String>,
+    /// High-confidence threshold of the list's policy when screened
+    #[serde(default = "default_high_confidence")]
+    pub high_confidence_threshold: f32,
+}
+
+impl SanctionsMatch {
+    /// Check whether the match is a hit under its list's policy
+    pub fn is_high_confidence(&self) -> bool {
+        self.confidence >= self.high_confidence_threshold
+    }
+}
+
+/// New or changed matches for a name found by re-screening
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RescreeningChange {
+    pub screened_value: String,
+    /// Matches to entries the name did not match before
+    pub new_matches: Vec<SanctionsMatch>,
+    /// Matches to previously matched entries whose type or confidence changed
+    pub changed_matches: Vec<SanctionsMatch>,
+    /// Full current result, with the provenance of the lists now in force
+    pub result: SanctionsResult,
 }
 
 /// Sanctioned entity for the database
@@ -94,11 +189,39 @@ struct SanctionedEntity {
     country: Option<String>,
 }
 
+impl SanctionedEntity {
+    fn to_match(
+        &self,
+        match_type: MatchType,
+        confidence: f32,
+        policy: &ListPolicy,
+    ) -> SanctionsMatch {
+        SanctionsMatch {
+            matched_name: self.name.clone(),
+            list: self.list.clone(),
+            match_type,
+            confidence,
+            entry_id: self.id.clone(),
+            program: self.program.clone(),
+            country: self.country.clone(),
+            high_confidence_threshold: policy.high_confidence_threshold,
+        }
+    }
+}
+
 /// Sanctions screener
 pub struct SanctionsScreener {
     entities: Vec<SanctionedEntity>,
     enabled_lists: HashSet<SanctionsList>,
     fuzzy_threshold: f32,
+    similarity: SimilarityAlgorithm,
+    list_policies: HashMap<SanctionsList, ListPolicy>,
+    list_sources: HashMap<SanctionsList, ListSource>,
+    /// Lists whose last refresh failed, with the error
+    refresh_failures: HashMap<SanctionsList, String>,
+    /// Full records of loaded OFAC entries, by entry ID
+    ofac_entries: HashMap<String, OfacEntry>,
+    clock: SharedClock,
 }
 
 impl SanctionsScreener {
@@ -108,6 +231,12 @@ impl SanctionsScreener {
             entities: Vec::new(),
             enabled_lists: HashSet::new(),
             fuzzy_threshold: 0.85,
+            similarity: SimilarityAlgorithm::default(),
+            list_policies: HashMap::new(),
+            list_sources: HashMap::new(),
+            refresh_failures: HashMap::new(),
+            ofac_entries: HashMap::new(),
+            clock: system_clock(),
         };
         screener.enabled_lists.insert(SanctionsList::OFAC);
         screener.enabled_lists.insert(SanctionsList::EU);
@@ -159,64 +288,134 @@ impl SanctionsScreener {
         self.enabled_lists.remove(list);
     }
 
-    /// Set fuzzy matching threshold
+    /// Set fuzzy matching threshold for lists without their own policy
     pub fn set_fuzzy_threshold(&mut self, threshold: f32) {
         self.fuzzy_threshold = threshold.clamp(0.0, 1.0);
     }
 
+    /// Choose how fuzzy matches are scored
+    pub fn set_similarity_algorithm(&mut self, algorithm: SimilarityAlgorithm) {
+        self.similarity = algorithm;
+    }
+
+    /// Algorithm scoring fuzzy matches
+    pub fn similarity_algorithm(&self) -> SimilarityAlgorithm {
+        self.similarity
+    }
+
+    /// Override matching and reporting for one list
+    pub fn set_list_policy(&mut self, list: SanctionsList, policy: ListPolicy) {
+        let clamp = |v: f32| v.clamp(0.0, 1.0);
+        let policy = ListPolicy {
+            fuzzy_threshold: clamp(policy.fuzzy_threshold),
+            min_reportable_confidence: clamp(policy.min_reportable_confidence),
+            high_confidence_threshold: clamp(policy.high_confidence_threshold),
+        };
+        self.list_policies.insert(list, policy);
+    }
+
+    /// Record where a list's entries came from
+    ///
+    /// Setting a source marks the list as refreshed, clearing any failure
+    /// recorded with [`record_refresh_failure`](Self::record_refresh_failure).
+    pub fn set_list_source(&mut self, list: SanctionsList, source: ListSource) {
+        self.refresh_failures.remove(&list);
+        self.list_sources.insert(list, source);
+    }
+
+    /// Stamp screening results with times from `clock`
+    pub fn set_clock(&mut self, clock: SharedClock) {
+        self.clock = clock;
+    }
+
+    /// Record that refreshing a list failed and its current entries are stale
+    pub fn record_refresh_failure(&mut self, list: SanctionsList, error: &str) {
+        self.refresh_failures.insert(list, error.to_string());
+    }
+
+    /// Lists whose last refresh failed, with the error, ordered by name
+    pub fn refresh_failures(&self) -> Vec<(&SanctionsList, &str)> {
+        let mut failures: Vec<(&SanctionsList, &str)> = self
+            .refresh_failures
+            .iter()
+            .map(|(list, error)| (list, error.as_str()))
+            .collect();
+        failures.sort_by(|a, b| a.0.name().cmp(b.0.name()));
+        failures
+    }
+
+    /// Source, version, and entry count of each enabled list, ordered by name
+    pub fn list_provenance(&self) -> Vec<ListProvenance> {
+        let mut provenance: Vec<ListProvenance> = self
+            .enabled_lists
+            .iter()
+            .map(|list| {
+                let source = self.list_sources.get(list).cloned().unwrap_or_default();
+                ListProvenance {
+                    list: list.clone(),
+                    source: source.source,
+                    version: source.version,
+                    published_at: source.published_at,
+                    entry_count: self.entities.iter().filter(|e| &e.list == list).count(),
+                }
+            })
+            .collect();
+        provenance.sort_by(|a, b| a.list.name().cmp(b.list.name()));
+        provenance
+    }
+
+    /// Policy in force for a list
+    pub fn list_policy(&self, list: &SanctionsList) -> ListPolicy {
+        self.list_policies
+            .get(list)
+            .cloned()
+            .unwrap_or_else(|| ListPolicy {
+                fuzzy_threshold: self.fuzzy_threshold,
+                ..Default::default()
+            })
+    }
+
     /// Screen a name against sanctions lists
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "sanctions_screen",
+            skip_all,
+            fields(
+                lists = self.enabled_lists.len(),
+                match_count = tracing::field::Empty,
+            )
+        )
+    )]
     pub fn screen(&self, name: &str) -> SanctionsResult {
         let name_upper = name.to_uppercase();
         let mut matches = Vec::new();
-        let lists_checked: Vec<SanctionsList> = self.enabled_lists.iter().cloned().collect();
+        let lists_checked = self.list_provenance();
 
         for entity in &self.entities {
             if !self.enabled_lists.contains(&entity.list) {
                 continue;
             }
+            let policy = self.list_policy(&entity.list);
 
             // Exact match on primary name
             if entity.name == name_upper {
-                matches.push(SanctionsMatch {
-                    matched_name: entity.name.clone(),
-                    list: entity.list.clone(),
-                    match_type: MatchType::Exact,
-                    confidence: 1.0,
-                    entry_id: entity.id.clone(),
-                    program: entity.program.clone(),
-                    country: entity.country.clone(),
-                });
+                matches.push(entity.to_match(MatchType::Exact, 1.0, &policy));
                 continue;
             }
 
             // Check aliases
             for alias in &entity.aliases {
                 if alias.to_uppercase() == name_upper {
-                    matches.push(SanctionsMatch {
-                        matched_name: entity.name.clone(),
-                        list: entity.list.clone(),
-                        match_type: MatchType::Alias,
-                        confidence: 0.95,
-                        entry_id: entity.id.clone(),
-                        program: entity.program.clone(),
-                        country: entity.country.clone(),
-                    });
+                    matches.push(entity.to_match(MatchType::Alias, 0.95, &policy));
                     break;
                 }
             }
 
             // Fuzzy matching
             let similarity = self.calculate_similarity(&name_upper, &entity.name);
-            if similarity >= self.fuzzy_threshold {
-                matches.push(SanctionsMatch {
-                    matched_name: entity.name.clone(),
-                    list: entity.list.clone(),
-                    match_type: MatchType::Fuzzy,
-                    confidence: similarity,
-                    entry_id: entity.id.clone(),
-                    program: entity.program.clone(),
-                    country: entity.country.clone(),
-                });
+            if similarity >= policy.fuzzy_threshold {
+                matches.push(entity.to_match(MatchType::Fuzzy, similarity, &policy));
             }
 
             // Partial match (contains)
@@ -227,19 +426,13 @@ impl SanctionsScreener {
                             / name_upper.len().max(entity.name.len()) as f32));
 
                 if !matches.iter().any(|m| m.entry_id == entity.id) {
-                    matches.push(SanctionsMatch {
-                        matched_name: entity.name.clone(),
-                        list: entity.list.clone(),
-                        match_type: MatchType::Partial,
-                        confidence: partial_conf,
-                        entry_id: entity.id.clone(),
-                        program: entity.program.clone(),
-                        country: entity.country.clone(),
-                    });
+                    matches.push(entity.to_match(MatchType::Partial, partial_conf, &policy));
                 }
             }
         }
 
+        matches.retain(|m| m.confidence >= self.list_policy(&m.list).min_reportable_confidence);
+
         // Sort by confidence
         matches.sort_by(|a, b| {
             b.confidence
@@ -247,49 +440,156 @@ impl SanctionsScreener {
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("match_count", matches.len());
+
         SanctionsResult {
             screened_value: name.to_string(),
             is_match: !matches.is_empty(),
             matches,
-            screening_time: Utc::now(),
+            screening_time: self.clock.now(),
             lists_checked,
         }
     }
 
+    /// Comprehensive sanctions program covering a country or region code
+    ///
+    /// Unlike name screening this does not depend on the enabled lists:
+    /// comprehensively sanctioned jurisdictions are always blocked.
+    pub fn screen_country(&self, code: &str) -> Option<&'static str> {
+        comprehensive_sanctions_program(code)
+    }
+
     /// Screen multiple names in batch
     pub fn screen_batch(&self, names: &[&str]) -> Vec<SanctionsResult> {
         names.iter().map(|name| self.screen(name)).collect()
     }
 
-    /// Calculate string similarity using Levenshtein-based metric
-    fn calculate_similarity(&self, s1: &str, s2: &str) -> f32 {
-        if s1.is_empty() || s2.is_empty() {
-            return 0.0;
+    /// Re-screen previously screened names after a list update
+    ///
+    /// Returns only names with new or changed matches compared to their
+    /// earlier result. When a name appears more than once, its latest
+    /// result is the baseline.
+    pub fn rescreen(&self, previous: &[SanctionsResult]) -> Vec<RescreeningChange> {
+        let mut latest: Vec<&SanctionsResult> = Vec::new();
+        for result in previous {
+            match latest
+                .iter_mut()
+                .find(|r| r.screened_value == result.screened_value)
+            {
+                Some(slot) if slot.screening_time <= result.screening_time => *slot = result,
+                Some(_) => {}
+                None => latest.push(result),
+            }
         }
 
-        let len1 = s1.len();
-        let len2 = s2.len();
-        let max_len = len1.max(len2);
+        latest
+            .into_iter()
+            .filter_map(|before| {
+                let result = self.screen(&before.screened_value);
+                let mut new_matches = Vec::new();
+                let mut changed_matches = Vec::new();
+                for current in &result.matches {
+                    match before
+                        .matches
+                        .iter()
+                        .find(|m| m.entry_id == current.entry_id)
+                    {
+                        None => new_matches.push(current.clone()),
+                        Some(earlier)
+                            if earlier.match_type != current.match_type
+                                || (earlier.confidence - current.confidence).abs() > 0.001 =>
+                        {
+                            changed_matches.push(current.clone())
+                        }
+                        Some(_) => {}
+                    }
+                }
+                (!new_matches.is_empty() || !changed_matches.is_empty()).then(|| {
+                    RescreeningChange {
+                        screened_value: before.screened_value.clone(),
+                        new_matches,
+                        changed_matches,
+                        result,
+                    }
+                })
+            })
+            .collect()
+    }
 
-        // Simple character-based similarity
-        let common_chars: usize = s1.chars().filter(|c| s2.contains(*c)).count();
+    /// Re-screen a supplied list of names with no earlier results
+    ///
+    /// Every current match counts as new.
+    pub fn rescreen_names(&self, names: &[&str]) -> Vec<RescreeningChange> {
+        names
+            .iter()
+            .map(|name| self.screen(name))
+            .filter(|result| result.is_match)
+            .map(|result| RescreeningChange {
+                screened_value: result.screened_value.clone(),
+                new_matches: result.matches.clone(),
+                changed_matches: Vec::new(),
+                result,
+            })
+            .collect()
+    }
 
-        let char_similarity = common_chars as f32 / max_len as f32;
+    /// Similarity of two names under the configured algorithm
+    fn calculate_similarity(&self, s1: &str, s2: &str) -> f32 {
+        self.similarity.score(s1, s2)
+    }
 
-        // Word-based similarity
-        let words1: HashSet<&str> = s1.split_whitespace().collect();
-        let words2: HashSet<&str> = s2.split_whitespace().collect();
-        let common_words = words1.intersection(&words2).count();
-        let total_words = words1.union(&words2).count();
+    /// Replace the entries of an OFAC publication's list
+    ///
+    /// The list is enabled and its source set to the publication. Returns
+    /// the number of entries loaded.
+    pub fn load_ofac(&mut self, publication: OfacPublication) -> usize {
+        let kind = publication.kind;
+        let list = kind.list();
+        self.entities.retain(|e| e.list != list);
+        self.ofac_entries
+            .retain(|id, _| !id.starts_with(&format!("{}-", kind.id_prefix())));
+
+        let count = publication.entries.len();
+        for entry in publication.entries {
+            let id = format!("{}-{}", kind.id_prefix(), entry.uid);
+            self.entities.push(SanctionedEntity {
+                id: id.clone(),
+                name: entry.name.to_uppercase(),
+                aliases: entry
+                    .aliases
+                    .iter()
+                    .filter(|a| a.strong)
+                    .map(|a| a.name.to_uppercase())
+                    .collect(),
+                list: list.clone(),
+                program: (!entry.programs.is_empty()).then(|| entry.programs.join(", ")),
+                country: entry.addresses.iter().find_map(|a| a.country.clone()),
+            });
+            self.ofac_entries.insert(id, entry);
+        }
 
-        let word_similarity = if total_words > 0 {
-            common_words as f32 / total_words as f32
-        } else {
-            0.0
-        };
+        self.enabled_lists.insert(list.clone());
+        self.set_list_source(
+            list,
+            ListSource {
+                source: kind.source_name().to_string(),
+                version: publication
+                    .publish_date
+                    .map(|d| d.to_string())
+                    .unwrap_or_else(|| "undated".to_string()),
+                published_at: publication
+                    .publish_date
+                    .and_then(|d| d.and_hms_opt(0, 0, 0))
+                    .map(|d| d.and_utc()),
+            },
+        );
+        count
+    }
 
-        // Weighted combination
-        (char_similarity * 0.4) + (word_similarity * 0.6)
+    /// Full OFAC record behind a match's `entry_id`
+    pub fn ofac_entry(&self, entry_id: &str) -> Option<&OfacEntry> {
+        self.ofac_entries.get(entry_id)
     }
 
     /// Add a custom sanctioned entity
@@ -380,7 +680,57 @@ mod tests {
 
         let result = screener.screen("SANCTIONED ENTITY ONE");
         // OFAC entry should not match since we disabled OFAC
-        assert!(!result.lists_checked.contains(&SanctionsList::OFAC));
+        assert!(!result.checked_list(&SanctionsList::OFAC));
+    }
+
+    #[test]
+    fn test_rescreening_reports_only_changes() {
+        let mut screener = SanctionsScreener::new();
+        let previous = screener.screen_batch(&["SANCTIONED ENTITY ONE", "NEWLY LISTED HOLDINGS"]);
+        assert!(screener.rescreen(&previous).is_empty());
+
+        screener.add_entity("NEWLY LISTED HOLDINGS", vec![], SanctionsList::OFAC);
+        let changes = screener.rescreen(&previous);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].screened_value, "NEWLY LISTED HOLDINGS");
+        assert_eq!(changes[0].new_matches[0].match_type, MatchType::Exact);
+        assert!(changes[0].changed_matches.is_empty());
+
+        let supplied =
+            screener.rescreen_names(&["NEWLY LISTED HOLDINGS", "LEGITIMATE COMPANY XYZ"]);
+        assert_eq!(supplied.len(), 1);
+    }
+
+    #[test]
+    fn test_list_provenance() {
+        let mut screener = SanctionsScreener::new();
+        let published = Utc::now();
+        screener.set_list_source(
+            SanctionsList::OFAC,
+            ListSource {
+                source: "https://sanctionslist.ofac.treas.gov".to_string(),
+                version: "2024-06-01".to_string(),
+                published_at: Some(published),
+            },
+        );
+
+        let result = screener.screen("ACME TRADING");
+        assert_eq!(result.lists_checked.len(), 3);
+        let ofac = result
+            .lists_checked
+            .iter()
+            .find(|p| p.list == SanctionsList::OFAC)
+            .unwrap();
+        assert_eq!(ofac.version, "2024-06-01");
+        assert_eq!(ofac.published_at, Some(published));
+        assert_eq!(ofac.entry_count, 1);
+
+        let eu = result
+            .lists_checked
+            .iter()
+            .find(|p| p.list == SanctionsList::EU)
+            .unwrap();
+        assert_eq!(eu.source, ListSource::default().source);
     }
 
     #[test]
@@ -395,6 +745,66 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_similarity_algorithm_choice() {
+        let mut screener = SanctionsScreener::new();
+        assert_eq!(
+            screener.similarity_algorithm(),
+            SimilarityAlgorithm::Levenshtein
+        );
+        let reordered = screener.screen("ONE SANCTIONED ENTITY");
+        assert_eq!(reordered.matches[0].match_type, MatchType::Fuzzy);
+        assert!(reordered.has_high_confidence_match());
+        assert!(screener.screen("SANCTIONED ENTTY ONE").is_match);
+
+        screener.set_similarity_algorithm(SimilarityAlgorithm::Overlap);
+        assert!(!screener.screen("SANCTIONED ENTTY ONE").is_match);
+
+        screener.set_similarity_algorithm(SimilarityAlgorithm::JaroWinkler);
+        assert!(screener
+            .screen("SANCTIONED ENTTY ONE")
+            .has_high_confidence_match());
+    }
+
+    #[test]
+    fn test_per_list_policies() {
+        let mut screener = SanctionsScreener::new();
+        screener.add_entity(
+            "WATCHED TRADING HOUSE",
+            vec![],
+            SanctionsList::Custom("INTERNAL".to_string()),
+        );
+        screener.enable_list(SanctionsList::Custom("INTERNAL".to_string()));
+
+        // Internal watch list: only report strong matches, and only exact ones are hits
+        screener.set_list_policy(
+            SanctionsList::Custom("INTERNAL".to_string()),
+            ListPolicy {
+                min_reportable_confidence: 0.8,
+                high_confidence_threshold: 1.0,
+                ..Default::default()
+            },
+        );
+        // OFAC: treat partial matches as hits
+        screener.set_list_policy(
+            SanctionsList::OFAC,
+            ListPolicy {
+                high_confidence_threshold: 0.75,
+                ..Default::default()
+            },
+        );
+
+        let watched = screener.screen("WATCHED TRADING");
+        assert!(watched.matches.iter().all(|m| m.confidence >= 0.8));
+        assert!(!watched.has_high_confidence_match());
+
+        let ofac = screener.screen("SANCTIONED ENTITY");
+        let hit = ofac.highest_high_confidence().unwrap();
+        assert_eq!(hit.list, SanctionsList::OFAC);
+        assert_eq!(hit.high_confidence_threshold, 0.75);
+        assert!(hit.confidence < DEFAULT_HIGH_CONFIDENCE);
+    }
+
     #[test]
     fn test_highest_confidence() {
         let screener = SanctionsScreener::new();
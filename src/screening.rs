@@ -0,0 +1,342 @@
+//! Pluggable sanctions screening backends
+//!
+//! The validator screens names through a [`ScreeningBackend`]. The local
+//! [`SanctionsScreener`] implements it directly and needs no network, so it
+//! stays the default. Deployments that use a vendor service implement the
+//! trait for their client and wrap it in [`FallbackScreening`], which retries
+//! transient failures within a deadline and then falls back to the local
+//! engine instead of leaving the transaction unscreened.
+//!
+//! Remote clients that are async implement [`AsyncScreeningBackend`] and are
+//! driven with [`screen_with_retry`] in the caller's runtime.
+//!
+//! [`FallbackScreening`] cannot interrupt a call that hangs; use
+//! [`ResilientScreening`](crate::ResilientScreening) when the vendor client
+//! has no timeout of its own.
+
+use crate::{DegradationReason, ReferenceDataset, SanctionsResult, SanctionsScreener, SharedClock};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// Screening backend errors
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScreeningError {
+    #[error("Screening service unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("Screening service timed out")]
+    Timeout,
+
+    #[error("Invalid screening response: {0}")]
+    InvalidResponse(String),
+}
+
+impl ScreeningError {
+    /// Whether retrying the same request may succeed
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            ScreeningError::Unavailable(_) | ScreeningError::Timeout
+        )
+    }
+}
+
+/// Synchronous sanctions screening, called from `validate()`
+pub trait ScreeningBackend: Send + Sync {
+    /// Backend name used in warnings and audit records
+    fn name(&self) -> &str;
+
+    /// Screen one name against the backend's lists
+    fn screen(&self, name: &str) -> Result<SanctionsResult, ScreeningError>;
+
+    /// Reference data the backend is currently screening without
+    fn degradations(&self) -> Vec<DegradationReason> {
+        Vec::new()
+    }
+
+    /// Lists the backend screens against and their publication dates
+    fn datasets(&self) -> Vec<ReferenceDataset> {
+        Vec::new()
+    }
+
+    /// Read the current time from `clock`; backends without local time ignore it
+    fn set_clock(&mut self, _clock: SharedClock) {}
+}
+
+impl ScreeningBackend for SanctionsScreener {
+    fn name(&self) -> &str {
+        "local"
+    }
+
+    fn screen(&self, name: &str) -> Result<SanctionsResult, ScreeningError> {
+        Ok(SanctionsScreener::screen(self, name))
+    }
+
+    fn degradations(&self) -> Vec<DegradationReason> {
+        self.refresh_failures()
+            .into_iter()
+            .map(|(list, error)| DegradationReason::SanctionsRefreshFailed {
+                list: list.clone(),
+                error: error.to_string(),
+            })
+            .collect()
+    }
+
+    fn datasets(&self) -> Vec<ReferenceDataset> {
+        self.list_provenance()
+            .into_iter()
+            .map(|p| ReferenceDataset::new(p.list.name(), p.published_at))
+            .collect()
+    }
+
+    fn set_clock(&mut self, clock: SharedClock) {
+        SanctionsScreener::set_clock(self, clock);
+    }
+}
+
+/// Asynchronous screening for remote vendor services
+///
+/// Per-call timeouts belong to the client (most HTTP clients take one);
+/// [`screen_with_retry`] bounds the total time spent retrying.
+pub trait AsyncScreeningBackend: Send + Sync {
+    /// Backend name used in warnings and audit records
+    fn name(&self) -> &str;
+
+    /// Screen one name against the backend's lists
+    fn screen(
+        &self,
+        name: &str,
+    ) -> impl Future<Output = Result<SanctionsResult, ScreeningError>> + Send;
+}
+
+/// How long and how often to retry a remote backend
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Attempts including the first
+    pub max_attempts: u32,
+    /// Total time after which no further attempt is started
+    pub deadline: Duration,
+    /// Pause between attempts (synchronous backends only)
+    pub backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            deadline: Duration::from_secs(2),
+            backoff: Duration::from_millis(50),
+        }
+    }
+}
+
+/// Remote backend with retries and a local fallback
+pub struct FallbackScreening<B> {
+    primary: B,
+    fallback: Option<SanctionsScreener>,
+    policy: RetryPolicy,
+}
+
+impl<B: ScreeningBackend> FallbackScreening<B> {
+    /// Retry `primary` under the default policy, then screen locally
+    pub fn new(primary: B, fallback: SanctionsScreener) -> Self {
+        Self {
+            primary,
+            fallback: Some(fallback),
+            policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Retry `primary` and report its error once retries are exhausted
+    pub fn without_fallback(primary: B) -> Self {
+        Self {
+            primary,
+            fallback: None,
+            policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Use a custom retry policy
+    pub fn with_policy(mut self, policy: RetryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+impl<B: ScreeningBackend> ScreeningBackend for FallbackScreening<B> {
+    fn name(&self) -> &str {
+        self.primary.name()
+    }
+
+    fn screen(&self, name: &str) -> Result<SanctionsResult, ScreeningError> {
+        let started = Instant::now();
+        let mut attempt = 0;
+        let error = loop {
+            attempt += 1;
+            match self.primary.screen(name) {
+                Ok(result) => return Ok(result),
+                Err(error)
+                    if error.is_transient()
+                        && attempt < self.policy.max_attempts
+                        && started.elapsed() + self.policy.backoff < self.policy.deadline =>
+                {
+                    std::thread::sleep(self.policy.backoff);
+                }
+                Err(error) => break error,
+            }
+        };
+
+        match &self.fallback {
+            Some(local) => Ok(SanctionsScreener::screen(local, name)),
+            None => Err(error),
+        }
+    }
+
+    fn degradations(&self) -> Vec<DegradationReason> {
+        let mut reasons = self.primary.degradations();
+        if let Some(ref local) = self.fallback {
+            reasons.extend(ScreeningBackend::degradations(local));
+        }
+        reasons
+    }
+
+    fn datasets(&self) -> Vec<ReferenceDataset> {
+        let mut datasets = self.primary.datasets();
+        if let Some(ref local) = self.fallback {
+            datasets.extend(ScreeningBackend::datasets(local));
+        }
+        datasets
+    }
+
+    fn set_clock(&mut self, clock: SharedClock) {
+        if let Some(ref mut local) = self.fallback {
+            local.set_clock(clock.clone());
+        }
+        self.primary.set_clock(clock);
+    }
+}
+
+/// Screen with an async backend, retrying transient failures
+///
+/// Attempts are not paced, since pausing needs the caller's runtime timer;
+/// the deadline is checked before each retry. Falls back to `fallback` when
+/// given and retries are exhausted.
+pub async fn screen_with_retry<B: AsyncScreeningBackend>(
+    backend: &B,
+    fallback: Option<&SanctionsScreener>,
+    name: &str,
+    policy: &RetryPolicy,
+) -> Result<SanctionsResult, ScreeningError> {
+    let started = Instant::now();
+    let mut attempt = 0;
+    let error = loop {
+        attempt += 1;
+        match backend.screen(name).await {
+            Ok(result) => return Ok(result),
+            Err(error)
+                if error.is_transient()
+                    && attempt < policy.max_attempts
+                    && started.elapsed() < policy.deadline => {}
+            Err(error) => break error,
+        }
+    };
+
+    match fallback {
+        Some(local) => Ok(local.screen(name)),
+        None => Err(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{TransactionMetadata, TransactionValidator, ValidationError};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Fails the first `failures` calls, then screens locally
+    struct FlakyBackend {
+        failures: u32,
+        calls: AtomicU32,
+        local: SanctionsScreener,
+    }
+
+    impl FlakyBackend {
+        fn new(failures: u32) -> Self {
+            Self {
+                failures,
+                calls: AtomicU32::new(0),
+                local: SanctionsScreener::new(),
+            }
+        }
+    }
+
+    impl ScreeningBackend for FlakyBackend {
+        fn name(&self) -> &str {
+            "vendor"
+        }
+
+        fn screen(&self, name: &str) -> Result<SanctionsResult, ScreeningError> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+                Err(ScreeningError::Timeout)
+            } else {
+                Ok(self.local.screen(name))
+            }
+        }
+    }
+
+    fn quick_policy() -> RetryPolicy {
+        RetryPolicy {
+            backoff: Duration::ZERO,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_retries_transient_failures() {
+        let backend =
+            FallbackScreening::without_fallback(FlakyBackend::new(2)).with_policy(quick_policy());
+        let result = ScreeningBackend::screen(&backend, "SANCTIONED ENTITY ONE").unwrap();
+        assert!(result.is_match);
+        assert_eq!(backend.primary.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_falls_back_to_local_engine() {
+        let unreachable =
+            FallbackScreening::without_fallback(FlakyBackend::new(10)).with_policy(quick_policy());
+        assert_eq!(
+            ScreeningBackend::screen(&unreachable, "SANCTIONED ENTITY ONE").unwrap_err(),
+            ScreeningError::Timeout
+        );
+
+        let backend = FallbackScreening::new(FlakyBackend::new(10), SanctionsScreener::new())
+            .with_policy(quick_policy());
+        let result = ScreeningBackend::screen(&backend, "SANCTIONED ENTITY ONE").unwrap();
+        assert!(result.has_high_confidence_match());
+        assert_eq!(backend.primary.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_validator_fails_closed_on_backend_error() {
+        let mut validator = TransactionValidator::new();
+        validator.set_screening_backend(
+            FallbackScreening::without_fallback(FlakyBackend::new(10)).with_policy(quick_policy()),
+        );
+        let mut transaction = create_test_transaction("TXN-SCR-001", 100.0);
+        transaction.metadata = Some(TransactionMetadata::from([(
+            "beneficiary_name".to_string(),
+            "ACME TRADING".to_string(),
+        )]));
+
+        let result = validator.validate(&transaction);
+        assert!(!result.is_valid);
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::ComplianceFailed(d) if d.contains("vendor"))));
+        assert_eq!(result.compliance_checks.get("SANCTIONS"), Some(&false));
+    }
+}
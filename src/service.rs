@@ -0,0 +1,548 @@
+//! Streaming validation service
+//!
+//! [`ValidatorService`] validates a stream of transactions on a pool of
+//! worker threads and emits results on an output channel. Each worker owns a
+//! validator built by the caller's factory; transactions are routed to
+//! workers by user ID, so one user's transactions are validated in
+//! submission order by the same validator and velocity and duplicate checks
+//! see them exactly as a single validator would.
+//!
+//! Every queue is bounded. [`ValidatorService::submit`] blocks while the
+//! target worker's queue is full, and workers block while the output
+//! channel is full, so a slow consumer slows intake instead of growing
+//! memory without limit.
+//!
+//! Factories should share one [`SharedHistory`](crate::SharedHistory)
+//! through
+//! [`TransactionValidatorBuilder::with_history`](crate::TransactionValidatorBuilder::with_history)
+//! so checks that read history see every worker's transactions, and one
+//! [`SharedDedup`](crate::SharedDedup) through
+//! [`TransactionValidatorBuilder::with_dedup`](crate::TransactionValidatorBuilder::with_dedup)
+//! so a transaction ID replayed by another user is still caught.
+//!
+//! The service runs on OS threads and std channels. With the `async`
+//! feature, [`ValidatorService::start_async`] returns results as an
+//! [`AsyncResultStream`] and [`ValidatorService::submit_async`] waits for
+//! queue space without blocking the executor, so an async application can
+//! feed the service from its `Stream<Item = Transaction>`. Both work on any
+//! executor: the crate depends on neither tokio nor `futures`, so
+//! [`AsyncResultStream`] has `Stream`'s `poll_next` as an inherent method
+//! instead of implementing the trait.
+
+use crate::{Transaction, TransactionValidator, ValidationResult};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+#[cfg(feature = "async")]
+use std::sync::{Condvar, Mutex, MutexGuard};
+#[cfg(feature = "async")]
+use std::task::{Context, Poll, Waker};
+use std::thread::JoinHandle;
+use thiserror::Error;
+
+/// Worker count and queue sizes for a [`ValidatorService`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServiceConfig {
+    /// Validation worker threads
+    pub workers: usize,
+    /// Transactions each worker may have queued before `submit` blocks
+    pub queue_capacity: usize,
+    /// Results buffered before workers block on the consumer
+    pub output_capacity: usize,
+}
+
+impl Default for ServiceConfig {
+    fn default() -> Self {
+        Self {
+            workers: 4,
+            queue_capacity: 64,
+            output_capacity: 256,
+        }
+    }
+}
+
+/// Transaction rejected by the service, handed back to the caller
+#[derive(Error, Debug)]
+pub enum SubmitError {
+    #[error("Worker queue is full")]
+    Full(Box<Transaction>),
+
+    #[error("Service has stopped")]
+    Stopped(Box<Transaction>),
+}
+
+impl SubmitError {
+    /// The transaction that could not be submitted
+    pub fn into_transaction(self) -> Transaction {
+        match self {
+            SubmitError::Full(tx) | SubmitError::Stopped(tx) => *tx,
+        }
+    }
+}
+
+/// Pool of validators consuming transactions with bounded queues
+pub struct ValidatorService {
+    queues: Vec<SyncSender<Transaction>>,
+    /// Tasks in `submit_async` waiting for room in each queue
+    #[cfg(feature = "async")]
+    waiting: Vec<Arc<Mutex<Vec<Waker>>>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl ValidatorService {
+    /// Start the workers and return the service with its result channel
+    ///
+    /// `factory` is called once per worker, on that worker's thread, with
+    /// the worker's index. The result channel closes once the service is
+    /// shut down and every queued transaction has been validated.
+    pub fn start<F>(config: ServiceConfig, factory: F) -> (Self, Receiver<ValidationResult>)
+    where
+        F: Fn(usize) -> TransactionValidator + Send + Sync + 'static,
+    {
+        let factory = Arc::new(factory);
+        let (output, results) = mpsc::sync_channel(config.output_capacity);
+        let mut queues = Vec::new();
+        #[cfg(feature = "async")]
+        let mut waiting = Vec::new();
+        let mut workers = Vec::new();
+        for index in 0..config.workers.max(1) {
+            let (queue, input) = mpsc::sync_channel::<Transaction>(config.queue_capacity);
+            let factory = Arc::clone(&factory);
+            let output = output.clone();
+            #[cfg(feature = "async")]
+            let waiters = Arc::new(Mutex::new(Vec::<Waker>::new()));
+            #[cfg(feature = "async")]
+            waiting.push(Arc::clone(&waiters));
+            workers.push(std::thread::spawn(move || {
+                let mut validator = factory(index);
+                for transaction in input {
+                    // Taking a transaction made room in the queue
+                    #[cfg(feature = "async")]
+                    wake_all(&waiters);
+                    if output.send(validator.validate(&transaction)).is_err() {
+                        break;
+                    }
+                }
+            }));
+            queues.push(queue);
+        }
+        let service = Self {
+            queues,
+            #[cfg(feature = "async")]
+            waiting,
+            workers,
+        };
+        (service, results)
+    }
+
+    /// Start the workers and return results as an async stream
+    #[cfg(feature = "async")]
+    pub fn start_async<F>(config: ServiceConfig, factory: F) -> (Self, AsyncResultStream)
+    where
+        F: Fn(usize) -> TransactionValidator + Send + Sync + 'static,
+    {
+        let (service, results) = Self::start(config, factory);
+        (service, AsyncResultStream::new(results))
+    }
+
+    /// Queue a transaction, waiting without blocking while its worker's queue is full
+    #[cfg(feature = "async")]
+    pub async fn submit_async(&self, transaction: Transaction) -> Result<(), SubmitError> {
+        let worker = self.worker_for(&transaction.user_id);
+        let mut transaction = Some(transaction);
+        std::future::poll_fn(|cx| {
+            let Some(tx) = transaction.take() else {
+                return Poll::Ready(Ok(()));
+            };
+            let tx = match self.try_submit_to(worker, tx) {
+                Err(SubmitError::Full(tx)) => tx,
+                done => return Poll::Ready(done),
+            };
+            // Register before retrying so a worker taking a transaction in
+            // between still wakes this task
+            lock(&self.waiting[worker]).push(cx.waker().clone());
+            match self.try_submit_to(worker, *tx) {
+                Err(SubmitError::Full(tx)) => {
+                    transaction = Some(*tx);
+                    Poll::Pending
+                }
+                done => Poll::Ready(done),
+            }
+        })
+        .await
+    }
+
+    /// Number of workers
+    pub fn workers(&self) -> usize {
+        self.queues.len()
+    }
+
+    /// Worker that validates transactions from `user_id`
+    pub fn worker_for(&self, user_id: &str) -> usize {
+        route(user_id, self.queues.len())
+    }
+
+    /// Queue a transaction, blocking while its worker's queue is full
+    pub fn submit(&self, transaction: Transaction) -> Result<(), SubmitError> {
+        let queue = &self.queues[self.worker_for(&transaction.user_id)];
+        queue
+            .send(transaction)
+            .map_err(|e| SubmitError::Stopped(Box::new(e.0)))
+    }
+
+    /// Queue a transaction without blocking
+    pub fn try_submit(&self, transaction: Transaction) -> Result<(), SubmitError> {
+        self.try_submit_to(self.worker_for(&transaction.user_id), transaction)
+    }
+
+    fn try_submit_to(&self, worker: usize, transaction: Transaction) -> Result<(), SubmitError> {
+        self.queues[worker]
+            .try_send(transaction)
+            .map_err(|e| match e {
+                TrySendError::Full(tx) => SubmitError::Full(Box::new(tx)),
+                TrySendError::Disconnected(tx) => SubmitError::Stopped(Box::new(tx)),
+            })
+    }
+
+    /// Stop accepting transactions and wait for queued ones to finish
+    ///
+    /// The result channel must be drained concurrently, or workers blocked
+    /// on a full output channel never finish.
+    pub fn shutdown(self) {
+        drop(self.queues);
+        for worker in self.workers {
+            let _ = worker.join();
+        }
+    }
+
+    /// Validate every transaction from `stream`, yielding results as they finish
+    ///
+    /// Results for the same user keep submission order; results for
+    /// different users interleave in completion order. Intake is paced by
+    /// the consumer: the stream is read only as fast as results are taken.
+    /// Dropping the returned iterator early stops the service.
+    pub fn process<I, F>(config: ServiceConfig, factory: F, stream: I) -> ResultStream
+    where
+        I: IntoIterator<Item = Transaction>,
+        I::IntoIter: Send + 'static,
+        F: Fn(usize) -> TransactionValidator + Send + Sync + 'static,
+    {
+        let (service, results) = Self::start(config, factory);
+        let stream = stream.into_iter();
+        let feeder = std::thread::spawn(move || {
+            for transaction in stream {
+                if service.submit(transaction).is_err() {
+                    break;
+                }
+            }
+            service.shutdown();
+        });
+        ResultStream {
+            results,
+            feeder: Some(feeder),
+        }
+    }
+}
+
+/// Results of [`ValidatorService::process`], in completion order
+pub struct ResultStream {
+    results: Receiver<ValidationResult>,
+    feeder: Option<JoinHandle<()>>,
+}
+
+impl Iterator for ResultStream {
+    type Item = ValidationResult;
+
+    fn next(&mut self) -> Option<ValidationResult> {
+        match self.results.recv() {
+            Ok(result) => Some(result),
+            Err(_) => {
+                // Every worker has exited; the feeder finishes shutting down
+                if let Some(feeder) = self.feeder.take() {
+                    let _ = feeder.join();
+                }
+                None
+            }
+        }
+    }
+}
+
+/// Results of [`ValidatorService::start_async`], in completion order
+///
+/// A bridge thread hands over one result at a time, so workers still block
+/// once the output channel is full and the consumer paces intake.
+#[cfg(feature = "async")]
+pub struct AsyncResultStream {
+    bridge: Arc<Bridge>,
+    thread: Option<JoinHandle<()>>,
+}
+
+#[cfg(feature = "async")]
+#[derive(Default)]
+struct Bridge {
+    slot: Mutex<BridgeSlot>,
+    /// Signalled when the slot is emptied or the stream is dropped
+    taken: Condvar,
+}
+
+#[cfg(feature = "async")]
+#[derive(Default)]
+struct BridgeSlot {
+    result: Option<ValidationResult>,
+    /// Every worker has exited
+    closed: bool,
+    /// The stream was dropped
+    dropped: bool,
+    waker: Option<Waker>,
+}
+
+#[cfg(feature = "async")]
+impl AsyncResultStream {
+    /// Bridge a result channel from [`ValidatorService::start`]
+    pub fn new(results: Receiver<ValidationResult>) -> Self {
+        let bridge = Arc::new(Bridge::default());
+        let shared = Arc::clone(&bridge);
+        let thread = std::thread::spawn(move || {
+            for result in results {
+                let mut slot = lock(&shared.slot);
+                while slot.result.is_some() && !slot.dropped {
+                    slot = shared.taken.wait(slot).unwrap_or_else(|e| e.into_inner());
+                }
+                if slot.dropped {
+                    return;
+                }
+                slot.result = Some(result);
+                if let Some(waker) = slot.waker.take() {
+                    waker.wake();
+                }
+            }
+            let mut slot = lock(&shared.slot);
+            slot.closed = true;
+            if let Some(waker) = slot.waker.take() {
+                waker.wake();
+            }
+        });
+        Self {
+            bridge,
+            thread: Some(thread),
+        }
+    }
+
+    /// Poll for the next result, with the signature of `Stream::poll_next`
+    pub fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<ValidationResult>> {
+        let mut slot = lock(&self.bridge.slot);
+        if let Some(result) = slot.result.take() {
+            self.bridge.taken.notify_one();
+            return Poll::Ready(Some(result));
+        }
+        if slot.closed {
+            drop(slot);
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+            return Poll::Ready(None);
+        }
+        slot.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+
+    /// Wait for the next result; `None` once the service has shut down
+    pub async fn next(&mut self) -> Option<ValidationResult> {
+        std::future::poll_fn(|cx| std::pin::Pin::new(&mut *self).poll_next(cx)).await
+    }
+}
+
+#[cfg(feature = "async")]
+impl Drop for AsyncResultStream {
+    fn drop(&mut self) {
+        // Release the bridge so its receiver drops and workers stop
+        lock(&self.bridge.slot).dropped = true;
+        self.bridge.taken.notify_one();
+    }
+}
+
+/// Lock ignoring poisoning; the guarded state stays consistent
+#[cfg(feature = "async")]
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(feature = "async")]
+fn wake_all(waiters: &Mutex<Vec<Waker>>) {
+    for waker in lock(waiters).drain(..) {
+        waker.wake();
+    }
+}
+
+/// Index in `0..count` that transactions from `user_id` are routed to
+pub(crate) fn route(user_id: &str, count: usize) -> usize {
+    let mut hasher = DefaultHasher::new();
+    user_id.hash(&mut hasher);
+    (hasher.finish() % count as u64) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{SharedHistory, ValidationError, ValidatorConfig};
+    use chrono::Duration;
+
+    #[test]
+    fn test_process_keeps_per_user_order_and_velocity() {
+        let history = SharedHistory::default();
+        let factory = move |_| {
+            TransactionValidator::builder()
+                .with_config(ValidatorConfig {
+                    max_transactions_per_window: 3,
+                    ..Default::default()
+                })
+                .with_history(history.clone())
+                .build()
+        };
+
+        let mut stream = Vec::new();
+        for i in 0..5 {
+            for (n, user) in ["USER-A", "USER-B", "USER-C"].into_iter().enumerate() {
+                let mut tx = create_test_transaction(&format!("TXN-SVC-{user}-{i}"), 10.0);
+                tx.user_id = user.to_string();
+                tx.from_account = Some(format!("ACCT-1111-2222-000{n}"));
+                tx.timestamp += Duration::seconds(i);
+                stream.push(tx);
+            }
+        }
+
+        let config = ServiceConfig {
+            workers: 3,
+            queue_capacity: 2,
+            output_capacity: 2,
+        };
+        let results: Vec<ValidationResult> =
+            ValidatorService::process(config, factory, stream).collect();
+        assert_eq!(results.len(), 15);
+
+        let user_a: Vec<&ValidationResult> = results
+            .iter()
+            .filter(|r| r.transaction_id.starts_with("TXN-SVC-USER-A-"))
+            .collect();
+        let ids: Vec<&str> = user_a.iter().map(|r| r.transaction_id.as_str()).collect();
+        assert_eq!(
+            ids,
+            (0..5)
+                .map(|i| format!("TXN-SVC-USER-A-{i}"))
+                .collect::<Vec<_>>()
+        );
+        // The fourth transaction in the window breaches the per-user limit
+        assert!(user_a[..3].iter().all(|r| r.is_valid));
+        assert!(user_a[3]
+            .errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::VelocityViolation(_))));
+    }
+
+    #[test]
+    fn test_process_is_paced_by_the_consumer() {
+        let consumed = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = Arc::clone(&consumed);
+        let stream = (0..1000).map(move |i| {
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            create_test_transaction(&format!("TXN-SVC-P-{i}"), 10.0)
+        });
+        let config = ServiceConfig {
+            workers: 1,
+            queue_capacity: 1,
+            output_capacity: 1,
+        };
+        let mut results =
+            ValidatorService::process(config, |_| TransactionValidator::new(), stream);
+        assert!(results.next().is_some());
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        // Bounded queues hold only a handful of transactions ahead of the consumer
+        assert!(consumed.load(std::sync::atomic::Ordering::SeqCst) < 10);
+        drop(results);
+    }
+
+    #[test]
+    fn test_try_submit_reports_full_queue() {
+        let (service, results) = ValidatorService::start(
+            ServiceConfig {
+                workers: 1,
+                queue_capacity: 0,
+                output_capacity: 0,
+            },
+            |_| TransactionValidator::new(),
+        );
+        // Nothing drains the output, so the worker blocks on its first result
+        service
+            .submit(create_test_transaction("TXN-SVC-1", 10.0))
+            .unwrap();
+        let rejected = loop {
+            match service.try_submit(create_test_transaction("TXN-SVC-2", 10.0)) {
+                Err(SubmitError::Full(tx)) => break tx,
+                Ok(()) => {}
+                Err(e) => panic!("unexpected error: {e}"),
+            }
+        };
+        assert_eq!(rejected.transaction_id, "TXN-SVC-2");
+
+        let drain = std::thread::spawn(move || results.iter().count());
+        service.shutdown();
+        assert!(drain.join().unwrap() >= 1);
+    }
+
+    /// Run a future on this thread, parking until its waker fires
+    #[cfg(feature = "async")]
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        struct Unpark(std::thread::Thread);
+
+        impl std::task::Wake for Unpark {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+
+        let mut future = std::pin::pin!(future);
+        let waker = Waker::from(Arc::new(Unpark(std::thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+            std::thread::park();
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn test_async_submit_and_results_wake_on_progress() {
+        let config = ServiceConfig {
+            workers: 2,
+            queue_capacity: 1,
+            output_capacity: 1,
+        };
+        let (service, mut results) =
+            ValidatorService::start_async(config, |_| TransactionValidator::new());
+        let producer = std::thread::spawn(move || {
+            for i in 0..20 {
+                let mut tx = create_test_transaction(&format!("TXN-SVC-A-{i}"), 10.0);
+                tx.user_id = format!("USER-{}", i % 3);
+                block_on(service.submit_async(tx)).unwrap();
+            }
+            service.shutdown();
+        });
+
+        let mut ids = Vec::new();
+        while let Some(result) = block_on(results.next()) {
+            ids.push(result.transaction_id);
+        }
+        producer.join().unwrap();
+        ids.sort();
+        let mut expected: Vec<String> = (0..20).map(|i| format!("TXN-SVC-A-{i}")).collect();
+        expected.sort();
+        assert_eq!(ids, expected);
+    }
+}
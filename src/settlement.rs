@@ -0,0 +1,261 @@
+//! Value dates, settlement dates, and cut-offs
+//!
+//! Payment operations reject or re-date a large share of payments on dates
+//! alone: a value date weeks in the past, a settlement date on a day the
+//! currency's clearing system is closed, a same-day wire submitted after
+//! the currency's cut-off. A [`SettlementCalendar`] holds per-currency
+//! holidays and wire cut-off times (UTC) and checks
+//! `Transaction::value_date` and `Transaction::settlement_date` against
+//! them and the [`SettlementPolicy`]. Back-dating beyond the policy and a
+//! settlement date before the value date are business rule violations;
+//! weekend and holiday dates and missed cut-offs are warnings, since
+//! operations usually roll those payments to the next business day.
+
+use crate::{Transaction, TransactionType, ValidationError, Warning, WarningSeverity};
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// Warning code for a value or settlement date on a Saturday or Sunday
+pub const WEEKEND_SETTLEMENT_WARNING: &str = "WEEKEND_SETTLEMENT";
+
+/// Warning code for a value or settlement date on a currency holiday
+pub const HOLIDAY_SETTLEMENT_WARNING: &str = "HOLIDAY_SETTLEMENT";
+
+/// Warning code for a same-day wire submitted after the currency's cut-off
+pub const CUT_OFF_MISSED_WARNING: &str = "CUT_OFF_MISSED";
+
+/// Limits on how far value dates may sit from the submission date
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SettlementPolicy {
+    /// Days a value date may precede the submission date
+    pub max_backdated_days: i64,
+    /// Days a value date may follow the submission date
+    pub max_forward_days: i64,
+}
+
+impl Default for SettlementPolicy {
+    fn default() -> Self {
+        Self {
+            max_backdated_days: 5,
+            max_forward_days: 365,
+        }
+    }
+}
+
+/// Per-currency holidays and same-day wire cut-offs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SettlementCalendar {
+    policy: SettlementPolicy,
+    holidays: HashMap<String, BTreeSet<NaiveDate>>,
+    cut_offs: HashMap<String, NaiveTime>,
+}
+
+impl SettlementCalendar {
+    /// Empty calendar with the default policy
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Empty calendar with a custom policy
+    pub fn with_policy(policy: SettlementPolicy) -> Self {
+        Self {
+            policy,
+            ..Default::default()
+        }
+    }
+
+    /// The policy in use
+    pub fn policy(&self) -> &SettlementPolicy {
+        &self.policy
+    }
+
+    /// Mark a date closed for settlement in a currency
+    pub fn add_holiday(&mut self, currency: &str, date: NaiveDate) {
+        self.holidays
+            .entry(currency.to_uppercase())
+            .or_default()
+            .insert(date);
+    }
+
+    /// Last UTC time a same-day wire in this currency is accepted
+    pub fn set_cut_off(&mut self, currency: &str, cut_off: NaiveTime) {
+        self.cut_offs.insert(currency.to_uppercase(), cut_off);
+    }
+
+    /// Same-day wire cut-off for a currency, if configured
+    pub fn cut_off(&self, currency: &str) -> Option<NaiveTime> {
+        self.cut_offs.get(&currency.to_uppercase()).copied()
+    }
+
+    /// Whether the date is a holiday for the currency
+    pub fn is_holiday(&self, currency: &str, date: NaiveDate) -> bool {
+        self.holidays
+            .get(&currency.to_uppercase())
+            .is_some_and(|days| days.contains(&date))
+    }
+
+    /// Whether the currency settles on this date
+    pub fn is_business_day(&self, currency: &str, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.is_holiday(currency, date)
+    }
+
+    /// First business day strictly after `date`
+    pub fn next_business_day(&self, currency: &str, date: NaiveDate) -> NaiveDate {
+        let mut next = date + Duration::days(1);
+        while !self.is_business_day(currency, next) {
+            next += Duration::days(1);
+        }
+        next
+    }
+
+    /// Check a transaction's dates, returning warnings when they are usable
+    pub fn check(&self, transaction: &Transaction) -> Result<Vec<Warning>, ValidationError> {
+        let submitted = transaction.timestamp.date_naive();
+        let currency = &transaction.currency;
+        let mut warnings = Vec::new();
+
+        if let Some(value_date) = transaction.value_date {
+            let offset = (value_date - submitted).num_days();
+            if -offset > self.policy.max_backdated_days {
+                return Err(ValidationError::BusinessRuleViolation(format!(
+                    "Value date {} is {} days before submission (limit {})",
+                    value_date, -offset, self.policy.max_backdated_days
+                )));
+            }
+            if offset > self.policy.max_forward_days {
+                return Err(ValidationError::BusinessRuleViolation(format!(
+                    "Value date {} is {} days after submission (limit {})",
+                    value_date, offset, self.policy.max_forward_days
+                )));
+            }
+            if let Some(settlement_date) = transaction.settlement_date {
+                if settlement_date < value_date {
+                    return Err(ValidationError::BusinessRuleViolation(format!(
+                        "Settlement date {} precedes value date {}",
+                        settlement_date, value_date
+                    )));
+                }
+            }
+            if transaction.transaction_type == TransactionType::WireTransfer
+                && value_date == submitted
+            {
+                if let Some(cut_off) = self.cut_off(currency) {
+                    if transaction.timestamp.time() > cut_off {
+                        warnings.push(Warning::new(
+                            CUT_OFF_MISSED_WARNING,
+                            WarningSeverity::Medium,
+                            format!(
+                                "Submitted after the {} cut-off of {} UTC; next value date {}",
+                                currency,
+                                cut_off.format("%H:%M"),
+                                self.next_business_day(currency, submitted)
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+
+        let dates = [
+            ("Value", transaction.value_date),
+            ("Settlement", transaction.settlement_date),
+        ];
+        for (label, date) in dates {
+            let Some(date) = date else { continue };
+            if matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+                warnings.push(Warning::new(
+                    WEEKEND_SETTLEMENT_WARNING,
+                    WarningSeverity::Low,
+                    format!("{} date {} falls on a {}", label, date, date.weekday()),
+                ));
+            } else if self.is_holiday(currency, date) {
+                warnings.push(Warning::new(
+                    HOLIDAY_SETTLEMENT_WARNING,
+                    WarningSeverity::Low,
+                    format!("{} date {} is a {} holiday", label, date, currency),
+                ));
+            }
+        }
+        Ok(warnings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::TransactionValidator;
+
+    #[test]
+    fn test_date_rules() {
+        let mut calendar = SettlementCalendar::new();
+        // A Friday public holiday followed by a weekend
+        let holiday = NaiveDate::from_ymd_opt(2026, 12, 25).unwrap();
+        calendar.add_holiday("usd", holiday);
+        assert!(!calendar.is_business_day("USD", holiday));
+        assert_eq!(
+            calendar.next_business_day("USD", NaiveDate::from_ymd_opt(2026, 12, 24).unwrap()),
+            NaiveDate::from_ymd_opt(2026, 12, 28).unwrap()
+        );
+
+        // Submitted on the Tuesday before
+        let mut tx = create_test_transaction("TXN-SD-1", 100.0);
+        tx.timestamp = holiday.and_hms_opt(12, 0, 0).unwrap().and_utc() - Duration::days(3);
+        let submitted = tx.timestamp.date_naive();
+        assert!(calendar.check(&tx).unwrap().is_empty());
+
+        tx.value_date = Some(submitted - Duration::days(6));
+        assert!(matches!(
+            calendar.check(&tx),
+            Err(ValidationError::BusinessRuleViolation(_))
+        ));
+        tx.value_date = Some(submitted - Duration::days(5));
+        assert!(calendar.check(&tx).unwrap().is_empty());
+        tx.value_date = Some(submitted);
+        tx.settlement_date = Some(submitted - Duration::days(1));
+        assert!(calendar.check(&tx).is_err());
+
+        tx.settlement_date = Some(holiday);
+        let warnings = calendar.check(&tx).unwrap();
+        assert_eq!(warnings[0].code, HOLIDAY_SETTLEMENT_WARNING);
+        tx.settlement_date = Some(holiday + Duration::days(1));
+        let warnings = calendar.check(&tx).unwrap();
+        assert_eq!(warnings[0].code, WEEKEND_SETTLEMENT_WARNING);
+        assert!(warnings[0].message.contains("Sat"));
+    }
+
+    #[test]
+    fn test_wire_after_cut_off_warns() {
+        let mut calendar = SettlementCalendar::new();
+        calendar.set_cut_off("USD", NaiveTime::from_hms_opt(11, 0, 0).unwrap());
+        let mut validator = TransactionValidator::builder()
+            .with_settlement_calendar(calendar)
+            .build();
+
+        let mut wire = create_test_transaction("TXN-SD-2", 500.0);
+        wire.transaction_type = TransactionType::WireTransfer;
+        wire.value_date = Some(wire.timestamp.date_naive());
+        let result = validator.validate(&wire);
+        assert!(result.is_valid);
+        assert!(result
+            .warning_codes()
+            .contains(&CUT_OFF_MISSED_WARNING.to_string()));
+
+        // Forward-dated wires are not subject to today's cut-off
+        let mut forward = create_test_transaction("TXN-SD-3", 500.0);
+        forward.transaction_type = TransactionType::WireTransfer;
+        forward.value_date = validator
+            .settlement_calendar()
+            .map(|c| c.next_business_day("USD", forward.timestamp.date_naive()));
+        let result = validator.validate(&forward);
+        assert!(!result
+            .warning_codes()
+            .contains(&CUT_OFF_MISSED_WARNING.to_string()));
+
+        let mut backdated = create_test_transaction("TXN-SD-4", 500.0);
+        backdated.value_date = Some(backdated.timestamp.date_naive() - Duration::days(30));
+        let result = validator.validate(&backdated);
+        assert!(!result.is_valid);
+    }
+}
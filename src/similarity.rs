@@ -0,0 +1,199 @@
+//! Name similarity for fuzzy sanctions matching
+//!
+//! [`SimilarityAlgorithm::Levenshtein`] scores the share of characters that
+//! survive the minimum number of single-character edits, so one typo in a
+//! long name costs little. [`SimilarityAlgorithm::JaroWinkler`] rewards
+//! transposed and shared characters and gives extra weight to a common
+//! prefix; it is the more forgiving of the two on short names. Both also
+//! score the names with their words sorted and keep the better score, so
+//! `SMITH JOHN` matches `JOHN SMITH`. [`SimilarityAlgorithm::Overlap`] is
+//! the original character and word overlap heuristic, kept for callers that
+//! calibrated thresholds against it.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Algorithm scoring fuzzy name matches, from 0.0 (unrelated) to 1.0 (equal)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SimilarityAlgorithm {
+    /// Character and word overlap
+    Overlap,
+    /// Normalized edit distance, with token sorting
+    #[default]
+    Levenshtein,
+    /// Jaro-Winkler, with token sorting
+    JaroWinkler,
+}
+
+impl SimilarityAlgorithm {
+    /// Similarity of two names, compared as given
+    pub fn score(&self, a: &str, b: &str) -> f32 {
+        if a.is_empty() || b.is_empty() {
+            return 0.0;
+        }
+        match self {
+            SimilarityAlgorithm::Overlap => overlap(a, b),
+            SimilarityAlgorithm::Levenshtein => best_of_token_orders(a, b, normalized_levenshtein),
+            SimilarityAlgorithm::JaroWinkler => best_of_token_orders(a, b, jaro_winkler),
+        }
+    }
+}
+
+fn best_of_token_orders(a: &str, b: &str, score: fn(&str, &str) -> f32) -> f32 {
+    let direct = score(a, b);
+    if direct >= 1.0 {
+        return direct;
+    }
+    direct.max(score(&token_sort(a), &token_sort(b)))
+}
+
+/// Words in alphabetical order, single-spaced
+fn token_sort(name: &str) -> String {
+    let mut words: Vec<&str> = name.split_whitespace().collect();
+    words.sort_unstable();
+    words.join(" ")
+}
+
+/// Minimum insertions, deletions, and substitutions turning `a` into `b`
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+fn normalized_levenshtein(a: &str, b: &str) -> f32 {
+    let longest = a.chars().count().max(b.chars().count());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein(a, b) as f32 / longest as f32
+}
+
+/// Jaro similarity: matching characters within a window, and their transpositions
+pub fn jaro(a: &str, b: &str) -> f32 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.is_empty() || b.is_empty() {
+        return if a.is_empty() && b.is_empty() {
+            1.0
+        } else {
+            0.0
+        };
+    }
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut a_matched = vec![false; a.len()];
+    let mut b_matched = vec![false; b.len()];
+    let mut matches = 0usize;
+    for (i, ca) in a.iter().enumerate() {
+        let start = i.saturating_sub(window);
+        let end = (i + window + 1).min(b.len());
+        for j in start..end {
+            if !b_matched[j] && b[j] == *ca {
+                a_matched[i] = true;
+                b_matched[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+    if matches == 0 {
+        return 0.0;
+    }
+
+    let a_order = a
+        .iter()
+        .zip(&a_matched)
+        .filter(|(_, m)| **m)
+        .map(|(c, _)| c);
+    let b_order = b
+        .iter()
+        .zip(&b_matched)
+        .filter(|(_, m)| **m)
+        .map(|(c, _)| c);
+    let transpositions = a_order.zip(b_order).filter(|(x, y)| x != y).count() / 2;
+
+    let m = matches as f32;
+    (m / a.len() as f32 + m / b.len() as f32 + (m - transpositions as f32) / m) / 3.0
+}
+
+/// Jaro similarity boosted by a shared prefix of up to four characters
+pub fn jaro_winkler(a: &str, b: &str) -> f32 {
+    let jaro = jaro(a, b);
+    // Winkler's boost only applies to names that are already similar
+    if jaro < 0.7 {
+        return jaro;
+    }
+    let prefix = a
+        .chars()
+        .zip(b.chars())
+        .take(4)
+        .take_while(|(x, y)| x == y)
+        .count();
+    jaro + prefix as f32 * 0.1 * (1.0 - jaro)
+}
+
+fn overlap(a: &str, b: &str) -> f32 {
+    let max_len = a.len().max(b.len());
+    let common_chars: usize = a.chars().filter(|c| b.contains(*c)).count();
+    let char_similarity = common_chars as f32 / max_len as f32;
+
+    let words_a: HashSet<&str> = a.split_whitespace().collect();
+    let words_b: HashSet<&str> = b.split_whitespace().collect();
+    let common_words = words_a.intersection(&words_b).count();
+    let total_words = words_a.union(&words_b).count();
+    let word_similarity = if total_words > 0 {
+        common_words as f32 / total_words as f32
+    } else {
+        0.0
+    };
+
+    (char_similarity * 0.4) + (word_similarity * 0.6)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(actual: f32, expected: f32) -> bool {
+        (actual - expected).abs() < 0.001
+    }
+
+    #[test]
+    fn test_reference_scores() {
+        assert_eq!(levenshtein("KITTEN", "SITTING"), 3);
+        assert_eq!(levenshtein("", "ABC"), 3);
+        assert_eq!(levenshtein("ÅSA", "ASA"), 1);
+        assert!(close(jaro("MARTHA", "MARHTA"), 0.944));
+        assert!(close(jaro_winkler("MARTHA", "MARHTA"), 0.961));
+        assert!(close(jaro_winkler("DWAYNE", "DUANE"), 0.84));
+        assert!(close(jaro_winkler("DIXON", "DICKSONX"), 0.813));
+    }
+
+    #[test]
+    fn test_token_sort_handles_reordered_names() {
+        for algorithm in [
+            SimilarityAlgorithm::Levenshtein,
+            SimilarityAlgorithm::JaroWinkler,
+        ] {
+            assert_eq!(algorithm.score("SMITH JOHN", "JOHN SMITH"), 1.0);
+            assert!(algorithm.score("PETROV IVAN", "IVAN PETRV") > 0.85);
+            assert!(algorithm.score("ACME TRADING", "GLOBAL LOGISTICS") < 0.6);
+        }
+
+        // One dropped letter is a miss for the overlap heuristic
+        let (typo, listed) = ("SANCTIONED ENTTY ONE", "SANCTIONED ENTITY ONE");
+        assert!(SimilarityAlgorithm::Overlap.score(typo, listed) < 0.85);
+        assert!(SimilarityAlgorithm::Levenshtein.score(typo, listed) > 0.9);
+        assert!(SimilarityAlgorithm::JaroWinkler.score(typo, listed) > 0.9);
+    }
+}
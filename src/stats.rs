@@ -0,0 +1,220 @@
+//! Validator statistics for dashboards and monitoring
+
+use crate::{hash_identifier, DatasetAge, Decision, ValidationResult};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Number of users reported in [`ValidatorStats::top_users`]
+pub const TOP_USERS_LIMIT: usize = 10;
+
+/// Validation counts per decision
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecisionCounts {
+    pub approve: usize,
+    pub review: usize,
+    #[serde(default)]
+    pub step_up: usize,
+    pub decline: usize,
+}
+
+impl DecisionCounts {
+    pub(crate) fn record(&mut self, decision: Decision) {
+        match decision {
+            Decision::Approve => self.approve += 1,
+            Decision::Review => self.review += 1,
+            Decision::StepUp => self.step_up += 1,
+            Decision::Decline => self.decline += 1,
+        }
+    }
+}
+
+/// Fraud score distribution (same bands as `ValidationResult::risk_level`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreDistribution {
+    pub low: usize,      // 0-25
+    pub medium: usize,   // 26-50
+    pub high: usize,     // 51-75
+    pub critical: usize, // 76-100
+}
+
+impl ScoreDistribution {
+    pub(crate) fn record(&mut self, score: u8) {
+        match score {
+            0..=25 => self.low += 1,
+            26..=50 => self.medium += 1,
+            51..=75 => self.high += 1,
+            _ => self.critical += 1,
+        }
+    }
+}
+
+/// Activity summary for one user
+///
+/// Stats feed dashboards, so the user is identified by the same hash as in
+/// decision logs and audit records, never by the raw ID.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserActivity {
+    pub user_id_hash: String,
+    pub transaction_count: usize,
+    pub total_amount: f64,
+}
+
+/// Validator statistics snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidatorStats {
+    /// Number of `validate()` calls
+    pub total_validated: usize,
+    /// Transaction IDs held for duplicate detection
+    pub total_processed: usize,
+    /// Entries held for velocity checks
+    pub total_transactions_in_history: usize,
+    pub decisions: DecisionCounts,
+    /// Error counts keyed by reason code
+    pub errors_by_type: HashMap<String, usize>,
+    /// Warning counts keyed by warning code
+    pub warnings_by_code: HashMap<String, usize>,
+    pub score_distribution: ScoreDistribution,
+    /// Rough estimate of memory held by validator state
+    pub estimated_memory_bytes: usize,
+    pub started_at: DateTime<Utc>,
+    pub uptime_seconds: i64,
+    /// Most active users in the current history window
+    pub top_users: Vec<UserActivity>,
+    pub decision_log_failures: usize,
+    /// Events the write-ahead log failed to persist; none of them was
+    /// committed or approved
+    #[serde(default)]
+    pub event_log_failures: usize,
+    /// Audit batches the sink failed to write
+    #[serde(default)]
+    pub audit_failures: usize,
+    /// Alerts observers dropped, e.g. on a full webhook queue or after
+    /// exhausting retries
+    #[serde(default)]
+    pub alerts_dropped: usize,
+    /// Age of each loaded reference dataset
+    #[serde(default)]
+    pub reference_data: Vec<DatasetAge>,
+    /// Datasets older than `max_reference_data_age_days`
+    #[serde(default)]
+    pub stale_reference_data: usize,
+}
+
+/// Running counters maintained by the validator
+#[derive(Debug, Clone)]
+pub(crate) struct StatsCollector {
+    pub(crate) started_at: DateTime<Utc>,
+    pub(crate) total_validated: usize,
+    pub(crate) decisions: DecisionCounts,
+    pub(crate) errors_by_type: HashMap<String, usize>,
+    pub(crate) warnings_by_code: HashMap<String, usize>,
+    pub(crate) score_distribution: ScoreDistribution,
+    pub(crate) decision_log_failures: usize,
+    pub(crate) event_log_failures: usize,
+    pub(crate) audit_failures: usize,
+}
+
+impl StatsCollector {
+    pub(crate) fn new() -> Self {
+        Self {
+            started_at: Utc::now(),
+            total_validated: 0,
+            decisions: DecisionCounts::default(),
+            errors_by_type: HashMap::new(),
+            warnings_by_code: HashMap::new(),
+            score_distribution: ScoreDistribution::default(),
+            decision_log_failures: 0,
+            event_log_failures: 0,
+            audit_failures: 0,
+        }
+    }
+
+    pub(crate) fn record(&mut self, result: &ValidationResult) {
+        self.total_validated += 1;
+        self.decisions.record(result.decision);
+        self.score_distribution.record(result.fraud_score);
+        for error in &result.errors {
+            *self
+                .errors_by_type
+                .entry(error.reason_code().to_string())
+                .or_insert(0) += 1;
+        }
+        for warning in &result.warnings {
+            *self
+                .warnings_by_code
+                .entry(warning.code.clone())
+                .or_insert(0) += 1;
+        }
+    }
+}
+
+/// Rank users by transaction count (then amount), keeping the top `limit`
+pub(crate) fn top_users<'a>(
+    entries: impl Iterator<Item = (&'a str, f64)>,
+    limit: usize,
+) -> Vec<UserActivity> {
+    let mut by_user: HashMap<&str, UserActivity> = HashMap::new();
+    for (user_id, amount) in entries {
+        let activity = by_user.entry(user_id).or_insert_with(|| UserActivity {
+            user_id_hash: hash_identifier(user_id),
+            transaction_count: 0,
+            total_amount: 0.0,
+        });
+        activity.transaction_count += 1;
+        activity.total_amount += amount;
+    }
+
+    let mut users: Vec<UserActivity> = by_user.into_values().collect();
+    users.sort_by(|a, b| {
+        b.transaction_count
+            .cmp(&a.transaction_count)
+            .then_with(|| {
+                b.total_amount
+                    .partial_cmp(&a.total_amount)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .then_with(|| a.user_id_hash.cmp(&b.user_id_hash))
+    });
+    users.truncate(limit);
+    users
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_distribution_bands() {
+        let mut distribution = ScoreDistribution::default();
+        for score in [0, 25, 26, 50, 51, 75, 76, 100] {
+            distribution.record(score);
+        }
+        assert_eq!(
+            distribution,
+            ScoreDistribution {
+                low: 2,
+                medium: 2,
+                high: 2,
+                critical: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_top_users_ordering() {
+        let entries = vec![
+            ("USER-A", 100.0),
+            ("USER-B", 50.0),
+            ("USER-B", 50.0),
+            ("USER-C", 500.0),
+            ("USER-A", 10.0),
+        ];
+        let users = top_users(entries.into_iter(), 2);
+
+        assert_eq!(users.len(), 2);
+        assert_eq!(users[0].user_id_hash, hash_identifier("USER-A")); // 2 txns, 110.0
+        assert_eq!(users[1].user_id_hash, hash_identifier("USER-B")); // 2 txns, 100.0
+        assert_eq!(users[0].transaction_count, 2);
+    }
+}
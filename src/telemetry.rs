@@ -0,0 +1,128 @@
+//! Metrics as tracing fields
+//!
+//! With the `metrics` feature the validator emits metric events alongside
+//! its `tracing` spans, using the field-prefix convention of
+//! `tracing-opentelemetry`'s `MetricsLayer` (`monotonic_counter.*`,
+//! `histogram.*`). This is not an OTLP exporter: the crate depends on
+//! neither `opentelemetry` nor `opentelemetry-otlp`, so exporting is left
+//! to the application. One that installs `tracing-opentelemetry` with its
+//! own OTLP exporter gets:
+//!
+//! - a `validate` span per validation with `decision`, `reason_codes`, and
+//!   `correlation_id` attributes, and a `check` child span per check;
+//! - `validations` (counter) and `validation.duration_us` (histogram),
+//!   labelled with the decision;
+//! - `check.duration_us` (histogram), labelled with the check name.
+//!
+//! The correlation ID comes from the [`CORRELATION_ID_METADATA_KEY`]
+//! metadata entry and is recorded as a span attribute, so traces can be
+//! joined to the upstream request that carried it. It also rides on every
+//! [`AlertEvent`](crate::AlertEvent), and webhook deliveries pass it on in a
+//! W3C `baggage` header, independent of this feature.
+//!
+//! [`CORRELATION_ID_METADATA_KEY`]: crate::metadata::CORRELATION_ID_METADATA_KEY
+
+use crate::{Check, ValidationResult};
+use std::time::Duration;
+use tracing::Level;
+
+/// Target of metric events, for filtering in the subscriber
+pub const METRICS_TARGET: &str = "transaction_validator::metrics";
+
+/// Count one validation and record its latency
+pub(crate) fn record_validation(result: &ValidationResult, elapsed: Duration) {
+    tracing::event!(
+        target: METRICS_TARGET,
+        Level::INFO,
+        monotonic_counter.validations = 1_u64,
+        histogram.validation.duration_us = elapsed.as_micros() as u64,
+        decision = %result.decision,
+    );
+}
+
+/// Record the latency of one check
+pub(crate) fn record_check(check: Check, elapsed: Duration) {
+    tracing::event!(
+        target: METRICS_TARGET,
+        Level::DEBUG,
+        histogram.check.duration_us = elapsed.as_micros() as u64,
+        check = check.name(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{TransactionMetadata, TransactionValidator};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    /// Subscriber keeping metric field names and the values spans open with
+    #[derive(Clone, Default)]
+    struct Capture {
+        metric_fields: Arc<Mutex<Vec<(String, String)>>>,
+        span_values: Arc<Mutex<Vec<(String, String)>>>,
+        next_id: Arc<AtomicU64>,
+    }
+
+    struct Collect<'a>(&'a mut Vec<(String, String)>);
+
+    impl Visit for Collect<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .push((field.name().to_string(), format!("{:?}", value)));
+        }
+    }
+
+    impl Subscriber for Capture {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            span.record(&mut Collect(&mut self.span_values.lock().unwrap()));
+            Id::from_u64(self.next_id.fetch_add(1, Ordering::SeqCst) + 1)
+        }
+
+        fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            if event.metadata().target() == METRICS_TARGET {
+                event.record(&mut Collect(&mut self.metric_fields.lock().unwrap()));
+            }
+        }
+
+        fn enter(&self, _span: &Id) {}
+
+        fn exit(&self, _span: &Id) {}
+    }
+
+    #[test]
+    fn test_validation_emits_metrics_and_check_spans() {
+        let capture = Capture::default();
+        let mut transaction = create_test_transaction("TXN-OTEL-1", -5.0);
+        transaction.metadata = Some(TransactionMetadata::from([(
+            "correlation_id".to_string(),
+            "req-42".to_string(),
+        )]));
+
+        tracing::subscriber::with_default(capture.clone(), || {
+            TransactionValidator::new().validate(&transaction);
+        });
+
+        let metrics = capture.metric_fields.lock().unwrap();
+        let names: Vec<&str> = metrics.iter().map(|(name, _)| name.as_str()).collect();
+        assert!(names.contains(&"monotonic_counter.validations"));
+        assert!(names.contains(&"histogram.validation.duration_us"));
+        assert!(names.contains(&"histogram.check.duration_us"));
+        assert!(metrics.contains(&("decision".to_string(), "decline".to_string())));
+        let spans = capture.span_values.lock().unwrap();
+        assert!(spans.contains(&("check".to_string(), "\"amount\"".to_string())));
+    }
+}
@@ -0,0 +1,307 @@
+//! Multi-tenant validation with isolated state
+//!
+//! Each tenant gets its own `TransactionValidator`, so dedup sets, velocity
+//! history, screening lists, configuration, and stats never leak between
+//! institutions served by the same process. Tenant configs may be given
+//! as a [`ConfigOverride`] layered on shared global defaults.
+//!
+//! Registering a validator stamps it with the tenant ID, so results, audit
+//! records, decision logs, and events carry the tenant however the
+//! validator is called.
+
+use crate::{
+    ConfigOverride, Transaction, TransactionValidator, ValidationResult, ValidatorConfig,
+    ValidatorStats,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Tenant registry errors
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TenantError {
+    #[error("Unknown tenant: {0}")]
+    UnknownTenant(String),
+
+    #[error("Tenant already registered: {0}")]
+    DuplicateTenant(String),
+}
+
+/// Validator serving several tenants with fully isolated state
+#[derive(Default)]
+pub struct MultiTenantValidator {
+    defaults: ValidatorConfig,
+    tenants: HashMap<String, TransactionValidator>,
+}
+
+impl MultiTenantValidator {
+    /// Create an empty multi-tenant validator
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty multi-tenant validator with global default config
+    pub fn with_defaults(defaults: ValidatorConfig) -> Self {
+        Self {
+            defaults,
+            tenants: HashMap::new(),
+        }
+    }
+
+    /// Global defaults that tenant overrides are layered on
+    pub fn defaults(&self) -> &ValidatorConfig {
+        &self.defaults
+    }
+
+    /// Register a tenant whose config overrides the global defaults
+    pub fn register_tenant_override(
+        &mut self,
+        tenant_id: &str,
+        layer: &ConfigOverride,
+    ) -> Result<&mut TransactionValidator, TenantError> {
+        let config = layer.apply(&self.defaults);
+        self.register_tenant(tenant_id, config)
+    }
+
+    /// Register a tenant with its own configuration
+    pub fn register_tenant(
+        &mut self,
+        tenant_id: &str,
+        config: ValidatorConfig,
+    ) -> Result<&mut TransactionValidator, TenantError> {
+        self.register_tenant_validator(tenant_id, TransactionValidator::with_config(config))
+    }
+
+    /// Register a tenant with a fully assembled validator
+    pub fn register_tenant_validator(
+        &mut self,
+        tenant_id: &str,
+        mut validator: TransactionValidator,
+    ) -> Result<&mut TransactionValidator, TenantError> {
+        if self.tenants.contains_key(tenant_id) {
+            return Err(TenantError::DuplicateTenant(tenant_id.to_string()));
+        }
+        validator.set_tenant_id(tenant_id);
+        Ok(self
+            .tenants
+            .entry(tenant_id.to_string())
+            .or_insert(validator))
+    }
+
+    /// Remove a tenant and all of its state
+    pub fn remove_tenant(&mut self, tenant_id: &str) -> Option<TransactionValidator> {
+        self.tenants.remove(tenant_id)
+    }
+
+    /// Access a tenant's validator (e.g. to attach screeners or hooks)
+    pub fn tenant_mut(&mut self, tenant_id: &str) -> Option<&mut TransactionValidator> {
+        self.tenants.get_mut(tenant_id)
+    }
+
+    /// Registered tenant IDs
+    pub fn tenant_ids(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self.tenants.keys().map(|id| id.as_str()).collect();
+        ids.sort();
+        ids
+    }
+
+    /// Validate a transaction against one tenant's state and config
+    pub fn validate(
+        &mut self,
+        tenant_id: &str,
+        transaction: &Transaction,
+    ) -> Result<ValidationResult, TenantError> {
+        let validator = self
+            .tenants
+            .get_mut(tenant_id)
+            .ok_or_else(|| TenantError::UnknownTenant(tenant_id.to_string()))?;
+        Ok(validator.validate(transaction))
+    }
+
+    /// Statistics for one tenant
+    pub fn tenant_stats(&self, tenant_id: &str) -> Result<ValidatorStats, TenantError> {
+        self.tenants
+            .get(tenant_id)
+            .map(|v| v.get_stats())
+            .ok_or_else(|| TenantError::UnknownTenant(tenant_id.to_string()))
+    }
+
+    /// Statistics for every tenant
+    pub fn all_stats(&self) -> HashMap<String, ValidatorStats> {
+        self.tenants
+            .iter()
+            .map(|(id, v)| (id.clone(), v.get_stats()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+    use crate::{
+        AuditRecord, AuditSink, SanctionsList, SanctionsScreener, TransactionMetadata,
+        ValidationError,
+    };
+
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    fn create_test_transaction(amount: f64) -> Transaction {
+        test_fixtures::create_test_transaction("TXN-TENANT-001", amount)
+    }
+
+    #[test]
+    fn test_dedup_isolated_per_tenant() {
+        let mut validator = MultiTenantValidator::new();
+        validator
+            .register_tenant("bank-a", ValidatorConfig::default())
+            .unwrap();
+        validator
+            .register_tenant("bank-b", ValidatorConfig::default())
+            .unwrap();
+
+        let transaction = create_test_transaction(100.0);
+        let a = validator.validate("bank-a", &transaction).unwrap();
+        let b = validator.validate("bank-b", &transaction).unwrap();
+        assert!(a.is_valid);
+        assert!(b.is_valid, "same ID at another tenant is not a duplicate");
+        assert_eq!(b.tenant_id.as_deref(), Some("bank-b"));
+
+        let again = validator.validate("bank-a", &transaction).unwrap();
+        assert!(again
+            .errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::DuplicateTransaction(_))));
+
+        assert_eq!(validator.tenant_stats("bank-a").unwrap().total_validated, 2);
+        assert_eq!(validator.tenant_stats("bank-b").unwrap().total_validated, 1);
+    }
+
+    #[test]
+    fn test_per_tenant_config_and_watchlist() {
+        let mut validator = MultiTenantValidator::new();
+        validator
+            .register_tenant(
+                "strict",
+                ValidatorConfig {
+                    max_transaction_amount: 500.0,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let tenant = validator
+            .register_tenant("watchful", ValidatorConfig::default())
+            .unwrap();
+        let mut screener = SanctionsScreener::new();
+        let internal = SanctionsList::Custom("INTERNAL".to_string());
+        screener.add_entity("ACME SHELL CO", vec![], internal.clone());
+        screener.enable_list(internal);
+        tenant.set_sanctions_screener(screener);
+
+        let transaction = create_test_transaction(1000.0);
+        assert!(!validator.validate("strict", &transaction).unwrap().is_valid);
+        assert!(
+            validator
+                .validate("watchful", &transaction)
+                .unwrap()
+                .is_valid
+        );
+
+        let mut flagged = create_test_transaction(100.0);
+        flagged.transaction_id = "TXN-TENANT-002".to_string();
+        flagged.metadata = Some(TransactionMetadata::from([(
+            "beneficiary_name".to_string(),
+            "Acme Shell Co".to_string(),
+        )]));
+        assert!(validator.validate("strict", &flagged).unwrap().is_valid);
+        assert!(!validator.validate("watchful", &flagged).unwrap().is_valid);
+    }
+
+    #[test]
+    fn test_unknown_and_duplicate_tenant() {
+        let mut validator = MultiTenantValidator::new();
+        validator
+            .register_tenant("bank-a", ValidatorConfig::default())
+            .unwrap();
+
+        assert!(matches!(
+            validator.register_tenant("bank-a", ValidatorConfig::default()),
+            Err(TenantError::DuplicateTenant(_))
+        ));
+        assert_eq!(
+            validator
+                .validate("bank-z", &create_test_transaction(1.0))
+                .unwrap_err(),
+            TenantError::UnknownTenant("bank-z".to_string())
+        );
+        assert_eq!(validator.tenant_ids(), vec!["bank-a"]);
+        assert_eq!(validator.all_stats().len(), 1);
+    }
+
+    #[test]
+    fn test_tenant_override_layers_on_defaults() {
+        let mut validator = MultiTenantValidator::with_defaults(ValidatorConfig {
+            max_transaction_amount: 5_000.0,
+            fraud_threshold: 60,
+            ..Default::default()
+        });
+        let tenant = validator
+            .register_tenant_override(
+                "bank-a",
+                &ConfigOverride {
+                    max_transaction_amount: Some(20_000.0),
+                    trust_segment_metadata: Some(true),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        tenant.set_segment_override(
+            crate::CustomerSegment::Corporate,
+            ConfigOverride {
+                fraud_threshold: Some(90),
+                ..Default::default()
+            },
+        );
+
+        let mut transaction = create_test_transaction(10_000.0);
+        transaction.metadata = Some(TransactionMetadata::from([(
+            "customer_segment".to_string(),
+            "corporate".to_string(),
+        )]));
+        let result = validator.validate("bank-a", &transaction).unwrap();
+        assert!(result.is_valid);
+        assert_eq!(result.effective_config.max_transaction_amount, 20_000.0);
+        assert_eq!(result.effective_config.fraud_threshold, 90);
+    }
+
+    #[test]
+    fn test_sinks_see_the_tenant() {
+        #[derive(Clone, Default)]
+        struct MemorySink(Arc<Mutex<Vec<AuditRecord>>>);
+
+        impl AuditSink for MemorySink {
+            fn write_batch(&mut self, records: &[AuditRecord]) -> io::Result<()> {
+                self.0.lock().unwrap().extend_from_slice(records);
+                Ok(())
+            }
+        }
+
+        let mut validator = MultiTenantValidator::new();
+        let sink = MemorySink::default();
+        validator
+            .register_tenant("bank-a", ValidatorConfig::default())
+            .unwrap()
+            .set_audit_sink(Box::new(sink.clone()), 1);
+
+        // Calling the tenant's validator directly still tags the tenant
+        let tenant = validator.tenant_mut("bank-a").unwrap();
+        assert_eq!(tenant.tenant_id(), Some("bank-a"));
+        let result = tenant.validate(&create_test_transaction(100.0));
+        assert_eq!(result.tenant_id.as_deref(), Some("bank-a"));
+
+        let records = sink.0.lock().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].result.tenant_id.as_deref(), Some("bank-a"));
+    }
+}
@@ -0,0 +1,33 @@
+//! Transactions shared by the unit tests of every module
+
+use crate::{Transaction, TransactionType};
+use chrono::{DateTime, TimeZone, Utc};
+
+/// Instant the fixtures are timestamped at: noon UTC on a Friday
+///
+/// A fixed instant keeps tests off the wall clock, so none of them can
+/// cross a day or week boundary mid-run. Pair it with a
+/// [`FixedClock`](crate::FixedClock) wherever "now" matters.
+pub(crate) fn test_now() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap()
+}
+
+/// Transfer between two well-formed accounts at [`test_now`]
+///
+/// Noon keeps time-of-day risk out of the score. Tests override whichever
+/// fields they exercise.
+pub(crate) fn create_test_transaction(id: &str, amount: f64) -> Transaction {
+    Transaction {
+        transaction_id: id.to_string(),
+        transaction_type: TransactionType::Transfer,
+        amount,
+        currency: "USD".to_string(),
+        from_account: Some("ACCT-1234-5678-9012".to_string()),
+        to_account: Some("ACCT-6789-0123-4567".to_string()),
+        timestamp: test_now(),
+        user_id: "USER-001".to_string(),
+        metadata: None,
+        value_date: None,
+        settlement_date: None,
+    }
+}
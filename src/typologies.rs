@@ -0,0 +1,536 @@
+//! AML typology rule packs
+//!
+//! A [`TypologyPack`] is a named, versioned bundle of rules implementing a
+//! documented money laundering typology. Each [`TypologyRule`] matches when
+//! all of its [`Indicator`]s hold. Packs are plain data: the built-in ones
+//! come from [`TypologyPack::builtin`], and others can be loaded from JSON.
+//! A deployment enables the packs it wants in a [`TypologyLibrary`] and
+//! attaches it to the validator. Matching rules add risk to the pattern
+//! score, and each match is recorded in the result as a [`TypologyHit`]
+//! naming the pack, its version, and the rule.
+//!
+//! Built-in packs:
+//!
+//! - `funnel_accounts`: many depositors into one account, quickly drained
+//!   (FinCEN FIN-2014-A005)
+//! - `trade_based_ml`: round cross-border trade payments (FinCEN
+//!   FIN-2014-A005, FATF trade-based money laundering guidance)
+//! - `human_trafficking`: late-night lodging and ride payments, escort
+//!   advertising (FinCEN FIN-2020-A008)
+//! - `elder_exploitation`: older customers sending large sums to new payees
+//!   or paying scam-typical purposes (FinCEN FIN-2022-A002)
+
+use crate::history::{HistoryKey, HistoryStore};
+use crate::{Transaction, TransactionType, Warning, WarningSeverity};
+use chrono::{Duration, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Warning code for a matched typology rule
+pub const TYPOLOGY_MATCH_WARNING: &str = "TYPOLOGY_MATCH";
+
+/// Metadata key for the customer's age in years
+pub const CUSTOMER_AGE_METADATA_KEY: &str = "customer_age";
+
+/// Names of the packs available from [`TypologyPack::builtin`]
+pub const BUILTIN_TYPOLOGY_PACKS: [&str; 4] = [
+    "funnel_accounts",
+    "trade_based_ml",
+    "human_trafficking",
+    "elder_exploitation",
+];
+
+/// One condition a rule requires
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Indicator {
+    /// Transaction type is one of these
+    TransactionTypes { types: Vec<TransactionType> },
+    /// Amount within the bounds (inclusive)
+    AmountRange { min: Option<f64>, max: Option<f64> },
+    /// Amount is a whole multiple of `multiple`
+    RoundAmount { multiple: f64 },
+    /// Metadata value equals one of these
+    MetadataIn { key: String, values: Vec<String> },
+    /// Metadata value contains one of these, ignoring case
+    MetadataKeywords { key: String, keywords: Vec<String> },
+    /// Origin and destination countries differ
+    CrossBorder,
+    /// Timestamp hour (UTC) in `start..end`, wrapping past midnight
+    HoursUtc { start: u32, end: u32 },
+    /// `customer_age` metadata at least this many years
+    CustomerAgeAtLeast { years: u32 },
+    /// The user has not paid the destination account before
+    NewCounterparty,
+    /// Distinct users paying the destination account within the window,
+    /// this transaction included
+    DistinctPayers { min: usize, window_hours: i64 },
+    /// The transaction moves out at least `min_ratio` of what the source
+    /// account received within the window
+    RapidOutflow { min_ratio: f64, window_hours: i64 },
+}
+
+impl Indicator {
+    fn matches(&self, transaction: &Transaction, history: &HistoryStore) -> bool {
+        let metadata = |key: &str| {
+            transaction
+                .metadata
+                .as_ref()
+                .and_then(|m| m.get(key))
+                .map(str::trim)
+        };
+        match self {
+            Indicator::TransactionTypes { types } => types.contains(&transaction.transaction_type),
+            Indicator::AmountRange { min, max } => {
+                min.is_none_or(|min| transaction.amount >= min)
+                    && max.is_none_or(|max| transaction.amount <= max)
+            }
+            Indicator::RoundAmount { multiple } => {
+                *multiple > 0.0 && (transaction.amount / multiple).fract().abs() < 1e-9
+            }
+            Indicator::MetadataIn { key, values } => {
+                metadata(key).is_some_and(|value| values.iter().any(|v| v == value))
+            }
+            Indicator::MetadataKeywords { key, keywords } => metadata(key).is_some_and(|value| {
+                let value = value.to_lowercase();
+                keywords.iter().any(|k| value.contains(&k.to_lowercase()))
+            }),
+            Indicator::CrossBorder => {
+                metadata("cross_border") == Some("true")
+                    || matches!(
+                        (metadata("country"), metadata("destination_country")),
+                        (Some(from), Some(to)) if !from.eq_ignore_ascii_case(to)
+                    )
+            }
+            Indicator::HoursUtc { start, end } => {
+                let hour = transaction.timestamp.hour();
+                if start <= end {
+                    (*start..*end).contains(&hour)
+                } else {
+                    hour >= *start || hour < *end
+                }
+            }
+            Indicator::CustomerAgeAtLeast { years } => metadata(CUSTOMER_AGE_METADATA_KEY)
+                .and_then(|age| age.parse::<u32>().ok())
+                .is_some_and(|age| age >= *years),
+            Indicator::NewCounterparty => {
+                let Some(to) = transaction.to_account.as_deref() else {
+                    return false;
+                };
+                !history
+                    .get(HistoryKey::User, &transaction.user_id)
+                    .iter()
+                    .any(|past| past.to_account.as_deref() == Some(to))
+            }
+            Indicator::DistinctPayers { min, window_hours } => {
+                let Some(to) = transaction.to_account.as_deref() else {
+                    return false;
+                };
+                let since = transaction.timestamp - Duration::hours(*window_hours);
+                let mut payers: HashSet<&str> = history
+                    .get(HistoryKey::Counterparty, to)
+                    .iter()
+                    .filter(|past| past.timestamp >= since)
+                    .map(|past| past.user_id.as_str())
+                    .collect();
+                payers.insert(&transaction.user_id);
+                payers.len() >= *min
+            }
+            Indicator::RapidOutflow {
+                min_ratio,
+                window_hours,
+            } => {
+                let Some(from) = transaction.from_account.as_deref() else {
+                    return false;
+                };
+                let since = transaction.timestamp - Duration::hours(*window_hours);
+                let received: f64 = history
+                    .get(HistoryKey::Counterparty, from)
+                    .iter()
+                    .filter(|past| past.timestamp >= since)
+                    .map(|past| past.amount)
+                    .sum();
+                received > 0.0 && transaction.amount >= received * min_ratio
+            }
+        }
+    }
+}
+
+/// A typology rule: every indicator must hold for it to match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypologyRule {
+    pub id: String,
+    pub description: String,
+    pub indicators: Vec<Indicator>,
+    /// Pattern risk added when the rule matches
+    pub risk: u8,
+    pub severity: WarningSeverity,
+}
+
+impl TypologyRule {
+    /// Whether every indicator holds; a rule without indicators never matches
+    pub fn matches(&self, transaction: &Transaction, history: &HistoryStore) -> bool {
+        !self.indicators.is_empty()
+            && self
+                .indicators
+                .iter()
+                .all(|indicator| indicator.matches(transaction, history))
+    }
+}
+
+/// Named, versioned bundle of typology rules
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypologyPack {
+    pub name: String,
+    pub version: String,
+    /// Advisory or guidance the pack implements
+    pub source: String,
+    pub rules: Vec<TypologyRule>,
+}
+
+fn rule(
+    id: &str,
+    description: &str,
+    risk: u8,
+    severity: WarningSeverity,
+    indicators: Vec<Indicator>,
+) -> TypologyRule {
+    TypologyRule {
+        id: id.to_string(),
+        description: description.to_string(),
+        indicators,
+        risk,
+        severity,
+    }
+}
+
+fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|v| v.to_string()).collect()
+}
+
+impl TypologyPack {
+    /// Load a pack from its JSON form
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// A built-in pack by name (see [`BUILTIN_TYPOLOGY_PACKS`])
+    pub fn builtin(name: &str) -> Option<Self> {
+        let (source, rules) = match name {
+            "funnel_accounts" => (
+                "FinCEN FIN-2014-A005",
+                vec![
+                    rule(
+                        "FA-01",
+                        "Deposits from many unrelated payers into one account",
+                        20,
+                        WarningSeverity::Medium,
+                        vec![
+                            Indicator::TransactionTypes {
+                                types: vec![
+                                    TransactionType::Deposit,
+                                    TransactionType::Transfer,
+                                    TransactionType::AchCredit,
+                                ],
+                            },
+                            Indicator::DistinctPayers {
+                                min: 5,
+                                window_hours: 72,
+                            },
+                        ],
+                    ),
+                    rule(
+                        "FA-02",
+                        "Account drained shortly after being funded",
+                        25,
+                        WarningSeverity::High,
+                        vec![
+                            Indicator::TransactionTypes {
+                                types: vec![
+                                    TransactionType::Withdrawal,
+                                    TransactionType::Transfer,
+                                    TransactionType::WireTransfer,
+                                ],
+                            },
+                            Indicator::RapidOutflow {
+                                min_ratio: 0.9,
+                                window_hours: 48,
+                            },
+                        ],
+                    ),
+                ],
+            ),
+            "trade_based_ml" => (
+                "FinCEN FIN-2014-A005; FATF TBML guidance",
+                vec![
+                    rule(
+                        "TB-01",
+                        "Round-amount cross-border wire for goods",
+                        20,
+                        WarningSeverity::Medium,
+                        vec![
+                            Indicator::TransactionTypes {
+                                types: vec![TransactionType::WireTransfer],
+                            },
+                            Indicator::CrossBorder,
+                            Indicator::RoundAmount { multiple: 1000.0 },
+                            Indicator::MetadataKeywords {
+                                key: "purpose".to_string(),
+                                keywords: strings(&["invoice", "goods", "shipment", "trade"]),
+                            },
+                        ],
+                    ),
+                    rule(
+                        "TB-02",
+                        "Large cross-border trade payment to a new counterparty",
+                        15,
+                        WarningSeverity::Medium,
+                        vec![
+                            Indicator::CrossBorder,
+                            Indicator::AmountRange {
+                                min: Some(10000.0),
+                                max: None,
+                            },
+                            Indicator::NewCounterparty,
+                            Indicator::MetadataKeywords {
+                                key: "purpose".to_string(),
+                                keywords: strings(&["invoice", "goods", "shipment", "trade"]),
+                            },
+                        ],
+                    ),
+                ],
+            ),
+            "human_trafficking" => (
+                "FinCEN FIN-2020-A008",
+                vec![
+                    rule(
+                        "HT-01",
+                        "Late-night lodging or ride card payments",
+                        15,
+                        WarningSeverity::Medium,
+                        vec![
+                            Indicator::TransactionTypes {
+                                types: vec![TransactionType::CardPurchase],
+                            },
+                            // Hotels and motels, taxis and rideshare
+                            Indicator::MetadataIn {
+                                key: "mcc".to_string(),
+                                values: strings(&["7011", "4121"]),
+                            },
+                            Indicator::HoursUtc { start: 22, end: 5 },
+                        ],
+                    ),
+                    rule(
+                        "HT-02",
+                        "Payments referencing escort or adult advertising",
+                        30,
+                        WarningSeverity::High,
+                        vec![Indicator::MetadataKeywords {
+                            key: "purpose".to_string(),
+                            keywords: strings(&["escort", "adult ad", "massage parlor"]),
+                        }],
+                    ),
+                ],
+            ),
+            "elder_exploitation" => (
+                "FinCEN FIN-2022-A002",
+                vec![
+                    rule(
+                        "EE-01",
+                        "Older customer sending a large sum to a new payee",
+                        25,
+                        WarningSeverity::High,
+                        vec![
+                            Indicator::CustomerAgeAtLeast { years: 65 },
+                            Indicator::AmountRange {
+                                min: Some(5000.0),
+                                max: None,
+                            },
+                            Indicator::NewCounterparty,
+                        ],
+                    ),
+                    rule(
+                        "EE-02",
+                        "Older customer paying a scam-typical purpose",
+                        20,
+                        WarningSeverity::High,
+                        vec![
+                            Indicator::CustomerAgeAtLeast { years: 65 },
+                            Indicator::MetadataKeywords {
+                                key: "purpose".to_string(),
+                                keywords: strings(&[
+                                    "gift card",
+                                    "lottery",
+                                    "prize",
+                                    "tech support",
+                                    "bail",
+                                ]),
+                            },
+                        ],
+                    ),
+                ],
+            ),
+            _ => return None,
+        };
+        Some(Self {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            source: source.to_string(),
+            rules,
+        })
+    }
+}
+
+/// Reference to the pack rule a transaction matched
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypologyHit {
+    pub pack: String,
+    pub version: String,
+    pub rule_id: String,
+}
+
+/// Packs enabled for a deployment
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TypologyLibrary {
+    packs: Vec<TypologyPack>,
+}
+
+impl TypologyLibrary {
+    /// Library with no packs enabled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Library with every built-in pack enabled
+    pub fn builtin() -> Self {
+        let mut library = Self::new();
+        for name in BUILTIN_TYPOLOGY_PACKS {
+            library.enable(TypologyPack::builtin(name).expect("built-in pack exists"));
+        }
+        library
+    }
+
+    /// Enable a pack, replacing any enabled version of the same name
+    pub fn enable(&mut self, pack: TypologyPack) -> Option<TypologyPack> {
+        match self.packs.iter_mut().find(|p| p.name == pack.name) {
+            Some(existing) => Some(std::mem::replace(existing, pack)),
+            None => {
+                self.packs.push(pack);
+                None
+            }
+        }
+    }
+
+    /// Disable a pack by name
+    pub fn disable(&mut self, name: &str) -> Option<TypologyPack> {
+        let index = self.packs.iter().position(|p| p.name == name)?;
+        Some(self.packs.remove(index))
+    }
+
+    /// Enabled packs, in the order they were enabled
+    pub fn packs(&self) -> &[TypologyPack] {
+        &self.packs
+    }
+
+    /// Matching rules: total risk, one hit and one warning per rule
+    pub fn evaluate(
+        &self,
+        transaction: &Transaction,
+        history: &HistoryStore,
+    ) -> (u8, Vec<TypologyHit>, Vec<Warning>) {
+        let mut risk = 0u8;
+        let mut hits = Vec::new();
+        let mut warnings = Vec::new();
+        for pack in &self.packs {
+            for rule in pack
+                .rules
+                .iter()
+                .filter(|rule| rule.matches(transaction, history))
+            {
+                risk = risk.saturating_add(rule.risk);
+                warnings.push(Warning::new(
+                    TYPOLOGY_MATCH_WARNING,
+                    rule.severity,
+                    format!(
+                        "{}@{} {}: {}",
+                        pack.name, pack.version, rule.id, rule.description
+                    ),
+                ));
+                hits.push(TypologyHit {
+                    pack: pack.name.clone(),
+                    version: pack.version.clone(),
+                    rule_id: rule.id.clone(),
+                });
+            }
+        }
+        (risk, hits, warnings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{TransactionMetadata, TransactionValidator};
+
+    #[test]
+    fn test_builtin_packs_round_trip_through_json() {
+        for name in BUILTIN_TYPOLOGY_PACKS {
+            let pack = TypologyPack::builtin(name).unwrap();
+            let json = serde_json::to_string(&pack).unwrap();
+            assert_eq!(TypologyPack::from_json(&json).unwrap(), pack);
+        }
+        assert!(TypologyPack::builtin("unknown").is_none());
+    }
+
+    #[test]
+    fn test_funnel_account_deposits_are_flagged() {
+        let mut library = TypologyLibrary::new();
+        library.enable(TypologyPack::builtin("funnel_accounts").unwrap());
+        let mut validator = TransactionValidator::new();
+        validator.set_typologies(library);
+
+        for i in 0..5 {
+            let mut deposit = create_test_transaction(&format!("TXN-FUN-{}", i), 900.0);
+            deposit.user_id = format!("USER-10{}", i);
+            deposit.from_account = Some(format!("ACCT-1111-2222-000{}", i));
+            let result = validator.validate(&deposit);
+            assert_eq!(result.typology_hits.is_empty(), i < 4);
+        }
+        let result = validator.validate(&create_test_transaction("TXN-FUN-9", 900.0));
+        assert_eq!(
+            result.typology_hits[0],
+            TypologyHit {
+                pack: "funnel_accounts".to_string(),
+                version: "1.0.0".to_string(),
+                rule_id: "FA-01".to_string(),
+            }
+        );
+        assert!(result
+            .warnings
+            .iter()
+            .any(|w| w.code == TYPOLOGY_MATCH_WARNING
+                && w.message.starts_with("funnel_accounts@1.0.0 FA-01")));
+    }
+
+    #[test]
+    fn test_elder_exploitation_needs_age_and_new_payee() {
+        let library = TypologyLibrary::builtin();
+        let history = HistoryStore::new();
+        let mut tx = create_test_transaction("TXN-ELD-1", 8000.0);
+        assert!(library.evaluate(&tx, &history).1.is_empty());
+
+        tx.metadata = Some(TransactionMetadata::from([(
+            CUSTOMER_AGE_METADATA_KEY.to_string(),
+            "78".to_string(),
+        )]));
+        let (risk, hits, _) = library.evaluate(&tx, &history);
+        assert_eq!(risk, 25);
+        assert_eq!(hits[0].rule_id, "EE-01");
+
+        // A payee the customer has paid before is not new
+        let mut history = HistoryStore::new();
+        history.record(&create_test_transaction("TXN-ELD-0", 50.0));
+        assert!(library.evaluate(&tx, &history).1.is_empty());
+    }
+}
@@ -0,0 +1,93 @@
+//! Structured validation warnings
+//!
+//! Warnings carry a stable code, a severity, and the check that raised them
+//! so downstream systems can filter and count them without parsing text.
+
+use crate::Check;
+use serde::{Deserialize, Serialize};
+
+/// Warning severity, ordered from least to most severe
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum WarningSeverity {
+    Info,
+    Low,
+    Medium,
+    High,
+}
+
+/// Non-fatal finding attached to a validation result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Warning {
+    /// Stable machine-readable code, e.g. `HIGH_VALUE`
+    pub code: String,
+    pub severity: WarningSeverity,
+    /// Human-readable description
+    pub message: String,
+    /// Check that raised the warning, if any
+    pub source_check: Option<Check>,
+}
+
+impl Warning {
+    /// Create a warning not yet attributed to a check
+    pub fn new(code: &str, severity: WarningSeverity, message: impl Into<String>) -> Self {
+        Self {
+            code: code.to_string(),
+            severity,
+            message: message.into(),
+            source_check: None,
+        }
+    }
+
+    /// Attribute the warning to a check
+    pub fn with_source(mut self, check: Check) -> Self {
+        self.source_check = Some(check);
+        self
+    }
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::create_test_transaction;
+    use crate::{TransactionType, TransactionValidator};
+
+    #[test]
+    fn test_warnings_are_attributed_and_filterable() {
+        let mut transaction = create_test_transaction("TXN-WARN-001", 60_000.0);
+        transaction.transaction_type = TransactionType::WireTransfer;
+
+        let mut validator = TransactionValidator::new();
+        let result = validator.validate(&transaction);
+
+        let high_value = result
+            .warnings
+            .iter()
+            .find(|w| w.code == "HIGH_VALUE")
+            .expect("high-value warning");
+        assert_eq!(high_value.source_check, Some(Check::FraudPatterns));
+        assert!(result
+            .warning_codes()
+            .contains(&"WIRE_TRANSFER".to_string()));
+        assert!(result
+            .warnings_at_least(WarningSeverity::Medium)
+            .iter()
+            .all(|w| w.severity >= WarningSeverity::Medium));
+        assert_eq!(validator.get_stats().warnings_by_code["HIGH_VALUE"], 1);
+    }
+
+    #[test]
+    fn test_display_is_message() {
+        let warning = Warning::new("TEST", WarningSeverity::Low, "Something odd");
+        assert_eq!(warning.to_string(), "Something odd");
+        assert_eq!(
+            warning.with_source(Check::Amount).source_check,
+            Some(Check::Amount)
+        );
+    }
+}
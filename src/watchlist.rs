@@ -0,0 +1,416 @@
+//! Institution-managed watchlists
+//!
+//! A [`Watchlist`] holds the institution's own decisions about accounts,
+//! users, devices, and IP addresses, separate from external sanctions
+//! lists. The validator consults it before any other check:
+//!
+//! - **Block** entries fail the transaction with
+//!   [`ValidationError::Blocklisted`](crate::ValidationError::Blocklisted)
+//!   and skip the remaining checks.
+//! - **Allow** entries exempt the transaction from the risk-score threshold;
+//!   sanctions, jurisdiction, and format checks still apply.
+//! - **Gray** entries turn an approval or review into
+//!   [`Decision::StepUp`](crate::Decision::StepUp): the customer must pass
+//!   extra verification before the transaction proceeds. Failed
+//!   transactions are still declined.
+//! - **Monitor** entries add a warning without changing the outcome.
+//!
+//! Entries past their expiry stop matching on their own; [`Watchlist::prune_expired`]
+//! only reclaims their memory.
+//!
+//! Account entries match either side of a transfer, so counterparties are
+//! graylisted or blocked as accounts. Devices and IP addresses are read from
+//! the `device_id` and `ip_address` metadata fields.
+
+use crate::Transaction;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Warning code added for graylist matches
+pub const STEP_UP_WARNING_CODE: &str = "STEP_UP_REQUIRED";
+
+/// What a watchlist entry identifies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WatchlistSubject {
+    /// Either side of the transfer
+    Account,
+    User,
+    Device,
+    Ip,
+}
+
+impl WatchlistSubject {
+    /// All subjects, in matching order
+    pub const ALL: [WatchlistSubject; 4] = [
+        WatchlistSubject::Account,
+        WatchlistSubject::User,
+        WatchlistSubject::Device,
+        WatchlistSubject::Ip,
+    ];
+
+    /// Values of this subject carried by the transaction
+    pub fn values<'a>(&self, transaction: &'a Transaction) -> Vec<&'a str> {
+        let metadata = transaction.metadata.as_ref();
+        match self {
+            WatchlistSubject::Account => [&transaction.from_account, &transaction.to_account]
+                .into_iter()
+                .filter_map(|a| a.as_deref())
+                .collect(),
+            WatchlistSubject::User => vec![transaction.user_id.as_str()],
+            WatchlistSubject::Device => metadata
+                .and_then(|m| m.device_id.as_deref())
+                .into_iter()
+                .collect(),
+            WatchlistSubject::Ip => metadata
+                .and_then(|m| m.ip_address.as_deref())
+                .into_iter()
+                .collect(),
+        }
+    }
+}
+
+/// How a listed subject is treated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ListType {
+    Block,
+    Allow,
+    /// Require step-up verification
+    Gray,
+    Monitor,
+}
+
+/// One listed subject
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchlistEntry {
+    pub subject: WatchlistSubject,
+    pub value: String,
+    pub list_type: ListType,
+    pub reason: String,
+    /// Analyst or system that added the entry
+    pub added_by: String,
+    pub added_at: DateTime<Utc>,
+    /// Entry stops matching after this time; `None` never expires
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl WatchlistEntry {
+    /// Entry added now that never expires
+    pub fn new(
+        subject: WatchlistSubject,
+        value: impl Into<String>,
+        list_type: ListType,
+        reason: impl Into<String>,
+        added_by: impl Into<String>,
+    ) -> Self {
+        Self {
+            subject,
+            value: value.into(),
+            list_type,
+            reason: reason.into(),
+            added_by: added_by.into(),
+            added_at: Utc::now(),
+            expires_at: None,
+        }
+    }
+
+    /// Stop matching after `expires_at`
+    pub fn with_expiry(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    /// Check whether the entry still applies at `now`
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_none_or(|expiry| now < expiry)
+    }
+}
+
+/// Watchlist entries indexed by subject and value
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Watchlist {
+    entries: HashMap<WatchlistSubject, HashMap<String, Vec<WatchlistEntry>>>,
+}
+
+impl Watchlist {
+    /// Empty watchlist
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an entry
+    pub fn add(&mut self, entry: WatchlistEntry) {
+        self.entries
+            .entry(entry.subject)
+            .or_default()
+            .entry(entry.value.clone())
+            .or_default()
+            .push(entry);
+    }
+
+    /// Remove entries of `list_type` for one subject value, returning how many
+    pub fn remove(&mut self, subject: WatchlistSubject, value: &str, list_type: ListType) -> usize {
+        let Some(by_value) = self.entries.get_mut(&subject) else {
+            return 0;
+        };
+        let Some(entries) = by_value.get_mut(value) else {
+            return 0;
+        };
+        let before = entries.len();
+        entries.retain(|e| e.list_type != list_type);
+        let removed = before - entries.len();
+        if entries.is_empty() {
+            by_value.remove(value);
+        }
+        removed
+    }
+
+    /// Entries for one subject value that are active at `now`
+    pub fn lookup(
+        &self,
+        subject: WatchlistSubject,
+        value: &str,
+        now: DateTime<Utc>,
+    ) -> Vec<&WatchlistEntry> {
+        self.entries
+            .get(&subject)
+            .and_then(|by_value| by_value.get(value))
+            .map_or(Vec::new(), |entries| {
+                entries.iter().filter(|e| e.is_active(now)).collect()
+            })
+    }
+
+    /// Check whether a subject value has an active entry of `list_type`
+    pub fn contains(
+        &self,
+        subject: WatchlistSubject,
+        value: &str,
+        list_type: ListType,
+        now: DateTime<Utc>,
+    ) -> bool {
+        self.lookup(subject, value, now)
+            .iter()
+            .any(|e| e.list_type == list_type)
+    }
+
+    /// Drop entries that expired before `now`
+    pub fn prune_expired(&mut self, now: DateTime<Utc>) {
+        for by_value in self.entries.values_mut() {
+            for entries in by_value.values_mut() {
+                entries.retain(|e| e.is_active(now));
+            }
+            by_value.retain(|_, entries| !entries.is_empty());
+        }
+    }
+
+    /// Entries that had been added by `at`
+    ///
+    /// Entries removed since cannot be recovered and are missing.
+    pub fn as_of(&self, at: DateTime<Utc>) -> Self {
+        let mut watchlist = Self::new();
+        for entry in self
+            .entries
+            .values()
+            .flat_map(|m| m.values())
+            .flatten()
+            .filter(|e| e.added_at <= at)
+        {
+            watchlist.add(entry.clone());
+        }
+        watchlist
+    }
+
+    /// Number of entries, including expired ones
+    pub fn len(&self) -> usize {
+        self.entries
+            .values()
+            .flat_map(|m| m.values())
+            .map(Vec::len)
+            .sum()
+    }
+
+    /// Check whether the watchlist has no entries
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Entries active at `now` that match the transaction
+    pub fn matches(&self, transaction: &Transaction, now: DateTime<Utc>) -> Vec<&WatchlistEntry> {
+        WatchlistSubject::ALL
+            .iter()
+            .filter_map(|subject| Some((subject, self.entries.get(subject)?)))
+            .flat_map(|(subject, by_value)| {
+                subject
+                    .values(transaction)
+                    .into_iter()
+                    .filter_map(|value| by_value.get(value))
+                    .flatten()
+            })
+            .filter(|entry| entry.is_active(now))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+    use crate::{Decision, TransactionMetadata, TransactionValidator};
+    use chrono::Duration;
+
+    fn create_test_transaction(id: &str, amount: f64) -> Transaction {
+        Transaction {
+            metadata: Some(TransactionMetadata::from([(
+                "device_id".to_string(),
+                "DEVICE-42".to_string(),
+            )])),
+            ..test_fixtures::create_test_transaction(id, amount)
+        }
+    }
+
+    #[test]
+    fn test_matches_by_subject_and_expiry() {
+        let mut watchlist = Watchlist::new();
+        watchlist.add(WatchlistEntry::new(
+            WatchlistSubject::Account,
+            "ACCT-6789-0123-4567",
+            ListType::Monitor,
+            "Chargeback dispute",
+            "analyst-7",
+        ));
+        watchlist.add(
+            WatchlistEntry::new(
+                WatchlistSubject::Device,
+                "DEVICE-42",
+                ListType::Block,
+                "Emulator farm",
+                "analyst-7",
+            )
+            .with_expiry(Utc::now() - Duration::hours(1)),
+        );
+        watchlist.add(WatchlistEntry::new(
+            WatchlistSubject::Ip,
+            "203.0.113.9",
+            ListType::Block,
+            "Botnet",
+            "feed",
+        ));
+
+        let transaction = create_test_transaction("TXN-WL-1", 100.0);
+        let matches = watchlist.matches(&transaction, Utc::now());
+        assert_eq!(watchlist.len(), 3);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].list_type, ListType::Monitor);
+    }
+
+    #[test]
+    fn test_add_remove_and_query_entries() {
+        let now = Utc::now();
+        let mut watchlist = Watchlist::new();
+        watchlist.add(
+            WatchlistEntry::new(
+                WatchlistSubject::User,
+                "USER-001",
+                ListType::Block,
+                "Account takeover",
+                "analyst-7",
+            )
+            .with_expiry(now + Duration::days(1)),
+        );
+        watchlist.add(WatchlistEntry::new(
+            WatchlistSubject::User,
+            "USER-001",
+            ListType::Monitor,
+            "Prior fraud report",
+            "analyst-7",
+        ));
+
+        assert!(watchlist.contains(WatchlistSubject::User, "USER-001", ListType::Block, now));
+        // The block lapses on its own once the expiry passes
+        let later = now + Duration::days(2);
+        assert!(!watchlist.contains(WatchlistSubject::User, "USER-001", ListType::Block, later));
+        assert_eq!(
+            watchlist
+                .lookup(WatchlistSubject::User, "USER-001", later)
+                .len(),
+            1
+        );
+
+        watchlist.prune_expired(later);
+        assert_eq!(watchlist.len(), 1);
+        assert_eq!(
+            watchlist.remove(WatchlistSubject::User, "USER-001", ListType::Monitor),
+            1
+        );
+        assert!(watchlist.is_empty());
+        assert_eq!(
+            watchlist.remove(WatchlistSubject::User, "USER-001", ListType::Monitor),
+            0
+        );
+    }
+
+    #[test]
+    fn test_validator_consults_watchlist_first() {
+        let mut validator = TransactionValidator::new();
+        validator.watchlist_mut().add(WatchlistEntry::new(
+            WatchlistSubject::Device,
+            "DEVICE-42",
+            ListType::Block,
+            "Emulator farm",
+            "analyst-7",
+        ));
+        let result = validator.validate(&create_test_transaction("TXN-WL-2", 100.0));
+        assert_eq!(result.decision, Decision::Decline);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].reason_code(), "BLOCKLISTED");
+        assert!(result.errors[0].detail().contains("Emulator farm"));
+        assert_eq!(result.compliance_checks.get("WATCHLIST"), Some(&false));
+        assert!(!result.skipped_checks.is_empty());
+
+        // Allowlisted users pass a score that would otherwise be declined
+        let mut validator = TransactionValidator::builder()
+            .with_config(crate::ValidatorConfig {
+                fraud_threshold: 0,
+                ..Default::default()
+            })
+            .build();
+        let transaction = create_test_transaction("TXN-WL-3", 60_000.0);
+        assert!(!validator.validate(&transaction).is_valid);
+        validator.watchlist_mut().add(WatchlistEntry::new(
+            WatchlistSubject::User,
+            "USER-001",
+            ListType::Allow,
+            "Treasury desk",
+            "ops",
+        ));
+        let mut retry = transaction.clone();
+        retry.transaction_id = "TXN-WL-4".to_string();
+        let result = validator.validate(&retry);
+        assert!(result.is_valid, "{:?}", result.errors);
+        assert!(result
+            .warning_codes()
+            .contains(&"WATCHLIST_ALLOW".to_string()));
+    }
+
+    #[test]
+    fn test_graylist_requires_step_up() {
+        let mut validator = TransactionValidator::new();
+        validator.watchlist_mut().add(WatchlistEntry::new(
+            WatchlistSubject::Account,
+            "ACCT-6789-0123-4567",
+            ListType::Gray,
+            "New payee linked to mule network",
+            "analyst-7",
+        ));
+
+        let result = validator.validate(&create_test_transaction("TXN-WL-5", 100.0));
+        assert!(result.is_valid);
+        assert!(result.requires_step_up());
+        assert_eq!(result.decision, Decision::StepUp);
+        assert_eq!(validator.get_stats().decisions.step_up, 1);
+
+        // Failing transactions are declined, not stepped up
+        let result = validator.validate(&create_test_transaction("TXN-WL-6", -5.0));
+        assert_eq!(result.decision, Decision::Decline);
+    }
+}